@@ -1,16 +1,211 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{ItemFn, parse_macro_input};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{FnArg, ItemFn, LitStr, Pat, ReturnType, Type, parse_macro_input};
+
+/// Options accepted by the export macros
+#[derive(Default)]
+struct ExportArgs {
+    /// Symbol name for the generated C wrapper
+    rename: Option<String>,
+}
+
+fn parse_export_args(args: TokenStream) -> syn::Result<ExportArgs> {
+    let mut export_args = ExportArgs::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("rename") {
+            export_args.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else if meta.path.is_ident("kotlin_name") || meta.path.is_ident("wasm_name") {
+            // Consumed by the bindings generator, not by the C wrapper
+            meta.value()?.parse::<LitStr>()?;
+            Ok(())
+        } else {
+            Err(meta.error("expected `rename`, `kotlin_name` or `wasm_name`"))
+        }
+    });
+    syn::parse::Parser::parse(parser, args)?;
+    Ok(export_args)
+}
+
+/// Rust primitives that can cross the C ABI unchanged
+fn is_c_primitive(ty: &Type) -> bool {
+    const PRIMITIVES: &[&str] = &[
+        "bool", "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32",
+        "f64",
+    ];
+    matches!(ty, Type::Path(p) if p.qself.is_none() && PRIMITIVES.iter().any(|name| p.path.is_ident(name)))
+}
+
+fn is_str_ref(ty: &Type) -> bool {
+    matches!(ty, Type::Reference(r) if matches!(&*r.elem, Type::Path(p) if p.path.is_ident("str")))
+}
+
+fn is_string(ty: &Type) -> bool {
+    matches!(ty, Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "String"))
+}
+
+fn returns_result(output: &ReturnType) -> bool {
+    matches!(output, ReturnType::Type(_, ty)
+        if matches!(&**ty, Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "Result")))
+}
+
+/// Generate an `extern "C"` wrapper for `input_fn`
+///
+/// - `&self`/`&mut self` become a `*const Self`/`*mut Self` first parameter
+/// - `&str` and `String` parameters become `*const c_char`
+/// - primitive parameters (integers, floats, bool) pass through unchanged
+/// - any other parameter is passed as a JSON `*const c_char` and deserialized
+/// - the return value is boxed into `crate::ffi::c_ffi::FfiResult` (code + JSON/message string)
+///
+/// Functions with a receiver must live in an inherent impl; functions without one must be free
+/// functions. The wrapper is omitted on wasm32, where the C ABI doesn't apply.
+fn c_wrapper(input_fn: &ItemFn, args: &ExportArgs) -> syn::Result<TokenStream2> {
+    let sig = &input_fn.sig;
+    if sig.asyncness.is_some() {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "C wrappers can't be generated for async functions",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "C wrappers can't be generated for generic functions",
+        ));
+    }
+
+    let fn_name = &sig.ident;
+    let symbol = args
+        .rename
+        .clone()
+        .unwrap_or_else(|| format!("halvor_{}", fn_name));
+    let wrapper_name = format_ident!("__c_export_{}", fn_name);
+    let ffi = quote!(crate::ffi::c_ffi);
+
+    let mut params = Vec::new();
+    let mut conversions = Vec::new();
+    let mut call_args = Vec::new();
+    let mut receiver = None;
+
+    for input in &sig.inputs {
+        match input {
+            FnArg::Receiver(recv) => {
+                if recv.reference.is_none() {
+                    return Err(syn::Error::new_spanned(
+                        recv,
+                        "C wrappers require a `&self` or `&mut self` receiver",
+                    ));
+                }
+                let (ptr_ty, deref) = if recv.mutability.is_some() {
+                    (quote!(*mut Self), quote!(ffi_mut))
+                } else {
+                    (quote!(*const Self), quote!(ffi_ref))
+                };
+                params.push(quote!(this: #ptr_ty));
+                conversions.push(quote! {
+                    let this = match unsafe { #ffi::#deref(this) } {
+                        Ok(value) => value,
+                        Err(e) => return #ffi::FfiResult::from_error(e),
+                    };
+                });
+                receiver = Some(quote!(this));
+            }
+            FnArg::Typed(arg) => {
+                let Pat::Ident(pat) = &*arg.pat else {
+                    return Err(syn::Error::new_spanned(
+                        &arg.pat,
+                        "C wrappers require simple argument names",
+                    ));
+                };
+                let name = &pat.ident;
+                let name_str = name.to_string();
+                let ty = &*arg.ty;
+
+                if is_c_primitive(ty) {
+                    params.push(quote!(#name: #ty));
+                    call_args.push(quote!(#name));
+                    continue;
+                }
+
+                params.push(quote!(#name: *const ::std::os::raw::c_char));
+                let convert = if is_str_ref(ty) || is_string(ty) {
+                    quote!(#ffi::ffi_string(#name, #name_str))
+                } else {
+                    quote!(#ffi::ffi_json::<#ty>(#name, #name_str))
+                };
+                conversions.push(quote! {
+                    let #name = match unsafe { #convert } {
+                        Ok(value) => value,
+                        Err(e) => return #ffi::FfiResult::from_error(e),
+                    };
+                });
+                call_args.push(if is_str_ref(ty) {
+                    quote!(&#name)
+                } else {
+                    quote!(#name)
+                });
+            }
+        }
+    }
+
+    let call = match receiver {
+        Some(this) => quote!(#this.#fn_name(#(#call_args),*)),
+        None => quote!(#fn_name(#(#call_args),*)),
+    };
+    let result = if returns_result(&sig.output) {
+        quote!(#ffi::FfiResult::from_result(#call))
+    } else {
+        quote!(#ffi::FfiResult::from_value(#call))
+    };
+
+    let doc = format!(
+        "C ABI wrapper for `{}`, exported as `{}`\n\n# Safety\nPointer arguments must be NULL or valid \
+         (`this` from the matching constructor, strings NUL-terminated). Free `FfiResult::data` \
+         with `halvor_string_free`.",
+        fn_name, symbol
+    );
+
+    Ok(quote! {
+        #[cfg(not(target_arch = "wasm32"))]
+        #[doc = #doc]
+        #[unsafe(export_name = #symbol)]
+        pub unsafe extern "C" fn #wrapper_name(#(#params),*) -> #ffi::FfiResult {
+            #(#conversions)*
+            #result
+        }
+    })
+}
+
+/// Keep the original function and append its generated C wrapper
+fn export_with_c_wrapper(args: TokenStream, input: TokenStream) -> TokenStream {
+    let input_fn = parse_macro_input!(input as ItemFn);
+    let wrapper = parse_export_args(args).and_then(|args| c_wrapper(&input_fn, &args));
+    match wrapper {
+        Ok(wrapper) => TokenStream::from(quote! {
+            #input_fn
+            #wrapper
+        }),
+        Err(e) => {
+            let error = e.to_compile_error();
+            TokenStream::from(quote! {
+                #input_fn
+                #error
+            })
+        }
+    }
+}
 
 /// Macro to mark a Rust function for multi-platform export (Swift, Kotlin, WASM)
 ///
 /// This macro:
 /// 1. Keeps the original function unchanged
-/// 2. Generates metadata for build scripts to create bindings for all platforms
-/// 3. Supports platform-specific attributes
+/// 2. Generates an `extern "C"` wrapper (see `c_wrapper`) so Swift can call it without a
+///    hand-written shim in `c_ffi`. The wrapper is exported as `halvor_<fn_name>`.
+/// 3. Leaves metadata for the bindings generator (`halvor generate ffi-bindings`)
 ///
 /// Example:
-/// ```rust
+/// ```rust,ignore
 /// #[swift_export]
 /// pub fn discover_agents(client: &HalvorClient) -> Result<Vec<DiscoveredHost>, String> {
 ///     client.discover_agents()
@@ -18,18 +213,12 @@ use syn::{ItemFn, parse_macro_input};
 /// ```
 ///
 /// Platform-specific attributes:
-/// - `#[swift_export(rename = "newName")]` - Rename for Swift
+/// - `#[swift_export(rename = "newName")]` - Export the C wrapper as `newName`
 /// - `#[swift_export(kotlin_name = "newName")]` - Rename for Kotlin
 /// - `#[swift_export(wasm_name = "newName")]` - Rename for WASM
 #[proc_macro_attribute]
-pub fn swift_export(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let input_fn = parse_macro_input!(input as ItemFn);
-
-    // For now, just pass through - the build script will handle generation
-    // In the future, we could generate FFI wrappers here too
-    TokenStream::from(quote! {
-        #input_fn
-    })
+pub fn swift_export(args: TokenStream, input: TokenStream) -> TokenStream {
+    export_with_c_wrapper(args, input)
 }
 
 /// Macro to mark a Rust function for Kotlin/JNI export
@@ -51,10 +240,8 @@ pub fn wasm_export(_args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// Macro to mark a Rust function for all platforms (Swift, Kotlin, WASM)
+/// Generates the same C wrapper as `swift_export`
 #[proc_macro_attribute]
-pub fn multi_platform_export(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let input_fn = parse_macro_input!(input as ItemFn);
-    TokenStream::from(quote! {
-        #input_fn
-    })
+pub fn multi_platform_export(args: TokenStream, input: TokenStream) -> TokenStream {
+    export_with_c_wrapper(args, input)
 }
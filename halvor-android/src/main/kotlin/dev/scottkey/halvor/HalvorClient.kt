@@ -2,6 +2,8 @@ package dev.scottkey.halvor
 
 import com.sun.jna.Library
 import com.sun.jna.Native
+import com.sun.jna.Pointer
+import com.sun.jna.ptr.PointerByReference
 
 /** Kotlin wrapper for Halvor Rust FFI Auto-generated bindings are in GeneratedBindings.kt */
 class HalvorClient(private val agentPort: UShort? = null) {
@@ -10,16 +12,25 @@ class HalvorClient(private val agentPort: UShort? = null) {
     interface HalvorNativeLib : Library {
         fun halvor_client_new(agentPort: UShort): Long
         fun halvor_client_free(ptr: Long)
-        fun halvor_client_discover_agents(ptr: Long): String?
-        fun halvor_string_free(ptr: String)
+        fun halvor_client_discover_agents(ptr: Long, out: PointerByReference): Int
+        fun halvor_string_free(ptr: Pointer)
     }
 
     private val clientPtr: Long = nativeLib.halvor_client_new(agentPort ?: 0u)
 
+    /** Run an FFI call, returning the JSON result or throwing a categorized HalvorException */
+    private fun call(body: (PointerByReference) -> Int): String {
+        val out = PointerByReference()
+        val code = body(out)
+        val text = out.value?.let { ptr -> ptr.getString(0).also { nativeLib.halvor_string_free(ptr) } } ?: ""
+        if (code != 0) {
+            throw HalvorException(HalvorErrorKind.fromCode(code), text)
+        }
+        return text
+    }
+
     fun discoverAgents(): List<DiscoveredHost> {
-        val json =
-                nativeLib.halvor_client_discover_agents(clientPtr)
-                        ?: throw HalvorException("Failed to discover agents")
+        val json = call { out -> nativeLib.halvor_client_discover_agents(clientPtr, out) }
 
         // Parse JSON to List<DiscoveredHost>
         // Implementation depends on JSON library
@@ -31,4 +42,16 @@ class HalvorClient(private val agentPort: UShort? = null) {
     }
 }
 
-class HalvorException(message: String) : Exception(message)
+/** Error categories, codes match HalvorError::code() in src/ffi/error.rs */
+enum class HalvorErrorKind(val code: Int) {
+    NETWORK(1),
+    AUTH(2),
+    NOT_FOUND(3),
+    INTERNAL(4);
+
+    companion object {
+        fun fromCode(code: Int): HalvorErrorKind = values().firstOrNull { it.code == code } ?: INTERNAL
+    }
+}
+
+class HalvorException(val kind: HalvorErrorKind, message: String) : Exception(message)
@@ -45,8 +45,8 @@ struct BasicExample {
                     print()
                 }
             }
-        } catch HalvorError.discoveryError(let message) {
-            print("Discovery error: \(message)")
+        } catch HalvorError.network(let message) {
+            print("Network error: \(message)")
         } catch {
             print("Error: \(error)")
         }
@@ -70,7 +70,7 @@ public class HalvorClient {
     /// - Returns: true if agent is reachable
     /// - Throws: HalvorError if ping fails
     public func pingAgent(host: String, port: UInt16 = 23500) throws -> Bool {
-        return ffiClient.pingAgent(host: host, port: port)
+        return try ffiClient.pingAgent(host: host, port: port)
     }
     
     /// Get host information from an agent
@@ -104,6 +104,32 @@ public class HalvorClient {
     ) throws -> String {
         return try ffiClient.executeCommand(host: host, port: port, command: command, args: args)
     }
+    
+    /// Restart a docker service on a remote agent
+    /// - Parameters:
+    ///   - host: Host address (IP or hostname)
+    ///   - port: Agent port (default: 23500)
+    ///   - service: Container name
+    /// - Returns: Service status after the restart
+    /// - Throws: HalvorError if the request fails
+    public func restartService(host: String, port: UInt16 = 23500, service: String) throws -> ServiceStatus {
+        return ServiceStatus(try ffiClient.restartService(host: host, port: port, service: service))
+    }
+    
+    /// Start a docker service on a remote agent
+    public func startService(host: String, port: UInt16 = 23500, service: String) throws -> ServiceStatus {
+        return ServiceStatus(try ffiClient.startService(host: host, port: port, service: service))
+    }
+    
+    /// Stop a docker service on a remote agent
+    public func stopService(host: String, port: UInt16 = 23500, service: String) throws -> ServiceStatus {
+        return ServiceStatus(try ffiClient.stopService(host: host, port: port, service: service))
+    }
+    
+    /// Get the status of a docker service on a remote agent
+    public func getServiceStatus(host: String, port: UInt16 = 23500, service: String) throws -> ServiceStatus {
+        return ServiceStatus(try ffiClient.getServiceStatus(host: host, port: port, service: service))
+    }
 }
 
 // HalvorClient is used across concurrent tasks; the underlying FFI client is thread-safe.
@@ -162,23 +188,25 @@ public struct HostInfo: Sendable {
     }
 }
 
-/// Error types for Halvor operations
-public enum HalvorError: Error {
-    case connectionFailed(String)
-    case agentError(String)
-    case discoveryError(String)
-    case unknown(String)
-    
-    init(_ message: String) {
-        // Parse error message to determine error type
-        if message.contains("Connection failed") || message.contains("connection") {
-            self = .connectionFailed(message)
-        } else if message.contains("Agent error") {
-            self = .agentError(message)
-        } else if message.contains("discover") || message.contains("Discovery") {
-            self = .discoveryError(message)
-        } else {
-            self = .unknown(message)
-        }
+/// Swift wrapper for a remote docker service's state
+public struct ServiceStatus: Sendable {
+    public let service: String
+    public let state: String
+    public let running: Bool
+    public let health: String?
+    public let image: String?
+    public let startedAt: String?
+    
+    init(_ status: HalvorSwiftFFI.ServiceStatus) {
+        self.service = status.service
+        self.state = status.state
+        self.running = status.running
+        self.health = status.health
+        self.image = status.image
+        self.startedAt = status.started_at
     }
 }
+
+/// Error types for Halvor operations
+/// Cases (network, auth, notFound, internal) mirror the Rust `HalvorError` so callers can branch on them
+public typealias HalvorError = HalvorSwiftFFI.HalvorError
@@ -9,5 +9,9 @@ import Foundation
 // - discover_via_local_network
 // - ping_agent
 // - get_host_info
+// - restart_service
+// - start_service
+// - stop_service
+// - get_service_status
 // - execute_command
 // - get_version
@@ -8,22 +8,34 @@ func halvor_client_new(_ agent_port: UInt16) -> UnsafeMutableRawPointer?
 func halvor_client_free(_ ptr: UnsafeMutableRawPointer)
 
 @_silgen_name("halvor_client_discover_agents")
-func halvor_client_discover_agents(_ ptr: UnsafeMutableRawPointer) -> UnsafeMutablePointer<CChar>?
+func halvor_client_discover_agents(_ ptr: UnsafeMutableRawPointer, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
 
 @_silgen_name("halvor_client_discover_via_tailscale")
-func halvor_client_discover_via_tailscale(_ ptr: UnsafeMutableRawPointer) -> UnsafeMutablePointer<CChar>?
+func halvor_client_discover_via_tailscale(_ ptr: UnsafeMutableRawPointer, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
 
 @_silgen_name("halvor_client_discover_via_local_network")
-func halvor_client_discover_via_local_network(_ ptr: UnsafeMutableRawPointer) -> UnsafeMutablePointer<CChar>?
+func halvor_client_discover_via_local_network(_ ptr: UnsafeMutableRawPointer, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
 
 @_silgen_name("halvor_client_ping_agent")
-func halvor_client_ping_agent(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16) -> Int32
+func halvor_client_ping_agent(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
 
 @_silgen_name("halvor_client_get_host_info")
-func halvor_client_get_host_info(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16) -> UnsafeMutablePointer<CChar>?
+func halvor_client_get_host_info(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
 
 @_silgen_name("halvor_client_execute_command")
-func halvor_client_execute_command(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ command: UnsafePointer<CChar>, _ args_json: UnsafePointer<CChar>?) -> UnsafeMutablePointer<CChar>?
+func halvor_client_execute_command(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ command: UnsafePointer<CChar>, _ args_json: UnsafePointer<CChar>?, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
+
+@_silgen_name("halvor_client_restart_service")
+func halvor_client_restart_service(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ service: UnsafePointer<CChar>, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
+
+@_silgen_name("halvor_client_start_service")
+func halvor_client_start_service(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ service: UnsafePointer<CChar>, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
+
+@_silgen_name("halvor_client_stop_service")
+func halvor_client_stop_service(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ service: UnsafePointer<CChar>, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
+
+@_silgen_name("halvor_client_get_service_status")
+func halvor_client_get_service_status(_ ptr: UnsafeMutableRawPointer, _ host: UnsafePointer<CChar>, _ port: UInt16, _ service: UnsafePointer<CChar>, _ out: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
 
 @_silgen_name("halvor_string_free")
 func halvor_string_free(_ ptr: UnsafeMutablePointer<CChar>)
@@ -44,6 +56,15 @@ public struct HostInfo: Codable {
     public let portainer_installed: Bool
 }
 
+public struct ServiceStatus: Codable {
+    public let service: String
+    public let state: String
+    public let running: Bool
+    public let health: String?
+    public let image: String?
+    public let started_at: String?
+}
+
 // Swift wrapper for HalvorClient
 public class HalvorClient {
     private let ptr: UnsafeMutableRawPointer
@@ -60,53 +81,46 @@ public class HalvorClient {
         halvor_client_free(ptr)
     }
     
-    private func getString(from cString: UnsafeMutablePointer<CChar>?) -> String? {
-        guard let cString = cString else { return nil }
-        defer { halvor_string_free(cString) }
-        return String(cString: cString)
+    /// Run an FFI call, returning the JSON result or throwing the categorized error
+    private func call(_ body: (UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>) -> Int32) throws -> Data {
+        var out: UnsafeMutablePointer<CChar>? = nil
+        let code = body(&out)
+        var text = ""
+        if let out = out {
+            text = String(cString: out)
+            halvor_string_free(out)
+        }
+        guard code == 0 else {
+            throw HalvorError(code: code, message: text)
+        }
+        return Data(text.utf8)
     }
     
     public func discoverAgents() throws -> [DiscoveredHost] {
-        guard let jsonPtr = halvor_client_discover_agents(ptr),
-              let json = getString(from: jsonPtr),
-              let data = json.data(using: .utf8) else {
-            throw HalvorError.unknown("Failed to discover agents")
-        }
+        let data = try call { halvor_client_discover_agents(ptr, $0) }
         return try JSONDecoder().decode([DiscoveredHost].self, from: data)
     }
     
     public func discoverViaTailscale() throws -> [DiscoveredHost] {
-        guard let jsonPtr = halvor_client_discover_via_tailscale(ptr),
-              let json = getString(from: jsonPtr),
-              let data = json.data(using: .utf8) else {
-            throw HalvorError.unknown("Failed to discover agents via Tailscale")
-        }
+        let data = try call { halvor_client_discover_via_tailscale(ptr, $0) }
         return try JSONDecoder().decode([DiscoveredHost].self, from: data)
     }
     
     public func discoverViaLocalNetwork() throws -> [DiscoveredHost] {
-        guard let jsonPtr = halvor_client_discover_via_local_network(ptr),
-              let json = getString(from: jsonPtr),
-              let data = json.data(using: .utf8) else {
-            throw HalvorError.unknown("Failed to discover agents on local network")
-        }
+        let data = try call { halvor_client_discover_via_local_network(ptr, $0) }
         return try JSONDecoder().decode([DiscoveredHost].self, from: data)
     }
     
-    public func pingAgent(host: String, port: UInt16) -> Bool {
-        let result = host.withCString { hostPtr in
-            halvor_client_ping_agent(ptr, hostPtr, port)
+    public func pingAgent(host: String, port: UInt16) throws -> Bool {
+        let data = try call { out in
+            host.withCString { hostPtr in halvor_client_ping_agent(ptr, hostPtr, port, out) }
         }
-        return result != 0
+        return try JSONDecoder().decode(Bool.self, from: data)
     }
     
     public func getHostInfo(host: String, port: UInt16) throws -> HostInfo {
-        guard let jsonPtr = host.withCString({ hostPtr in
-            halvor_client_get_host_info(ptr, hostPtr, port)
-        }),
-        let json = getString(from: jsonPtr),
-        let data = json.data(using: .utf8) else {
-            throw HalvorError.unknown("Failed to get host info")
+        let data = try call { out in
+            host.withCString { hostPtr in halvor_client_get_host_info(ptr, hostPtr, port, out) }
         }
         return try JSONDecoder().decode(HostInfo.self, from: data)
     }
@@ -115,21 +129,71 @@ public class HalvorClient {
         let argsJson = try JSONEncoder().encode(args)
         let argsJsonString = String(data: argsJson, encoding: .utf8) ?? "[]"
         
-        guard let jsonPtr = host.withCString({ hostPtr in
-            command.withCString { cmdPtr in
-                argsJsonString.withCString { argsPtr in
-                    halvor_client_execute_command(ptr, hostPtr, port, cmdPtr, argsPtr)
+        let data = try call { out in
+            host.withCString { hostPtr in
+                command.withCString { cmdPtr in
+                    argsJsonString.withCString { argsPtr in
+                        halvor_client_execute_command(ptr, hostPtr, port, cmdPtr, argsPtr, out)
+                    }
                 }
             }
-        }),
-        let json = getString(from: jsonPtr),
-        let data = json.data(using: .utf8) else {
-            throw HalvorError.unknown("Failed to execute command")
         }
         return try JSONDecoder().decode(String.self, from: data)
     }
+    
+    private func serviceCall(
+        host: String,
+        port: UInt16,
+        service: String,
+        _ function: (UnsafeMutableRawPointer, UnsafePointer<CChar>, UInt16, UnsafePointer<CChar>, UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?) -> Int32
+    ) throws -> ServiceStatus {
+        let data = try call { out in
+            host.withCString { hostPtr in
+                service.withCString { servicePtr in
+                    function(ptr, hostPtr, port, servicePtr, out)
+                }
+            }
+        }
+        return try JSONDecoder().decode(ServiceStatus.self, from: data)
+    }
+    
+    public func restartService(host: String, port: UInt16, service: String) throws -> ServiceStatus {
+        return try serviceCall(host: host, port: port, service: service, halvor_client_restart_service)
+    }
+    
+    public func startService(host: String, port: UInt16, service: String) throws -> ServiceStatus {
+        return try serviceCall(host: host, port: port, service: service, halvor_client_start_service)
+    }
+    
+    public func stopService(host: String, port: UInt16, service: String) throws -> ServiceStatus {
+        return try serviceCall(host: host, port: port, service: service, halvor_client_stop_service)
+    }
+    
+    public func getServiceStatus(host: String, port: UInt16, service: String) throws -> ServiceStatus {
+        return try serviceCall(host: host, port: port, service: service, halvor_client_get_service_status)
+    }
 }
 
+/// Categorized error from the Rust FFI (codes match HalvorError::code() in src/ffi/error.rs)
 public enum HalvorError: Error {
-    case unknown(String)
+    case network(String)
+    case auth(String)
+    case notFound(String)
+    case `internal`(String)
+    
+    init(code: Int32, message: String) {
+        switch code {
+        case 1: self = .network(message)
+        case 2: self = .auth(message)
+        case 3: self = .notFound(message)
+        default: self = .internal(message)
+        }
+    }
+    
+    public var message: String {
+        switch self {
+        case .network(let message), .auth(let message), .notFound(let message), .internal(let message):
+            return message
+        }
+    }
 }
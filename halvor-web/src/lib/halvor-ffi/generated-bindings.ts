@@ -6,6 +6,11 @@ export interface HalvorWasmModule {
     discoverViaLocalNetwork(): Promise<any>;
     pingAgent(): Promise<any>;
     getHostInfo(): Promise<any>;
+    restartService(): Promise<any>;
+    startService(): Promise<any>;
+    stopService(): Promise<any>;
+    getServiceStatus(): Promise<any>;
     executeCommand(): Promise<any>;
     getVersion(): Promise<any>;
+    discoverAgentsWasm(): Promise<any>;
 }
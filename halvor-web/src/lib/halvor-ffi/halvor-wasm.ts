@@ -31,7 +31,21 @@ export async function loadWasmModule(): Promise<HalvorWasmModule> {
         tailscaleInstalled: false,
         portainerInstalled: false,
       }),
+      restartService: async () => {
+        throw new Error('WASM module not loaded');
+      },
+      startService: async () => {
+        throw new Error('WASM module not loaded');
+      },
+      stopService: async () => {
+        throw new Error('WASM module not loaded');
+      },
+      getServiceStatus: async () => {
+        throw new Error('WASM module not loaded');
+      },
       executeCommand: async () => '',
+      getVersion: async () => 'unknown',
+      discoverAgentsWasm: async () => [],
     };
   }
 }
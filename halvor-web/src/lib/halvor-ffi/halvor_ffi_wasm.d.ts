@@ -8,6 +8,8 @@ declare module './halvor_ffi_wasm.js' {
 
   // Named exports from the WASM module
   export const discoverAgents: () => Promise<any[]>;
+  // Browser discovery via the web server's /api/discover-agents; rejects with { code, message }
+  export const discoverAgentsWasm: () => Promise<any[]>;
   export const discoverViaTailscale: () => Promise<any[]>;
   export const discoverViaLocalNetwork: () => Promise<any[]>;
   export const pingAgent: (host: string, port: number) => Promise<boolean>;
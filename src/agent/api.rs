@@ -1,4 +1,4 @@
-use crate::agent::server::{AgentRequest, AgentResponse, HostInfo};
+use crate::agent::server::{AgentRequest, AgentResponse, HostInfo, ServiceAction, ServiceStatus};
 use crate::utils::{format_address, read_json, write_json};
 use anyhow::{Context, Result};
 use std::net::TcpStream;
@@ -40,6 +40,29 @@ impl AgentClient {
         }
     }
 
+    /// Start, stop, restart or inspect a docker service on the agent's host
+    pub fn control_service(&self, service: &str, action: ServiceAction) -> Result<ServiceStatus> {
+        let response = self.send_request(AgentRequest::ControlService {
+            service: service.to_string(),
+            action,
+        })?;
+        match response {
+            AgentResponse::ServiceStatus { status } => Ok(status),
+            AgentResponse::Error { message } => anyhow::bail!("Agent error: {}", message),
+            _ => anyhow::bail!("Unexpected response type"),
+        }
+    }
+
+    /// Restart a docker service on the agent's host
+    pub fn restart_service(&self, service: &str) -> Result<ServiceStatus> {
+        self.control_service(service, ServiceAction::Restart)
+    }
+
+    /// Get the status of a docker service on the agent's host
+    pub fn get_service_status(&self, service: &str) -> Result<ServiceStatus> {
+        self.control_service(service, ServiceAction::Status)
+    }
+
     /// Execute a command remotely
     pub fn execute_command(&self, command: &str, args: &[&str]) -> Result<String> {
         let token = self.token.as_deref().unwrap_or("default");
@@ -1,11 +1,21 @@
+use crate::agent::api::AgentClient;
+use crate::db;
 use crate::services::tailscale;
 use crate::utils::{format_address, networking, write_json};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::net::{TcpStream, ToSocketAddrs};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Smallest prefix accepted for a CIDR scan (a /16 is 65k addresses)
+const MIN_SCAN_PREFIX: u8 = 16;
+
+/// Upper bound on concurrent probes, each holds one socket open
+pub const MAX_SCAN_CONCURRENCY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiscoveredHost {
     pub hostname: String,
     pub local_ip: Option<String>,
@@ -94,18 +104,10 @@ impl HostDiscovery {
     /// Check if agent is reachable at given IP
     fn check_agent_reachable(&self, ip: &str) -> bool {
         let addr = format_address(ip, self.agent_port);
-        if let Ok(mut addr_iter) = addr.to_socket_addrs() {
-            if let Some(addr) = addr_iter.next() {
-                if let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_secs(1)) {
-                    // Try to send ping
-                    let ping = serde_json::json!({
-                        "Ping": {}
-                    });
-                    if write_json(&mut stream, &ping).is_ok() {
-                        return true;
-                    }
-                }
-            }
+        if let Ok(mut addr_iter) = addr.to_socket_addrs()
+            && let Some(addr) = addr_iter.next()
+        {
+            return probe_agent(&addr, Duration::from_secs(1));
         }
         false
     }
@@ -139,3 +141,114 @@ impl HostDiscovery {
         Ok(hosts)
     }
 }
+
+/// Connect to an agent port and send a ping
+fn probe_agent(addr: &SocketAddr, timeout: Duration) -> bool {
+    let Ok(mut stream) = TcpStream::connect_timeout(addr, timeout) else {
+        return false;
+    };
+    let _ = stream.set_write_timeout(Some(timeout));
+    let ping = serde_json::json!({
+        "Ping": {}
+    });
+    write_json(&mut stream, &ping).is_ok()
+}
+
+/// Parse an IPv4 CIDR (e.g. 192.168.1.0/24) into the host addresses to scan
+pub fn parse_cidr_hosts(cidr: &str) -> Result<Vec<Ipv4Addr>> {
+    let (addr, prefix) = cidr
+        .trim()
+        .split_once('/')
+        .with_context(|| format!("Invalid CIDR '{}': expected ADDRESS/PREFIX", cidr))?;
+    let addr: Ipv4Addr = addr
+        .parse()
+        .with_context(|| format!("Invalid CIDR '{}': '{}' is not an IPv4 address", cidr, addr))?;
+    let prefix: u8 = prefix
+        .parse()
+        .ok()
+        .filter(|p| *p <= 32)
+        .with_context(|| format!("Invalid CIDR '{}': prefix must be 0-32", cidr))?;
+    if prefix < MIN_SCAN_PREFIX {
+        anyhow::bail!(
+            "CIDR '{}' is too large to scan (smallest allowed prefix is /{})",
+            cidr,
+            MIN_SCAN_PREFIX
+        );
+    }
+
+    let mask = u32::MAX << (32 - prefix);
+    let network = u32::from(addr) & mask;
+    let broadcast = network | !mask;
+    // /31 and /32 have no network/broadcast addresses to skip
+    let (first, last) = if prefix >= 31 {
+        (network, broadcast)
+    } else {
+        (network + 1, broadcast - 1)
+    };
+    Ok((first..=last).map(Ipv4Addr::from).collect())
+}
+
+/// Scan a CIDR range for halvor agents, probing at most `concurrency` addresses at a time
+pub fn discover_agents_in_cidr(
+    cidr: &str,
+    port: u16,
+    concurrency: usize,
+    timeout: Duration,
+) -> Result<Vec<DiscoveredHost>> {
+    let targets = parse_cidr_hosts(cidr)?;
+    let workers = concurrency
+        .clamp(1, MAX_SCAN_CONCURRENCY)
+        .min(targets.len());
+    let next = AtomicUsize::new(0);
+    let found = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(ip) = targets.get(i) else {
+                        break;
+                    };
+                    let addr = SocketAddr::from((*ip, port));
+                    if probe_agent(&addr, timeout) {
+                        found.lock().unwrap().push(*ip);
+                    }
+                }
+            });
+        }
+    });
+
+    let mut found = found.into_inner().unwrap();
+    found.sort();
+    Ok(found
+        .into_iter()
+        .map(|ip| DiscoveredHost {
+            hostname: ip.to_string(),
+            local_ip: Some(ip.to_string()),
+            tailscale_ip: None,
+            tailscale_hostname: None,
+            agent_port: port,
+            reachable: true,
+        })
+        .collect())
+}
+
+/// Record discovered agents in the known agents registry along with their reported host info
+/// Failures are reported as warnings so discovery output is never blocked by the registry
+pub fn record_discovered_agents(hosts: &[DiscoveredHost]) {
+    for host in hosts {
+        if let Err(e) = db::upsert_agent(host) {
+            eprintln!("⚠ Failed to record agent {}: {}", host.hostname, e);
+            continue;
+        }
+        let Some(address) = host.tailscale_ip.as_ref().or(host.local_ip.as_ref()) else {
+            continue;
+        };
+        if let Ok(info) = AgentClient::new(address, host.agent_port).get_host_info()
+            && let Err(e) = db::record_agent_host_info(address, &info)
+        {
+            eprintln!("⚠ Failed to record host info for {}: {}", host.hostname, e);
+        }
+    }
+}
@@ -37,14 +37,41 @@ pub enum AgentRequest {
         /// Timestamp of last sync (to avoid unnecessary transfers)
         last_sync: Option<i64>,
     },
+    /// Start, stop, restart or inspect a docker service on the agent's host
+    ControlService {
+        service: String,
+        action: ServiceAction,
+    },
     Ping,
 }
 
+/// Action to perform on a service via `AgentRequest::ControlService`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+    Status,
+}
+
+impl ServiceAction {
+    /// Docker subcommand for actions that change state (None for Status)
+    fn docker_command(self) -> Option<&'static str> {
+        match self {
+            ServiceAction::Start => Some("start"),
+            ServiceAction::Stop => Some("stop"),
+            ServiceAction::Restart => Some("restart"),
+            ServiceAction::Status => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum AgentResponse {
     Success { output: String },
     Error { message: String },
     HostInfo { info: HostInfo },
+    ServiceStatus { status: ServiceStatus },
     Pong,
 }
 
@@ -59,6 +86,19 @@ pub struct HostInfo {
     pub portainer_installed: bool,
 }
 
+/// State of a docker service (container) as reported by an agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub service: String,
+    /// Docker state: running, exited, restarting, paused, ...
+    pub state: String,
+    pub running: bool,
+    /// Health check status when the container defines one
+    pub health: Option<String>,
+    pub image: Option<String>,
+    pub started_at: Option<String>,
+}
+
 impl AgentServer {
     pub fn new(port: u16, secret: Option<String>) -> Self {
         Self { port, secret }
@@ -106,6 +146,14 @@ impl AgentServer {
                 from_hostname,
                 last_sync,
             } => self.sync_database(&from_hostname, last_sync)?,
+            AgentRequest::ControlService { service, action } => {
+                match self.control_service(&service, action) {
+                    Ok(status) => AgentResponse::ServiceStatus { status },
+                    Err(e) => AgentResponse::Error {
+                        message: format!("{:#}", e),
+                    },
+                }
+            }
         };
 
         // Send response
@@ -199,6 +247,58 @@ impl AgentServer {
         }
     }
 
+    /// Run the docker operation for `action` and report the service's resulting state
+    fn control_service(&self, service: &str, action: ServiceAction) -> Result<ServiceStatus> {
+        use std::process::Command;
+
+        // Reject names that docker could parse as flags or that aren't container names
+        if service.is_empty()
+            || service.starts_with('-')
+            || !service
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        {
+            anyhow::bail!("Invalid service name: {:?}", service);
+        }
+
+        if let Some(command) = action.docker_command() {
+            let output = Command::new("docker")
+                .args([command, service])
+                .output()
+                .context("Failed to run docker")?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "docker {} {} failed: {}",
+                    command,
+                    service,
+                    bytes_to_string(&output.stderr).trim()
+                );
+            }
+        }
+
+        let output = Command::new("docker")
+            .args(["inspect", "--type", "container", service])
+            .output()
+            .context("Failed to run docker")?;
+        if !output.status.success() {
+            anyhow::bail!("Service {} not found", service);
+        }
+        let inspect: serde_json::Value =
+            serde_json::from_slice(&output.stdout).context("Failed to parse docker inspect")?;
+        let container = &inspect[0];
+        let state = &container["State"];
+        let text = |value: &serde_json::Value| value.as_str().map(|s| s.to_string());
+
+        Ok(ServiceStatus {
+            service: service.to_string(),
+            state: text(&state["Status"]).unwrap_or_else(|| "unknown".to_string()),
+            running: state["Running"].as_bool().unwrap_or(false),
+            health: text(&state["Health"]["Status"]),
+            image: text(&container["Config"]["Image"]),
+            started_at: text(&state["StartedAt"]),
+        })
+    }
+
     fn sync_config(&self, _data: Vec<u8>) -> Result<AgentResponse> {
         // TODO: Decrypt and apply config sync
         // TODO: Handle conflicts
@@ -231,9 +331,7 @@ impl AgentServer {
         let mut db_settings = std::collections::HashMap::new();
         if let Ok(all_settings) = settings::select_many("1=1", &[]) {
             for row in all_settings {
-                if let Some(key) = row.key {
-                    db_settings.insert(key, row.value);
-                }
+                db_settings.insert(row.key, row.value);
             }
         }
 
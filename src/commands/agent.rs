@@ -1,5 +1,7 @@
-use crate::agent::{discovery::HostDiscovery, server::AgentServer, sync::ConfigSync};
+use crate::agent::discovery::{self, DiscoveredHost, HostDiscovery};
+use crate::agent::{server::AgentServer, sync::ConfigSync};
 use crate::config::service::get_current_hostname;
+use crate::db;
 use anyhow::{Context, Result};
 use clap::Subcommand;
 use std::io::Write;
@@ -29,6 +31,27 @@ pub enum AgentCommands {
         /// Show verbose output
         #[arg(long)]
         verbose: bool,
+        /// Scan this IPv4 CIDR range (e.g. 192.168.1.0/24) instead of Tailscale/local discovery
+        #[arg(long, value_name = "CIDR")]
+        cidr: Option<String>,
+        /// Agent port to probe when scanning a CIDR range
+        #[arg(long, default_value = "23500")]
+        port: u16,
+        /// Maximum number of hosts probed at once
+        #[arg(long, default_value = "64")]
+        concurrency: usize,
+        /// Connect timeout per host in milliseconds
+        #[arg(long, value_name = "MS", default_value = "500")]
+        timeout_ms: u64,
+    },
+    /// List every agent ever discovered with online/offline status
+    List {
+        /// Consider an agent offline when it hasn't been seen for this many seconds
+        #[arg(long, value_name = "SECONDS", default_value = "300")]
+        offline_after: u64,
+        /// Forget agents not seen for this many days
+        #[arg(long, value_name = "DAYS", default_value = "30")]
+        ttl_days: u64,
     },
     /// Sync configuration with discovered agents
     Sync {
@@ -60,8 +83,30 @@ pub async fn handle_agent(command: AgentCommands) -> Result<()> {
         AgentCommands::Status => {
             show_agent_status()?;
         }
-        AgentCommands::Discover { verbose } => {
-            discover_agents(verbose)?;
+        AgentCommands::Discover {
+            verbose,
+            cidr,
+            port,
+            concurrency,
+            timeout_ms,
+        } => match cidr {
+            Some(cidr) => scan_agents(
+                &cidr,
+                port,
+                concurrency,
+                Duration::from_millis(timeout_ms),
+                verbose,
+            )?,
+            None => discover_agents(verbose)?,
+        },
+        AgentCommands::List {
+            offline_after,
+            ttl_days,
+        } => {
+            list_agents(
+                Duration::from_secs(offline_after),
+                Duration::from_secs(ttl_days * 24 * 60 * 60),
+            )?;
         }
         AgentCommands::Sync { force } => {
             sync_with_agents(force)?;
@@ -228,6 +273,7 @@ fn show_agent_status() -> Result<()> {
         // Try to discover other agents
         let discovery = HostDiscovery::default();
         if let Ok(hosts) = discovery.discover_all() {
+            discovery::record_discovered_agents(&hosts);
             println!("Discovered Agents:");
             if hosts.is_empty() {
                 println!("  (none)");
@@ -259,7 +305,39 @@ fn discover_agents(verbose: bool) -> Result<()> {
 
     let discovery = HostDiscovery::default();
     let hosts = discovery.discover_all()?;
+    discovery::record_discovered_agents(&hosts);
+    print_discovered_hosts(&hosts, verbose)
+}
+
+/// Scan a CIDR range for agents
+fn scan_agents(
+    cidr: &str,
+    port: u16,
+    concurrency: usize,
+    timeout: Duration,
+    verbose: bool,
+) -> Result<()> {
+    let total = discovery::parse_cidr_hosts(cidr)?.len();
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Scanning {} for Halvor Agents", cidr);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+    println!(
+        "Probing {} address(es) on port {} ({} at a time, {}ms timeout)...",
+        total,
+        port,
+        concurrency.clamp(1, discovery::MAX_SCAN_CONCURRENCY),
+        timeout.as_millis()
+    );
+    println!();
 
+    let hosts = discovery::discover_agents_in_cidr(cidr, port, concurrency, timeout)?;
+    discovery::record_discovered_agents(&hosts);
+    print_discovered_hosts(&hosts, verbose)
+}
+
+fn print_discovered_hosts(hosts: &[DiscoveredHost], verbose: bool) -> Result<()> {
     if hosts.is_empty() {
         println!("No agents discovered.");
         println!();
@@ -270,7 +348,7 @@ fn discover_agents(verbose: bool) -> Result<()> {
     } else {
         println!("Discovered {} agent(s):", hosts.len());
         println!();
-        for host in &hosts {
+        for host in hosts {
             println!("  Hostname: {}", host.hostname);
             if let Some(ref ip) = host.tailscale_ip {
                 println!("    Tailscale IP: {}", ip);
@@ -304,6 +382,80 @@ fn discover_agents(verbose: bool) -> Result<()> {
     Ok(())
 }
 
+/// List known agents from the registry, pruning ones older than `ttl`
+fn list_agents(offline_after: Duration, ttl: Duration) -> Result<()> {
+    let pruned = db::prune_agents(ttl)?;
+    let agents = db::list_known_agents()?;
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Known Halvor Agents");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+
+    if pruned > 0 {
+        println!(
+            "Pruned {} agent(s) not seen in the last {} day(s)",
+            pruned,
+            ttl.as_secs() / 86400
+        );
+        println!();
+    }
+
+    if agents.is_empty() {
+        println!("No agents known yet. Run 'hal agent discover' to find agents.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<24} {:<18} {:<7} {:<10} LAST SEEN",
+        "HOSTNAME", "ADDRESS", "PORT", "STATUS"
+    );
+    for agent in &agents {
+        let seen_ago = agent.seen_ago();
+        let status = if seen_ago as u64 <= offline_after.as_secs() {
+            "✓ online"
+        } else {
+            "✗ offline"
+        };
+        println!(
+            "{:<24} {:<18} {:<7} {:<10} {} ago",
+            agent.hostname,
+            agent.address,
+            agent.agent_port,
+            status,
+            format_age(seen_ago)
+        );
+        if let Some(info) = agent.reported_host_info() {
+            println!(
+                "    docker: {}  tailscale: {}  portainer: {}",
+                info.docker_version.as_deref().unwrap_or("not installed"),
+                if info.tailscale_installed {
+                    "yes"
+                } else {
+                    "no"
+                },
+                if info.portainer_installed {
+                    "yes"
+                } else {
+                    "no"
+                }
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Format an age in seconds as a short human-readable string
+fn format_age(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
 /// Sync configuration with discovered agents
 fn sync_with_agents(force: bool) -> Result<()> {
     let local_hostname = get_current_hostname()?;
@@ -1,46 +1,152 @@
 use crate::config;
 use crate::services::backup;
+use crate::utils::crypto;
 use anyhow::Result;
 
+/// Options for creating service backups
+pub struct BackupOptions {
+    /// Retention for service backups (falls back to BACKUP_RETENTION)
+    pub keep: Option<usize>,
+    /// Check every archive after creating a service backup
+    pub verify: bool,
+    /// Sync volumes with rsync against the previous backup instead of tarring them
+    pub incremental: bool,
+    /// Encrypt the archives with a passphrase (HALVOR_BACKUP_KEY or a prompt)
+    pub encrypt: bool,
+}
+
 /// Handle backup command
 /// hostname: None = local, Some(hostname) = remote host
+/// dry_run: skip the backup and only preview which old backups would be pruned
 pub fn handle_backup(
     hostname: Option<&str>,
     service: Option<&str>,
     env: bool,
     list: bool,
+    options: BackupOptions,
+    dry_run: bool,
 ) -> Result<()> {
     let config = config::load_config()?;
     let target_host = hostname.unwrap_or("localhost");
+    let BackupOptions {
+        keep,
+        verify,
+        incremental,
+        encrypt,
+    } = options;
+
+    let keep = match keep {
+        Some(keep) => Some(keep),
+        None => backup::retention_from_env()?,
+    };
+    if keep == Some(0) {
+        anyhow::bail!("Retention must keep at least 1 backup");
+    }
+    if dry_run && (service.is_none() || keep.is_none() || list || env) {
+        anyhow::bail!(
+            "--dry-run for backup previews retention pruning and requires a service and --keep (or BACKUP_RETENTION)"
+        );
+    }
+
+    if encrypt && (list || env || dry_run) {
+        anyhow::bail!("--encrypt only applies when creating service or database backups");
+    }
+    // Ask up front so a long backup isn't left waiting on a prompt
+    let passphrase = if encrypt {
+        Some(crypto::get_backup_passphrase(true)?)
+    } else {
+        None
+    };
 
     if list {
         backup::list_backups(target_host, &config)?;
     } else if env {
         backup::backup_to_env(target_host, service, &config)?;
     } else if let Some(service) = service {
-        backup::backup_service(target_host, service, &config)?;
+        if !dry_run {
+            backup::backup_service(
+                target_host,
+                service,
+                verify,
+                incremental,
+                passphrase.as_deref(),
+                &config,
+            )?;
+        }
+        if let Some(keep) = keep {
+            backup::prune_service_backups(target_host, service, keep, dry_run, &config)?;
+        }
     } else {
         // Interactive backup selection
-        backup::backup_interactive(target_host, &config)?;
+        backup::backup_interactive(target_host, incremental, passphrase.as_deref(), &config)?;
+    }
+    Ok(())
+}
+
+/// Install (`schedule` is Some) or remove a scheduled backup on this machine
+/// hostname, service, keep, verify and incremental are passed on to the scheduled `hal backup`
+pub fn handle_backup_schedule(
+    schedule: Option<&str>,
+    hostname: Option<&str>,
+    service: Option<&str>,
+    keep: Option<usize>,
+    verify: bool,
+    incremental: bool,
+) -> Result<()> {
+    if keep == Some(0) {
+        anyhow::bail!("Retention must keep at least 1 backup");
     }
+    let scheduled = backup::schedule::ScheduledBackup {
+        service,
+        hostname,
+        keep,
+        verify,
+        incremental,
+    };
+    match schedule {
+        Some(schedule) => backup::schedule::install_schedule(schedule, &scheduled),
+        None => backup::schedule::uninstall_schedule(&scheduled),
+    }
+}
+
+/// Print every backup on the host as JSON (`backup --list --output json`)
+pub fn handle_backup_list_json(hostname: Option<&str>) -> Result<()> {
+    let config = config::load_config()?;
+    let entries = backup::backup_entries(hostname.unwrap_or("localhost"), &config)?;
+    println!("{}", serde_json::to_string_pretty(&entries)?);
     Ok(())
 }
 
 /// Handle restore command
 /// hostname: None = local, Some(hostname) = remote host
+/// list: show the backup's contents instead of restoring
+/// volumes: only restore these volumes (all when empty)
 pub fn handle_restore(
     hostname: Option<&str>,
     service: Option<&str>,
     env: bool,
     backup: Option<&str>,
+    list: bool,
+    volumes: &[String],
 ) -> Result<()> {
     let config = config::load_config()?;
     let target_host = hostname.unwrap_or("localhost");
 
-    if env {
+    if !volumes.is_empty() && (service.is_none() || env || list) {
+        anyhow::bail!(
+            "--volume requires a service. Usage: halvor restore <service> --volume <name>"
+        );
+    }
+
+    if list {
+        let service = service.ok_or_else(|| {
+            anyhow::anyhow!("--list requires a service. Usage: halvor restore <service> --list")
+        })?;
+        backup::show_service_backup(target_host, service, backup, &config)?;
+    } else if env {
         backup::restore_from_env(target_host, service, &config)?;
     } else if let Some(service) = service {
-        backup::restore_service(target_host, service, backup, &config)?;
+        backup::restore_service(target_host, service, backup, volumes, &config)?;
     } else {
         // Interactive restore selection
         backup::restore_interactive(target_host, &config)?;
@@ -50,19 +156,23 @@ pub fn handle_restore(
 
 /// Handle database backup command
 /// Requires administrator password for security
-pub fn handle_backup_db(path: Option<&str>) -> Result<()> {
+pub fn handle_backup_db(path: Option<&str>, encrypt: bool) -> Result<()> {
     use crate::config::service;
 
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Database Backup");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
-    println!("⚠️  This operation requires administrator privileges.");
-    println!("   The database backup will be unencrypted (plain SQLite format).");
+    if encrypt {
+        println!("The database backup will be encrypted with your backup passphrase.");
+    } else {
+        println!("⚠️  This operation requires administrator privileges.");
+        println!("   The database backup will be unencrypted (plain SQLite format).");
+    }
     println!();
 
     // Perform the backup (will use sudo if needed)
-    service::backup_database(path)?;
+    service::backup_database(path, encrypt)?;
 
     println!();
     println!("✓ Database backup complete");
@@ -1,7 +1,9 @@
 // Build command handler - delegates to build service modules
+use crate::services::build::cli::select_platforms;
 use crate::services::build::{
-    build_and_sign_ios, build_and_sign_mac, build_android, build_cli, build_web, build_web_docker,
-    push_ios_to_app_store, run_web_prod, sign_android,
+    android_artifacts, build_and_sign_ios, build_and_sign_mac, build_android, build_cli, build_web,
+    build_web_docker, ios_artifacts, mac_artifacts, push_ios_to_app_store, run_web_prod,
+    sign_android, toolchain, verify_and_record, web_artifacts,
 };
 use anyhow::Result;
 use clap::Subcommand;
@@ -47,10 +49,48 @@ pub enum BuildCommands {
     },
 }
 
-pub fn handle_build(command: BuildCommands) -> Result<()> {
+/// Toolchain problems that would stop this build
+fn check_environment(command: &BuildCommands) -> Result<Vec<toolchain::ToolchainIssue>> {
+    Ok(match command {
+        BuildCommands::Ios { .. } | BuildCommands::Mac => toolchain::check_apple(),
+        BuildCommands::Android => toolchain::check_android(),
+        BuildCommands::Web {
+            release,
+            bare_metal,
+            docker,
+            ..
+        } => toolchain::check_web(!bare_metal && (*release || *docker)),
+        BuildCommands::Cli { platforms, .. } => {
+            let targets: Vec<&str> = select_platforms(platforms.as_deref())?
+                .iter()
+                .flat_map(|(_, targets)| targets.iter().copied())
+                .collect();
+            // Without --platforms, `build cli` skips what this machine can't build
+            if platforms.is_some() {
+                toolchain::check_cli_targets(&targets)
+            } else {
+                toolchain::skip_unbuildable_cli_targets(&targets).1
+            }
+        }
+    })
+}
+
+/// Build, or with `check` only validate the build environment
+pub fn handle_build(command: BuildCommands, check: bool) -> Result<()> {
+    if check {
+        toolchain::ensure_ready(&check_environment(&command)?)?;
+        println!("✓ Build environment ready");
+        return Ok(());
+    }
+    // The CLI build installs missing Rust targets itself before validating
+    if !matches!(command, BuildCommands::Cli { .. }) {
+        toolchain::ensure_ready(&check_environment(&command)?)?;
+    }
+
     match command {
         BuildCommands::Ios { push } => {
             build_and_sign_ios()?;
+            verify_and_record("ios", &ios_artifacts())?;
             println!("✓ iOS build complete");
 
             if push {
@@ -59,11 +99,13 @@ pub fn handle_build(command: BuildCommands) -> Result<()> {
         }
         BuildCommands::Mac => {
             build_and_sign_mac()?;
+            verify_and_record("mac", &mac_artifacts())?;
             println!("✓ macOS build complete");
         }
         BuildCommands::Android => {
             build_android()?;
             sign_android()?;
+            verify_and_record("android", &android_artifacts())?;
             println!("✓ Android build complete");
         }
         BuildCommands::Web {
@@ -76,6 +118,7 @@ pub fn handle_build(command: BuildCommands) -> Result<()> {
             // If --bare-metal is specified, build local Rust binary only
             if bare_metal {
                 build_web(release)?;
+                verify_and_record("web", &web_artifacts(release))?;
             }
             // If --release is specified, build Docker production container
             // Otherwise, if --docker is specified, build Docker container
@@ -83,6 +126,7 @@ pub fn handle_build(command: BuildCommands) -> Result<()> {
                 build_web_docker(release, push)?;
             } else {
                 build_web(release)?;
+                verify_and_record("web", &web_artifacts(release))?;
                 if run {
                     run_web_prod()?;
                 }
@@ -0,0 +1,9 @@
+use anyhow::Result;
+use clap_complete::Shell;
+
+/// Write a completion script for `shell` to stdout
+/// `cli` is the full command definition built by the binary (see `build_cli` in main.rs)
+pub fn handle_completions(shell: Shell, mut cli: clap::Command, bin_name: &str) -> Result<()> {
+    clap_complete::generate(shell, &mut cli, bin_name, &mut std::io::stdout());
+    Ok(())
+}
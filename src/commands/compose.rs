@@ -0,0 +1,55 @@
+use crate::config;
+use crate::services::compose;
+use anyhow::Result;
+use clap::ValueEnum;
+
+/// What to do with a compose project
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComposeAction {
+    /// Create and start the services in the background
+    Up,
+    /// Stop and remove the services
+    Down,
+    /// Restart the services
+    Restart,
+}
+
+impl ComposeAction {
+    /// Compose arguments for this action, limited to `service` when given
+    fn compose_args(self, service: Option<&str>) -> Vec<&str> {
+        let mut args = match (self, service) {
+            (ComposeAction::Up, _) => vec!["up", "-d"],
+            // `down` can't target a single service, so stop and remove just that one
+            (ComposeAction::Down, Some(_)) => vec!["rm", "--stop", "--force"],
+            (ComposeAction::Down, None) => vec!["down"],
+            (ComposeAction::Restart, _) => vec!["restart"],
+        };
+        args.extend(service);
+        args
+    }
+
+    /// Copy `file` to `hostname` (local when None) and run this action there
+    // The binary dispatches on the library's copy of this enum, so its own copy goes unused
+    #[allow(dead_code)]
+    pub fn handle(
+        self,
+        hostname: Option<&str>,
+        file: &str,
+        env_file: Option<&str>,
+        service: Option<&str>,
+        bwlimit: Option<u32>,
+    ) -> Result<()> {
+        let config = config::load_config()?;
+        let target_host = hostname.unwrap_or("localhost");
+        let compose_file = compose::resolve_compose_file(file)?;
+        let env_file = env_file.map(compose::resolve_env_file).transpose()?;
+        compose::run_compose(
+            target_host,
+            &config,
+            &compose_file,
+            env_file.as_deref(),
+            &self.compose_args(service),
+            bwlimit,
+        )
+    }
+}
@@ -5,8 +5,18 @@ use anyhow::Result;
 pub enum ConfigCommands {
     /// List current configuration
     List,
-    /// Initialize or update HAL configuration (interactive)
-    Init,
+    /// Initialize or update HAL configuration (interactive unless --non-interactive)
+    Init {
+        /// Path to the .env file (skips the prompt)
+        #[arg(long, value_name = "PATH")]
+        env_file: Option<String>,
+        /// Release channel to use for updates
+        #[arg(long, value_enum)]
+        channel: Option<crate::config::config_manager::ReleaseChannel>,
+        /// Never prompt; fail if a required value is missing
+        #[arg(long)]
+        non_interactive: bool,
+    },
     /// Set the environment file path
     SetEnv {
         /// Path to the .env file
@@ -18,11 +28,41 @@ pub enum ConfigCommands {
     /// Set release channel to experimental
     #[command(name = "experimental")]
     SetExperimental,
+    /// Set the host commands use when -H is omitted (HALVOR_HOST overrides it)
+    SetDefaultHost {
+        /// Configured hostname
+        name: Option<String>,
+        /// Clear the default host so commands target this machine again
+        #[arg(long, conflicts_with = "name", required_unless_present = "name")]
+        clear: bool,
+    },
+    /// Try IPv4 or IPv6 addresses first when connecting to hosts (--prefer overrides it)
+    SetPrefer {
+        /// Address family to try first
+        #[arg(value_enum)]
+        family: Option<crate::config::config_manager::AddressFamily>,
+        /// Clear the preference so addresses are tried in the configured order
+        #[arg(long, conflicts_with = "family", required_unless_present = "family")]
+        clear: bool,
+    },
+    /// Hours between startup update checks (0 turns the check off)
+    SetUpdateInterval {
+        /// Hours the last answer is reused before GitHub is asked again
+        hours: Option<u64>,
+        /// Go back to the default of once a day
+        #[arg(long, conflicts_with = "hours", required_unless_present = "hours")]
+        clear: bool,
+    },
     /// Create new configuration
     Create {
         #[command(subcommand)]
         command: CreateConfigCommands,
     },
+    /// Manage profiles (separate env file, database and release channel per homelab)
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
     /// Create example .env file
     Env,
     /// Set backup location (for current system if no hostname provided)
@@ -41,6 +81,8 @@ pub enum ConfigCommands {
         #[arg(long)]
         from_env: bool,
     },
+    /// Interactively edit a host, pre-filled with its current values (writes .env and database)
+    Edit,
     /// Set IP address for hostname
     Ip {
         /// IP address
@@ -61,8 +103,70 @@ pub enum ConfigCommands {
         /// Backup path
         value: String,
     },
+    /// Set the SSH user for hostname (defaults to the current user)
+    SshUser {
+        /// SSH user name
+        value: String,
+    },
+    /// Set the SSH port for hostname (defaults to 22)
+    SshPort {
+        /// SSH port (1-65535)
+        value: String,
+    },
+    /// Set the SSH jump host for hostname (another configured host, or [user@]host[:port])
+    Jump {
+        /// Jump host
+        value: String,
+    },
     /// Show differences between .env and database configurations
-    Diff,
+    Diff {
+        /// Choose the .env or database value for each differing field and write it to both
+        #[arg(long)]
+        apply: bool,
+        /// With --apply, keep the .env value for every field without asking
+        #[arg(long, requires = "apply", conflicts_with = "db_wins")]
+        env_wins: bool,
+        /// With --apply, keep the database value for every field without asking
+        #[arg(long, requires = "apply")]
+        db_wins: bool,
+    },
+    /// Validate configuration (SMB hosts, backup paths, duplicate IPs, tailscale names)
+    Validate,
+    /// Encrypt the .env file to .env.enc (passphrase from HALVOR_ENV_KEY or prompt)
+    Encrypt,
+    /// Decrypt .env.enc back to a plaintext .env file
+    Decrypt,
+    /// Set a global setting in the database (e.g. ACME_EMAIL, NGINX_PROXY_MANAGER_URL)
+    Set {
+        /// Setting name
+        key: String,
+        /// Setting value
+        value: String,
+    },
+    /// Remove a global setting from the database
+    Unset {
+        /// Setting name
+        key: String,
+    },
+    /// Export hosts, SMB servers and settings from the database to a JSON or TOML file
+    Export {
+        /// Output file (.toml for TOML, anything else for JSON)
+        file: String,
+        /// Include passwords and other secrets in plaintext instead of redacting them
+        #[arg(long)]
+        include_secrets: bool,
+    },
+    /// Import a file written by `config export` into the database
+    Import {
+        /// Snapshot file (.toml for TOML, anything else for JSON)
+        file: String,
+        /// Add and update entries, keeping everything else (default)
+        #[arg(long, conflicts_with = "replace")]
+        merge: bool,
+        /// Also delete hosts, SMB servers and settings that aren't in the file
+        #[arg(long)]
+        replace: bool,
+    },
 }
 
 #[derive(clap::Subcommand, Clone)]
@@ -81,6 +185,31 @@ pub enum CreateConfigCommands {
     },
 }
 
+#[derive(clap::Subcommand, Clone)]
+pub enum ProfileCommands {
+    /// Create a profile
+    Create {
+        /// Profile name
+        name: String,
+        /// Path to the profile's .env file
+        #[arg(long, value_name = "PATH")]
+        env_file: Option<String>,
+        /// Release channel to use for updates (default: stable)
+        #[arg(long, value_enum)]
+        channel: Option<crate::config::config_manager::ReleaseChannel>,
+        /// Database file (default: profiles/<name>/halvor.db in the config directory)
+        #[arg(long, value_name = "PATH")]
+        db: Option<String>,
+    },
+    /// Use a profile when --profile isn't given ("default" for the top-level settings)
+    Use {
+        /// Profile name
+        name: String,
+    },
+    /// List profiles (* marks the one in effect)
+    List,
+}
+
 #[derive(clap::Subcommand, Clone)]
 pub enum DbCommands {
     /// Backup the SQLite database
@@ -88,18 +217,46 @@ pub enum DbCommands {
         /// Path to save backup (defaults to current directory with timestamp)
         #[arg(long)]
         path: Option<String>,
+        /// Encrypt the backup with a passphrase (HALVOR_BACKUP_KEY or prompt)
+        #[arg(long)]
+        encrypt: bool,
     },
     /// Generate Rust structs from database schema
     Generate,
     /// Manage database migrations (defaults to running all pending migrations)
     Migrate {
+        /// Accept applied migrations whose files changed since they ran, recording the new checksums
+        #[arg(long, global = true)]
+        allow_checksum_mismatch: bool,
+        /// Show the pending migrations and their SQL without applying them (migrate and up only)
+        #[arg(long, global = true)]
+        plan: bool,
+        /// Don't copy the database before applying pending migrations
+        #[arg(long, global = true)]
+        no_backup: bool,
         #[command(subcommand)]
         command: Option<MigrateCommands>,
     },
     /// Sync environment file to database (load env values into DB, delete DB values not in env)
-    Sync,
+    Sync {
+        /// Only print the changes that would be made
+        #[arg(long)]
+        plan: bool,
+        /// Apply the changes without asking for confirmation
+        #[arg(long, short = 'y', conflicts_with = "plan")]
+        yes: bool,
+        /// Overwrite host fields that were also changed in the database since the last sync
+        #[arg(long)]
+        force: bool,
+    },
     /// Restore database from backup
     Restore,
+    /// Compact the database (VACUUM) and refresh query statistics (ANALYZE)
+    Vacuum {
+        /// Run PRAGMA integrity_check first and fail if the database is not healthy
+        #[arg(long)]
+        integrity_check: bool,
+    },
 }
 
 #[derive(clap::Subcommand, Clone)]
@@ -107,7 +264,11 @@ pub enum MigrateCommands {
     /// Run the next pending migration (migrate forward one)
     Up,
     /// Rollback the last applied migration (migrate backward one)
-    Down,
+    Down {
+        /// Roll back every migration newer than this version instead of just the last one
+        #[arg(long, value_name = "VERSION")]
+        to: Option<u32>,
+    },
     /// List migrations and interactively select one to migrate to
     List,
     /// Generate a new migration file
@@ -123,17 +284,124 @@ pub enum MigrateCommands {
     },
 }
 
+/// Subcommand names (and aliases) accepted by `hal config`, read from the clap definition
+pub fn config_command_names() -> Vec<String> {
+    use clap::Subcommand;
+    ConfigCommands::augment_subcommands(clap::Command::new("config"))
+        .get_subcommands()
+        .flat_map(|cmd| {
+            std::iter::once(cmd.get_name().to_string())
+                .chain(cmd.get_all_aliases().map(str::to_string))
+        })
+        .collect()
+}
+
+/// Parse a bare command name (e.g. "list", "diff") the same way `hal config <name>` does
+///
+/// Returns None for unknown names and for commands that need further arguments.
+pub fn parse_config_command(name: &str) -> Option<ConfigCommands> {
+    use clap::{FromArgMatches, Subcommand};
+    let matches = ConfigCommands::augment_subcommands(clap::Command::new("config"))
+        .try_get_matches_from(["config", &name.to_lowercase()])
+        .ok()?;
+    ConfigCommands::from_arg_matches(&matches).ok()
+}
+
 /// Handle config commands - delegates to service layer
 pub fn handle_config(
     arg: Option<&str>,
     verbose: bool,
     db: bool,
+    json: bool,
     command: Option<&ConfigCommands>,
 ) -> Result<()> {
-    service::handle_config_command(arg, verbose, db, command)
+    service::handle_config_command(arg, verbose, db, json, command)
 }
 
 /// Handle db subcommands - delegates to service layer
 pub fn handle_db_command(command: DbCommands) -> Result<()> {
     service::handle_db_command(command)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_command_names_cover_global_commands() {
+        let names = config_command_names();
+        for name in [
+            "list",
+            "init",
+            "set-env",
+            "stable",
+            "experimental",
+            "create",
+            "env",
+            "backup",
+            "commit",
+            "delete",
+            "diff",
+            "validate",
+            "encrypt",
+            "decrypt",
+            "export",
+            "import",
+        ] {
+            assert!(
+                names.iter().any(|n| n == name),
+                "missing command '{}'",
+                name
+            );
+        }
+        for retired in ["show", "backup-to-env", "set-stable", "set-experimental"] {
+            assert!(
+                !names.iter().any(|n| n == retired),
+                "unexpected '{}'",
+                retired
+            );
+        }
+    }
+
+    #[test]
+    fn parse_config_command_matches_clap_names() {
+        assert!(matches!(
+            parse_config_command("list"),
+            Some(ConfigCommands::List)
+        ));
+        assert!(matches!(
+            parse_config_command("LIST"),
+            Some(ConfigCommands::List)
+        ));
+        assert!(matches!(
+            parse_config_command("stable"),
+            Some(ConfigCommands::SetStable)
+        ));
+        assert!(matches!(
+            parse_config_command("experimental"),
+            Some(ConfigCommands::SetExperimental)
+        ));
+        assert!(matches!(
+            parse_config_command("backup"),
+            Some(ConfigCommands::Backup)
+        ));
+        assert!(matches!(
+            parse_config_command("diff"),
+            Some(ConfigCommands::Diff { apply: false, .. })
+        ));
+        assert!(matches!(
+            parse_config_command("validate"),
+            Some(ConfigCommands::Validate)
+        ));
+    }
+
+    #[test]
+    fn parse_config_command_rejects_unknown_and_incomplete_commands() {
+        assert!(parse_config_command("show").is_none());
+        assert!(parse_config_command("backup-to-env").is_none());
+        assert!(parse_config_command("my-server").is_none());
+        // Commands that need an argument can't be given as a bare name
+        assert!(parse_config_command("ip").is_none());
+        assert!(parse_config_command("set-env").is_none());
+    }
+}
@@ -3,17 +3,50 @@ use crate::services::docker;
 use crate::utils::exec::Executor;
 use anyhow::Result;
 
-pub fn handle_docker(hostname: &str) -> Result<()> {
+#[derive(clap::Subcommand, Clone)]
+pub enum DockerCommands {
+    /// Change the Docker daemon configuration (/etc/docker/daemon.json)
+    Config {
+        /// JSON object to deep-merge into daemon.json (null removes a key), e.g.
+        /// '{"log-opts":{"max-size":"10m"}}'
+        #[arg(long, value_name = "JSON")]
+        set_json: String,
+    },
+}
+
+pub fn handle_docker(hostname: &str, dry_run: bool) -> Result<()> {
     let config = config::load_config()?;
-    docker::install_docker(hostname, &config)?;
+    docker::install_docker(hostname, dry_run, &config)?;
     Ok(())
 }
 
-/// Diagnose Docker daemon issues
-pub fn diagnose_docker(hostname: Option<&str>) -> Result<()> {
+/// Diagnose Docker daemon issues, as text or a JSON report
+pub fn diagnose_docker(hostname: Option<&str>, json: bool) -> Result<()> {
     let config = config::load_config()?;
     let target_host = hostname.unwrap_or("localhost");
     let exec = Executor::new(target_host, &config)?;
 
-    docker::diagnostics::diagnose_docker(&exec, target_host)
+    docker::diagnostics::diagnose_docker(&exec, target_host, json)
+}
+
+/// Prune unused Docker resources to reclaim disk space
+pub fn prune_docker(hostname: Option<&str>, include_volumes: bool) -> Result<()> {
+    let config = config::load_config()?;
+    let target_host = hostname.unwrap_or("localhost");
+    docker::prune_docker(target_host, include_volumes, &config)
+}
+
+impl DockerCommands {
+    /// Run this subcommand against `hostname` (local when None)
+    // The binary dispatches on the library's copy of this enum, so its own copy goes unused
+    #[allow(dead_code)]
+    pub fn handle(&self, hostname: Option<&str>) -> Result<()> {
+        let config = config::load_config()?;
+        let target_host = hostname.unwrap_or("localhost");
+        match self {
+            DockerCommands::Config { set_json } => {
+                docker::set_daemon_json(target_host, set_json, &config)
+            }
+        }
+    }
 }
@@ -0,0 +1,51 @@
+use crate::config;
+use crate::utils::exec::Executor;
+use crate::utils::ssh::shell_escape;
+use anyhow::Result;
+use std::process::ExitStatus;
+
+/// Handle exec command: run `command` on `host` with the terminal attached,
+/// then exit with the command's exit code
+pub fn handle_exec(host: &str, shell: bool, command: &[String]) -> Result<()> {
+    let config = config::load_config()?;
+    // Resolves the hostname through find_hostname_in_config and picks local vs SSH
+    let exec = Executor::new(host, &config)?;
+
+    let status = if shell {
+        exec.execute_shell_interactive_status(&command.join(" "))?
+    } else {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("No command given"))?;
+        // ssh hands the command line to the remote shell, so quote each argument to keep
+        // the argv intact (locally the arguments are passed as-is)
+        let args: Vec<String> = if exec.is_local() {
+            args.to_vec()
+        } else {
+            args.iter().map(|arg| shell_escape(arg)).collect()
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        exec.execute_interactive_status(program, &args)?
+    };
+
+    let code = exit_code(status);
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Exit code to propagate, using the shell convention of 128 + signal for killed processes
+fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
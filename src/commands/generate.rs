@@ -3,8 +3,13 @@ use clap::Subcommand;
 
 #[derive(Subcommand, Clone)]
 pub enum GenerateCommands {
-    /// Generate FFI bindings for all platforms
-    FfiBindings,
+    /// Regenerate the Swift, Kotlin and TypeScript FFI bindings from src/ffi
+    #[command(alias = "ffi")]
+    FfiBindings {
+        /// Don't write anything; fail if the bindings are out of date (for CI)
+        #[arg(long)]
+        check: bool,
+    },
     /// Generate migration declarations
     Migrations,
     /// Generate everything (migrations + FFI bindings)
@@ -13,10 +18,21 @@ pub enum GenerateCommands {
 
 pub fn handle_generate(command: GenerateCommands) -> Result<()> {
     match command {
-        GenerateCommands::FfiBindings => {
+        GenerateCommands::FfiBindings { check } => {
             println!("Generating FFI bindings...");
-            crate::utils::ffi_bindings::generate_ffi_bindings_cli()?;
-            println!("✓ FFI bindings generated");
+            let changes = crate::utils::ffi_bindings::regenerate_ffi_bindings(check)?;
+            print_binding_changes(&changes);
+            if check && !changes.is_empty() {
+                anyhow::bail!(
+                    "{} FFI binding file(s) are out of date. Run: hal generate ffi",
+                    changes.len()
+                );
+            }
+            if check {
+                println!("✓ FFI bindings are up to date");
+            } else {
+                println!("✓ FFI bindings generated");
+            }
         }
         GenerateCommands::Migrations => {
             println!("Generating migration declarations...");
@@ -33,3 +49,23 @@ pub fn handle_generate(command: GenerateCommands) -> Result<()> {
 
     Ok(())
 }
+
+/// List each binding file that changed and the functions added to or removed from it
+fn print_binding_changes(changes: &[crate::utils::ffi_bindings::BindingsChange]) {
+    if changes.is_empty() {
+        println!("No changes to the FFI bindings");
+        return;
+    }
+    for change in changes {
+        println!("  {}", change.path.display());
+        for name in &change.added {
+            println!("    + {}", name);
+        }
+        for name in &change.removed {
+            println!("    - {}", name);
+        }
+        if change.added.is_empty() && change.removed.is_empty() {
+            println!("    (same functions, other content changed)");
+        }
+    }
+}
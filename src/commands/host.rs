@@ -0,0 +1,83 @@
+use crate::config::{HostConfig, service};
+use anyhow::Result;
+use clap::Subcommand;
+
+#[derive(Subcommand, Clone)]
+pub enum HostCommands {
+    /// Add a host without prompts (writes the database; --write-env also writes .env)
+    Add {
+        /// Host name (a DNS label, e.g. maple)
+        name: String,
+        /// IP address
+        #[arg(long)]
+        ip: Option<String>,
+        /// Tailscale hostname
+        #[arg(long)]
+        tailscale: Option<String>,
+        /// Absolute path for backups on the host
+        #[arg(long, value_name = "PATH")]
+        backup_path: Option<String>,
+        /// SSH user (defaults to the current user)
+        #[arg(long)]
+        ssh_user: Option<String>,
+        /// SSH port (defaults to 22)
+        #[arg(long, value_parser = crate::config::parse_ssh_port)]
+        ssh_port: Option<u16>,
+        /// SSH jump host (another configured host, or [user@]host[:port])
+        #[arg(long)]
+        jump: Option<String>,
+        /// Also write the host to the .env file
+        #[arg(long)]
+        write_env: bool,
+        /// Overwrite a host that already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Rename a host, moving its database records (--write-env also renames its .env keys)
+    Rename {
+        /// Current host name
+        old: String,
+        /// New host name (a DNS label)
+        new: String,
+        /// Also rename the host's keys in the .env file
+        #[arg(long)]
+        write_env: bool,
+    },
+}
+
+impl HostCommands {
+    /// Run the host command
+    // The binary dispatches on the library's copy of this enum, so its own copy goes unused
+    #[allow(dead_code)]
+    pub fn handle(self) -> Result<()> {
+        match self {
+            HostCommands::Add {
+                name,
+                ip,
+                tailscale,
+                backup_path,
+                ssh_user,
+                ssh_port,
+                jump,
+                write_env,
+                force,
+            } => {
+                let config = HostConfig {
+                    ip,
+                    hostname: None,
+                    tailscale,
+                    backup_path,
+                    ssh_user,
+                    ssh_port,
+                    jump,
+                };
+                service::add_host(&name, &config, write_env, force)
+            }
+            HostCommands::Rename {
+                old,
+                new,
+                write_env,
+            } => service::rename_host(&old, &new, write_env),
+        }
+    }
+}
@@ -1,67 +1,132 @@
 use crate::config;
 use crate::services;
 use crate::services::build::cli::build_target;
+use crate::services::installable::{self, InstallOptions, ServiceTarget};
 use anyhow::{Context, Result};
 use std::path::PathBuf;
 use std::process::Command;
 
+/// Portainer-specific install options
+pub struct PortainerOptions<'a> {
+    pub edition: &'a str,
+    /// Install the server (with UI) instead of the agent
+    pub host: bool,
+    /// Replace an existing install of a different edition
+    pub force: bool,
+    /// `--admin-password[=<value>]`
+    pub admin_password: Option<Option<&'a str>>,
+    pub admin_password_file: Option<&'a str>,
+}
+
 /// Handle install command
 /// hostname: None = local, Some(hostname) = remote host
 pub fn handle_install(
     hostname: Option<&str>,
     service: &str,
-    edition: &str,
-    host: bool,
+    portainer: PortainerOptions,
+    tailscale_tags: Option<&str>,
+    ignore_port_conflicts: bool,
+    dry_run: bool,
 ) -> Result<()> {
+    let PortainerOptions {
+        edition,
+        host,
+        force,
+        admin_password,
+        admin_password_file,
+    } = portainer;
     let config = config::load_config()?;
     let target_host = hostname.unwrap_or("localhost");
+    let service = service.to_lowercase();
 
-    match service.to_lowercase().as_str() {
-        "docker" => {
-            services::docker::install_docker(target_host, &config)?;
-        }
-        "tailscale" => {
-            if target_host == "localhost" {
-                services::tailscale::install_tailscale()?;
-            } else {
-                services::tailscale::install_tailscale_on_host(target_host, &config)?;
-            }
-        }
-        "portainer" => {
-            if host {
-                services::portainer::install_portainer_host(target_host, edition, &config)?;
-            } else {
-                services::portainer::install_portainer_agent(target_host, edition, &config)?;
-            }
-        }
-        "npm" => {
-            anyhow::bail!(
-                "NPM installation not yet implemented. Use 'halvor {} npm' to configure proxy hosts",
-                target_host
-            );
-        }
-        "cli" => {
-            install_cli()?;
-        }
-        _ => {
-            anyhow::bail!(
-                "Unknown service: {}. Supported services: docker, tailscale, portainer, npm, cli",
-                service
-            );
+    if tailscale_tags.is_some() && service != "tailscale" {
+        anyhow::bail!("--tailscale-tags is only supported when installing tailscale");
+    }
+    // Validate tags before touching the host
+    let tailscale_tags = match tailscale_tags {
+        Some(tags) => services::tailscale::parse_tailscale_tags(tags)?,
+        None => Vec::new(),
+    };
+
+    if force && service != "portainer" {
+        anyhow::bail!("--force is only supported when installing portainer");
+    }
+    if (admin_password.is_some() || admin_password_file.is_some())
+        && !(service == "portainer" && host)
+    {
+        anyhow::bail!(
+            "--admin-password and --admin-password-file are only supported with portainer --host"
+        );
+    }
+
+    // The halvor binary itself rather than a service on a host
+    if service == "cli" {
+        if ignore_port_conflicts {
+            anyhow::bail!("--ignore-port-conflicts is not supported when installing cli");
         }
+        return install_cli(dry_run);
     }
 
-    Ok(())
+    let Some(installable) = installable::find_service(&service) else {
+        anyhow::bail!(
+            "Unknown service: {}. Available services:\n{}\n  (or 'cli' to build and install halvor itself)",
+            service,
+            installable::service_list()
+        );
+    };
+    if ignore_port_conflicts && !installable.checks_ports() {
+        let supported: Vec<&str> = installable::services()
+            .filter(|s| s.checks_ports())
+            .map(|s| s.name())
+            .collect();
+        anyhow::bail!(
+            "--ignore-port-conflicts is not supported when installing {}. Supported services: {}",
+            service,
+            supported.join(", ")
+        );
+    }
+
+    // Resolve (and validate) the password before touching the host
+    let admin_password =
+        services::portainer::AdminPassword::resolve(admin_password, admin_password_file)?;
+
+    let target = ServiceTarget {
+        hostname: target_host,
+        config: &config,
+    };
+    installable.install(
+        &target,
+        &InstallOptions {
+            edition: edition.to_string(),
+            host,
+            tailscale_tags,
+            force,
+            admin_password,
+            ignore_port_conflicts,
+            dry_run,
+        },
+    )
 }
 
 /// Build and install CLI to system
-fn install_cli() -> Result<()> {
+/// When `dry_run` is set, only the target and install location are printed
+fn install_cli(dry_run: bool) -> Result<()> {
     println!("Building and installing CLI to system...");
 
     // Determine the current target triple
     let current_target = get_current_target()?;
     println!("Building for target: {}", current_target);
 
+    let install_path = cli_install_path();
+    if dry_run {
+        println!("[dry-run] localhost$ build halvor for {}", current_target);
+        println!(
+            "[dry-run] localhost$ install the binary to {}",
+            install_path.display()
+        );
+        return Ok(());
+    }
+
     // Build the CLI for the current platform
     let binary_path = match build_target(&current_target)? {
         Some(path) => {
@@ -75,19 +140,10 @@ fn install_cli() -> Result<()> {
 
     // Install the binary to cargo's bin directory
     println!("Installing CLI to system...");
-    let cargo_home = std::env::var("CARGO_HOME")
-        .ok()
-        .or_else(|| {
-            std::env::var("HOME")
-                .map(|home| format!("{}/.cargo", home))
-                .ok()
-        })
-        .unwrap_or_else(|| String::from("~/.cargo"));
-
-    let cargo_bin = PathBuf::from(&cargo_home).join("bin");
-    std::fs::create_dir_all(&cargo_bin).context("Failed to create cargo bin directory")?;
-
-    let install_path = cargo_bin.join("halvor");
+    let cargo_bin = install_path
+        .parent()
+        .context("Install path has no parent directory")?;
+    std::fs::create_dir_all(cargo_bin).context("Failed to create cargo bin directory")?;
 
     // Copy the binary to the install location
     std::fs::copy(&binary_path, &install_path).with_context(|| {
@@ -112,6 +168,20 @@ fn install_cli() -> Result<()> {
     Ok(())
 }
 
+/// Where `hal install cli` puts the binary: cargo's bin directory
+fn cli_install_path() -> PathBuf {
+    let cargo_home = std::env::var("CARGO_HOME")
+        .ok()
+        .or_else(|| {
+            std::env::var("HOME")
+                .map(|home| format!("{}/.cargo", home))
+                .ok()
+        })
+        .unwrap_or_else(|| String::from("~/.cargo"));
+
+    PathBuf::from(&cargo_home).join("bin").join("halvor")
+}
+
 /// Get the current Rust target triple
 fn get_current_target() -> Result<String> {
     // Try to get target from rustc
@@ -3,19 +3,101 @@ use anyhow::Result;
 
 /// Handle list command
 /// hostname: None = list all hosts, Some(hostname) = list services on that host
-pub fn handle_list(hostname: Option<&str>, verbose: bool) -> Result<()> {
-    if let Some(hostname) = hostname {
+/// all: list services on every configured host, `parallel` at a time
+/// options: filter and order the host list (only used when listing hosts)
+/// json: print machine-readable JSON instead of text
+pub fn handle_list(
+    hostname: Option<&str>,
+    verbose: bool,
+    all: bool,
+    parallel: usize,
+    options: &host::HostListOptions,
+    json: bool,
+) -> Result<()> {
+    if hostname.is_some() && (options.filter.is_some() || options.sort != host::HostSort::Name) {
+        anyhow::bail!("--filter and --sort apply to the host list, not to a single host");
+    }
+    if all {
+        if json {
+            anyhow::bail!("JSON output is not supported with --all");
+        }
+        let hosts = super::configured_hosts()?;
+        super::run_on_all_hosts(&hosts, parallel, super::rerun_on_host)?;
+    } else if let Some(hostname) = hostname {
         // List services on a specific host
-        list_host_services(hostname, verbose)?;
+        if json {
+            let services = host_services_json(hostname)?;
+            println!("{}", serde_json::to_string_pretty(&services)?);
+        } else {
+            list_host_services(hostname, verbose)?;
+        }
+    } else if json {
+        let hosts = host::list_hosts_json(verbose, options)?;
+        println!("{}", serde_json::to_string_pretty(&hosts)?);
     } else {
         // List all hosts
-        host::list_hosts_display(verbose)?;
+        host::list_hosts_display(verbose, options)?;
     }
     Ok(())
 }
 
+/// Collect the services running on a host as JSON
+fn host_services_json(hostname: &str) -> Result<serde_json::Value> {
+    use crate::config;
+    use crate::services::docker;
+    use crate::utils::exec::Executor;
+
+    let config = config::load_config()?;
+    let exec = Executor::new(hostname, &config)?;
+
+    let docker_version = exec
+        .execute_simple("docker", &["version", "--format", "{{.Server.Version}}"])
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    let portainer_running = docker::is_container_running(&exec, "portainer")?
+        || docker::is_container_running(&exec, "portainer-agent")?;
+
+    let tailscale_installed = exec
+        .execute_shell("tailscale status --json")?
+        .status
+        .success();
+
+    let mut containers = Vec::new();
+    let ps_output = exec
+        .execute_simple(
+            "docker",
+            &["ps", "--format", "{{.Names}}\t{{.Image}}\t{{.Status}}"],
+        )
+        .ok()
+        .filter(|output| output.status.success());
+    if let Some(output) = ps_output {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines().filter(|l| !l.trim().is_empty()) {
+            let mut fields = line.splitn(3, '\t');
+            containers.push(serde_json::json!({
+                "name": fields.next().unwrap_or_default(),
+                "image": fields.next().unwrap_or_default(),
+                "status": fields.next().unwrap_or_default(),
+            }));
+        }
+    }
+
+    let problem_containers = docker::list_unhealthy_containers(&exec).unwrap_or_default();
+
+    Ok(serde_json::json!({
+        "hostname": hostname,
+        "docker_version": docker_version,
+        "portainer_running": portainer_running,
+        "tailscale_installed": tailscale_installed,
+        "containers": containers,
+        "problem_containers": problem_containers,
+    }))
+}
+
 /// List services running on a host
-fn list_host_services(hostname: &str, _verbose: bool) -> Result<()> {
+fn list_host_services(hostname: &str, verbose: bool) -> Result<()> {
     use crate::config;
     use crate::services::docker;
     use crate::utils::exec::Executor;
@@ -82,5 +164,22 @@ fn list_host_services(hostname: &str, _verbose: bool) -> Result<()> {
         }
     }
 
+    if verbose {
+        println!();
+        match docker::list_unhealthy_containers(&exec) {
+            Ok(problems) if problems.is_empty() => {
+                println!("✓ No unhealthy, restarting or failed containers")
+            }
+            Ok(mut problems) => {
+                println!("Containers needing attention:");
+                for container in &mut problems {
+                    container.load_recent_logs(&exec);
+                    container.print("  ");
+                }
+            }
+            Err(e) => println!("⚠ Could not check container health: {}", e),
+        }
+    }
+
     Ok(())
 }
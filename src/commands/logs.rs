@@ -0,0 +1,9 @@
+use crate::config;
+use crate::services::docker;
+use anyhow::Result;
+
+/// Handle logs command: print (or with `follow`, stream) a container's logs on `host`
+pub fn handle_logs(host: &str, container: &str, follow: bool, tail: &str) -> Result<()> {
+    let config = config::load_config()?;
+    docker::show_container_logs(host, container, follow, tail, &config)
+}
@@ -9,33 +9,92 @@
 pub mod agent;
 pub mod backup;
 pub mod build;
+pub mod completions;
+pub mod compose;
 pub mod config;
 pub mod dev;
 pub mod docker;
+pub mod exec;
 pub mod generate;
+pub mod host;
 pub mod install;
 pub mod list;
+pub mod logs;
 pub mod npm;
 pub mod pia_vpn;
 pub mod portainer;
 pub mod provision;
 pub mod smb;
+pub mod ssh;
 pub mod sync;
 pub mod tailscale;
 pub mod uninstall;
 pub mod update;
 pub mod utils;
+pub mod web;
 
 use crate::Commands;
 use crate::Commands::*;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::io::Read;
 use std::mem;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Output format for commands that display data
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text (default)
+    #[default]
+    Text,
+    /// Machine-readable JSON
+    Json,
+}
 
 /// Dispatch command to appropriate handler
 ///
 /// Routes commands to their respective handlers based on the Commands enum.
 /// Each command variant should have a corresponding handler function in its module.
-pub fn handle_command(hostname: Option<String>, command: Commands) -> Result<()> {
+/// `dry_run` is honored by provision, install, docker install, and backup retention; other
+/// commands reject it.
+/// `output` selects JSON output for commands that display data (list, config, docker --diagnose,
+/// backup --list).
+pub fn handle_command(
+    hostname: Option<String>,
+    dry_run: bool,
+    output: OutputFormat,
+    command: Commands,
+) -> Result<()> {
+    let json = output == OutputFormat::Json;
+    // Without -H, `list` shows every host and `config` the global configuration,
+    // so only commands that act on a single host pick up HALVOR_HOST or the default host
+    let hostname = if matches!(
+        command,
+        Backup { .. }
+            | Restore { .. }
+            | Sync { .. }
+            | Install { .. }
+            | Uninstall { .. }
+            | Provision { .. }
+            | Smb { .. }
+            | Docker { .. }
+            | Compose { .. }
+            | Npm { .. }
+    ) {
+        crate::config::config_manager::resolve_target_host(hostname)
+    } else {
+        hostname
+    };
+    if dry_run
+        && !matches!(
+            command,
+            Provision { .. } | Install { .. } | Docker { .. } | Backup { .. }
+        )
+    {
+        anyhow::bail!("--dry-run is only supported for provision, install, docker, and backup");
+    }
+
     match command {
         Backup {
             service,
@@ -43,41 +102,125 @@ pub fn handle_command(hostname: Option<String>, command: Commands) -> Result<()>
             list,
             db,
             path,
+            keep,
+            verify,
+            incremental,
+            encrypt,
+            install_schedule,
+            uninstall_schedule,
         } => {
-            if db {
-                backup::handle_backup_db(path.as_deref())?;
+            if install_schedule.is_some() || uninstall_schedule {
+                if dry_run || json {
+                    anyhow::bail!("--dry-run and --output json don't apply to backup schedules");
+                }
+                backup::handle_backup_schedule(
+                    install_schedule.as_deref(),
+                    hostname.as_deref(),
+                    service.as_deref(),
+                    keep,
+                    verify,
+                    incremental,
+                )?;
+            } else if db {
+                if dry_run {
+                    anyhow::bail!("--dry-run is not supported with --db");
+                }
+                backup::handle_backup_db(path.as_deref(), encrypt)?;
+            } else if json {
+                if !list || dry_run || encrypt {
+                    anyhow::bail!("JSON output for backup is only supported with --list");
+                }
+                backup::handle_backup_list_json(hostname.as_deref())?;
             } else {
-                backup::handle_backup(hostname.as_deref(), service.as_deref(), env, list)?;
+                backup::handle_backup(
+                    hostname.as_deref(),
+                    service.as_deref(),
+                    env,
+                    list,
+                    backup::BackupOptions {
+                        keep,
+                        verify,
+                        incremental,
+                        encrypt,
+                    },
+                    dry_run,
+                )?;
             }
         }
         Restore {
             service,
             env,
             backup,
+            list,
+            volumes,
         } => {
             backup::handle_restore(
                 hostname.as_deref(),
                 service.as_deref(),
                 env,
                 backup.as_deref(),
+                list,
+                &volumes,
             )?;
         }
-        Sync { pull } => {
-            sync::handle_sync(hostname.as_deref(), pull)?;
+        Sync {
+            pull,
+            status,
+            ours,
+            theirs,
+            command,
+        } => {
+            if let Some(command) = command {
+                command.handle()?;
+            } else {
+                sync::handle_sync(hostname.as_deref(), pull, status, ours, theirs)?;
+            }
         }
-        List { verbose } => {
-            list::handle_list(hostname.as_deref(), verbose)?;
+        List {
+            verbose,
+            all,
+            parallel,
+            filter,
+            sort,
+        } => {
+            let sort = <crate::services::host::HostSort as clap::ValueEnum>::from_str(&sort, true)
+                .map_err(|e| anyhow::anyhow!(e))?;
+            let options = crate::services::host::HostListOptions { filter, sort };
+            list::handle_list(hostname.as_deref(), verbose, all, parallel, &options, json)?;
         }
         Install {
             service,
             edition,
             host,
+            tailscale_tags,
+            force,
+            admin_password,
+            admin_password_file,
+            ignore_port_conflicts,
         } => {
-            install::handle_install(hostname.as_deref(), &service, &edition, host)?;
+            install::handle_install(
+                hostname.as_deref(),
+                &service,
+                install::PortainerOptions {
+                    edition: &edition,
+                    host,
+                    force,
+                    admin_password: admin_password.as_ref().map(|value| value.as_deref()),
+                    admin_password_file: admin_password_file.as_deref(),
+                },
+                tailscale_tags.as_deref(),
+                ignore_port_conflicts,
+                dry_run,
+            )?;
         }
-        Uninstall { service } => {
+        Uninstall {
+            service,
+            keep_volumes: _,
+            purge_volumes,
+            yes,
+        } => {
             if let Some(service) = service {
-                uninstall::handle_uninstall(hostname.as_deref(), &service)?;
+                uninstall::handle_uninstall(hostname.as_deref(), &service, purge_volumes, yes)?;
             } else {
                 uninstall::handle_guided_uninstall(hostname.as_deref())?;
             }
@@ -85,25 +228,121 @@ pub fn handle_command(hostname: Option<String>, command: Commands) -> Result<()>
         Provision {
             portainer_host,
             portainer_edition,
+            tailscale_tags,
+            ignore_port_conflicts,
+            all,
+            parallel,
         } => {
-            provision::handle_provision(hostname.as_deref(), portainer_host, &portainer_edition)?;
+            if all {
+                let hosts = configured_hosts()?;
+                run_on_all_hosts(&hosts, parallel, rerun_on_host)?;
+                return Ok(());
+            }
+            provision::handle_provision(
+                hostname.as_deref(),
+                portainer_host,
+                &portainer_edition,
+                tailscale_tags.as_deref(),
+                ignore_port_conflicts,
+                dry_run,
+            )?;
         }
-        Smb { uninstall } => {
-            smb::handle_smb(hostname.as_deref(), uninstall)?;
+        Smb {
+            uninstall,
+            status,
+            remount,
+            force,
+        } => {
+            smb::handle_smb(hostname.as_deref(), uninstall, status, remount, force)?;
         }
-        Docker { diagnose } => {
-            if diagnose {
-                docker::diagnose_docker(hostname.as_deref())?;
+        Docker {
+            diagnose,
+            prune,
+            prune_volumes,
+            all,
+            parallel,
+            command,
+        } => {
+            if dry_run && (diagnose || prune || prune_volumes || command.is_some()) {
+                anyhow::bail!("--dry-run is only supported when installing Docker");
+            }
+            let run_docker = |target: Option<&str>| -> Result<()> {
+                if let Some(command) = &command {
+                    command.handle(target)
+                } else if diagnose {
+                    docker::diagnose_docker(target, json)
+                } else if prune || prune_volumes {
+                    docker::prune_docker(target, prune_volumes)
+                } else {
+                    docker::handle_docker(target.unwrap_or("localhost"), dry_run)
+                }
+            };
+            if all {
+                let hosts = configured_hosts()?;
+                run_on_all_hosts(&hosts, parallel, rerun_on_host)?;
             } else {
-                let target_host = hostname.as_deref().unwrap_or("localhost");
-                docker::handle_docker(target_host)?;
+                run_docker(hostname.as_deref())?;
             }
         }
+        Compose {
+            file,
+            action,
+            env_file,
+            service,
+            bwlimit,
+        } => {
+            action.handle(
+                hostname.as_deref(),
+                &file,
+                env_file.as_deref(),
+                service.as_deref(),
+                bwlimit,
+            )?;
+        }
+        Exec {
+            host,
+            shell,
+            command,
+        } => {
+            exec::handle_exec(&host, shell, &command)?;
+        }
+        Ssh {
+            hostname,
+            user,
+            fix_keys,
+            copy_keys,
+            args,
+        } => {
+            ssh::handle_ssh(&hostname, user, fix_keys, copy_keys, &args)?;
+        }
+        SshKeys { command } => {
+            command.handle()?;
+        }
+        Logs {
+            host,
+            container,
+            follow,
+            tail,
+        } => {
+            logs::handle_logs(&host, &container, follow, &tail)?;
+        }
         Npm {
             compose_file,
             service,
+            delete,
+            update,
+            profiles,
+            ssl,
         } => {
-            npm::handle_npm(hostname.as_deref(), &compose_file, service.as_deref())?;
+            npm::handle_npm(
+                hostname.as_deref(),
+                &compose_file,
+                service.as_deref(),
+                delete.as_deref(),
+                update.as_deref(),
+                &profiles,
+                ssl,
+            )?;
         }
         Vpn { command } => {
             // Convert from halvor::commands::pia_vpn::VpnCommands to commands::pia_vpn::VpnCommands
@@ -114,8 +353,21 @@ pub fn handle_command(hostname: Option<String>, command: Commands) -> Result<()>
         Update {
             experimental,
             force,
+            version,
+            unpin,
+            rollback,
+            check,
+            command,
         } => {
-            update::handle_update(experimental, force)?;
+            if let Some(command) = command {
+                command.handle(json)?;
+            } else if check {
+                update::handle_check(experimental, json)?;
+            } else if rollback {
+                update::handle_rollback()?;
+            } else {
+                update::handle_update(experimental, force, version.as_deref(), unpin)?;
+            }
         }
         Config {
             verbose,
@@ -125,20 +377,32 @@ pub fn handle_command(hostname: Option<String>, command: Commands) -> Result<()>
             // Convert Option<halvor::commands::config::ConfigCommands> to Option<commands::config::ConfigCommands>
             let local_command =
                 command.map(|c| unsafe { mem::transmute::<_, config::ConfigCommands>(c) });
-            config::handle_config(None, verbose, db, local_command.as_ref())?;
+            config::handle_config(
+                hostname.as_deref(),
+                verbose,
+                db,
+                json,
+                local_command.as_ref(),
+            )?;
         }
         Db { command } => {
             let local_command: config::DbCommands = unsafe { mem::transmute(command) };
             config::handle_db_command(local_command)?;
         }
+        Host { command } => {
+            command.handle()?;
+        }
+        Web { command } => {
+            command.handle()?;
+        }
         Agent { command } => {
             let local_command: agent::AgentCommands = unsafe { mem::transmute(command) };
             let rt = tokio::runtime::Runtime::new()?;
             rt.block_on(agent::handle_agent(local_command))?;
         }
-        Build { command } => {
+        Build { check, command } => {
             let local_command: build::BuildCommands = unsafe { mem::transmute(command) };
-            build::handle_build(local_command)?;
+            build::handle_build(local_command, check)?;
         }
         Dev { command } => {
             let rt = tokio::runtime::Runtime::new()?;
@@ -149,9 +413,243 @@ pub fn handle_command(hostname: Option<String>, command: Commands) -> Result<()>
             let local_command: generate::GenerateCommands = unsafe { mem::transmute(command) };
             generate::handle_generate(local_command)?;
         }
+        Completions { .. } => {
+            // Needs the full CLI definition (global flags included), which lives in main.rs
+            anyhow::bail!("Completions are generated by the binary entry point");
+        }
+    }
+    Ok(())
+}
+
+/// Get all configured hostnames, sorted for stable output
+pub fn configured_hosts() -> Result<Vec<String>> {
+    let config = crate::config::load_config()?;
+    let mut hosts: Vec<String> = config.hosts.keys().cloned().collect();
+    hosts.sort();
+    Ok(hosts)
+}
+
+/// Run an operation against multiple hosts concurrently
+///
+/// `command` builds the process to run for each host (usually [`rerun_on_host`]). At most
+/// `parallel` hosts are processed at once, and each host's output is captured and printed as
+/// one block when it finishes so hosts never interleave. A failure on one host does not abort
+/// the others; every host's result is collected and a summary table is printed.
+/// Hosts run without a terminal, so anything that would prompt (SSH or `sudo` passwords) fails
+/// on that host instead of waiting. Returns an error if any host failed.
+pub fn run_on_all_hosts<F>(hosts: &[String], parallel: usize, command: F) -> Result<()>
+where
+    F: Fn(&str) -> Result<Command> + std::marker::Sync,
+{
+    if hosts.is_empty() {
+        println!("No hosts configured.");
+        return Ok(());
+    }
+
+    let workers = parallel.clamp(1, hosts.len());
+    let width = hosts.iter().map(|h| h.len()).max().unwrap_or(0);
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<()>>>> = Mutex::new(hosts.iter().map(|_| None).collect());
+    let print_lock = Mutex::new(());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(host) = hosts.get(idx) else {
+                        break;
+                    };
+                    let (output, result) = match command(host).and_then(run_captured) {
+                        Ok((output, status)) => {
+                            let result = if status.success() {
+                                Ok(())
+                            } else {
+                                Err(anyhow::anyhow!(host_error(&output, status)))
+                            };
+                            (output, result)
+                        }
+                        Err(e) => (String::new(), Err(e)),
+                    };
+
+                    {
+                        let _guard = print_lock.lock().unwrap_or_else(|e| e.into_inner());
+                        for line in output.lines() {
+                            println!("{:<width$} │ {}", host, line, width = width);
+                        }
+                    }
+                    results.lock().unwrap_or_else(|e| e.into_inner())[idx] = Some(result);
+                }
+            });
+        }
+    });
+
+    let results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+
+    println!();
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Summary");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let mut failed = 0;
+    for (host, result) in hosts.iter().zip(results) {
+        match result {
+            Some(Ok(())) => println!("  ✓ {:<width$}  ok", host, width = width),
+            Some(Err(e)) => {
+                failed += 1;
+                println!("  ✗ {:<width$}  {}", host, e, width = width);
+            }
+            None => {
+                failed += 1;
+                println!("  ✗ {:<width$}  did not run", host, width = width);
+            }
+        }
+    }
+    println!();
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} hosts failed", failed, hosts.len());
     }
+    println!("✓ All {} hosts succeeded", hosts.len());
     Ok(())
 }
 
+/// Re-run this invocation of `hal` against a single host
+///
+/// Used with [`run_on_all_hosts`] so each host runs in its own process whose output can be
+/// captured
+pub fn rerun_on_host(host: &str) -> Result<Command> {
+    let exe = std::env::current_exe().context("Failed to get current executable path")?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut command = Command::new(exe);
+    command
+        .args(single_host_args(&args, host))
+        // The parent already checked once
+        .env("HALVOR_NO_UPDATE_CHECK", "1");
+    Ok(command)
+}
+
+/// Arguments for running `args` against only `host`: `--all`, `--parallel` and any host
+/// selection are dropped and `-H <host>` is added
+fn single_host_args(args: &[String], host: &str) -> Vec<String> {
+    let mut kept = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--" => {
+                kept.push(arg.clone());
+                kept.extend(iter.by_ref().cloned());
+            }
+            "--all" => {}
+            "--parallel" | "-H" | "--hostname" => {
+                iter.next();
+            }
+            _ if arg.starts_with("--parallel=")
+                || arg.starts_with("--hostname=")
+                || (arg.starts_with("-H") && arg.len() > 2) => {}
+            _ => kept.push(arg.clone()),
+        }
+    }
+    // Before any `--` so it isn't taken as a positional argument
+    let at = kept
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(kept.len());
+    kept.splice(at..at, ["-H".to_string(), host.to_string()]);
+    kept
+}
+
+/// Run a command without a terminal, returning its combined stdout and stderr
+fn run_captured(mut command: Command) -> Result<(String, ExitStatus)> {
+    let (mut reader, writer) = std::io::pipe()?;
+    command
+        .stdin(Stdio::null())
+        .stdout(writer.try_clone()?)
+        .stderr(writer);
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // A new session has no controlling terminal, so ssh can't prompt on /dev/tty
+        unsafe {
+            command.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+    }
+    let mut child = command.spawn().context("Failed to start hal for host")?;
+    // Our copies of the write end must be closed for the read to see end-of-file
+    drop(command);
+
+    let mut output = Vec::new();
+    reader.read_to_end(&mut output)?;
+    let status = child.wait()?;
+    Ok((String::from_utf8_lossy(&output).into_owned(), status))
+}
+
+/// The error a failed host reported, or its exit status if it didn't print one
+fn host_error(output: &str, status: ExitStatus) -> String {
+    output
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix("Error: "))
+        .map(|message| message.trim().to_string())
+        .unwrap_or_else(|| format!("exited with {}", status))
+}
+
 // Re-export command enums for convenience (these are used in main.rs)
 // Note: These are re-exported from their respective modules, not defined here
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn single_host_args_replace_host_selection() {
+        assert_eq!(
+            single_host_args(
+                &args(&["docker", "--prune", "--all", "--parallel", "8"]),
+                "nas"
+            ),
+            args(&["docker", "--prune", "-H", "nas"])
+        );
+        assert_eq!(
+            single_host_args(
+                &args(&["-H", "old", "list", "--all", "--parallel=2"]),
+                "nas"
+            ),
+            args(&["list", "-H", "nas"])
+        );
+        assert_eq!(
+            single_host_args(
+                &args(&["--hostname=old", "provision", "-Hx", "--all"]),
+                "nas"
+            ),
+            args(&["provision", "-H", "nas"])
+        );
+    }
+
+    #[test]
+    fn single_host_args_leave_arguments_after_separator_alone() {
+        assert_eq!(
+            single_host_args(&args(&["exec", "--all", "--", "ls", "--all"]), "nas"),
+            args(&["exec", "-H", "nas", "--", "ls", "--all"])
+        );
+    }
+
+    #[test]
+    fn host_error_is_the_reported_error() {
+        let status = std::process::Command::new("false").status().unwrap();
+        assert_eq!(
+            host_error(
+                "Checking...\nError: Docker is not installed\n\nStack backtrace:\n   0: main\n",
+                status
+            ),
+            "Docker is not installed"
+        );
+        assert!(host_error("", status).starts_with("exited with"));
+    }
+}
@@ -4,16 +4,34 @@ use anyhow::Result;
 
 /// Handle NPM command
 /// hostname: None = local, Some(hostname) = remote host
-pub fn handle_npm(hostname: Option<&str>, compose_file: &str, service: Option<&str>) -> Result<()> {
+pub fn handle_npm(
+    hostname: Option<&str>,
+    compose_file: &str,
+    service: Option<&str>,
+    delete: Option<&str>,
+    update: Option<&str>,
+    profiles: &[String],
+    ssl: bool,
+) -> Result<()> {
     let config = config::load_config()?;
     let target_host = hostname.unwrap_or("localhost");
     let rt = tokio::runtime::Runtime::new()?;
-    if let Some(service_spec) = service {
-        rt.block_on(npm::setup_single_proxy_host(target_host, service_spec))?;
+    if let Some(domain) = delete {
+        rt.block_on(npm::delete_proxy_host(target_host, domain))?;
+    } else if let Some(service_spec) = update {
+        rt.block_on(npm::update_proxy_host(target_host, service_spec))?;
+    } else if let Some(service_spec) = service {
+        rt.block_on(npm::setup_single_proxy_host(target_host, service_spec, ssl))?;
     } else if !compose_file.is_empty() {
-        rt.block_on(npm::setup_proxy_hosts(target_host, compose_file, &config))?;
+        rt.block_on(npm::setup_proxy_hosts(
+            target_host,
+            compose_file,
+            profiles,
+            ssl,
+            &config,
+        ))?;
     } else {
-        anyhow::bail!("Either --service or compose_file must be provided");
+        anyhow::bail!("Either --service, --update, --delete or compose_file must be provided");
     }
     Ok(())
 }
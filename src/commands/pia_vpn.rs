@@ -1,6 +1,7 @@
 use crate::config;
 use crate::services::pia_vpn as vpn;
 use anyhow::Result;
+use std::time::Duration;
 
 #[derive(clap::Subcommand, Clone)]
 pub enum VpnCommands {
@@ -17,11 +18,23 @@ pub enum VpnCommands {
     Deploy {
         /// Hostname to deploy VPN to
         hostname: String,
+        /// PIA region: the name of a .ovpn file in openvpn/ (default: ca-montreal)
+        #[arg(long)]
+        region: Option<String>,
+        /// Pull the image, start the container with docker compose and verify it
+        #[arg(long)]
+        start: bool,
+        /// Limit file uploads to this many kilobits per second
+        #[arg(long, value_name = "KBPS")]
+        bwlimit: Option<u32>,
     },
     /// Verify VPN is working correctly
     Verify {
         /// Hostname where VPN is running
         hostname: String,
+        /// Give up after this many seconds; slow checks are reported as timed out
+        #[arg(long, value_name = "SECONDS", default_value_t = vpn::DEFAULT_VERIFY_TIMEOUT.as_secs())]
+        timeout: u64,
     },
 }
 
@@ -33,11 +46,19 @@ pub fn handle_vpn(command: VpnCommands) -> Result<()> {
             let build_hostname = "localhost";
             vpn::build_and_push_vpn_image(build_hostname, &github_user, tag.as_deref(), &config)?;
         }
-        VpnCommands::Deploy { hostname } => {
-            vpn::deploy_vpn(&hostname, &config)?;
+        VpnCommands::Deploy {
+            hostname,
+            region,
+            start,
+            bwlimit,
+        } => {
+            vpn::deploy_vpn(&hostname, &config, region.as_deref(), start, bwlimit)?;
         }
-        VpnCommands::Verify { hostname } => {
-            vpn::verify_vpn(&hostname, &config)?;
+        VpnCommands::Verify { hostname, timeout } => {
+            let report = vpn::verify_vpn(&hostname, &config, Duration::from_secs(timeout))?;
+            if !report.passed() {
+                anyhow::bail!("VPN verification failed on {}", hostname);
+            }
         }
     }
 
@@ -6,9 +6,9 @@ use anyhow::Result;
 pub fn handle_portainer(hostname: &str, edition: &str, host: bool) -> Result<()> {
     let config = config::load_config()?;
     if host {
-        portainer::install_portainer_host(hostname, edition, &config)?;
+        portainer::install_portainer_host(hostname, edition, false, None, false, false, &config)?;
     } else {
-        portainer::install_portainer_agent(hostname, edition, &config)?;
+        portainer::install_portainer_agent(hostname, edition, false, false, false, &config)?;
     }
     Ok(())
 }
@@ -1,5 +1,5 @@
 use crate::config;
-use crate::services::provision;
+use crate::services::{provision, tailscale};
 use anyhow::Result;
 
 /// Handle provision command
@@ -8,9 +8,26 @@ pub fn handle_provision(
     hostname: Option<&str>,
     portainer_host: bool,
     portainer_edition: &str,
+    tailscale_tags: Option<&str>,
+    ignore_port_conflicts: bool,
+    dry_run: bool,
 ) -> Result<()> {
+    // Validate tags before touching the host
+    let tailscale_tags = match tailscale_tags {
+        Some(tags) => tailscale::parse_tailscale_tags(tags)?,
+        None => Vec::new(),
+    };
+
     let config = config::load_config()?;
     let target_host = hostname.unwrap_or("localhost");
-    provision::provision_host(target_host, portainer_host, portainer_edition, &config)?;
+    provision::provision_host(
+        target_host,
+        portainer_host,
+        portainer_edition,
+        &tailscale_tags,
+        ignore_port_conflicts,
+        dry_run,
+        &config,
+    )?;
     Ok(())
 }
@@ -4,7 +4,13 @@ use anyhow::Result;
 
 /// Handle SMB command
 /// hostname: None = local, Some(hostname) = remote host
-pub fn handle_smb(hostname: Option<&str>, uninstall: bool) -> Result<()> {
+pub fn handle_smb(
+    hostname: Option<&str>,
+    uninstall: bool,
+    status: bool,
+    remount: bool,
+    force: bool,
+) -> Result<()> {
     let config = config::load_config()?;
 
     // Ensure host is in config, prompt to set up if not
@@ -16,10 +22,26 @@ pub fn handle_smb(hostname: Option<&str>, uninstall: bool) -> Result<()> {
         config::service::ensure_host_in_config(None, &config)?
     };
 
-    if uninstall {
+    if [uninstall, status, remount]
+        .iter()
+        .filter(|&&flag| flag)
+        .count()
+        > 1
+    {
+        anyhow::bail!("Only one of --uninstall, --status and --remount can be used at a time");
+    }
+    if force && !remount {
+        anyhow::bail!("--force can only be used with --remount");
+    }
+
+    if status {
+        smb::show_smb_status(&target_host, &config)?;
+    } else if remount {
+        smb::remount_smb_shares(&target_host, force, &config)?;
+    } else if uninstall {
         smb::uninstall_smb_mounts(&target_host, &config)?;
     } else {
-        smb::setup_smb_mounts(&target_host, &config)?;
+        smb::setup_smb_mounts(&target_host, false, &config)?;
     }
     Ok(())
 }
@@ -0,0 +1,44 @@
+use crate::config;
+use crate::utils::ssh;
+use anyhow::Result;
+use clap::Subcommand;
+
+#[derive(Subcommand, Clone)]
+pub enum SshKeysCommands {
+    /// Show the known_hosts keys for a host's addresses (every configured host when omitted)
+    List {
+        /// Configured host
+        host: Option<String>,
+    },
+    /// Remove the known_hosts entries for every address of a host, e.g. after reprovisioning it
+    /// on the same IP ("REMOTE HOST IDENTIFICATION HAS CHANGED")
+    Remove {
+        /// Configured host
+        host: String,
+    },
+}
+
+/// Handle ssh command: open an interactive session (or run `args` remotely) on `hostname`
+pub fn handle_ssh(
+    hostname: &str,
+    user: Option<String>,
+    fix_keys: bool,
+    copy_keys: bool,
+    args: &[String],
+) -> Result<()> {
+    let config = config::load_config()?;
+    ssh::ssh_to_host(hostname, user, fix_keys, copy_keys, args, &config)
+}
+
+impl SshKeysCommands {
+    /// Run the ssh-keys command
+    // The binary dispatches on the library's copy of this enum, so its own copy goes unused
+    #[allow(dead_code)]
+    pub fn handle(self) -> Result<()> {
+        let config = config::load_config()?;
+        match self {
+            SshKeysCommands::List { host } => ssh::list_host_keys(host.as_deref(), &config),
+            SshKeysCommands::Remove { host } => ssh::remove_host_keys(&host, &config),
+        }
+    }
+}
@@ -2,16 +2,49 @@ use crate::config;
 use crate::services::sync;
 use anyhow::Result;
 
+#[derive(clap::Subcommand, Clone)]
+pub enum SyncCommands {
+    /// Re-encrypt all stored env data with a new key (HALVOR_NEW_KEY, or prompted for)
+    RotateKey,
+}
+
+impl SyncCommands {
+    // The binary dispatches on the library's copy of this enum, so its own copy goes unused
+    #[allow(dead_code)]
+    pub fn handle(&self) -> Result<()> {
+        match self {
+            SyncCommands::RotateKey => sync::rotate_key(),
+        }
+    }
+}
+
 /// Handle sync command
 /// hostname: None = local sync (push to remote), Some(hostname) = remote sync (pull from remote)
-pub fn handle_sync(hostname: Option<&str>, pull: bool) -> Result<()> {
+/// status: compare with the host instead of syncing
+/// ours/theirs: which side wins entries changed on both sides (prompts if neither is set)
+pub fn handle_sync(
+    hostname: Option<&str>,
+    pull: bool,
+    status: bool,
+    ours: bool,
+    theirs: bool,
+) -> Result<()> {
     let config = config::load_config()?;
 
-    if let Some(hostname) = hostname {
+    if let (Some(hostname), true) = (hostname, status) {
+        sync::sync_status(hostname, &config)?;
+    } else if let Some(hostname) = hostname {
         // Remote sync: sync with specified host
         // If pull=true, we're pulling from that host
         // If pull=false, we're pushing to that host
-        sync::sync_data(hostname, pull, &config)?;
+        let resolution = if ours {
+            Some(sync::ConflictResolution::Ours)
+        } else if theirs {
+            Some(sync::ConflictResolution::Theirs)
+        } else {
+            None
+        };
+        sync::sync_data(hostname, pull, resolution, &config)?;
     } else {
         // Local sync: push to all configured hosts (or pull from all)
         // For now, this requires a hostname - we could enhance this later
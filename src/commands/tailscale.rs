@@ -8,7 +8,7 @@ pub fn handle_tailscale(hostname: &str) -> Result<()> {
         tailscale::install_tailscale()?;
     } else {
         let config = config::load_config()?;
-        tailscale::install_tailscale_on_host(hostname, &config)?;
+        tailscale::install_tailscale_on_host(hostname, &[], false, &config)?;
     }
     Ok(())
 }
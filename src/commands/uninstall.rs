@@ -1,7 +1,7 @@
 use crate::config;
 use crate::config::config_manager;
 use crate::db;
-use crate::services;
+use crate::services::installable::{self, ServiceTarget, UninstallOptions};
 use anyhow::Result;
 use std::env;
 use std::io::{self, Write};
@@ -9,31 +9,32 @@ use std::path::Path;
 
 /// Handle uninstall command for a service on a host
 /// hostname: None = local, Some(hostname) = remote host
-pub fn handle_uninstall(hostname: Option<&str>, service: &str) -> Result<()> {
+/// Docker volumes are kept unless `purge_volumes` is set; `yes` skips the confirmation
+pub fn handle_uninstall(
+    hostname: Option<&str>,
+    service: &str,
+    purge_volumes: bool,
+    yes: bool,
+) -> Result<()> {
     let config = config::load_config()?;
     let target_host = hostname.unwrap_or("localhost");
 
-    match service.to_lowercase().as_str() {
-        "npm" => {
-            // TODO: Implement NPM uninstall
-            anyhow::bail!("NPM uninstall not yet implemented");
-        }
-        "portainer" => {
-            // TODO: Implement Portainer uninstall
-            anyhow::bail!("Portainer uninstall not yet implemented");
-        }
-        "smb" => {
-            services::smb::uninstall_smb_mounts(target_host, &config)?;
-        }
-        _ => {
-            anyhow::bail!(
-                "Unknown service: {}. Supported services: npm, portainer, smb",
-                service
-            );
-        }
+    let Some(installable) = installable::find_service(service) else {
+        anyhow::bail!(
+            "Unknown service: {}. Available services:\n{}",
+            service,
+            installable::service_list()
+        );
+    };
+    let target = ServiceTarget {
+        hostname: target_host,
+        config: &config,
+    };
+    if !installable.is_installed(&target.executor()?)? {
+        println!("{} is not installed on {}", installable.name(), target_host);
+        return Ok(());
     }
-
-    Ok(())
+    installable.uninstall(&target, &UninstallOptions { purge_volumes, yes })
 }
 
 /// Handle guided uninstall for halvor (local or remote)
@@ -48,9 +49,7 @@ pub fn handle_guided_uninstall(hostname: Option<&str>) -> Result<()> {
         println!();
         println!("For remote hosts, you can uninstall services but not the halvor binary.");
         println!("Available services to uninstall:");
-        println!("  - npm (Nginx Proxy Manager)");
-        println!("  - portainer (Portainer)");
-        println!("  - smb (SMB mounts)");
+        println!("{}", installable::service_list());
         println!();
         print!("Enter service to uninstall (or press Enter to cancel): ");
         io::stdout().flush()?;
@@ -63,7 +62,7 @@ pub fn handle_guided_uninstall(hostname: Option<&str>) -> Result<()> {
             return Ok(());
         }
 
-        return handle_uninstall(hostname, service);
+        return handle_uninstall(hostname, service, false, false);
     }
 
     // Local uninstall - guided flow
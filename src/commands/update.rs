@@ -1,10 +1,181 @@
+use crate::config::config_manager;
 use crate::utils::update;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::Subcommand;
 use std::env;
 
-pub fn handle_update(experimental: bool, force: bool) -> Result<()> {
+/// Updates listed by `hal update history` unless `--limit` says otherwise
+const DEFAULT_HISTORY_LIMIT: usize = 10;
+
+#[derive(Subcommand, Clone)]
+pub enum UpdateCommands {
+    /// List installed updates with their versions, times and channels
+    History {
+        /// Only updates installed on or after this date (YYYY-MM-DD, RFC 3339, or e.g. 7d, 12h, 2w)
+        #[arg(long, value_name = "DATE", value_parser = parse_since)]
+        since: Option<i64>,
+        /// Show at most this many updates, most recent first
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = DEFAULT_HISTORY_LIMIT,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        limit: usize,
+    },
+}
+
+impl UpdateCommands {
+    /// Run the update subcommand
+    // The binary dispatches on the library's copy of this enum, so its own copy goes unused
+    #[allow(dead_code)]
+    pub fn handle(self, json: bool) -> Result<()> {
+        match self {
+            UpdateCommands::History { since, limit } => handle_history(since, limit, json),
+        }
+    }
+}
+
+/// Parse `--since`: a date (midnight UTC), an RFC 3339 timestamp, or an age such as `7d`
+/// Returns a Unix timestamp.
+fn parse_since(value: &str) -> Result<i64> {
+    let value = value.trim();
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.timestamp());
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp());
+    }
+    let age = [('h', 3600), ('d', 86400), ('w', 7 * 86400)]
+        .iter()
+        .find_map(|(unit, secs)| {
+            value
+                .strip_suffix(*unit)
+                .and_then(|count| count.parse::<i64>().ok())
+                .filter(|count| *count >= 0)
+                .map(|count| count.checked_mul(*secs))
+        });
+    match age {
+        Some(age) => age
+            .and_then(|age| chrono::Utc::now().timestamp().checked_sub(age))
+            .with_context(|| format!("'{}' is too far back", value)),
+        None => anyhow::bail!(
+            "invalid date '{}' (expected YYYY-MM-DD, an RFC 3339 timestamp, or an age like 7d, 12h or 2w)",
+            value
+        ),
+    }
+}
+
+/// List past updates as a table (or JSON), most recent first
+fn handle_history(since: Option<i64>, limit: usize, json: bool) -> Result<()> {
+    let limit = i32::try_from(limit).unwrap_or(i32::MAX);
+    let history = crate::db::get_update_history(since, Some(limit))?;
+
+    if json {
+        let entries: Vec<_> = history
+            .iter()
+            .map(|(version, channel, installed_at, source)| {
+                serde_json::json!({
+                    "version": version,
+                    "previous_version": source,
+                    "channel": channel,
+                    "installed_at": installed_at,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if history.is_empty() {
+        match since {
+            Some(_) => println!("No updates installed in that period."),
+            None => println!("No updates recorded yet."),
+        }
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 3]> = history
+        .iter()
+        .map(|(version, channel, installed_at, source)| {
+            let when = chrono::DateTime::from_timestamp(*installed_at, 0)
+                .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| installed_at.to_string());
+            let transition = format!("{} → {}", source.as_deref().unwrap_or("?"), version);
+            [when, transition, channel.clone()]
+        })
+        .collect();
+    let headers = ["Installed", "Version", "Channel"];
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([headers[i].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    println!(
+        "{:<w0$}  {:<w1$}  {}",
+        headers[0],
+        headers[1],
+        headers[2],
+        w0 = widths[0],
+        w1 = widths[1]
+    );
+    for row in &rows {
+        println!(
+            "{:<w0$}  {:<w1$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            w0 = widths[0],
+            w1 = widths[1]
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_update(
+    experimental: bool,
+    force: bool,
+    version: Option<&str>,
+    unpin: bool,
+) -> Result<()> {
     let current_version = env!("CARGO_PKG_VERSION");
 
+    if unpin {
+        match config_manager::get_pinned_version() {
+            Some(pinned) => {
+                config_manager::set_pinned_version(None)?;
+                println!("✓ Unpinned from {}", pinned);
+                println!("  Run 'hal update' to move to the latest release.");
+            }
+            None => println!("No version pinned."),
+        }
+        return Ok(());
+    }
+
+    if let Some(tag) = version {
+        println!("Installing release {}...", tag);
+        let installed = update::install_release(tag)?;
+        config_manager::set_pinned_version(Some(&installed))?;
+        println!("✓ Pinned to {}", installed);
+        println!("  Update checks are paused until you run 'hal update --unpin'.");
+        return Ok(());
+    }
+
+    let pinned = config_manager::get_pinned_version();
+    if let Some(pinned) = &pinned
+        && !force
+    {
+        println!("Pinned to {}; skipping update.", pinned);
+        println!("  Run 'hal update --unpin' to follow the release channel again,");
+        println!("  or 'hal update --force' to install the latest release and unpin.");
+        return Ok(());
+    }
+
     if force {
         // Force mode: get the latest version and install it regardless of current version
         if experimental {
@@ -18,6 +189,11 @@ pub fn handle_update(experimental: bool, force: bool) -> Result<()> {
             println!("Latest version: {}", latest_version);
             update::download_and_install_update(&latest_version)?;
         }
+        // --force overrides a pin; keeping it would pause checks on a version we've left
+        if let Some(pinned) = pinned {
+            config_manager::set_pinned_version(None)?;
+            println!("✓ Unpinned from {}", pinned);
+        }
     } else if experimental {
         // Experimental channel: check for updates based on timestamps (version less)
         if let Ok(Some(new_version)) = update::check_for_experimental_updates(current_version) {
@@ -39,3 +215,118 @@ pub fn handle_update(experimental: bool, force: bool) -> Result<()> {
     }
     Ok(())
 }
+
+pub fn handle_rollback() -> Result<()> {
+    update::rollback_update()
+}
+
+/// Report whether a newer release is available without downloading or installing it
+/// Exits 0 either way; only a failed check is an error.
+pub fn handle_check(experimental: bool, json: bool) -> Result<()> {
+    let current_version = crate::commands::utils::get_version_string();
+
+    // A pinned release (hal update --version) opts out of update prompts
+    if let Some(pinned) = config_manager::get_pinned_version() {
+        if json {
+            let report = serde_json::json!({
+                "current": current_version,
+                "pinned": pinned,
+                "update_available": false,
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("Pinned to {}; not checking for updates.", pinned);
+            println!("  Run 'hal update --unpin' to follow the release channel again.");
+        }
+        return Ok(());
+    }
+
+    let experimental = experimental
+        || config_manager::get_release_channel() == config_manager::ReleaseChannel::Experimental;
+    let check = update::check_release(current_version, experimental)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&check)?);
+        return Ok(());
+    }
+
+    let published = check
+        .published_at
+        .as_deref()
+        .and_then(|published| chrono::DateTime::parse_from_rfc3339(published).ok())
+        .map(|published| format!(" (published {})", published.format("%Y-%m-%d")))
+        .unwrap_or_default();
+    println!("Current version: {} ({})", check.current, check.channel);
+    println!("Latest release:  {}{}", check.latest, published);
+    if check.update_available {
+        println!(
+            "Update available: run '{}' to install it.",
+            if experimental {
+                "hal update --experimental"
+            } else {
+                "hal update"
+            }
+        );
+    } else {
+        println!("✓ You're running the latest version.");
+    }
+    if let Some(ref notes) = check.notes {
+        println!();
+        println!("Release notes for {}:", check.latest);
+        for line in update::render_release_notes(notes) {
+            println!("  {}", line);
+        }
+    }
+    if let Some(ref url) = check.url {
+        println!();
+        println!("  {}", url);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(subcommand)]
+        command: UpdateCommands,
+    }
+
+    #[test]
+    fn since_accepts_dates_timestamps_and_ages() {
+        assert_eq!(parse_since("2026-01-02").unwrap(), 1767312000);
+        assert_eq!(parse_since("2026-01-02T03:00:00Z").unwrap(), 1767322800);
+        let now = chrono::Utc::now().timestamp();
+        let week = parse_since("1w").unwrap();
+        assert!((now - 7 * 86400 - week).abs() <= 1);
+        assert!(parse_since("yesterday").is_err());
+        assert!(parse_since("-3d").is_err());
+    }
+
+    #[test]
+    fn since_rejects_ages_that_overflow() {
+        let err = parse_since(&format!("{}w", i64::MAX / 2)).unwrap_err();
+        assert!(err.to_string().contains("too far back"), "{}", err);
+        assert!(parse_since(&format!("{}h", i64::MAX)).is_err());
+    }
+
+    #[test]
+    fn limit_must_be_positive() {
+        assert!(TestCli::try_parse_from(["hal", "history", "--limit", "0"]).is_err());
+        let cli = TestCli::try_parse_from(["hal", "history", "--limit", "3"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            UpdateCommands::History { limit: 3, .. }
+        ));
+        let cli = TestCli::try_parse_from(["hal", "history"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            UpdateCommands::History {
+                limit: DEFAULT_HISTORY_LIMIT,
+                ..
+            }
+        ));
+    }
+}
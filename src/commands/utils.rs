@@ -1,11 +1,32 @@
 use crate::config::config_manager;
 use crate::utils::update;
 use std::env;
+use std::io::IsTerminal;
+
+/// Environment variable that turns the startup update check off, like `--no-update-check`
+pub const NO_UPDATE_CHECK_ENV_VAR: &str = "HALVOR_NO_UPDATE_CHECK";
 
 /// Check for updates in background (non-blocking)
+/// GitHub is asked at most once per `update_check_interval_hours`; scripts and pipes (stdout not
+/// a terminal) are never prompted.
 pub fn check_for_updates() {
+    if env::var_os(NO_UPDATE_CHECK_ENV_VAR).is_some_and(|value| !value.is_empty() && value != "0")
+        || !std::io::stdout().is_terminal()
+    {
+        return;
+    }
+    // A pinned release (hal update --version) opts out of update prompts
+    if config_manager::get_pinned_version().is_some() {
+        return;
+    }
+    let interval = config_manager::get_update_check_interval();
+    if interval.is_zero() {
+        return;
+    }
     // Check for updates in background (non-blocking)
-    if let Ok(Some(new_version)) = update::check_for_updates(env!("CARGO_PKG_VERSION")) {
+    if let Ok(Some(new_version)) =
+        update::check_for_updates_cached(env!("CARGO_PKG_VERSION"), interval)
+    {
         if let Ok(true) = update::prompt_for_update(&new_version, env!("CARGO_PKG_VERSION")) {
             if let Err(e) = update::download_and_install_update(&new_version) {
                 eprintln!("Failed to install update: {}", e);
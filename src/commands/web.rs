@@ -0,0 +1,37 @@
+use crate::services::web;
+use anyhow::Result;
+use clap::Subcommand;
+use std::net::{IpAddr, SocketAddr};
+
+#[derive(Subcommand, Clone)]
+pub enum WebCommands {
+    /// Serve read-only JSON endpoints for a status dashboard: /hosts, /health and /agents
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value = "8080")]
+        port: u16,
+        /// Address to listen on (e.g. 0.0.0.0 for every interface; the API has no authentication)
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: IpAddr,
+    },
+}
+
+impl WebCommands {
+    /// Run the web command
+    // The binary dispatches on the library's copy of this enum, so its own copy goes unused
+    #[allow(dead_code)]
+    pub fn handle(self) -> Result<()> {
+        match self {
+            WebCommands::Serve { port, bind } => {
+                if !bind.is_loopback() {
+                    eprintln!(
+                        "⚠ Listening on {}: anyone who can reach this machine can read your host list, health and agents (there is no authentication)",
+                        bind
+                    );
+                }
+                let rt = tokio::runtime::Runtime::new()?;
+                rt.block_on(web::start_dashboard(SocketAddr::new(bind, port)))
+            }
+        }
+    }
+}
@@ -1,13 +1,25 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 const CONFIG_DIR_NAME: &str = "halvor";
 const CONFIG_FILE_NAME: &str = "config.toml";
+const PROFILES_DIR_NAME: &str = "profiles";
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+/// Name of the profile made of the top-level settings in config.toml
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Hours between startup update checks unless `update_check_interval_hours` says otherwise
+pub const DEFAULT_UPDATE_CHECK_INTERVAL_HOURS: u64 = 24;
+
+/// Environment variable naming the host to use when `-H` isn't given
+pub const HOST_ENV_VAR: &str = "HALVOR_HOST";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum ReleaseChannel {
     Stable,
     Experimental,
@@ -19,11 +31,54 @@ impl Default for ReleaseChannel {
     }
 }
 
+/// IP version to try first when a host has addresses of both kinds
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl AddressFamily {
+    /// Family of a literal IP address
+    pub fn of(ip: &std::net::IpAddr) -> Self {
+        match ip {
+            std::net::IpAddr::V4(_) => AddressFamily::Ipv4,
+            std::net::IpAddr::V6(_) => AddressFamily::Ipv6,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AddressFamily::Ipv4 => "ipv4",
+            AddressFamily::Ipv6 => "ipv6",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HalConfig {
     pub env_file_path: Option<PathBuf>,
     #[serde(default)]
     pub release_channel: ReleaseChannel,
+    /// Release tag installed with `hal update --version`; suppresses update checks until unpinned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_version: Option<String>,
+    /// Hours between startup update checks (default 24; 0 turns the startup check off)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_check_interval_hours: Option<u64>,
+    /// Address family tried first when connecting to hosts (set with `hal config set-prefer`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_address_family: Option<AddressFamily>,
+    /// Host used when `-H` is omitted (set with `hal config set-default-host`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_host: Option<String>,
+    /// Profile selected with `hal config profile use` (None is the default profile)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// Named profiles created with `hal config profile create`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
 }
 
 impl Default for HalConfig {
@@ -31,6 +86,91 @@ impl Default for HalConfig {
         Self {
             env_file_path: None,
             release_channel: ReleaseChannel::Stable,
+            pinned_version: None,
+            update_check_interval_hours: None,
+            prefer_address_family: None,
+            default_host: None,
+            active_profile: None,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+/// A named env file, database and release channel (e.g. one per homelab)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Profile {
+    pub env_file_path: Option<PathBuf>,
+    #[serde(default)]
+    pub release_channel: ReleaseChannel,
+    /// Database file; defaults to `profiles/<name>/halvor.db` in the config directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_path: Option<PathBuf>,
+}
+
+/// Profile selected with `--profile` for this invocation
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Use `name` instead of the active profile for this invocation (`--profile`)
+pub fn set_profile_override(name: &str) -> Result<()> {
+    let config = load_config()?;
+    if name != DEFAULT_PROFILE && !config.profiles.contains_key(name) {
+        anyhow::bail!(
+            "--profile {}: no such profile (create it with 'hal config profile create {}')",
+            name,
+            name
+        );
+    }
+    let _ = PROFILE_OVERRIDE.set(name.to_string());
+    Ok(())
+}
+
+impl HalConfig {
+    /// Name of the profile in effect: `--profile`, then `hal config profile use`, then the
+    /// default profile. A selected profile that no longer exists falls back to the default.
+    pub fn profile_name(&self) -> &str {
+        PROFILE_OVERRIDE
+            .get()
+            .map(String::as_str)
+            .or(self.active_profile.as_deref())
+            .filter(|name| self.profiles.contains_key(*name))
+            .unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// The named profile in effect (None for the default profile)
+    pub fn profile(&self) -> Option<(&str, &Profile)> {
+        let name = self.profile_name();
+        self.profiles.get(name).map(|profile| (name, profile))
+    }
+
+    /// Env file of the profile in effect
+    pub fn active_env_file_path(&self) -> Option<&PathBuf> {
+        match self.profile() {
+            Some((_, profile)) => profile.env_file_path.as_ref(),
+            None => self.env_file_path.as_ref(),
+        }
+    }
+
+    /// Release channel of the profile in effect
+    pub fn active_release_channel(&self) -> ReleaseChannel {
+        match self.profile() {
+            Some((_, profile)) => profile.release_channel,
+            None => self.release_channel,
+        }
+    }
+
+    fn set_active_env_file_path(&mut self, path: PathBuf) {
+        let name = self.profile_name().to_string();
+        match self.profiles.get_mut(&name) {
+            Some(profile) => profile.env_file_path = Some(path),
+            None => self.env_file_path = Some(path),
+        }
+    }
+
+    fn set_active_release_channel(&mut self, channel: ReleaseChannel) {
+        let name = self.profile_name().to_string();
+        match self.profiles.get_mut(&name) {
+            Some(profile) => profile.release_channel = channel,
+            None => self.release_channel = channel,
         }
     }
 }
@@ -52,6 +192,11 @@ pub fn get_config_dir() -> Result<PathBuf> {
     Ok(config_dir)
 }
 
+/// Directory holding a named profile's files (its database unless `--db` was given)
+pub fn get_profile_dir(name: &str) -> Result<PathBuf> {
+    Ok(get_config_dir()?.join(PROFILES_DIR_NAME).join(name))
+}
+
 pub fn get_config_file_path() -> Result<PathBuf> {
     let config_dir = get_config_dir()?;
     Ok(config_dir.join(CONFIG_FILE_NAME))
@@ -92,15 +237,31 @@ pub fn save_config(config: &HalConfig) -> Result<()> {
 
 pub fn set_env_file_path(env_path: &Path) -> Result<()> {
     let mut config = load_config().unwrap_or_default();
-    config.env_file_path = Some(env_path.to_path_buf());
+    config.set_active_env_file_path(env_path.to_path_buf());
     save_config(&config)?;
 
-    println!("✓ Environment file path configured: {}", env_path.display());
+    println!(
+        "✓ Environment file path configured{}: {}",
+        profile_suffix(&config),
+        env_path.display()
+    );
     Ok(())
 }
 
+/// Env file configured for the profile in effect
 pub fn get_env_file_path() -> Option<PathBuf> {
-    load_config().ok()?.env_file_path
+    Some(configured_env_file()?.0)
+}
+
+/// Env file configured for the profile in effect and what selected that profile
+pub fn configured_env_file() -> Option<(PathBuf, &'static str)> {
+    let config = load_config().ok()?;
+    let source = match config.profile() {
+        None => "hal config init",
+        Some(_) if PROFILE_OVERRIDE.get().is_some() => "--profile",
+        Some(_) => "hal config profile use",
+    };
+    Some((config.active_env_file_path()?.clone(), source))
 }
 
 pub fn prompt_for_env_file() -> Result<PathBuf> {
@@ -109,8 +270,11 @@ pub fn prompt_for_env_file() -> Result<PathBuf> {
 
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
-    let path_str = input.trim();
+    resolve_env_file_path(input.trim())
+}
 
+/// Expand `~`, make absolute and check that the .env file exists
+pub fn resolve_env_file_path(path_str: &str) -> Result<PathBuf> {
     if path_str.is_empty() {
         anyhow::bail!("Path cannot be empty");
     }
@@ -149,17 +313,81 @@ pub fn prompt_for_env_file() -> Result<PathBuf> {
     Ok(path)
 }
 
-pub fn init_config_interactive() -> Result<()> {
+/// Values for `hal config init` given as flags
+#[derive(Debug, Default)]
+pub struct InitOptions<'a> {
+    pub env_file: Option<&'a str>,
+    pub channel: Option<ReleaseChannel>,
+    /// Never prompt; fail if a required value is missing
+    pub non_interactive: bool,
+}
+
+/// Initialize or update the configuration, prompting only for values not given as flags
+/// Falls back to non-interactive mode when stdin isn't a terminal (e.g. cloud-init, Ansible)
+pub fn init_config(options: &InitOptions) -> Result<()> {
+    let non_interactive = options.non_interactive || !io::stdin().is_terminal();
+    if non_interactive
+        && !options.non_interactive
+        && (options.env_file.is_none() || options.channel.is_none())
+    {
+        eprintln!(
+            "⚠ stdin is not a terminal; running non-interactively (pass --env-file, --channel and --non-interactive to set everything explicitly)"
+        );
+    }
+
+    if non_interactive || options.env_file.is_some() {
+        init_config_from_options(options)
+    } else {
+        init_config_interactive(options.channel)
+    }
+}
+
+/// Apply `hal config init` flags without prompting
+fn init_config_from_options(options: &InitOptions) -> Result<()> {
+    let mut config = load_config()?;
+    match options.env_file {
+        Some(path) => config.set_active_env_file_path(resolve_env_file_path(path)?),
+        None if config.active_env_file_path().is_some() => {}
+        None => anyhow::bail!(
+            "No environment file is configured\nPass --env-file <path> when running non-interactively"
+        ),
+    }
+    if let Some(channel) = options.channel {
+        config.set_active_release_channel(channel);
+    }
+    save_config(&config)?;
+
+    println!("✓ Configuration saved!");
+    println!("  Config location: {}", get_config_file_path()?.display());
+    if config.profile().is_some() {
+        println!("  Profile: {}", config.profile_name());
+    }
+    if let Some(env_path) = config.active_env_file_path() {
+        println!("  Environment file: {}", env_path.display());
+    }
+    println!(
+        "  Release channel: {}",
+        channel_name(config.active_release_channel())
+    );
+    Ok(())
+}
+
+fn init_config_interactive(channel: Option<ReleaseChannel>) -> Result<()> {
     println!("HAL Configuration Setup");
     println!("======================");
     println!();
 
+    if let Some(channel) = channel {
+        set_release_channel(channel)?;
+        println!();
+    }
+
     let config = load_config()?;
 
     // Show current configuration summary
     show_config_summary()?;
 
-    if let Some(ref env_path) = config.env_file_path {
+    if let Some(env_path) = config.active_env_file_path() {
         println!();
         println!("Current environment file: {}", env_path.display());
         print!("Change it? [y/N]: ");
@@ -334,19 +562,288 @@ fn show_config_summary() -> Result<()> {
     Ok(())
 }
 
+fn channel_name(channel: ReleaseChannel) -> &'static str {
+    match channel {
+        ReleaseChannel::Stable => "stable",
+        ReleaseChannel::Experimental => "experimental",
+    }
+}
+
+/// " for profile <name>" when a named profile is in effect, for confirmation messages
+fn profile_suffix(config: &HalConfig) -> String {
+    match config.profile() {
+        Some((name, _)) => format!(" for profile {}", name),
+        None => String::new(),
+    }
+}
+
 pub fn set_release_channel(channel: ReleaseChannel) -> Result<()> {
     let mut config = load_config().unwrap_or_default();
-    config.release_channel = channel;
+    config.set_active_release_channel(channel);
     save_config(&config)?;
 
-    let channel_name = match channel {
-        ReleaseChannel::Stable => "stable",
-        ReleaseChannel::Experimental => "experimental",
-    };
-    println!("✓ Release channel set to: {}", channel_name);
+    println!(
+        "✓ Release channel{} set to: {}",
+        profile_suffix(&config),
+        channel_name(channel)
+    );
     Ok(())
 }
 
+/// Release channel of the profile in effect
 pub fn get_release_channel() -> ReleaseChannel {
-    load_config().unwrap_or_default().release_channel
+    load_config().unwrap_or_default().active_release_channel()
+}
+
+/// Values for `hal config profile create`
+#[derive(Debug, Default)]
+pub struct ProfileOptions<'a> {
+    pub env_file: Option<&'a str>,
+    pub channel: Option<ReleaseChannel>,
+    pub db: Option<&'a str>,
+}
+
+/// Create a named profile; it takes effect with `hal config profile use` or `--profile`
+pub fn create_profile(name: &str, options: &ProfileOptions) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    if name == DEFAULT_PROFILE {
+        anyhow::bail!(
+            "The {} profile always exists; it uses the top-level settings",
+            DEFAULT_PROFILE
+        );
+    }
+
+    let mut config = load_config()?;
+    if config.profiles.contains_key(name) {
+        anyhow::bail!("Profile '{}' already exists", name);
+    }
+
+    let db_path = match options.db {
+        Some(path) => {
+            let path = expand_home(path)?;
+            Some(if path.is_relative() {
+                std::env::current_dir()?.join(path)
+            } else {
+                path
+            })
+        }
+        None => None,
+    };
+    let profile = Profile {
+        env_file_path: options.env_file.map(resolve_env_file_path).transpose()?,
+        release_channel: options.channel.unwrap_or_default(),
+        db_path,
+    };
+    let db_path = crate::db::profile_db_path(Some((name, &profile)))?;
+    config.profiles.insert(name.to_string(), profile.clone());
+    save_config(&config)?;
+
+    println!("✓ Created profile {}", name);
+    match &profile.env_file_path {
+        Some(path) => println!("  Environment file: {}", path.display()),
+        None => println!(
+            "  Environment file: (not set; use 'hal --profile {} config set-env <path>')",
+            name
+        ),
+    }
+    println!(
+        "  Release channel: {}",
+        channel_name(profile.release_channel)
+    );
+    println!("  Database: {}", db_path.display());
+    println!("Switch to it with 'hal config profile use {}'", name);
+    Ok(())
+}
+
+/// Make `name` the profile used when `--profile` isn't given
+pub fn use_profile(name: &str) -> Result<()> {
+    let mut config = load_config()?;
+    if name == DEFAULT_PROFILE {
+        config.active_profile = None;
+    } else if config.profiles.contains_key(name) {
+        config.active_profile = Some(name.to_string());
+    } else {
+        anyhow::bail!(
+            "No profile named '{}' (see 'hal config profile list')",
+            name
+        );
+    }
+    save_config(&config)?;
+
+    println!("✓ Using profile {}", name);
+    Ok(())
+}
+
+/// Print the default profile and every named profile; `*` marks the one in effect
+pub fn list_profiles(json: bool) -> Result<()> {
+    let config = load_config()?;
+    let current = config.profile_name();
+
+    let mut rows = vec![(
+        DEFAULT_PROFILE,
+        config.env_file_path.as_ref(),
+        config.release_channel,
+        crate::db::profile_db_path(None)?,
+    )];
+    for (name, profile) in &config.profiles {
+        rows.push((
+            name,
+            profile.env_file_path.as_ref(),
+            profile.release_channel,
+            crate::db::profile_db_path(Some((name, profile)))?,
+        ));
+    }
+
+    if json {
+        let profiles: Vec<_> = rows
+            .iter()
+            .map(|(name, env_file, channel, db_path)| {
+                serde_json::json!({
+                    "name": name,
+                    "active": *name == current,
+                    "env_file": env_file,
+                    "release_channel": channel_name(*channel),
+                    "db_path": db_path,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&profiles)?);
+        return Ok(());
+    }
+
+    for (name, env_file, channel, db_path) in &rows {
+        println!("{} {}", if *name == current { "*" } else { " " }, name);
+        println!(
+            "    Environment file: {}",
+            env_file.map_or_else(|| "(not set)".to_string(), |p| p.display().to_string())
+        );
+        println!("    Release channel: {}", channel_name(*channel));
+        println!("    Database: {}", db_path.display());
+    }
+    Ok(())
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(path_str: &str) -> Result<PathBuf> {
+    Ok(match path_str.strip_prefix('~') {
+        Some(rest) => get_home_dir()?.join(rest.trim_start_matches('/')),
+        None => PathBuf::from(path_str),
+    })
+}
+
+/// Pin (Some) or unpin (None) the installed release
+pub fn set_pinned_version(version: Option<&str>) -> Result<()> {
+    let mut config = load_config().unwrap_or_default();
+    config.pinned_version = version.map(|v| v.to_string());
+    save_config(&config)
+}
+
+pub fn get_pinned_version() -> Option<String> {
+    load_config().ok()?.pinned_version
+}
+
+/// Set (Some) or clear (None) the hours between startup update checks
+pub fn set_update_check_interval(hours: Option<u64>) -> Result<()> {
+    let mut config = load_config().unwrap_or_default();
+    config.update_check_interval_hours = hours;
+    save_config(&config)?;
+
+    match hours {
+        Some(0) => println!("✓ Startup update check turned off"),
+        Some(hours) => println!("✓ Update check interval set to {} hour(s)", hours),
+        None => println!(
+            "✓ Update check interval reset to {} hours",
+            DEFAULT_UPDATE_CHECK_INTERVAL_HOURS
+        ),
+    }
+    Ok(())
+}
+
+/// How long the startup update check reuses its last answer (zero turns the check off)
+pub fn get_update_check_interval() -> std::time::Duration {
+    let hours = load_config()
+        .ok()
+        .and_then(|config| config.update_check_interval_hours)
+        .unwrap_or(DEFAULT_UPDATE_CHECK_INTERVAL_HOURS);
+    std::time::Duration::from_secs(hours * 3600)
+}
+
+/// Address family chosen with `--prefer` for this invocation
+static ADDRESS_FAMILY_OVERRIDE: OnceLock<AddressFamily> = OnceLock::new();
+
+/// Prefer `family` over the saved preference for this invocation (`--prefer`)
+pub fn set_address_family_override(family: AddressFamily) {
+    let _ = ADDRESS_FAMILY_OVERRIDE.set(family);
+}
+
+/// Set (Some) or clear (None) the address family tried first when connecting to hosts
+pub fn set_address_family(family: Option<AddressFamily>) -> Result<()> {
+    let mut config = load_config().unwrap_or_default();
+    config.prefer_address_family = family;
+    save_config(&config)?;
+
+    match family {
+        Some(family) => println!("✓ Preferred address family set to: {}", family.name()),
+        None => println!("✓ Preferred address family cleared"),
+    }
+    Ok(())
+}
+
+/// Address family to try first: `--prefer`, then the saved preference. None keeps the
+/// configured order (IP, then Tailscale names).
+pub fn get_address_family() -> Option<AddressFamily> {
+    if let Some(family) = ADDRESS_FAMILY_OVERRIDE.get() {
+        return Some(*family);
+    }
+    load_config().ok()?.prefer_address_family
+}
+
+/// Set (Some) or clear (None) the host used when `-H` is omitted
+pub fn set_default_host(hostname: Option<&str>) -> Result<()> {
+    let mut config = load_config().unwrap_or_default();
+    config.default_host = hostname.map(|h| h.to_string());
+    save_config(&config)?;
+
+    match hostname {
+        Some(hostname) => println!("✓ Default host set to: {}", hostname),
+        None => println!("✓ Default host cleared"),
+    }
+    Ok(())
+}
+
+pub fn get_default_host() -> Option<String> {
+    load_config().ok()?.default_host
+}
+
+/// Pick the host a command targets when it accepts `-H`
+/// Order: the `-H` flag, then `HALVOR_HOST`, then the configured default host.
+/// None means the command falls back to the current machine.
+pub fn resolve_target_host(explicit: Option<String>) -> Option<String> {
+    let (hostname, source) = if let Some(hostname) = explicit {
+        (hostname, "-H")
+    } else if let Some(hostname) = std::env::var(HOST_ENV_VAR)
+        .ok()
+        .filter(|h| !h.trim().is_empty())
+    {
+        (hostname.trim().to_string(), HOST_ENV_VAR)
+    } else if let Some(hostname) = get_default_host() {
+        (hostname, "default host")
+    } else {
+        log::debug!(
+            "Target host: this machine (no -H, {} or default host)",
+            HOST_ENV_VAR
+        );
+        return None;
+    };
+    log::debug!("Target host: {} (from {})", hostname, source);
+    Some(hostname)
 }
@@ -1,7 +1,21 @@
 use crate::config::HostConfig;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Fail when the env file only exists encrypted
+/// Writing it would create a plaintext file holding just this change, which would then be
+/// loaded instead of the full encrypted configuration
+pub fn ensure_env_file_not_encrypted(env_path: &Path) -> Result<()> {
+    let encrypted = crate::config::encrypted_env_file_path_for(env_path);
+    if !env_path.exists() && encrypted.exists() {
+        anyhow::bail!(
+            "{} is encrypted and can't be edited in place.\nRun 'hal config decrypt', make the change, then 'hal config encrypt'.",
+            encrypted.display()
+        );
+    }
+    Ok(())
+}
 
 /// Write host configuration to .env file
 pub fn write_host_to_env_file(
@@ -9,6 +23,8 @@ pub fn write_host_to_env_file(
     hostname: &str,
     config: &HostConfig,
 ) -> Result<()> {
+    ensure_env_file_not_encrypted(env_path)?;
+
     // Read existing .env file
     let content = if env_path.exists() {
         fs::read_to_string(env_path)
@@ -20,10 +36,15 @@ pub fn write_host_to_env_file(
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
     let hostname_upper = hostname.to_uppercase();
 
-    // Remove existing entries for this host
+    // Remove existing entries for this host (comments and other hosts are kept)
+    let prefix = format!("HOST_{}_", hostname_upper);
     lines.retain(|line| {
-        let trimmed = line.trim();
-        !trimmed.starts_with('#') && !trimmed.starts_with(&format!("HOST_{}_", hostname_upper))
+        let line = line.trim();
+        !line
+            .strip_prefix("export ")
+            .unwrap_or(line)
+            .to_uppercase()
+            .starts_with(&prefix)
     });
 
     // Add new entries
@@ -42,6 +63,15 @@ pub fn write_host_to_env_file(
             hostname_upper, backup_path
         ));
     }
+    if let Some(ref ssh_user) = config.ssh_user {
+        lines.push(format!("HOST_{}_SSH_USER={}", hostname_upper, ssh_user));
+    }
+    if let Some(ssh_port) = config.ssh_port {
+        lines.push(format!("HOST_{}_SSH_PORT={}", hostname_upper, ssh_port));
+    }
+    if let Some(ref jump) = config.jump {
+        lines.push(format!("HOST_{}_JUMP={}", hostname_upper, jump));
+    }
 
     // Write back to file
     fs::write(env_path, lines.join("\n") + "\n")
@@ -52,6 +82,7 @@ pub fn write_host_to_env_file(
 
 /// Remove host configuration from .env file
 pub fn remove_host_from_env_file(env_path: &PathBuf, hostname: &str) -> Result<()> {
+    ensure_env_file_not_encrypted(env_path)?;
     if !env_path.exists() {
         return Ok(());
     }
@@ -74,3 +105,88 @@ pub fn remove_host_from_env_file(env_path: &PathBuf, hostname: &str) -> Result<(
 
     Ok(())
 }
+
+/// Rename a host's `HOST_<name>_*` keys in the .env file in place, keeping their values, order
+/// and surrounding comments. Returns the number of keys renamed.
+pub fn rename_host_in_env_file(env_path: &Path, old: &str, new: &str) -> Result<usize> {
+    ensure_env_file_not_encrypted(env_path)?;
+    let content = fs::read_to_string(env_path)
+        .with_context(|| format!("Failed to read .env file: {}", env_path.display()))?;
+
+    let old_keys: Vec<(String, &str)> = crate::config::HOST_KEY_SUFFIXES
+        .iter()
+        .map(|suffix| (format!("HOST_{}{}", old.to_uppercase(), suffix), *suffix))
+        .collect();
+    let mut renamed = 0;
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let rest = line.trim_start();
+            let (export, rest) = match rest.strip_prefix("export ") {
+                Some(rest) => ("export ", rest),
+                None => ("", rest),
+            };
+            let Some((key, value)) = rest.split_once('=') else {
+                return line.to_string();
+            };
+            match old_keys
+                .iter()
+                .find(|(old_key, _)| old_key.eq_ignore_ascii_case(key.trim()))
+            {
+                Some((_, suffix)) => {
+                    renamed += 1;
+                    format!(
+                        "{}{}HOST_{}{}={}",
+                        indent,
+                        export,
+                        new.to_uppercase(),
+                        suffix,
+                        value
+                    )
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+
+    if renamed > 0 {
+        fs::write(env_path, lines.join("\n") + "\n")
+            .with_context(|| format!("Failed to write .env file: {}", env_path.display()))?;
+    }
+    Ok(renamed)
+}
+
+/// Parse .env file content into key/value pairs
+/// Supports comments, blank lines, `export` prefixes, and single/double quoted values
+pub fn parse_env_content(content: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+        let value = if value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')))
+        {
+            value[1..value.len() - 1].to_string()
+        } else {
+            // Strip trailing inline comments from unquoted values
+            match value.find(" #") {
+                Some(pos) => value[..pos].trim_end().to_string(),
+                None => value.to_string(),
+            }
+        };
+        if !key.is_empty() {
+            vars.push((key.to_string(), value));
+        }
+    }
+    vars
+}
@@ -1,21 +1,42 @@
 use anyhow::{Context, Result};
+use log::debug;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Once, OnceLock};
 
 pub mod config_manager;
 pub mod env_file;
 pub mod service;
+pub mod snapshot;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct HostConfig {
     pub ip: Option<String>,
     pub hostname: Option<String>, // Primary hostname (replaces tailscale)
     pub tailscale: Option<String>, // Optional different tailscale hostname
     pub backup_path: Option<String>,
+    pub ssh_user: Option<String>, // SSH login user (defaults to the current user when unset)
+    pub ssh_port: Option<u16>,    // sshd port (defaults to 22 when unset)
+    pub jump: Option<String>,     // SSH jump host ([user@]host[:port], may name another host)
 }
 
+impl HostConfig {
+    /// User to SSH in as: `HOST_<name>_SSH_USER` when set, otherwise the current user
+    pub fn ssh_user_or_default(&self) -> String {
+        self.ssh_user.clone().unwrap_or_else(get_default_username)
+    }
+
+    /// Whether any address to reach the host is set (IP, hostname or Tailscale name)
+    /// Database records without one only hold provisioning info for a host configured in .env.
+    pub fn has_address(&self) -> bool {
+        self.ip.is_some() || self.hostname.is_some() || self.tailscale.is_some()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SmbServerConfig {
     pub host: String,
     pub shares: Vec<String>, // Multiple shares per server
@@ -28,29 +49,119 @@ pub struct EnvConfig {
     pub _tailnet_base: String,
     pub hosts: HashMap<String, HostConfig>,
     pub smb_servers: HashMap<String, SmbServerConfig>,
+    pub ssh_jump_host: Option<String>, // SSH_JUMP_HOST: jump host for hosts without their own
+    pub warnings: Vec<String>, // Non-fatal problems found while parsing (e.g. unrecognized keys)
+}
+
+/// Recognized `HOST_<name><SUFFIX>` suffixes
+/// Order matters: `_TAILSCALE_IP` must be checked before `_IP`
+pub const HOST_KEY_SUFFIXES: &[&str] = &[
+    "_TAILSCALE_IP",
+    "_IP",
+    "_HOSTNAME",
+    "_TAILSCALE",
+    "_BACKUP_PATH",
+    "_SSH_USER",
+    "_SSH_PORT",
+    "_JUMP",
+];
+
+/// Recognized `SMB_<server>_<PROPERTY>` properties
+pub const SMB_KEY_PROPERTIES: &[&str] =
+    &["HOST", "SHARES", "SHARE", "USERNAME", "PASSWORD", "OPTIONS"];
+
+/// Environment variable pinning the config directory or env file (same as `--config`)
+pub const CONFIG_ENV_VAR: &str = "HALVOR_CONFIG";
+
+/// Config pinned with `--config` for this invocation
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// A config directory or env file given with `--config` or `HALVOR_CONFIG`
+struct PinnedConfig {
+    dir: PathBuf,
+    env_file: PathBuf,
+    source: &'static str,
+}
+
+/// Pin the config for this invocation (`--config`), bypassing discovery
+/// `path` is either a directory holding `.env` or the env file itself.
+pub fn set_config_override(path: &Path) -> Result<()> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("--config {}: no such file or directory", path.display()))?;
+    let _ = CONFIG_OVERRIDE.set(path);
+    Ok(())
+}
+
+fn pinned_config() -> Result<Option<PinnedConfig>> {
+    let (path, source) = if let Some(path) = CONFIG_OVERRIDE.get() {
+        (path.clone(), "--config")
+    } else {
+        match env::var(CONFIG_ENV_VAR) {
+            Ok(path) if !path.trim().is_empty() => {
+                let path = PathBuf::from(path.trim());
+                let path = path.canonicalize().with_context(|| {
+                    format!(
+                        "{}={}: no such file or directory",
+                        CONFIG_ENV_VAR,
+                        path.display()
+                    )
+                })?;
+                (path, CONFIG_ENV_VAR)
+            }
+            _ => return Ok(None),
+        }
+    };
+
+    Ok(Some(if path.is_dir() {
+        PinnedConfig {
+            env_file: path.join(".env"),
+            dir: path,
+            source,
+        }
+    } else {
+        PinnedConfig {
+            dir: path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("/")),
+            env_file: path,
+            source,
+        }
+    }))
 }
 
 pub fn find_homelab_dir() -> Result<PathBuf> {
+    Ok(resolve_homelab_dir()?.0)
+}
+
+/// The homelab directory and what chose it
+fn resolve_homelab_dir() -> Result<(PathBuf, &'static str)> {
     use crate::config::config_manager;
 
+    // --config / HALVOR_CONFIG win over everything else
+    if let Some(pinned) = pinned_config()? {
+        return Ok((pinned.dir, pinned.source));
+    }
+
     // Check for environment variable override
     if let Ok(dir) = env::var("HOMELAB_DIR") {
-        return Ok(PathBuf::from(dir));
+        return Ok((PathBuf::from(dir), "HOMELAB_DIR"));
     }
 
-    // Check if env file path is configured in hal config
-    if let Some(env_path) = config_manager::get_env_file_path() {
+    // Check if env file path is configured in hal config (for the profile in effect)
+    if let Some((env_path, source)) = config_manager::configured_env_file() {
         if let Some(parent) = env_path.parent() {
-            return Ok(parent.to_path_buf());
+            return Ok((parent.to_path_buf(), source));
         }
     }
 
-    // Try to find .env file in current directory or parent directories
+    // Try to find .env (or encrypted .env.enc) file in current directory or parent directories
     let mut current = env::current_dir()?;
     loop {
         let env_file = current.join(".env");
-        if env_file.exists() {
-            return Ok(current);
+        if env_file.exists() || current.join(".env.enc").exists() {
+            return Ok((current, "search from the current directory"));
         }
         if !current.pop() {
             break;
@@ -58,143 +169,214 @@ pub fn find_homelab_dir() -> Result<PathBuf> {
     }
 
     // Fallback: use current directory
-    Ok(env::current_dir()?)
+    Ok((env::current_dir()?, "current directory"))
 }
 
 pub fn get_env_file_path() -> Result<PathBuf> {
+    Ok(resolve_env_file()?.0)
+}
+
+/// The env file in use and what chose it, in order of precedence: `--config`,
+/// `HALVOR_CONFIG`, `HOMELAB_ENV_FILE`, the profile's env file (`hal config init`),
+/// `HOMELAB_DIR`, then a `.env` in the current directory or one of its parents
+pub fn resolve_env_file() -> Result<(PathBuf, &'static str)> {
     use crate::config::config_manager;
 
+    if let Some(pinned) = pinned_config()? {
+        return Ok((pinned.env_file, pinned.source));
+    }
+
     // Check for environment variable override
     if let Ok(path) = env::var("HOMELAB_ENV_FILE") {
-        return Ok(PathBuf::from(path));
+        return Ok((PathBuf::from(path), "HOMELAB_ENV_FILE"));
     }
 
-    // Check if env file path is configured in hal config
-    if let Some(env_path) = config_manager::get_env_file_path() {
+    // Check if env file path is configured in hal config (for the profile in effect)
+    if let Some(env_path) = config_manager::configured_env_file() {
         return Ok(env_path);
     }
 
     // Fallback: try to find .env in homelab directory
-    let homelab_dir = find_homelab_dir()?;
-    Ok(homelab_dir.join(".env"))
+    let (homelab_dir, source) = resolve_homelab_dir()?;
+    Ok((homelab_dir.join(".env"), source))
+}
+
+/// Get the path of the encrypted env file (`<env file>.enc`)
+pub fn get_encrypted_env_file_path() -> Result<PathBuf> {
+    Ok(encrypted_env_file_path_for(&get_env_file_path()?))
+}
+
+/// The encrypted counterpart (`<env file>.enc`) of an env file
+pub fn encrypted_env_file_path_for(env_file: &Path) -> PathBuf {
+    let mut file_name = env_file
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| ".env".into());
+    file_name.push(".enc");
+    env_file.with_file_name(file_name)
+}
+
+/// Decrypt an encrypted env file and load its variables
+/// Like dotenv, variables already set in the environment are not overridden
+fn load_encrypted_env_file(path: &Path) -> Result<()> {
+    use crate::utils::crypto;
+
+    let encrypted = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read encrypted env file: {}", path.display()))?;
+    let passphrase = crypto::get_env_passphrase(false)?;
+    let content = crypto::decrypt_with_passphrase(encrypted.trim(), &passphrase)
+        .with_context(|| format!("Failed to decrypt {}", path.display()))?;
+
+    for (key, value) in env_file::parse_env_content(&content) {
+        if env::var_os(&key).is_none() {
+            unsafe {
+                env::set_var(key, value);
+            }
+        }
+    }
+    Ok(())
 }
 
 pub fn load_env_config(_homelab_dir: &Path) -> Result<EnvConfig> {
+    static BOTH_ENV_FILES_WARNING: Once = Once::new();
+
     let env_file = get_env_file_path()?;
+    let encrypted_file = get_encrypted_env_file_path()?;
+
+    if env_file.exists() {
+        if encrypted_file.exists() {
+            BOTH_ENV_FILES_WARNING.call_once(|| {
+                eprintln!(
+                    "⚠ Both {} and {} exist; using the plaintext file",
+                    env_file.display(),
+                    encrypted_file.display()
+                );
+            });
+        }
 
-    if !env_file.exists() {
+        // Load .env file
+        dotenv::from_path(&env_file)
+            .with_context(|| format!("Failed to load .env file from {}", env_file.display()))?;
+    } else if encrypted_file.exists() {
+        load_encrypted_env_file(&encrypted_file)?;
+    } else {
         anyhow::bail!(
             "Error: .env file not found at {}\n\nRun 'hal config init' to configure the environment file location.\nOr copy .env.example to .env and configure your settings.",
             env_file.display()
         );
     }
 
-    // Load .env file
-    dotenv::from_path(&env_file)
-        .with_context(|| format!("Failed to load .env file from {}", env_file.display()))?;
-
     let tailnet_base = env::var("TAILNET_BASE").unwrap_or_else(|_| "ts.net".to_string());
+    let ssh_jump_host = env::var("SSH_JUMP_HOST")
+        .ok()
+        .filter(|v| !v.trim().is_empty());
+    if let Some(jump) = &ssh_jump_host {
+        validate_jump_host(jump).map_err(|e| anyhow::anyhow!("SSH_JUMP_HOST: {}", e))?;
+    }
 
     // Parse host configurations
     let mut hosts = HashMap::new();
     let mut smb_servers = HashMap::new();
-    let env_vars: Vec<(String, String)> = env::vars().collect();
+    let mut warnings = Vec::new();
+    let mut env_vars: Vec<(String, String)> = env::vars().collect();
+    env_vars.sort_by(|a, b| a.0.cmp(&b.0));
 
     for (key, value) in env_vars {
         if let Some(hostname) = key.strip_prefix("HOST_") {
-            // Check for _TAILSCALE_IP first (before _IP) to avoid parsing it as a separate host
-            if let Some(rest) = hostname.strip_suffix("_TAILSCALE_IP") {
-                // Tailscale IP - use as primary IP if no regular IP is set
-                let hostname_lower = rest.to_lowercase();
-                let config = hosts.entry(hostname_lower).or_insert_with(|| HostConfig {
-                    ip: None,
-                    hostname: None,
-                    tailscale: None,
-                    backup_path: None,
-                });
-                // Only set IP if not already set by HOST_<name>_IP
-                if config.ip.is_none() {
-                    config.ip = Some(value);
+            let Some((rest, suffix)) = HOST_KEY_SUFFIXES
+                .iter()
+                .find_map(|suffix| hostname.strip_suffix(suffix).map(|rest| (rest, *suffix)))
+                .filter(|(rest, _)| !rest.is_empty())
+            else {
+                warnings.push(format!(
+                    "Unrecognized host key '{}' (expected HOST_<name> followed by one of: {})",
+                    key,
+                    HOST_KEY_SUFFIXES.join(", ")
+                ));
+                continue;
+            };
+
+            let hostname_lower = rest.to_lowercase();
+            let config = hosts.entry(hostname_lower).or_insert_with(|| HostConfig {
+                ip: None,
+                hostname: None,
+                tailscale: None,
+                backup_path: None,
+                ssh_user: None,
+                ssh_port: None,
+                jump: None,
+            });
+            match suffix {
+                // Tailscale IP - use as primary IP only if HOST_<name>_IP is not set
+                "_TAILSCALE_IP" => {
+                    if config.ip.is_none() {
+                        config.ip = Some(value);
+                    }
+                }
+                "_IP" => config.ip = Some(value),
+                "_HOSTNAME" => config.hostname = Some(value),
+                "_TAILSCALE" => config.tailscale = Some(value),
+                "_BACKUP_PATH" => config.backup_path = Some(value),
+                "_SSH_USER" => config.ssh_user = Some(value),
+                "_SSH_PORT" => {
+                    let port =
+                        parse_ssh_port(&value).map_err(|e| anyhow::anyhow!("{}: {}", key, e))?;
+                    config.ssh_port = Some(port);
                 }
-            } else if let Some(rest) = hostname.strip_suffix("_IP") {
-                let hostname_lower = rest.to_lowercase();
-                let config = hosts.entry(hostname_lower).or_insert_with(|| HostConfig {
-                    ip: None,
-                    hostname: None,
-                    tailscale: None,
-                    backup_path: None,
-                });
-                config.ip = Some(value);
-            } else if let Some(rest) = hostname.strip_suffix("_HOSTNAME") {
-                let hostname_lower = rest.to_lowercase();
-                let config = hosts.entry(hostname_lower).or_insert_with(|| HostConfig {
-                    ip: None,
-                    hostname: None,
-                    tailscale: None,
-                    backup_path: None,
-                });
-                config.hostname = Some(value);
-            } else if let Some(rest) = hostname.strip_suffix("_TAILSCALE") {
-                let hostname_lower = rest.to_lowercase();
-                let config = hosts.entry(hostname_lower).or_insert_with(|| HostConfig {
-                    ip: None,
-                    hostname: None,
-                    tailscale: None,
-                    backup_path: None,
-                });
-                config.tailscale = Some(value);
-            } else if let Some(rest) = hostname.strip_suffix("_BACKUP_PATH") {
-                let hostname_lower = rest.to_lowercase();
-                let config = hosts.entry(hostname_lower).or_insert_with(|| HostConfig {
-                    ip: None,
-                    hostname: None,
-                    tailscale: None,
-                    backup_path: None,
-                });
-                config.backup_path = Some(value);
+                "_JUMP" => {
+                    validate_jump_host(&value).map_err(|e| anyhow::anyhow!("{}: {}", key, e))?;
+                    config.jump = Some(value);
+                }
+                _ => unreachable!("suffix list and parser are out of sync"),
             }
         } else if let Some(server_name) = key.strip_prefix("SMB_") {
             // Parse SMB server configuration
             // Format: SMB_<SERVERNAME>_<PROPERTY>
             // Properties: HOST, SHARES (comma-separated), USERNAME, PASSWORD, OPTIONS
             let parts: Vec<&str> = server_name.split('_').collect();
-            if parts.len() >= 2 {
-                let server_name_lower = parts[0].to_lowercase();
-                let property = parts[1..].join("_");
-
-                let server_config =
-                    smb_servers
-                        .entry(server_name_lower)
-                        .or_insert_with(|| SmbServerConfig {
-                            host: String::new(),
-                            shares: Vec::new(),
-                            username: None,
-                            password: None,
-                            options: None,
-                        });
-
-                match property.as_str() {
-                    "HOST" => server_config.host = value,
-                    "SHARES" => {
-                        // Parse comma-separated shares
-                        server_config.shares = value
-                            .split(',')
-                            .map(|s| s.trim().to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect();
-                    }
-                    "SHARE" => {
-                        // Legacy support: single share (adds to shares vec)
-                        let share = value.trim().to_string();
-                        if !share.is_empty() && !server_config.shares.contains(&share) {
-                            server_config.shares.push(share);
-                        }
+            let property = parts[1..].join("_");
+            if parts.len() < 2 || !SMB_KEY_PROPERTIES.contains(&property.as_str()) {
+                warnings.push(format!(
+                    "Unrecognized SMB key '{}' (expected SMB_<server>_ followed by one of: {})",
+                    key,
+                    SMB_KEY_PROPERTIES.join(", ")
+                ));
+                continue;
+            }
+
+            let server_name_lower = parts[0].to_lowercase();
+            let server_config =
+                smb_servers
+                    .entry(server_name_lower)
+                    .or_insert_with(|| SmbServerConfig {
+                        host: String::new(),
+                        shares: Vec::new(),
+                        username: None,
+                        password: None,
+                        options: None,
+                    });
+
+            match property.as_str() {
+                "HOST" => server_config.host = value,
+                "SHARES" => {
+                    // Parse comma-separated shares
+                    server_config.shares = value
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+                "SHARE" => {
+                    // Legacy support: single share (adds to shares vec)
+                    let share = value.trim().to_string();
+                    if !share.is_empty() && !server_config.shares.contains(&share) {
+                        server_config.shares.push(share);
                     }
-                    "USERNAME" => server_config.username = Some(value),
-                    "PASSWORD" => server_config.password = Some(value),
-                    "OPTIONS" => server_config.options = Some(value),
-                    _ => {}
                 }
+                "USERNAME" => server_config.username = Some(value),
+                "PASSWORD" => server_config.password = Some(value),
+                "OPTIONS" => server_config.options = Some(value),
+                _ => unreachable!("property list and parser are out of sync"),
             }
         }
     }
@@ -219,9 +401,74 @@ pub fn load_env_config(_homelab_dir: &Path) -> Result<EnvConfig> {
         _tailnet_base: tailnet_base,
         hosts,
         smb_servers,
+        ssh_jump_host,
+        warnings,
     })
 }
 
+/// Parse an SSH port, rejecting anything outside 1-65535
+pub fn parse_ssh_port(value: &str) -> Result<u16> {
+    match value.trim().parse::<u16>() {
+        Ok(port) if port > 0 => Ok(port),
+        _ => anyhow::bail!(
+            "invalid SSH port '{}' (expected a number from 1 to 65535)",
+            value
+        ),
+    }
+}
+
+/// Check a jump host spec: one or more comma-separated `[user@]host[:port]` hops
+/// `host` may be an address or the name of another configured host
+pub fn validate_jump_host(value: &str) -> Result<()> {
+    for hop in value.split(',') {
+        let invalid = |reason: &str| {
+            anyhow::anyhow!(
+                "invalid jump host '{}': {} (expected [user@]host[:port])",
+                value,
+                reason
+            )
+        };
+        if hop.is_empty() {
+            return Err(invalid("empty hop"));
+        }
+        if hop.chars().any(char::is_whitespace) {
+            return Err(invalid("contains whitespace"));
+        }
+        if hop.starts_with('-') {
+            return Err(invalid("starts with '-'"));
+        }
+        let (user, host_port) = match hop.split_once('@') {
+            Some((user, rest)) => (Some(user), rest),
+            None => (None, hop),
+        };
+        if user.is_some_and(str::is_empty) {
+            return Err(invalid("empty user"));
+        }
+        let (host, port) = split_host_port(host_port);
+        if host.is_empty() || host.contains('@') {
+            return Err(invalid("missing or malformed host"));
+        }
+        if let Some(port) = port {
+            parse_ssh_port(port).map_err(|_| invalid("port must be 1-65535"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Split `host:port` (or `[v6addr]:port`); a bare IPv6 address has no port
+pub fn split_host_port(value: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = value.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((host, tail)) => (host, tail.strip_prefix(':')),
+            None => (value, None),
+        };
+    }
+    match value.split_once(':') {
+        Some((host, port)) if !port.contains(':') => (host, Some(port)),
+        _ => (value, None),
+    }
+}
+
 pub fn get_default_username() -> String {
     env::var("USER")
         .or_else(|_| env::var("USERNAME"))
@@ -236,16 +483,40 @@ pub fn get_arch() -> &'static str {
     env::consts::ARCH
 }
 
+/// Nginx Proxy Manager URL, from .env or the settings database
 pub fn get_npm_url() -> Option<String> {
-    env::var("NPM_URL").ok()
+    env::var("NPM_URL").ok().or_else(|| {
+        crate::db::generated::settings::get_setting("NGINX_PROXY_MANAGER_URL")
+            .ok()
+            .flatten()
+    })
 }
 
+/// Nginx Proxy Manager username, from .env or the settings database
 pub fn get_npm_username() -> Option<String> {
-    env::var("NPM_USERNAME").ok()
+    env::var("NPM_USERNAME").ok().or_else(|| {
+        crate::db::generated::settings::get_setting("NGINX_PROXY_MANAGER_USERNAME")
+            .ok()
+            .flatten()
+    })
 }
 
+/// Nginx Proxy Manager password, from .env or the settings database
 pub fn get_npm_password() -> Option<String> {
-    env::var("NPM_PASSWORD").ok()
+    env::var("NPM_PASSWORD").ok().or_else(|| {
+        crate::db::generated::settings::get_setting("NGINX_PROXY_MANAGER_PASSWORD")
+            .ok()
+            .flatten()
+    })
+}
+
+/// Let's Encrypt account email, from .env or the settings database
+pub fn get_acme_email() -> Option<String> {
+    env::var("ACME_EMAIL").ok().or_else(|| {
+        crate::db::generated::settings::get_setting("ACME_EMAIL")
+            .ok()
+            .flatten()
+    })
 }
 
 /// Helper function to load config - used by commands and services
@@ -253,27 +524,24 @@ pub fn get_npm_password() -> Option<String> {
 pub fn load_config() -> Result<EnvConfig> {
     use crate::db;
 
-    #[cfg(debug_assertions)]
-    println!("[DEBUG] Loading configuration...");
+    debug!("Loading configuration...");
 
     let homelab_dir = find_homelab_dir()?;
     let mut env_config = load_env_config(&homelab_dir)?;
 
-    #[cfg(debug_assertions)]
-    println!(
-        "[DEBUG] Loaded {} hosts from .env file",
-        env_config.hosts.len()
-    );
+    debug!("Loaded {} hosts from .env file", env_config.hosts.len());
 
     // Merge database hosts (database takes precedence)
     if let Ok(db_hosts) = db::list_hosts() {
-        #[cfg(debug_assertions)]
-        println!("[DEBUG] Found {} hosts in database", db_hosts.len());
+        debug!("Found {} hosts in database", db_hosts.len());
 
         for hostname in db_hosts {
             if let Ok(Some(db_config)) = db::get_host_config(&hostname) {
-                #[cfg(debug_assertions)]
-                println!("[DEBUG] Merging database config for '{}'", hostname);
+                // Keep the .env settings when the record only holds provisioning info
+                if !db_config.has_address() && env_config.hosts.contains_key(&hostname) {
+                    continue;
+                }
+                debug!("Merging database config for '{}'", hostname);
 
                 // Database config overrides .env config
                 env_config.hosts.insert(hostname, db_config);
@@ -281,8 +549,7 @@ pub fn load_config() -> Result<EnvConfig> {
         }
     }
 
-    #[cfg(debug_assertions)]
-    println!("[DEBUG] Final config has {} hosts", env_config.hosts.len());
+    debug!("Final config has {} hosts", env_config.hosts.len());
 
     Ok(env_config)
 }
@@ -7,10 +7,12 @@ use crate::{
         delete_host_config as delete_host_config_service, get_host_config, list_hosts,
         store_host_config,
     },
+    utils::format_bytes,
 };
 use anyhow::{Context, Result};
-use std::io::{self, Write};
-use std::path::PathBuf;
+use log::{debug, error, info, warn};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 /// Set a host field value (legacy - use update_host_config instead)
 pub fn set_host_field(hostname: &str, field: &str, value: &str) -> Result<()> {
@@ -19,6 +21,9 @@ pub fn set_host_field(hostname: &str, field: &str, value: &str) -> Result<()> {
         hostname: None,
         tailscale: None,
         backup_path: None,
+        ssh_user: None,
+        ssh_port: None,
+        jump: None,
     });
 
     match field {
@@ -26,11 +31,17 @@ pub fn set_host_field(hostname: &str, field: &str, value: &str) -> Result<()> {
         "hostname" => config.hostname = Some(value.to_string()),
         "tailscale" => config.tailscale = Some(value.to_string()),
         "backup_path" => config.backup_path = Some(value.to_string()),
+        "ssh_user" => config.ssh_user = Some(value.to_string()),
+        "ssh_port" => config.ssh_port = Some(crate::config::parse_ssh_port(value)?),
+        "jump" => {
+            crate::config::validate_jump_host(value)?;
+            config.jump = Some(value.to_string());
+        }
         _ => anyhow::bail!("Unknown field: {}", field),
     }
 
     store_host_config(hostname, &config)?;
-    println!("✓ Updated {} for host '{}'", field, hostname);
+    info!("✓ Updated {} for host '{}'", field, hostname);
     Ok(())
 }
 
@@ -42,6 +53,9 @@ pub fn update_host_config(hostname: &str, updates: &HostConfig) -> Result<()> {
         hostname: None,
         tailscale: None,
         backup_path: None,
+        ssh_user: None,
+        ssh_port: None,
+        jump: None,
     });
 
     // Update only fields that are Some()
@@ -57,16 +71,25 @@ pub fn update_host_config(hostname: &str, updates: &HostConfig) -> Result<()> {
     if let Some(ref backup_path) = updates.backup_path {
         config.backup_path = Some(backup_path.clone());
     }
+    if let Some(ref ssh_user) = updates.ssh_user {
+        config.ssh_user = Some(ssh_user.clone());
+    }
+    if let Some(ssh_port) = updates.ssh_port {
+        config.ssh_port = Some(ssh_port);
+    }
+    if let Some(ref jump) = updates.jump {
+        config.jump = Some(jump.clone());
+    }
 
     store_host_config(hostname, &config)?;
-    println!("✓ Updated host configuration for '{}'", hostname);
+    info!("✓ Updated host configuration for '{}'", hostname);
     Ok(())
 }
 
 /// Replace host configuration completely
 pub fn replace_host_config(hostname: &str, config: &HostConfig) -> Result<()> {
     store_host_config(hostname, config)?;
-    println!("✓ Replaced host configuration for '{}'", hostname);
+    info!("✓ Replaced host configuration for '{}'", hostname);
     Ok(())
 }
 
@@ -88,6 +111,20 @@ pub fn show_host_config(hostname: &str) -> Result<()> {
     if let Some(ref backup_path) = config.backup_path {
         println!("  Backup Path: {}", backup_path);
     }
+    if let Some(ref ssh_user) = config.ssh_user {
+        println!("  SSH User: {}", ssh_user);
+    }
+    if let Some(ssh_port) = config.ssh_port {
+        println!("  SSH Port: {}", ssh_port);
+    }
+    if let Some(ref jump) = config.jump {
+        println!("  Jump Host: {}", jump);
+    }
+    if let Some(inventory) = crate::services::host::get_host_inventory(hostname)? {
+        for line in inventory.display_lines() {
+            println!("  {}", line);
+        }
+    }
     Ok(())
 }
 
@@ -98,7 +135,7 @@ pub fn commit_host_config_to_db(hostname: &str) -> Result<()> {
 
     if let Some(config) = env_config.hosts.get(hostname) {
         store_host_config(hostname, config)?;
-        println!(
+        info!(
             "✓ Committed host configuration for '{}' from .env to database",
             hostname
         );
@@ -117,7 +154,7 @@ pub fn backup_host_config_to_env(hostname: &str) -> Result<()> {
     let env_path = homelab_dir.join(".env");
 
     env_file::write_host_to_env_file(&env_path, hostname, &config)?;
-    println!(
+    info!(
         "✓ Backed up host configuration for '{}' from database to .env",
         hostname
     );
@@ -127,7 +164,7 @@ pub fn backup_host_config_to_env(hostname: &str) -> Result<()> {
 /// Delete host configuration
 pub fn delete_host_config(hostname: &str, from_env: bool) -> Result<()> {
     delete_host_config_service(hostname)?;
-    println!(
+    info!(
         "✓ Deleted host configuration for '{}' from database",
         hostname
     );
@@ -136,7 +173,7 @@ pub fn delete_host_config(hostname: &str, from_env: bool) -> Result<()> {
         let homelab_dir = find_homelab_dir()?;
         let env_path = homelab_dir.join(".env");
         env_file::remove_host_from_env_file(&env_path, hostname)?;
-        println!(
+        info!(
             "✓ Removed host configuration for '{}' from .env file",
             hostname
         );
@@ -155,7 +192,7 @@ pub fn commit_all_to_db() -> Result<()> {
         count += 1;
     }
 
-    println!(
+    info!(
         "✓ Committed {} host configuration(s) from .env to database",
         count
     );
@@ -176,7 +213,7 @@ pub fn backup_all_to_env_with_backup() -> Result<()> {
         let backup_path = homelab_dir.join(format!(".env.backup_{}", timestamp));
         fs::copy(&env_path, &backup_path)
             .with_context(|| format!("Failed to backup .env file to {}", backup_path.display()))?;
-        println!("✓ Backed up current .env to {}", backup_path.display());
+        info!("✓ Backed up current .env to {}", backup_path.display());
     }
 
     // Now write all DB configs to .env
@@ -189,7 +226,7 @@ pub fn backup_all_to_env_with_backup() -> Result<()> {
         }
     }
 
-    println!(
+    info!(
         "✓ Wrote {} host configuration(s) from database to .env",
         count
     );
@@ -260,6 +297,15 @@ pub fn show_db_config(verbose: bool) -> Result<()> {
                     println!("  Backup Path: {}", backup_path);
                 }
             }
+            if let Some(ref ssh_user) = config.ssh_user {
+                println!("  SSH User: {}", ssh_user);
+            }
+            if let Some(ssh_port) = config.ssh_port {
+                println!("  SSH Port: {}", ssh_port);
+            }
+            if let Some(ref jump) = config.jump {
+                println!("  Jump Host: {}", jump);
+            }
             println!();
         }
     }
@@ -295,6 +341,24 @@ pub fn show_current_config(verbose: bool) -> Result<()> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
+    let hal_config = config_manager::load_config().unwrap_or_default();
+    if hal_config.profile().is_some() {
+        println!("Profile: {}", hal_config.profile_name());
+    }
+    if let Some(family) = config_manager::get_address_family() {
+        println!("Preferred address family: {}", family.name());
+    }
+    let (env_file, source) = crate::config::resolve_env_file()?;
+    println!("Env file: {}", env_file.display());
+    println!("  Source: {}", source);
+    if verbose {
+        println!(
+            "  Precedence: --config, {}, HOMELAB_ENV_FILE, profile env file (hal config init), HOMELAB_DIR, search from the current directory",
+            crate::config::CONFIG_ENV_VAR
+        );
+    }
+    println!();
+
     // Show Tailnet configuration (env vs db)
     println!("Tailnet:");
     let env_tld = env::var("TAILNET_TLD").or_else(|_| env::var("TLD")).ok();
@@ -431,9 +495,9 @@ pub fn show_current_config(verbose: bool) -> Result<()> {
 
     // Show NPM configuration (env vs db)
     println!("Nginx Proxy Manager:");
-    let npm_url = crate::config::get_npm_url();
-    let npm_username = crate::config::get_npm_username();
-    let npm_password = crate::config::get_npm_password();
+    let npm_url = std::env::var("NPM_URL").ok();
+    let npm_username = std::env::var("NPM_USERNAME").ok();
+    let npm_password = std::env::var("NPM_PASSWORD").ok();
     let db_npm_url = settings::get_setting("NGINX_PROXY_MANAGER_URL")
         .ok()
         .flatten();
@@ -634,6 +698,21 @@ pub fn show_current_config(verbose: bool) -> Result<()> {
                     cfg.backup_path.clone(),
                     db_cfg.and_then(|c| c.backup_path.clone()),
                 );
+                field(
+                    "SSH User",
+                    cfg.ssh_user.clone(),
+                    db_cfg.and_then(|c| c.ssh_user.clone()),
+                );
+                field(
+                    "SSH Port",
+                    cfg.ssh_port.map(|p| p.to_string()),
+                    db_cfg.and_then(|c| c.ssh_port.map(|p| p.to_string())),
+                );
+                field(
+                    "Jump Host",
+                    cfg.jump.clone(),
+                    db_cfg.and_then(|c| c.jump.clone()),
+                );
             } else if let Some(cfg) = db_cfg {
                 if let Some(ip) = &cfg.ip {
                     println!("    IP: (env missing) [db: {}]", ip);
@@ -647,6 +726,15 @@ pub fn show_current_config(verbose: bool) -> Result<()> {
                 if let Some(bp) = &cfg.backup_path {
                     println!("    Backup Path: (env missing) [db: {}]", bp);
                 }
+                if let Some(user) = &cfg.ssh_user {
+                    println!("    SSH User: (env missing) [db: {}]", user);
+                }
+                if let Some(port) = cfg.ssh_port {
+                    println!("    SSH Port: (env missing) [db: {}]", port);
+                }
+                if let Some(jump) = &cfg.jump {
+                    println!("    Jump Host: (env missing) [db: {}]", jump);
+                }
             }
         }
         println!();
@@ -724,11 +812,421 @@ pub fn show_current_config(verbose: bool) -> Result<()> {
             println!("    - {}", issue);
         }
     }
+    for warning in &env_config.warnings {
+        println!("  ⚠ {}", warning);
+    }
     println!();
 
     Ok(())
 }
 
+/// Show current configuration (.env merged with database) as JSON
+/// Passwords are masked unless `verbose` is set
+pub fn show_current_config_json(verbose: bool) -> Result<()> {
+    use crate::db::generated::smb_servers;
+    use serde_json::{Value, json};
+    use std::collections::BTreeMap;
+    use std::collections::btree_map::Entry;
+    use std::env;
+
+    let homelab_dir = find_homelab_dir()?;
+    let env_config = load_env_config(&homelab_dir)?;
+
+    // .env values take precedence; fall back to the database
+    let setting = |key: &str| {
+        env::var(key)
+            .ok()
+            .or_else(|| settings::get_setting(key).ok().flatten())
+    };
+    let secret = |value: Option<String>| {
+        if verbose {
+            value
+        } else {
+            value.map(|_| "***".to_string())
+        }
+    };
+
+    let mut hosts: BTreeMap<String, HostConfig> = env_config.hosts.clone().into_iter().collect();
+    for name in db::list_hosts().unwrap_or_default() {
+        if let Entry::Vacant(entry) = hosts.entry(name)
+            && let Ok(Some(cfg)) = db::get_host_config(entry.key())
+        {
+            entry.insert(cfg);
+        }
+    }
+
+    let smb_json = |server: &crate::config::SmbServerConfig| {
+        json!({
+            "host": server.host,
+            "shares": server.shares,
+            "username": server.username,
+            "password": secret(server.password.clone()),
+            "options": server.options,
+        })
+    };
+    let mut smb: BTreeMap<String, Value> = env_config
+        .smb_servers
+        .iter()
+        .map(|(name, server)| (name.clone(), smb_json(server)))
+        .collect();
+    for name in smb_servers::list_smb_servers().unwrap_or_default() {
+        if let Entry::Vacant(entry) = smb.entry(name)
+            && let Ok(Some(cfg)) = smb_servers::get_smb_server(entry.key())
+        {
+            entry.insert(smb_json(&cfg));
+        }
+    }
+
+    let (env_file, source) = crate::config::resolve_env_file()?;
+    let config = json!({
+        "profile": config_manager::load_config().unwrap_or_default().profile_name(),
+        "env_file": {
+            "path": env_file,
+            "source": source,
+        },
+        "tailnet": {
+            "base": env_config._tailnet_base,
+            "tld": setting("TAILNET_TLD").or_else(|| setting("TLD")),
+            "acme_email": setting("ACME_EMAIL"),
+        },
+        "pia_vpn": {
+            "username": setting("PIA_USERNAME"),
+            "password": secret(setting("PIA_PASSWORD")),
+        },
+        "media_paths": {
+            "downloads": setting("DOWNLOADS_PATH"),
+            "movies": setting("MOVIES_PATH"),
+            "tv": setting("TV_PATH"),
+            "movies_4k": setting("MOVIES_4K_PATH"),
+            "music": setting("MUSIC_PATH"),
+        },
+        "nginx_proxy_manager": {
+            "url": crate::config::get_npm_url(),
+            "username": crate::config::get_npm_username(),
+            "password": secret(crate::config::get_npm_password()),
+        },
+        "smb_servers": smb,
+        "hosts": hosts,
+        "warnings": env_config.warnings,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&config)?);
+    Ok(())
+}
+
+/// Show configuration from database as JSON
+pub fn show_db_config_json() -> Result<()> {
+    let mut hosts = std::collections::BTreeMap::new();
+    for hostname in list_hosts()? {
+        if let Some(config) = get_host_config(&hostname)? {
+            hosts.insert(hostname, config);
+        }
+    }
+    println!("{}", serde_json::to_string_pretty(&hosts)?);
+    Ok(())
+}
+
+/// Severity of a configuration validation finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A single problem found by `validate_config`
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Check whether a string is a valid DNS label (1-63 alphanumerics/hyphens, no leading/trailing hyphen)
+fn is_valid_dns_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Check whether a string looks like something we could connect to (IP address or hostname)
+fn is_valid_host_address(host: &str) -> bool {
+    host.parse::<std::net::IpAddr>().is_ok()
+        || (!host.is_empty() && host.split('.').all(is_valid_dns_label))
+}
+
+/// Check whether `path` is a directory we can create files in
+fn is_writable_dir(path: &std::path::Path) -> bool {
+    if !path.is_dir() {
+        return false;
+    }
+    let probe = path.join(format!(".halvor-write-test-{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Validate configuration cross-references
+/// Backup paths can only be checked for the local host; remote paths must at least be absolute
+pub fn validate_config(config: &EnvConfig) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut error = |message: String| {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            message,
+        })
+    };
+
+    if config.hosts.is_empty() {
+        error("No hosts configured".to_string());
+    }
+
+    let mut server_names: Vec<&String> = config.smb_servers.keys().collect();
+    server_names.sort();
+    for name in server_names {
+        let server = &config.smb_servers[name];
+        if server.host.is_empty() {
+            error(format!("SMB server '{}' missing host", name));
+        } else if !is_valid_host_address(&server.host) {
+            error(format!(
+                "SMB server '{}' host '{}' is not a valid IP address or hostname",
+                name, server.host
+            ));
+        }
+        if server.shares.is_empty() {
+            error(format!("SMB server '{}' missing shares", name));
+        }
+    }
+
+    let local_host = get_current_hostname()
+        .ok()
+        .and_then(|h| find_hostname_in_config(&h, config));
+
+    let mut hostnames: Vec<&String> = config.hosts.keys().collect();
+    hostnames.sort();
+    let mut seen_ips: std::collections::HashMap<&str, &str> = std::collections::HashMap::new();
+    for hostname in hostnames {
+        let host = &config.hosts[hostname];
+
+        if let Some(ref ip) = host.ip
+            && let Some(other) = seen_ips.insert(ip.as_str(), hostname.as_str())
+        {
+            error(format!(
+                "Hosts '{}' and '{}' share the same IP {}",
+                other, hostname, ip
+            ));
+        }
+
+        if let Some(ref tailscale) = host.tailscale
+            && !is_valid_dns_label(tailscale)
+        {
+            error(format!(
+                "Host '{}' tailscale name '{}' is not a valid DNS label",
+                hostname, tailscale
+            ));
+        }
+
+        if let Some(ref backup_path) = host.backup_path {
+            let path = std::path::Path::new(backup_path);
+            if local_host.as_deref() == Some(hostname.as_str()) {
+                if !is_writable_dir(path) {
+                    error(format!(
+                        "Host '{}' backup path {} is not a writable directory",
+                        hostname, backup_path
+                    ));
+                }
+            } else if !path.is_absolute() {
+                error(format!(
+                    "Host '{}' backup path {} is not an absolute path",
+                    hostname, backup_path
+                ));
+            }
+        }
+    }
+
+    issues.extend(config.warnings.iter().map(|warning| ValidationIssue {
+        severity: Severity::Warning,
+        message: warning.clone(),
+    }));
+
+    issues
+}
+
+/// Run `validate_config` and print the results
+/// Returns an error (non-zero exit) when any errors were found
+pub fn show_config_validation(json: bool) -> Result<()> {
+    let config = crate::config::load_config()?;
+    let issues = validate_config(&config);
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .count();
+    let warnings = issues.len() - errors;
+
+    if json {
+        let entries: Vec<serde_json::Value> = issues
+            .iter()
+            .map(|i| serde_json::json!({ "severity": i.severity.as_str(), "message": i.message }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        println!("Validation:");
+        if issues.is_empty() {
+            println!("  ✓ Configuration is valid");
+        }
+        for issue in &issues {
+            let marker = match issue.severity {
+                Severity::Error => "✗",
+                Severity::Warning => "⚠",
+            };
+            println!(
+                "  {} [{}] {}",
+                marker,
+                issue.severity.as_str(),
+                issue.message
+            );
+        }
+        println!();
+        println!("{} error(s), {} warning(s)", errors, warnings);
+    }
+
+    if errors > 0 {
+        anyhow::bail!("Configuration validation failed with {} error(s)", errors);
+    }
+    Ok(())
+}
+
+/// Write a file that may contain secrets, readable only by the owner on Unix
+/// The permissions are set before anything is written, for new and existing files alike
+pub fn write_private_file(path: &std::path::Path, content: &str) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // `mode` only applies when the file is created
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set permissions on {}", path.display()))?;
+    }
+    file.write_all(content.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Encrypt the configured .env file into .env.enc and remove the plaintext file
+pub fn encrypt_env_file() -> Result<()> {
+    use crate::utils::crypto;
+
+    let env_path = crate::config::get_env_file_path()?;
+    let enc_path = crate::config::get_encrypted_env_file_path()?;
+
+    if !env_path.exists() {
+        anyhow::bail!("No .env file found at {}", env_path.display());
+    }
+    if enc_path.exists() {
+        anyhow::bail!(
+            "{} already exists. Remove it or run 'halvor config decrypt' first.",
+            enc_path.display()
+        );
+    }
+
+    let content = std::fs::read_to_string(&env_path)
+        .with_context(|| format!("Failed to read {}", env_path.display()))?;
+    let passphrase = crypto::get_env_passphrase(true)?;
+    let encrypted = crypto::encrypt_with_passphrase(&content, &passphrase)?;
+
+    write_private_file(&enc_path, &encrypted)?;
+
+    // Make sure the encrypted file round-trips before removing the plaintext
+    crypto::decrypt_with_passphrase(&encrypted, &passphrase)
+        .context("Verification of encrypted file failed; plaintext .env left in place")?;
+    std::fs::remove_file(&env_path)
+        .with_context(|| format!("Failed to remove {}", env_path.display()))?;
+
+    info!(
+        "✓ Encrypted {} -> {}",
+        env_path.display(),
+        enc_path.display()
+    );
+    info!(
+        "  Set {} or enter the passphrase when prompted to use it.",
+        crypto::ENV_KEY_VAR
+    );
+    Ok(())
+}
+
+/// Decrypt .env.enc back into a plaintext .env file and remove the encrypted file
+pub fn decrypt_env_file() -> Result<()> {
+    use crate::utils::crypto;
+
+    let env_path = crate::config::get_env_file_path()?;
+    let enc_path = crate::config::get_encrypted_env_file_path()?;
+
+    if !enc_path.exists() {
+        anyhow::bail!("No encrypted env file found at {}", enc_path.display());
+    }
+    if env_path.exists() {
+        anyhow::bail!(
+            "{} already exists. Remove it before decrypting to avoid overwriting it.",
+            env_path.display()
+        );
+    }
+
+    let encrypted = std::fs::read_to_string(&enc_path)
+        .with_context(|| format!("Failed to read {}", enc_path.display()))?;
+    let passphrase = crypto::get_env_passphrase(false)?;
+    let content = crypto::decrypt_with_passphrase(encrypted.trim(), &passphrase)?;
+
+    write_private_file(&env_path, &content)?;
+    std::fs::remove_file(&enc_path)
+        .with_context(|| format!("Failed to remove {}", enc_path.display()))?;
+
+    info!(
+        "✓ Decrypted {} -> {}",
+        enc_path.display(),
+        env_path.display()
+    );
+    Ok(())
+}
+
+/// Set the default host after checking it is configured (None clears it)
+pub fn set_default_host(hostname: Option<&str>) -> Result<()> {
+    let Some(hostname) = hostname else {
+        return config_manager::set_default_host(None);
+    };
+    let config = crate::config::load_config()?;
+    let actual_hostname = find_hostname_in_config(hostname, &config).with_context(|| {
+        format!(
+            "Host '{}' not found\n\nAdd configuration:\n  halvor config create ssh {}",
+            hostname, hostname
+        )
+    })?;
+    config_manager::set_default_host(Some(&actual_hostname))
+}
+
 /// Set environment file path
 pub fn set_env_path(path: &str) -> Result<()> {
     config_manager::set_env_file_path(PathBuf::from(path).as_path())
@@ -752,6 +1250,9 @@ TAILNET_BASE=ts.net
 # HOST_bellerophon_HOSTNAME=bellerophon
 # HOST_bellerophon_TAILSCALE=bellerophon
 # HOST_bellerophon_BACKUP_PATH=/mnt/backups/bellerophon
+# HOST_bellerophon_SSH_USER=admin
+# HOST_bellerophon_SSH_PORT=2222
+# HOST_bellerophon_JUMP=bastion   # another configured host, or [user@]host[:port]
 
 # SMB Server configurations
 # Format: SMB_<SERVERNAME>_<FIELD>=<value>
@@ -770,14 +1271,14 @@ TAILNET_BASE=ts.net
     std::fs::write(&env_path, example_content)
         .with_context(|| format!("Failed to write example .env file: {}", env_path.display()))?;
 
-    println!("✓ Created example .env file at {}", env_path.display());
+    info!("✓ Created example .env file at {}", env_path.display());
     Ok(())
 }
 
 /// Backup SQLite database
-pub fn backup_database(path: Option<&str>) -> Result<()> {
+/// With `encrypt`, the copy is encrypted with the backup passphrase and saved as `.db.enc`
+pub fn backup_database(path: Option<&str>, encrypt: bool) -> Result<()> {
     use chrono::Utc;
-    use std::fs;
 
     let db_path = db::get_db_path()?;
 
@@ -789,64 +1290,235 @@ pub fn backup_database(path: Option<&str>) -> Result<()> {
         PathBuf::from(p)
     } else {
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-        std::env::current_dir()?.join(format!("halvor_backup_{}.db", timestamp))
+        let extension = if encrypt { "db.enc" } else { "db" };
+        std::env::current_dir()?.join(format!("halvor_backup_{}.{}", timestamp, extension))
     };
 
-    // Use sudo to ensure we have proper access to the database
-    // This ensures we can read the database even if it has restricted permissions
-    #[cfg(unix)]
-    {
-        // Try to copy with sudo first (for system-protected databases)
-        let sudo_copy = std::process::Command::new("sudo")
-            .arg("cp")
-            .arg(&db_path)
-            .arg(&backup_path)
-            .output();
-
-        if let Ok(output) = sudo_copy {
-            if output.status.success() {
-                println!("✓ Database backed up to {}", backup_path.display());
-                println!("  Note: Backup is unencrypted (plain SQLite format)");
-                return Ok(());
-            }
+    if encrypt {
+        let passphrase = crate::utils::crypto::get_backup_passphrase(true)?;
+        let mut input = std::fs::File::open(&db_path)
+            .with_context(|| format!("Failed to open database: {}", db_path.display()))?;
+        let mut output = std::fs::File::create(&backup_path)
+            .with_context(|| format!("Failed to create {}", backup_path.display()))?;
+        if let Err(e) = crate::utils::crypto::encrypt_stream(&mut input, &mut output, &passphrase) {
+            let _ = std::fs::remove_file(&backup_path);
+            return Err(e.context("Failed to encrypt database backup"));
+        }
+        info!(
+            "✓ Database backed up to {} (encrypted)",
+            backup_path.display()
+        );
+        return Ok(());
+    }
+
+    db::copy_database(&db_path, &backup_path)?;
+
+    info!("✓ Database backed up to {}", backup_path.display());
+    info!("  Note: Backup is unencrypted (plain SQLite format)");
+    Ok(())
+}
+
+/// Run `PRAGMA integrity_check`, failing unless SQLite reports "ok"
+fn check_database_integrity(conn: &rusqlite::Connection) -> Result<()> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let results: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()
+        .context("Failed to run integrity check")?;
+
+    if results != ["ok"] {
+        error!("✗ Integrity check failed:");
+        for problem in &results {
+            error!("  {}", problem);
         }
+        anyhow::bail!(
+            "Database integrity check failed ({} problem(s))",
+            results.len()
+        );
     }
+    info!("✓ Integrity check passed");
+    Ok(())
+}
 
-    // Fallback to regular copy (for user-owned databases)
-    fs::copy(&db_path, &backup_path).with_context(|| {
-        format!(
-            "Failed to copy database from {} to {}. You may need administrator privileges.",
-            db_path.display(),
-            backup_path.display()
-        )
-    })?;
+/// Compact the database with VACUUM and refresh query planner statistics with ANALYZE
+/// With `integrity_check`, the database is checked first and left untouched if it is corrupt
+pub fn vacuum_database(integrity_check: bool) -> Result<()> {
+    let db_path = db::get_db_path()?;
+    if !db_path.exists() {
+        anyhow::bail!("Database not found at {}", db_path.display());
+    }
+
+    let conn = db::get_connection()?;
+    let file_size = || std::fs::metadata(&db_path).map(|m| m.len());
+    let size_before = file_size()?;
+
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("Optimizing database: {}", db_path.display());
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("");
+
+    if integrity_check {
+        check_database_integrity(&conn)?;
+    }
+
+    conn.execute_batch("VACUUM")
+        .context("Failed to vacuum database")?;
+    info!("✓ VACUUM complete");
+    conn.execute_batch("ANALYZE")
+        .context("Failed to analyze database")?;
+    info!("✓ ANALYZE complete");
+
+    let size_after = file_size()?;
+    info!("");
+    if size_after < size_before {
+        info!(
+            "Size: {} -> {} ({} reclaimed)",
+            format_bytes(size_before),
+            format_bytes(size_after),
+            format_bytes(size_before - size_after)
+        );
+    } else {
+        // ANALYZE stores its statistics in the database, so a compact file can grow slightly
+        info!(
+            "Size: {} -> {} (nothing to reclaim)",
+            format_bytes(size_before),
+            format_bytes(size_after)
+        );
+    }
 
-    println!("✓ Database backed up to {}", backup_path.display());
-    println!("  Note: Backup is unencrypted (plain SQLite format)");
     Ok(())
 }
 
-/// Show differences between .env and database configurations
-pub fn show_config_diff() -> Result<()> {
-    let homelab_dir = find_homelab_dir()?;
-    let env_config = load_env_config(&homelab_dir)?;
-    let db_hosts = list_hosts().unwrap_or_default();
+/// One field that differs between two host configurations
+pub struct HostFieldDiff {
+    pub field: &'static str,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Compare two host configurations field by field
+pub fn diff_host_configs(left: &HostConfig, right: &HostConfig) -> Vec<HostFieldDiff> {
+    let port = |config: &HostConfig| config.ssh_port.map(|port| port.to_string());
+    [
+        ("IP", left.ip.clone(), right.ip.clone()),
+        ("Hostname", left.hostname.clone(), right.hostname.clone()),
+        ("Tailscale", left.tailscale.clone(), right.tailscale.clone()),
+        (
+            "Backup path",
+            left.backup_path.clone(),
+            right.backup_path.clone(),
+        ),
+        ("SSH user", left.ssh_user.clone(), right.ssh_user.clone()),
+        ("SSH port", port(left), port(right)),
+        ("Jump host", left.jump.clone(), right.jump.clone()),
+    ]
+    .into_iter()
+    .filter(|(_, left, right)| left != right)
+    .map(|(field, left, right)| HostFieldDiff { field, left, right })
+    .collect()
+}
+
+/// One host field whose .env and database values differ
+pub struct ConfigFieldDiff {
+    pub host: String,
+    pub field: &'static str,
+    pub env: Option<String>,
+    pub db: Option<String>,
+}
 
-    let mut env_hostnames: Vec<_> = env_config.hosts.keys().collect();
-    env_hostnames.sort();
+/// Differences between the .env file and the database, host by host
+pub struct ConfigDiff {
+    /// Every host in either store, sorted
+    pub hosts: Vec<String>,
+    pub fields: Vec<ConfigFieldDiff>,
+    pub only_in_env: Vec<String>,
+    pub only_in_db: Vec<String>,
+}
 
-    let mut all_hostnames = std::collections::HashSet::new();
-    for hostname in &env_hostnames {
-        all_hostnames.insert(*hostname);
+/// Compare the .env hosts with the database hosts
+/// Database records without an address don't count as configured, as in `load_config`.
+pub fn collect_config_diff() -> Result<ConfigDiff> {
+    let homelab_dir = find_homelab_dir()?;
+    let env_config = load_env_config(&homelab_dir)?;
+    let mut db_hosts = std::collections::HashMap::new();
+    for hostname in db::list_hosts().unwrap_or_default() {
+        if let Some(config) = db::get_host_config(&hostname)
+            .ok()
+            .flatten()
+            .filter(HostConfig::has_address)
+        {
+            db_hosts.insert(hostname, config);
+        }
+    }
+
+    let mut hosts: Vec<String> = env_config
+        .hosts
+        .keys()
+        .chain(db_hosts.keys())
+        .cloned()
+        .collect();
+    hosts.sort();
+    hosts.dedup();
+
+    let mut diff = ConfigDiff {
+        hosts,
+        fields: Vec::new(),
+        only_in_env: Vec::new(),
+        only_in_db: Vec::new(),
+    };
+    for hostname in &diff.hosts {
+        match (env_config.hosts.get(hostname), db_hosts.get(hostname)) {
+            (Some(env), Some(db)) => {
+                diff.fields
+                    .extend(
+                        diff_host_configs(env, db)
+                            .into_iter()
+                            .map(|field| ConfigFieldDiff {
+                                host: hostname.clone(),
+                                field: field.field,
+                                env: field.left,
+                                db: field.right,
+                            }),
+                    );
+            }
+            (Some(_), None) => diff.only_in_env.push(hostname.clone()),
+            (None, Some(_)) => diff.only_in_db.push(hostname.clone()),
+            (None, None) => {}
+        }
     }
-    for hostname in &db_hosts {
-        all_hostnames.insert(hostname);
+    Ok(diff)
+}
+
+/// Set the field `diff_host_configs` labels `field` to `value`
+fn set_diff_field(config: &mut HostConfig, field: &str, value: Option<String>) -> Result<()> {
+    match field {
+        "IP" => config.ip = value,
+        "Hostname" => config.hostname = value,
+        "Tailscale" => config.tailscale = value,
+        "Backup path" => config.backup_path = value,
+        "SSH user" => config.ssh_user = value,
+        "SSH port" => {
+            config.ssh_port = value
+                .map(|port| crate::config::parse_ssh_port(&port))
+                .transpose()?
+        }
+        "Jump host" => config.jump = value,
+        _ => anyhow::bail!("Unknown field: {}", field),
     }
+    Ok(())
+}
 
-    let mut all_hostnames: Vec<_> = all_hostnames.into_iter().collect();
-    all_hostnames.sort();
+/// Side that wins every differing field in `hal config diff --apply`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DiffWinner {
+    Env,
+    Db,
+}
 
-    if all_hostnames.is_empty() {
+/// Show differences between .env and database configurations
+pub fn show_config_diff() -> Result<()> {
+    let diff = collect_config_diff()?;
+    if diff.hosts.is_empty() {
         println!("No hosts found in either .env or database.");
         return Ok(());
     }
@@ -854,82 +1526,20 @@ pub fn show_config_diff() -> Result<()> {
     println!("Configuration differences between .env and database:");
     println!();
 
-    for hostname in &all_hostnames {
-        let env_config = env_config.hosts.get(*hostname);
-        let db_config = get_host_config(hostname).ok().flatten();
-
-        match (env_config, db_config) {
-            (Some(env), Some(db)) => {
-                // Compare fields
-                let mut has_diff = false;
-                if env.ip != db.ip {
-                    println!("  {} - IP differs:", hostname);
-                    if let Some(ref ip) = env.ip {
-                        println!("    .env: {}", ip);
-                    } else {
-                        println!("    .env: (not set)");
-                    }
-                    if let Some(ref ip) = db.ip {
-                        println!("    db:   {}", ip);
-                    } else {
-                        println!("    db:   (not set)");
-                    }
-                    has_diff = true;
-                }
-                if env.hostname != db.hostname {
-                    println!("  {} - Hostname differs:", hostname);
-                    if let Some(ref h) = env.hostname {
-                        println!("    .env: {}", h);
-                    } else {
-                        println!("    .env: (not set)");
-                    }
-                    if let Some(ref h) = db.hostname {
-                        println!("    db:   {}", h);
-                    } else {
-                        println!("    db:   (not set)");
-                    }
-                    has_diff = true;
-                }
-                if env.tailscale != db.tailscale {
-                    println!("  {} - Tailscale differs:", hostname);
-                    if let Some(ref t) = env.tailscale {
-                        println!("    .env: {}", t);
-                    } else {
-                        println!("    .env: (not set)");
-                    }
-                    if let Some(ref t) = db.tailscale {
-                        println!("    db:   {}", t);
-                    } else {
-                        println!("    db:   (not set)");
-                    }
-                    has_diff = true;
-                }
-                if env.backup_path != db.backup_path {
-                    println!("  {} - Backup path differs:", hostname);
-                    if let Some(ref p) = env.backup_path {
-                        println!("    .env: {}", p);
-                    } else {
-                        println!("    .env: (not set)");
-                    }
-                    if let Some(ref p) = db.backup_path {
-                        println!("    db:   {}", p);
-                    } else {
-                        println!("    db:   (not set)");
-                    }
-                    has_diff = true;
-                }
-                if !has_diff {
-                    println!("  {} - No differences", hostname);
-                }
-            }
-            (Some(_), None) => {
-                println!("  {} - Only in .env (not in database)", hostname);
-            }
-            (None, Some(_)) => {
-                println!("  {} - Only in database (not in .env)", hostname);
+    for hostname in &diff.hosts {
+        if diff.only_in_env.contains(hostname) {
+            println!("  {} - Only in .env (not in database)", hostname);
+        } else if diff.only_in_db.contains(hostname) {
+            println!("  {} - Only in database (not in .env)", hostname);
+        } else {
+            let fields: Vec<_> = diff.fields.iter().filter(|f| &f.host == hostname).collect();
+            for field in &fields {
+                println!("  {} - {} differs:", hostname, field.field);
+                println!("    .env: {}", field.env.as_deref().unwrap_or("(not set)"));
+                println!("    db:   {}", field.db.as_deref().unwrap_or("(not set)"));
             }
-            (None, None) => {
-                // Shouldn't happen, but handle it
+            if fields.is_empty() {
+                println!("  {} - No differences", hostname);
             }
         }
         println!();
@@ -938,10 +1548,134 @@ pub fn show_config_diff() -> Result<()> {
     Ok(())
 }
 
-/// Get the current machine's hostname
-pub fn get_current_hostname() -> Result<String> {
-    use crate::utils::exec::local;
-    use std::env;
+/// Reconcile the .env file and the database field by field (`hal config diff --apply`)
+/// Asks which value to keep for each differing field unless `winner` answers for all of them.
+/// The chosen values are written to both stores once every field is decided, so Ctrl-C at a
+/// prompt leaves everything as it was.
+pub fn apply_config_diff(winner: Option<DiffWinner>) -> Result<()> {
+    let diff = collect_config_diff()?;
+    if diff.fields.is_empty() {
+        println!("✓ The .env file and the database agree on every host they share");
+    }
+    if winner.is_none() && !diff.fields.is_empty() && !io::stdin().is_terminal() {
+        anyhow::bail!(
+            "Not a terminal; pass --env-wins or --db-wins to choose a side for every field"
+        );
+    }
+
+    // Every decision is made before anything is written
+    let mut decided: Vec<(&str, Vec<(&ConfigFieldDiff, DiffWinner)>)> = Vec::new();
+    let mut skipped = 0;
+    for field in &diff.fields {
+        let choice = match winner {
+            Some(winner) => Some(winner),
+            None => prompt_diff_choice(field)?,
+        };
+        let Some(choice) = choice else {
+            skipped += 1;
+            continue;
+        };
+        match decided.iter_mut().find(|(host, _)| *host == field.host) {
+            Some((_, decisions)) => decisions.push((field, choice)),
+            None => decided.push((&field.host, vec![(field, choice)])),
+        }
+    }
+
+    let env_config = load_env_config(&find_homelab_dir()?)?;
+    let env_path = crate::config::get_env_file_path()?;
+    // Database values only ever go into the .env file
+    if decided
+        .iter()
+        .any(|(_, decisions)| decisions.iter().any(|(_, w)| matches!(w, DiffWinner::Db)))
+    {
+        env_file::ensure_env_file_not_encrypted(&env_path)?;
+    }
+    for (hostname, decisions) in &decided {
+        let env = &env_config.hosts[*hostname];
+        let db = db::get_host_config(hostname)?
+            .with_context(|| format!("Host '{}' is no longer in the database", hostname))?;
+        let (new_env, new_db) = reconcile_host(env, &db, decisions)?;
+        let env_changed = !diff_host_configs(env, &new_env).is_empty();
+        let db_changed = !diff_host_configs(&db, &new_db).is_empty();
+        if env_changed {
+            env_file::write_host_to_env_file(&env_path, hostname, &new_env)?;
+        }
+        if db_changed {
+            store_host_config(hostname, &new_db)?;
+        }
+        match (env_changed, db_changed) {
+            (true, true) => println!(
+                "✓ Updated host '{}' in {} and the database",
+                hostname,
+                env_path.display()
+            ),
+            (true, false) => println!("✓ Updated host '{}' in {}", hostname, env_path.display()),
+            (false, true) => println!("✓ Updated host '{}' in the database", hostname),
+            (false, false) => {}
+        }
+    }
+    if skipped > 0 {
+        println!("{} field(s) skipped and still differ", skipped);
+    }
+    for hostname in &diff.only_in_env {
+        println!(
+            "  {} is only in .env; copy it with 'hal config -H {} commit'",
+            hostname, hostname
+        );
+    }
+    for hostname in &diff.only_in_db {
+        println!(
+            "  {} is only in the database; copy it with 'hal config -H {} backup'",
+            hostname, hostname
+        );
+    }
+    Ok(())
+}
+
+/// A host's .env and database records after `decisions`
+/// Each decided field takes the winning side's value in the other store; everything else,
+/// including skipped fields, stays as each store had it.
+fn reconcile_host(
+    env: &HostConfig,
+    db: &HostConfig,
+    decisions: &[(&ConfigFieldDiff, DiffWinner)],
+) -> Result<(HostConfig, HostConfig)> {
+    let (mut env, mut db) = (env.clone(), db.clone());
+    for (field, winner) in decisions {
+        match winner {
+            DiffWinner::Env => set_diff_field(&mut db, field.field, field.env.clone())?,
+            DiffWinner::Db => set_diff_field(&mut env, field.field, field.db.clone())?,
+        }
+    }
+    Ok((env, db))
+}
+
+/// Ask which value of a differing field to keep (None skips the field)
+fn prompt_diff_choice(field: &ConfigFieldDiff) -> Result<Option<DiffWinner>> {
+    println!();
+    println!("{} - {} differs:", field.host, field.field);
+    println!("  .env: {}", field.env.as_deref().unwrap_or("(not set)"));
+    println!("  db:   {}", field.db.as_deref().unwrap_or("(not set)"));
+    loop {
+        print!("Keep the .env value or the database value? [e/d/s(kip)]: ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            anyhow::bail!("No answer (end of input); nothing was changed");
+        }
+        match input.trim().to_lowercase().as_str() {
+            "e" | "env" | ".env" => return Ok(Some(DiffWinner::Env)),
+            "d" | "db" | "database" => return Ok(Some(DiffWinner::Db)),
+            "s" | "skip" => return Ok(None),
+            _ => println!("Answer e (.env), d (database) or s (skip)"),
+        }
+    }
+}
+
+/// Get the current machine's hostname
+pub fn get_current_hostname() -> Result<String> {
+    use crate::utils::exec::local;
+    use std::env;
 
     // Try multiple methods to get hostname
     // 1. Try HOSTNAME environment variable
@@ -1090,9 +1824,9 @@ pub fn ensure_host_in_config(hostname: Option<&str>, config: &EnvConfig) -> Resu
     }
 
     // Interactive setup
-    println!();
-    println!("Setting up node...");
-    println!();
+    info!("");
+    info!("Setting up node...");
+    info!("");
 
     // Configure hostname (allow override)
     print!("Hostname [{}]: ", detected_hostname);
@@ -1106,13 +1840,94 @@ pub fn ensure_host_in_config(hostname: Option<&str>, config: &EnvConfig) -> Resu
         current_hostname.to_string()
     };
 
-    #[cfg(debug_assertions)]
-    println!("[DEBUG] Using hostname: {}", current_hostname);
+    debug!("Using hostname: {}", current_hostname);
+
+    let ip = detect_ip_interactive()?;
+    let tailscale = detect_tailscale_hostname_interactive()?;
+    let use_tailscale_ip = detect_tailscale_ip_interactive()?;
+
+    // Use Tailscale IP as primary IP if provided, otherwise use detected IP
+    let using_tailscale_ip = use_tailscale_ip.is_some();
+    let final_ip = use_tailscale_ip.unwrap_or(ip);
+
+    debug!("Final configuration:");
+    debug!("  hostname: {}", current_hostname);
+    debug!("  ip: {}", final_ip);
+    debug!("  tailscale hostname: {:?}", tailscale);
+    if using_tailscale_ip {
+        debug!("  using Tailscale IP as primary IP");
+    }
+
+    // Create host config
+    let host_config = HostConfig {
+        ip: Some(final_ip),
+        hostname: Some(current_hostname.clone()),
+        tailscale,
+        backup_path: None,
+        ssh_user: None,
+        ssh_port: None,
+        jump: None,
+    };
+
+    // Store in database only (not .env file)
+    debug!("Storing host config to database:");
+    debug!("  hostname: {}", current_hostname);
+    debug!("  ip: {:?}", host_config.ip);
+    debug!("  tailscale: {:?}", host_config.tailscale);
+
+    store_host_config(&current_hostname, &host_config).with_context(|| {
+        format!(
+            "Failed to store host config for '{}' in database",
+            current_hostname
+        )
+    })?;
+
+    debug!("✓ Host config stored to database");
+
+    // Verify it can be retrieved (only worth the extra query when someone is looking)
+    if log::log_enabled!(log::Level::Debug) {
+        match get_host_config(&current_hostname) {
+            Ok(Some(retrieved)) => {
+                debug!("✓ Verified: Host config retrieved from database");
+                debug!("  Retrieved hostname: {:?}", retrieved.hostname);
+                debug!("  Retrieved IP: {:?}", retrieved.ip);
+                debug!("  Retrieved tailscale: {:?}", retrieved.tailscale);
+            }
+            Ok(None) => {
+                debug!("⚠ Warning: Host config not found after storing");
+            }
+            Err(e) => {
+                debug!("⚠ Error retrieving host config: {}", e);
+            }
+        }
+    }
+
+    info!("");
+    info!("✓ Node '{}' configured successfully!", current_hostname);
+    info!("  Configuration saved to database");
+    info!("");
+
+    Ok(current_hostname)
+}
 
-    // Get IP address - auto-detect
+/// Pick this machine's IP address: auto-detected when unambiguous, otherwise prompted
+fn detect_ip_interactive() -> Result<String> {
     use crate::utils::networking;
+
+    // Prefer the address the default route actually uses
+    if let Ok(Some(route)) = networking::get_default_route()
+        && let Some(ip) = route.source_ip
+    {
+        info!(
+            "✓ Detected IP: {} (default route via {})",
+            ip, route.interface
+        );
+        return Ok(ip);
+    }
+
+    // Route command unavailable - fall back to filtering all local addresses
     let local_ips = networking::get_local_ips()?;
-    let ip = if local_ips.is_empty() {
+    Ok(if local_ips.is_empty() {
         // No IPs detected - prompt user
         print!("Enter IP address: ");
         io::stdout().flush()?;
@@ -1125,7 +1940,7 @@ pub fn ensure_host_in_config(hostname: Option<&str>, config: &EnvConfig) -> Resu
         ip
     } else if local_ips.len() == 1 {
         // Single IP detected - use it automatically
-        println!("✓ Detected IP: {}", local_ips[0]);
+        info!("✓ Detected IP: {}", local_ips[0]);
         local_ips[0].clone()
     } else {
         // Multiple IPs detected - prefer non-loopback, non-link-local
@@ -1141,7 +1956,7 @@ pub fn ensure_host_in_config(hostname: Option<&str>, config: &EnvConfig) -> Resu
 
         if preferred_ips.len() == 1 {
             // One preferred IP - use it automatically
-            println!("✓ Detected IP: {}", preferred_ips[0]);
+            info!("✓ Detected IP: {}", preferred_ips[0]);
             preferred_ips[0].to_string()
         } else if !preferred_ips.is_empty() {
             // Multiple preferred IPs - show them and let user choose
@@ -1184,14 +1999,14 @@ pub fn ensure_host_in_config(hostname: Option<&str>, config: &EnvConfig) -> Resu
                 local_ips[idx - 1].clone()
             }
         }
-    };
-
-    let tailscale_ips = networking::get_tailscale_ips().ok().unwrap_or_default();
+    })
+}
 
-    // Get Tailscale hostname (optional)
+/// Offer the detected Tailscale hostname, or prompt for one (None when skipped)
+fn detect_tailscale_hostname_interactive() -> Result<Option<String>> {
     use crate::services::tailscale;
     let tailscale_hostname = tailscale::get_tailscale_hostname().ok().flatten();
-    let tailscale = if let Some(ts) = tailscale_hostname {
+    Ok(if let Some(ts) = tailscale_hostname {
         println!("Detected Tailscale hostname: {}", ts);
         print!("Use this Tailscale hostname? [Y/n]: ");
         io::stdout().flush()?;
@@ -1223,11 +2038,17 @@ pub fn ensure_host_in_config(hostname: Option<&str>, config: &EnvConfig) -> Resu
         } else {
             Some(ts.to_string())
         }
-    };
+    })
+}
 
-    // Get Tailscale IP (optional) - can be used as primary IP
-    // Use detected Tailscale IPs from networking module, or fallback to tailscale service
-    let use_tailscale_ip = if !tailscale_ips.is_empty() {
+/// Offer a detected Tailscale IP to use as the primary IP (None when declined)
+///
+/// Uses the IPs from the networking module, falling back to the tailscale service.
+fn detect_tailscale_ip_interactive() -> Result<Option<String>> {
+    use crate::services::tailscale;
+    use crate::utils::networking;
+    let tailscale_ips = networking::get_tailscale_ips().ok().unwrap_or_default();
+    Ok(if !tailscale_ips.is_empty() {
         if tailscale_ips.len() == 1 {
             println!("Detected Tailscale IP: {}", tailscale_ips[0]);
             print!("Use Tailscale IP as primary IP? [y/N]: ");
@@ -1289,78 +2110,448 @@ pub fn ensure_host_in_config(hostname: Option<&str>, config: &EnvConfig) -> Resu
                 Some(ts_ip.to_string())
             }
         }
-    };
-
-    // Use Tailscale IP as primary IP if provided, otherwise use detected IP
-    let using_tailscale_ip = use_tailscale_ip.is_some();
-    let final_ip = use_tailscale_ip.unwrap_or(ip);
+    })
+}
 
-    #[cfg(debug_assertions)]
-    println!("[DEBUG] Final configuration:");
-    #[cfg(debug_assertions)]
-    println!("[DEBUG]   hostname: {}", current_hostname);
-    #[cfg(debug_assertions)]
-    println!("[DEBUG]   ip: {}", final_ip);
-    #[cfg(debug_assertions)]
-    println!("[DEBUG]   tailscale hostname: {:?}", tailscale);
-    if using_tailscale_ip {
-        #[cfg(debug_assertions)]
-        println!("[DEBUG]   using Tailscale IP as primary IP");
+/// Prompt for one host field showing its current value
+///
+/// Enter keeps the value, `-` clears it, and `detect` (when `detect` is given) runs auto-detection.
+/// End of input (Ctrl-D) aborts the edit.
+fn prompt_host_field(
+    label: &str,
+    current: Option<&str>,
+    detect: Option<&dyn Fn() -> Result<Option<String>>>,
+) -> Result<Option<String>> {
+    loop {
+        let hint = if detect.is_some() { ", 'detect'" } else { "" };
+        print!(
+            "{} [{}] (Enter keeps, '-' clears{}): ",
+            label,
+            current.unwrap_or("not set"),
+            hint
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            println!();
+            anyhow::bail!("Edit cancelled - no changes were made");
+        }
+        return match (input.trim(), detect) {
+            ("", _) => Ok(current.map(str::to_string)),
+            ("-", _) => Ok(None),
+            ("detect", Some(detect)) => match detect()? {
+                Some(value) => Ok(Some(value)),
+                None => {
+                    println!("Nothing detected");
+                    continue;
+                }
+            },
+            ("detect", None) => {
+                println!("Auto-detection only works when editing the machine halvor runs on");
+                continue;
+            }
+            (value, _) => Ok(Some(value.to_string())),
+        };
     }
+}
 
-    // Create host config
-    let host_config = HostConfig {
-        ip: Some(final_ip),
-        hostname: Some(current_hostname.clone()),
-        tailscale,
-        backup_path: None,
+/// Interactively edit an existing host, then write it to the .env file and the database
+///
+/// Nothing is written until every field has been answered and the change confirmed, so Ctrl-C
+/// at any prompt leaves the configuration untouched.
+pub fn edit_host_config(hostname: &str) -> Result<()> {
+    env_file::ensure_env_file_not_encrypted(&crate::config::get_env_file_path()?)?;
+    let config = crate::config::load_config()?;
+    let hostname = find_hostname_in_config(hostname, &config).with_context(|| {
+        format!(
+            "Host '{}' not found. Add it with `hal config -H {} ip <address>` first",
+            hostname, hostname
+        )
+    })?;
+    let current = config.hosts[&hostname].clone();
+    let is_this_machine = get_current_hostname()
+        .map(|this| this.eq_ignore_ascii_case(&hostname))
+        .unwrap_or(false);
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Edit host '{}'", hostname);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Press Ctrl-C at any prompt to abort without saving.");
+    println!();
+
+    // Auto-detection only describes the machine halvor is running on. As in first-time setup, a
+    // Tailscale IP can replace the detected LAN IP.
+    let detect_ip = || -> Result<Option<String>> {
+        let ip = detect_ip_interactive()?;
+        Ok(Some(detect_tailscale_ip_interactive()?.unwrap_or(ip)))
+    };
+    let detect_tailscale = detect_tailscale_hostname_interactive;
+
+    let edited = HostConfig {
+        ip: prompt_host_field(
+            "IP",
+            current.ip.as_deref(),
+            is_this_machine.then_some(&detect_ip as &dyn Fn() -> Result<Option<String>>),
+        )?,
+        hostname: prompt_host_field("Hostname", current.hostname.as_deref(), None)?,
+        tailscale: prompt_host_field(
+            "Tailscale hostname",
+            current.tailscale.as_deref(),
+            is_this_machine.then_some(&detect_tailscale as &dyn Fn() -> Result<Option<String>>),
+        )?,
+        backup_path: prompt_host_field("Backup path", current.backup_path.as_deref(), None)?,
+        ssh_user: prompt_host_field("SSH user", current.ssh_user.as_deref(), None)?,
+        ssh_port: prompt_host_field(
+            "SSH port",
+            current.ssh_port.map(|port| port.to_string()).as_deref(),
+            None,
+        )?
+        .map(|port| crate::config::parse_ssh_port(&port))
+        .transpose()?,
+        jump: prompt_host_field("Jump host", current.jump.as_deref(), None)?
+            .map(|jump| crate::config::validate_jump_host(&jump).map(|_| jump))
+            .transpose()?,
     };
 
-    // Store in database only (not .env file)
-    #[cfg(debug_assertions)]
-    println!("[DEBUG] Storing host config to database:");
-    #[cfg(debug_assertions)]
-    println!("[DEBUG]   hostname: {}", current_hostname);
-    #[cfg(debug_assertions)]
-    println!("[DEBUG]   ip: {:?}", host_config.ip);
-    #[cfg(debug_assertions)]
-    println!("[DEBUG]   tailscale: {:?}", host_config.tailscale);
+    let diffs = diff_host_configs(&current, &edited);
+    println!();
+    if diffs.is_empty() {
+        println!("No changes");
+        return Ok(());
+    }
+    for diff in &diffs {
+        println!(
+            "  {}: {} → {}",
+            diff.field,
+            diff.left.as_deref().unwrap_or("(not set)"),
+            diff.right.as_deref().unwrap_or("(not set)")
+        );
+    }
+    print!("Save these changes to .env and the database? [Y/n]: ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let response = input.trim().to_lowercase();
+    if !response.is_empty() && response != "y" && response != "yes" {
+        println!("Cancelled - no changes were made.");
+        return Ok(());
+    }
 
-    store_host_config(&current_hostname, &host_config).with_context(|| {
-        format!(
-            "Failed to store host config for '{}' in database",
-            current_hostname
+    // The database record is written first in a transaction that only commits once the .env file
+    // has been written, and the file is restored if the commit fails, so the two never disagree
+    let env_path = crate::config::get_env_file_path()?;
+    let original_env = if env_path.exists() {
+        Some(
+            std::fs::read_to_string(&env_path)
+                .with_context(|| format!("Failed to read .env file: {}", env_path.display()))?,
         )
-    })?;
+    } else {
+        None
+    };
+    let conn = db::get_connection()?;
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to start edit transaction")?;
+    db::generated::host_info::store_host_config_with(&tx, &hostname, &edited)?;
+    let saved = env_file::write_host_to_env_file(&env_path, &hostname, &edited)
+        .and_then(|()| tx.commit().context("Failed to commit host edit"));
+    if let Err(e) = saved {
+        match original_env {
+            Some(content) => std::fs::write(&env_path, content),
+            None if env_path.exists() => std::fs::remove_file(&env_path),
+            None => Ok(()),
+        }
+        .with_context(|| format!("Failed to restore .env file: {}", env_path.display()))?;
+        return Err(e);
+    }
+    println!(
+        "✓ Updated host '{}' in {} and the database",
+        hostname,
+        env_path.display()
+    );
+    Ok(())
+}
 
-    #[cfg(debug_assertions)]
-    println!("[DEBUG] ✓ Host config stored to database");
+/// Hosts and settings from the .env file alone, without the database hosts `load_config` merges in
+fn load_env_file_config() -> Option<EnvConfig> {
+    load_env_config(&find_homelab_dir().ok()?).ok()
+}
 
-    // Verify it can be retrieved
-    #[cfg(debug_assertions)]
+/// Add a host without prompting (`hal host add`), the scripted counterpart of the onboarding
+/// in `ensure_host_in_config`
+/// Writes the database record, and the .env entries too when `write_env` is set. Fails if a host
+/// with this name is already in the database or the .env file unless `force` is set.
+pub fn add_host(name: &str, config: &HostConfig, write_env: bool, force: bool) -> Result<()> {
+    let name = name.to_lowercase();
+    if !is_valid_dns_label(&name) {
+        anyhow::bail!(
+            "Invalid host name '{}': use 1-63 letters, digits and '-', not starting or ending with '-'",
+            name
+        );
+    }
+    if config.ip.is_none() && config.tailscale.is_none() {
+        anyhow::bail!("Give --ip or --tailscale so halvor can reach '{}'", name);
+    }
+    if let Some(ref ip) = config.ip
+        && ip.parse::<std::net::IpAddr>().is_err()
     {
-        match get_host_config(&current_hostname) {
-            Ok(Some(retrieved)) => {
-                println!("[DEBUG] ✓ Verified: Host config retrieved from database");
-                println!("[DEBUG]   Retrieved hostname: {:?}", retrieved.hostname);
-                println!("[DEBUG]   Retrieved IP: {:?}", retrieved.ip);
-                println!("[DEBUG]   Retrieved tailscale: {:?}", retrieved.tailscale);
-            }
-            Ok(None) => {
-                eprintln!("[DEBUG] ⚠ Warning: Host config not found after storing");
+        anyhow::bail!("Invalid IP address '{}'", ip);
+    }
+    if let Some(ref tailscale) = config.tailscale
+        && !is_valid_dns_label(tailscale)
+    {
+        anyhow::bail!(
+            "Invalid Tailscale name '{}': expected a single DNS label (e.g. 'maple', not 'maple.ts.net')",
+            tailscale
+        );
+    }
+    if let Some(ref backup_path) = config.backup_path
+        && !std::path::Path::new(backup_path).is_absolute()
+    {
+        anyhow::bail!("Backup path '{}' must be absolute", backup_path);
+    }
+    if let Some(ref jump) = config.jump {
+        crate::config::validate_jump_host(jump)?;
+    }
+
+    let in_db = db::list_hosts()?
+        .iter()
+        .any(|host| host.eq_ignore_ascii_case(&name));
+    let in_env = load_env_file_config()
+        .map(|env| {
+            env.hosts
+                .keys()
+                .any(|host| host.eq_ignore_ascii_case(&name))
+        })
+        .unwrap_or(false);
+    if write_env {
+        env_file::ensure_env_file_not_encrypted(&crate::config::get_env_file_path()?)?;
+    }
+    if !force && (in_db || in_env) {
+        anyhow::bail!(
+            "Host '{}' already exists in {} (pass --force to overwrite it)",
+            name,
+            match (in_db, in_env) {
+                (true, true) => "the database and the .env file",
+                (true, false) => "the database",
+                _ => "the .env file",
             }
-            Err(e) => {
-                eprintln!("[DEBUG] ⚠ Error retrieving host config: {}", e);
+        );
+    }
+
+    store_host_config(&name, config)
+        .with_context(|| format!("Failed to store host config for '{}' in database", name))?;
+    if write_env {
+        let env_path = crate::config::get_env_file_path()?;
+        env_file::write_host_to_env_file(&env_path, &name, config)?;
+        info!(
+            "✓ Added host '{}' to the database and {}",
+            name,
+            env_path.display()
+        );
+    } else {
+        info!("✓ Added host '{}' to the database", name);
+        if in_env {
+            warn!(
+                "⚠ The .env file still has the old settings for '{}' (the database ones are used); pass --write-env to replace them",
+                name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Rename a host (`hal host rename`)
+/// The database records move in one transaction; with `write_env` the .env keys are renamed
+/// inside that transaction too, and the file is restored if the commit fails.
+pub fn rename_host(old: &str, new: &str, write_env: bool) -> Result<()> {
+    let new = new.to_lowercase();
+    if !is_valid_dns_label(&new) {
+        anyhow::bail!(
+            "Invalid host name '{}': use 1-63 letters, digits and '-', not starting or ending with '-'",
+            new
+        );
+    }
+
+    let db_hosts = db::list_hosts()?;
+    let env_config = load_env_file_config();
+    let env_hosts: Vec<String> = env_config
+        .as_ref()
+        .map(|env| env.hosts.keys().cloned().collect())
+        .unwrap_or_default();
+    let db_name = db_hosts
+        .iter()
+        .find(|h| h.eq_ignore_ascii_case(old))
+        .cloned();
+    let env_name = env_hosts
+        .iter()
+        .find(|h| h.eq_ignore_ascii_case(old))
+        .cloned();
+
+    if db_name.is_none() && env_name.is_none() {
+        anyhow::bail!("Host '{}' not found in the database or the .env file", old);
+    }
+    if old.eq_ignore_ascii_case(&new) {
+        anyhow::bail!("Host '{}' is already named '{}'", old, new);
+    }
+    if db_hosts.iter().any(|h| h.eq_ignore_ascii_case(&new)) {
+        anyhow::bail!("Host '{}' already exists in the database", new);
+    }
+    if env_hosts.iter().any(|h| h.eq_ignore_ascii_case(&new)) {
+        anyhow::bail!("Host '{}' already exists in the .env file", new);
+    }
+    if db_name.is_none() && !write_env {
+        anyhow::bail!(
+            "Host '{}' is only in the .env file; pass --write-env to rename it there",
+            old
+        );
+    }
+
+    let env_path = match env_name {
+        Some(_) if write_env => Some(crate::config::get_env_file_path()?),
+        _ => None,
+    };
+    if let Some(ref path) = env_path {
+        env_file::ensure_env_file_not_encrypted(path)?;
+    }
+    let original_env = match env_path {
+        Some(ref path) => Some(
+            std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read .env file: {}", path.display()))?,
+        ),
+        None => None,
+    };
+
+    let mut env_keys = 0;
+    let renamed = db::rename_host(db_name.as_deref().unwrap_or(old), &new, || {
+        if let Some(ref path) = env_path {
+            env_keys = env_file::rename_host_in_env_file(path, old, &new)?;
+        }
+        Ok(())
+    });
+    let renamed = match renamed {
+        Ok(renamed) => renamed,
+        Err(e) => {
+            if let (Some(path), Some(content)) = (&env_path, &original_env) {
+                std::fs::write(path, content)
+                    .with_context(|| format!("Failed to restore .env file: {}", path.display()))?;
             }
+            return Err(e);
         }
+    };
+
+    info!("✓ Renamed host '{}' to '{}'", old, new);
+    if renamed.host_info {
+        info!("  Database: host configuration");
+    }
+    if renamed.provision_events > 0 {
+        info!(
+            "  Database: {} provisioning record(s)",
+            renamed.provision_events
+        );
+    }
+    if renamed.encrypted_values > 0 {
+        info!(
+            "  Database: {} encrypted value(s)",
+            renamed.encrypted_values
+        );
+    }
+    if renamed.known_agents > 0 {
+        info!("  Database: {} known agent(s)", renamed.known_agents);
+    }
+    if let Some(ref path) = env_path {
+        info!("  {}: {} key(s)", path.display(), env_keys);
+    } else if env_name.is_some() {
+        warn!(
+            "⚠ The .env file still lists '{}' under its old name; pass --write-env to rename it there",
+            old
+        );
     }
 
-    println!();
-    println!("✓ Node '{}' configured successfully!", current_hostname);
-    println!("  Configuration saved to database");
-    println!();
+    if config_manager::get_default_host().is_some_and(|h| h.eq_ignore_ascii_case(old)) {
+        config_manager::set_default_host(Some(&new))?;
+    }
 
-    Ok(current_hostname)
+    // The hostname and Tailscale fields often repeat the host name; they describe the machine
+    // itself, so they are only changed when asked
+    let mut config = if renamed.host_info {
+        get_host_config(&new)?
+    } else {
+        None
+    }
+    .or_else(|| {
+        env_config
+            .as_ref()
+            .zip(env_name.as_ref())
+            .and_then(|(env, name)| env.hosts.get(name).cloned())
+    })
+    .unwrap_or_default();
+    let stale: Vec<(&str, &str)> = [
+        ("hostname", config.hostname.as_deref()),
+        ("tailscale", config.tailscale.as_deref()),
+    ]
+    .into_iter()
+    .filter(|(_, value)| value.is_some_and(|v| v.eq_ignore_ascii_case(old)))
+    .map(|(field, _)| {
+        (
+            field,
+            if field == "tailscale" {
+                "Tailscale name"
+            } else {
+                "hostname"
+            },
+        )
+    })
+    .collect();
+    if stale.is_empty() {
+        return Ok(());
+    }
+
+    for (_, label) in &stale {
+        warn!("⚠ The {} of '{}' is still '{}'", label, new, old);
+    }
+    let update = if io::stdin().is_terminal() {
+        print!("Change it to '{}' as well? [y/N]: ", new);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+    } else {
+        false
+    };
+    if !update {
+        for (field, _) in &stale {
+            info!(
+                "  Update it later with: hal config -H {} {} {}",
+                new, field, new
+            );
+        }
+        return Ok(());
+    }
+
+    for (field, _) in &stale {
+        match *field {
+            "tailscale" => config.tailscale = Some(new.clone()),
+            _ => config.hostname = Some(new.clone()),
+        }
+    }
+    if renamed.host_info {
+        store_host_config(&new, &config)
+            .with_context(|| format!("Failed to store host config for '{}' in database", new))?;
+    }
+    if let Some(ref path) = env_path {
+        env_file::write_host_to_env_file(path, &new, &config)?;
+    }
+    info!(
+        "✓ Updated the {} of '{}' (this only changes halvor's configuration, not the machine)",
+        stale
+            .iter()
+            .map(|(_, label)| *label)
+            .collect::<Vec<_>>()
+            .join(" and "),
+        new
+    );
+    Ok(())
 }
 
 /// Handle create config commands
@@ -1375,7 +2566,7 @@ pub fn handle_create_config(command: crate::commands::config::CreateConfigComman
                 "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
             );
             println!();
-            println!("⚠ App configuration creation not yet implemented");
+            warn!("⚠ App configuration creation not yet implemented");
         }
         crate::commands::config::CreateConfigCommands::Smb { server_name: _ } => {
             println!(
@@ -1386,7 +2577,7 @@ pub fn handle_create_config(command: crate::commands::config::CreateConfigComman
                 "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
             );
             println!();
-            println!("⚠ SMB configuration creation not yet implemented");
+            warn!("⚠ SMB configuration creation not yet implemented");
         }
         crate::commands::config::CreateConfigCommands::Ssh { hostname: _ } => {
             println!(
@@ -1397,7 +2588,7 @@ pub fn handle_create_config(command: crate::commands::config::CreateConfigComman
                 "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
             );
             println!();
-            println!("⚠ SSH configuration creation not yet implemented");
+            warn!("⚠ SSH configuration creation not yet implemented");
         }
     }
     Ok(())
@@ -1408,34 +2599,33 @@ pub fn handle_config_command(
     arg: Option<&str>,
     verbose: bool,
     db: bool,
+    json: bool,
     command: Option<&crate::commands::config::ConfigCommands>,
 ) -> Result<()> {
     use crate::commands::config::ConfigCommands;
 
-    // Known global commands that should not be treated as hostnames
-    let global_commands = [
-        "list",
-        "init",
-        "set-env",
-        "stable",
-        "experimental",
-        "create",
-        "env",
-        "db",
-        "backup",
-        "commit",
-        "delete",
-        "diff",
-    ];
+    let command_names = crate::commands::config::config_command_names();
 
     // If arg is provided and it's not a known command, treat it as a hostname
     if let Some(arg_str) = arg {
-        if !global_commands.contains(&arg_str.to_lowercase().as_str()) {
+        if !command_names.contains(&arg_str.to_lowercase()) {
             // This is a hostname
             let hostname = arg_str;
             match command {
                 None | Some(ConfigCommands::List) => {
-                    show_host_config(hostname)?;
+                    if json {
+                        let config = get_host_config(hostname)?
+                            .with_context(|| format!("Host '{}' not found", hostname))?;
+                        let mut value = serde_json::to_value(&config)?;
+                        if let Some(inventory) =
+                            crate::services::host::get_host_inventory(hostname)?
+                        {
+                            value["inventory"] = serde_json::to_value(&inventory)?;
+                        }
+                        println!("{}", serde_json::to_string_pretty(&value)?);
+                    } else {
+                        show_host_config(hostname)?;
+                    }
                 }
                 Some(ConfigCommands::Commit) => {
                     commit_host_config_to_db(hostname)?;
@@ -1458,13 +2648,25 @@ pub fn handle_config_command(
                 Some(ConfigCommands::BackupPath { value }) => {
                     set_host_field(hostname, "backup_path", &value)?;
                 }
+                Some(ConfigCommands::SshUser { value }) => {
+                    set_host_field(hostname, "ssh_user", value)?;
+                }
+                Some(ConfigCommands::SshPort { value }) => {
+                    set_host_field(hostname, "ssh_port", value)?;
+                }
+                Some(ConfigCommands::Jump { value }) => {
+                    set_host_field(hostname, "jump", value)?;
+                }
                 Some(ConfigCommands::SetBackup { hostname: _ }) => {
                     // This shouldn't happen when hostname is provided, but handle it
                     set_backup_location(Some(hostname))?;
                 }
-                Some(ConfigCommands::Diff) => {
+                Some(ConfigCommands::Edit) => {
+                    edit_host_config(hostname)?;
+                }
+                Some(ConfigCommands::Diff { .. }) => {
                     anyhow::bail!(
-                        "Diff command is global only. Use 'halvor config diff' to see all differences"
+                        "Diff command is global only. Use 'hal config diff' to see all differences"
                     );
                 }
                 _ => {
@@ -1477,43 +2679,34 @@ pub fn handle_config_command(
 
     // Handle global config commands
     // If arg is a known command, use it; otherwise use the subcommand
-    let cmd = if let Some(arg_str) = arg {
-        // Map string to command
-        match arg_str.to_lowercase().as_str() {
-            "list" => ConfigCommands::List,
-            "init" => ConfigCommands::Init,
-            "env" => ConfigCommands::Env,
-            "stable" => ConfigCommands::SetStable,
-            "experimental" => ConfigCommands::SetExperimental,
-            "commit" => ConfigCommands::Commit,
-            "backup" => ConfigCommands::Backup,
-            "diff" => ConfigCommands::Diff,
-            _ => {
-                // Use the subcommand if provided, otherwise default to Show
-                command.cloned().unwrap_or(ConfigCommands::List)
-            }
-        }
-    } else {
-        // Use the subcommand if provided, otherwise default to Show
-        command.cloned().unwrap_or(ConfigCommands::List)
-    };
+    let cmd = arg
+        .and_then(crate::commands::config::parse_config_command)
+        .or_else(|| command.cloned())
+        .unwrap_or(ConfigCommands::List);
 
     match cmd {
-        ConfigCommands::List => {
-            if db {
-                show_db_config(verbose)?;
-            } else {
-                show_current_config(verbose)?;
-            }
-        }
+        ConfigCommands::List => match (db, json) {
+            (true, true) => show_db_config_json()?,
+            (true, false) => show_db_config(verbose)?,
+            (false, true) => show_current_config_json(verbose)?,
+            (false, false) => show_current_config(verbose)?,
+        },
         ConfigCommands::Commit => {
             commit_all_to_db()?;
         }
         ConfigCommands::Backup => {
             backup_all_to_env_with_backup()?;
         }
-        ConfigCommands::Init => {
-            config_manager::init_config_interactive()?;
+        ConfigCommands::Init {
+            env_file,
+            channel,
+            non_interactive,
+        } => {
+            config_manager::init_config(&config_manager::InitOptions {
+                env_file: env_file.as_deref(),
+                channel,
+                non_interactive,
+            })?;
         }
         ConfigCommands::SetEnv { path } => {
             set_env_path(path.as_str())?;
@@ -1524,9 +2717,21 @@ pub fn handle_config_command(
         ConfigCommands::SetExperimental => {
             config_manager::set_release_channel(config_manager::ReleaseChannel::Experimental)?;
         }
+        ConfigCommands::SetDefaultHost { name, clear: _ } => {
+            set_default_host(name.as_deref())?;
+        }
+        ConfigCommands::SetPrefer { family, clear: _ } => {
+            config_manager::set_address_family(family)?;
+        }
+        ConfigCommands::SetUpdateInterval { hours, clear: _ } => {
+            config_manager::set_update_check_interval(hours)?;
+        }
         ConfigCommands::Create { command } => {
             handle_create_config(command)?;
         }
+        ConfigCommands::Profile { command } => {
+            handle_profile_command(command, json)?;
+        }
         ConfigCommands::Env => {
             create_example_env_file()?;
         }
@@ -1535,18 +2740,61 @@ pub fn handle_config_command(
         }
         ConfigCommands::Delete { .. } => {
             anyhow::bail!(
-                "Delete requires a hostname. Usage: halvor config <hostname> delete [--from-env]"
+                "Delete requires a hostname. Usage: hal config -H <hostname> delete [--from-env]"
             );
         }
-        ConfigCommands::Diff => {
-            show_config_diff()?;
+        ConfigCommands::Diff {
+            apply,
+            env_wins,
+            db_wins,
+        } => {
+            if apply {
+                let winner = if env_wins {
+                    Some(DiffWinner::Env)
+                } else if db_wins {
+                    Some(DiffWinner::Db)
+                } else {
+                    None
+                };
+                apply_config_diff(winner)?;
+            } else {
+                show_config_diff()?;
+            }
+        }
+        ConfigCommands::Validate => {
+            show_config_validation(json)?;
+        }
+        ConfigCommands::Encrypt => {
+            encrypt_env_file()?;
+        }
+        ConfigCommands::Decrypt => {
+            decrypt_env_file()?;
+        }
+        ConfigCommands::Set { key, value } => {
+            set_global_setting(&key, &value)?;
+        }
+        ConfigCommands::Unset { key } => {
+            unset_global_setting(&key)?;
+        }
+        ConfigCommands::Export {
+            file,
+            include_secrets,
+        } => {
+            super::snapshot::export_config(std::path::Path::new(&file), include_secrets)?;
+        }
+        ConfigCommands::Import { file, replace, .. } => {
+            super::snapshot::import_config(std::path::Path::new(&file), replace)?;
         }
-        ConfigCommands::Ip { .. }
+        ConfigCommands::Edit
+        | ConfigCommands::Ip { .. }
         | ConfigCommands::Hostname { .. }
         | ConfigCommands::Tailscale { .. }
-        | ConfigCommands::BackupPath { .. } => {
+        | ConfigCommands::BackupPath { .. }
+        | ConfigCommands::SshUser { .. }
+        | ConfigCommands::SshPort { .. }
+        | ConfigCommands::Jump { .. } => {
             anyhow::bail!(
-                "This command requires a hostname. Usage: halvor config <hostname> <command>"
+                "This command requires a hostname. Usage: hal config -H <hostname> <command>"
             );
         }
     }
@@ -1554,28 +2802,70 @@ pub fn handle_config_command(
     Ok(())
 }
 
+/// Handle `hal config profile` commands
+fn handle_profile_command(
+    command: crate::commands::config::ProfileCommands,
+    json: bool,
+) -> Result<()> {
+    use crate::commands::config::ProfileCommands;
+    match command {
+        ProfileCommands::Create {
+            name,
+            env_file,
+            channel,
+            db,
+        } => config_manager::create_profile(
+            &name,
+            &config_manager::ProfileOptions {
+                env_file: env_file.as_deref(),
+                channel,
+                db: db.as_deref(),
+            },
+        ),
+        ProfileCommands::Use { name } => config_manager::use_profile(&name),
+        ProfileCommands::List => config_manager::list_profiles(json),
+    }
+}
+
 /// Handle db commands
 pub fn handle_db_command(command: crate::commands::config::DbCommands) -> Result<()> {
     match command {
         crate::commands::config::DbCommands::Generate => {
             db::core::generator::generate_structs()?;
         }
-        crate::commands::config::DbCommands::Backup { path } => {
-            backup_database(path.as_deref())?;
-        }
-        crate::commands::config::DbCommands::Migrate { command } => {
+        crate::commands::config::DbCommands::Backup { path, encrypt } => {
+            backup_database(path.as_deref(), encrypt)?;
+        }
+        crate::commands::config::DbCommands::Migrate {
+            allow_checksum_mismatch,
+            plan,
+            no_backup,
+            command,
+        } => {
+            db::migrations::set_allow_checksum_mismatch(allow_checksum_mismatch);
+            db::set_pre_migration_backup(!no_backup);
             // Default to running all migrations if no subcommand provided
             match command {
+                None if plan => db::migrate::plan_migrations(false)?,
+                Some(crate::commands::config::MigrateCommands::Up) if plan => {
+                    db::migrate::plan_migrations(true)?
+                }
+                Some(_) if plan => {
+                    anyhow::bail!("--plan only applies to 'hal db migrate' and 'hal db migrate up'")
+                }
                 Some(cmd) => handle_migrate_command(cmd)?,
                 None => db::migrate::migrate_all()?,
             }
         }
-        crate::commands::config::DbCommands::Sync => {
-            sync_db_from_env()?;
+        crate::commands::config::DbCommands::Sync { plan, yes, force } => {
+            sync_db_from_env(plan, yes, force)?;
         }
         crate::commands::config::DbCommands::Restore => {
             restore_database()?;
         }
+        crate::commands::config::DbCommands::Vacuum { integrity_check } => {
+            vacuum_database(integrity_check)?;
+        }
     }
     Ok(())
 }
@@ -1586,7 +2876,10 @@ pub fn handle_migrate_command(command: crate::commands::config::MigrateCommands)
         crate::commands::config::MigrateCommands::Up => {
             db::migrate::migrate_up()?;
         }
-        crate::commands::config::MigrateCommands::Down => {
+        crate::commands::config::MigrateCommands::Down { to: Some(version) } => {
+            db::migrate::migrate_down_to(version)?;
+        }
+        crate::commands::config::MigrateCommands::Down { to: None } => {
             db::migrate::migrate_down()?;
         }
         crate::commands::config::MigrateCommands::List => {
@@ -1600,158 +2893,445 @@ pub fn handle_migrate_command(command: crate::commands::config::MigrateCommands)
     Ok(())
 }
 
-/// Sync environment file to database (load env values into DB, delete DB values not in env)
-pub fn sync_db_from_env() -> Result<()> {
-    use crate::db::generated::{settings, smb_servers};
-    use std::collections::HashSet;
-
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("Syncing .env file to database");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!();
+/// Changes `hal db sync` would make to bring the database in line with the .env file
+#[derive(Default)]
+pub struct SyncPlan {
+    pub hosts: SyncChanges<HostConfig>,
+    pub smb_servers: SyncChanges<crate::config::SmbServerConfig>,
+    pub settings: SyncChanges<String>,
+    /// Host fields changed in the database since the last sync that the .env would overwrite
+    pub conflicts: Vec<(String, Vec<HostFieldDiff>)>,
+    /// Hosts already in agreement whose recorded baseline is missing or out of date
+    pub baselines: Vec<(String, HostConfig)>,
+}
 
-    // Load .env config
-    let homelab_dir = find_homelab_dir()?;
-    let env_config = load_env_config(&homelab_dir)?;
+/// Host metadata key holding the last configuration the .env and the database agreed on
+const SYNC_BASELINE_METADATA_KEY: &str = "env_sync_baseline";
+
+/// Fields where both the .env and the database moved away from the last synced value
+///
+/// Without a baseline (hosts never synced), any field the database has set differently counts.
+fn host_sync_conflicts(
+    env: &HostConfig,
+    db: &HostConfig,
+    baseline: Option<&HostConfig>,
+) -> Vec<HostFieldDiff> {
+    let reference = baseline.cloned().unwrap_or_default();
+    let changed_fields = |config: &HostConfig| -> Vec<&'static str> {
+        diff_host_configs(config, &reference)
+            .into_iter()
+            .map(|diff| diff.field)
+            .collect()
+    };
+    let db_changed = changed_fields(db);
+    let env_changed = changed_fields(env);
+    diff_host_configs(env, db)
+        .into_iter()
+        .filter(|diff| {
+            db_changed.contains(&diff.field)
+                && (baseline.is_none() || env_changed.contains(&diff.field))
+        })
+        .collect()
+}
 
-    // Get all DB hosts
-    let db_hosts = list_hosts()?;
-    let db_hosts_set: HashSet<String> = db_hosts.iter().cloned().collect();
-    let env_hosts_set: HashSet<String> = env_config.hosts.keys().cloned().collect();
+/// Added, changed and removed entries for one kind of record
+pub struct SyncChanges<T> {
+    pub added: Vec<(String, T)>,
+    pub updated: Vec<(String, T)>,
+    pub deleted: Vec<String>,
+    pub unchanged: usize,
+}
 
-    // Add/update hosts from .env
-    let mut added = 0;
-    let mut updated = 0;
-    for (hostname, config) in &env_config.hosts {
-        let exists = db_hosts_set.contains(hostname);
-        store_host_config(hostname, config)?;
-        if exists {
-            updated += 1;
-        } else {
-            added += 1;
+impl<T> Default for SyncChanges<T> {
+    fn default() -> Self {
+        Self {
+            added: Vec::new(),
+            updated: Vec::new(),
+            deleted: Vec::new(),
+            unchanged: 0,
         }
     }
+}
 
-    // Delete hosts from DB that aren't in .env
-    let mut deleted = 0;
-    for hostname in &db_hosts {
-        if !env_hosts_set.contains(hostname) {
-            delete_host_config_service(hostname)?;
-            deleted += 1;
+impl<T> SyncChanges<T> {
+    /// Diff `desired` against `current`, deleting current entries that aren't desired
+    fn diff(
+        current: &std::collections::HashMap<String, T>,
+        desired: impl IntoIterator<Item = (String, T)>,
+        same: impl Fn(&T, &T) -> bool,
+        deletable: impl Fn(&str) -> bool,
+    ) -> Self {
+        let mut changes = Self::default();
+        let mut desired_names = std::collections::HashSet::new();
+        for (name, value) in desired {
+            desired_names.insert(name.clone());
+            match current.get(&name) {
+                None => changes.added.push((name, value)),
+                Some(existing) if same(existing, &value) => changes.unchanged += 1,
+                Some(_) => changes.updated.push((name, value)),
+            }
         }
+        changes.deleted = current
+            .keys()
+            .filter(|name| !desired_names.contains(*name) && deletable(name))
+            .cloned()
+            .collect();
+        changes.added.sort_by(|a, b| a.0.cmp(&b.0));
+        changes.updated.sort_by(|a, b| a.0.cmp(&b.0));
+        changes.deleted.sort();
+        changes
     }
 
-    println!("✓ Sync complete:");
-    println!("  Added: {}", added);
-    println!("  Updated: {}", updated);
-    println!("  Deleted: {}", deleted);
-    println!();
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
 
-    // Sync SMB servers
-    let db_smb_servers = smb_servers::list_smb_servers().unwrap_or_default();
-    let db_smb_set: HashSet<String> = db_smb_servers.iter().cloned().collect();
-    let env_smb_set: HashSet<String> = env_config.smb_servers.keys().cloned().collect();
-
-    let mut smb_added = 0;
-    let mut smb_updated = 0;
-    for (name, cfg) in &env_config.smb_servers {
-        let exists = db_smb_set.contains(name);
-        smb_servers::store_smb_server(name, cfg)?;
-        if exists {
-            smb_updated += 1;
-        } else {
-            smb_added += 1;
+    fn print(&self, label: &str) {
+        println!(
+            "{}: {} to add, {} to update, {} to delete, {} unchanged",
+            label,
+            self.added.len(),
+            self.updated.len(),
+            self.deleted.len(),
+            self.unchanged
+        );
+        for (name, _) in &self.added {
+            println!("  + {}", name);
+        }
+        for (name, _) in &self.updated {
+            println!("  ~ {}", name);
+        }
+        for name in &self.deleted {
+            println!("  - {}", name);
         }
     }
-    let mut smb_deleted = 0;
-    for name in &db_smb_servers {
-        if !env_smb_set.contains(name) {
-            smb_servers::delete_smb_server(name)?;
-            smb_deleted += 1;
+}
+
+/// Global settings managed by `hal db sync` and `hal config set`
+pub const MANAGED_SETTING_KEYS: &[&str] = &[
+    "TAILNET_BASE",
+    "TAILNET_TLD",
+    "ACME_EMAIL",
+    "PIA_USERNAME",
+    "PIA_PASSWORD",
+    "DOWNLOADS_PATH",
+    "MOVIES_PATH",
+    "TV_PATH",
+    "MOVIES_4K_PATH",
+    "MUSIC_PATH",
+    "NGINX_PROXY_MANAGER_URL",
+    "NGINX_PROXY_MANAGER_USERNAME",
+    "NGINX_PROXY_MANAGER_PASSWORD",
+];
+
+/// Settings whose values are credentials
+pub fn is_secret_setting(key: &str) -> bool {
+    let key = key.to_uppercase();
+    ["PASSWORD", "SECRET", "TOKEN", "_KEY"]
+        .iter()
+        .any(|marker| key.contains(marker))
+}
+
+/// Settings that `hal db sync` manages, with their values from the environment
+fn env_managed_settings(tailnet_base: &str) -> Vec<(&'static str, String)> {
+    MANAGED_SETTING_KEYS
+        .iter()
+        .map(|&key| {
+            let value = match key {
+                "TAILNET_BASE" => tailnet_base.to_string(),
+                "TAILNET_TLD" => std::env::var("TAILNET_TLD")
+                    .or_else(|_| std::env::var("TLD"))
+                    .unwrap_or_default(),
+                _ => std::env::var(key).unwrap_or_default(),
+            };
+            (key, value)
+        })
+        .collect()
+}
+
+/// Look up a managed setting key case-insensitively, listing the valid keys when it isn't one
+fn managed_setting_key(key: &str) -> Result<&'static str> {
+    MANAGED_SETTING_KEYS
+        .iter()
+        .find(|managed| managed.eq_ignore_ascii_case(key))
+        .copied()
+        .with_context(|| {
+            format!(
+                "Unknown setting '{}'. Valid settings:\n  {}",
+                key,
+                MANAGED_SETTING_KEYS.join("\n  ")
+            )
+        })
+}
+
+/// Store a global setting in the database (`hal config set`)
+pub fn set_global_setting(key: &str, value: &str) -> Result<()> {
+    let key = managed_setting_key(key)?;
+    if value.is_empty() {
+        anyhow::bail!(
+            "Value for {} is empty. Use `hal config unset {}` to remove it",
+            key,
+            key
+        );
+    }
+    settings::set_setting(key, value)?;
+    let shown = if is_secret_setting(key) {
+        "********"
+    } else {
+        value
+    };
+    println!("✓ Set {} = {}", key, shown);
+    Ok(())
+}
+
+/// Remove a global setting from the database (`hal config unset`)
+pub fn unset_global_setting(key: &str) -> Result<()> {
+    let key = managed_setting_key(key)?;
+    if settings::get_setting(key)?.is_none() {
+        println!("{} is not set", key);
+        return Ok(());
+    }
+    settings::delete_by_key(key)?;
+    println!("✓ Unset {}", key);
+    Ok(())
+}
+
+impl SyncPlan {
+    /// Compare the loaded .env configuration with the database without changing anything
+    pub fn build(env_config: &EnvConfig) -> Result<Self> {
+        use crate::db::generated::smb_servers;
+        use std::collections::HashMap;
+
+        let mut db_hosts = HashMap::new();
+        for hostname in list_hosts()? {
+            if let Some(config) = get_host_config(&hostname)? {
+                db_hosts.insert(hostname, config);
+            }
+        }
+        let hosts = SyncChanges::diff(
+            &db_hosts,
+            env_config
+                .hosts
+                .iter()
+                .map(|(name, config)| (name.clone(), config.clone())),
+            |db, env| diff_host_configs(db, env).is_empty(),
+            |_| true,
+        );
+
+        let mut conflicts = Vec::new();
+        let mut baselines = Vec::new();
+        for (hostname, env) in &env_config.hosts {
+            let Some(db) = db_hosts.get(hostname) else {
+                continue;
+            };
+            let baseline =
+                crate::services::host::get_host_metadata(hostname, SYNC_BASELINE_METADATA_KEY)?
+                    .and_then(|value| serde_json::from_value::<HostConfig>(value).ok());
+            if diff_host_configs(env, db).is_empty() {
+                if baseline.is_none_or(|baseline| !diff_host_configs(env, &baseline).is_empty()) {
+                    baselines.push((hostname.clone(), env.clone()));
+                }
+                continue;
+            }
+            let diffs = host_sync_conflicts(env, db, baseline.as_ref());
+            if !diffs.is_empty() {
+                conflicts.push((hostname.clone(), diffs));
+            }
         }
+        conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut db_smb_servers = HashMap::new();
+        for name in smb_servers::list_smb_servers().unwrap_or_default() {
+            if let Some(config) = smb_servers::get_smb_server(&name)? {
+                db_smb_servers.insert(name, config);
+            }
+        }
+        let smb_servers = SyncChanges::diff(
+            &db_smb_servers,
+            env_config
+                .smb_servers
+                .iter()
+                .map(|(name, config)| (name.clone(), config.clone())),
+            |db, env| serde_json::to_value(db).ok() == serde_json::to_value(env).ok(),
+            |_| true,
+        );
+
+        // Settings are never deleted: keys missing from .env may have been stored with
+        // `hal config set`. Empty env values count as absent
+        let managed = env_managed_settings(&env_config._tailnet_base);
+        let db_settings: HashMap<String, String> = settings::select_many("1=1", &[])
+            .unwrap_or_default()
+            .into_iter()
+            .map(|row| (row.key, row.value))
+            .collect();
+        let settings = SyncChanges::diff(
+            &db_settings,
+            managed
+                .iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(key, value)| (key.to_string(), value.clone())),
+            |db, env| db == env,
+            |_| false,
+        );
+
+        Ok(Self {
+            hosts,
+            smb_servers,
+            settings,
+            conflicts,
+            baselines,
+        })
     }
-    println!("SMB servers synced:");
-    println!("  Added: {}", smb_added);
-    println!("  Updated: {}", smb_updated);
-    println!("  Deleted: {}", smb_deleted);
-    println!();
 
-    // Sync settings (tailnet, ACME, PIA, media paths, NPM)
-    let tailnet_tld = std::env::var("TAILNET_TLD")
-        .or_else(|_| std::env::var("TLD"))
-        .unwrap_or_default();
-    let acme_email = std::env::var("ACME_EMAIL").unwrap_or_default();
-    let pia_username = std::env::var("PIA_USERNAME").unwrap_or_default();
-    let pia_password = std::env::var("PIA_PASSWORD").unwrap_or_default();
-    let downloads_path = std::env::var("DOWNLOADS_PATH").unwrap_or_default();
-    let movies_path = std::env::var("MOVIES_PATH").unwrap_or_default();
-    let tv_path = std::env::var("TV_PATH").unwrap_or_default();
-    let movies_4k_path = std::env::var("MOVIES_4K_PATH").unwrap_or_default();
-    let music_path = std::env::var("MUSIC_PATH").unwrap_or_default();
-    let npm_url = std::env::var("NGINX_PROXY_MANAGER_URL").unwrap_or_default();
-    let npm_user = std::env::var("NGINX_PROXY_MANAGER_USERNAME").unwrap_or_default();
-    let npm_pass = std::env::var("NGINX_PROXY_MANAGER_PASSWORD").unwrap_or_default();
-
-    let setting_keys: Vec<(&str, String)> = vec![
-        ("TAILNET_BASE", env_config._tailnet_base.clone()),
-        ("TAILNET_TLD", tailnet_tld),
-        ("ACME_EMAIL", acme_email),
-        ("PIA_USERNAME", pia_username),
-        ("PIA_PASSWORD", pia_password),
-        ("DOWNLOADS_PATH", downloads_path),
-        ("MOVIES_PATH", movies_path),
-        ("TV_PATH", tv_path),
-        ("MOVIES_4K_PATH", movies_4k_path),
-        ("MUSIC_PATH", music_path),
-        ("NGINX_PROXY_MANAGER_URL", npm_url),
-        ("NGINX_PROXY_MANAGER_USERNAME", npm_user),
-        ("NGINX_PROXY_MANAGER_PASSWORD", npm_pass),
-    ];
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty() && self.smb_servers.is_empty() && self.settings.is_empty()
+    }
 
-    let mut settings_added = 0;
-    let mut settings_updated = 0;
-    let mut settings_deleted = 0;
-
-    // Upsert env settings (skip empty values)
-    for (key, val) in &setting_keys {
-        if !val.is_empty() {
-            let existing = settings::get_setting(key).unwrap_or(None);
-            settings::set_setting(key, val)?;
-            if existing.is_some() {
-                settings_updated += 1;
-            } else {
-                settings_added += 1;
+    pub fn deletions(&self) -> usize {
+        self.hosts.deleted.len() + self.smb_servers.deleted.len() + self.settings.deleted.len()
+    }
+
+    /// Print every change by name (setting values are never shown)
+    pub fn print(&self) {
+        self.hosts.print("Hosts");
+        self.smb_servers.print("SMB servers");
+        self.settings.print("Settings");
+    }
+
+    /// Print host fields that were changed on both sides since the last sync
+    pub fn print_conflicts(&self) {
+        println!(
+            "⚠ {} host(s) changed in both the .env file and the database since the last sync:",
+            self.conflicts.len()
+        );
+        for (hostname, diffs) in &self.conflicts {
+            for diff in diffs {
+                println!("  {} - {}:", hostname, diff.field);
+                println!("    .env: {}", diff.left.as_deref().unwrap_or("(not set)"));
+                println!("    db:   {}", diff.right.as_deref().unwrap_or("(not set)"));
             }
         }
     }
 
-    // Delete DB settings not present in env (only for keys we manage)
-    if let Ok(all) = settings::select_many("1=1", &[]) {
-        let managed: HashSet<&str> = setting_keys.iter().map(|(k, _)| *k).collect();
-        let env_present: HashSet<&str> = setting_keys
+    /// Write the planned changes to the database
+    pub fn apply(&self) -> Result<()> {
+        use crate::db::generated::smb_servers;
+
+        for (hostname, config) in self.hosts.added.iter().chain(&self.hosts.updated) {
+            store_host_config(hostname, config)?;
+            crate::services::host::set_host_metadata(
+                hostname,
+                SYNC_BASELINE_METADATA_KEY,
+                serde_json::to_value(config)?,
+            )?;
+        }
+        for (hostname, config) in &self.baselines {
+            crate::services::host::set_host_metadata(
+                hostname,
+                SYNC_BASELINE_METADATA_KEY,
+                serde_json::to_value(config)?,
+            )?;
+        }
+        for hostname in &self.hosts.deleted {
+            delete_host_config_service(hostname)?;
+        }
+
+        for (name, config) in self
+            .smb_servers
+            .added
             .iter()
-            .filter(|(_, v)| !v.is_empty())
-            .map(|(k, _)| *k)
-            .collect();
-        for row in all {
-            if let Some(k) = row.key.as_deref() {
-                if managed.contains(k) && !env_present.contains(k) {
-                    settings::delete_by_key(k)?;
-                    settings_deleted += 1;
-                }
-            }
+            .chain(&self.smb_servers.updated)
+        {
+            smb_servers::store_smb_server(name, config)?;
         }
+        for name in &self.smb_servers.deleted {
+            smb_servers::delete_smb_server(name)?;
+        }
+
+        for (key, value) in self.settings.added.iter().chain(&self.settings.updated) {
+            settings::set_setting(key, value)?;
+        }
+        for key in &self.settings.deleted {
+            settings::delete_by_key(key)?;
+        }
+        Ok(())
     }
+}
 
-    println!("Settings synced:");
-    println!("  Added: {}", settings_added);
-    println!("  Updated: {}", settings_updated);
-    println!("  Deleted: {}", settings_deleted);
+/// Sync environment file to database (load env values into DB, delete DB values not in env)
+///
+/// The plan is always printed first. `plan_only` stops there; otherwise the changes are applied
+/// after confirmation, or straight away with `yes`. Host fields edited in the database since the
+/// last sync are conflicts and are only overwritten with `force`.
+pub fn sync_db_from_env(plan_only: bool, yes: bool, force: bool) -> Result<()> {
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Syncing .env file to database");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
+    let homelab_dir = find_homelab_dir()?;
+    let env_config = load_env_config(&homelab_dir)?;
+    let plan = SyncPlan::build(&env_config)?;
+    plan.print();
+    println!();
+
+    if plan.is_empty() {
+        if !plan_only {
+            plan.apply()?;
+        }
+        println!("✓ Database already matches the .env file");
+        return Ok(());
+    }
+    if !plan.conflicts.is_empty() {
+        plan.print_conflicts();
+        println!();
+    }
+    if plan_only {
+        println!("Plan only - no changes were made. Run `hal db sync` to apply them.");
+        return Ok(());
+    }
+    if !plan.conflicts.is_empty() && !force {
+        anyhow::bail!(
+            "Refusing to overwrite database changes for {} host(s). Update the .env file to match \
+             (see `hal config diff`), or rerun with --force to let the .env win.",
+            plan.conflicts.len()
+        );
+    }
+
+    if !yes {
+        if plan.deletions() > 0 {
+            println!(
+                "⚠ {} entr{} will be deleted from the database",
+                plan.deletions(),
+                if plan.deletions() == 1 { "y" } else { "ies" }
+            );
+        }
+        print!("Apply these changes? [y/N]: ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let response = input.trim().to_lowercase();
+        if response != "y" && response != "yes" {
+            println!("Cancelled - no changes were made.");
+            return Ok(());
+        }
+    }
+
+    plan.apply()?;
+    println!("✓ Sync complete");
     Ok(())
 }
 
+/// Whether a database backup file was written with `--encrypt`
+fn is_encrypted_backup(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 8];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|()| crate::utils::crypto::is_encrypted_stream(&header))
+}
+
 /// Restore database from backup
 pub fn restore_database() -> Result<()> {
     use glob::glob;
@@ -1764,31 +3344,46 @@ pub fn restore_database() -> Result<()> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
-    // Find all backup files
+    // Find all backup files (plain and encrypted)
+    const BACKUP_PATTERNS: [&str; 2] = ["halvor_backup_*.db", "halvor_backup_*.db.enc"];
     let current_dir = std::env::current_dir()?;
-    let backup_pattern = current_dir.join("halvor_backup_*.db");
 
     let mut backups: Vec<PathBuf> = Vec::new();
-    if let Ok(entries) = glob(backup_pattern.to_str().unwrap()) {
-        for entry in entries.flatten() {
-            backups.push(entry);
+    for pattern in BACKUP_PATTERNS {
+        let backup_pattern = current_dir.join(pattern);
+        if let Ok(entries) = glob(backup_pattern.to_str().unwrap()) {
+            for entry in entries.flatten() {
+                backups.push(entry);
+            }
+        }
+    }
+
+    // Automatic backups taken before migrations sit next to the database
+    if let Some(db_dir) = db::get_db_path()?.parent() {
+        let backup_pattern = db_dir.join("halvor_pre_migration_*.db");
+        if let Ok(entries) = glob(backup_pattern.to_str().unwrap()) {
+            backups.extend(entries.flatten());
         }
     }
 
     // Also check homelab directory
     if let Ok(homelab_dir) = find_homelab_dir() {
-        let backup_pattern = homelab_dir.join("halvor_backup_*.db");
-        if let Ok(entries) = glob(backup_pattern.to_str().unwrap()) {
-            for entry in entries.flatten() {
-                if !backups.contains(&entry) {
-                    backups.push(entry);
+        for pattern in BACKUP_PATTERNS {
+            let backup_pattern = homelab_dir.join(pattern);
+            if let Ok(entries) = glob(backup_pattern.to_str().unwrap()) {
+                for entry in entries.flatten() {
+                    if !backups.contains(&entry) {
+                        backups.push(entry);
+                    }
                 }
             }
         }
     }
 
     if backups.is_empty() {
-        anyhow::bail!("No backup files found. Look for files matching 'halvor_backup_*.db'");
+        anyhow::bail!(
+            "No backup files found. Look for files matching 'halvor_backup_*.db' or 'halvor_pre_migration_*.db'"
+        );
     }
 
     // Sort by modification time (newest first)
@@ -1808,10 +3403,15 @@ pub fn restore_database() -> Result<()> {
             if let Ok(modified) = metadata.modified() {
                 let datetime: chrono::DateTime<chrono::Utc> = modified.into();
                 println!(
-                    "  [{}] {} ({})",
+                    "  [{}] {} ({}){}",
                     i + 1,
                     backup.display(),
-                    datetime.format("%Y-%m-%d %H:%M:%S")
+                    datetime.format("%Y-%m-%d %H:%M:%S"),
+                    if is_encrypted_backup(backup) {
+                        " [encrypted]"
+                    } else {
+                        ""
+                    }
                 );
             } else {
                 println!("  [{}] {}", i + 1, backup.display());
@@ -1841,6 +3441,37 @@ pub fn restore_database() -> Result<()> {
     let backup_path = &backups[idx - 1];
     let db_path = db::get_db_path()?;
 
+    // Decrypt first so a wrong passphrase fails before the current database is touched
+    let decrypted = if is_encrypted_backup(backup_path) {
+        let passphrase = crate::utils::crypto::get_backup_passphrase(false)?;
+        let decrypted = db_path.with_extension("db.decrypted");
+        // The plaintext database is only ever readable by the owner; a leftover file from an
+        // interrupted restore is replaced rather than reused with its old permissions
+        let _ = fs::remove_file(&decrypted);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let result = fs::File::open(backup_path)
+            .with_context(|| format!("Failed to open {}", backup_path.display()))
+            .and_then(|mut input| {
+                let mut output = options
+                    .open(&decrypted)
+                    .with_context(|| format!("Failed to create {}", decrypted.display()))?;
+                crate::utils::crypto::decrypt_stream(&mut input, &mut output, &passphrase)
+            });
+        if let Err(e) = result {
+            let _ = fs::remove_file(&decrypted);
+            return Err(e.context(format!("Failed to decrypt {}", backup_path.display())));
+        }
+        Some(decrypted)
+    } else {
+        None
+    };
+
     // Backup current database before restore
     if db_path.exists() {
         use chrono::Utc;
@@ -1855,23 +3486,194 @@ pub fn restore_database() -> Result<()> {
                 current_backup.display()
             )
         })?;
-        println!(
+        info!(
             "✓ Backed up current database to {}",
             current_backup.display()
         );
     }
 
     // Restore from backup
-    fs::copy(backup_path, &db_path).with_context(|| {
-        format!(
-            "Failed to restore database from {} to {}",
-            backup_path.display(),
-            db_path.display()
-        )
-    })?;
+    match &decrypted {
+        Some(decrypted) => fs::rename(decrypted, &db_path).with_context(|| {
+            format!(
+                "Failed to restore database from {} to {}",
+                decrypted.display(),
+                db_path.display()
+            )
+        })?,
+        None => {
+            fs::copy(backup_path, &db_path).with_context(|| {
+                format!(
+                    "Failed to restore database from {} to {}",
+                    backup_path.display(),
+                    db_path.display()
+                )
+            })?;
+        }
+    }
 
-    println!("✓ Database restored from {}", backup_path.display());
-    println!();
+    info!("✓ Database restored from {}", backup_path.display());
+    info!("");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn host(ip: &str, ssh_user: Option<&str>) -> HostConfig {
+        HostConfig {
+            ip: Some(ip.to_string()),
+            ssh_user: ssh_user.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn fields(diffs: &[HostFieldDiff]) -> Vec<&'static str> {
+        diffs.iter().map(|diff| diff.field).collect()
+    }
+
+    #[test]
+    fn diff_sorts_added_updated_and_deleted() {
+        let current: HashMap<String, String> = [("b", "1"), ("c", "2"), ("d", "3")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let desired = [("e", "5"), ("a", "0"), ("b", "1"), ("c", "changed")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()));
+
+        let changes = SyncChanges::diff(&current, desired, |a, b| a == b, |_| true);
+
+        let names = |entries: &[(String, String)]| -> Vec<String> {
+            entries.iter().map(|(name, _)| name.clone()).collect()
+        };
+        assert_eq!(names(&changes.added), ["a", "e"]);
+        assert_eq!(names(&changes.updated), ["c"]);
+        assert_eq!(changes.deleted, ["d"]);
+        assert_eq!(changes.unchanged, 1);
+        assert!(!changes.is_empty());
+    }
+
+    #[test]
+    fn diff_only_deletes_deletable_entries() {
+        let current: HashMap<String, String> = [("ACME_EMAIL", "a@b"), ("OTHER", "x")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        let changes = SyncChanges::diff(
+            &current,
+            std::iter::empty(),
+            |a, b| a == b,
+            |key| MANAGED_SETTING_KEYS.contains(&key),
+        );
+
+        assert_eq!(changes.deleted, ["ACME_EMAIL"]);
+        assert!(changes.added.is_empty() && changes.updated.is_empty());
+    }
+
+    #[test]
+    fn diff_of_identical_sets_is_empty() {
+        let current: HashMap<String, String> = [("a".to_string(), "1".to_string())].into();
+        let changes = SyncChanges::diff(
+            &current,
+            [("a".to_string(), "1".to_string())],
+            |a, b| a == b,
+            |_| true,
+        );
+        assert!(changes.is_empty());
+        assert_eq!(changes.unchanged, 1);
+    }
+
+    #[test]
+    fn conflicts_without_baseline_count_every_db_field() {
+        let env = host("10.0.0.1", Some("alice"));
+        let db = host("10.0.0.2", Some("alice"));
+        assert_eq!(fields(&host_sync_conflicts(&env, &db, None)), ["IP"]);
+    }
+
+    #[test]
+    fn conflicts_need_both_sides_to_move_from_baseline() {
+        let baseline = host("10.0.0.1", Some("alice"));
+        // Only the database changed the user; only the .env changed the IP
+        let env = host("10.0.0.9", Some("alice"));
+        let db = host("10.0.0.1", Some("bob"));
+        assert!(host_sync_conflicts(&env, &db, Some(&baseline)).is_empty());
+
+        // Both sides changed the user to different values
+        let env = host("10.0.0.1", Some("carol"));
+        assert_eq!(
+            fields(&host_sync_conflicts(&env, &db, Some(&baseline))),
+            ["SSH user"]
+        );
+    }
+
+    #[test]
+    fn conflicts_ignore_fields_only_set_in_env() {
+        let env = HostConfig {
+            backup_path: Some("/backups".to_string()),
+            ..host("10.0.0.1", None)
+        };
+        let db = host("10.0.0.1", None);
+        assert!(host_sync_conflicts(&env, &db, None).is_empty());
+    }
+
+    #[test]
+    fn secret_settings_are_detected_case_insensitively() {
+        assert!(is_secret_setting("PIA_PASSWORD"));
+        assert!(is_secret_setting("api_token"));
+        assert!(!is_secret_setting("ACME_EMAIL"));
+    }
+
+    #[test]
+    fn reconciling_changes_only_the_decided_fields() {
+        let env = HostConfig {
+            jump: Some("bastion".to_string()),
+            ..host("10.0.0.1", Some("skey"))
+        };
+        let db = HostConfig {
+            ssh_port: Some(2222),
+            ..host("10.0.0.2", Some("admin"))
+        };
+        let diffs: Vec<ConfigFieldDiff> = diff_host_configs(&env, &db)
+            .into_iter()
+            .map(|field| ConfigFieldDiff {
+                host: "maple".to_string(),
+                field: field.field,
+                env: field.left,
+                db: field.right,
+            })
+            .collect();
+        let decision = |name: &str, winner| {
+            let field = diffs.iter().find(|f| f.field == name).unwrap();
+            (field, winner)
+        };
+
+        // IP from .env, SSH port from the database; SSH user and jump host skipped
+        let (new_env, new_db) = reconcile_host(
+            &env,
+            &db,
+            &[
+                decision("IP", DiffWinner::Env),
+                decision("SSH port", DiffWinner::Db),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(new_env.ip.as_deref(), Some("10.0.0.1"));
+        assert_eq!(new_env.ssh_port, Some(2222));
+        assert_eq!(new_env.ssh_user.as_deref(), Some("skey"));
+        assert_eq!(new_env.jump.as_deref(), Some("bastion"));
+        assert_eq!(new_db.ip.as_deref(), Some("10.0.0.1"));
+        assert_eq!(new_db.ssh_port, Some(2222));
+        assert_eq!(new_db.ssh_user.as_deref(), Some("admin"));
+        assert_eq!(new_db.jump, None);
+        assert_eq!(
+            fields(&diff_host_configs(&new_env, &new_db)),
+            ["SSH user", "Jump host"]
+        );
+    }
+}
@@ -0,0 +1,254 @@
+// Portable configuration snapshots (`hal config export` / `hal config import`)
+use crate::config::service::is_secret_setting;
+use crate::config::{HostConfig, SmbServerConfig};
+use crate::db;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Bumped whenever the snapshot layout changes incompatibly
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 1;
+
+/// Stands in for secrets in snapshots exported without --include-secrets
+const REDACTED: &str = "<redacted>";
+
+/// Hosts, SMB servers and settings from the database, in a human-editable form
+#[derive(Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub secrets_included: bool,
+    #[serde(default)]
+    pub hosts: BTreeMap<String, HostConfig>,
+    #[serde(default)]
+    pub smb_servers: BTreeMap<String, SmbServerConfig>,
+    #[serde(default)]
+    pub settings: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SnapshotFormat {
+    Json,
+    Toml,
+}
+
+impl SnapshotFormat {
+    /// TOML for `.toml` files, JSON otherwise
+    fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => SnapshotFormat::Toml,
+            _ => SnapshotFormat::Json,
+        }
+    }
+}
+
+/// Read hosts, SMB servers and settings from the database
+pub fn build_snapshot(include_secrets: bool) -> Result<ConfigSnapshot> {
+    let mut hosts = BTreeMap::new();
+    for hostname in db::list_hosts()? {
+        if let Some(config) = db::get_host_config(&hostname)? {
+            hosts.insert(hostname, config);
+        }
+    }
+
+    let mut smb_servers = BTreeMap::new();
+    for server_name in db::list_smb_servers()? {
+        if let Some(mut config) = db::get_smb_server(&server_name)? {
+            if !include_secrets && config.password.is_some() {
+                config.password = Some(REDACTED.to_string());
+            }
+            smb_servers.insert(server_name, config);
+        }
+    }
+
+    let settings = db::settings::select_many("1=1", &[])?
+        .into_iter()
+        .map(|row| {
+            let value = if !include_secrets && is_secret_setting(&row.key) {
+                REDACTED.to_string()
+            } else {
+                row.value
+            };
+            (row.key, value)
+        })
+        .collect();
+
+    Ok(ConfigSnapshot {
+        schema_version: SNAPSHOT_SCHEMA_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        secrets_included: include_secrets,
+        hosts,
+        smb_servers,
+        settings,
+    })
+}
+
+/// Write the database configuration to `path` (JSON, or TOML for .toml files)
+pub fn export_config(path: &Path, include_secrets: bool) -> Result<()> {
+    let snapshot = build_snapshot(include_secrets)?;
+    let content = match SnapshotFormat::for_path(path) {
+        SnapshotFormat::Json => serde_json::to_string_pretty(&snapshot)? + "\n",
+        SnapshotFormat::Toml => {
+            toml::to_string_pretty(&snapshot).context("Failed to serialize snapshot as TOML")?
+        }
+    };
+
+    if include_secrets {
+        super::service::write_private_file(path, &content)?;
+    } else {
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    println!("✓ Exported configuration to {}", path.display());
+    println!(
+        "  {} host(s), {} SMB server(s), {} setting(s)",
+        snapshot.hosts.len(),
+        snapshot.smb_servers.len(),
+        snapshot.settings.len()
+    );
+    if include_secrets {
+        println!("  ⚠ Secrets are included in plaintext - keep this file private");
+    } else {
+        println!(
+            "  Secrets are shown as {} (use --include-secrets to export them)",
+            REDACTED
+        );
+    }
+    Ok(())
+}
+
+/// Parse a snapshot, checking its schema version before anything else
+pub fn parse_snapshot(path: &Path) -> Result<ConfigSnapshot> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let value: serde_json::Value = match SnapshotFormat::for_path(path) {
+        SnapshotFormat::Json => serde_json::from_str(&content)
+            .with_context(|| format!("{} is not valid JSON", path.display()))?,
+        SnapshotFormat::Toml => toml::from_str(&content)
+            .with_context(|| format!("{} is not valid TOML", path.display()))?,
+    };
+
+    let version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .with_context(|| {
+            format!(
+                "{} has no schema_version - is it a file from `hal config export`?",
+                path.display()
+            )
+        })?;
+    if version != SNAPSHOT_SCHEMA_VERSION as u64 {
+        anyhow::bail!(
+            "{} uses snapshot schema version {}, but this version of halvor reads version {}{}",
+            path.display(),
+            version,
+            SNAPSHOT_SCHEMA_VERSION,
+            if version > SNAPSHOT_SCHEMA_VERSION as u64 {
+                " (update halvor with `hal update`)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    serde_json::from_value(value).with_context(|| format!("Invalid snapshot in {}", path.display()))
+}
+
+/// Load a snapshot into the database
+///
+/// Merge mode adds and updates entries; replace mode also deletes hosts, SMB servers and settings
+/// that aren't in the snapshot. Redacted secrets keep their current values.
+pub fn import_config(path: &Path, replace: bool) -> Result<()> {
+    use crate::db::generated::{host_info, settings, smb_servers};
+
+    let snapshot = parse_snapshot(path)?;
+    let mut kept_secrets = 0;
+
+    // Read everything first so the writes below run in one transaction
+    let mut removed = Vec::new();
+    let mut removed_hosts = Vec::new();
+    let mut removed_servers = Vec::new();
+    let mut removed_settings = Vec::new();
+    if replace {
+        for hostname in db::list_hosts()? {
+            if !snapshot.hosts.contains_key(&hostname) {
+                removed.push(format!("  - Removed host {}", hostname));
+                removed_hosts.push(hostname);
+            }
+        }
+        for server_name in db::list_smb_servers()? {
+            if !snapshot.smb_servers.contains_key(&server_name) {
+                removed.push(format!("  - Removed SMB server {}", server_name));
+                removed_servers.push(server_name);
+            }
+        }
+        for row in db::settings::select_many("1=1", &[])? {
+            if !snapshot.settings.contains_key(&row.key) {
+                removed.push(format!("  - Removed setting {}", row.key));
+                removed_settings.push(row.key);
+            }
+        }
+    }
+
+    let mut smb_servers_to_store = Vec::new();
+    for (server_name, config) in &snapshot.smb_servers {
+        let mut config = config.clone();
+        if config.password.as_deref() == Some(REDACTED) {
+            config.password = db::get_smb_server(server_name)?.and_then(|c| c.password);
+            kept_secrets += 1;
+        }
+        smb_servers_to_store.push((server_name, config));
+    }
+
+    let conn = db::get_connection()?;
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to start import transaction")?;
+
+    for hostname in &removed_hosts {
+        host_info::delete_host_config_with(&tx, hostname)?;
+    }
+    for server_name in &removed_servers {
+        smb_servers::delete_smb_server_with(&tx, server_name)?;
+    }
+    for key in &removed_settings {
+        settings::delete_setting_with(&tx, key)?;
+    }
+
+    for (hostname, config) in &snapshot.hosts {
+        host_info::store_host_config_with(&tx, hostname, config)?;
+    }
+    for (server_name, config) in &smb_servers_to_store {
+        smb_servers::store_smb_server_with(&tx, server_name, config)?;
+    }
+    for (key, value) in &snapshot.settings {
+        if value == REDACTED {
+            kept_secrets += 1;
+            continue;
+        }
+        settings::set_setting_with(&tx, key, value)?;
+    }
+
+    tx.commit().context("Failed to commit import")?;
+
+    for line in &removed {
+        println!("{}", line);
+    }
+    println!(
+        "✓ Imported {} host(s), {} SMB server(s), {} setting(s) from {} ({})",
+        snapshot.hosts.len(),
+        snapshot.smb_servers.len(),
+        snapshot.settings.len(),
+        path.display(),
+        if replace { "replace" } else { "merge" }
+    );
+    if kept_secrets > 0 {
+        println!(
+            "  {} redacted secret(s) were left unchanged in the database",
+            kept_secrets
+        );
+    }
+    Ok(())
+}
@@ -306,7 +306,7 @@ fn sql_type_to_rust(sql_type: &str, not_null: bool, column_name: &str) -> String
     // Special handling for boolean-like INTEGER fields
     let rust_type = if sql_type == "INTEGER" {
         match column_name {
-            "tailscale_installed" | "portainer_installed" => "i32",
+            "tailscale_installed" | "portainer_installed" | "docker_installed" | "success" => "i32",
             _ => "i64",
         }
     } else {
@@ -489,7 +489,8 @@ pub fn delete_by_id(id: &str) -> Result<usize> {{
     ));
 
     // Generate delete functions for unique constraints
-    for constraint in unique_constraints {
+    // (the primary key already has delete_by_id above)
+    for constraint in unique_constraints.iter().filter(|c| c.columns != ["id"]) {
         if constraint.columns.len() == 1 {
             // Single column unique constraint
             let col_name = &constraint.columns[0];
@@ -508,6 +509,13 @@ pub fn delete_by_{}({}_value: &str) -> Result<usize> {{
         }
     }
 
+    // Generate typed lookup helpers
+    ops.push_str(&generate_find_helpers(
+        struct_name,
+        data_fields,
+        unique_constraints,
+    ));
+
     // Generate data struct
     ops.insert_str(
         0,
@@ -538,3 +546,109 @@ fn generate_field_updates(data_fields: &[&ColumnInfo]) -> String {
     }
     updates
 }
+
+/// Parameter type for a lookup on a column (borrowed where possible)
+fn lookup_param_type(rust_type: &str) -> String {
+    match rust_type
+        .strip_prefix("Option<")
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        Some(inner) => format!("Option<{}>", lookup_param_type(inner)),
+        None => match rust_type {
+            "String" => "&str".to_string(),
+            "Vec<u8>" => "&[u8]".to_string(),
+            other => other.to_string(),
+        },
+    }
+}
+
+/// Generate `find_by_*` helpers that build parameterized queries from the schema columns
+/// Single-column and composite unique constraints return one row; other columns return all matches.
+/// Nullable columns compare with `IS` so `None` matches NULL.
+fn generate_find_helpers(
+    struct_name: &str,
+    data_fields: &[&ColumnInfo],
+    unique_constraints: &[UniqueConstraint],
+) -> String {
+    let is_unique = |columns: &[String]| unique_constraints.iter().any(|c| c.columns == columns);
+
+    // Each lookup is a list of columns; every data column gets one, plus composite unique keys
+    let mut lookups: Vec<Vec<&ColumnInfo>> = data_fields.iter().map(|f| vec![*f]).collect();
+    for constraint in unique_constraints.iter().filter(|c| c.columns.len() > 1) {
+        let columns: Option<Vec<&ColumnInfo>> = constraint
+            .columns
+            .iter()
+            .map(|name| data_fields.iter().find(|f| &f.name == name).copied())
+            .collect();
+        if let Some(columns) = columns {
+            lookups.push(columns);
+        }
+    }
+
+    let mut helpers = String::new();
+    for columns in lookups {
+        let names: Vec<String> = columns.iter().map(|c| to_field_name(&c.name)).collect();
+        let params = columns
+            .iter()
+            .zip(&names)
+            .map(|(col, name)| {
+                let rust_type = sql_type_to_rust(&col.sql_type, col.not_null, &col.name);
+                format!("{}: {}", name, lookup_param_type(&rust_type))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let where_clause = columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                let op = if col.not_null { "=" } else { "IS" };
+                format!("{} {} ?{}", col.name, op, i + 1)
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let where_params = names
+            .iter()
+            .map(|name| format!("&{} as &dyn rusqlite::types::ToSql", name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let column_names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+
+        let (doc, return_type, select_fn) = if is_unique(&column_names) {
+            (
+                format!("Find the {} record", struct_name),
+                "Option<Self>",
+                "select_one",
+            )
+        } else {
+            (
+                format!("Find all {} records", struct_name),
+                "Vec<Self>",
+                "select_many",
+            )
+        };
+
+        helpers.push_str(&format!(
+            r#"
+    /// {} by {}
+    pub fn find_by_{}({}) -> Result<{}> {{
+        {}("{}", &[{}])
+    }}
+"#,
+            doc,
+            column_names.join(" and "),
+            names.join("_and_"),
+            params,
+            return_type,
+            select_fn,
+            where_clause,
+            where_params
+        ));
+    }
+
+    format!(
+        r#"
+impl {} {{{}}}
+"#,
+        struct_name, helpers
+    )
+}
@@ -131,6 +131,40 @@ pub fn delete_by_id(id: &str) -> Result<usize> {
     )
 }
 
+impl EncryptedEnvDataRow {
+    /// Find all EncryptedEnvDataRow records by hostname
+    pub fn find_by_hostname(hostname: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "hostname IS ?1",
+            &[&hostname as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all EncryptedEnvDataRow records by key
+    pub fn find_by_key(key: &str) -> Result<Vec<Self>> {
+        select_many("key = ?1", &[&key as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all EncryptedEnvDataRow records by encrypted_value
+    pub fn find_by_encrypted_value(encrypted_value: &str) -> Result<Vec<Self>> {
+        select_many(
+            "encrypted_value = ?1",
+            &[&encrypted_value as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find the EncryptedEnvDataRow record by hostname and key
+    pub fn find_by_hostname_and_key(hostname: Option<&str>, key: &str) -> Result<Option<Self>> {
+        select_one(
+            "hostname IS ?1 AND key = ?2",
+            &[
+                &hostname as &dyn rusqlite::types::ToSql,
+                &key as &dyn rusqlite::types::ToSql,
+            ],
+        )
+    }
+}
+
 use crate::utils::crypto;
 use anyhow::Context;
 
@@ -154,22 +188,13 @@ pub fn store_encrypted_env(hostname: Option<&str>, key: &str, value: &str) -> Re
 
 /// Get encrypted environment variable
 pub fn get_encrypted_env(hostname: Option<&str>, key: &str) -> Result<Option<String>> {
-    let row = select_one(
-        "hostname IS ?1 AND key = ?2",
-        &[
-            &hostname as &dyn rusqlite::types::ToSql,
-            &key as &dyn rusqlite::types::ToSql,
-        ],
-    )?;
+    let row = EncryptedEnvDataRow::find_by_hostname_and_key(hostname, key)?;
     Ok(row.and_then(|r| crypto::decrypt(&r.encrypted_value).ok()))
 }
 
 /// Get all encrypted environment variables for a hostname (or global if None)
 pub fn get_all_encrypted_envs(hostname: Option<&str>) -> Result<Vec<(String, String)>> {
-    let rows = select_many(
-        "hostname IS ?1",
-        &[&hostname as &dyn rusqlite::types::ToSql],
-    )?;
+    let rows = EncryptedEnvDataRow::find_by_hostname(hostname)?;
     let mut envs = Vec::new();
     for row in rows {
         if let Ok(decrypted) = crypto::decrypt(&row.encrypted_value) {
@@ -186,6 +211,33 @@ pub fn export_encrypted_data() -> Result<Vec<u8>> {
     Ok(json.into_bytes())
 }
 
+/// Re-encrypt every stored value in one transaction
+/// `reencrypt` gets each ciphertext and returns its replacement; any error rolls everything back
+pub fn reencrypt_all(reencrypt: impl Fn(&str) -> Result<String>) -> Result<usize> {
+    let conn = db::get_connection()?;
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to start re-encryption transaction")?;
+
+    let rows: Vec<(String, String)> = {
+        let mut stmt = tx.prepare("SELECT id, encrypted_value FROM encrypted_env_data")?;
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    for (id, encrypted_value) in &rows {
+        let replacement = reencrypt(encrypted_value)?;
+        tx.execute(
+            "UPDATE encrypted_env_data SET encrypted_value = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![replacement, now, id],
+        )?;
+    }
+
+    tx.commit().context("Failed to commit re-encrypted data")?;
+    Ok(rows.len())
+}
+
 /// Import encrypted data from sync
 pub fn import_encrypted_data(data: &[u8]) -> Result<()> {
     let rows: Vec<EncryptedEnvDataRow> =
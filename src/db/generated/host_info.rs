@@ -10,16 +10,19 @@ use anyhow::Result;
 #[derive(Debug, Clone)]
 pub struct HostInfoRow {
     pub id: String,
-    pub hostname: Option<String>,
+    pub hostname: String,
     pub last_provisioned_at: Option<i64>,
     pub docker_version: Option<String>,
     pub tailscale_installed: Option<i32>,
     pub portainer_installed: Option<i32>,
     pub metadata: Option<String>,
     pub ip: Option<String>,
+    pub hostname_field: Option<String>,
     pub tailscale: Option<String>,
     pub backup_path: Option<String>,
-    pub hostname_field: Option<String>,
+    pub ssh_user: Option<String>,
+    pub ssh_port: Option<i64>,
+    pub jump: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -36,25 +39,31 @@ impl_table_auto!(
         portainer_installed,
         metadata,
         ip,
+        hostname_field,
         tailscale,
         backup_path,
-        hostname_field
+        ssh_user,
+        ssh_port,
+        jump
     ]
 );
 
 /// Data structure for HostInfoRow operations (excludes id, created_at, updated_at)
 #[derive(Debug, Clone)]
 pub struct HostInfoRowData {
-    pub hostname: Option<String>,
+    pub hostname: String,
     pub last_provisioned_at: Option<i64>,
     pub docker_version: Option<String>,
     pub tailscale_installed: Option<i32>,
     pub portainer_installed: Option<i32>,
     pub metadata: Option<String>,
     pub ip: Option<String>,
+    pub hostname_field: Option<String>,
     pub tailscale: Option<String>,
     pub backup_path: Option<String>,
-    pub hostname_field: Option<String>,
+    pub ssh_user: Option<String>,
+    pub ssh_port: Option<i64>,
+    pub jump: Option<String>,
 }
 
 /// Insert a new HostInfoRow record
@@ -70,9 +79,12 @@ pub fn insert_one(data: HostInfoRowData) -> Result<String> {
         portainer_installed: data.portainer_installed.clone(),
         metadata: data.metadata.clone(),
         ip: data.ip.clone(),
+        hostname_field: data.hostname_field.clone(),
         tailscale: data.tailscale.clone(),
         backup_path: data.backup_path.clone(),
-        hostname_field: data.hostname_field.clone(),
+        ssh_user: data.ssh_user.clone(),
+        ssh_port: data.ssh_port,
+        jump: data.jump.clone(),
 
         created_at: 0, // Set automatically
         updated_at: 0, // Set automatically
@@ -94,9 +106,12 @@ pub fn insert_many(data_vec: Vec<HostInfoRowData>) -> Result<Vec<String>> {
             portainer_installed: data.portainer_installed.clone(),
             metadata: data.metadata.clone(),
             ip: data.ip.clone(),
+            hostname_field: data.hostname_field.clone(),
             tailscale: data.tailscale.clone(),
             backup_path: data.backup_path.clone(),
-            hostname_field: data.hostname_field.clone(),
+            ssh_user: data.ssh_user.clone(),
+            ssh_port: data.ssh_port,
+            jump: data.jump.clone(),
 
             created_at: 0, // Set automatically
             updated_at: 0, // Set automatically
@@ -114,20 +129,33 @@ pub fn upsert_one(
     data: HostInfoRowData,
 ) -> Result<String> {
     let conn = db::get_connection()?;
-    DbTable::<HostInfoRow>::upsert_by(&conn, where_clause, where_params, |existing| {
+    upsert_one_with(&conn, where_clause, where_params, data)
+}
+
+/// Upsert a HostInfoRow record on an open connection (e.g. inside a transaction)
+pub fn upsert_one_with(
+    conn: &rusqlite::Connection,
+    where_clause: &str,
+    where_params: &[&dyn rusqlite::types::ToSql],
+    data: HostInfoRowData,
+) -> Result<String> {
+    DbTable::<HostInfoRow>::upsert_by(conn, where_clause, where_params, |existing| {
         let mut row = existing.cloned().unwrap_or_else(|| {
             let mut r = HostInfoRow {
                 id: String::new(), // Set automatically
-                hostname: None,
+                hostname: String::new(),
                 last_provisioned_at: None,
                 docker_version: None,
                 tailscale_installed: None,
                 portainer_installed: None,
                 metadata: None,
                 ip: None,
+                hostname_field: None,
                 tailscale: None,
                 backup_path: None,
-                hostname_field: None,
+                ssh_user: None,
+                ssh_port: None,
+                jump: None,
 
                 created_at: 0, // Set automatically
                 updated_at: 0, // Set automatically
@@ -140,9 +168,12 @@ pub fn upsert_one(
             r.portainer_installed = data.portainer_installed.clone();
             r.metadata = data.metadata.clone();
             r.ip = data.ip.clone();
+            r.hostname_field = data.hostname_field.clone();
             r.tailscale = data.tailscale.clone();
             r.backup_path = data.backup_path.clone();
-            r.hostname_field = data.hostname_field.clone();
+            r.ssh_user = data.ssh_user.clone();
+            r.ssh_port = data.ssh_port;
+            r.jump = data.jump.clone();
 
             r
         });
@@ -154,9 +185,12 @@ pub fn upsert_one(
         row.portainer_installed = data.portainer_installed;
         row.metadata = data.metadata;
         row.ip = data.ip;
+        row.hostname_field = data.hostname_field;
         row.tailscale = data.tailscale;
         row.backup_path = data.backup_path;
-        row.hostname_field = data.hostname_field;
+        row.ssh_user = data.ssh_user;
+        row.ssh_port = data.ssh_port;
+        row.jump = data.jump;
 
         row
     })
@@ -196,6 +230,103 @@ pub fn delete_by_hostname(hostname_value: &str) -> Result<usize> {
     )
 }
 
+impl HostInfoRow {
+    /// Find the HostInfoRow record by hostname
+    pub fn find_by_hostname(hostname: &str) -> Result<Option<Self>> {
+        select_one("hostname = ?1", &[&hostname as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all HostInfoRow records by last_provisioned_at
+    pub fn find_by_last_provisioned_at(last_provisioned_at: Option<i64>) -> Result<Vec<Self>> {
+        select_many(
+            "last_provisioned_at IS ?1",
+            &[&last_provisioned_at as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by docker_version
+    pub fn find_by_docker_version(docker_version: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "docker_version IS ?1",
+            &[&docker_version as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by tailscale_installed
+    pub fn find_by_tailscale_installed(tailscale_installed: Option<i32>) -> Result<Vec<Self>> {
+        select_many(
+            "tailscale_installed IS ?1",
+            &[&tailscale_installed as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by portainer_installed
+    pub fn find_by_portainer_installed(portainer_installed: Option<i32>) -> Result<Vec<Self>> {
+        select_many(
+            "portainer_installed IS ?1",
+            &[&portainer_installed as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by metadata
+    pub fn find_by_metadata(metadata: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "metadata IS ?1",
+            &[&metadata as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by ip
+    pub fn find_by_ip(ip: Option<&str>) -> Result<Vec<Self>> {
+        select_many("ip IS ?1", &[&ip as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all HostInfoRow records by hostname_field
+    pub fn find_by_hostname_field(hostname_field: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "hostname_field IS ?1",
+            &[&hostname_field as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by tailscale
+    pub fn find_by_tailscale(tailscale: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "tailscale IS ?1",
+            &[&tailscale as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by backup_path
+    pub fn find_by_backup_path(backup_path: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "backup_path IS ?1",
+            &[&backup_path as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by ssh_user
+    pub fn find_by_ssh_user(ssh_user: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "ssh_user IS ?1",
+            &[&ssh_user as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by ssh_port
+    pub fn find_by_ssh_port(ssh_port: Option<i64>) -> Result<Vec<Self>> {
+        select_many(
+            "ssh_port IS ?1",
+            &[&ssh_port as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all HostInfoRow records by jump
+    pub fn find_by_jump(jump: Option<&str>) -> Result<Vec<Self>> {
+        select_many("jump IS ?1", &[&jump as &dyn rusqlite::types::ToSql])
+    }
+}
+
 use crate::config;
 use chrono;
 
@@ -211,7 +342,7 @@ pub fn store_host_info(
         "hostname = ?1",
         &[&hostname as &dyn rusqlite::types::ToSql],
         HostInfoRowData {
-            hostname: Some(hostname.to_string()),
+            hostname: hostname.to_string(),
             last_provisioned_at: Some(chrono::Utc::now().timestamp()),
             docker_version: docker_version.map(|s| s.to_string()),
             tailscale_installed: Some(tailscale_installed as i32),
@@ -221,16 +352,50 @@ pub fn store_host_info(
             hostname_field: None,
             tailscale: None,
             backup_path: None,
+            ssh_user: None,
+            ssh_port: None,
+            jump: None,
         },
     )?;
     Ok(())
 }
 
+/// Store a host's metadata, leaving the rest of its record as it is
+pub fn store_host_metadata(hostname: &str, metadata: &str) -> Result<()> {
+    if HostInfoRow::find_by_hostname(hostname)?.is_none() {
+        upsert_one(
+            "hostname = ?1",
+            &[&hostname as &dyn rusqlite::types::ToSql],
+            HostInfoRowData {
+                hostname: hostname.to_string(),
+                last_provisioned_at: None,
+                docker_version: None,
+                tailscale_installed: None,
+                portainer_installed: None,
+                metadata: Some(metadata.to_string()),
+                ip: None,
+                hostname_field: None,
+                tailscale: None,
+                backup_path: None,
+                ssh_user: None,
+                ssh_port: None,
+                jump: None,
+            },
+        )?;
+        return Ok(());
+    }
+    db::get_connection()?.execute(
+        "UPDATE host_info SET metadata = ?2, updated_at = ?3 WHERE hostname = ?1",
+        rusqlite::params![hostname, metadata, chrono::Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
 /// Get host provisioning information
 pub fn get_host_info(
     hostname: &str,
 ) -> Result<Option<(Option<i64>, Option<String>, bool, bool, Option<String>)>> {
-    let row = select_one("hostname = ?1", &[&hostname as &dyn rusqlite::types::ToSql])?;
+    let row = HostInfoRow::find_by_hostname(hostname)?;
     Ok(row.map(|r| {
         (
             r.last_provisioned_at,
@@ -245,7 +410,7 @@ pub fn get_host_info(
 /// List all known hosts
 pub fn list_hosts() -> Result<Vec<String>> {
     let rows = select_many("1=1", &[])?;
-    let mut hostnames: Vec<String> = rows.into_iter().filter_map(|r| r.hostname).collect();
+    let mut hostnames: Vec<String> = rows.into_iter().map(|r| r.hostname).collect();
     hostnames.sort();
     Ok(hostnames)
 }
@@ -257,23 +422,40 @@ impl From<HostInfoRow> for config::HostConfig {
             hostname: row.hostname_field,
             tailscale: row.tailscale,
             backup_path: row.backup_path,
+            ssh_user: row.ssh_user,
+            // A port outside 1-65535 can only come from editing the database by hand
+            ssh_port: row
+                .ssh_port
+                .and_then(|port| u16::try_from(port).ok())
+                .filter(|port| *port > 0),
+            jump: row.jump,
         }
     }
 }
 
 /// Get host configuration from database
 pub fn get_host_config(hostname: &str) -> Result<Option<config::HostConfig>> {
-    let row = select_one("hostname = ?1", &[&hostname as &dyn rusqlite::types::ToSql])?;
+    let row = HostInfoRow::find_by_hostname(hostname)?;
     Ok(row.map(|r| r.into()))
 }
 
 /// Store host configuration in database
 pub fn store_host_config(hostname: &str, config: &config::HostConfig) -> Result<()> {
-    upsert_one(
+    store_host_config_with(&db::get_connection()?, hostname, config)
+}
+
+/// Store host configuration on an open connection (e.g. inside a transaction)
+pub fn store_host_config_with(
+    conn: &rusqlite::Connection,
+    hostname: &str,
+    config: &config::HostConfig,
+) -> Result<()> {
+    upsert_one_with(
+        conn,
         "hostname = ?1",
         &[&hostname as &dyn rusqlite::types::ToSql],
         HostInfoRowData {
-            hostname: Some(hostname.to_string()),
+            hostname: hostname.to_string(),
             last_provisioned_at: Some(chrono::Utc::now().timestamp()),
             docker_version: None,
             tailscale_installed: Some(0),
@@ -283,13 +465,86 @@ pub fn store_host_config(hostname: &str, config: &config::HostConfig) -> Result<
             hostname_field: config.hostname.clone(),
             tailscale: config.tailscale.clone(),
             backup_path: config.backup_path.clone(),
+            ssh_user: config.ssh_user.clone(),
+            ssh_port: config.ssh_port.map(i64::from),
+            jump: config.jump.clone(),
         },
     )?;
     Ok(())
 }
 
+/// Rows moved by `rename_host`
+#[derive(Debug, Default)]
+pub struct HostRename {
+    /// Whether the host had a host_info record (config and provisioning info)
+    pub host_info: bool,
+    pub provision_events: usize,
+    pub encrypted_values: usize,
+    /// Discovered agents listed under the old name (the agent reports its own name on next contact)
+    pub known_agents: usize,
+}
+
+/// Move everything stored under `old` to `new` in one transaction: the host_info record,
+/// provisioning history, per-host encrypted env data and known agents
+/// `before_commit` runs last inside the transaction; if it fails nothing is changed.
+pub fn rename_host(
+    old: &str,
+    new: &str,
+    before_commit: impl FnOnce() -> Result<()>,
+) -> Result<HostRename> {
+    use anyhow::Context;
+
+    let conn = db::get_connection()?;
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to start rename transaction")?;
+
+    let taken: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM host_info WHERE hostname = ?1)",
+        [new],
+        |row| row.get(0),
+    )?;
+    if taken {
+        anyhow::bail!("Host '{}' already exists in the database", new);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let rename = HostRename {
+        host_info: tx.execute(
+            "UPDATE host_info SET hostname = ?2, updated_at = ?3 WHERE hostname = ?1",
+            rusqlite::params![old, new, now],
+        )? > 0,
+        provision_events: tx.execute(
+            "UPDATE provision_history SET hostname = ?2, updated_at = ?3 WHERE hostname = ?1",
+            rusqlite::params![old, new, now],
+        )?,
+        encrypted_values: tx.execute(
+            "UPDATE encrypted_env_data SET hostname = ?2, updated_at = ?3 WHERE hostname = ?1",
+            rusqlite::params![old, new, now],
+        )?,
+        known_agents: tx.execute(
+            "UPDATE known_agents SET hostname = ?2, updated_at = ?3 WHERE hostname = ?1",
+            rusqlite::params![old, new, now],
+        )?,
+    };
+
+    before_commit()?;
+    tx.commit().context("Failed to commit host rename")?;
+    Ok(rename)
+}
+
 /// Delete host configuration from database
 pub fn delete_host_config(hostname: &str) -> Result<()> {
     delete_by_hostname(hostname)?;
     Ok(())
 }
+
+/// Delete host configuration on an open connection (e.g. inside a transaction)
+pub fn delete_host_config_with(conn: &rusqlite::Connection, hostname: &str) -> Result<()> {
+    DbTable::<HostInfoRow>::delete_many(
+        conn,
+        "hostname = ?1",
+        &[&hostname as &dyn rusqlite::types::ToSql],
+    )?;
+    Ok(())
+}
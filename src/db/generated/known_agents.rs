@@ -0,0 +1,342 @@
+// Auto-generated from database schema
+// This file is generated - do not edit manually
+// Run `halvor db generate` to regenerate
+
+use crate::db;
+use crate::db::core::table::DbTable;
+use crate::impl_table_auto;
+use anyhow::Result;
+
+#[derive(Debug, Clone)]
+pub struct KnownAgentsRow {
+    pub id: String,
+    pub address: String,
+    pub hostname: String,
+    pub local_ip: Option<String>,
+    pub tailscale_ip: Option<String>,
+    pub tailscale_hostname: Option<String>,
+    pub agent_port: i64,
+    pub host_info: Option<String>,
+    pub last_seen: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Automatically implement Table trait from struct definition
+impl_table_auto!(
+    KnownAgentsRow,
+    "known_agents",
+    [
+        address,
+        hostname,
+        local_ip,
+        tailscale_ip,
+        tailscale_hostname,
+        agent_port,
+        host_info,
+        last_seen
+    ]
+);
+
+/// Data structure for KnownAgentsRow operations (excludes id, created_at, updated_at)
+#[derive(Debug, Clone)]
+pub struct KnownAgentsRowData {
+    pub address: String,
+    pub hostname: String,
+    pub local_ip: Option<String>,
+    pub tailscale_ip: Option<String>,
+    pub tailscale_hostname: Option<String>,
+    pub agent_port: i64,
+    pub host_info: Option<String>,
+    pub last_seen: i64,
+}
+
+/// Insert a new KnownAgentsRow record
+/// Only data fields are required - id, created_at, and updated_at are set automatically
+pub fn insert_one(data: KnownAgentsRowData) -> Result<String> {
+    let conn = db::get_connection()?;
+    let row = KnownAgentsRow {
+        id: String::new(), // Set automatically
+        address: data.address.clone(),
+        hostname: data.hostname.clone(),
+        local_ip: data.local_ip.clone(),
+        tailscale_ip: data.tailscale_ip.clone(),
+        tailscale_hostname: data.tailscale_hostname.clone(),
+        agent_port: data.agent_port.clone(),
+        host_info: data.host_info.clone(),
+        last_seen: data.last_seen.clone(),
+
+        created_at: 0, // Set automatically
+        updated_at: 0, // Set automatically
+    };
+    DbTable::<KnownAgentsRow>::insert(&conn, &row)
+}
+
+/// Insert multiple KnownAgentsRow records
+pub fn insert_many(data_vec: Vec<KnownAgentsRowData>) -> Result<Vec<String>> {
+    let conn = db::get_connection()?;
+    let mut ids = Vec::new();
+    for data in data_vec {
+        let row = KnownAgentsRow {
+            id: String::new(), // Set automatically
+            address: data.address.clone(),
+            hostname: data.hostname.clone(),
+            local_ip: data.local_ip.clone(),
+            tailscale_ip: data.tailscale_ip.clone(),
+            tailscale_hostname: data.tailscale_hostname.clone(),
+            agent_port: data.agent_port.clone(),
+            host_info: data.host_info.clone(),
+            last_seen: data.last_seen.clone(),
+
+            created_at: 0, // Set automatically
+            updated_at: 0, // Set automatically
+        };
+        ids.push(DbTable::<KnownAgentsRow>::insert(&conn, &row)?);
+    }
+    Ok(ids)
+}
+
+/// Upsert a KnownAgentsRow record (insert if new, update if exists)
+/// Only data fields are required - id, created_at, and updated_at are handled automatically
+pub fn upsert_one(
+    where_clause: &str,
+    where_params: &[&dyn rusqlite::types::ToSql],
+    data: KnownAgentsRowData,
+) -> Result<String> {
+    let conn = db::get_connection()?;
+    DbTable::<KnownAgentsRow>::upsert_by(&conn, where_clause, where_params, |existing| {
+        let mut row = existing.cloned().unwrap_or_else(|| {
+            let mut r = KnownAgentsRow {
+                id: String::new(), // Set automatically
+                address: String::new(),
+                hostname: String::new(),
+                local_ip: None,
+                tailscale_ip: None,
+                tailscale_hostname: None,
+                agent_port: 0,
+                host_info: None,
+                last_seen: 0,
+
+                created_at: 0, // Set automatically
+                updated_at: 0, // Set automatically
+            };
+            // Set initial values from data
+            r.address = data.address.clone();
+            r.hostname = data.hostname.clone();
+            r.local_ip = data.local_ip.clone();
+            r.tailscale_ip = data.tailscale_ip.clone();
+            r.tailscale_hostname = data.tailscale_hostname.clone();
+            r.agent_port = data.agent_port.clone();
+            r.host_info = data.host_info.clone();
+            r.last_seen = data.last_seen.clone();
+
+            r
+        });
+        // Update only the data fields
+        row.address = data.address;
+        row.hostname = data.hostname;
+        row.local_ip = data.local_ip;
+        row.tailscale_ip = data.tailscale_ip;
+        row.tailscale_hostname = data.tailscale_hostname;
+        row.agent_port = data.agent_port;
+        row.host_info = data.host_info;
+        row.last_seen = data.last_seen;
+
+        row
+    })
+}
+
+/// Select one KnownAgentsRow record
+pub fn select_one(
+    where_clause: &str,
+    params: &[&dyn rusqlite::types::ToSql],
+) -> Result<Option<KnownAgentsRow>> {
+    let conn = db::get_connection()?;
+    DbTable::<KnownAgentsRow>::select_one(&conn, where_clause, params)
+}
+
+/// Select many KnownAgentsRow records
+pub fn select_many(
+    where_clause: &str,
+    params: &[&dyn rusqlite::types::ToSql],
+) -> Result<Vec<KnownAgentsRow>> {
+    let conn = db::get_connection()?;
+    DbTable::<KnownAgentsRow>::select_many(&conn, where_clause, params)
+}
+
+/// Delete KnownAgentsRow record by primary key (id)
+pub fn delete_by_id(id: &str) -> Result<usize> {
+    let conn = db::get_connection()?;
+    DbTable::<KnownAgentsRow>::delete_many(&conn, "id = ?1", &[&id as &dyn rusqlite::types::ToSql])
+}
+
+/// Delete KnownAgentsRow record by unique key: address
+pub fn delete_by_address(address_value: &str) -> Result<usize> {
+    let conn = db::get_connection()?;
+    DbTable::<KnownAgentsRow>::delete_many(
+        &conn,
+        "address = ?1",
+        &[&address_value as &dyn rusqlite::types::ToSql],
+    )
+}
+
+impl KnownAgentsRow {
+    /// Find the KnownAgentsRow record by address
+    pub fn find_by_address(address: &str) -> Result<Option<Self>> {
+        select_one("address = ?1", &[&address as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all KnownAgentsRow records by hostname
+    pub fn find_by_hostname(hostname: &str) -> Result<Vec<Self>> {
+        select_many("hostname = ?1", &[&hostname as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all KnownAgentsRow records by local_ip
+    pub fn find_by_local_ip(local_ip: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "local_ip IS ?1",
+            &[&local_ip as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all KnownAgentsRow records by tailscale_ip
+    pub fn find_by_tailscale_ip(tailscale_ip: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "tailscale_ip IS ?1",
+            &[&tailscale_ip as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all KnownAgentsRow records by tailscale_hostname
+    pub fn find_by_tailscale_hostname(tailscale_hostname: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "tailscale_hostname IS ?1",
+            &[&tailscale_hostname as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all KnownAgentsRow records by agent_port
+    pub fn find_by_agent_port(agent_port: i64) -> Result<Vec<Self>> {
+        select_many(
+            "agent_port = ?1",
+            &[&agent_port as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all KnownAgentsRow records by host_info
+    pub fn find_by_host_info(host_info: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "host_info IS ?1",
+            &[&host_info as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all KnownAgentsRow records by last_seen
+    pub fn find_by_last_seen(last_seen: i64) -> Result<Vec<Self>> {
+        select_many(
+            "last_seen = ?1",
+            &[&last_seen as &dyn rusqlite::types::ToSql],
+        )
+    }
+}
+
+use crate::agent::discovery::DiscoveredHost;
+use crate::agent::server::HostInfo;
+use anyhow::Context;
+use std::time::Duration;
+
+impl KnownAgentsRow {
+    /// Host info last reported by the agent, if any
+    pub fn reported_host_info(&self) -> Option<HostInfo> {
+        self.host_info
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// Seconds since the agent was last seen
+    pub fn seen_ago(&self) -> i64 {
+        (chrono::Utc::now().timestamp() - self.last_seen).max(0)
+    }
+}
+
+/// Record a discovered agent, refreshing its last-seen timestamp
+/// Agents are keyed by the address used to reach them (Tailscale IP, else local IP)
+pub fn upsert_agent(host: &DiscoveredHost) -> Result<()> {
+    let address = host
+        .tailscale_ip
+        .as_ref()
+        .or(host.local_ip.as_ref())
+        .with_context(|| format!("Agent {} has no address to record", host.hostname))?
+        .clone();
+    let existing = KnownAgentsRow::find_by_address(&address)?;
+
+    // CIDR scans only know the IP, keep a better name from an earlier discovery
+    let hostname = match &existing {
+        Some(row) if host.hostname == address => row.hostname.clone(),
+        _ => host.hostname.clone(),
+    };
+    upsert_one(
+        "address = ?1",
+        &[&address as &dyn rusqlite::types::ToSql],
+        KnownAgentsRowData {
+            address: address.clone(),
+            hostname,
+            local_ip: host
+                .local_ip
+                .clone()
+                .or_else(|| existing.as_ref().and_then(|r| r.local_ip.clone())),
+            tailscale_ip: host
+                .tailscale_ip
+                .clone()
+                .or_else(|| existing.as_ref().and_then(|r| r.tailscale_ip.clone())),
+            tailscale_hostname: host
+                .tailscale_hostname
+                .clone()
+                .or_else(|| existing.as_ref().and_then(|r| r.tailscale_hostname.clone())),
+            agent_port: host.agent_port as i64,
+            host_info: existing.and_then(|r| r.host_info),
+            last_seen: chrono::Utc::now().timestamp(),
+        },
+    )?;
+    Ok(())
+}
+
+/// Store the host info reported by a known agent
+pub fn record_agent_host_info(address: &str, info: &HostInfo) -> Result<()> {
+    let row = KnownAgentsRow::find_by_address(address)?
+        .with_context(|| format!("No known agent at {}", address))?;
+    let info_json = serde_json::to_string(info).context("Failed to serialize host info")?;
+    upsert_one(
+        "address = ?1",
+        &[&address as &dyn rusqlite::types::ToSql],
+        KnownAgentsRowData {
+            address: row.address,
+            hostname: info.hostname.clone(),
+            local_ip: row.local_ip,
+            tailscale_ip: row.tailscale_ip,
+            tailscale_hostname: row.tailscale_hostname,
+            agent_port: row.agent_port,
+            host_info: Some(info_json),
+            last_seen: row.last_seen,
+        },
+    )?;
+    Ok(())
+}
+
+/// List every agent ever seen (most recently seen first)
+pub fn list_known_agents() -> Result<Vec<KnownAgentsRow>> {
+    let mut rows = select_many("1=1", &[])?;
+    rows.sort_by_key(|r| std::cmp::Reverse(r.last_seen));
+    Ok(rows)
+}
+
+/// Remove agents not seen within `ttl`, returning how many were removed
+pub fn prune_agents(ttl: Duration) -> Result<usize> {
+    let conn = db::get_connection()?;
+    let cutoff = chrono::Utc::now().timestamp() - ttl.as_secs() as i64;
+    DbTable::<KnownAgentsRow>::delete_many(
+        &conn,
+        "last_seen < ?1",
+        &[&cutoff as &dyn rusqlite::types::ToSql],
+    )
+}
@@ -4,6 +4,8 @@
 
 pub mod encrypted_env_data;
 pub mod host_info;
+pub mod known_agents;
+pub mod provision_history;
 pub mod settings;
 pub mod smb_servers;
 pub mod update_history;
@@ -11,6 +13,8 @@ pub mod update_history;
 // Re-export all generated structs
 pub use encrypted_env_data::{EncryptedEnvDataRow, EncryptedEnvDataRowData};
 pub use host_info::{HostInfoRow, HostInfoRowData};
+pub use known_agents::{KnownAgentsRow, KnownAgentsRowData};
+pub use provision_history::{ProvisionHistoryRow, ProvisionHistoryRowData};
 pub use settings::{SettingsRow, SettingsRowData};
 pub use smb_servers::{SmbServersRow, SmbServersRowData};
 pub use update_history::{UpdateHistoryRow, UpdateHistoryRowData};
@@ -24,18 +28,24 @@ pub use settings::{get_setting, set_setting};
 
 // Host info wrapper functions
 pub use host_info::{
-    delete_host_config, get_host_config, get_host_info, list_hosts, store_host_config,
-    store_host_info,
+    HostRename, delete_host_config, get_host_config, get_host_info, list_hosts, rename_host,
+    store_host_config, store_host_info, store_host_metadata,
 };
 
 // SMB servers wrapper functions
 pub use smb_servers::{delete_smb_server, get_smb_server, list_smb_servers, store_smb_server};
 
+// Known agents wrapper functions
+pub use known_agents::{list_known_agents, prune_agents, record_agent_host_info, upsert_agent};
+
+// Provision history wrapper functions
+pub use provision_history::{get_provision_history, record_provision};
+
 // Update history wrapper functions
 pub use update_history::{get_update_history, record_update};
 
 // Encrypted env data wrapper functions
 pub use encrypted_env_data::{
     export_encrypted_data, get_all_encrypted_envs, get_encrypted_env, import_encrypted_data,
-    store_encrypted_env,
+    reencrypt_all, store_encrypted_env,
 };
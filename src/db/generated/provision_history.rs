@@ -0,0 +1,250 @@
+// Auto-generated from database schema
+// This file is generated - do not edit manually
+// Run `halvor db generate` to regenerate
+
+use crate::db;
+use crate::db::core::table::DbTable;
+use crate::impl_table_auto;
+use anyhow::Result;
+
+#[derive(Debug, Clone)]
+pub struct ProvisionHistoryRow {
+    pub id: String,
+    pub hostname: String,
+    pub provisioned_at: i64,
+    pub docker_installed: i32,
+    pub tailscale_installed: i32,
+    pub portainer_installed: i32,
+    pub success: i32,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Automatically implement Table trait from struct definition
+impl_table_auto!(
+    ProvisionHistoryRow,
+    "provision_history",
+    [
+        hostname,
+        provisioned_at,
+        docker_installed,
+        tailscale_installed,
+        portainer_installed,
+        success,
+        error
+    ]
+);
+
+/// Data structure for ProvisionHistoryRow operations (excludes id, created_at, updated_at)
+#[derive(Debug, Clone)]
+pub struct ProvisionHistoryRowData {
+    pub hostname: String,
+    pub provisioned_at: i64,
+    pub docker_installed: i32,
+    pub tailscale_installed: i32,
+    pub portainer_installed: i32,
+    pub success: i32,
+    pub error: Option<String>,
+}
+
+/// Insert a new ProvisionHistoryRow record
+/// Only data fields are required - id, created_at, and updated_at are set automatically
+pub fn insert_one(data: ProvisionHistoryRowData) -> Result<String> {
+    let conn = db::get_connection()?;
+    let row = ProvisionHistoryRow {
+        id: String::new(), // Set automatically
+        hostname: data.hostname.clone(),
+        provisioned_at: data.provisioned_at.clone(),
+        docker_installed: data.docker_installed.clone(),
+        tailscale_installed: data.tailscale_installed.clone(),
+        portainer_installed: data.portainer_installed.clone(),
+        success: data.success.clone(),
+        error: data.error.clone(),
+
+        created_at: 0, // Set automatically
+        updated_at: 0, // Set automatically
+    };
+    DbTable::<ProvisionHistoryRow>::insert(&conn, &row)
+}
+
+/// Insert multiple ProvisionHistoryRow records
+pub fn insert_many(data_vec: Vec<ProvisionHistoryRowData>) -> Result<Vec<String>> {
+    let conn = db::get_connection()?;
+    let mut ids = Vec::new();
+    for data in data_vec {
+        let row = ProvisionHistoryRow {
+            id: String::new(), // Set automatically
+            hostname: data.hostname.clone(),
+            provisioned_at: data.provisioned_at.clone(),
+            docker_installed: data.docker_installed.clone(),
+            tailscale_installed: data.tailscale_installed.clone(),
+            portainer_installed: data.portainer_installed.clone(),
+            success: data.success.clone(),
+            error: data.error.clone(),
+
+            created_at: 0, // Set automatically
+            updated_at: 0, // Set automatically
+        };
+        ids.push(DbTable::<ProvisionHistoryRow>::insert(&conn, &row)?);
+    }
+    Ok(ids)
+}
+
+/// Upsert a ProvisionHistoryRow record (insert if new, update if exists)
+/// Only data fields are required - id, created_at, and updated_at are handled automatically
+pub fn upsert_one(
+    where_clause: &str,
+    where_params: &[&dyn rusqlite::types::ToSql],
+    data: ProvisionHistoryRowData,
+) -> Result<String> {
+    let conn = db::get_connection()?;
+    DbTable::<ProvisionHistoryRow>::upsert_by(&conn, where_clause, where_params, |existing| {
+        let mut row = existing.cloned().unwrap_or_else(|| {
+            let mut r = ProvisionHistoryRow {
+                id: String::new(), // Set automatically
+                hostname: String::new(),
+                provisioned_at: 0,
+                docker_installed: 0,
+                tailscale_installed: 0,
+                portainer_installed: 0,
+                success: 0,
+                error: None,
+
+                created_at: 0, // Set automatically
+                updated_at: 0, // Set automatically
+            };
+            // Set initial values from data
+            r.hostname = data.hostname.clone();
+            r.provisioned_at = data.provisioned_at.clone();
+            r.docker_installed = data.docker_installed.clone();
+            r.tailscale_installed = data.tailscale_installed.clone();
+            r.portainer_installed = data.portainer_installed.clone();
+            r.success = data.success.clone();
+            r.error = data.error.clone();
+
+            r
+        });
+        // Update only the data fields
+        row.hostname = data.hostname;
+        row.provisioned_at = data.provisioned_at;
+        row.docker_installed = data.docker_installed;
+        row.tailscale_installed = data.tailscale_installed;
+        row.portainer_installed = data.portainer_installed;
+        row.success = data.success;
+        row.error = data.error;
+
+        row
+    })
+}
+
+/// Select one ProvisionHistoryRow record
+pub fn select_one(
+    where_clause: &str,
+    params: &[&dyn rusqlite::types::ToSql],
+) -> Result<Option<ProvisionHistoryRow>> {
+    let conn = db::get_connection()?;
+    DbTable::<ProvisionHistoryRow>::select_one(&conn, where_clause, params)
+}
+
+/// Select many ProvisionHistoryRow records
+pub fn select_many(
+    where_clause: &str,
+    params: &[&dyn rusqlite::types::ToSql],
+) -> Result<Vec<ProvisionHistoryRow>> {
+    let conn = db::get_connection()?;
+    DbTable::<ProvisionHistoryRow>::select_many(&conn, where_clause, params)
+}
+
+/// Delete ProvisionHistoryRow record by primary key (id)
+pub fn delete_by_id(id: &str) -> Result<usize> {
+    let conn = db::get_connection()?;
+    DbTable::<ProvisionHistoryRow>::delete_many(
+        &conn,
+        "id = ?1",
+        &[&id as &dyn rusqlite::types::ToSql],
+    )
+}
+
+impl ProvisionHistoryRow {
+    /// Find all ProvisionHistoryRow records by hostname
+    pub fn find_by_hostname(hostname: &str) -> Result<Vec<Self>> {
+        select_many("hostname = ?1", &[&hostname as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all ProvisionHistoryRow records by provisioned_at
+    pub fn find_by_provisioned_at(provisioned_at: i64) -> Result<Vec<Self>> {
+        select_many(
+            "provisioned_at = ?1",
+            &[&provisioned_at as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all ProvisionHistoryRow records by docker_installed
+    pub fn find_by_docker_installed(docker_installed: i32) -> Result<Vec<Self>> {
+        select_many(
+            "docker_installed = ?1",
+            &[&docker_installed as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all ProvisionHistoryRow records by tailscale_installed
+    pub fn find_by_tailscale_installed(tailscale_installed: i32) -> Result<Vec<Self>> {
+        select_many(
+            "tailscale_installed = ?1",
+            &[&tailscale_installed as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all ProvisionHistoryRow records by portainer_installed
+    pub fn find_by_portainer_installed(portainer_installed: i32) -> Result<Vec<Self>> {
+        select_many(
+            "portainer_installed = ?1",
+            &[&portainer_installed as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all ProvisionHistoryRow records by success
+    pub fn find_by_success(success: i32) -> Result<Vec<Self>> {
+        select_many("success = ?1", &[&success as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all ProvisionHistoryRow records by error
+    pub fn find_by_error(error: Option<&str>) -> Result<Vec<Self>> {
+        select_many("error IS ?1", &[&error as &dyn rusqlite::types::ToSql])
+    }
+}
+
+use chrono;
+
+/// Record a provisioning run
+/// `error` is None when the run succeeded
+pub fn record_provision(
+    hostname: &str,
+    docker_installed: bool,
+    tailscale_installed: bool,
+    portainer_installed: bool,
+    error: Option<&str>,
+) -> Result<()> {
+    insert_one(ProvisionHistoryRowData {
+        hostname: hostname.to_string(),
+        provisioned_at: chrono::Utc::now().timestamp(),
+        docker_installed: docker_installed as i32,
+        tailscale_installed: tailscale_installed as i32,
+        portainer_installed: portainer_installed as i32,
+        success: error.is_none() as i32,
+        error: error.map(|s| s.to_string()),
+    })?;
+    Ok(())
+}
+
+/// Get provisioning history for a host (most recent first)
+pub fn get_provision_history(
+    hostname: &str,
+    limit: Option<i32>,
+) -> Result<Vec<ProvisionHistoryRow>> {
+    let mut rows = ProvisionHistoryRow::find_by_hostname(hostname)?;
+    rows.sort_by(|a, b| b.provisioned_at.cmp(&a.provisioned_at));
+    let limit = limit.unwrap_or(10) as usize;
+    Ok(rows.into_iter().take(limit).collect())
+}
@@ -10,7 +10,7 @@ use anyhow::Result;
 #[derive(Debug, Clone)]
 pub struct SettingsRow {
     pub id: String,
-    pub key: Option<String>,
+    pub key: String,
     pub value: String,
     pub created_at: i64,
     pub updated_at: i64,
@@ -22,7 +22,7 @@ impl_table_auto!(SettingsRow, "settings", [key, value]);
 /// Data structure for SettingsRow operations (excludes id, created_at, updated_at)
 #[derive(Debug, Clone)]
 pub struct SettingsRowData {
-    pub key: Option<String>,
+    pub key: String,
     pub value: String,
 }
 
@@ -67,11 +67,21 @@ pub fn upsert_one(
     data: SettingsRowData,
 ) -> Result<String> {
     let conn = db::get_connection()?;
-    DbTable::<SettingsRow>::upsert_by(&conn, where_clause, where_params, |existing| {
+    upsert_one_with(&conn, where_clause, where_params, data)
+}
+
+/// Upsert a SettingsRow record on an open connection (e.g. inside a transaction)
+pub fn upsert_one_with(
+    conn: &rusqlite::Connection,
+    where_clause: &str,
+    where_params: &[&dyn rusqlite::types::ToSql],
+    data: SettingsRowData,
+) -> Result<String> {
+    DbTable::<SettingsRow>::upsert_by(conn, where_clause, where_params, |existing| {
         let mut row = existing.cloned().unwrap_or_else(|| {
             let mut r = SettingsRow {
                 id: String::new(), // Set automatically
-                key: None,
+                key: String::new(),
                 value: String::new(),
 
                 created_at: 0, // Set automatically
@@ -125,21 +135,45 @@ pub fn delete_by_key(key_value: &str) -> Result<usize> {
     )
 }
 
+impl SettingsRow {
+    /// Find the SettingsRow record by key
+    pub fn find_by_key(key: &str) -> Result<Option<Self>> {
+        select_one("key = ?1", &[&key as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all SettingsRow records by value
+    pub fn find_by_value(value: &str) -> Result<Vec<Self>> {
+        select_many("value = ?1", &[&value as &dyn rusqlite::types::ToSql])
+    }
+}
+
 /// Set a setting value (convenience wrapper)
 pub fn set_setting(key: &str, value: &str) -> Result<()> {
-    upsert_one(
+    set_setting_with(&db::get_connection()?, key, value)
+}
+
+/// Set a setting value on an open connection (e.g. inside a transaction)
+pub fn set_setting_with(conn: &rusqlite::Connection, key: &str, value: &str) -> Result<()> {
+    upsert_one_with(
+        conn,
         "key = ?1",
         &[&key as &dyn rusqlite::types::ToSql],
         SettingsRowData {
-            key: Some(key.to_string()),
+            key: key.to_string(),
             value: value.to_string(),
         },
     )?;
     Ok(())
 }
 
+/// Delete a setting on an open connection (e.g. inside a transaction)
+pub fn delete_setting_with(conn: &rusqlite::Connection, key: &str) -> Result<()> {
+    DbTable::<SettingsRow>::delete_many(conn, "key = ?1", &[&key as &dyn rusqlite::types::ToSql])?;
+    Ok(())
+}
+
 /// Get a setting value (convenience wrapper)
 pub fn get_setting(key: &str) -> Result<Option<String>> {
-    let row = select_one("key = ?1", &[&key as &dyn rusqlite::types::ToSql])?;
+    let row = SettingsRow::find_by_key(key)?;
     Ok(row.map(|r| r.value))
 }
@@ -10,7 +10,7 @@ use anyhow::Result;
 #[derive(Debug, Clone)]
 pub struct SmbServersRow {
     pub id: String,
-    pub server_name: Option<String>,
+    pub server_name: String,
     pub host: String,
     pub shares: String,
     pub username: Option<String>,
@@ -30,7 +30,7 @@ impl_table_auto!(
 /// Data structure for SmbServersRow operations (excludes id, created_at, updated_at)
 #[derive(Debug, Clone)]
 pub struct SmbServersRowData {
-    pub server_name: Option<String>,
+    pub server_name: String,
     pub host: String,
     pub shares: String,
     pub username: Option<String>,
@@ -87,11 +87,21 @@ pub fn upsert_one(
     data: SmbServersRowData,
 ) -> Result<String> {
     let conn = db::get_connection()?;
-    DbTable::<SmbServersRow>::upsert_by(&conn, where_clause, where_params, |existing| {
+    upsert_one_with(&conn, where_clause, where_params, data)
+}
+
+/// Upsert a SmbServersRow record on an open connection (e.g. inside a transaction)
+pub fn upsert_one_with(
+    conn: &rusqlite::Connection,
+    where_clause: &str,
+    where_params: &[&dyn rusqlite::types::ToSql],
+    data: SmbServersRowData,
+) -> Result<String> {
+    DbTable::<SmbServersRow>::upsert_by(conn, where_clause, where_params, |existing| {
         let mut row = existing.cloned().unwrap_or_else(|| {
             let mut r = SmbServersRow {
                 id: String::new(), // Set automatically
-                server_name: None,
+                server_name: String::new(),
                 host: String::new(),
                 shares: String::new(),
                 username: None,
@@ -157,19 +167,70 @@ pub fn delete_by_server_name(server_name_value: &str) -> Result<usize> {
     )
 }
 
+impl SmbServersRow {
+    /// Find the SmbServersRow record by server_name
+    pub fn find_by_server_name(server_name: &str) -> Result<Option<Self>> {
+        select_one(
+            "server_name = ?1",
+            &[&server_name as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all SmbServersRow records by host
+    pub fn find_by_host(host: &str) -> Result<Vec<Self>> {
+        select_many("host = ?1", &[&host as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all SmbServersRow records by shares
+    pub fn find_by_shares(shares: &str) -> Result<Vec<Self>> {
+        select_many("shares = ?1", &[&shares as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all SmbServersRow records by username
+    pub fn find_by_username(username: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "username IS ?1",
+            &[&username as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all SmbServersRow records by password
+    pub fn find_by_password(password: Option<&str>) -> Result<Vec<Self>> {
+        select_many(
+            "password IS ?1",
+            &[&password as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all SmbServersRow records by options
+    pub fn find_by_options(options: Option<&str>) -> Result<Vec<Self>> {
+        select_many("options IS ?1", &[&options as &dyn rusqlite::types::ToSql])
+    }
+}
+
 use crate::config;
 use anyhow::Context;
 use serde_json;
 
 /// Store SMB server configuration in database
 pub fn store_smb_server(server_name: &str, smb_config: &config::SmbServerConfig) -> Result<()> {
+    store_smb_server_with(&db::get_connection()?, server_name, smb_config)
+}
+
+/// Store SMB server configuration on an open connection (e.g. inside a transaction)
+pub fn store_smb_server_with(
+    conn: &rusqlite::Connection,
+    server_name: &str,
+    smb_config: &config::SmbServerConfig,
+) -> Result<()> {
     let shares_json =
         serde_json::to_string(&smb_config.shares).context("Failed to serialize shares to JSON")?;
-    upsert_one(
+    upsert_one_with(
+        conn,
         "server_name = ?1",
         &[&server_name as &dyn rusqlite::types::ToSql],
         SmbServersRowData {
-            server_name: Some(server_name.to_string()),
+            server_name: server_name.to_string(),
             host: smb_config.host.clone(),
             shares: shares_json,
             username: smb_config.username.clone(),
@@ -182,10 +243,7 @@ pub fn store_smb_server(server_name: &str, smb_config: &config::SmbServerConfig)
 
 /// Get SMB server configuration from database
 pub fn get_smb_server(server_name: &str) -> Result<Option<config::SmbServerConfig>> {
-    let row = select_one(
-        "server_name = ?1",
-        &[&server_name as &dyn rusqlite::types::ToSql],
-    )?;
+    let row = SmbServersRow::find_by_server_name(server_name)?;
     Ok(row.map(|row| {
         let shares: Vec<String> = serde_json::from_str(&row.shares).unwrap_or_else(|_| Vec::new());
         config::SmbServerConfig {
@@ -201,7 +259,7 @@ pub fn get_smb_server(server_name: &str) -> Result<Option<config::SmbServerConfi
 /// List all SMB server names in database
 pub fn list_smb_servers() -> Result<Vec<String>> {
     let rows = select_many("1=1", &[])?;
-    Ok(rows.into_iter().filter_map(|r| r.server_name).collect())
+    Ok(rows.into_iter().map(|r| r.server_name).collect())
 }
 
 /// Delete SMB server configuration from database
@@ -209,3 +267,13 @@ pub fn delete_smb_server(server_name: &str) -> Result<()> {
     delete_by_server_name(server_name)?;
     Ok(())
 }
+
+/// Delete SMB server configuration on an open connection (e.g. inside a transaction)
+pub fn delete_smb_server_with(conn: &rusqlite::Connection, server_name: &str) -> Result<()> {
+    DbTable::<SmbServersRow>::delete_many(
+        conn,
+        "server_name = ?1",
+        &[&server_name as &dyn rusqlite::types::ToSql],
+    )?;
+    Ok(())
+}
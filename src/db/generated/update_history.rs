@@ -137,9 +137,35 @@ pub fn delete_by_id(id: &str) -> Result<usize> {
     )
 }
 
+impl UpdateHistoryRow {
+    /// Find all UpdateHistoryRow records by version
+    pub fn find_by_version(version: &str) -> Result<Vec<Self>> {
+        select_many("version = ?1", &[&version as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all UpdateHistoryRow records by channel
+    pub fn find_by_channel(channel: &str) -> Result<Vec<Self>> {
+        select_many("channel = ?1", &[&channel as &dyn rusqlite::types::ToSql])
+    }
+
+    /// Find all UpdateHistoryRow records by installed_at
+    pub fn find_by_installed_at(installed_at: i64) -> Result<Vec<Self>> {
+        select_many(
+            "installed_at = ?1",
+            &[&installed_at as &dyn rusqlite::types::ToSql],
+        )
+    }
+
+    /// Find all UpdateHistoryRow records by source
+    pub fn find_by_source(source: Option<&str>) -> Result<Vec<Self>> {
+        select_many("source IS ?1", &[&source as &dyn rusqlite::types::ToSql])
+    }
+}
+
 use chrono;
 
 /// Record an update installation
+/// `source` is the version that was replaced
 pub fn record_update(version: &str, channel: &str, source: Option<&str>) -> Result<()> {
     insert_one(UpdateHistoryRowData {
         version: version.to_string(),
@@ -150,11 +176,18 @@ pub fn record_update(version: &str, channel: &str, source: Option<&str>) -> Resu
     Ok(())
 }
 
-/// Get update history
+/// Get update history (most recent first), optionally only updates installed at or after `since`
 pub fn get_update_history(
+    since: Option<i64>,
     limit: Option<i32>,
 ) -> Result<Vec<(String, String, i64, Option<String>)>> {
-    let rows = select_many("1=1", &[])?;
+    let rows = match since {
+        Some(since) => select_many(
+            "installed_at >= ?1",
+            &[&since as &dyn rusqlite::types::ToSql],
+        )?,
+        None => select_many("1=1", &[])?,
+    };
     let mut sorted: Vec<_> = rows.into_iter().collect();
     sorted.sort_by(|a, b| b.installed_at.cmp(&a.installed_at));
     let limit = limit.unwrap_or(10) as usize;
@@ -4,7 +4,7 @@
 //! Handles all migration-related operations including:
 //! - Running all pending migrations
 //! - Migrating up one step
-//! - Migrating down one step
+//! - Migrating down one step (or down to a given version)
 //! - Listing migrations with interactive selection
 
 use crate::db;
@@ -22,6 +22,10 @@ pub fn migrate_all() -> Result<()> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
+    if db::migrations::migrations_paused(&conn)? {
+        println!("Resuming automatic migrations (paused by an earlier rollback)");
+        db::migrations::set_migrations_paused(&conn, false)?;
+    }
     db::migrations::run_migrations(&conn)?;
 
     println!();
@@ -30,6 +34,57 @@ pub fn migrate_all() -> Result<()> {
     Ok(())
 }
 
+/// Print the migrations that would run (all pending, or just the next one) and their SQL
+pub fn plan_migrations(next_only: bool) -> Result<()> {
+    let conn = db::open_without_migrations()?;
+    let current_version = db::migrations::get_current_migration_version(&conn)?;
+    let mut planned = db::migrations::plan_migrations(&conn)?;
+    if next_only {
+        planned.truncate(1);
+    }
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Migration plan (nothing will be applied)");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+    println!("Current version: {}", current_version);
+    println!();
+
+    if planned.is_empty() {
+        println!("✓ No pending migrations");
+        return Ok(());
+    }
+
+    for migration in &planned {
+        println!(
+            "Migration {}: {}",
+            migration.version,
+            migration.name.replace('_', " ")
+        );
+        if migration.statements.is_empty() {
+            println!("    (no SQL found; see src/db/migrations for the code that runs)");
+        }
+        for statement in &migration.statements {
+            for (i, line) in statement.lines().map(str::trim).enumerate() {
+                let indent = if i == 0 || line.starts_with(')') {
+                    "    "
+                } else {
+                    "        "
+                };
+                println!("{}{}", indent, line);
+            }
+            println!();
+        }
+    }
+
+    println!(
+        "{} migration(s) would run. Apply with: hal db migrate{}",
+        planned.len(),
+        if next_only { " up" } else { "" }
+    );
+    Ok(())
+}
+
 /// Migrate up one step
 pub fn migrate_up() -> Result<()> {
     let conn = db::get_connection()?;
@@ -47,19 +102,59 @@ pub fn migrate_up() -> Result<()> {
     Ok(())
 }
 
+/// Tell the user that the rollback paused automatic migrations, and how to resume them
+fn print_migrations_paused() {
+    println!(
+        "Automatic migrations are paused so the rollback sticks. Run 'hal db migrate' to apply \
+         pending migrations again."
+    );
+}
+
 /// Migrate down one step
+/// Automatic migrations are paused afterwards so the next command doesn't re-apply it
 pub fn migrate_down() -> Result<()> {
-    let conn = db::get_connection()?;
+    let conn = db::open_without_migrations()?;
 
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Rolling back database (one migration)");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
+    let current_version = db::migrations::get_current_migration_version(&conn)?;
     db::migrations::migrate_down(&conn)?;
+    if current_version == 0 {
+        return Ok(());
+    }
+    db::migrations::set_migrations_paused(&conn, true)?;
 
     println!();
     println!("✓ Rollback complete");
+    print_migrations_paused();
+
+    Ok(())
+}
+
+/// Roll back migrations until `target_version` is the latest applied one
+/// Pending migrations aren't applied first, and automatic migrations are paused afterwards so
+/// the next command doesn't re-apply the reverted ones
+pub fn migrate_down_to(target_version: u32) -> Result<()> {
+    let conn = db::open_without_migrations()?;
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Rolling back database to version {}", target_version);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+
+    let reverted = db::migrations::migrate_down_to(&conn, target_version)?;
+    db::migrations::set_migrations_paused(&conn, true)?;
+
+    println!();
+    println!(
+        "✓ Rolled back {} migration(s), now at version {}",
+        reverted.len(),
+        target_version
+    );
+    print_migrations_paused();
 
     Ok(())
 }
@@ -166,8 +261,10 @@ fn rollback_to_version(conn: &Connection, target_version: u32) -> Result<()> {
         db::migrations::migrate_down(conn)?;
         current_version = db::migrations::get_current_migration_version(conn)?;
     }
+    db::migrations::set_migrations_paused(conn, true)?;
 
     println!("✓ Rolled back to version {}", target_version);
+    print_migrations_paused();
     Ok(())
 }
 
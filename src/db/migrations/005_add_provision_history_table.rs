@@ -0,0 +1,30 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+/// Migration 005: Add provision history table
+pub fn up(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS provision_history (
+            id TEXT PRIMARY KEY,
+            hostname TEXT NOT NULL,
+            provisioned_at INTEGER NOT NULL,
+            docker_installed INTEGER NOT NULL,
+            tailscale_installed INTEGER NOT NULL,
+            portainer_installed INTEGER NOT NULL,
+            success INTEGER NOT NULL,
+            error TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create provision_history table")?;
+    Ok(())
+}
+
+/// Rollback: Remove provision history table
+pub fn down(conn: &Connection) -> Result<()> {
+    conn.execute("DROP TABLE IF EXISTS provision_history", [])
+        .context("Failed to drop provision_history table")?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+/// Migration 006: Add known agents table (agent registry with last-seen timestamps)
+pub fn up(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS known_agents (
+            id TEXT PRIMARY KEY,
+            address TEXT NOT NULL UNIQUE,
+            hostname TEXT NOT NULL,
+            local_ip TEXT,
+            tailscale_ip TEXT,
+            tailscale_hostname TEXT,
+            agent_port INTEGER NOT NULL,
+            host_info TEXT,
+            last_seen INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create known_agents table")?;
+    Ok(())
+}
+
+/// Rollback: Remove known agents table
+pub fn down(conn: &Connection) -> Result<()> {
+    conn.execute("DROP TABLE IF EXISTS known_agents", [])
+        .context("Failed to drop known_agents table")?;
+    Ok(())
+}
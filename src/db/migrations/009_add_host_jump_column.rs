@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+/// Columns of host_info before this migration, in table order
+const HOST_INFO_COLUMNS: &str = "id, hostname, last_provisioned_at, docker_version, \
+    tailscale_installed, portainer_installed, metadata, ip, hostname_field, tailscale, \
+    backup_path, ssh_user, ssh_port, created_at, updated_at";
+
+/// Migration 009: Add jump column to host_info
+/// Rows are read by position, so the table is rebuilt to keep created_at/updated_at last
+/// instead of appending the column with ALTER TABLE
+pub fn up(conn: &Connection) -> Result<()> {
+    let has_column = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('host_info') WHERE name='jump'",
+            [],
+            |row| {
+                let count: i32 = row.get(0)?;
+                Ok(count > 0)
+            },
+        )
+        .unwrap_or(false);
+    if has_column {
+        return Ok(());
+    }
+
+    conn.execute(
+        "CREATE TABLE host_info_new (
+            id TEXT PRIMARY KEY,
+            hostname TEXT NOT NULL UNIQUE,
+            last_provisioned_at INTEGER,
+            docker_version TEXT,
+            tailscale_installed INTEGER,
+            portainer_installed INTEGER,
+            metadata TEXT,
+            ip TEXT,
+            hostname_field TEXT,
+            tailscale TEXT,
+            backup_path TEXT,
+            ssh_user TEXT,
+            ssh_port INTEGER,
+            jump TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create new host_info table")?;
+
+    conn.execute(
+        &format!(
+            "INSERT INTO host_info_new ({cols}) SELECT {cols} FROM host_info",
+            cols = HOST_INFO_COLUMNS
+        ),
+        [],
+    )
+    .context("Failed to copy host_info rows")?;
+
+    conn.execute("DROP TABLE host_info", [])
+        .context("Failed to drop old host_info table")?;
+    conn.execute("ALTER TABLE host_info_new RENAME TO host_info", [])
+        .context("Failed to rename new host_info table")?;
+    Ok(())
+}
+
+/// Rollback: Rebuild host_info without the jump column
+pub fn down(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE host_info_old (
+            id TEXT PRIMARY KEY,
+            hostname TEXT NOT NULL UNIQUE,
+            last_provisioned_at INTEGER,
+            docker_version TEXT,
+            tailscale_installed INTEGER,
+            portainer_installed INTEGER,
+            metadata TEXT,
+            ip TEXT,
+            hostname_field TEXT,
+            tailscale TEXT,
+            backup_path TEXT,
+            ssh_user TEXT,
+            ssh_port INTEGER,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create host_info table without jump")?;
+
+    conn.execute(
+        &format!(
+            "INSERT INTO host_info_old ({cols}) SELECT {cols} FROM host_info",
+            cols = HOST_INFO_COLUMNS
+        ),
+        [],
+    )
+    .context("Failed to copy host_info rows")?;
+
+    conn.execute("DROP TABLE host_info", [])
+        .context("Failed to drop host_info table")?;
+    conn.execute("ALTER TABLE host_info_old RENAME TO host_info", [])
+        .context("Failed to rename host_info table")?;
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+/// Migration 010: Add checksum column to migrations
+/// Rows recorded before this migration are backfilled by run_migrations
+pub fn up(conn: &Connection) -> Result<()> {
+    let has_column = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('migrations') WHERE name='checksum'",
+            [],
+            |row| {
+                let count: i32 = row.get(0)?;
+                Ok(count > 0)
+            },
+        )
+        .unwrap_or(false);
+    if has_column {
+        return Ok(());
+    }
+
+    conn.execute("ALTER TABLE migrations ADD COLUMN checksum TEXT", [])
+        .context("Failed to add checksum column to migrations")?;
+    Ok(())
+}
+
+/// Rollback: Rebuild migrations without the checksum column
+pub fn down(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE migrations_old (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create migrations table without checksum")?;
+
+    conn.execute(
+        "INSERT INTO migrations_old (version, name, applied_at) \
+         SELECT version, name, applied_at FROM migrations",
+        [],
+    )
+    .context("Failed to copy migration records")?;
+
+    conn.execute("DROP TABLE migrations", [])
+        .context("Failed to drop migrations table")?;
+    conn.execute("ALTER TABLE migrations_old RENAME TO migrations", [])
+        .context("Failed to rename migrations table")?;
+    Ok(())
+}
@@ -13,32 +13,96 @@ mod migration_003_add_smb_servers_table {
 mod migration_004_fix_host_info_id_column {
     include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/004_fix_host_info_id_column.rs"));
 }
+mod migration_005_add_provision_history_table {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/005_add_provision_history_table.rs"));
+}
+mod migration_006_add_known_agents_table {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/006_add_known_agents_table.rs"));
+}
+mod migration_007_add_host_ssh_user_column {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/007_add_host_ssh_user_column.rs"));
+}
+mod migration_008_add_host_ssh_port_column {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/008_add_host_ssh_port_column.rs"));
+}
+mod migration_009_add_host_jump_column {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/009_add_host_jump_column.rs"));
+}
+mod migration_010_add_migration_checksums {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/010_add_migration_checksums.rs"));
+}
 
 
 const MIGRATIONS: &[Migration] = &[
     Migration {
         version: 1,
         name: "initial_schema",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/001_initial_schema.rs")),
         up: migration_001_initial_schema::up,
         down: None,
     },
     Migration {
         version: 2,
         name: "add_host_config_columns",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/002_add_host_config_columns.rs")),
         up: migration_002_add_host_config_columns::up,
         down: Some(migration_002_add_host_config_columns::down),
     },
     Migration {
         version: 3,
         name: "add_smb_servers_table",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/003_add_smb_servers_table.rs")),
         up: migration_003_add_smb_servers_table::up,
         down: Some(migration_003_add_smb_servers_table::down),
     },
     Migration {
         version: 4,
         name: "fix_host_info_id_column",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/004_fix_host_info_id_column.rs")),
         up: migration_004_fix_host_info_id_column::up,
         down: Some(migration_004_fix_host_info_id_column::down),
     },
+    Migration {
+        version: 5,
+        name: "add_provision_history_table",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/005_add_provision_history_table.rs")),
+        up: migration_005_add_provision_history_table::up,
+        down: Some(migration_005_add_provision_history_table::down),
+    },
+    Migration {
+        version: 6,
+        name: "add_known_agents_table",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/006_add_known_agents_table.rs")),
+        up: migration_006_add_known_agents_table::up,
+        down: Some(migration_006_add_known_agents_table::down),
+    },
+    Migration {
+        version: 7,
+        name: "add_host_ssh_user_column",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/007_add_host_ssh_user_column.rs")),
+        up: migration_007_add_host_ssh_user_column::up,
+        down: Some(migration_007_add_host_ssh_user_column::down),
+    },
+    Migration {
+        version: 8,
+        name: "add_host_ssh_port_column",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/008_add_host_ssh_port_column.rs")),
+        up: migration_008_add_host_ssh_port_column::up,
+        down: Some(migration_008_add_host_ssh_port_column::down),
+    },
+    Migration {
+        version: 9,
+        name: "add_host_jump_column",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/009_add_host_jump_column.rs")),
+        up: migration_009_add_host_jump_column::up,
+        down: Some(migration_009_add_host_jump_column::down),
+    },
+    Migration {
+        version: 10,
+        name: "add_migration_checksums",
+        source: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/db/migrations/010_add_migration_checksums.rs")),
+        up: migration_010_add_migration_checksums::up,
+        down: Some(migration_010_add_migration_checksums::down),
+    },
 
 ];
@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use rusqlite::Connection;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 // Include auto-generated migration declarations
 // Generated by: halvor generate migrations
@@ -15,6 +17,8 @@ type MigrationDownFn = fn(&Connection) -> Result<()>;
 struct Migration {
     version: u32,
     name: &'static str,
+    /// Source of the migration file; the SQL in it is checksummed when the migration is applied
+    source: &'static str,
     up: MigrationUpFn,
     down: Option<MigrationDownFn>,
 }
@@ -22,17 +26,16 @@ struct Migration {
 /// Get the highest migration version that has been applied
 pub fn get_current_migration_version(conn: &Connection) -> Result<u32> {
     // Check if migrations table exists
-    let table_exists: bool = match conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='migrations'",
-        [],
-        |row| {
-            let count: i32 = row.get(0)?;
-            Ok(count > 0)
-        },
-    ) {
-        Ok(exists) => exists,
-        Err(_) => false,
-    };
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='migrations'",
+            [],
+            |row| {
+                let count: i32 = row.get(0)?;
+                Ok(count > 0)
+            },
+        )
+        .context("Failed to check for migrations table")?;
 
     if !table_exists {
         return Ok(0);
@@ -57,17 +60,16 @@ pub fn get_current_migration_version(conn: &Connection) -> Result<u32> {
 
 /// Get the list of applied migrations
 pub fn get_applied_migrations(conn: &Connection) -> Result<HashMap<u32, String>> {
-    let table_exists: bool = match conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='migrations'",
-        [],
-        |row| {
-            let count: i32 = row.get(0)?;
-            Ok(count > 0)
-        },
-    ) {
-        Ok(exists) => exists,
-        Err(_) => false,
-    };
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='migrations'",
+            [],
+            |row| {
+                let count: i32 = row.get(0)?;
+                Ok(count > 0)
+            },
+        )
+        .context("Failed to check for migrations table")?;
 
     if !table_exists {
         return Ok(HashMap::new());
@@ -93,17 +95,225 @@ pub fn get_applied_migrations(conn: &Connection) -> Result<HashMap<u32, String>>
     Ok(applied)
 }
 
+static ALLOW_CHECKSUM_MISMATCH: AtomicBool = AtomicBool::new(false);
+
+/// Accept applied migrations whose source changed, re-recording their checksums
+/// (set from `hal db migrate --allow-checksum-mismatch`)
+pub fn set_allow_checksum_mismatch(allow: bool) {
+    ALLOW_CHECKSUM_MISMATCH.store(allow, Ordering::Relaxed);
+}
+
+impl Migration {
+    /// SHA-256 of the SQL in the migration file (see `sql_literals`)
+    fn checksum(&self) -> String {
+        format!(
+            "{:x}",
+            Sha256::digest(sql_literals(self.source).join("\n").as_bytes())
+        )
+    }
+
+    /// SHA-256 of the whole migration file, which older versions recorded as the checksum
+    fn file_checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.source.as_bytes()))
+    }
+}
+
+/// Calls whose string argument is a message rather than SQL
+/// Matched against the code before a literal with whitespace removed
+const MESSAGE_CALLS: &[&str] = &[
+    "context(",
+    "println!(",
+    "eprintln!(",
+    "bail!(",
+    "||format!(",
+    "||{format!(",
+];
+
+/// The SQL in a migration file: its string literals, except messages, with whitespace collapsed
+/// Comments, formatting and error text can change without the migration counting as modified.
+fn sql_literals(source: &str) -> Vec<String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut literals = Vec::new();
+    // Code seen so far, without whitespace, to tell which call a literal belongs to
+    let mut code = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let prev_is_ident = i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+
+        // Comments
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+
+        // Char literals (and lifetimes, which have no closing quote)
+        if c == '\'' {
+            let end = if next == Some('\\') {
+                (i + 3..chars.len()).find(|&j| chars[j] == '\'')
+            } else if chars.get(i + 2) == Some(&'\'') {
+                Some(i + 2)
+            } else {
+                None
+            };
+            code.push(c);
+            i = end.map_or(i + 1, |end| end + 1);
+            continue;
+        }
+
+        // String literals, raw (r"..." / r#"..."#) or not
+        let raw_hashes = if c == 'r' && !prev_is_ident {
+            let hashes = chars[i + 1..].iter().take_while(|&&h| h == '#').count();
+            (chars.get(i + 1 + hashes) == Some(&'"')).then_some(hashes)
+        } else {
+            None
+        };
+        if c == '"' || raw_hashes.is_some() {
+            let mut text = String::new();
+            if let Some(hashes) = raw_hashes {
+                i += hashes + 2;
+                let closing: Vec<char> = std::iter::once('"')
+                    .chain(std::iter::repeat_n('#', hashes))
+                    .collect();
+                while i < chars.len() && !chars[i..].starts_with(&closing) {
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                i += closing.len();
+            } else {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        // A line continuation is just whitespace; other escapes are kept as written
+                        if chars[i + 1] == '\n' {
+                            text.push(' ');
+                        } else {
+                            text.push(chars[i]);
+                            text.push(chars[i + 1]);
+                        }
+                        i += 2;
+                        continue;
+                    }
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                i += 1;
+            }
+            if !MESSAGE_CALLS.iter().any(|call| code.ends_with(call)) {
+                literals.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+            }
+            code.push('"');
+            continue;
+        }
+
+        if !c.is_whitespace() {
+            code.push(c);
+        }
+        i += 1;
+    }
+    literals
+}
+
+/// Whether the migrations table has the checksum column (added by migration 010)
+fn has_checksum_column(conn: &Connection) -> Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('migrations') WHERE name='checksum'",
+        [],
+        |row| {
+            let count: i32 = row.get(0)?;
+            Ok(count > 0)
+        },
+    )
+    .context("Failed to check for migrations checksum column")
+}
+
 /// Record that a migration has been applied
-fn record_migration(conn: &Connection, version: u32, name: &str) -> Result<()> {
+fn record_migration(conn: &Connection, migration: &Migration) -> Result<()> {
     let now = chrono::Utc::now().timestamp();
-    conn.execute(
-        "INSERT INTO migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
-        rusqlite::params![version, name, now],
-    )
+    if has_checksum_column(conn)? {
+        conn.execute(
+            "INSERT INTO migrations (version, name, applied_at, checksum) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![migration.version, migration.name, now, migration.checksum()],
+        )
+    } else {
+        conn.execute(
+            "INSERT INTO migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![migration.version, migration.name, now],
+        )
+    }
     .context("Failed to record migration")?;
     Ok(())
 }
 
+/// Compare stored checksums of applied migrations with the migration files
+///
+/// Missing checksums (migrations applied before checksums were recorded) are backfilled.
+/// A mismatch means a migration was edited after it ran, so the schema may not match
+/// what the code expects; this fails unless mismatches are explicitly allowed.
+fn verify_checksums(conn: &Connection) -> Result<()> {
+    if !has_checksum_column(conn)? {
+        return Ok(());
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT version, checksum FROM migrations ORDER BY version")
+        .context("Failed to prepare migration checksum query")?;
+    let stored = stmt
+        .query_map([], |row| {
+            let version: u32 = row.get(0)?;
+            let checksum: Option<String> = row.get(1)?;
+            Ok((version, checksum))
+        })
+        .context("Failed to query migration checksums")?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let allow_mismatch = ALLOW_CHECKSUM_MISMATCH.load(Ordering::Relaxed);
+    for (version, checksum) in stored {
+        let Some(migration) = MIGRATIONS.iter().find(|m| m.version == version) else {
+            continue;
+        };
+        let current = migration.checksum();
+        match checksum {
+            Some(stored) if stored == current => continue,
+            // Recorded when checksums covered the whole file; re-record it as a SQL checksum
+            Some(stored) if stored == migration.file_checksum() => {}
+            Some(stored) if !allow_mismatch => anyhow::bail!(
+                "Migration {} ({}) was modified after it was applied \
+                 (recorded checksum {}, file checksum {}).\n\
+                 Restore the original migration and add a new one for the change, or run \
+                 'hal db migrate --allow-checksum-mismatch' to accept the edited file.",
+                migration.version,
+                migration.name,
+                &stored[..12.min(stored.len())],
+                &current[..12]
+            ),
+            Some(_) => println!(
+                "⚠ Migration {} ({}) was modified after it was applied; recording the new checksum",
+                migration.version, migration.name
+            ),
+            None => {}
+        }
+        conn.execute(
+            "UPDATE migrations SET checksum = ?1 WHERE version = ?2",
+            rusqlite::params![current, version],
+        )
+        .context("Failed to record migration checksum")?;
+    }
+
+    Ok(())
+}
+
 /// Remove a migration record (for rollback)
 fn remove_migration_record(conn: &Connection, version: u32) -> Result<()> {
     conn.execute(
@@ -114,27 +324,88 @@ fn remove_migration_record(conn: &Connection, version: u32) -> Result<()> {
     Ok(())
 }
 
+/// Whether automatic migrations are paused because migrations were rolled back
+/// Stored in SQLite's `user_version` header field so it travels with the database file
+pub fn migrations_paused(conn: &Connection) -> Result<bool> {
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .context("Failed to read the migration pause flag")?;
+    Ok(version == 1)
+}
+
+/// Pause or resume automatic migrations (see `migrations_paused`)
+pub fn set_migrations_paused(conn: &Connection, paused: bool) -> Result<()> {
+    conn.execute_batch(&format!("PRAGMA user_version = {}", u8::from(paused)))
+        .context("Failed to set the migration pause flag")
+}
+
+/// Transient lock errors are retried this many times before giving up
+const LOCK_RETRY_ATTEMPTS: u32 = 3;
+
 /// Run all pending migrations
 ///
 /// This function automatically runs any migrations that haven't been applied yet,
-/// in sequential order based on their version number.
+/// in sequential order based on their version number. Transient lock errors are retried.
 pub fn run_migrations(conn: &Connection) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        match run_pending_migrations(conn) {
+            Err(e) if attempt < LOCK_RETRY_ATTEMPTS && super::is_lock_error(&e) => {
+                eprintln!(
+                    "⚠ Database is locked, retrying migrations ({}/{})",
+                    attempt, LOCK_RETRY_ATTEMPTS
+                );
+                std::thread::sleep(std::time::Duration::from_millis(500 * attempt as u64));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Migrations newer than the latest applied version, in the order they would run
+fn pending_migrations(conn: &Connection) -> Result<Vec<&'static Migration>> {
     let current_version = get_current_migration_version(conn)?;
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current_version)
+        .collect())
+}
 
-    for migration in MIGRATIONS {
-        if migration.version > current_version {
-            println!(
-                "Running migration {}: {}",
+/// Whether any migration has yet to be applied
+pub fn has_pending_migrations(conn: &Connection) -> Result<bool> {
+    Ok(!pending_migrations(conn)?.is_empty())
+}
+
+fn run_pending_migrations(conn: &Connection) -> Result<()> {
+    verify_checksums(conn)?;
+    let pending = pending_migrations(conn)?;
+
+    for migration in &pending {
+        println!(
+            "Running migration {}: {}",
+            migration.version, migration.name
+        );
+        // Apply and record in one transaction so a lock error mid-way can be retried cleanly
+        let tx = conn.unchecked_transaction()?;
+        (migration.up)(&tx).with_context(|| {
+            format!(
+                "Failed to run migration {}: {}",
                 migration.version, migration.name
-            );
-            (migration.up)(conn).with_context(|| {
-                format!(
-                    "Failed to run migration {}: {}",
-                    migration.version, migration.name
-                )
-            })?;
-            record_migration(conn, migration.version, migration.name)?;
-        }
+            )
+        })?;
+        record_migration(&tx, migration)?;
+        tx.commit().with_context(|| {
+            format!(
+                "Failed to commit migration {}: {}",
+                migration.version, migration.name
+            )
+        })?;
+    }
+
+    // Backfill checksums for migrations recorded before the checksum column existed
+    if !pending.is_empty() {
+        verify_checksums(conn)?;
     }
 
     Ok(())
@@ -142,26 +413,130 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
 
 /// Run the next pending migration (migrate up one)
 pub fn migrate_up(conn: &Connection) -> Result<()> {
-    let current_version = get_current_migration_version(conn)?;
+    let Some(migration) = pending_migrations(conn)?.into_iter().next() else {
+        println!("No pending migrations to run");
+        return Ok(());
+    };
 
-    for migration in MIGRATIONS {
-        if migration.version > current_version {
-            println!(
-                "Running migration {}: {}",
-                migration.version, migration.name
-            );
-            (migration.up)(conn).with_context(|| {
-                format!(
-                    "Failed to run migration {}: {}",
-                    migration.version, migration.name
-                )
-            })?;
-            record_migration(conn, migration.version, migration.name)?;
-            return Ok(());
+    println!(
+        "Running migration {}: {}",
+        migration.version, migration.name
+    );
+    (migration.up)(conn).with_context(|| {
+        format!(
+            "Failed to run migration {}: {}",
+            migration.version, migration.name
+        )
+    })?;
+    record_migration(conn, migration)?;
+    Ok(())
+}
+
+/// A pending migration and the SQL it would execute
+pub struct PlannedMigration {
+    pub version: u32,
+    pub name: &'static str,
+    /// Statements found in the migration's `up` function; `{placeholders}` are filled at runtime
+    pub statements: Vec<String>,
+}
+
+/// Pending migrations with their SQL, without applying anything
+pub fn plan_migrations(conn: &Connection) -> Result<Vec<PlannedMigration>> {
+    Ok(pending_migrations(conn)?
+        .into_iter()
+        .map(|m| PlannedMigration {
+            version: m.version,
+            name: m.name,
+            statements: up_statements(m.source),
+        })
+        .collect())
+}
+
+/// SQL string literals in the `up` function of a migration's source
+fn up_statements(source: &str) -> Vec<String> {
+    const SQL_KEYWORDS: &[&str] = &["CREATE", "ALTER", "DROP", "INSERT", "UPDATE", "DELETE"];
+
+    let start = source.find("pub fn up(").unwrap_or(0);
+    let end = source[start..]
+        .find("pub fn down(")
+        .map_or(source.len(), |i| start + i);
+    string_literals(&source[start..end])
+        .into_iter()
+        .filter(|literal| {
+            let upper = literal.trim_start().to_uppercase();
+            SQL_KEYWORDS.iter().any(|k| upper.starts_with(k))
+        })
+        .collect()
+}
+
+/// Contents of the ordinary (non-raw) string literals in a piece of Rust source
+fn string_literals(code: &str) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut chars = code.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // Skip line comments so quotes in them aren't mistaken for strings
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let mut literal = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => literal.push('\n'),
+                            Some('t') => literal.push('\t'),
+                            // Line continuation: skip the newline and leading whitespace
+                            Some('\n') => {
+                                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                                    chars.next();
+                                }
+                            }
+                            Some(other) => literal.push(other),
+                            None => break,
+                        },
+                        _ => literal.push(c),
+                    }
+                }
+                literals.push(literal);
+            }
+            _ => {}
         }
     }
+    literals
+}
 
-    println!("No pending migrations to run");
+/// Roll back a single migration inside a transaction, removing its migration record
+fn rollback_migration(conn: &Connection, migration: &Migration) -> Result<()> {
+    let Some(down_fn) = migration.down else {
+        anyhow::bail!(
+            "Migration {} ({}) does not support rollback",
+            migration.version,
+            migration.name
+        );
+    };
+
+    println!(
+        "Rolling back migration {}: {}",
+        migration.version, migration.name
+    );
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to start rollback transaction")?;
+    down_fn(&tx).with_context(|| {
+        format!(
+            "Failed to rollback migration {}: {}",
+            migration.version, migration.name
+        )
+    })?;
+    remove_migration_record(&tx, migration.version)?;
+    tx.commit().context("Failed to commit rollback")?;
+    println!("✓ Successfully rolled back migration {}", migration.version);
     Ok(())
 }
 
@@ -175,33 +550,67 @@ pub fn migrate_down(conn: &Connection) -> Result<()> {
     }
 
     // Find the migration to rollback
-    for migration in MIGRATIONS.iter().rev() {
-        if migration.version == current_version {
-            if let Some(down_fn) = migration.down {
-                println!(
-                    "Rolling back migration {}: {}",
-                    migration.version, migration.name
-                );
-                down_fn(conn).with_context(|| {
-                    format!(
-                        "Failed to rollback migration {}: {}",
-                        migration.version, migration.name
-                    )
-                })?;
-                remove_migration_record(conn, migration.version)?;
-                println!("✓ Successfully rolled back migration {}", migration.version);
+    match MIGRATIONS.iter().find(|m| m.version == current_version) {
+        Some(migration) => rollback_migration(conn, migration),
+        None => anyhow::bail!("Migration version {} not found", current_version),
+    }
+}
+
+/// Roll back every applied migration newer than `target_version`, newest first
+/// Each rollback runs in its own transaction. If one fails, the error lists the versions that
+/// were already reverted. Returns the reverted versions.
+pub fn migrate_down_to(conn: &Connection, target_version: u32) -> Result<Vec<u32>> {
+    let current_version = get_current_migration_version(conn)?;
+
+    if !MIGRATIONS.iter().any(|m| m.version == target_version) {
+        anyhow::bail!("Migration version {} not found", target_version);
+    }
+    if target_version >= current_version {
+        anyhow::bail!(
+            "Target version {} must be older than the current version {}",
+            target_version,
+            current_version
+        );
+    }
+
+    let applied = get_applied_migrations(conn)?;
+    let to_revert: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .rev()
+        .filter(|m| m.version > target_version && applied.contains_key(&m.version))
+        .collect();
+
+    // Check up front so we don't stop halfway on a migration that can never be reverted
+    if let Some(migration) = to_revert.iter().find(|m| m.down.is_none()) {
+        anyhow::bail!(
+            "Cannot roll back to version {}: migration {} ({}) does not support rollback",
+            target_version,
+            migration.version,
+            migration.name
+        );
+    }
+
+    let mut reverted = Vec::new();
+    for migration in to_revert {
+        if let Err(e) = rollback_migration(conn, migration) {
+            let reverted_list = if reverted.is_empty() {
+                "none".to_string()
             } else {
-                anyhow::bail!(
-                    "Migration {} ({}) does not support rollback",
-                    migration.version,
-                    migration.name
-                );
-            }
-            return Ok(());
+                reverted
+                    .iter()
+                    .map(|v: &u32| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            return Err(e.context(format!(
+                "Rollback to version {} stopped at migration {} (already reverted: {})",
+                target_version, migration.version, reverted_list
+            )));
         }
+        reverted.push(migration.version);
     }
 
-    anyhow::bail!("Migration version {} not found", current_version);
+    Ok(reverted)
 }
 
 /// Get the list of all available migrations
@@ -230,3 +639,86 @@ pub fn get_migration_status(conn: &Connection) -> Result<Vec<(u32, String, bool,
 
     Ok(status)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIGRATION: &str = r##"use anyhow::{Context, Result};
+
+/// Columns copied across
+const COLUMNS: &str = "id, \
+    name";
+
+/// Migration 042: Example
+pub fn up(conn: &Connection) -> Result<()> {
+    // Rebuild the table
+    conn.execute(
+        "CREATE TABLE example (
+            id TEXT PRIMARY KEY,
+            name TEXT
+        )",
+        [],
+    )
+    .context("Failed to create example table")?;
+    println!("✓ example table created");
+    conn.execute(&format!("INSERT INTO example ({cols}) SELECT {cols} FROM old", cols = COLUMNS), [])
+        .with_context(|| format!("Failed to copy rows"))?;
+    conn.execute(r#"UPDATE example SET name = 'it''s "quoted"'"#, [])?;
+    let _sep = '"';
+    Ok(())
+}
+"##;
+
+    #[test]
+    fn sql_literals_skip_comments_and_messages() {
+        assert_eq!(
+            sql_literals(MIGRATION),
+            [
+                "id, name",
+                "CREATE TABLE example ( id TEXT PRIMARY KEY, name TEXT )",
+                "INSERT INTO example ({cols}) SELECT {cols} FROM old",
+                r#"UPDATE example SET name = 'it''s "quoted"'"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn checksum_only_changes_with_the_sql() {
+        let migration = |source: &'static str| Migration {
+            version: 42,
+            name: "example",
+            source,
+            up: |_| Ok(()),
+            down: None,
+        };
+        let original = migration(MIGRATION).checksum();
+        let edited = |from: &str, to: &str| {
+            let source: &'static str = Box::leak(MIGRATION.replace(from, to).into_boxed_str());
+            migration(source).checksum()
+        };
+
+        assert_eq!(edited("// Rebuild the table", "// Rebuilt"), original);
+        assert_eq!(
+            edited("Failed to create example table", "Couldn't create it"),
+            original
+        );
+        assert_eq!(edited("✓ example table created", "done"), original);
+        assert_eq!(
+            edited("            id TEXT PRIMARY KEY,", "  id TEXT PRIMARY KEY,"),
+            original
+        );
+        assert_ne!(edited("name TEXT\n", "name TEXT NOT NULL\n"), original);
+        assert_ne!(edited("FROM old", "FROM older"), original);
+    }
+
+    #[test]
+    fn pausing_migrations_round_trips() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(!migrations_paused(&conn).unwrap());
+        set_migrations_paused(&conn, true).unwrap();
+        assert!(migrations_paused(&conn).unwrap());
+        set_migrations_paused(&conn, false).unwrap();
+        assert!(!migrations_paused(&conn).unwrap());
+    }
+}
@@ -5,33 +5,188 @@ pub mod migrations;
 
 use crate::config::config_manager;
 use anyhow::{Context, Result};
-use rusqlite::Connection;
-use std::path::PathBuf;
+use rusqlite::{Connection, ErrorCode};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 const DB_FILE_NAME: &str = "halvor.db";
 
-/// Get the database file path (in the config directory)
+/// Default time to wait on a locked database before giving up
+pub const DEFAULT_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+static BUSY_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Override the busy timeout used for new connections (set once from `--db-timeout`)
+pub fn set_busy_timeout(timeout: Duration) {
+    let _ = BUSY_TIMEOUT.set(timeout);
+}
+
+/// Busy timeout applied to new connections
+pub fn busy_timeout() -> Duration {
+    BUSY_TIMEOUT.get().copied().unwrap_or(DEFAULT_BUSY_TIMEOUT)
+}
+
+static PRE_MIGRATION_BACKUP: AtomicBool = AtomicBool::new(true);
+
+/// Turn the automatic backup before pending migrations on or off (off with `--no-backup`)
+pub fn set_pre_migration_backup(enabled: bool) {
+    PRE_MIGRATION_BACKUP.store(enabled, Ordering::Relaxed);
+}
+
+/// Get the database file path of the profile in effect
 pub fn get_db_path() -> Result<PathBuf> {
-    let config_dir = config_manager::get_config_dir()?;
-    Ok(config_dir.join(DB_FILE_NAME))
+    let config = config_manager::load_config().unwrap_or_default();
+    let db_path = profile_db_path(config.profile())?;
+    if let Some(dir) = db_path.parent()
+        && !dir.exists()
+    {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    Ok(db_path)
+}
+
+/// Database file of a named profile, or of the default profile (in the config directory)
+pub fn profile_db_path(profile: Option<(&str, &config_manager::Profile)>) -> Result<PathBuf> {
+    let Some((name, profile)) = profile else {
+        return Ok(config_manager::get_config_dir()?.join(DB_FILE_NAME));
+    };
+    match &profile.db_path {
+        Some(path) => Ok(path.clone()),
+        None => Ok(config_manager::get_profile_dir(name)?.join(DB_FILE_NAME)),
+    }
+}
+
+/// Whether an error (or anything in its cause chain) is SQLITE_BUSY/SQLITE_LOCKED
+pub fn is_lock_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+        )
+    })
+}
+
+/// Add an actionable hint to database lock errors
+fn explain_lock_error(err: anyhow::Error, db_path: &Path) -> anyhow::Error {
+    if !is_lock_error(&err) {
+        return err;
+    }
+    err.context(format!(
+        "Database {} is locked (waited {}s). Likely causes:\n  \
+         - another halvor process is using it (wait for it to finish)\n  \
+         - the config directory is on a networked filesystem (SMB/NFS) without reliable locking\n\
+         Retry with a longer --db-timeout, or move the config directory to local storage.",
+        db_path.display(),
+        busy_timeout().as_secs_f32()
+    ))
+}
+
+/// Open the database with the configured busy timeout applied
+fn open_connection(db_path: &Path) -> Result<Connection> {
+    let conn = Connection::open(db_path)
+        .with_context(|| format!("Failed to open database: {}", db_path.display()))?;
+    conn.busy_timeout(busy_timeout())
+        .context("Failed to set database busy timeout")?;
+    Ok(conn)
+}
+
+/// Copy the database file, falling back to `sudo cp` when this user can't read it
+pub fn copy_database(db_path: &Path, backup_path: &Path) -> Result<()> {
+    let copy_error = match std::fs::copy(db_path, backup_path) {
+        Ok(_) => return Ok(()),
+        Err(e) => e,
+    };
+
+    // System-protected databases need elevated access
+    #[cfg(unix)]
+    {
+        let sudo_copy = std::process::Command::new("sudo")
+            .arg("cp")
+            .arg(db_path)
+            .arg(backup_path)
+            .output();
+        if sudo_copy.is_ok_and(|output| output.status.success()) {
+            return Ok(());
+        }
+    }
+
+    Err(copy_error).with_context(|| {
+        format!(
+            "Failed to copy database from {} to {}. You may need administrator privileges.",
+            db_path.display(),
+            backup_path.display()
+        )
+    })
+}
+
+/// Timestamped copy of the database, saved next to it before pending migrations run
+fn backup_before_migrations(db_path: &Path) -> Result<PathBuf> {
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let backup_path = db_path.with_file_name(format!("halvor_pre_migration_{}.db", timestamp));
+    copy_database(db_path, &backup_path).context("Failed to back up database before migrating")?;
+    println!(
+        "✓ Backed up database to {} before migrating",
+        backup_path.display()
+    );
+    Ok(backup_path)
 }
 
 /// Initialize the database and run migrations
 ///
 /// This function automatically runs all pending migrations when the database is first accessed.
 /// Migrations are run sequentially in order, ensuring the database schema is always up to date.
+/// An existing database is copied first (unless disabled with `--no-backup`) so a failed
+/// migration can be undone with `hal db restore`.
 pub fn init_db() -> Result<Connection> {
     let db_path = get_db_path()?;
-    let conn = Connection::open(&db_path)
-        .with_context(|| format!("Failed to open database: {}", db_path.display()))?;
+    let conn = open_connection(&db_path).map_err(|e| explain_lock_error(e, &db_path))?;
+
+    // After `hal db migrate down` the schema stays where it was put until `hal db migrate`
+    if migrations::migrations_paused(&conn)? {
+        return Ok(conn);
+    }
+
+    // A brand new database has nothing worth backing up
+    let backup = if PRE_MIGRATION_BACKUP.load(Ordering::Relaxed)
+        && migrations::get_current_migration_version(&conn)? > 0
+        && migrations::has_pending_migrations(&conn)?
+    {
+        Some(backup_before_migrations(&db_path)?)
+    } else {
+        None
+    };
 
     // Run migrations to set up/update schema
     // This happens automatically on every database access to ensure schema is current
-    migrations::run_migrations(&conn)?;
+    migrations::run_migrations(&conn).map_err(|e| {
+        let e = explain_lock_error(e, &db_path);
+        match &backup {
+            Some(path) => e.context(format!(
+                "Migrations failed. The database as it was before migrating is saved at {}\n\
+                 Restore it with: hal db restore",
+                path.display()
+            )),
+            None => e,
+        }
+    })?;
 
     Ok(conn)
 }
 
+/// Open the database without running migrations, for inspecting what would change
+/// A database that doesn't exist yet is opened in memory, so nothing is created on disk
+pub fn open_without_migrations() -> Result<Connection> {
+    let db_path = get_db_path()?;
+    if !db_path.exists() {
+        return Connection::open_in_memory().context("Failed to open in-memory database");
+    }
+    open_connection(&db_path).map_err(|e| explain_lock_error(e, &db_path))
+}
+
 /// Get a database connection
 pub fn get_connection() -> Result<Connection> {
     init_db()
@@ -53,6 +208,14 @@ pub mod host_info {
     pub use super::generated::host_info::*;
 }
 
+pub mod known_agents {
+    pub use super::generated::known_agents::*;
+}
+
+pub mod provision_history {
+    pub use super::generated::provision_history::*;
+}
+
 pub mod smb_servers {
     pub use super::generated::smb_servers::*;
 }
@@ -69,12 +232,14 @@ pub mod encrypted_env_data {
 // These can be called directly via db::get_host_config(), etc.
 // Note: Generic CRUD functions are accessible via module paths like db::settings::insert_one()
 pub use generated::{
-    delete_host_config, get_host_config, get_host_info, get_setting, list_hosts, set_setting,
-    store_host_config, store_host_info,
+    HostRename, delete_host_config, get_host_config, get_host_info, get_setting, list_hosts,
+    rename_host, set_setting, store_host_config, store_host_info, store_host_metadata,
 };
 pub use generated::{delete_smb_server, get_smb_server, list_smb_servers, store_smb_server};
 pub use generated::{
     export_encrypted_data, get_all_encrypted_envs, get_encrypted_env, import_encrypted_data,
-    store_encrypted_env,
+    reencrypt_all, store_encrypted_env,
 };
+pub use generated::{get_provision_history, record_provision};
 pub use generated::{get_update_history, record_update};
+pub use generated::{list_known_agents, prune_agents, record_agent_host_info, upsert_agent};
@@ -1,14 +1,24 @@
 // C FFI bindings for Swift
 // This module exports C-compatible functions that Swift can call
+//
+// Every call returns a status code: 0 on success, otherwise `HalvorError::code()`
+// (1 network, 2 auth, 3 not_found, 4 internal). The `out` pointer receives a string
+// the caller must free with halvor_string_free: the JSON result on success, or the
+// error message on failure.
 
 use crate::ffi::client::HalvorClient; // This is fine - c_ffi is part of the ffi module
+use crate::ffi::error::HalvorError;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
-use std::ptr;
 
 /// Opaque pointer type for HalvorClient (matches C typedef)
 pub type HalvorClientPtr = *mut HalvorClient;
 
+/// Status code returned on success
+pub const HALVOR_OK: i32 = 0;
+
 /// Create a new Halvor client
 /// Returns a pointer to the client, or NULL on error
 /// agent_port: 0 means use default port
@@ -33,133 +43,200 @@ pub unsafe extern "C" fn halvor_client_free(ptr: HalvorClientPtr) {
     }
 }
 
-/// Discover all agents
-/// Returns JSON string with array of DiscoveredHost, or NULL on error
-/// Caller must free the returned string with halvor_string_free
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn halvor_client_discover_agents(ptr: HalvorClientPtr) -> *mut c_char {
-    if ptr.is_null() {
-        return ptr::null_mut();
+/// Result of a macro-generated C wrapper (`#[swift_export]`/`#[multi_platform_export]`)
+/// `code` follows the same convention as `out`-style calls; `data` holds the JSON result or
+/// the error message and must be freed with halvor_string_free
+#[repr(C)]
+pub struct FfiResult {
+    pub code: i32,
+    pub data: *mut c_char,
+}
+
+impl FfiResult {
+    /// Serialize a successful value
+    pub fn from_value<T: Serialize>(value: T) -> Self {
+        Self::from_result(Ok::<T, HalvorError>(value))
     }
 
-    let client = unsafe { &*ptr };
-    match client.discover_agents() {
-        Ok(hosts) => match serde_json::to_string(&hosts) {
-            Ok(json) => match CString::new(json) {
-                Ok(c_str) => c_str.into_raw(),
-                Err(_) => ptr::null_mut(),
+    /// Serialize a result, mapping the error into a `HalvorError`
+    pub fn from_result<T: Serialize, E: Into<HalvorError>>(result: Result<T, E>) -> Self {
+        let result = result.map_err(Into::into).and_then(|value| {
+            serde_json::to_string(&value)
+                .map_err(|e| HalvorError::Internal(format!("Failed to serialize result: {}", e)))
+        });
+        match result {
+            Ok(json) => Self {
+                code: HALVOR_OK,
+                data: into_c_string(json),
             },
-            Err(_) => ptr::null_mut(),
-        },
-        Err(_) => ptr::null_mut(),
+            Err(e) => Self::from_error(e),
+        }
+    }
+
+    pub fn from_error(error: HalvorError) -> Self {
+        Self {
+            code: error.code(),
+            data: into_c_string(error.message().to_string()),
+        }
     }
 }
 
-/// Discover agents via Tailscale
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn halvor_client_discover_via_tailscale(ptr: HalvorClientPtr) -> *mut c_char {
+fn into_c_string(value: String) -> *mut c_char {
+    // Interior NULs can't cross the C boundary, strip them rather than losing the string
+    CString::new(value.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Serialize a client result into `out` and return its status code
+unsafe fn finish<T: Serialize>(result: Result<T, HalvorError>, out: *mut *mut c_char) -> i32 {
+    let result = FfiResult::from_result(result);
+    if out.is_null() {
+        unsafe { halvor_string_free(result.data) };
+    } else {
+        unsafe { *out = result.data };
+    }
+    result.code
+}
+
+/// Borrow the value behind a pointer handed out to C
+///
+/// # Safety
+/// `ptr` must be NULL or point to a live `T`
+pub unsafe fn ffi_ref<'a, T>(ptr: *const T) -> Result<&'a T, HalvorError> {
     if ptr.is_null() {
-        return ptr::null_mut();
+        return Err(HalvorError::Internal("Client pointer is NULL".to_string()));
     }
+    Ok(unsafe { &*ptr })
+}
 
-    let client = unsafe { &*ptr };
-    match client.discover_via_tailscale() {
-        Ok(hosts) => match serde_json::to_string(&hosts) {
-            Ok(json) => match CString::new(json) {
-                Ok(c_str) => c_str.into_raw(),
-                Err(_) => ptr::null_mut(),
-            },
-            Err(_) => ptr::null_mut(),
-        },
-        Err(_) => ptr::null_mut(),
+/// Mutably borrow the value behind a pointer handed out to C
+///
+/// # Safety
+/// `ptr` must be NULL or point to a live `T` with no other borrows
+pub unsafe fn ffi_mut<'a, T>(ptr: *mut T) -> Result<&'a mut T, HalvorError> {
+    if ptr.is_null() {
+        return Err(HalvorError::Internal("Client pointer is NULL".to_string()));
+    }
+    Ok(unsafe { &mut *ptr })
+}
+
+/// Read a required string argument
+///
+/// # Safety
+/// `value` must be NULL or a valid NUL-terminated string
+pub unsafe fn ffi_string(value: *const c_char, name: &str) -> Result<String, HalvorError> {
+    if value.is_null() {
+        return Err(HalvorError::Internal(format!("{} is NULL", name)));
     }
+    unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .map(|s| s.to_string())
+        .map_err(|_| HalvorError::Internal(format!("{} is not valid UTF-8", name)))
+}
+
+/// Read a required argument passed as a JSON string
+///
+/// # Safety
+/// `value` must be NULL or a valid NUL-terminated string
+pub unsafe fn ffi_json<T: DeserializeOwned>(
+    value: *const c_char,
+    name: &str,
+) -> Result<T, HalvorError> {
+    let json = unsafe { ffi_string(value, name) }?;
+    serde_json::from_str(&json)
+        .map_err(|e| HalvorError::Internal(format!("{} is not valid JSON: {}", name, e)))
+}
+
+/// Discover all agents
+/// On success `out` receives a JSON array of DiscoveredHost
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `out` must be NULL or a valid pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn halvor_client_discover_agents(
+    ptr: HalvorClientPtr,
+    out: *mut *mut c_char,
+) -> i32 {
+    let result = unsafe { ffi_ref(ptr) }.and_then(|client| client.discover_agents());
+    unsafe { finish(result, out) }
+}
+
+/// Discover agents via Tailscale
+/// On success `out` receives a JSON array of DiscoveredHost
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `out` must be NULL or a valid pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn halvor_client_discover_via_tailscale(
+    ptr: HalvorClientPtr,
+    out: *mut *mut c_char,
+) -> i32 {
+    let result = unsafe { ffi_ref(ptr) }.and_then(|client| client.discover_via_tailscale());
+    unsafe { finish(result, out) }
 }
 
 /// Discover agents on local network
+/// On success `out` receives a JSON array of DiscoveredHost
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `out` must be NULL or a valid pointer
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn halvor_client_discover_via_local_network(
     ptr: HalvorClientPtr,
-) -> *mut c_char {
-    if ptr.is_null() {
-        return ptr::null_mut();
-    }
-
-    let client = unsafe { &*ptr };
-    match client.discover_via_local_network() {
-        Ok(hosts) => match serde_json::to_string(&hosts) {
-            Ok(json) => match CString::new(json) {
-                Ok(c_str) => c_str.into_raw(),
-                Err(_) => ptr::null_mut(),
-            },
-            Err(_) => ptr::null_mut(),
-        },
-        Err(_) => ptr::null_mut(),
-    }
+    out: *mut *mut c_char,
+) -> i32 {
+    let result = unsafe { ffi_ref(ptr) }.and_then(|client| client.discover_via_local_network());
+    unsafe { finish(result, out) }
 }
 
 /// Ping an agent
-/// Returns 1 if reachable, 0 if not reachable or on error
+/// On success `out` receives `true` or `false`
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `host` must be a NUL-terminated string;
+/// `out` must be NULL or a valid pointer
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn halvor_client_ping_agent(
     ptr: HalvorClientPtr,
     host: *const c_char,
     port: u16,
+    out: *mut *mut c_char,
 ) -> i32 {
-    if ptr.is_null() || host.is_null() {
-        return 0;
-    }
-
-    let host_str = match unsafe { CStr::from_ptr(host) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(_) => return 0,
-    };
-
-    let client = unsafe { &*ptr };
-    match client.ping_agent(host_str, port) {
-        Ok(reachable) => {
-            if reachable {
-                1
-            } else {
-                0
-            }
-        }
-        Err(_) => 0,
-    }
+    let result = unsafe { ffi_ref(ptr) }.and_then(|client| {
+        let host = unsafe { ffi_string(host, "host") }?;
+        client.ping_agent(host, port)
+    });
+    unsafe { finish(result, out) }
 }
 
 /// Get host info
-/// Returns JSON string with HostInfo, or NULL on error
+/// On success `out` receives HostInfo as JSON
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `host` must be a NUL-terminated string;
+/// `out` must be NULL or a valid pointer
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn halvor_client_get_host_info(
     ptr: HalvorClientPtr,
     host: *const c_char,
     port: u16,
-) -> *mut c_char {
-    if ptr.is_null() || host.is_null() {
-        return ptr::null_mut();
-    }
-
-    let host_str = match unsafe { CStr::from_ptr(host) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(_) => return ptr::null_mut(),
-    };
-
-    let client = unsafe { &*ptr };
-    match client.get_host_info(host_str, port) {
-        Ok(info) => match serde_json::to_string(&info) {
-            Ok(json) => match CString::new(json) {
-                Ok(c_str) => c_str.into_raw(),
-                Err(_) => ptr::null_mut(),
-            },
-            Err(_) => ptr::null_mut(),
-        },
-        Err(_) => ptr::null_mut(),
-    }
+    out: *mut *mut c_char,
+) -> i32 {
+    let result = unsafe { ffi_ref(ptr) }.and_then(|client| {
+        let host = unsafe { ffi_string(host, "host") }?;
+        client.get_host_info(host, port)
+    });
+    unsafe { finish(result, out) }
 }
 
 /// Execute a command
-/// Returns JSON string with command output, or NULL on error
 /// args_json: JSON array of strings, or NULL for empty array
+/// On success `out` receives the command output as a JSON string
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `host`, `command` and `args_json` (if not NULL)
+/// must be NUL-terminated strings; `out` must be NULL or a valid pointer
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn halvor_client_execute_command(
     ptr: HalvorClientPtr,
@@ -167,40 +244,120 @@ pub unsafe extern "C" fn halvor_client_execute_command(
     port: u16,
     command: *const c_char,
     args_json: *const c_char,
-) -> *mut c_char {
-    if ptr.is_null() || host.is_null() || command.is_null() {
-        return ptr::null_mut();
-    }
+    out: *mut *mut c_char,
+) -> i32 {
+    let result = unsafe { ffi_ref(ptr) }.and_then(|client| {
+        let host = unsafe { ffi_string(host, "host") }?;
+        let command = unsafe { ffi_string(command, "command") }?;
+        let args: Vec<String> = if args_json.is_null() {
+            Vec::new()
+        } else {
+            let json = unsafe { ffi_string(args_json, "args_json") }?;
+            serde_json::from_str(&json).map_err(|e| {
+                HalvorError::Internal(format!("args_json is not a JSON string array: {}", e))
+            })?
+        };
+        client.execute_command(host, port, command, args)
+    });
+    unsafe { finish(result, out) }
+}
 
-    let host_str = match unsafe { CStr::from_ptr(host) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(_) => return ptr::null_mut(),
-    };
+/// Shared body for the service control functions
+unsafe fn service_call(
+    ptr: HalvorClientPtr,
+    host: *const c_char,
+    port: u16,
+    service: *const c_char,
+    out: *mut *mut c_char,
+    call: fn(
+        &HalvorClient,
+        String,
+        u16,
+        String,
+    ) -> Result<crate::agent::server::ServiceStatus, HalvorError>,
+) -> i32 {
+    let result = unsafe { ffi_ref(ptr) }.and_then(|client| {
+        let host = unsafe { ffi_string(host, "host") }?;
+        let service = unsafe { ffi_string(service, "service") }?;
+        call(client, host, port, service)
+    });
+    unsafe { finish(result, out) }
+}
 
-    let command_str = match unsafe { CStr::from_ptr(command) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(_) => return ptr::null_mut(),
-    };
+/// Restart a service
+/// On success `out` receives ServiceStatus as JSON
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `host` and `service` must be NUL-terminated strings;
+/// `out` must be NULL or a valid pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn halvor_client_restart_service(
+    ptr: HalvorClientPtr,
+    host: *const c_char,
+    port: u16,
+    service: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    unsafe { service_call(ptr, host, port, service, out, HalvorClient::restart_service) }
+}
 
-    let args: Vec<String> = if args_json.is_null() {
-        Vec::new()
-    } else {
-        match unsafe { CStr::from_ptr(args_json) }.to_str() {
-            Ok(json) => match serde_json::from_str::<Vec<String>>(json) {
-                Ok(v) => v,
-                Err(_) => Vec::new(),
-            },
-            Err(_) => Vec::new(),
-        }
-    };
+/// Start a service
+/// On success `out` receives ServiceStatus as JSON
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `host` and `service` must be NUL-terminated strings;
+/// `out` must be NULL or a valid pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn halvor_client_start_service(
+    ptr: HalvorClientPtr,
+    host: *const c_char,
+    port: u16,
+    service: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    unsafe { service_call(ptr, host, port, service, out, HalvorClient::start_service) }
+}
 
-    let client = unsafe { &*ptr };
-    match client.execute_command(host_str, port, command_str, args) {
-        Ok(output) => match CString::new(output) {
-            Ok(c_str) => c_str.into_raw(),
-            Err(_) => ptr::null_mut(),
-        },
-        Err(_) => ptr::null_mut(),
+/// Stop a service
+/// On success `out` receives ServiceStatus as JSON
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `host` and `service` must be NUL-terminated strings;
+/// `out` must be NULL or a valid pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn halvor_client_stop_service(
+    ptr: HalvorClientPtr,
+    host: *const c_char,
+    port: u16,
+    service: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    unsafe { service_call(ptr, host, port, service, out, HalvorClient::stop_service) }
+}
+
+/// Get service status
+/// On success `out` receives ServiceStatus as JSON
+///
+/// # Safety
+/// `ptr` must come from `halvor_client_new`; `host` and `service` must be NUL-terminated strings;
+/// `out` must be NULL or a valid pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn halvor_client_get_service_status(
+    ptr: HalvorClientPtr,
+    host: *const c_char,
+    port: u16,
+    service: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    unsafe {
+        service_call(
+            ptr,
+            host,
+            port,
+            service,
+            out,
+            HalvorClient::get_service_status,
+        )
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::agent::discovery::DiscoveredHost;
-use crate::agent::server::HostInfo;
+use crate::agent::server::{HostInfo, ServiceAction, ServiceStatus};
 use crate::agent::{api::AgentClient, discovery::HostDiscovery};
-use anyhow::Result;
+use crate::ffi::error::HalvorError;
 
 /// Client for discovering and interacting with Halvor agents
 pub struct HalvorClient {
@@ -21,38 +21,92 @@ impl HalvorClient {
 
     /// Discover all available agents on the network
     #[halvor_ffi_macro::multi_platform_export]
-    pub fn discover_agents(&self) -> Result<Vec<DiscoveredHost>, String> {
-        self.discovery.discover_all().map_err(|e| e.to_string())
+    pub fn discover_agents(&self) -> Result<Vec<DiscoveredHost>, HalvorError> {
+        self.discovery.discover_all().map_err(HalvorError::from)
     }
 
     /// Discover agents via Tailscale
     #[halvor_ffi_macro::multi_platform_export]
-    pub fn discover_via_tailscale(&self) -> Result<Vec<DiscoveredHost>, String> {
+    pub fn discover_via_tailscale(&self) -> Result<Vec<DiscoveredHost>, HalvorError> {
         self.discovery
             .discover_via_tailscale()
-            .map_err(|e| e.to_string())
+            .map_err(HalvorError::from)
     }
 
     /// Discover agents on local network
     #[halvor_ffi_macro::multi_platform_export]
-    pub fn discover_via_local_network(&self) -> Result<Vec<DiscoveredHost>, String> {
+    pub fn discover_via_local_network(&self) -> Result<Vec<DiscoveredHost>, HalvorError> {
         self.discovery
             .discover_via_local_network()
-            .map_err(|e| e.to_string())
+            .map_err(HalvorError::from)
     }
 
     /// Ping an agent at the given address
     #[halvor_ffi_macro::multi_platform_export]
-    pub fn ping_agent(&self, host: String, port: u16) -> Result<bool, String> {
+    pub fn ping_agent(&self, host: String, port: u16) -> Result<bool, HalvorError> {
         let client = AgentClient::new(&host, port);
-        client.ping().map_err(|e| e.to_string())
+        client.ping().map_err(HalvorError::from)
     }
 
     /// Get host information from an agent
     #[halvor_ffi_macro::multi_platform_export]
-    pub fn get_host_info(&self, host: String, port: u16) -> Result<HostInfo, String> {
+    pub fn get_host_info(&self, host: String, port: u16) -> Result<HostInfo, HalvorError> {
         let client = AgentClient::new(&host, port);
-        client.get_host_info().map_err(|e| e.to_string())
+        client.get_host_info().map_err(HalvorError::from)
+    }
+
+    /// Restart a docker service on a remote agent
+    #[halvor_ffi_macro::multi_platform_export]
+    pub fn restart_service(
+        &self,
+        host: String,
+        port: u16,
+        service: String,
+    ) -> Result<ServiceStatus, HalvorError> {
+        let client = AgentClient::new(&host, port);
+        client.restart_service(&service).map_err(HalvorError::from)
+    }
+
+    /// Start a docker service on a remote agent
+    #[halvor_ffi_macro::multi_platform_export]
+    pub fn start_service(
+        &self,
+        host: String,
+        port: u16,
+        service: String,
+    ) -> Result<ServiceStatus, HalvorError> {
+        let client = AgentClient::new(&host, port);
+        client
+            .control_service(&service, ServiceAction::Start)
+            .map_err(HalvorError::from)
+    }
+
+    /// Stop a docker service on a remote agent
+    #[halvor_ffi_macro::multi_platform_export]
+    pub fn stop_service(
+        &self,
+        host: String,
+        port: u16,
+        service: String,
+    ) -> Result<ServiceStatus, HalvorError> {
+        let client = AgentClient::new(&host, port);
+        client
+            .control_service(&service, ServiceAction::Stop)
+            .map_err(HalvorError::from)
+    }
+
+    /// Get the status of a docker service on a remote agent
+    #[halvor_ffi_macro::multi_platform_export]
+    pub fn get_service_status(
+        &self,
+        host: String,
+        port: u16,
+        service: String,
+    ) -> Result<ServiceStatus, HalvorError> {
+        let client = AgentClient::new(&host, port);
+        client
+            .get_service_status(&service)
+            .map_err(HalvorError::from)
     }
 
     /// Execute a command on a remote agent
@@ -63,18 +117,18 @@ impl HalvorClient {
         port: u16,
         command: String,
         args: Vec<String>,
-    ) -> Result<String, String> {
+    ) -> Result<String, HalvorError> {
         let client = AgentClient::new(&host, port);
         let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         client
             .execute_command(&command, &args_refs)
-            .map_err(|e| e.to_string())
+            .map_err(HalvorError::from)
     }
 
     /// Get the version of the Halvor client
     /// This is a test function to verify macro generation works correctly
     #[halvor_ffi_macro::multi_platform_export]
-    pub fn get_version(&self) -> Result<String, String> {
+    pub fn get_version(&self) -> Result<String, HalvorError> {
         Ok(env!("CARGO_PKG_VERSION").to_string())
     }
 }
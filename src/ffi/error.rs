@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::ErrorKind;
+
+/// Error returned across the FFI boundary, categorized so mobile callers can branch on it
+/// Serializes as `{"code": "network", "message": "..."}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum HalvorError {
+    /// Agent unreachable, connection refused/reset, timeouts
+    Network(String),
+    /// Rejected token or insufficient permissions
+    Auth(String),
+    /// Requested host, service or file does not exist
+    NotFound(String),
+    /// Anything else (bad input, protocol or serialization errors, agent failures)
+    Internal(String),
+}
+
+impl HalvorError {
+    /// Stable integer code used by the C FFI (0 is reserved for success)
+    pub fn code(&self) -> i32 {
+        match self {
+            HalvorError::Network(_) => 1,
+            HalvorError::Auth(_) => 2,
+            HalvorError::NotFound(_) => 3,
+            HalvorError::Internal(_) => 4,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            HalvorError::Network(m)
+            | HalvorError::Auth(m)
+            | HalvorError::NotFound(m)
+            | HalvorError::Internal(m) => m,
+        }
+    }
+
+    /// Classify an error message when no typed cause is available
+    fn from_message(message: String) -> Self {
+        let lower = message.to_lowercase();
+        if [
+            "unauthorized",
+            "invalid token",
+            "authentication",
+            "permission denied",
+        ]
+        .iter()
+        .any(|needle| lower.contains(needle))
+        {
+            HalvorError::Auth(message)
+        } else if ["not found", "no such"]
+            .iter()
+            .any(|needle| lower.contains(needle))
+        {
+            HalvorError::NotFound(message)
+        } else {
+            HalvorError::Internal(message)
+        }
+    }
+}
+
+impl fmt::Display for HalvorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for HalvorError {}
+
+impl From<anyhow::Error> for HalvorError {
+    fn from(err: anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        let io_kind = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+            .map(|io| io.kind());
+
+        match io_kind {
+            Some(
+                ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::NotConnected
+                | ErrorKind::AddrNotAvailable
+                | ErrorKind::HostUnreachable
+                | ErrorKind::NetworkUnreachable
+                | ErrorKind::TimedOut
+                | ErrorKind::BrokenPipe
+                | ErrorKind::UnexpectedEof,
+            ) => HalvorError::Network(message),
+            Some(ErrorKind::PermissionDenied) => HalvorError::Auth(message),
+            Some(ErrorKind::NotFound) => HalvorError::NotFound(message),
+            _ => HalvorError::from_message(message),
+        }
+    }
+}
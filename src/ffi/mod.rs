@@ -5,19 +5,29 @@
 // Functions in this module use existing types from the main crate:
 // - crate::agent::discovery::DiscoveredHost
 // - crate::agent::server::HostInfo
+// - crate::agent::server::ServiceStatus
 //
 // The build script (build.rs) automatically generates platform-specific bindings
 // from functions marked with export macros.
 
+#[cfg(not(target_arch = "wasm32"))]
 pub mod client;
+pub mod error;
+pub mod transport;
+
+// WASM entry points for the web UI (discovery over HTTP, since browsers have no sockets)
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
 
 // C FFI bindings for Swift (only compiled for non-WASM targets)
 #[cfg(not(target_arch = "wasm32"))]
 pub mod c_ffi;
 
 // Re-export for convenience
+#[cfg(not(target_arch = "wasm32"))]
 pub use client::HalvorClient;
+pub use error::HalvorError;
 
 // Re-export existing types (no duplicates)
 pub use crate::agent::discovery::DiscoveredHost;
-pub use crate::agent::server::HostInfo;
+pub use crate::agent::server::{HostInfo, ServiceStatus};
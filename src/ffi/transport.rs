@@ -0,0 +1,24 @@
+// Transport abstraction for agent discovery
+//
+// Native builds reach agents directly over sockets (Tailscale lookup + TCP probes).
+// Browsers can't open raw sockets, so the WASM build uses `FetchTransport` (src/ffi/wasm.rs),
+// which asks a halvor web server (`halvor agent start --web-port`) to run discovery on its behalf
+// via `GET /api/discover-agents`.
+
+use crate::agent::discovery::DiscoveredHost;
+use crate::ffi::error::HalvorError;
+
+/// A way of discovering halvor agents
+/// Futures aren't required to be `Send` since the WASM transport runs on the browser's event loop
+#[allow(async_fn_in_trait)]
+pub trait DiscoveryTransport {
+    async fn discover_agents(&self) -> Result<Vec<DiscoveredHost>, HalvorError>;
+}
+
+/// Native transport: probe agents over sockets
+#[cfg(not(target_arch = "wasm32"))]
+impl DiscoveryTransport for crate::ffi::client::HalvorClient {
+    async fn discover_agents(&self) -> Result<Vec<DiscoveredHost>, HalvorError> {
+        crate::ffi::client::HalvorClient::discover_agents(self)
+    }
+}
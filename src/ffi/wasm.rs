@@ -0,0 +1,113 @@
+// WASM entry points for the web UI
+// Only compiled for wasm32; discovery goes through `FetchTransport` since browsers have no sockets
+
+use crate::agent::discovery::DiscoveredHost;
+use crate::ffi::error::HalvorError;
+use crate::ffi::transport::DiscoveryTransport;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, Response};
+
+/// Default API base: the halvor web server that serves the UI also serves `/api`
+const DEFAULT_API_BASE: &str = "/api";
+
+/// Response envelope used by the web server (`ApiResponse` in services/web.rs)
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    success: bool,
+    data: Option<T>,
+    error: Option<String>,
+}
+
+/// Discovery over HTTP via a halvor web server
+pub struct FetchTransport {
+    api_base: String,
+}
+
+impl FetchTransport {
+    pub fn new(api_base: &str) -> Self {
+        Self {
+            api_base: api_base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// GET `{api_base}{endpoint}` and unwrap the `ApiResponse` envelope
+    async fn get<T: for<'de> Deserialize<'de>>(&self, endpoint: &str) -> Result<T, HalvorError> {
+        let url = format!("{}{}", self.api_base, endpoint);
+        let network = |e: JsValue| HalvorError::Network(format!("{}: {:?}", url, e));
+
+        let window = web_sys::window()
+            .ok_or_else(|| HalvorError::Internal("No window available".to_string()))?;
+        let init = RequestInit::new();
+        init.set_method("GET");
+        let request = Request::new_with_str_and_init(&url, &init).map_err(network)?;
+        request
+            .headers()
+            .set("Accept", "application/json")
+            .map_err(network)?;
+
+        let response: Response = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(network)?
+            .dyn_into()
+            .map_err(network)?;
+        let status = response.status();
+        let body = JsFuture::from(response.text().map_err(network)?)
+            .await
+            .map_err(network)?
+            .as_string()
+            .unwrap_or_default();
+        let envelope: ApiResponse<T> = serde_json::from_str(&body).map_err(|e| {
+            HalvorError::Internal(format!("Unexpected response from {}: {}", url, e))
+        })?;
+
+        match envelope {
+            ApiResponse {
+                success: true,
+                data: Some(data),
+                ..
+            } => Ok(data),
+            ApiResponse { error, .. } => {
+                let message = error.unwrap_or_else(|| format!("{} failed ({})", url, status));
+                Err(match status {
+                    401 | 403 => HalvorError::Auth(message),
+                    404 => HalvorError::NotFound(message),
+                    _ => HalvorError::Internal(message),
+                })
+            }
+        }
+    }
+}
+
+impl Default for FetchTransport {
+    fn default() -> Self {
+        Self::new(DEFAULT_API_BASE)
+    }
+}
+
+impl DiscoveryTransport for FetchTransport {
+    async fn discover_agents(&self) -> Result<Vec<DiscoveredHost>, HalvorError> {
+        self.get("/discover-agents").await
+    }
+}
+
+/// Serialize a value into a JS object (via JSON, so field names match the serde output)
+fn to_js<T: serde::Serialize>(value: &T) -> JsValue {
+    serde_json::to_string(value)
+        .ok()
+        .and_then(|json| js_sys::JSON::parse(&json).ok())
+        .unwrap_or(JsValue::NULL)
+}
+
+/// Discover agents from the browser
+/// Resolves to an array of DiscoveredHost; rejects with `{code, message}` (see HalvorError)
+#[halvor_ffi_macro::wasm_export]
+#[wasm_bindgen(js_name = discoverAgentsWasm)]
+pub async fn discover_agents_wasm() -> Result<JsValue, JsValue> {
+    FetchTransport::default()
+        .discover_agents()
+        .await
+        .map(|hosts| to_js(&hosts))
+        .map_err(|e| to_js(&e))
+}
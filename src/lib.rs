@@ -28,6 +28,25 @@ pub enum Commands {
         /// Path to save database backup (only used with --db)
         #[arg(long)]
         path: Option<String>,
+        /// Keep only the N most recent backups of the service (defaults to BACKUP_RETENTION)
+        #[arg(long)]
+        keep: Option<usize>,
+        /// Verify every archive after creating the backup (fails if any is corrupt)
+        #[arg(long)]
+        verify: bool,
+        /// Sync volumes with rsync, hard-linking files unchanged since the previous backup
+        /// (falls back to tar; the backup is kept as a directory instead of zipped)
+        #[arg(long, conflicts_with_all = ["list", "env", "db", "encrypt"])]
+        incremental: bool,
+        /// Encrypt the backup with a passphrase (HALVOR_BACKUP_KEY or prompt); restore detects it
+        #[arg(long)]
+        encrypt: bool,
+        /// Run this backup on a cron schedule (e.g. "0 3 * * *") via a systemd timer or crontab
+        #[arg(long, value_name = "CRON", conflicts_with_all = ["list", "env", "db", "encrypt"])]
+        install_schedule: Option<String>,
+        /// Remove the schedule installed with --install-schedule for this service and host
+        #[arg(long, conflicts_with_all = ["install_schedule", "list", "env", "db", "encrypt", "keep", "verify", "incremental"])]
+        uninstall_schedule: bool,
     },
     /// Restore services, config, or database
     Restore {
@@ -39,22 +58,51 @@ pub enum Commands {
         /// Specific backup timestamp to restore (required when service is specified)
         #[arg(long)]
         backup: Option<String>,
+        /// Show the contents of the backup instead of restoring it
+        #[arg(long)]
+        list: bool,
+        /// Only restore the named volume (repeatable)
+        #[arg(long = "volume", value_name = "NAME")]
+        volumes: Vec<String>,
     },
     /// Sync encrypted data between hal installations
     Sync {
         /// Pull data from remote instead of pushing
         #[arg(long)]
         pull: bool,
+        /// Compare local and remote data without transferring anything
+        #[arg(long, conflicts_with = "pull")]
+        status: bool,
+        /// Keep local values for entries changed on both sides since the last sync
+        #[arg(long, conflicts_with_all = ["theirs", "status"])]
+        ours: bool,
+        /// Keep remote values for entries changed on both sides since the last sync
+        #[arg(long, conflicts_with = "status")]
+        theirs: bool,
+        #[command(subcommand)]
+        command: Option<commands::sync::SyncCommands>,
     },
     /// List services or hosts
     List {
         /// Show verbose information
         #[arg(long)]
         verbose: bool,
+        /// List services on every configured host
+        #[arg(long)]
+        all: bool,
+        /// Maximum number of hosts to query concurrently (only used with --all)
+        #[arg(long, default_value_t = 4)]
+        parallel: usize,
+        /// Only show hosts whose name, IP, hostname or Tailscale name contains this (case-insensitive)
+        #[arg(long, value_name = "SUBSTR", conflicts_with = "all")]
+        filter: Option<String>,
+        /// Order hosts by name, IP, or reachability (status probes every host)
+        #[arg(long, default_value = "name", value_parser = ["name", "ip", "status"], conflicts_with = "all")]
+        sort: String,
     },
     /// Install a service on a host
     Install {
-        /// Service to install: docker, tailscale, portainer, npm
+        /// Service to install, e.g. docker, tailscale, portainer, watchtower (an unknown name lists them all)
         service: String,
         /// Portainer edition (ce or be) - only used with portainer
         #[arg(long, default_value = "ce")]
@@ -62,11 +110,35 @@ pub enum Commands {
         /// Install Portainer host (with UI) instead of agent - only used with portainer
         #[arg(long)]
         host: bool,
+        /// Comma-separated ACL tags to advertise (e.g. tag:server,tag:homelab) - only used with tailscale
+        #[arg(long, value_name = "TAGS")]
+        tailscale_tags: Option<String>,
+        /// Replace an existing Portainer install of a different edition - only used with portainer
+        #[arg(long)]
+        force: bool,
+        /// Seed the Portainer admin password (from PORTAINER_ADMIN_PASSWORD or a prompt if no value is given) - only used with portainer --host
+        #[arg(long, value_name = "PASSWORD", num_args = 0..=1)]
+        admin_password: Option<Option<String>>,
+        /// Seed the Portainer admin password from a local file - only used with portainer --host
+        #[arg(long, value_name = "FILE", conflicts_with = "admin_password")]
+        admin_password_file: Option<String>,
+        /// Install even if another service already uses a port the service needs
+        #[arg(long)]
+        ignore_port_conflicts: bool,
     },
     /// Uninstall a service from a host or halvor itself
     Uninstall {
-        /// Service to uninstall: npm, portainer, smb. If not provided, guided uninstall of halvor
+        /// Service to uninstall, e.g. smb or watchtower. If not provided, guided uninstall of halvor
         service: Option<String>,
+        /// Keep the service's Docker volumes (default)
+        #[arg(long, conflicts_with = "purge_volumes")]
+        keep_volumes: bool,
+        /// Delete the service's Docker volumes along with its containers
+        #[arg(long)]
+        purge_volumes: bool,
+        /// Skip the confirmation prompt
+        #[arg(long, short = 'y')]
+        yes: bool,
     },
     /// Provision a host (install Docker, Tailscale, Portainer)
     Provision {
@@ -76,18 +148,117 @@ pub enum Commands {
         /// Portainer edition to install (ce or be). Only used with --portainer-host
         #[arg(long, default_value = "ce")]
         portainer_edition: String,
+        /// Comma-separated Tailscale ACL tags to advertise (e.g. tag:server,tag:homelab)
+        #[arg(long, value_name = "TAGS")]
+        tailscale_tags: Option<String>,
+        /// Provision even if another service already uses a port Portainer needs
+        #[arg(long)]
+        ignore_port_conflicts: bool,
+        /// Provision every configured host
+        #[arg(long)]
+        all: bool,
+        /// Maximum number of hosts to provision concurrently (only used with --all)
+        #[arg(long, default_value_t = 4)]
+        parallel: usize,
     },
     /// Setup and mount SMB shares
     Smb {
         /// Unmount and remove SMB mounts
         #[arg(long)]
         uninstall: bool,
+        /// Report whether each configured share is mounted and writable
+        #[arg(long)]
+        status: bool,
+        /// Mount any configured shares that are not currently mounted
+        #[arg(long)]
+        remount: bool,
+        /// With --remount, unmount and remount every share, even healthy ones
+        #[arg(long)]
+        force: bool,
     },
     /// Diagnose Docker daemon issues
     Docker {
         /// Run diagnostics instead of installing
         #[arg(long)]
         diagnose: bool,
+        /// Remove stopped containers, dangling images, and unused networks
+        #[arg(long)]
+        prune: bool,
+        /// Also remove unused volumes, named ones included, when pruning (implies --prune)
+        #[arg(long)]
+        prune_volumes: bool,
+        /// Run the operation on every configured host
+        #[arg(long)]
+        all: bool,
+        /// Maximum number of hosts to operate on concurrently (only used with --all)
+        #[arg(long, default_value_t = 4)]
+        parallel: usize,
+        #[command(subcommand)]
+        command: Option<commands::docker::DockerCommands>,
+    },
+    /// Copy a compose file to a host and bring its services up, down, or restart them
+    Compose {
+        /// Compose file, relative to the homelab directory or its compose/ folder
+        file: String,
+        /// Action to run
+        #[arg(value_enum)]
+        action: commands::compose::ComposeAction,
+        /// Env file to copy alongside the compose file and pass with --env-file
+        #[arg(long, value_name = "FILE")]
+        env_file: Option<String>,
+        /// Only act on this service from the compose file
+        #[arg(long, value_name = "NAME")]
+        service: Option<String>,
+        /// Limit file uploads to this many kilobits per second
+        #[arg(long, value_name = "KBPS")]
+        bwlimit: Option<u32>,
+    },
+    /// Run a one-off command on a configured host (e.g. hal exec frigg -- df -h)
+    Exec {
+        /// Host to run the command on (resolved through the config)
+        host: String,
+        /// Run the command through `sh -c` (pipes, globs, &&) instead of executing it directly
+        #[arg(long)]
+        shell: bool,
+        /// Command and arguments, after `--`
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+    /// Open an SSH session to a configured host, trying its IP then Tailscale addresses
+    /// (e.g. hal ssh maple, or hal ssh maple -- uptime)
+    Ssh {
+        /// Host to connect to (resolved through the config)
+        hostname: String,
+        /// User to log in as (defaults to the host's SSH user)
+        #[arg(long, short)]
+        user: Option<String>,
+        /// Offer to remove stale known_hosts entries for the host's addresses first
+        #[arg(long)]
+        fix_keys: bool,
+        /// Install your SSH public key on the host before connecting
+        #[arg(long = "keys")]
+        copy_keys: bool,
+        /// Extra arguments passed to ssh after the host, e.g. a remote command after `--`
+        #[arg(last = true, value_name = "ARGS")]
+        args: Vec<String>,
+    },
+    /// Manage known_hosts entries for a host's addresses (e.g. after reprovisioning it)
+    SshKeys {
+        #[command(subcommand)]
+        command: commands::ssh::SshKeysCommands,
+    },
+    /// Show a container's logs on a configured host (e.g. hal logs maple sonarr --follow)
+    Logs {
+        /// Host the container runs on (resolved through the config)
+        host: String,
+        /// Container name
+        container: String,
+        /// Keep streaming new log lines until Ctrl-C
+        #[arg(long, short)]
+        follow: bool,
+        /// Number of lines to show from the end of the logs, or "all"
+        #[arg(long, value_name = "N", default_value = "100")]
+        tail: String,
     },
     /// Automatically create proxy hosts in Nginx Proxy Manager
     Npm {
@@ -97,6 +268,18 @@ pub enum Commands {
         /// Create proxy host for a specific service (e.g., portainer:9000 or npm:81)
         #[arg(long)]
         service: Option<String>,
+        /// Delete the proxy host for a domain (e.g., sonarr.local)
+        #[arg(long, value_name = "DOMAIN", conflicts_with_all = ["service", "update"])]
+        delete: Option<String>,
+        /// Point an existing service's proxy host at this host (e.g., sonarr:8989)
+        #[arg(long, value_name = "SERVICE", conflicts_with = "service")]
+        update: Option<String>,
+        /// Compose profile to treat as active (repeatable); services behind other profiles are skipped
+        #[arg(long = "profile", value_name = "NAME")]
+        profiles: Vec<String>,
+        /// Request a Let's Encrypt certificate (using ACME_EMAIL) for each created proxy host
+        #[arg(long, conflicts_with_all = ["delete", "update"])]
+        ssl: bool,
     },
     /// Build and push VPN container image to GitHub Container Registry
     Vpn {
@@ -119,14 +302,39 @@ pub enum Commands {
         #[command(subcommand)]
         command: commands::config::DbCommands,
     },
-    /// Check for and install updates
+    /// Check for and install updates (history: list past updates)
+    #[command(args_conflicts_with_subcommands = true)]
     Update {
         /// Use experimental channel for updates (version less, continuously updated)
         #[arg(long)]
         experimental: bool,
-        /// Force download and install the latest version (skips version check)
+        /// Force download and install the latest version (skips version check and removes a pin)
         #[arg(long)]
         force: bool,
+        /// Install this exact release tag (e.g. v0.8.3) and pin to it
+        #[arg(long, value_name = "TAG", conflicts_with_all = ["experimental", "force", "unpin"])]
+        version: Option<String>,
+        /// Remove a version pin so update checks resume
+        #[arg(long)]
+        unpin: bool,
+        /// Restore the binary that was replaced by the last update
+        #[arg(long, conflicts_with_all = ["experimental", "force", "version", "unpin"])]
+        rollback: bool,
+        /// Only report whether an update is available (with its release notes); installs nothing
+        #[arg(long, conflicts_with_all = ["force", "version", "unpin", "rollback"])]
+        check: bool,
+        #[command(subcommand)]
+        command: Option<commands::update::UpdateCommands>,
+    },
+    /// Manage configured hosts without the interactive setup (add, rename)
+    Host {
+        #[command(subcommand)]
+        command: commands::host::HostCommands,
+    },
+    /// Serve a local status dashboard API (hosts, health, agents)
+    Web {
+        #[command(subcommand)]
+        command: commands::web::WebCommands,
     },
     /// Manage halvor agent daemon (start/stop/status/discover)
     Agent {
@@ -135,6 +343,9 @@ pub enum Commands {
     },
     /// Build applications for different platforms
     Build {
+        /// Only check that the toolchain, targets and linkers are installed; don't build
+        #[arg(long, global = true)]
+        check: bool,
         #[command(subcommand)]
         command: commands::build::BuildCommands,
     },
@@ -148,4 +359,84 @@ pub enum Commands {
         #[command(subcommand)]
         command: commands::generate::GenerateCommands,
     },
+    /// Print a shell completion script (e.g. hal completions zsh > ~/.zfunc/_hal)
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+        /// Command name the completions are registered for
+        #[arg(long, default_value = "hal")]
+        bin_name: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(subcommand)]
+        command: Commands,
+    }
+
+    fn parse(args: &[&str]) -> Result<Commands, clap::Error> {
+        TestCli::try_parse_from(std::iter::once("hal").chain(args.iter().copied()))
+            .map(|cli| cli.command)
+    }
+
+    #[test]
+    fn ssh_takes_a_hostname_and_defaults() {
+        let Ok(Commands::Ssh {
+            hostname,
+            user,
+            fix_keys,
+            copy_keys,
+            args,
+        }) = parse(&["ssh", "maple"])
+        else {
+            panic!("expected the ssh command");
+        };
+        assert_eq!(hostname, "maple");
+        assert_eq!(user, None);
+        assert!(!fix_keys && !copy_keys);
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn ssh_parses_user_and_key_flags() {
+        for user_flag in ["-u", "--user"] {
+            let Ok(Commands::Ssh {
+                user,
+                fix_keys,
+                copy_keys,
+                ..
+            }) = parse(&["ssh", "maple", user_flag, "root", "--fix-keys", "--keys"])
+            else {
+                panic!("expected the ssh command");
+            };
+            assert_eq!(user.as_deref(), Some("root"));
+            assert!(fix_keys && copy_keys);
+        }
+    }
+
+    #[test]
+    fn ssh_passes_everything_after_double_dash_to_ssh() {
+        let Ok(Commands::Ssh { hostname, args, .. }) =
+            parse(&["ssh", "maple", "--", "uptime", "-p", "--keys"])
+        else {
+            panic!("expected the ssh command");
+        };
+        assert_eq!(hostname, "maple");
+        assert_eq!(args, ["uptime", "-p", "--keys"]);
+    }
+
+    #[test]
+    fn ssh_rejects_missing_host_and_stray_arguments() {
+        assert!(parse(&["ssh"]).is_err());
+        // A remote command has to come after `--`
+        assert!(parse(&["ssh", "maple", "uptime"]).is_err());
+        assert!(parse(&["ssh", "maple", "--user"]).is_err());
+    }
 }
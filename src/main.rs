@@ -3,22 +3,60 @@ pub mod agent;
 mod commands;
 pub mod config;
 pub mod db;
-pub mod ffi;
+// Reuse the library's FFI module: the client methods carry exported C symbols
+// that would otherwise be defined twice when linking the binary
+pub use halvor::ffi;
 pub mod services;
 pub mod utils;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
 #[derive(Parser)]
 #[command(name = "halvor")]
 #[command(about = "Homelab Automation Layer - CLI tool for managing homelab infrastructure", long_about = None)]
 #[command(version = commands::utils::get_version_string())]
 struct Cli {
-    /// Hostname to operate on (defaults to localhost if not provided)
+    /// Hostname to operate on (defaults to $HALVOR_HOST, then the configured default host, then this machine)
     #[arg(long, short = 'H', value_name = "HOSTNAME", global = true)]
     hostname: Option<String>,
 
+    /// Print the commands that would run instead of executing them (provision, install, docker)
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Output format for commands that display data (list, config)
+    #[arg(long, value_enum, global = true, default_value = "text")]
+    output: commands::OutputFormat,
+
+    /// Config directory or .env file to use instead of searching for one (or HALVOR_CONFIG)
+    #[arg(long, value_name = "PATH", global = true)]
+    config: Option<std::path::PathBuf>,
+
+    /// Profile to use for this command instead of the active one (see `hal config profile`)
+    #[arg(long, value_name = "NAME", global = true)]
+    profile: Option<String>,
+
+    /// Connect over this IP version first when a host has both (see `hal config set-prefer`)
+    #[arg(long, value_enum, value_name = "FAMILY", global = true)]
+    prefer: Option<config::config_manager::AddressFamily>,
+
+    /// Skip the startup check for a newer release (or set HALVOR_NO_UPDATE_CHECK=1)
+    #[arg(long, global = true)]
+    no_update_check: bool,
+
+    /// Seconds to wait for a locked database before failing (default: 5)
+    #[arg(long, value_name = "SECONDS", global = true)]
+    db_timeout: Option<u64>,
+
+    /// Show more detail: -v for debug output, -vv for trace (give before the subcommand)
+    #[arg(short, long, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only print warnings, errors and command results (give before the subcommand)
+    #[arg(short, long)]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -27,6 +65,11 @@ struct Cli {
 // Since main.rs is a binary crate, we need to reference the library crate
 use halvor::Commands;
 
+/// Full clap definition of the CLI (global flags plus every subcommand)
+fn build_cli() -> clap::Command {
+    Cli::command()
+}
+
 fn main() -> Result<()> {
     // Handle version flags before parsing (to show channel info)
     let args: Vec<String> = std::env::args().collect();
@@ -35,11 +78,42 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    // Check for updates (non-blocking, only in production mode)
-    commands::utils::check_for_updates();
-
     let cli = Cli::parse();
-    commands::handle_command(cli.hostname, cli.command)?;
+    if let Commands::Completions { shell, bin_name } = &cli.command {
+        return commands::completions::handle_completions(*shell, build_cli(), bin_name);
+    }
+
+    utils::logging::init(utils::logging::level_for(cli.verbose, cli.quiet));
+    if let Some(path) = &cli.config {
+        config::set_config_override(path)?;
+        // Handlers that live in the library crate read its copy of the config module
+        halvor::config::set_config_override(path)?;
+    }
+    if let Some(name) = &cli.profile {
+        config::config_manager::set_profile_override(name)?;
+        halvor::config::config_manager::set_profile_override(name)?;
+    }
+    if let Some(family) = cli.prefer {
+        use halvor::config::config_manager::AddressFamily as LibAddressFamily;
+        config::config_manager::set_address_family_override(family);
+        // The library has its own copy of the enum
+        halvor::config::config_manager::set_address_family_override(match family {
+            config::config_manager::AddressFamily::Ipv4 => LibAddressFamily::Ipv4,
+            config::config_manager::AddressFamily::Ipv6 => LibAddressFamily::Ipv6,
+        });
+    }
+    if let Some(secs) = cli.db_timeout {
+        let timeout = std::time::Duration::from_secs(secs);
+        db::set_busy_timeout(timeout);
+        // Handlers in the library crate open the database through its copy of the db module
+        halvor::db::set_busy_timeout(timeout);
+    }
+    // Check for updates (non-blocking, only in production mode); `hal update` does its own check.
+    // Runs once logging and the config/profile overrides are in place, since it reads both
+    if !cli.no_update_check && !matches!(cli.command, Commands::Update { .. }) {
+        commands::utils::check_for_updates();
+    }
+    commands::handle_command(cli.hostname, cli.dry_run, cli.output, cli.command)?;
 
     Ok(())
 }
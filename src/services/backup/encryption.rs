@@ -0,0 +1,151 @@
+// Encryption at rest for service backups (`backup --encrypt`)
+use crate::utils::crypto;
+use crate::utils::exec::{CommandExecutor, Executor};
+use crate::utils::ssh::shell_escape;
+use anyhow::{Context, Result};
+use std::process::{ChildStdin, ChildStdout, Stdio};
+
+/// Suffix added to each file of an encrypted backup
+pub const ENCRYPTED_SUFFIX: &str = ".enc";
+
+/// Run `read_cmd` and `write_cmd` on the host, streaming the output of the first through
+/// `transform` into the second. Data never touches local disk, so this works the same for local
+/// and remote backup paths.
+fn pipe_through(
+    exec: &Executor,
+    read_cmd: &str,
+    write_cmd: &str,
+    transform: impl FnOnce(&mut ChildStdout, &mut ChildStdin) -> Result<()>,
+) -> Result<()> {
+    let mut reader = exec.spawn_shell(read_cmd, Stdio::null(), Stdio::piped())?;
+    let mut writer = match exec.spawn_shell(write_cmd, Stdio::piped(), Stdio::null()) {
+        Ok(writer) => writer,
+        Err(e) => {
+            let _ = reader.kill();
+            let _ = reader.wait();
+            return Err(e);
+        }
+    };
+
+    let result = match (reader.stdout.take(), writer.stdin.take()) {
+        // Dropping `output` at the end of this arm closes the writer's stdin
+        (Some(mut input), Some(mut output)) => transform(&mut input, &mut output),
+        _ => Err(anyhow::anyhow!("Failed to open pipes for `{}`", read_cmd)),
+    };
+    if result.is_err() {
+        // Don't leave the reader blocked on a full pipe
+        let _ = reader.kill();
+    }
+    let read_status = reader.wait().context("Failed to wait for reader")?;
+    let write_status = writer.wait().context("Failed to wait for writer")?;
+
+    result.and_then(|()| {
+        if !read_status.success() {
+            anyhow::bail!("`{}` failed", read_cmd);
+        }
+        if !write_status.success() {
+            anyhow::bail!("`{}` failed", write_cmd);
+        }
+        Ok(())
+    })
+}
+
+/// Stream the output of `command` on the host through `transform` into `dst` on the same host
+/// `dst` is removed if anything fails.
+fn transform_into(
+    exec: &Executor,
+    command: &str,
+    dst: &str,
+    transform: impl FnOnce(&mut ChildStdout, &mut ChildStdin) -> Result<()>,
+) -> Result<()> {
+    let result = pipe_through(
+        exec,
+        command,
+        &format!("cat > {}", shell_escape(dst)),
+        transform,
+    );
+    if result.is_err() {
+        let _ = exec.execute_shell(&format!("rm -f {}", shell_escape(dst)));
+    }
+    result
+}
+
+/// Tar a Docker volume or host directory straight into an encrypted archive at `dst`
+/// The plaintext tarball only ever exists in the pipe, never on the backup share.
+pub fn archive_encrypted(exec: &Executor, source: &str, dst: &str, passphrase: &str) -> Result<()> {
+    let tar_cmd = format!(
+        "docker run --rm -v {}:/data:ro alpine tar czf - -C /data .",
+        shell_escape(source)
+    );
+    let encrypt = |input: &mut ChildStdout, output: &mut ChildStdin| {
+        crypto::encrypt_stream(input, output, passphrase)
+    };
+    if transform_into(exec, &tar_cmd, dst, encrypt).is_ok() {
+        return Ok(());
+    }
+    // Try with sudo
+    transform_into(exec, &format!("sudo {}", tar_cmd), dst, encrypt)
+        .with_context(|| format!("Failed to back up {}", source))
+}
+
+/// Encrypt `data` and write it to `dst` on the host
+pub fn write_encrypted<E: CommandExecutor>(
+    exec: &E,
+    dst: &str,
+    data: &[u8],
+    passphrase: &str,
+) -> Result<()> {
+    let mut encrypted = Vec::new();
+    crypto::encrypt_stream(&mut &data[..], &mut encrypted, passphrase)?;
+    exec.write_file(dst, &encrypted)
+}
+
+/// Check that an encrypted archive decrypts and lists end to end
+pub fn verify_encrypted_archive(exec: &Executor, path: &str, passphrase: &str) -> Result<bool> {
+    Ok(pipe_through(
+        exec,
+        &format!("cat {}", shell_escape(path)),
+        "tar tzf - > /dev/null",
+        |input, output| crypto::decrypt_stream(input, output, passphrase),
+    )
+    .is_ok())
+}
+
+/// Decrypt the archives of `volumes` from an encrypted backup into a new temporary directory
+/// on the host and return its path. Nothing is left behind if any archive fails to decrypt
+/// (e.g. a wrong passphrase); the caller removes the directory after restoring.
+pub fn decrypt_volumes(
+    exec: &Executor,
+    backup_dir: &str,
+    volumes: &[String],
+    passphrase: &str,
+) -> Result<String> {
+    let output = exec.execute_shell("mktemp -d")?;
+    let temp_dir = crate::utils::bytes_to_string(&output.stdout);
+    if !output.status.success() || temp_dir.is_empty() {
+        anyhow::bail!("Failed to create a temporary directory for decrypted archives");
+    }
+
+    for volume in volumes {
+        let archive = format!("{}.tar.gz", volume);
+        let result = transform_into(
+            exec,
+            &format!(
+                "cat {}",
+                shell_escape(&format!("{}/{}{}", backup_dir, archive, ENCRYPTED_SUFFIX))
+            ),
+            &format!("{}/{}", temp_dir, archive),
+            |input, output| crypto::decrypt_stream(input, output, passphrase),
+        );
+        if let Err(e) = result {
+            remove_temp_dir(exec, &temp_dir);
+            return Err(e.context(format!("Failed to decrypt volume '{}'", volume)));
+        }
+    }
+    Ok(temp_dir)
+}
+
+/// Delete a directory created by `decrypt_volumes`
+pub fn remove_temp_dir(exec: &Executor, temp_dir: &str) {
+    let _ = exec.execute_shell(&format!("rm -rf {}", shell_escape(temp_dir)));
+}
@@ -0,0 +1,158 @@
+use crate::utils::exec::CommandExecutor;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Name of the manifest file written into each backup directory
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Current manifest schema version (bump when the format changes incompatibly)
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Describes the contents of a backup directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub schema_version: u32,
+    pub hostname: String,
+    pub service: Option<String>,
+    pub created_at: String,
+    pub volumes: Vec<VolumeBackup>,
+    pub bind_mounts: Vec<BindMountBackup>,
+    pub containers: Vec<ContainerBackup>,
+    /// Set when the backup was verified after creation (false if any archive was corrupt)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+    /// Every archive and container config is stored with an `.enc` suffix (`backup --encrypt`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+}
+
+/// A Docker volume stored in the backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeBackup {
+    pub name: String,
+    pub archive: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// A container bind mount stored in the backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindMountBackup {
+    pub container: String,
+    pub source: String,
+    pub archive: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// A container's configuration (`docker inspect` output) stored in the backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerBackup {
+    pub name: String,
+    pub image: Option<String>,
+    pub inspect_file: String,
+}
+
+impl BackupManifest {
+    pub fn new(hostname: &str, service: Option<&str>) -> Self {
+        Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            hostname: hostname.to_string(),
+            service: service.map(|s| s.to_string()),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            volumes: Vec::new(),
+            bind_mounts: Vec::new(),
+            containers: Vec::new(),
+            verified: None,
+            encrypted: false,
+        }
+    }
+
+    /// Names of the volumes contained in the backup
+    pub fn volume_names(&self) -> Vec<&str> {
+        self.volumes.iter().map(|v| v.name.as_str()).collect()
+    }
+
+    /// File name an archive is stored under, accounting for encryption
+    pub fn stored_name(&self, archive: &str) -> String {
+        if self.encrypted {
+            format!("{}{}", archive, super::encryption::ENCRYPTED_SUFFIX)
+        } else {
+            archive.to_string()
+        }
+    }
+
+    /// Write the manifest into `backup_dir`
+    pub fn write<E: CommandExecutor>(&self, exec: &E, backup_dir: &str) -> Result<()> {
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize backup manifest")?;
+        exec.write_file(
+            &format!("{}/{}", backup_dir, MANIFEST_FILE_NAME),
+            content.as_bytes(),
+        )
+    }
+
+    /// Read the manifest from `backup_dir`
+    /// Returns None for legacy backups created before manifests existed
+    pub fn read<E: CommandExecutor>(exec: &E, backup_dir: &str) -> Result<Option<Self>> {
+        let path = format!("{}/{}", backup_dir, MANIFEST_FILE_NAME);
+        if !exec.file_exists(&path)? {
+            return Ok(None);
+        }
+
+        let content = exec.read_file(&path)?;
+        let manifest: BackupManifest = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse backup manifest: {}", path))?;
+        if manifest.schema_version > MANIFEST_SCHEMA_VERSION {
+            anyhow::bail!(
+                "Backup manifest {} uses schema version {} but this version of halvor only understands up to {}. Please update halvor.",
+                path,
+                manifest.schema_version,
+                MANIFEST_SCHEMA_VERSION
+            );
+        }
+        Ok(Some(manifest))
+    }
+}
+
+/// Save a container's `docker inspect` output into `backup_dir`
+/// With an `encrypt` passphrase only the encrypted `.enc` file is written.
+/// Returns the manifest entry describing it
+pub fn backup_container_config<E: CommandExecutor>(
+    exec: &E,
+    container: &str,
+    backup_dir: &str,
+    encrypt: Option<&str>,
+) -> Result<ContainerBackup> {
+    let mut output = exec.execute_simple("docker", &["inspect", container])?;
+    if !output.status.success() {
+        // Try with sudo
+        output = exec.execute_simple("sudo", &["docker", "inspect", container])?;
+        if !output.status.success() {
+            anyhow::bail!("Failed to inspect container: {}", container);
+        }
+    }
+
+    let inspect = String::from_utf8_lossy(&output.stdout).to_string();
+    let image = serde_json::from_str::<serde_json::Value>(&inspect)
+        .ok()
+        .and_then(|v| v[0]["Config"]["Image"].as_str().map(|s| s.to_string()));
+
+    let inspect_file = format!("{}.inspect.json", container);
+    let path = format!("{}/{}", backup_dir, inspect_file);
+    match encrypt {
+        Some(passphrase) => super::encryption::write_encrypted(
+            exec,
+            &format!("{}{}", path, super::encryption::ENCRYPTED_SUFFIX),
+            inspect.as_bytes(),
+            passphrase,
+        )?,
+        None => exec.write_file(&path, inspect.as_bytes())?,
+    }
+
+    Ok(ContainerBackup {
+        name: container.to_string(),
+        image,
+        inspect_file,
+    })
+}
@@ -0,0 +1,634 @@
+// Scheduled backups (`backup --install-schedule` / `--uninstall-schedule`)
+use crate::utils::exec::{CommandExecutor, Executor};
+use crate::utils::ssh::shell_escape;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Local, TimeZone};
+
+const SYSTEMD_UNIT_DIR: &str = "/etc/systemd/system";
+const WEEKDAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// A five-field cron expression with every field expanded to the values it matches
+#[derive(Debug)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days: Vec<u32>,
+    months: Vec<u32>,
+    /// 0 = Sunday
+    weekdays: Vec<u32>,
+}
+
+impl CronSchedule {
+    /// Parse `minute hour day-of-month month day-of-week` (or `@daily`, `@weekly`, ...)
+    /// Fields take `*`, numbers, ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `0-30/10`);
+    /// months and weekdays also accept names (`jan`, `mon`).
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        let expanded = match expression {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            anyhow::bail!(
+                "Invalid schedule '{}': expected 5 fields (minute hour day-of-month month day-of-week), e.g. \"0 3 * * *\"",
+                expression
+            );
+        }
+
+        let field = |index: usize, name: &str, min: u32, max: u32, names: &[&str]| {
+            parse_field(fields[index], min, max, names).with_context(|| {
+                format!(
+                    "Invalid {} field '{}' in '{}'",
+                    name, fields[index], expression
+                )
+            })
+        };
+        let minutes = field(0, "minute", 0, 59, &[])?;
+        let hours = field(1, "hour", 0, 23, &[])?;
+        let days = field(2, "day-of-month", 1, 31, &[])?;
+        let months = field(3, "month", 1, 12, &MONTH_NAMES)?;
+        // 7 is Sunday too
+        let mut weekdays: Vec<u32> = field(4, "day-of-week", 0, 7, &WEEKDAY_NAMES)?
+            .into_iter()
+            .map(|d| d % 7)
+            .collect();
+        weekdays.sort_unstable();
+        weekdays.dedup();
+
+        // cron runs when *either* day field matches, systemd only when both do
+        if fields[2] != "*" && fields[4] != "*" {
+            anyhow::bail!(
+                "Schedules restricting both day-of-month and day-of-week aren't supported: '{}'",
+                expression
+            );
+        }
+
+        Ok(Self {
+            expression: expanded.to_string(),
+            minutes,
+            hours,
+            days,
+            months,
+            weekdays,
+        })
+    }
+
+    /// The expression as written to a crontab
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The equivalent systemd `OnCalendar=` value
+    pub fn on_calendar(&self) -> String {
+        let list = |values: &[u32], all: usize, format: &dyn Fn(u32) -> String| {
+            if values.len() == all {
+                "*".to_string()
+            } else {
+                values
+                    .iter()
+                    .map(|v| format(*v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }
+        };
+        let two_digits = |v: u32| format!("{:02}", v);
+
+        let date = format!(
+            "*-{}-{} {}:{}:00",
+            list(&self.months, 12, &two_digits),
+            list(&self.days, 31, &two_digits),
+            list(&self.hours, 24, &two_digits),
+            list(&self.minutes, 60, &two_digits),
+        );
+        if self.weekdays.len() == 7 {
+            date
+        } else {
+            let weekdays = list(&self.weekdays, 7, &|v| {
+                WEEKDAY_NAMES[v as usize].to_string()
+            });
+            format!("{} {}", weekdays, date)
+        }
+    }
+
+    /// The first time after `from` the schedule fires, in local time
+    /// `None` if it never does (e.g. February 30th).
+    pub fn next_after(&self, from: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut day = from.date_naive();
+        // Leap days can be up to eight years apart
+        for _ in 0..(366 * 8) {
+            if self.months.contains(&day.month())
+                && self.days.contains(&day.day())
+                && self
+                    .weekdays
+                    .contains(&day.weekday().num_days_from_sunday())
+            {
+                for &hour in &self.hours {
+                    for &minute in &self.minutes {
+                        // Times skipped by a DST change don't exist and are passed over
+                        let Some(time) = day.and_hms_opt(hour, minute, 0) else {
+                            continue;
+                        };
+                        if let Some(candidate) = Local.from_local_datetime(&time).earliest()
+                            && candidate > from
+                        {
+                            return Some(candidate);
+                        }
+                    }
+                }
+            }
+            day = day.succ_opt()?;
+        }
+        None
+    }
+}
+
+/// Expand one cron field into the sorted values it matches
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Vec<u32>> {
+    let value = |s: &str| -> Result<u32> {
+        if let Some(index) = names.iter().position(|n| n.eq_ignore_ascii_case(s)) {
+            // Month names start at 1, weekday names at 0 (Sunday)
+            return Ok(index as u32 + min);
+        }
+        let v: u32 = s
+            .parse()
+            .with_context(|| format!("'{}' is not a number", s))?;
+        if v < min || v > max {
+            anyhow::bail!("{} is out of range {}-{}", v, min, max);
+        }
+        Ok(v)
+    };
+
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .with_context(|| format!("Invalid step '{}'", step))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/15` means every 15 starting at 5
+            (start, if step > 1 { max } else { start })
+        };
+        if start > end {
+            anyhow::bail!("Range {} is backwards", range);
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+/// What a scheduled backup runs:
+/// `hal backup [service] [-H host] [--keep N] [--verify] [--incremental]`
+pub struct ScheduledBackup<'a> {
+    pub service: Option<&'a str>,
+    pub hostname: Option<&'a str>,
+    pub keep: Option<usize>,
+    pub verify: bool,
+    pub incremental: bool,
+}
+
+impl ScheduledBackup<'_> {
+    /// Unit / crontab marker name, e.g. `halvor-backup-maple-sonarr`
+    pub fn unit_name(&self) -> Result<String> {
+        let mut name = "halvor-backup".to_string();
+        for part in [self.hostname, self.service].into_iter().flatten() {
+            if part.is_empty()
+                || !part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            {
+                anyhow::bail!(
+                    "'{}' can't be used in a schedule name (letters, digits, '-', '_' and '.' only)",
+                    part
+                );
+            }
+            name.push('-');
+            name.push_str(part);
+        }
+        Ok(name)
+    }
+
+    fn description(&self) -> String {
+        let what = match self.service {
+            Some(service) => format!("halvor backup of {}", service),
+            None => "halvor backup of all running services".to_string(),
+        };
+        match self.hostname {
+            Some(hostname) => format!("{} on {}", what, hostname),
+            None => what,
+        }
+    }
+
+    fn args(&self) -> Vec<String> {
+        let mut args = vec!["backup".to_string()];
+        args.extend(self.service.map(str::to_string));
+        if let Some(hostname) = self.hostname {
+            args.extend(["-H".to_string(), hostname.to_string()]);
+        }
+        if let Some(keep) = self.keep {
+            args.extend(["--keep".to_string(), keep.to_string()]);
+        }
+        if self.verify {
+            args.push("--verify".to_string());
+        }
+        if self.incremental {
+            args.push("--incremental".to_string());
+        }
+        args
+    }
+}
+
+enum Scheduler {
+    Systemd,
+    Cron,
+}
+
+fn detect_scheduler(exec: &Executor) -> Result<Scheduler> {
+    // /run/systemd/system only exists when systemd is the running init
+    if exec.check_command_exists("systemctl")? && exec.is_directory("/run/systemd/system")? {
+        Ok(Scheduler::Systemd)
+    } else if exec.check_command_exists("crontab")? {
+        Ok(Scheduler::Cron)
+    } else {
+        anyhow::bail!("Neither systemd nor cron is available to schedule backups")
+    }
+}
+
+/// Install a systemd timer (or a crontab entry without systemd) running the backup on this
+/// machine at `schedule`. Installing again with the same service and host replaces the schedule.
+pub fn install_schedule(schedule: &str, backup: &ScheduledBackup) -> Result<()> {
+    let schedule = CronSchedule::parse(schedule)?;
+    let Some(next_run) = schedule.next_after(Local::now()) else {
+        anyhow::bail!("Schedule '{}' never matches a date", schedule.expression());
+    };
+    let name = backup.unit_name()?;
+    let exec = Executor::Local;
+
+    let exe = std::env::current_exe().context("Failed to get current executable path")?;
+    let exe = exe.to_string_lossy().to_string();
+    // The timer doesn't start in this directory, so pin the config it should use
+    let homelab_dir = crate::config::find_homelab_dir()?;
+    let homelab_dir = homelab_dir.to_string_lossy().to_string();
+
+    println!("Installing backup schedule '{}'...", name);
+    match detect_scheduler(&exec)? {
+        Scheduler::Systemd => {
+            install_systemd(&exec, &name, &schedule, backup, &exe, &homelab_dir)?;
+            println!(
+                "✓ Installed and enabled {}.timer ({})",
+                name,
+                schedule.on_calendar()
+            );
+            println!("  Logs: journalctl -u {}.service", name);
+        }
+        Scheduler::Cron => {
+            let command = std::iter::once(exe)
+                .chain(backup.args())
+                .map(|arg| shell_escape(&arg))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let line = format!(
+                "{} cd {} && HOMELAB_DIR={} {} # {}",
+                schedule.expression(),
+                shell_escape(&homelab_dir),
+                shell_escape(&homelab_dir),
+                command,
+                name
+            );
+            if line.contains('%') {
+                anyhow::bail!("crontab entries can't contain '%': {}", line);
+            }
+            let mut lines = crontab_without(&exec, &name)?;
+            lines.push(line);
+            write_crontab(&exec, &lines)?;
+            println!(
+                "✓ Added crontab entry '{}' ({})",
+                name,
+                schedule.expression()
+            );
+        }
+    }
+
+    println!("  Next run: {}", next_run.format("%Y-%m-%d %H:%M %Z"));
+    Ok(())
+}
+
+/// Remove the timer or crontab entry installed for the same service and host
+pub fn uninstall_schedule(backup: &ScheduledBackup) -> Result<()> {
+    let name = backup.unit_name()?;
+    let exec = Executor::Local;
+    let mut removed = false;
+
+    // Check both, in case the schedule was installed before systemd was available (or after)
+    let timer = format!("{}/{}.timer", SYSTEMD_UNIT_DIR, name);
+    let service = format!("{}/{}.service", SYSTEMD_UNIT_DIR, name);
+    if exec.file_exists(&timer)? || exec.file_exists(&service)? {
+        let unit = format!("{}.timer", name);
+        if exec.check_command_exists("systemctl")? {
+            // Fails harmlessly when the timer isn't loaded
+            let _ = exec.execute_simple("sudo", &["systemctl", "disable", "--now", &unit]);
+        }
+        exec.execute_interactive("sudo", &["rm", "-f", &timer, &service])?;
+        if exec.is_directory("/run/systemd/system")? {
+            exec.execute_interactive("sudo", &["systemctl", "daemon-reload"])?;
+        }
+        println!("✓ Removed {}.timer and {}.service", name, name);
+        removed = true;
+    }
+
+    if exec.check_command_exists("crontab")? {
+        let current = read_crontab(&exec)?;
+        let kept = crontab_without(&exec, &name)?;
+        if kept.len() != current.len() {
+            write_crontab(&exec, &kept)?;
+            println!("✓ Removed crontab entry '{}'", name);
+            removed = true;
+        }
+    }
+
+    if !removed {
+        println!("No backup schedule '{}' is installed", name);
+    }
+    Ok(())
+}
+
+fn install_systemd(
+    exec: &Executor,
+    name: &str,
+    schedule: &CronSchedule,
+    backup: &ScheduledBackup,
+    exe: &str,
+    homelab_dir: &str,
+) -> Result<()> {
+    let on_calendar = schedule.on_calendar();
+    // Let systemd confirm it understands the converted schedule before anything is written
+    if exec.check_command_exists("systemd-analyze")? {
+        let check = exec.execute_simple("systemd-analyze", &["calendar", &on_calendar])?;
+        if !check.status.success() {
+            anyhow::bail!(
+                "systemd rejected the schedule '{}' (OnCalendar={}): {}",
+                schedule.expression(),
+                on_calendar,
+                String::from_utf8_lossy(&check.stderr).trim()
+            );
+        }
+    }
+
+    let exec_start = std::iter::once(exe.to_string())
+        .chain(backup.args())
+        .map(|arg| systemd_quote(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let service_unit = format!(
+        "# Generated by halvor (hal backup --install-schedule)\n\
+         [Unit]\n\
+         Description={description}\n\
+         Wants=network-online.target\n\
+         After=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         User={user}\n\
+         WorkingDirectory={dir}\n\
+         Environment={env}\n\
+         ExecStart={exec_start}\n",
+        description = backup.description(),
+        user = crate::config::get_default_username(),
+        dir = systemd_quote(homelab_dir),
+        env = systemd_quote(&format!("HOMELAB_DIR={}", homelab_dir)),
+        exec_start = exec_start,
+    );
+    let timer_unit = format!(
+        "# Generated by halvor (hal backup --install-schedule)\n\
+         # Schedule: {expression}\n\
+         [Unit]\n\
+         Description=Scheduled {description}\n\
+         \n\
+         [Timer]\n\
+         OnCalendar={on_calendar}\n\
+         Persistent=true\n\
+         \n\
+         [Install]\n\
+         WantedBy=timers.target\n",
+        expression = schedule.expression(),
+        description = backup.description(),
+        on_calendar = on_calendar,
+    );
+
+    install_unit_file(exec, &format!("{}.service", name), &service_unit)?;
+    install_unit_file(exec, &format!("{}.timer", name), &timer_unit)?;
+    exec.execute_interactive("sudo", &["systemctl", "daemon-reload"])?;
+    exec.execute_interactive(
+        "sudo",
+        &["systemctl", "enable", "--now", &format!("{}.timer", name)],
+    )?;
+    Ok(())
+}
+
+/// Write a root-owned unit file into /etc/systemd/system
+fn install_unit_file(exec: &Executor, file_name: &str, contents: &str) -> Result<()> {
+    let mktemp = exec.execute_shell("mktemp")?;
+    if !mktemp.status.success() {
+        anyhow::bail!("Failed to create temporary file for {}", file_name);
+    }
+    let temp_path = String::from_utf8_lossy(&mktemp.stdout).trim().to_string();
+    let path = format!("{}/{}", SYSTEMD_UNIT_DIR, file_name);
+
+    let result = exec
+        .write_file(&temp_path, contents.as_bytes())
+        .and_then(|_| {
+            exec.execute_interactive(
+                "sudo",
+                &[
+                    "install", "-m", "644", "-o", "root", "-g", "root", &temp_path, &path,
+                ],
+            )
+            .with_context(|| format!("Failed to install {}", path))
+        });
+    exec.execute_simple("rm", &["-f", &temp_path]).ok();
+    result
+}
+
+/// Quote a word for ExecStart=/WorkingDirectory= when it contains whitespace or quotes
+fn systemd_quote(arg: &str) -> String {
+    if arg
+        .chars()
+        .any(|c| c.is_whitespace() || c == '"' || c == '\\')
+    {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+fn read_crontab(exec: &Executor) -> Result<Vec<String>> {
+    // `crontab -l` exits non-zero when the user has no crontab yet
+    let output = exec.execute_shell("crontab -l 2>/dev/null")?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// The current crontab without the entry tagged `# <name>`
+fn crontab_without(exec: &Executor, name: &str) -> Result<Vec<String>> {
+    let marker = format!("# {}", name);
+    Ok(read_crontab(exec)?
+        .into_iter()
+        .filter(|line| !line.trim_end().ends_with(&marker))
+        .collect())
+}
+
+fn write_crontab(exec: &Executor, lines: &[String]) -> Result<()> {
+    let mktemp = exec.execute_shell("mktemp")?;
+    if !mktemp.status.success() {
+        anyhow::bail!("Failed to create temporary crontab file");
+    }
+    let temp_path = String::from_utf8_lossy(&mktemp.stdout).trim().to_string();
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    let result = exec
+        .write_file(&temp_path, contents.as_bytes())
+        .and_then(|_| {
+            let output = exec.execute_simple("crontab", &[&temp_path])?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "Failed to install crontab: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            Ok(())
+        });
+    exec.execute_simple("rm", &["-f", &temp_path]).ok();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn fields_expand_steps_ranges_and_lists() {
+        assert_eq!(parse_field("*/15", 0, 59, &[]).unwrap(), [0, 15, 30, 45]);
+        assert_eq!(parse_field("0-30/10", 0, 59, &[]).unwrap(), [0, 10, 20, 30]);
+        assert_eq!(parse_field("5/15", 0, 59, &[]).unwrap(), [5, 20, 35, 50]);
+        assert_eq!(parse_field("9-12", 0, 23, &[]).unwrap(), [9, 10, 11, 12]);
+        assert_eq!(parse_field("15,1,15", 1, 31, &[]).unwrap(), [1, 15]);
+        assert_eq!(parse_field("jan,Jul", 1, 12, &MONTH_NAMES).unwrap(), [1, 7]);
+        assert_eq!(
+            parse_field("mon-fri", 0, 7, &WEEKDAY_NAMES).unwrap(),
+            [1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn schedules_parse_shortcuts_and_sunday_as_seven() {
+        let weekly = CronSchedule::parse("@weekly").unwrap();
+        assert_eq!(weekly.expression(), "0 0 * * 0");
+
+        let sundays = CronSchedule::parse("30 2 * * 0,7").unwrap();
+        assert_eq!(sundays.weekdays, [0]);
+        assert_eq!(sundays.on_calendar(), "Sun *-*-* 02:30:00");
+
+        let workdays = CronSchedule::parse("*/15 3 * * mon-fri").unwrap();
+        assert_eq!(
+            workdays.on_calendar(),
+            "Mon,Tue,Wed,Thu,Fri *-*-* 03:00,15,30,45:00"
+        );
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        for expression in [
+            "",
+            "0 3 * *",
+            "0 3 * * * *",
+            "60 3 * * *",
+            "0 24 * * *",
+            "0 3 0 * *",
+            "0 3 * 13 *",
+            "0 3 * * 8",
+            "30-10 * * * *",
+            "*/0 * * * *",
+            "x 3 * * *",
+            "0 3 * foo *",
+            "0 3 1 * mon",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "'{}' should be rejected",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn next_run_is_strictly_after_the_start() {
+        let nightly = CronSchedule::parse("0 3 * * *").unwrap();
+        assert_eq!(
+            nightly.next_after(local(2026, 1, 1, 2, 59)),
+            Some(local(2026, 1, 1, 3, 0))
+        );
+        assert_eq!(
+            nightly.next_after(local(2026, 1, 1, 3, 0)),
+            Some(local(2026, 1, 2, 3, 0))
+        );
+
+        // 2026-01-01 is a Thursday
+        let weekly = CronSchedule::parse("@weekly").unwrap();
+        assert_eq!(
+            weekly.next_after(local(2026, 1, 1, 12, 0)),
+            Some(local(2026, 1, 4, 0, 0))
+        );
+
+        let monthly = CronSchedule::parse("0 0 31 * *").unwrap();
+        assert_eq!(
+            monthly.next_after(local(2026, 2, 1, 0, 0)),
+            Some(local(2026, 3, 31, 0, 0))
+        );
+
+        let leap_day = CronSchedule::parse("0 12 29 2 *").unwrap();
+        assert_eq!(
+            leap_day.next_after(local(2026, 1, 1, 0, 0)),
+            Some(local(2028, 2, 29, 12, 0))
+        );
+    }
+
+    #[test]
+    fn impossible_dates_never_run() {
+        let schedule = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(schedule.next_after(local(2026, 1, 1, 0, 0)), None);
+    }
+}
@@ -0,0 +1,142 @@
+use super::manifest::BackupManifest;
+use crate::utils::exec::{CommandExecutor, Executor};
+use anyhow::Result;
+
+/// Suffix appended to archives that fail verification
+pub const CORRUPT_SUFFIX: &str = ".corrupt";
+
+/// Run a shell command, retrying with sudo if it fails
+fn run_shell<E: CommandExecutor>(exec: &E, command: &str) -> Result<Option<String>> {
+    let output = exec.execute_shell(command)?;
+    if output.status.success() {
+        return Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()));
+    }
+
+    // Try with sudo (archives are written by root inside the helper container)
+    let sudo_output = exec.execute_shell(&format!("sudo {}", command))?;
+    if sudo_output.status.success() {
+        Ok(Some(
+            String::from_utf8_lossy(&sudo_output.stdout).to_string(),
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Check that a gzipped tarball can be listed end to end
+pub fn verify_archive<E: CommandExecutor>(exec: &E, path: &str) -> Result<bool> {
+    Ok(run_shell(exec, &format!("tar tzf '{}' > /dev/null", path))?.is_some())
+}
+
+/// Compute the SHA-256 of a file (None if neither sha256sum nor shasum is available)
+pub fn sha256_file<E: CommandExecutor>(exec: &E, path: &str) -> Result<Option<String>> {
+    let command = if exec.check_command_exists("sha256sum")? {
+        format!("sha256sum '{}'", path)
+    } else if exec.check_command_exists("shasum")? {
+        format!("shasum -a 256 '{}'", path)
+    } else {
+        return Ok(None);
+    };
+
+    Ok(run_shell(exec, &command)?
+        .and_then(|out| out.split_whitespace().next().map(|s| s.to_string())))
+}
+
+/// Outcome of verifying one archive
+enum ArchiveCheck {
+    /// The archive reads end to end
+    Verified,
+    /// The archive listed in the manifest isn't in the backup directory
+    Missing,
+    /// The archive can't be read; holds the name it was renamed to
+    Mismatch(String),
+}
+
+/// Verify one archive, renaming it with `.corrupt` if it can't be read
+/// Encrypted archives (`passphrase` given) are decrypted on the fly and listed
+fn verify_entry(
+    exec: &Executor,
+    backup_dir: &str,
+    archive: &str,
+    passphrase: Option<&str>,
+) -> Result<ArchiveCheck> {
+    let path = format!("{}/{}", backup_dir, archive);
+    if !exec.file_exists(&path)? {
+        println!("  ✗ {} is missing", archive);
+        return Ok(ArchiveCheck::Missing);
+    }
+    let readable = match passphrase {
+        Some(passphrase) => super::encryption::verify_encrypted_archive(exec, &path, passphrase)?,
+        None => verify_archive(exec, &path)?,
+    };
+    if readable {
+        println!("  ✓ {}", archive);
+        return Ok(ArchiveCheck::Verified);
+    }
+
+    println!("  ✗ {} is not readable, marking as corrupt", archive);
+    let renamed = format!("{}{}", archive, CORRUPT_SUFFIX);
+    run_shell(exec, &format!("mv '{}' '{}/{}'", path, backup_dir, renamed))?;
+    Ok(ArchiveCheck::Mismatch(renamed))
+}
+
+/// Verify one archive and return its SHA-256 (when a checksum tool is available), or add it to
+/// `failed` if it is missing or corrupt
+fn check_archive(
+    exec: &Executor,
+    backup_dir: &str,
+    archive: &str,
+    passphrase: Option<&str>,
+    failed: &mut Vec<String>,
+) -> Result<Option<String>> {
+    match verify_entry(exec, backup_dir, archive, passphrase)? {
+        ArchiveCheck::Verified => sha256_file(exec, &format!("{}/{}", backup_dir, archive)),
+        ArchiveCheck::Missing => {
+            failed.push(format!("{} (missing)", archive));
+            Ok(None)
+        }
+        ArchiveCheck::Mismatch(renamed) => {
+            failed.push(renamed);
+            Ok(None)
+        }
+    }
+}
+
+/// Verify every archive listed in the manifest
+/// Records SHA-256 checksums (of the stored files) in the manifest, renames unreadable archives
+/// with `.corrupt`, and returns the missing and corrupt archives. Encrypted backups need the
+/// `passphrase` they were written with.
+pub fn verify_backup(
+    exec: &Executor,
+    backup_dir: &str,
+    manifest: &mut BackupManifest,
+    passphrase: Option<&str>,
+) -> Result<Vec<String>> {
+    if manifest.encrypted && passphrase.is_none() {
+        anyhow::bail!("Verifying an encrypted backup needs its passphrase");
+    }
+    let mut corrupt = Vec::new();
+    let passphrase = passphrase.filter(|_| manifest.encrypted);
+    let suffix = if manifest.encrypted {
+        super::encryption::ENCRYPTED_SUFFIX
+    } else {
+        ""
+    };
+
+    for volume in &mut manifest.volumes {
+        // rsync trees from incremental backups aren't archives
+        if volume.archive.ends_with('/') {
+            println!("  - {} (rsync tree, not checked)", volume.archive);
+            continue;
+        }
+        let stored = format!("{}{}", volume.archive, suffix);
+        volume.sha256 = check_archive(exec, backup_dir, &stored, passphrase, &mut corrupt)?;
+    }
+    for mount in &mut manifest.bind_mounts {
+        let stored = format!("{}{}", mount.archive, suffix);
+        mount.sha256 = check_archive(exec, backup_dir, &stored, passphrase, &mut corrupt)?;
+    }
+
+    manifest.verified = Some(corrupt.is_empty());
+    Ok(corrupt)
+}
@@ -1,17 +1,22 @@
 // Android build and signing
 use crate::services::build::common::{copy_file, ensure_dir_exists, execute_command};
+use crate::services::build::manifest::ExpectedArtifact;
+use crate::utils::ffi_bindings::kotlin_bindings_file;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Android target triplets and their corresponding Android ABI directories
-const ANDROID_TARGETS: &[(&str, &str)] = &[
+pub const ANDROID_TARGETS: &[(&str, &str)] = &[
     ("aarch64-linux-android", "arm64-v8a"),
     ("armv7-linux-androideabi", "armeabi-v7a"),
     ("i686-linux-android", "x86"),
     ("x86_64-linux-android", "x86_64"),
 ];
 
+/// Where the JNI libraries are copied inside the Android project
+const JNI_LIBS_DIR: &str = "halvor-android/src/main/jniLibs";
+
 /// Build Android JNI library for all targets
 pub fn build_android() -> Result<()> {
     println!("Building Android JNI library...");
@@ -30,7 +35,7 @@ pub fn build_android() -> Result<()> {
     }
 
     println!("Copying JNI libraries to Android project...");
-    let jni_libs = PathBuf::from("halvor-android/src/main/jniLibs");
+    let jni_libs = PathBuf::from(JNI_LIBS_DIR);
 
     // Create directories and copy libraries
     for (target, arch) in ANDROID_TARGETS {
@@ -57,6 +62,26 @@ pub fn build_android() -> Result<()> {
     Ok(())
 }
 
+/// Files an Android build must produce: a JNI library per ABI and the Kotlin FFI bindings
+pub fn android_artifacts() -> Vec<ExpectedArtifact> {
+    let mut artifacts: Vec<ExpectedArtifact> = ANDROID_TARGETS
+        .iter()
+        .map(|(_, arch)| {
+            ExpectedArtifact::new(
+                *arch,
+                PathBuf::from(JNI_LIBS_DIR)
+                    .join(arch)
+                    .join("libhalvor_jni.so"),
+            )
+        })
+        .collect();
+    artifacts.push(ExpectedArtifact::new(
+        "kotlin-bindings",
+        kotlin_bindings_file(Path::new("")),
+    ));
+    artifacts
+}
+
 /// Sign Android app using Gradle
 pub fn sign_android() -> Result<()> {
     println!("Signing Android app...");
@@ -1,9 +1,15 @@
 // iOS and macOS build and signing
 use crate::services::build::common::ensure_path_exists;
+use crate::services::build::manifest::ExpectedArtifact;
+use crate::utils::ffi_bindings::swift_bindings_file;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// App bundles produced by the Fastlane builds
+const IOS_APP_PATH: &str = "halvor-swift/build/Build/Products/Release-iphoneos/HalvorApp-iOS.app";
+const MAC_APP_PATH: &str = "halvor-swift/build/Build/Products/Release/HalvorApp-macOS.app";
+
 /// Build iOS app using Fastlane
 pub fn build_ios() -> Result<()> {
     println!("Building iOS app with Fastlane...");
@@ -42,8 +48,7 @@ pub fn build_mac() -> Result<()> {
 pub fn sign_ios() -> Result<()> {
     println!("Signing iOS app with Fastlane...");
 
-    let app_path =
-        PathBuf::from("halvor-swift/build/Build/Products/Release-iphoneos/HalvorApp-iOS.app");
+    let app_path = PathBuf::from(IOS_APP_PATH);
     ensure_path_exists(
         &app_path,
         &format!(
@@ -70,7 +75,7 @@ pub fn sign_ios() -> Result<()> {
 pub fn sign_mac() -> Result<()> {
     println!("Signing macOS app with Fastlane...");
 
-    let app_path = PathBuf::from("halvor-swift/build/Build/Products/Release/HalvorApp-macOS.app");
+    let app_path = PathBuf::from(MAC_APP_PATH);
     ensure_path_exists(
         &app_path,
         &format!(
@@ -107,6 +112,22 @@ pub fn build_and_sign_mac() -> Result<()> {
     Ok(())
 }
 
+/// Files an iOS build must produce: the app bundle and the Swift FFI bindings
+pub fn ios_artifacts() -> Vec<ExpectedArtifact> {
+    vec![
+        ExpectedArtifact::new("ios", IOS_APP_PATH),
+        ExpectedArtifact::new("swift-bindings", swift_bindings_file(Path::new(""))),
+    ]
+}
+
+/// Files a macOS build must produce: the app bundle and the Swift FFI bindings
+pub fn mac_artifacts() -> Vec<ExpectedArtifact> {
+    vec![
+        ExpectedArtifact::new("macos", MAC_APP_PATH),
+        ExpectedArtifact::new("swift-bindings", swift_bindings_file(Path::new(""))),
+    ]
+}
+
 /// Push iOS app to App Store Connect
 pub fn push_ios_to_app_store() -> Result<()> {
     use crate::services::build::app_store::push_ios_to_app_store_impl;
@@ -1,9 +1,10 @@
 // CLI binary build with cross-compilation support
 use crate::services::build::common::{execute_command_output, get_binary_path};
 use crate::services::build::github::push_cli_to_github;
+use crate::services::build::manifest::{ExpectedArtifact, verify_and_record};
+use crate::services::build::toolchain;
 use crate::services::build::zig::setup_zig_cross_compilation;
 use anyhow::{Context, Result};
-use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -25,78 +26,91 @@ const PLATFORM_TARGETS: &[(&str, &[&str])] = &[
     ),
 ];
 
-/// Build CLI binaries for specified platforms
-pub fn build_cli(platforms: Option<&str>, push: bool) -> Result<()> {
-    let platform_targets: HashMap<&str, Vec<&str>> = PLATFORM_TARGETS
+/// Platforms (and their targets) selected by `--platforms`; all platforms when None
+pub fn select_platforms(
+    platforms: Option<&str>,
+) -> Result<Vec<(&'static str, &'static [&'static str])>> {
+    let Some(platforms_str) = platforms else {
+        return Ok(PLATFORM_TARGETS.to_vec());
+    };
+    let valid = PLATFORM_TARGETS
         .iter()
-        .map(|(k, v)| (*k, v.to_vec()))
-        .collect();
-
-    // Determine which platforms to build
-    let platforms_to_build: HashSet<&str> = if let Some(platforms_str) = platforms {
-        let parsed: HashSet<&str> = platforms_str
-            .split(',')
-            .map(|p| p.trim())
-            .filter(|p| !p.is_empty())
-            .collect();
-
-        if parsed.is_empty() {
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut selected = Vec::new();
+    for platform in platforms_str
+        .split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+    {
+        let Some(entry) = PLATFORM_TARGETS.iter().find(|(name, _)| *name == platform) else {
             anyhow::bail!(
-                "No valid platforms specified. Valid platforms are: {}",
-                platform_targets
-                    .keys()
-                    .cloned()
-                    .collect::<Vec<_>>()
-                    .join(", ")
+                "Unknown platform: '{}'. Valid platforms are: {}\n\nHint: Use comma-separated values like: --platforms apple,windows,linux",
+                platform,
+                valid
             );
+        };
+        if !selected.contains(entry) {
+            selected.push(*entry);
         }
+    }
 
-        // Validate platforms
-        for platform in &parsed {
-            if !platform_targets.contains_key(platform) {
-                anyhow::bail!(
-                    "Unknown platform: '{}'. Valid platforms are: {}\n\nHint: Use comma-separated values like: --platforms apple,windows,linux",
-                    platform,
-                    platform_targets
-                        .keys()
-                        .cloned()
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                );
-            }
-        }
+    if selected.is_empty() {
+        anyhow::bail!(
+            "No valid platforms specified. Valid platforms are: {}",
+            valid
+        );
+    }
+    Ok(selected)
+}
 
-        parsed
-    } else {
-        // Build all platforms
-        platform_targets.keys().copied().collect()
-    };
+/// Build CLI binaries for specified platforms
+pub fn build_cli(platforms: Option<&str>, push: bool) -> Result<()> {
+    let platforms_to_build = select_platforms(platforms)?;
 
     println!(
         "Building CLI binaries for platforms: {}",
         platforms_to_build
             .iter()
-            .cloned()
+            .map(|(platform, _)| *platform)
             .collect::<Vec<_>>()
             .join(", ")
     );
 
+    let all_targets: Vec<&str> = platforms_to_build
+        .iter()
+        .flat_map(|(_, targets)| targets.iter().copied())
+        .collect();
+
+    // Install missing Rust targets, then make sure the targets can actually be linked
+    // before spending time on the builds
+    for target in &all_targets {
+        if !is_target_installed(target)? {
+            println!("  Installing target: {}", target);
+            install_target(target)?;
+        }
+    }
+    // Explicitly requested platforms must all build; the default build covers what it can
+    let targets_to_build = if platforms.is_some() {
+        toolchain::ensure_ready(&toolchain::check_cli_targets(&all_targets))?;
+        all_targets
+    } else {
+        let (buildable, issues) = toolchain::skip_unbuildable_cli_targets(&all_targets);
+        toolchain::ensure_ready(&issues)?;
+        buildable
+    };
+
     let mut built_binaries: Vec<(String, PathBuf)> = Vec::new();
 
     // Build for each platform
-    for platform in &platforms_to_build {
-        let targets = platform_targets.get(platform).unwrap();
+    for (platform, targets) in &platforms_to_build {
         println!("\n📦 Building for {} platform...", platform);
 
-        for target in targets {
+        for target in targets.iter().filter(|t| targets_to_build.contains(t)) {
             println!("  Building target: {}", target);
 
-            // Ensure target is installed
-            if !is_target_installed(target)? {
-                println!("  Installing target: {}", target);
-                install_target(target)?;
-            }
-
             // Build for target
             if let Some(binary_path) = build_target(target)? {
                 println!("  ✓ Built: {}", binary_path.display());
@@ -114,6 +128,25 @@ pub fn build_cli(platforms: Option<&str>, push: bool) -> Result<()> {
         println!("  - {}: {}", target, path.display());
     }
 
+    // Every target must have produced a binary in this run; never push a partial set, and never
+    // count a binary left over from an earlier build
+    let missing: Vec<&str> = targets_to_build
+        .iter()
+        .copied()
+        .filter(|target| !built_binaries.iter().any(|(built, _)| built == target))
+        .collect();
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "No binary was built for {}; not recording or pushing a partial set",
+            missing.join(", ")
+        );
+    }
+    let expected: Vec<ExpectedArtifact> = built_binaries
+        .iter()
+        .map(|(target, path)| ExpectedArtifact::new(target, path))
+        .collect();
+    verify_and_record("cli", &expected)?;
+
     // Push to GitHub releases if requested
     if push {
         println!("\n📤 Pushing to GitHub releases...");
@@ -124,7 +157,7 @@ pub fn build_cli(platforms: Option<&str>, push: bool) -> Result<()> {
 }
 
 /// Check if a Rust target is installed
-fn is_target_installed(target: &str) -> Result<bool> {
+pub fn is_target_installed(target: &str) -> Result<bool> {
     let mut cmd = Command::new("rustup");
     cmd.args(["target", "list", "--installed"]);
     let output = execute_command_output(cmd, "Failed to check installed targets")?;
@@ -0,0 +1,169 @@
+// Post-build verification: check expected artifacts and record their checksums
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Manifest written next to the build output
+pub const MANIFEST_FILE: &str = "build-manifest.json";
+
+/// An output file (or app bundle directory) a build is expected to produce
+pub struct ExpectedArtifact {
+    /// Target triple, ABI or component the artifact belongs to
+    pub target: String,
+    pub path: PathBuf,
+}
+
+impl ExpectedArtifact {
+    pub fn new(target: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            target: target.into(),
+            path: path.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactRecord {
+    pub target: String,
+    pub path: String,
+    /// Bytes; for directories, the total of every file inside
+    pub size: u64,
+    /// For directories, a digest over each file's relative path and contents in sorted order
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BuildManifest {
+    /// Which build produced the artifacts (cli, ios, mac, android, web)
+    pub build: String,
+    pub version: String,
+    pub created_at: String,
+    pub artifacts: Vec<ArtifactRecord>,
+}
+
+/// Verify a build's artifacts, write `target/build-manifest.json` and print a summary
+///
+/// Fails if any expected artifact is missing or empty, so an incomplete build is never
+/// reported as a success.
+pub fn verify_and_record(build: &str, expected: &[ExpectedArtifact]) -> Result<PathBuf> {
+    let mut problems = Vec::new();
+    let mut artifacts = Vec::new();
+    for artifact in expected {
+        if !artifact.path.exists() {
+            problems.push(format!(
+                "{}: missing {}",
+                artifact.target,
+                artifact.path.display()
+            ));
+            continue;
+        }
+        let (size, sha256) = checksum(&artifact.path)?;
+        if size == 0 {
+            problems.push(format!(
+                "{}: empty {}",
+                artifact.target,
+                artifact.path.display()
+            ));
+            continue;
+        }
+        artifacts.push(ArtifactRecord {
+            target: artifact.target.clone(),
+            path: artifact.path.display().to_string(),
+            size,
+            sha256,
+        });
+    }
+
+    if !problems.is_empty() {
+        anyhow::bail!(
+            "{} of {} expected {} artifact(s) missing or empty:\n  {}",
+            problems.len(),
+            expected.len(),
+            build,
+            problems.join("\n  ")
+        );
+    }
+
+    let manifest = BuildManifest {
+        build: build.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        artifacts,
+    };
+    let manifest_path = PathBuf::from("target").join(MANIFEST_FILE);
+    fs::create_dir_all("target").context("Failed to create target directory")?;
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?).with_context(|| {
+        format!(
+            "Failed to write build manifest: {}",
+            manifest_path.display()
+        )
+    })?;
+
+    print_summary(&manifest);
+    println!("  Manifest: {}", manifest_path.display());
+    Ok(manifest_path)
+}
+
+fn print_summary(manifest: &BuildManifest) {
+    println!();
+    println!("✓ Verified {} artifact(s):", manifest.artifacts.len());
+    let width = manifest
+        .artifacts
+        .iter()
+        .map(|a| a.target.len())
+        .max()
+        .unwrap_or(0);
+    for artifact in &manifest.artifacts {
+        println!(
+            "  {:<width$}  {:>10}  {}  {}",
+            artifact.target,
+            crate::utils::format_bytes(artifact.size),
+            &artifact.sha256[..12],
+            artifact.path,
+            width = width
+        );
+    }
+}
+
+/// Size and SHA-256 of a file, or of every file under a directory
+fn checksum(path: &Path) -> Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    if path.is_dir() {
+        let mut files = Vec::new();
+        collect_files(path, &mut files)?;
+        files.sort();
+        let mut size = 0;
+        for file in &files {
+            let relative = file.strip_prefix(path).unwrap_or(file);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            size += hash_file(file, &mut hasher)?;
+        }
+        Ok((size, format!("{:x}", hasher.finalize())))
+    } else {
+        let size = hash_file(path, &mut hasher)?;
+        Ok((size, format!("{:x}", hasher.finalize())))
+    }
+}
+
+fn hash_file(path: &Path, hasher: &mut Sha256) -> Result<u64> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    io::copy(&mut file, hasher).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
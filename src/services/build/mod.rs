@@ -5,11 +5,16 @@ pub mod apple;
 pub mod cli;
 pub mod common;
 pub mod github;
+pub mod manifest;
+pub mod toolchain;
 pub mod web;
 pub mod zig;
 
 // Re-export commonly used functions
-pub use android::{build_android, sign_android};
-pub use apple::{build_and_sign_ios, build_and_sign_mac, push_ios_to_app_store};
+pub use android::{android_artifacts, build_android, sign_android};
+pub use apple::{
+    build_and_sign_ios, build_and_sign_mac, ios_artifacts, mac_artifacts, push_ios_to_app_store,
+};
 pub use cli::build_cli;
-pub use web::{build_web, build_web_docker, run_web_prod};
+pub use manifest::verify_and_record;
+pub use web::{build_web, build_web_docker, run_web_prod, web_artifacts};
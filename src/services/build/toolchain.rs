@@ -0,0 +1,289 @@
+// Build environment validation: Rust targets, linkers and platform tools
+use crate::services::build::android::ANDROID_TARGETS;
+use crate::services::build::cli::is_target_installed;
+use std::path::Path;
+
+/// Something missing from the build environment, with how to fix it
+pub struct ToolchainIssue {
+    /// Target triple or tool the problem applies to
+    pub subject: String,
+    pub problem: String,
+    pub fix: String,
+}
+
+impl ToolchainIssue {
+    fn new(subject: &str, problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            subject: subject.to_string(),
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+/// Print the issues and fail if there are any
+pub fn ensure_ready(issues: &[ToolchainIssue]) -> anyhow::Result<()> {
+    if issues.is_empty() {
+        return Ok(());
+    }
+    eprintln!("✗ Build environment is not ready:");
+    for issue in issues {
+        eprintln!("  ✗ {}: {}", issue.subject, issue.problem);
+        for line in issue.fix.lines() {
+            eprintln!("      {}", line);
+        }
+    }
+    anyhow::bail!(
+        "{} build environment problem(s) found. Fix them and re-run (add --check to validate without building)",
+        issues.len()
+    );
+}
+
+fn has_command(name: &str) -> bool {
+    which::which(name).is_ok()
+}
+
+/// Check that each CLI target is installed and has a linker on this machine
+pub fn check_cli_targets(targets: &[&str]) -> Vec<ToolchainIssue> {
+    let mut issues = check_rust_targets(targets);
+    issues.extend(targets.iter().filter_map(|target| linker_issue(target)));
+    issues
+}
+
+/// Check CLI targets for the default build, which covers every platform
+/// Targets this machine can't build are skipped with a warning; returns the rest along with the
+/// problems that still stop the build (ones not tied to a target, e.g. rustup missing)
+pub fn skip_unbuildable_cli_targets<'a>(
+    targets: &[&'a str],
+) -> (Vec<&'a str>, Vec<ToolchainIssue>) {
+    let (skipped, mut issues): (Vec<_>, Vec<_>) = check_cli_targets(targets)
+        .into_iter()
+        .partition(|issue| targets.contains(&issue.subject.as_str()));
+
+    let mut buildable = Vec::new();
+    for target in targets {
+        let problems: Vec<&str> = skipped
+            .iter()
+            .filter(|issue| issue.subject == *target)
+            .map(|issue| issue.problem.as_str())
+            .collect();
+        if problems.is_empty() {
+            buildable.push(*target);
+        } else {
+            println!("⚠ Skipping {}: {}", target, problems.join("; "));
+        }
+    }
+    if buildable.is_empty() && issues.is_empty() {
+        issues.push(ToolchainIssue::new(
+            "cli",
+            "none of the targets can be built on this machine",
+            "Set up a linker for one of the skipped targets, or pick platforms with --platforms",
+        ));
+    }
+    (buildable, issues)
+}
+
+/// Check Rust targets against `rustup target list --installed`
+fn check_rust_targets(targets: &[&str]) -> Vec<ToolchainIssue> {
+    if !has_command("rustup") {
+        return vec![ToolchainIssue::new(
+            "rustup",
+            "not found",
+            "Install Rust with rustup: https://rustup.rs",
+        )];
+    }
+    targets
+        .iter()
+        .filter(|target| !is_target_installed(target).unwrap_or(false))
+        .map(|target| {
+            ToolchainIssue::new(
+                target,
+                "Rust target not installed",
+                format!("rustup target add {}", target),
+            )
+        })
+        .collect()
+}
+
+/// Whether a target builds natively on this machine (no cross linker needed)
+fn is_native(target: &str) -> bool {
+    let os = match std::env::consts::OS {
+        "macos" => "apple-darwin",
+        "linux" => "linux",
+        "windows" => "windows",
+        other => other,
+    };
+    target.starts_with(std::env::consts::ARCH) && target.contains(os)
+}
+
+/// Linker environment variable cargo honors for a target
+fn linker_env_var(target: &str) -> String {
+    format!(
+        "CARGO_TARGET_{}_LINKER",
+        target.to_uppercase().replace('-', "_")
+    )
+}
+
+/// Missing linker or cross-compiler for a target, if any
+fn linker_issue(target: &str) -> Option<ToolchainIssue> {
+    // An explicitly configured linker wins over everything else
+    let env_var = linker_env_var(target);
+    if let Ok(linker) = std::env::var(&env_var) {
+        return (!has_command(&linker)).then(|| {
+            ToolchainIssue::new(
+                target,
+                format!("linker '{}' (from {}) not found", linker, env_var),
+                format!("Install {} or unset {}", linker, env_var),
+            )
+        });
+    }
+
+    let host = std::env::consts::OS;
+    if target.contains("apple") {
+        return (host != "macos").then(|| {
+            ToolchainIssue::new(
+                target,
+                "Apple targets can only be built on macOS",
+                "Build this platform on a Mac, or drop it from --platforms",
+            )
+        });
+    }
+
+    match host {
+        // Linux and Windows targets go through Zig wrappers; `build` installs Zig via Homebrew
+        "macos" => (!has_command("zig") && !has_command("brew")).then(|| {
+            ToolchainIssue::new(
+                target,
+                "Zig is required to cross-compile from macOS",
+                "brew install zig\nOr download from: https://ziglang.org/download/",
+            )
+        }),
+        "linux" if target.contains("windows") => Some(ToolchainIssue::new(
+            target,
+            "MSVC targets can't be linked on Linux",
+            "Build Windows binaries on Windows or macOS, or drop windows from --platforms",
+        )),
+        "linux" => {
+            let arch = target.split('-').next().unwrap_or(target);
+            let native = is_native(target);
+            let (compiler, install) = if target.ends_with("musl") && native {
+                (
+                    "musl-gcc".to_string(),
+                    "sudo apt install musl-tools".to_string(),
+                )
+            } else if target.ends_with("musl") {
+                (
+                    format!("{}-linux-musl-gcc", arch),
+                    "Install a musl cross toolchain (e.g. from https://musl.cc)".to_string(),
+                )
+            } else if native {
+                return None;
+            } else {
+                (
+                    format!("{}-linux-gnu-gcc", arch),
+                    format!("sudo apt install gcc-{}-linux-gnu", arch.replace('_', "-")),
+                )
+            };
+            (!has_command(&compiler)).then(|| {
+                let fix = if compiler == "musl-gcc" {
+                    install
+                } else {
+                    format!("{}\nexport {}={}", install, env_var, compiler)
+                };
+                ToolchainIssue::new(target, format!("{} not found", compiler), fix)
+            })
+        }
+        _ => (!is_native(target)).then(|| {
+            ToolchainIssue::new(
+                target,
+                format!("cross-compiling from {} is not supported", host),
+                "Build this platform on Linux or macOS",
+            )
+        }),
+    }
+}
+
+/// Check the Android NDK, JDK, Gradle wrapper and JNI targets
+pub fn check_android() -> Vec<ToolchainIssue> {
+    let targets: Vec<&str> = ANDROID_TARGETS.iter().map(|(target, _)| *target).collect();
+    let mut issues = check_rust_targets(&targets);
+
+    let ndk = ["ANDROID_NDK_HOME", "ANDROID_NDK_ROOT", "NDK_HOME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok());
+    match ndk {
+        Some(path) if !Path::new(&path).is_dir() => issues.push(ToolchainIssue::new(
+            "android-ndk",
+            format!("NDK directory {} does not exist", path),
+            "Point ANDROID_NDK_HOME at an installed NDK",
+        )),
+        Some(_) => {}
+        None => issues.push(ToolchainIssue::new(
+            "android-ndk",
+            "Android NDK not found",
+            "Install the NDK with Android Studio's SDK Manager, then set ANDROID_NDK_HOME",
+        )),
+    }
+    if !has_command("java") {
+        issues.push(ToolchainIssue::new(
+            "java",
+            "not found (Gradle needs a JDK)",
+            "Install a JDK 17 or newer",
+        ));
+    }
+    if !Path::new("halvor-android/gradlew").exists() {
+        issues.push(ToolchainIssue::new(
+            "gradlew",
+            "halvor-android/gradlew not found",
+            "Run hal build from the repository root",
+        ));
+    }
+    issues
+}
+
+/// Check Xcode and Fastlane for iOS and macOS builds
+pub fn check_apple() -> Vec<ToolchainIssue> {
+    if std::env::consts::OS != "macos" {
+        return vec![ToolchainIssue::new(
+            "xcode",
+            "iOS and macOS apps can only be built on macOS",
+            "Build on a Mac with Xcode installed",
+        )];
+    }
+    let mut issues = Vec::new();
+    if !has_command("xcodebuild") {
+        issues.push(ToolchainIssue::new(
+            "xcodebuild",
+            "not found",
+            "Install Xcode, then run: xcode-select --install",
+        ));
+    }
+    if !has_command("fastlane") {
+        issues.push(ToolchainIssue::new(
+            "fastlane",
+            "not found",
+            "brew install fastlane",
+        ));
+    }
+    issues
+}
+
+/// Check npm (and Docker for container builds) for the web app
+pub fn check_web(docker: bool) -> Vec<ToolchainIssue> {
+    let mut issues = Vec::new();
+    if !has_command("npm") {
+        issues.push(ToolchainIssue::new(
+            "npm",
+            "not found (needed for the Svelte frontend)",
+            "Install Node.js from https://nodejs.org",
+        ));
+    }
+    if docker && !has_command("docker") {
+        issues.push(ToolchainIssue::new(
+            "docker",
+            "not found",
+            "Install Docker: https://docs.docker.com/get-docker/",
+        ));
+    }
+    issues
+}
@@ -1,11 +1,13 @@
 // Web application build (Rust server + Svelte frontend)
 use crate::services::build::common::execute_command;
+use crate::services::build::manifest::ExpectedArtifact;
 use crate::services::docker::build::{
     DockerBuildConfig, build_image_with_push, check_docker_auth, generate_ghcr_tags, get_git_hash,
     get_github_user,
 };
+use crate::utils::ffi_bindings::wasm_bindings_file;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Build web application (Rust server + Svelte frontend) for bare metal
@@ -66,6 +68,21 @@ pub fn build_web(release: bool) -> Result<()> {
     Ok(())
 }
 
+/// Files a bare-metal web build must produce: the server binary, the Svelte app and the
+/// TypeScript FFI bindings
+pub fn web_artifacts(release: bool) -> Vec<ExpectedArtifact> {
+    vec![
+        ExpectedArtifact::new(
+            "server",
+            PathBuf::from("target")
+                .join(if release { "release" } else { "debug" })
+                .join("halvor"),
+        ),
+        ExpectedArtifact::new("frontend", "halvor-web/build"),
+        ExpectedArtifact::new("wasm-bindings", wasm_bindings_file(Path::new(""))),
+    ]
+}
+
 /// Build web application as Docker container
 pub fn build_web_docker(release: bool, push: bool) -> Result<()> {
     println!("Building Docker container for web application...");
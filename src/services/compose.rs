@@ -0,0 +1,193 @@
+use crate::config::EnvConfig;
+use crate::services::docker;
+use crate::utils::exec::{CommandExecutor, DryRunExecutor, Executor};
+use crate::utils::ssh::shell_escape;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Directory (under the home directory on the target) that deployed compose projects live in
+const COMPOSE_DIR: &str = "compose";
+
+/// Find a compose file: absolute paths as given, otherwise relative to the homelab directory
+/// or its `compose/` subdirectory
+pub fn resolve_compose_file(file: &str) -> Result<PathBuf> {
+    find_in_homelab(file, "Compose file")
+}
+
+/// Find an env file for `--env-file`, resolved the same way as compose files
+pub fn resolve_env_file(file: &str) -> Result<PathBuf> {
+    find_in_homelab(file, "Env file")
+}
+
+fn find_in_homelab(file: &str, what: &str) -> Result<PathBuf> {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        if path.is_file() {
+            return Ok(path.to_path_buf());
+        }
+        anyhow::bail!("{} not found: {}", what, path.display());
+    }
+
+    let homelab_dir = crate::config::find_homelab_dir()?;
+    let compose_dir = homelab_dir.join("compose");
+    [homelab_dir.join(file), compose_dir.join(file)]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .with_context(|| {
+            format!(
+                "{} '{}' not found (looked in {} and {})",
+                what,
+                file,
+                homelab_dir.display(),
+                compose_dir.display()
+            )
+        })
+}
+
+/// Compose project name for a file: `media.docker-compose.yml` -> `media`
+/// Generic names like `docker-compose.yml` use the parent directory's name instead
+pub fn project_name(compose_file: &Path) -> String {
+    let stem = compose_file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = stem
+        .strip_suffix(".docker-compose")
+        .or_else(|| stem.strip_suffix(".compose"))
+        .unwrap_or(&stem);
+    let name = if matches!(stem, "docker-compose" | "compose" | "") {
+        compose_file
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "default".to_string())
+    } else {
+        stem.to_string()
+    };
+    name.to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Copy a compose file (and optional env file) to `hostname`, then run `args` with the host's
+/// compose command in that project directory, streaming its output
+/// `bwlimit_kbps` caps the upload rate of the copied files
+pub fn run_compose(
+    hostname: &str,
+    config: &EnvConfig,
+    compose_file: &Path,
+    env_file: Option<&Path>,
+    args: &[&str],
+    bwlimit_kbps: Option<u32>,
+) -> Result<()> {
+    let exec = Executor::new(hostname, config)?.with_bwlimit(bwlimit_kbps);
+    let command = stage_compose(
+        &exec,
+        exec.is_local(),
+        hostname,
+        compose_file,
+        env_file,
+        args,
+    )?;
+    println!();
+    println!("Running on {}: {}", hostname, command);
+    println!();
+    let status = exec.execute_shell_interactive_status(&command)?;
+    if !status.success() {
+        anyhow::bail!(
+            "compose {} failed on {} ({})",
+            args.first().copied().unwrap_or_default(),
+            hostname,
+            status
+        );
+    }
+
+    println!();
+    println!("✓ compose {} complete on {}", args.join(" "), hostname);
+    Ok(())
+}
+
+/// Print what `run_compose` would copy and run on `hostname` without changing anything
+pub fn dry_run_compose(
+    hostname: &str,
+    config: &EnvConfig,
+    compose_file: &Path,
+    env_file: Option<&Path>,
+    args: &[&str],
+) -> Result<()> {
+    let exec = Executor::new(hostname, config)?;
+    let dry_exec = DryRunExecutor::new(&exec, hostname);
+    let command = stage_compose(
+        &dry_exec,
+        exec.is_local(),
+        hostname,
+        compose_file,
+        env_file,
+        args,
+    )?;
+    dry_exec.execute_shell_interactive(&command)
+}
+
+/// Copy the compose file (and env file) into the project directory on the host and return
+/// the compose command to run there
+fn stage_compose<E: CommandExecutor>(
+    exec: &E,
+    is_local: bool,
+    hostname: &str,
+    compose_file: &Path,
+    env_file: Option<&Path>,
+    args: &[&str],
+) -> Result<String> {
+    let project = project_name(compose_file);
+
+    // Local paths aren't shell-expanded, so resolve $HOME here
+    let project_dir = if is_local {
+        crate::config::config_manager::get_home_dir()?
+            .join(COMPOSE_DIR)
+            .join(&project)
+            .display()
+            .to_string()
+    } else {
+        format!("$HOME/{}/{}", COMPOSE_DIR, project)
+    };
+
+    let compose_content = std::fs::read(compose_file)
+        .with_context(|| format!("Failed to read compose file: {}", compose_file.display()))?;
+    exec.mkdir_p(&project_dir)?;
+    exec.write_file(
+        &format!("{}/docker-compose.yml", project_dir),
+        &compose_content,
+    )?;
+    println!(
+        "✓ Copied {} to {}:~/{}/{}/docker-compose.yml",
+        compose_file.display(),
+        hostname,
+        COMPOSE_DIR,
+        project
+    );
+
+    let mut compose = format!(
+        "cd \"{}\" && {} -p {}",
+        project_dir,
+        docker::get_compose_command(exec)?,
+        project
+    );
+    if let Some(env_file) = env_file {
+        let env_content = std::fs::read(env_file)
+            .with_context(|| format!("Failed to read env file: {}", env_file.display()))?;
+        exec.write_file(&format!("{}/.env", project_dir), &env_content)?;
+        exec.execute_shell(&format!("chmod 600 \"{}/.env\"", project_dir))?;
+        println!("✓ Copied {} to {}", env_file.display(), hostname);
+        compose.push_str(" --env-file .env");
+    }
+
+    let args_escaped: Vec<String> = args.iter().map(|arg| shell_escape(arg)).collect();
+    Ok(format!("{} {}", compose, args_escaped.join(" ")))
+}
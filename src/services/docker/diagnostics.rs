@@ -1,274 +1,458 @@
+use super::{ContainerStatus, DAEMON_JSON};
 use crate::utils::exec::CommandExecutor;
+use crate::utils::format_bytes;
 use anyhow::Result;
+use serde::Serialize;
 use serde_json;
 
-/// Run comprehensive Docker daemon diagnostics
-pub fn diagnose_docker<E: CommandExecutor>(exec: &E, hostname: &str) -> Result<()> {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("Docker Daemon Diagnostics");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!();
-    println!("Host: {}", hostname);
-    println!();
+const DOCKER_SOCKET: &str = "/var/run/docker.sock";
 
-    check_docker_installation(exec)?;
-    check_daemon_accessibility(exec)?;
-    check_service_status(exec)?;
-    check_service_logs(exec)?;
-    check_daemon_config(exec)?;
-    check_socket_permissions(exec)?;
-    check_user_permissions(exec)?;
-    check_containerd(exec)?;
-    print_summary(exec)?;
-    check_network_controller_errors(exec)?;
+/// Everything `hal docker --diagnose` checks, collected once and rendered as text or JSON
+#[derive(Debug, Default, Serialize)]
+pub struct DockerDiagnostics {
+    pub hostname: String,
+    pub installed: bool,
+    /// Client version from `docker --version`
+    pub client_version: Option<String>,
+    /// Server version from `docker info`
+    pub version: Option<String>,
+    pub daemon_running: bool,
+    /// The daemon only answers when run through sudo
+    pub requires_sudo: bool,
+    pub storage_driver: Option<String>,
+    pub disk_usage: Vec<DiskUsageEntry>,
+    pub service: Option<ServiceStatus>,
+    pub log_errors: Vec<String>,
+    pub daemon_json: DaemonJsonStatus,
+    pub ipv6_enabled: Option<bool>,
+    pub socket: Option<String>,
+    pub user: String,
+    pub in_docker_group: Option<bool>,
+    pub containers_running: Option<u64>,
+    pub unhealthy_containers: Vec<ContainerStatus>,
+    pub containerd: ContainerdStatus,
+    pub network_controller_error: bool,
+}
 
-    Ok(())
+/// One row of `docker system df`
+#[derive(Debug, Serialize)]
+pub struct DiskUsageEntry {
+    pub kind: String,
+    pub total: u64,
+    pub active: u64,
+    pub size_bytes: Option<u64>,
+    pub reclaimable_bytes: Option<u64>,
 }
 
-fn check_docker_installation<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[1/8] Checking if Docker is installed...");
-    if exec.check_command_exists("docker")? {
-        let version_output = exec.execute_simple("docker", &["--version"]);
-        if let Ok(output) = version_output {
-            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            println!("   ✓ Docker installed: {}", version);
-        } else {
-            println!("   ✗ Docker command found but version check failed");
-        }
-    } else {
-        println!("   ✗ Docker is not installed");
-        println!(
-            "   → Install with: halvor install docker -H {}",
-            exec.get_username().unwrap_or_default()
-        );
-        return Ok(());
-    }
-    println!();
-    Ok(())
+/// systemd state of docker.service
+#[derive(Debug, Serialize)]
+pub struct ServiceStatus {
+    pub active: String,
+    pub enabled: bool,
 }
 
-fn check_daemon_accessibility<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[2/8] Checking Docker daemon accessibility...");
-    let docker_info = exec.execute_simple("docker", &["info"]);
-    match docker_info {
-        Ok(output) if output.status.success() => {
-            println!("   ✓ Docker daemon is accessible");
+#[derive(Debug, Default, Serialize)]
+pub struct DaemonJsonStatus {
+    pub exists: bool,
+    pub valid: Option<bool>,
+    pub backup_exists: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ContainerdStatus {
+    pub installed: bool,
+    pub service: Option<String>,
+}
+
+impl DockerDiagnostics {
+    /// Run every check against `exec`
+    pub fn collect<E: CommandExecutor>(exec: &E, hostname: &str) -> Result<Self> {
+        let mut diag = DockerDiagnostics {
+            hostname: hostname.to_string(),
+            user: exec.get_username()?,
+            ..Default::default()
+        };
+
+        diag.installed = exec.check_command_exists("docker")?;
+        if diag.installed {
+            diag.client_version = stdout_of(exec, "docker", &["--version"]);
+            diag.collect_daemon_info(exec);
         }
-        Ok(_) => {
-            println!("   ✗ Docker daemon is not accessible (command failed)");
+
+        let has_systemctl = exec.check_command_exists("systemctl")?;
+        if has_systemctl {
+            let active = status_of(exec, "systemctl", &["is-active", "docker"]);
+            let enabled = status_of(exec, "systemctl", &["is-enabled", "docker"]);
+            diag.service = Some(ServiceStatus {
+                active,
+                enabled: enabled == "enabled",
+            });
         }
-        Err(e) => {
-            println!("   ✗ Docker daemon is not accessible: {}", e);
+
+        if exec.check_command_exists("journalctl")? {
+            let logs = stdout_of(
+                exec,
+                "journalctl",
+                &["-u", "docker.service", "-n", "50", "--no-pager"],
+            )
+            .unwrap_or_default();
+            // The error list only covers the most recent lines; older ones are usually stale
+            let recent: Vec<&str> = logs.lines().collect();
+            let start = recent.len().saturating_sub(20);
+            diag.log_errors = recent[start..]
+                .iter()
+                .filter(|line| {
+                    let lower = line.to_lowercase();
+                    lower.contains("error") || lower.contains("failed")
+                })
+                .map(|line| line.trim().to_string())
+                .collect();
+            let lower = logs.to_lowercase();
+            diag.network_controller_error =
+                lower.contains("network controller") || lower.contains("error creating default");
         }
-    }
 
-    // Try with sudo
-    let sudo_docker_info = exec.execute_simple("sudo", &["docker", "info"]);
-    match sudo_docker_info {
-        Ok(output) if output.status.success() => {
-            println!("   ⚠ Docker works with sudo - permission issue detected");
-            println!("   → User may need to be added to docker group");
+        diag.daemon_json.exists = exec.file_exists(DAEMON_JSON)?;
+        let mut daemon_config = None;
+        if diag.daemon_json.exists {
+            let parsed = exec
+                .read_file(DAEMON_JSON)
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+            diag.daemon_json.valid = Some(parsed.is_some());
+            daemon_config = parsed;
+        }
+        diag.daemon_json.backup_exists = exec.file_exists(&format!("{}.backup", DAEMON_JSON))?;
+        // The bridge network reports the live setting; daemon.json is the fallback
+        if diag.ipv6_enabled.is_none() {
+            diag.ipv6_enabled = daemon_config.as_ref().map(|config| {
+                config
+                    .get("ipv6")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+            });
         }
-        _ => {}
-    }
-    println!();
-    Ok(())
-}
 
-fn check_service_status<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[3/8] Checking Docker service status...");
-    if exec.check_command_exists("systemctl")? {
-        let status_output = exec.execute_simple("systemctl", &["is-active", "docker"]);
-        if let Ok(output) = status_output {
-            let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            match status.as_str() {
-                "active" => println!("   ✓ Docker service is active"),
-                "inactive" => println!("   ✗ Docker service is inactive"),
-                "failed" => println!("   ✗ Docker service has failed"),
-                _ => println!("   ⚠ Docker service status: {}", status),
-            }
+        if exec.file_exists(DOCKER_SOCKET)? {
+            let stat_args = ["-c", "%a %U:%G", DOCKER_SOCKET];
+            diag.socket = Some(
+                stdout_of(exec, "stat", &stat_args)
+                    .or_else(|| via_sudo(exec, "stat", &stat_args))
+                    .unwrap_or_default(),
+            );
         }
 
-        // Get detailed status
-        let detailed_status =
-            exec.execute_simple("systemctl", &["status", "docker", "--no-pager", "-l"]);
-        if let Ok(output) = detailed_status {
-            let status_text = String::from_utf8_lossy(&output.stdout);
-            // Look for key indicators
-            if status_text.contains("Active: active") {
-                println!("   ✓ Service is running");
-            } else if status_text.contains("Active: failed") {
-                println!("   ✗ Service has failed");
-            } else if status_text.contains("Active: inactive") {
-                println!("   ✗ Service is not running");
-            }
+        diag.in_docker_group = stdout_of(exec, "id", &["-nG", &diag.user])
+            .map(|groups| groups.split_whitespace().any(|g| g == "docker"));
 
-            // Check if enabled
-            if status_text.contains("enabled") {
-                println!("   ✓ Service is enabled (will start on boot)");
-            } else {
-                println!("   ⚠ Service is not enabled (won't start on boot)");
-                println!("   → Enable with: sudo systemctl enable docker");
-            }
+        diag.containerd.installed = exec.check_command_exists("containerd")?;
+        if diag.containerd.installed && has_systemctl {
+            diag.containerd.service =
+                Some(status_of(exec, "systemctl", &["is-active", "containerd"]));
         }
-    } else {
-        println!("   ⚠ systemctl not available (non-systemd system)");
+
+        Ok(diag)
     }
-    println!();
-    Ok(())
-}
 
-fn check_service_logs<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[4/8] Checking Docker service logs (last 20 lines)...");
-    if exec.check_command_exists("journalctl")? {
-        let log_output = exec.execute_simple(
-            "journalctl",
-            &["-u", "docker.service", "-n", "20", "--no-pager"],
-        );
-        if let Ok(output) = log_output {
-            let logs = String::from_utf8_lossy(&output.stdout);
-            if logs.trim().is_empty() {
-                println!("   ⚠ No recent logs found");
+    /// Query the daemon itself: info, disk usage, IPv6 and container health
+    fn collect_daemon_info<E: CommandExecutor>(&mut self, exec: &E) {
+        let info_args = ["info", "--format", "{{json .}}"];
+        let mut info = stdout_of(exec, "docker", &info_args);
+        if info.is_none() {
+            info = via_sudo(exec, "docker", &info_args);
+            self.requires_sudo = info.is_some();
+        }
+        let Some(info) = info.and_then(|out| serde_json::from_str::<serde_json::Value>(&out).ok())
+        else {
+            return;
+        };
+
+        self.daemon_running = true;
+        self.version = info["ServerVersion"].as_str().map(str::to_string);
+        self.storage_driver = info["Driver"].as_str().map(str::to_string);
+        self.containers_running = info["ContainersRunning"].as_u64();
+
+        let docker = |args: &[&str]| {
+            if self.requires_sudo {
+                via_sudo(exec, "docker", args)
             } else {
-                // Look for common error patterns
-                let log_lower = logs.to_lowercase();
-                if log_lower.contains("error") || log_lower.contains("failed") {
-                    println!("   ✗ Errors found in logs:");
-                    for line in logs.lines() {
-                        if line.to_lowercase().contains("error")
-                            || line.to_lowercase().contains("failed")
-                        {
-                            println!("      {}", line.trim());
-                        }
-                    }
-                } else {
-                    println!("   ✓ No obvious errors in recent logs");
-                }
+                stdout_of(exec, "docker", args)
             }
+        };
+
+        let df = docker(&["system", "df", "--format", "{{json .}}"]).unwrap_or_default();
+        let disk_usage = df
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .map(|row| {
+                let field = |key: &str| row[key].as_str().unwrap_or_default().to_string();
+                // Reclaimable reads like "1.2GB (50%)"
+                let reclaimable = field("Reclaimable");
+                DiskUsageEntry {
+                    kind: field("Type"),
+                    total: field("TotalCount").parse().unwrap_or(0),
+                    active: field("Active").parse().unwrap_or(0),
+                    size_bytes: super::parse_docker_size(&field("Size")),
+                    reclaimable_bytes: reclaimable
+                        .split_whitespace()
+                        .next()
+                        .and_then(super::parse_docker_size),
+                }
+            })
+            .collect();
+
+        let ipv6 = docker(&[
+            "network",
+            "inspect",
+            "bridge",
+            "--format",
+            "{{.EnableIPv6}}",
+        ])
+        .and_then(|out| out.parse::<bool>().ok());
+
+        let unhealthy = super::list_unhealthy_containers(exec).unwrap_or_default();
+
+        self.disk_usage = disk_usage;
+        self.ipv6_enabled = ipv6;
+        self.unhealthy_containers = unhealthy;
+    }
+
+    /// Print the report in the classic step-by-step layout
+    pub fn print(&self) {
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("Docker Daemon Diagnostics");
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!();
+        println!("Host: {}", self.hostname);
+        println!();
+
+        self.print_installation();
+        self.print_daemon();
+        self.print_service();
+        self.print_logs();
+        self.print_daemon_config();
+        self.print_socket();
+        self.print_user();
+        self.print_containerd();
+        print_summary();
+        if self.network_controller_error {
+            print_network_controller_help();
         }
-    } else {
-        println!("   ⚠ journalctl not available");
     }
-    println!();
-    Ok(())
-}
 
-fn check_daemon_config<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[5/8] Checking Docker daemon configuration...");
-    let daemon_json = "/etc/docker/daemon.json";
-    if exec.file_exists(daemon_json)? {
-        println!("   ✓ daemon.json exists");
-        let content = exec.read_file(daemon_json)?;
+    fn print_installation(&self) {
+        println!("[1/8] Checking if Docker is installed...");
+        if !self.installed {
+            println!("   ✗ Docker is not installed");
+            println!("   → Install with: hal install docker -H {}", self.hostname);
+        } else if let Some(version) = &self.client_version {
+            println!("   ✓ Docker installed: {}", version);
+        } else {
+            println!("   ✗ Docker command found but version check failed");
+        }
+        println!();
+    }
 
-        // Try to parse as JSON
-        if let Ok(_) = serde_json::from_str::<serde_json::Value>(&content) {
-            println!("   ✓ daemon.json is valid JSON");
+    fn print_daemon(&self) {
+        println!("[2/8] Checking Docker daemon accessibility...");
+        if !self.daemon_running {
+            println!("   ✗ Docker daemon is not accessible");
+            println!();
+            return;
+        }
+        if self.requires_sudo {
+            println!("   ⚠ Docker works with sudo - permission issue detected");
+            println!("   → User may need to be added to docker group");
+        } else {
+            println!("   ✓ Docker daemon is accessible");
+        }
+        if let Some(version) = &self.version {
+            println!("   → Server version: {}", version);
+        }
+        if let Some(driver) = &self.storage_driver {
+            println!("   → Storage driver: {}", driver);
+        }
+        match self.ipv6_enabled {
+            Some(true) => println!("   → IPv6: enabled"),
+            Some(false) => println!("   → IPv6: disabled"),
+            None => {}
+        }
+        for entry in &self.disk_usage {
+            println!(
+                "   → {}: {} total, {} active, {} ({} reclaimable)",
+                entry.kind,
+                entry.total,
+                entry.active,
+                size_or_unknown(entry.size_bytes),
+                size_or_unknown(entry.reclaimable_bytes)
+            );
+        }
+        if let Some(running) = self.containers_running {
+            println!("   → Running containers: {}", running);
+        }
+        if self.unhealthy_containers.is_empty() {
+            println!("   ✓ No unhealthy, restarting or failed containers");
         } else {
-            println!("   ✗ daemon.json contains invalid JSON!");
-            println!("   → This may prevent Docker from starting");
             println!(
-                "   → Validate with: sudo python3 -m json.tool {}",
-                daemon_json
+                "   ✗ {} container(s) need attention:",
+                self.unhealthy_containers.len()
             );
+            for container in &self.unhealthy_containers {
+                container.print("      ");
+            }
         }
+        println!();
+    }
 
-        // Check for backup
-        if exec.file_exists(&format!("{}.backup", daemon_json))? {
-            println!("   ✓ Backup exists: {}.backup", daemon_json);
+    fn print_service(&self) {
+        println!("[3/8] Checking Docker service status...");
+        match &self.service {
+            Some(service) => {
+                match service.active.as_str() {
+                    "active" => println!("   ✓ Docker service is active"),
+                    "inactive" => println!("   ✗ Docker service is inactive"),
+                    "failed" => println!("   ✗ Docker service has failed"),
+                    "" => println!("   ⚠ Docker service status unknown"),
+                    status => println!("   ⚠ Docker service status: {}", status),
+                }
+                if service.enabled {
+                    println!("   ✓ Service is enabled (will start on boot)");
+                } else {
+                    println!("   ⚠ Service is not enabled (won't start on boot)");
+                    println!("   → Enable with: sudo systemctl enable docker");
+                }
+            }
+            None => println!("   ⚠ systemctl not available (non-systemd system)"),
         }
-    } else {
-        println!("   ℹ daemon.json does not exist (using defaults)");
+        println!();
     }
-    println!();
-    Ok(())
-}
 
-fn check_socket_permissions<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[6/8] Checking Docker socket permissions...");
-    let socket_path = "/var/run/docker.sock";
-    if exec.file_exists(socket_path)? {
-        println!("   ✓ Docker socket exists");
-        // Try to get permissions (this might require sudo)
-        let stat_output = exec.execute_simple("stat", &["-c", "%a %U:%G", socket_path]);
-        if let Ok(output) = stat_output {
-            let perms = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            println!("   → Socket permissions: {}", perms);
+    fn print_logs(&self) {
+        println!("[4/8] Checking Docker service logs (last 20 lines)...");
+        if self.log_errors.is_empty() {
+            println!("   ✓ No obvious errors in recent logs");
         } else {
-            // Try with sudo
-            let sudo_stat = exec.execute_simple("sudo", &["stat", "-c", "%a %U:%G", socket_path]);
-            if let Ok(output) = sudo_stat {
-                let perms = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                println!("   → Socket permissions: {}", perms);
+            println!("   ✗ Errors found in logs:");
+            for line in &self.log_errors {
+                println!("      {}", line);
             }
         }
-    } else {
-        println!("   ✗ Docker socket not found (daemon likely not running)");
+        println!();
     }
-    println!();
-    Ok(())
-}
 
-fn check_user_permissions<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[7/8] Checking user Docker group membership...");
-    let username = exec.get_username()?;
-    println!("   → Current user: {}", username);
-
-    #[cfg(unix)]
-    {
-        use std::fs;
-        if let Ok(group_content) = fs::read_to_string("/etc/group") {
-            if let Some(docker_line) = group_content.lines().find(|l| l.starts_with("docker:")) {
-                if docker_line.contains(&username) {
-                    println!("   ✓ User is in docker group");
-                } else {
-                    println!("   ✗ User is NOT in docker group");
-                    println!("   → Add user with: sudo usermod -aG docker {}", username);
-                    println!("   → Then log out and back in, or run: newgrp docker");
-                }
-            } else {
-                println!("   ⚠ docker group not found");
-            }
+    fn print_daemon_config(&self) {
+        println!("[5/8] Checking Docker daemon configuration...");
+        if !self.daemon_json.exists {
+            println!("   ℹ daemon.json does not exist (using defaults)");
         } else {
-            // Fallback to groups command
-            let groups_output = exec.execute_simple("groups", &[])?;
-            let groups = String::from_utf8_lossy(&groups_output.stdout);
-            if groups.contains("docker") {
-                println!("   ✓ User is in docker group");
+            println!("   ✓ daemon.json exists");
+            if self.daemon_json.valid == Some(true) {
+                println!("   ✓ daemon.json is valid JSON");
             } else {
+                println!("   ✗ daemon.json contains invalid JSON!");
+                println!("   → This may prevent Docker from starting");
+                println!(
+                    "   → Validate with: sudo python3 -m json.tool {}",
+                    DAEMON_JSON
+                );
+            }
+        }
+        if self.daemon_json.backup_exists {
+            println!("   ✓ Backup exists: {}.backup", DAEMON_JSON);
+        }
+        println!();
+    }
+
+    fn print_socket(&self) {
+        println!("[6/8] Checking Docker socket permissions...");
+        match &self.socket {
+            Some(perms) => {
+                println!("   ✓ Docker socket exists");
+                if !perms.is_empty() {
+                    println!("   → Socket permissions: {}", perms);
+                }
+            }
+            None => println!("   ✗ Docker socket not found (daemon likely not running)"),
+        }
+        println!();
+    }
+
+    fn print_user(&self) {
+        println!("[7/8] Checking user Docker group membership...");
+        println!("   → Current user: {}", self.user);
+        match self.in_docker_group {
+            Some(true) => println!("   ✓ User is in docker group"),
+            Some(false) => {
                 println!("   ✗ User is NOT in docker group");
+                println!("   → Add user with: sudo usermod -aG docker {}", self.user);
+                println!("   → Then log out and back in, or run: newgrp docker");
             }
+            None => println!("   ⚠ Could not read group membership"),
         }
+        println!();
     }
-    println!();
-    Ok(())
-}
 
-fn check_containerd<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("[8/8] Checking containerd (Docker runtime)...");
-    if exec.check_command_exists("containerd")? {
-        println!("   ✓ containerd is installed");
-
-        if exec.check_command_exists("systemctl")? {
-            let ctrd_status = exec.execute_simple("systemctl", &["is-active", "containerd"]);
-            if let Ok(output) = ctrd_status {
-                let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                match status.as_str() {
-                    "active" => println!("   ✓ containerd service is active"),
-                    _ => {
-                        println!("   ⚠ containerd service is {}", status);
-                        println!("   → Start with: sudo systemctl start containerd");
-                    }
+    fn print_containerd(&self) {
+        println!("[8/8] Checking containerd (Docker runtime)...");
+        if !self.containerd.installed {
+            println!("   ⚠ containerd not found (may be bundled with Docker)");
+        } else {
+            println!("   ✓ containerd is installed");
+            match self.containerd.service.as_deref() {
+                Some("active") => println!("   ✓ containerd service is active"),
+                Some(status) => {
+                    println!("   ⚠ containerd service is {}", status);
+                    println!("   → Start with: sudo systemctl start containerd");
                 }
+                None => {}
             }
         }
+        println!();
+    }
+}
+
+/// Run comprehensive Docker daemon diagnostics, printing text or a JSON report
+pub fn diagnose_docker<E: CommandExecutor>(exec: &E, hostname: &str, json: bool) -> Result<()> {
+    let mut diag = DockerDiagnostics::collect(exec, hostname)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diag)?);
     } else {
-        println!("   ⚠ containerd not found (may be bundled with Docker)");
+        for container in &mut diag.unhealthy_containers {
+            container.load_recent_logs(exec);
+        }
+        diag.print();
     }
-    println!();
     Ok(())
 }
 
-fn print_summary<E: CommandExecutor>(_exec: &E) -> Result<()> {
+/// Trimmed stdout of a successful command, or None
+fn stdout_of<E: CommandExecutor>(exec: &E, program: &str, args: &[&str]) -> Option<String> {
+    let output = exec.execute_simple(program, args).ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Trimmed stdout regardless of exit status (`systemctl is-active` exits non-zero when inactive)
+fn status_of<E: CommandExecutor>(exec: &E, program: &str, args: &[&str]) -> String {
+    exec.execute_simple(program, args)
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Like `stdout_of`, but through sudo
+fn via_sudo<E: CommandExecutor>(exec: &E, program: &str, args: &[&str]) -> Option<String> {
+    let mut sudo_args = vec![program];
+    sudo_args.extend_from_slice(args);
+    stdout_of(exec, "sudo", &sudo_args)
+}
+
+fn size_or_unknown(bytes: Option<u64>) -> String {
+    bytes.map(format_bytes).unwrap_or_else(|| "?".to_string())
+}
+
+fn print_summary() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Diagnostic Summary");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -280,53 +464,32 @@ fn print_summary<E: CommandExecutor>(_exec: &E) -> Result<()> {
     println!("  4. Check logs: sudo journalctl -xeu docker.service");
     println!("  5. Validate config: sudo python3 -m json.tool /etc/docker/daemon.json");
     println!();
-    Ok(())
 }
 
-fn check_network_controller_errors<E: CommandExecutor>(exec: &E) -> Result<()> {
+fn print_network_controller_help() {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Network Controller Error Detection");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
-
-    if exec.check_command_exists("journalctl")? {
-        let log_output = exec.execute_simple(
-            "journalctl",
-            &["-u", "docker.service", "-n", "50", "--no-pager"],
-        );
-        if let Ok(output) = log_output {
-            let logs = String::from_utf8_lossy(&output.stdout);
-            let log_lower = logs.to_lowercase();
-
-            if log_lower.contains("network controller")
-                || log_lower.contains("error creating default")
-            {
-                println!("⚠ Network controller error detected!");
-                println!();
-                println!("This error typically indicates corrupted Docker network state.");
-                println!("Try these fixes (in order):");
-                println!();
-                println!("1. Clean Docker network state:");
-                println!("   sudo rm -rf /var/lib/docker/network");
-                println!("   sudo systemctl start docker");
-                println!();
-                println!("2. If that doesn't work, reset iptables:");
-                println!("   sudo iptables -t nat -F");
-                println!("   sudo iptables -t mangle -F");
-                println!("   sudo iptables -F");
-                println!("   sudo iptables -X");
-                println!("   sudo systemctl start docker");
-                println!();
-                println!(
-                    "3. As a last resort, clean all Docker data (⚠️  removes all containers/images):"
-                );
-                println!("   sudo systemctl stop docker");
-                println!("   sudo rm -rf /var/lib/docker");
-                println!("   sudo systemctl start docker");
-                println!();
-            }
-        }
-    }
-
-    Ok(())
+    println!("⚠ Network controller error detected!");
+    println!();
+    println!("This error typically indicates corrupted Docker network state.");
+    println!("Try these fixes (in order):");
+    println!();
+    println!("1. Clean Docker network state:");
+    println!("   sudo rm -rf /var/lib/docker/network");
+    println!("   sudo systemctl start docker");
+    println!();
+    println!("2. If that doesn't work, reset iptables:");
+    println!("   sudo iptables -t nat -F");
+    println!("   sudo iptables -t mangle -F");
+    println!("   sudo iptables -F");
+    println!("   sudo iptables -X");
+    println!("   sudo systemctl start docker");
+    println!();
+    println!("3. As a last resort, clean all Docker data (⚠️  removes all containers/images):");
+    println!("   sudo systemctl stop docker");
+    println!("   sudo rm -rf /var/lib/docker");
+    println!("   sudo systemctl start docker");
+    println!();
 }
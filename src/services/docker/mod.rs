@@ -1,7 +1,13 @@
 use crate::config::EnvConfig;
-use crate::utils::exec::{CommandExecutor, Executor};
+use crate::utils::exec::{CommandExecutor, DryRunExecutor, Executor};
+use crate::utils::format_bytes;
+use crate::utils::networking;
+use crate::utils::ssh::shell_escape;
 use anyhow::{Context, Result};
+use log::{debug, error, info, warn};
+use serde::Serialize;
 use serde_json::{Value, json};
+use std::collections::HashSet;
 
 pub mod build;
 pub mod diagnostics;
@@ -18,7 +24,7 @@ pub fn ensure_docker_running<E: CommandExecutor>(exec: &E) -> Result<()> {
     }
 
     // Docker daemon might not be running, try to start it
-    println!("Docker daemon not accessible, attempting to start...");
+    info!("Docker daemon not accessible, attempting to start...");
 
     if exec.check_command_exists("systemctl")? {
         // Check if docker service exists
@@ -26,7 +32,7 @@ pub fn ensure_docker_running<E: CommandExecutor>(exec: &E) -> Result<()> {
         if let Ok(output) = status_output {
             let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
             if status == "inactive" || status == "failed" {
-                println!("Starting Docker daemon...");
+                info!("Starting Docker daemon...");
                 exec.execute_interactive("sudo", &["systemctl", "start", "docker"])?;
                 exec.execute_interactive("sudo", &["systemctl", "enable", "docker"])?;
                 // Wait a moment for Docker to start
@@ -47,7 +53,7 @@ pub fn ensure_docker_running<E: CommandExecutor>(exec: &E) -> Result<()> {
     let verify_output = exec.execute_simple("docker", &["info"]);
     match verify_output {
         Ok(output) if output.status.success() => {
-            println!("✓ Docker daemon is running");
+            info!("✓ Docker daemon is running");
             Ok(())
         }
         _ => {
@@ -56,8 +62,8 @@ pub fn ensure_docker_running<E: CommandExecutor>(exec: &E) -> Result<()> {
             let sudo_check = exec.execute_simple("sudo", &["docker", "info"]);
             if let Ok(output) = sudo_check {
                 if output.status.success() {
-                    println!("⚠ Docker daemon is running but user doesn't have access");
-                    println!("⚠ User may need to be added to docker group or use sudo");
+                    warn!("⚠ Docker daemon is running but user doesn't have access");
+                    warn!("⚠ User may need to be added to docker group or use sudo");
                     // Don't fail here, let configure_permissions handle it
                     return Ok(());
                 }
@@ -71,10 +77,10 @@ pub fn ensure_docker_running<E: CommandExecutor>(exec: &E) -> Result<()> {
 
 /// Check if Docker is installed and install it if not
 pub fn check_and_install<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("=== Checking Docker installation ===");
+    info!("=== Checking Docker installation ===");
 
     if exec.check_command_exists("docker")? {
-        println!("✓ Docker already installed");
+        info!("✓ Docker already installed");
         // Ensure daemon is running
         ensure_docker_running(exec)?;
         return Ok(());
@@ -87,15 +93,15 @@ pub fn check_and_install<E: CommandExecutor>(exec: &E) -> Result<()> {
     let is_local_executor = exec.read_file("/etc/os-release").is_err();
 
     if is_local_executor {
-        println!("Docker not found. Please install Docker manually.");
-        println!("  Linux: https://docs.docker.com/engine/install/");
-        println!("  macOS: https://docs.docker.com/desktop/install/mac-install/");
-        println!("  Windows: https://docs.docker.com/desktop/install/windows-install/");
+        warn!("Docker not found. Please install Docker manually.");
+        warn!("  Linux: https://docs.docker.com/engine/install/");
+        warn!("  macOS: https://docs.docker.com/desktop/install/mac-install/");
+        warn!("  Windows: https://docs.docker.com/desktop/install/windows-install/");
         anyhow::bail!("Docker installation required");
     }
 
     // For remote execution, install Docker automatically
-    println!("Docker not found. Installing Docker...");
+    info!("Docker not found. Installing Docker...");
 
     // Detect OS type
     let os_release_output = exec.read_file("/etc/os-release")?;
@@ -129,19 +135,19 @@ pub fn check_and_install<E: CommandExecutor>(exec: &E) -> Result<()> {
     } else if exec.check_command_exists("dnf")? {
         install_fedora(exec)?;
     } else if exec.check_command_exists("brew")? {
-        println!("Detected macOS");
+        debug!("Detected macOS");
         exec.execute_interactive("brew", &["install", "--cask", "docker"])?;
-        println!("Please start Docker Desktop manually");
+        warn!("Please start Docker Desktop manually");
     } else {
         anyhow::bail!("Unsupported package manager. Please install Docker manually.");
     }
 
-    println!("✓ Docker installed");
+    info!("✓ Docker installed");
     Ok(())
 }
 
 fn install_debian<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("Detected Debian, using Debian Docker repository");
+    debug!("Detected Debian, using Debian Docker repository");
     exec.execute_interactive("sudo", &["rm", "-f", "/etc/apt/sources.list.d/docker.list"])?;
     exec.execute_interactive("sudo", &["apt-get", "update"])?;
     exec.execute_interactive(
@@ -198,7 +204,7 @@ fn install_debian<E: CommandExecutor>(exec: &E) -> Result<()> {
 }
 
 fn install_ubuntu<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("Detected Ubuntu, using Ubuntu Docker repository");
+    debug!("Detected Ubuntu, using Ubuntu Docker repository");
     exec.execute_interactive("sudo", &["rm", "-f", "/etc/apt/sources.list.d/docker.list"])?;
     exec.execute_interactive("sudo", &["apt-get", "update"])?;
     exec.execute_interactive(
@@ -250,7 +256,7 @@ fn install_ubuntu<E: CommandExecutor>(exec: &E) -> Result<()> {
 }
 
 fn install_rhel_centos<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("Detected RHEL/CentOS");
+    debug!("Detected RHEL/CentOS");
     exec.execute_interactive("sudo", &["yum", "install", "-y", "yum-utils"])?;
     exec.execute_interactive(
         "sudo",
@@ -279,7 +285,7 @@ fn install_rhel_centos<E: CommandExecutor>(exec: &E) -> Result<()> {
 }
 
 fn install_fedora<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!("Detected Fedora");
+    debug!("Detected Fedora");
     exec.execute_interactive("sudo", &["dnf", "install", "-y", "dnf-plugins-core"])?;
     exec.execute_interactive(
         "sudo",
@@ -325,8 +331,8 @@ fn install_gpg_key<E: CommandExecutor>(exec: &E, url: &str) -> Result<()> {
 
 /// Configure Docker permissions (works for both local and remote)
 pub fn configure_permissions<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!();
-    println!("=== Configuring Docker permissions ===");
+    info!("");
+    info!("=== Configuring Docker permissions ===");
 
     if !exec.is_linux()? {
         return Ok(());
@@ -357,17 +363,17 @@ pub fn configure_permissions<E: CommandExecutor>(exec: &E) -> Result<()> {
     };
 
     if !in_group {
-        println!("Adding user to docker group...");
+        info!("Adding user to docker group...");
         exec.execute_interactive("sudo", &["usermod", "-aG", "docker", &username])?;
-        println!("✓ User added to docker group");
-        println!("Note: You may need to log out and back in for changes to take effect");
+        info!("✓ User added to docker group");
+        info!("Note: You may need to log out and back in for changes to take effect");
 
         // Try to apply group changes immediately using newgrp or by checking if we can use docker
         // For SSH sessions, we can't easily apply group changes, but we can try using newgrp
         // However, this is complex, so we'll just note it and continue
         // The user can use 'newgrp docker' or restart their session
     } else {
-        println!("✓ User already in docker group");
+        info!("✓ User already in docker group");
     }
 
     // Verify Docker access after group configuration
@@ -378,134 +384,192 @@ pub fn configure_permissions<E: CommandExecutor>(exec: &E) -> Result<()> {
     match test_output {
         Ok(output) if output.status.success() => {
             let _version = String::from_utf8_lossy(&output.stdout);
-            println!("✓ Docker access verified");
+            info!("✓ Docker access verified");
         }
         _ => {
             // Try with newgrp docker if available, otherwise warn
-            println!(
+            warn!(
                 "⚠ Docker command failed - user may need to run 'newgrp docker' or restart SSH session"
             );
-            println!("⚠ Alternatively, using 'sudo docker' commands will work");
+            warn!("⚠ Alternatively, using 'sudo docker' commands will work");
         }
     }
 
     Ok(())
 }
 
+/// Path of the Docker daemon configuration on Linux hosts
+pub const DAEMON_JSON: &str = "/etc/docker/daemon.json";
+
 /// Configure Docker IPv6 support (works for both local and remote)
 pub fn configure_ipv6<E: CommandExecutor>(exec: &E) -> Result<()> {
-    println!();
-    println!("=== Configuring Docker IPv6 support ===");
+    info!("");
+    info!("=== Configuring Docker IPv6 support ===");
 
     if !exec.is_linux()? {
-        println!(
+        info!(
             "Skipping IPv6 configuration (macOS/Windows - Docker Desktop handles IPv6 differently)"
         );
         return Ok(());
     }
 
     let ipv6_subnet = "fd00:172:20::/64";
-    let daemon_file = "/etc/docker/daemon.json";
-
-    // Check if IPv6 is already enabled
-    let ipv6_enabled = if exec.file_exists(daemon_file)? {
-        let content = exec.read_file(daemon_file)?;
-        content.contains("\"ipv6\"") && content.contains("true")
-    } else {
-        false
-    };
 
+    // Leave hosts that already run IPv6 (possibly with their own subnet) alone
+    let ipv6_enabled = exec.file_exists(DAEMON_JSON)?
+        && serde_json::from_str::<Value>(&exec.read_file(DAEMON_JSON)?)
+            .is_ok_and(|config| config["ipv6"] == json!(true));
     if ipv6_enabled {
-        println!("✓ IPv6 already enabled in Docker daemon");
+        info!("✓ IPv6 already enabled in Docker daemon");
         return Ok(());
     }
 
-    println!("Configuring IPv6 in Docker daemon...");
+    info!("Configuring IPv6 in Docker daemon...");
+    merge_daemon_json(
+        exec,
+        json!({
+            "ipv6": true,
+            "fixed-cidr-v6": ipv6_subnet
+        }),
+    )?;
 
-    // Create directory if needed
-    exec.execute_interactive("sudo", &["mkdir", "-p", "/etc/docker"])?;
+    info!("✓ IPv6 configured in Docker daemon");
+    if !restart_docker_daemon(exec)? {
+        return Ok(());
+    }
 
-    // Check if daemon.json exists
-    let exists = exec.file_exists(daemon_file)?;
+    // Verify Docker is running and IPv6 is enabled
+    let verify_output = exec.execute_simple("docker", &["info"]);
+    match verify_output {
+        Ok(output) => {
+            let docker_info = String::from_utf8_lossy(&output.stdout);
+            if docker_info.to_lowercase().contains("ipv6")
+                && docker_info.to_lowercase().contains("true")
+            {
+                info!("✓ IPv6 verified in Docker");
+            } else {
+                warn!("Warning: IPv6 may not be enabled. Check with: docker info | grep -i ipv6");
+            }
+        }
+        Err(e) => {
+            warn!("Warning: Could not verify Docker status: {}", e);
+            warn!("Docker may still be starting up. Check with: docker info");
+        }
+    }
 
-    if !exists {
-        // Create new daemon.json
-        println!("Creating new Docker daemon configuration...");
-        let config = json!({
-            "ipv6": true,
-            "fixed-cidr-v6": ipv6_subnet
-        });
-        let config_str = serde_json::to_string_pretty(&config)?;
-        exec.write_file("/tmp/daemon.json", config_str.as_bytes())?;
-        exec.execute_interactive(
-            "sudo",
-            &["mv", "/tmp/daemon.json", "/etc/docker/daemon.json"],
-        )?;
-    } else {
-        // Update existing daemon.json
-        println!("Updating existing Docker daemon configuration...");
+    Ok(())
+}
 
-        // Use Rust-native JSON manipulation (more reliable than Python via SSH)
-        match update_daemon_json_rust(exec, ipv6_subnet) {
-            Ok(_) => {
-                println!("✓ Docker daemon configuration updated");
+/// Deep-merge `patch` into `base`
+///
+/// Objects merge key by key, `null` removes a key, arrays gain the patch items they don't already
+/// contain, and any other value replaces the existing one.
+pub fn merge_json(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    base.remove(&key);
+                } else {
+                    merge_json(base.entry(key).or_insert(Value::Null), value);
+                }
             }
-            Err(e) => {
-                // Fallback: backup and create new if Rust method fails
-                println!("Warning: Failed to update existing config: {}", e);
-                println!("Backing up existing config and creating new one...");
-                exec.execute_interactive(
-                    "sudo",
-                    &[
-                        "cp",
-                        "/etc/docker/daemon.json",
-                        "/etc/docker/daemon.json.backup",
-                    ],
-                )?;
-                let config = json!({
-                    "ipv6": true,
-                    "fixed-cidr-v6": ipv6_subnet
-                });
-                let config_str = serde_json::to_string_pretty(&config)?;
-                exec.write_file("/tmp/daemon.json", config_str.as_bytes())?;
+        }
+        (Value::Array(base), Value::Array(patch)) => {
+            for item in patch {
+                if !base.contains(&item) {
+                    base.push(item);
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+/// Deep-merge a JSON patch into /etc/docker/daemon.json, keeping every key the patch doesn't touch
+///
+/// The result is validated before it replaces the file. An existing file that isn't valid JSON is
+/// copied to daemon.json.backup and replaced by the patch alone. Returns whether the file changed;
+/// the daemon is not restarted.
+pub fn merge_daemon_json<E: CommandExecutor>(exec: &E, patch: Value) -> Result<bool> {
+    if !patch.is_object() {
+        anyhow::bail!("daemon.json patch must be a JSON object, got: {}", patch);
+    }
+
+    exec.execute_interactive("sudo", &["mkdir", "-p", "/etc/docker"])?;
+
+    let existing = if exec.file_exists(DAEMON_JSON)? {
+        let content = exec.read_file(DAEMON_JSON)?;
+        match serde_json::from_str::<Value>(&content) {
+            Ok(config) if config.is_object() => Some(config),
+            _ if content.trim().is_empty() => None,
+            _ => {
+                warn!("Warning: {} is not a valid JSON object", DAEMON_JSON);
                 exec.execute_interactive(
                     "sudo",
-                    &["mv", "/tmp/daemon.json", "/etc/docker/daemon.json"],
+                    &["cp", DAEMON_JSON, "/etc/docker/daemon.json.backup"],
                 )?;
-                println!("Original config backed up to /etc/docker/daemon.json.backup");
+                info!("Original config backed up to /etc/docker/daemon.json.backup");
+                None
             }
         }
+    } else {
+        None
+    };
+
+    let mut config = existing.clone().unwrap_or_else(|| json!({}));
+    merge_json(&mut config, patch);
+    if existing.as_ref() == Some(&config) {
+        return Ok(false);
     }
 
-    println!("✓ IPv6 configured in Docker daemon");
-    println!("Restarting Docker daemon to apply changes...");
+    // Validate JSON before writing
+    let updated_content = serde_json::to_string_pretty(&config)? + "\n";
+    serde_json::from_str::<Value>(&updated_content)
+        .with_context(|| "Generated invalid JSON for daemon.json")?;
+
+    exec.write_file("/tmp/daemon.json", updated_content.as_bytes())?;
+
+    // Validate the file can be read back as JSON before moving it
+    let verify_content = exec.read_file("/tmp/daemon.json")?;
+    serde_json::from_str::<Value>(&verify_content)
+        .with_context(|| "Written file contains invalid JSON")?;
+
+    exec.execute_interactive("sudo", &["mv", "/tmp/daemon.json", DAEMON_JSON])?;
+    debug!("Updated {}", DAEMON_JSON);
+    Ok(true)
+}
+
+/// Restart the Docker daemon after a daemon.json change
+///
+/// Returns false when neither systemctl nor service is available to restart it.
+pub fn restart_docker_daemon<E: CommandExecutor>(exec: &E) -> Result<bool> {
+    info!("Restarting Docker daemon to apply changes...");
 
     let restart_result = if exec.check_command_exists("systemctl")? {
         exec.execute_interactive("sudo", &["systemctl", "restart", "docker"])
     } else if exec.check_command_exists("service")? {
         exec.execute_interactive("sudo", &["service", "docker", "restart"])
     } else {
-        println!(
+        warn!(
             "Warning: Could not restart Docker daemon. Please restart manually: sudo systemctl restart docker"
         );
-        return Ok(());
+        return Ok(false);
     };
 
     match restart_result {
         Ok(_) => {
-            println!("✓ Docker daemon restarted successfully");
+            info!("✓ Docker daemon restarted successfully");
         }
         Err(e) => {
-            println!("Error: Failed to restart Docker daemon: {}", e);
-            println!();
-            println!("This may indicate a syntax error in /etc/docker/daemon.json");
-            println!("To diagnose:");
-            println!("  1. Check Docker daemon status: sudo systemctl status docker.service");
-            println!("  2. Check Docker logs: sudo journalctl -xeu docker.service");
-            println!(
-                "  3. Validate daemon.json: sudo python3 -m json.tool /etc/docker/daemon.json"
-            );
-            println!(
+            error!("Error: Failed to restart Docker daemon: {}", e);
+            error!("");
+            error!("This may indicate a syntax error in /etc/docker/daemon.json");
+            error!("To diagnose:");
+            error!("  1. Check Docker daemon status: sudo systemctl status docker.service");
+            error!("  2. Check Docker logs: sudo journalctl -xeu docker.service");
+            error!("  3. Validate daemon.json: sudo python3 -m json.tool /etc/docker/daemon.json");
+            error!(
                 "  4. If needed, restore backup: sudo cp /etc/docker/daemon.json.backup /etc/docker/daemon.json"
             );
             return Err(e).context("Docker daemon restart failed - check daemon.json syntax");
@@ -514,61 +578,33 @@ pub fn configure_ipv6<E: CommandExecutor>(exec: &E) -> Result<()> {
 
     // Wait a moment for Docker to start
     std::thread::sleep(std::time::Duration::from_secs(3));
-
-    // Verify Docker is running and IPv6 is enabled
-    let verify_output = exec.execute_simple("docker", &["info"]);
-    match verify_output {
-        Ok(output) => {
-            let docker_info = String::from_utf8_lossy(&output.stdout);
-            if docker_info.to_lowercase().contains("ipv6")
-                && docker_info.to_lowercase().contains("true")
-            {
-                println!("✓ IPv6 verified in Docker");
-            } else {
-                println!(
-                    "Warning: IPv6 may not be enabled. Check with: docker info | grep -i ipv6"
-                );
-            }
-        }
-        Err(e) => {
-            println!("Warning: Could not verify Docker status: {}", e);
-            println!("Docker may still be starting up. Check with: docker info");
-        }
-    }
-
-    Ok(())
+    Ok(true)
 }
 
-/// Generic version of update_daemon_json_rust that works with any CommandExecutor
-fn update_daemon_json_rust<E: CommandExecutor>(exec: &E, ipv6_subnet: &str) -> Result<()> {
-    // Read existing config
-    let content = exec.read_file("/etc/docker/daemon.json")?;
-    let mut config: Value = serde_json::from_str(&content)
-        .with_context(|| "Failed to parse /etc/docker/daemon.json")?;
-
-    // Update config
-    config["ipv6"] = json!(true);
-    config["fixed-cidr-v6"] = json!(ipv6_subnet);
-
-    // Validate JSON before writing
-    let updated_content = serde_json::to_string_pretty(&config)?;
-
-    // Verify the JSON is valid by parsing it back
-    serde_json::from_str::<Value>(&updated_content)
-        .with_context(|| "Generated invalid JSON for daemon.json")?;
-
-    exec.write_file("/tmp/daemon.json", updated_content.as_bytes())?;
+/// Merge a JSON patch into a host's daemon.json and restart Docker if it changed
+/// (`hal docker config --set-json`)
+pub fn set_daemon_json(hostname: &str, patch: &str, config: &EnvConfig) -> Result<()> {
+    let patch: Value = serde_json::from_str(patch)
+        .with_context(|| format!("--set-json is not valid JSON: {}", patch))?;
+    let exec = Executor::new(hostname, config)?;
 
-    // Validate the file can be read back as JSON before moving it
-    let verify_content = exec.read_file("/tmp/daemon.json")?;
-    serde_json::from_str::<Value>(&verify_content)
-        .with_context(|| "Written file contains invalid JSON")?;
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Docker daemon configuration on {}", hostname);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-    exec.execute_interactive(
-        "sudo",
-        &["mv", "/tmp/daemon.json", "/etc/docker/daemon.json"],
-    )?;
+    if !exec.is_linux()? {
+        anyhow::bail!(
+            "{} is not a Linux host - configure Docker Desktop through its settings instead",
+            hostname
+        );
+    }
 
+    if !merge_daemon_json(&exec, patch)? {
+        println!("✓ {} already contains these settings", DAEMON_JSON);
+        return Ok(());
+    }
+    println!("✓ Updated {}", DAEMON_JSON);
+    restart_docker_daemon(&exec)?;
     Ok(())
 }
 
@@ -616,6 +652,135 @@ pub fn start_containers<E: CommandExecutor>(exec: &E, container_ids: &[String])
     Ok(())
 }
 
+/// Summary of resources removed by a prune operation
+#[derive(Debug, Default, Clone)]
+pub struct PruneReport {
+    pub containers_removed: usize,
+    pub images_removed: usize,
+    pub networks_removed: usize,
+    pub volumes_removed: usize,
+    pub bytes_reclaimed: u64,
+}
+
+impl PruneReport {
+    /// Print a human-readable summary of the prune
+    pub fn print_summary(&self) {
+        println!("  Containers removed: {}", self.containers_removed);
+        println!("  Images removed:     {}", self.images_removed);
+        println!("  Networks removed:   {}", self.networks_removed);
+        println!("  Volumes removed:    {}", self.volumes_removed);
+        println!(
+            "  Space reclaimed:    {}",
+            format_bytes(self.bytes_reclaimed)
+        );
+    }
+
+    /// Parse the output of `docker system prune` / `docker volume prune` into this report
+    /// Images aren't counted here: "Deleted Images:" lists tags and every deleted layer
+    fn merge_output(&mut self, output: &str) {
+        let mut section: Option<&str> = None;
+        for line in output.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                section = None;
+                continue;
+            }
+            if let Some(size) = trimmed.strip_prefix("Total reclaimed space:") {
+                self.bytes_reclaimed += parse_docker_size(size.trim()).unwrap_or(0);
+                section = None;
+                continue;
+            }
+            if trimmed.starts_with("Deleted ") && trimmed.ends_with(':') {
+                section = Some(trimmed);
+                continue;
+            }
+            match section {
+                Some("Deleted Containers:") => self.containers_removed += 1,
+                Some("Deleted Networks:") => self.networks_removed += 1,
+                Some("Deleted Volumes:") => self.volumes_removed += 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Parse a Docker human-readable size (e.g. "1.2GB", "512kB", "0B") into bytes
+/// Docker reports sizes using decimal (SI) units
+fn parse_docker_size(size: &str) -> Option<u64> {
+    let split_at = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split_at);
+    let value: f64 = number.parse().ok()?;
+    let multiplier: f64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    Some((value * multiplier) as u64)
+}
+
+/// Run a docker subcommand and return its stdout, falling back to sudo on failure
+fn run_docker<E: CommandExecutor>(exec: &E, args: &[&str]) -> Result<String> {
+    let output = exec.execute_simple("docker", args)?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+
+    // Try with sudo
+    let mut sudo_args = vec!["docker"];
+    sudo_args.extend_from_slice(args);
+    let sudo_output = exec.execute_simple("sudo", &sudo_args)?;
+    if sudo_output.status.success() {
+        Ok(String::from_utf8_lossy(&sudo_output.stdout).to_string())
+    } else {
+        anyhow::bail!(
+            "Failed to run docker {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&sudo_output.stderr).trim()
+        )
+    }
+}
+
+/// IDs of every image on the host, including intermediate ones
+fn image_ids<E: CommandExecutor>(exec: &E) -> Result<HashSet<String>> {
+    let output = run_docker(exec, &["image", "ls", "-a", "-q", "--no-trunc"])?;
+    Ok(output
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+/// Remove stopped containers, dangling images, and unused networks
+/// Optionally also removes unused volumes, named ones included
+pub fn prune_system<E: CommandExecutor>(exec: &E, include_volumes: bool) -> Result<PruneReport> {
+    let mut report = PruneReport::default();
+
+    let images_before = image_ids(exec)?;
+    let system_output = run_docker(exec, &["system", "prune", "-f"])?;
+    report.merge_output(&system_output);
+    let images_after = image_ids(exec)?;
+    report.images_removed = images_before.difference(&images_after).count();
+
+    if include_volumes {
+        // Docker 23+ only prunes anonymous volumes unless --all is given; older versions
+        // don't know the flag but already prune every unused volume
+        let volume_output = match run_docker(exec, &["volume", "prune", "--all", "-f"]) {
+            Err(e) if e.to_string().contains("unknown flag") => {
+                run_docker(exec, &["volume", "prune", "-f"])?
+            }
+            result => result?,
+        };
+        report.merge_output(&volume_output);
+    }
+
+    Ok(report)
+}
+
 /// Get all Docker volumes
 pub fn list_volumes<E: CommandExecutor>(exec: &E) -> Result<Vec<String>> {
     let volumes_output =
@@ -629,6 +794,26 @@ pub fn list_volumes<E: CommandExecutor>(exec: &E) -> Result<Vec<String>> {
     Ok(volumes)
 }
 
+/// Named volumes belonging to a compose project, which `down -v` would delete
+pub fn list_project_volumes<E: CommandExecutor>(exec: &E, project: &str) -> Result<Vec<String>> {
+    let volumes = run_docker(
+        exec,
+        &[
+            "volume",
+            "ls",
+            "--filter",
+            &format!("label=com.docker.compose.project={}", project),
+            "--format",
+            "{{.Name}}",
+        ],
+    )?;
+    Ok(volumes
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|s| s.to_string())
+        .collect())
+}
+
 /// Backup a Docker volume
 pub fn backup_volume<E: CommandExecutor>(exec: &E, volume: &str, backup_dir: &str) -> Result<()> {
     let backup_cmd = format!(
@@ -653,7 +838,110 @@ pub fn backup_volume<E: CommandExecutor>(exec: &E, volume: &str, backup_dir: &st
     }
 }
 
+/// How a volume backup is stored in the backup directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeBackupStrategy {
+    /// `<volume>.tar.gz` tarball
+    Tar,
+    /// `<volume>/` directory tree kept in sync with rsync
+    Rsync,
+}
+
+impl VolumeBackupStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VolumeBackupStrategy::Tar => "tar",
+            VolumeBackupStrategy::Rsync => "rsync",
+        }
+    }
+}
+
+/// Path of the marker file recording which strategy was used for a volume backup
+fn backup_strategy_marker(volume: &str, backup_dir: &str) -> String {
+    format!("{}/{}.strategy", backup_dir, volume)
+}
+
+/// Read the strategy used for a volume backup (tarball if no marker was recorded)
+pub fn volume_backup_strategy<E: CommandExecutor>(
+    exec: &E,
+    volume: &str,
+    backup_dir: &str,
+) -> Result<VolumeBackupStrategy> {
+    let marker = backup_strategy_marker(volume, backup_dir);
+    if exec.file_exists(&marker)? && exec.read_file(&marker)?.trim() == "rsync" {
+        Ok(VolumeBackupStrategy::Rsync)
+    } else {
+        Ok(VolumeBackupStrategy::Tar)
+    }
+}
+
+/// Run a shell command, retrying with sudo if it fails
+fn run_shell_with_sudo<E: CommandExecutor>(exec: &E, command: &str) -> Result<bool> {
+    if exec.execute_shell(command)?.status.success() {
+        return Ok(true);
+    }
+    Ok(exec
+        .execute_shell(&format!("sudo {}", command))?
+        .status
+        .success())
+}
+
+/// Backup a Docker volume incrementally
+/// Syncs the volume into `<backup_dir>/<volume>/` with rsync (run inside the alpine helper
+/// container). When `previous_dir` holds an rsync tree of the same volume, files that haven't
+/// changed since are hard-linked from it instead of copied, so only changed files are
+/// transferred. Falls back to a full tarball when rsync isn't available on the host or the sync
+/// fails. The strategy used is recorded next to the backup for restore.
+pub fn backup_volume_incremental<E: CommandExecutor>(
+    exec: &E,
+    volume: &str,
+    backup_dir: &str,
+    previous_dir: Option<&str>,
+) -> Result<VolumeBackupStrategy> {
+    let mut strategy = VolumeBackupStrategy::Tar;
+
+    if exec.check_command_exists("rsync")? {
+        let previous = match previous_dir {
+            Some(dir)
+                if volume_backup_strategy(exec, volume, dir)? == VolumeBackupStrategy::Rsync =>
+            {
+                Some(dir)
+            }
+            _ => None,
+        };
+        let (previous_mount, link_dest) = match previous {
+            Some(dir) => (
+                format!(" -v {}:/previous:ro", dir),
+                format!(" --link-dest=/previous/{}", volume),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let rsync_cmd = format!(
+            "docker run --rm -v {}:/data:ro -v {}:/backup{} alpine sh -c 'apk add --no-cache -q rsync && mkdir -p /backup/{} && rsync -a --delete{} /data/ /backup/{}/'",
+            volume, backup_dir, previous_mount, volume, link_dest, volume
+        );
+        if run_shell_with_sudo(exec, &rsync_cmd)? {
+            strategy = VolumeBackupStrategy::Rsync;
+        } else {
+            warn!("    ⚠ rsync backup failed, falling back to tar");
+            // Don't leave a partial tree next to the tarball
+            run_shell_with_sudo(exec, &format!("rm -rf {}/{}", backup_dir, volume))?;
+        }
+    }
+
+    if strategy == VolumeBackupStrategy::Tar {
+        backup_volume(exec, volume, backup_dir)?;
+    }
+
+    exec.write_file(
+        &backup_strategy_marker(volume, backup_dir),
+        strategy.as_str().as_bytes(),
+    )?;
+    Ok(strategy)
+}
+
 /// Restore a Docker volume
+/// Handles both tarball and rsync tree backups (see `backup_volume_incremental`)
 pub fn restore_volume<E: CommandExecutor>(exec: &E, volume: &str, backup_dir: &str) -> Result<()> {
     // Check if volume exists, create if not
     let inspect_output = exec.execute_simple("docker", &["volume", "inspect", volume])?;
@@ -669,6 +957,17 @@ pub fn restore_volume<E: CommandExecutor>(exec: &E, volume: &str, backup_dir: &s
         }
     }
 
+    if volume_backup_strategy(exec, volume, backup_dir)? == VolumeBackupStrategy::Rsync {
+        let copy_cmd = format!(
+            "docker run --rm -v {}:/data -v {}:/backup:ro alpine sh -c 'cd /data && rm -rf * && cp -a /backup/{}/. /data/'",
+            volume, backup_dir, volume
+        );
+        if run_shell_with_sudo(exec, &copy_cmd)? {
+            return Ok(());
+        }
+        anyhow::bail!("Failed to restore volume: {}", volume);
+    }
+
     // Restore the volume
     let restore_cmd = format!(
         "docker run --rm -v {}:/data -v {}:/backup alpine sh -c 'cd /data && rm -rf * && tar xzf /backup/{}.tar.gz'",
@@ -695,7 +994,7 @@ pub fn restore_volume<E: CommandExecutor>(exec: &E, volume: &str, backup_dir: &s
 /// Get bind mounts from a container
 pub fn get_bind_mounts<E: CommandExecutor>(exec: &E, container: &str) -> Result<Vec<String>> {
     let inspect_cmd = format!(
-        r#"docker inspect {} --format '{{{{range .Mounts}}}}{{{{if eq .Type "bind"}}}}{{{{.Source}}}}{{{{end}}}}{{{{end}}}}'"#,
+        r#"docker inspect {} --format '{{{{range .Mounts}}}}{{{{if eq .Type "bind"}}}}{{{{.Source}}}}{{{{"\n"}}}}{{{{end}}}}{{{{end}}}}'"#,
         container
     );
     let mounts_output = exec.execute_shell(&inspect_cmd)?;
@@ -708,10 +1007,45 @@ pub fn get_bind_mounts<E: CommandExecutor>(exec: &E, container: &str) -> Result<
     Ok(mounts)
 }
 
+/// Get the named volumes mounted into a container
+pub fn get_volume_mounts<E: CommandExecutor>(exec: &E, container: &str) -> Result<Vec<String>> {
+    let inspect_cmd = format!(
+        r#"docker inspect {} --format '{{{{range .Mounts}}}}{{{{if eq .Type "volume"}}}}{{{{.Name}}}}{{{{"\n"}}}}{{{{end}}}}{{{{end}}}}'"#,
+        container
+    );
+    let mounts_output = exec.execute_shell(&inspect_cmd)?;
+    let mounts_str = String::from_utf8_lossy(&mounts_output.stdout);
+    let volumes: Vec<String> = mounts_str
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    Ok(volumes)
+}
+
+/// Remove a Docker volume
+pub fn remove_volume<E: CommandExecutor>(exec: &E, volume: &str) -> Result<()> {
+    let output = exec.execute_simple("docker", &["volume", "rm", volume])?;
+    if !output.status.success() {
+        // Try with sudo
+        let sudo_output = exec.execute_simple("sudo", &["docker", "volume", "rm", volume])?;
+        if !sudo_output.status.success() {
+            anyhow::bail!("Failed to remove volume: {}", volume);
+        }
+    }
+    Ok(())
+}
+
 /// Get all containers
 pub fn list_containers<E: CommandExecutor>(exec: &E) -> Result<Vec<String>> {
-    let containers_output =
-        exec.execute_simple("docker", &["ps", "-a", "--format", "{{.Names}}"])?;
+    let args = ["ps", "-a", "--format", "{{.Names}}"];
+    let mut containers_output = exec.execute_simple("docker", &args)?;
+    if !containers_output.status.success() {
+        // Try with sudo
+        let mut sudo_args = vec!["docker"];
+        sudo_args.extend_from_slice(&args);
+        containers_output = exec.execute_simple("sudo", &sudo_args)?;
+    }
     let containers_str = String::from_utf8_lossy(&containers_output.stdout);
     let containers: Vec<String> = containers_str
         .lines()
@@ -721,6 +1055,47 @@ pub fn list_containers<E: CommandExecutor>(exec: &E) -> Result<Vec<String>> {
     Ok(containers)
 }
 
+/// Names of the containers publishing a host port
+pub fn containers_publishing<E: CommandExecutor>(exec: &E, port: u16) -> Result<Vec<String>> {
+    let names = run_docker(
+        exec,
+        &[
+            "ps",
+            "--filter",
+            &format!("publish={}", port),
+            "--format",
+            "{{.Names}}",
+        ],
+    )?;
+    Ok(names
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|s| s.to_string())
+        .collect())
+}
+
+/// Make sure the ports a service publishes are free, ignoring ones held by its own containers
+/// (`is_own` matches their names), which are about to be replaced
+pub fn check_ports<E: CommandExecutor>(
+    exec: &E,
+    ports: &[u16],
+    is_own: impl Fn(&str) -> bool,
+    ignore_conflicts: bool,
+) -> Result<()> {
+    let mut needed = Vec::new();
+    for &port in ports {
+        if networking::is_port_free(exec, port) {
+            continue;
+        }
+        // Docker may not be installed yet (e.g. early in provisioning)
+        let publishers = containers_publishing(exec, port).unwrap_or_default();
+        if !publishers.iter().any(|name| is_own(name)) {
+            needed.push(port);
+        }
+    }
+    networking::ensure_ports_free(exec, &needed, ignore_conflicts)
+}
+
 /// Check if a container is running
 pub fn is_container_running<E: CommandExecutor>(exec: &E, container_name: &str) -> Result<bool> {
     let output = exec.execute_simple(
@@ -737,6 +1112,219 @@ pub fn is_container_running<E: CommandExecutor>(exec: &E, container_name: &str)
     Ok(stdout.trim().contains(container_name))
 }
 
+/// How many log lines to keep for each problem container
+const PROBLEM_CONTAINER_LOG_LINES: usize = 5;
+
+/// Restart count at which a container is considered stuck in a restart loop
+const RESTART_LOOP_THRESHOLD: u64 = 5;
+
+/// A container that needs attention: unhealthy, restarting, or exited with a non-zero code
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerStatus {
+    pub name: String,
+    pub image: String,
+    /// Docker state (`running`, `restarting`, `exited`, ...)
+    pub state: String,
+    /// Human-readable status, e.g. `Up 2 hours (unhealthy)` or `Exited (1) 5 minutes ago`
+    pub status: String,
+    /// Short reason the container was flagged
+    pub problem: String,
+    pub exit_code: Option<i32>,
+    /// Restarts since the container was created; a high count suggests a restart loop
+    pub restart_count: Option<u64>,
+    pub restart_loop: bool,
+    /// Last few log lines, only filled in by `load_recent_logs`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recent_logs: Vec<String>,
+}
+
+impl ContainerStatus {
+    /// Fetch the container's last few log lines for `print`
+    pub fn load_recent_logs<E: CommandExecutor>(&mut self, exec: &E) {
+        self.recent_logs = container_logs(exec, &self.name, PROBLEM_CONTAINER_LOG_LINES);
+    }
+
+    /// Print a one-line summary followed by the recent log lines
+    pub fn print(&self, indent: &str) {
+        let restarts = match self.restart_count {
+            Some(count) if count > 0 => format!(", {} restart(s)", count),
+            _ => String::new(),
+        };
+        println!(
+            "{}✗ {} ({}): {} - {}{}",
+            indent, self.name, self.image, self.problem, self.status, restarts
+        );
+        if self.restart_loop {
+            println!("{}  ⚠ Possible restart loop", indent);
+        }
+        for line in &self.recent_logs {
+            println!("{}  │ {}", indent, line);
+        }
+    }
+}
+
+/// Why a container from `docker ps -a` needs attention, if it does
+fn container_problem(state: &str, status: &str) -> Option<(String, Option<i32>)> {
+    if state == "restarting" || status.starts_with("Restarting") {
+        return Some(("restarting".to_string(), None));
+    }
+    if status.contains("(unhealthy)") {
+        return Some(("unhealthy".to_string(), None));
+    }
+    if state == "exited" {
+        // "Exited (137) 3 hours ago"
+        let code = status
+            .strip_prefix("Exited (")
+            .and_then(|rest| rest.split(')').next())
+            .and_then(|code| code.parse::<i32>().ok());
+        if let Some(code) = code.filter(|code| *code != 0) {
+            return Some((format!("exited with code {}", code), Some(code)));
+        }
+    }
+    None
+}
+
+/// List containers that are unhealthy, restarting, or exited with an error
+/// Each one includes its restart count; logs are fetched separately with `load_recent_logs`
+pub fn list_unhealthy_containers<E: CommandExecutor>(exec: &E) -> Result<Vec<ContainerStatus>> {
+    let ps = run_docker(
+        exec,
+        &[
+            "ps",
+            "-a",
+            "--format",
+            "{{.Names}}\t{{.Image}}\t{{.State}}\t{{.Status}}",
+        ],
+    )?;
+
+    let mut problems = Vec::new();
+    for line in ps.lines().filter(|l| !l.trim().is_empty()) {
+        let mut fields = line.splitn(4, '\t');
+        let name = fields.next().unwrap_or_default().to_string();
+        let image = fields.next().unwrap_or_default().to_string();
+        let state = fields.next().unwrap_or_default().to_string();
+        let status = fields.next().unwrap_or_default().to_string();
+        let Some((problem, exit_code)) = container_problem(&state, &status) else {
+            continue;
+        };
+
+        let restart_count = run_docker(exec, &["inspect", "--format", "{{.RestartCount}}", &name])
+            .ok()
+            .and_then(|out| out.trim().parse().ok());
+        let restart_loop = restart_count.is_some_and(|count| count >= RESTART_LOOP_THRESHOLD);
+
+        problems.push(ContainerStatus {
+            name,
+            image,
+            state,
+            status,
+            problem,
+            exit_code,
+            restart_count,
+            restart_loop,
+            recent_logs: Vec::new(),
+        });
+    }
+    Ok(problems)
+}
+
+/// Last `lines` lines of a container's combined stdout/stderr, empty if unavailable
+fn container_logs<E: CommandExecutor>(exec: &E, name: &str, lines: usize) -> Vec<String> {
+    get_container_logs(exec, name, &lines.to_string())
+        .map(|logs| {
+            logs.lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| l.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A container's combined stdout/stderr; `tail` is a line count or "all"
+pub fn get_container_logs<E: CommandExecutor>(
+    exec: &E,
+    container: &str,
+    tail: &str,
+) -> Result<String> {
+    let command = format!(
+        "docker logs --tail {} {} 2>&1",
+        shell_escape(tail),
+        shell_escape(container)
+    );
+    let mut output = exec.execute_shell(&command)?;
+    if !output.status.success() {
+        // Try with sudo
+        output = exec.execute_shell(&format!("sudo {}", command))?;
+    }
+    let logs = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        anyhow::bail!("Failed to read logs for {}: {}", container, logs.trim());
+    }
+    Ok(logs)
+}
+
+/// Print a container's logs on `hostname`; with `follow`, stream them until Ctrl-C
+pub fn show_container_logs(
+    hostname: &str,
+    container: &str,
+    follow: bool,
+    tail: &str,
+    config: &EnvConfig,
+) -> Result<()> {
+    if tail != "all" && tail.parse::<usize>().is_err() {
+        anyhow::bail!("--tail must be a number of lines or 'all', got '{}'", tail);
+    }
+
+    let exec = Executor::new(hostname, config)?;
+    let containers = list_containers(&exec)?;
+    if !containers.iter().any(|name| name == container) {
+        if containers.is_empty() {
+            anyhow::bail!(
+                "Container '{}' not found on {} (no containers found)",
+                container,
+                hostname
+            );
+        }
+        anyhow::bail!(
+            "Container '{}' not found on {}. Containers:\n  {}",
+            container,
+            hostname,
+            containers.join("\n  ")
+        );
+    }
+
+    if !follow {
+        print!("{}", get_container_logs(&exec, container, tail)?);
+        return Ok(());
+    }
+
+    // Inherited stdio (and a TTY over SSH) so lines print as they arrive and Ctrl-C reaches
+    // the remote `docker logs`, which ends the SSH session with it. A failed stream can't be
+    // retried, so check up front whether the daemon needs sudo.
+    let daemon_reachable = exec
+        .execute_simple("docker", &["version"])?
+        .status
+        .success();
+    let sudo = if daemon_reachable { "" } else { "sudo " };
+    let command = format!(
+        "{}docker logs --follow --tail {} {}",
+        sudo,
+        shell_escape(tail),
+        shell_escape(container)
+    );
+    let status = exec.execute_shell_interactive_status(&command)?;
+    // 130 = 128 + SIGINT: the stream was stopped with Ctrl-C
+    if !status.success() && status.code() != Some(130) {
+        anyhow::bail!(
+            "docker logs for {} on {} exited with {}",
+            container,
+            hostname,
+            status
+        );
+    }
+    Ok(())
+}
+
 /// Detect the docker compose command to use
 /// Returns "docker compose" (plugin) if available, otherwise "docker-compose" (standalone)
 pub fn get_compose_command<E: CommandExecutor>(exec: &E) -> Result<String> {
@@ -794,24 +1382,63 @@ pub fn stop_and_remove_container<E: CommandExecutor>(exec: &E, container_name: &
 }
 
 /// Install Docker on a host (public API for CLI)
-pub fn install_docker(hostname: &str, config: &EnvConfig) -> Result<()> {
+/// When `dry_run` is set, commands are printed instead of executed
+pub fn install_docker(hostname: &str, dry_run: bool, config: &EnvConfig) -> Result<()> {
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
+    if dry_run {
+        info!("Dry run: no changes will be made");
+    }
     if is_local {
-        println!("Installing Docker locally on {}...", hostname);
+        info!("Installing Docker locally on {}...", hostname);
+    } else {
+        info!("Installing Docker on {} ({})...", hostname, target_host);
+    }
+    info!("");
+
+    if dry_run {
+        run_install_steps(&DryRunExecutor::new(&exec, hostname))?;
+    } else {
+        run_install_steps(&exec)?;
+    }
+
+    info!("");
+    if dry_run {
+        info!("✓ Docker installation dry run complete for {}", hostname);
+    } else {
+        info!("✓ Docker installation complete for {}", hostname);
+    }
+
+    Ok(())
+}
+
+fn run_install_steps<E: CommandExecutor>(exec: &E) -> Result<()> {
+    check_and_install(exec)?;
+    configure_permissions(exec)?;
+    configure_ipv6(exec)?;
+    Ok(())
+}
+
+/// Prune unused Docker resources on a host (public API for CLI)
+pub fn prune_docker(hostname: &str, include_volumes: bool, config: &EnvConfig) -> Result<()> {
+    let exec = Executor::new(hostname, config)?;
+
+    if include_volumes {
+        info!(
+            "Pruning unused Docker resources (including volumes) on {}...",
+            hostname
+        );
     } else {
-        println!("Installing Docker on {} ({})...", hostname, target_host);
+        info!("Pruning unused Docker resources on {}...", hostname);
     }
-    println!();
+    info!("");
 
-    check_and_install(&exec)?;
-    configure_permissions(&exec)?;
-    configure_ipv6(&exec)?;
+    let report = prune_system(&exec, include_volumes)?;
 
-    println!();
-    println!("✓ Docker installation complete for {}", hostname);
+    info!("✓ Docker prune complete for {}", hostname);
+    report.print_summary();
 
     Ok(())
 }
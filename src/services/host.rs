@@ -1,21 +1,89 @@
 // Host service - all host-related business logic
-use crate::config::{HostConfig, find_homelab_dir, load_env_config};
+use crate::config::{EnvConfig, HostConfig, find_homelab_dir, load_env_config};
 use crate::db;
-use crate::utils::exec::Executor;
+use crate::utils::exec::{CommandExecutor, Executor, local};
+use crate::utils::format_bytes;
+use crate::utils::networking;
 use anyhow::{Context, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// How long a health probe may take before the host is considered unreachable
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Number of provisioning runs shown by `hal list --verbose`
+const RECENT_PROVISION_EVENTS: i32 = 3;
+
+/// Order of hosts in `hal list`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum HostSort {
+    /// Alphabetically by name
+    #[default]
+    Name,
+    /// By IP address, hosts without one last
+    Ip,
+    /// Reachable hosts first, then unreachable ones (probes every host)
+    Status,
+}
+
+/// Which hosts `hal list` shows, and in what order
+#[derive(Debug, Clone, Default)]
+pub struct HostListOptions {
+    /// Case-insensitive substring matched against the name, IP, hostname and Tailscale name
+    pub filter: Option<String>,
+    pub sort: HostSort,
+}
+
+impl HostListOptions {
+    /// Whether every listed host has to be health-checked
+    fn needs_health(&self, verbose: bool) -> bool {
+        verbose || self.sort == HostSort::Status
+    }
+}
+
+/// Connectivity and service health for a host
+#[derive(Debug, Clone, Serialize)]
+pub struct HostHealth {
+    pub reachable: bool,
+    pub docker_running: Option<bool>,
+    pub latency_ms: Option<u64>,
+    pub in_db: bool,
+}
+
+impl HostHealth {
+    /// One-line status summary for display
+    pub fn status_line(&self) -> String {
+        if !self.reachable {
+            return "✗ unreachable".to_string();
+        }
+        let mut line = match self.latency_ms {
+            Some(latency) => format!("✓ reachable ({} ms)", latency),
+            None => "✓ reachable".to_string(),
+        };
+        match self.docker_running {
+            Some(true) => line.push_str(", docker running"),
+            Some(false) => line.push_str(", docker not running"),
+            None => {}
+        }
+        line
+    }
+}
 
 /// Get host configuration from config or database
 /// This is the main entry point for getting host configuration
 pub fn get_host_config(hostname: &str) -> Result<Option<HostConfig>> {
     // Try database first if configured to use DB
-    if let Ok(Some(config)) = db::get_host_config(hostname) {
-        return Ok(Some(config));
+    let db_config = db::get_host_config(hostname).ok().flatten();
+    if let Some(config) = db_config.as_ref().filter(|config| config.has_address()) {
+        return Ok(Some(config.clone()));
     }
 
     // Fallback to .env config
     let homelab_dir = find_homelab_dir()?;
     let config = load_env_config(&homelab_dir)?;
-    Ok(config.hosts.get(hostname).cloned())
+    Ok(config.hosts.get(hostname).cloned().or(db_config))
 }
 
 /// Get host configuration with error message if not found
@@ -79,6 +147,190 @@ pub fn get_host_info(
     db::get_host_info(hostname)
 }
 
+/// Parse a host's metadata column as a JSON object
+/// Free-form text from older records is kept under "note"
+pub fn parse_host_metadata(metadata: Option<&str>) -> serde_json::Value {
+    match metadata.map(serde_json::from_str::<serde_json::Value>) {
+        Some(Ok(value @ serde_json::Value::Object(_))) => value,
+        Some(_) => serde_json::json!({ "note": metadata }),
+        None => serde_json::json!({}),
+    }
+}
+
+/// Read one key from the host's metadata
+pub fn get_host_metadata(hostname: &str, key: &str) -> Result<Option<serde_json::Value>> {
+    let metadata = get_host_info(hostname)?.and_then(|info| info.4);
+    Ok(parse_host_metadata(metadata.as_deref()).get(key).cloned())
+}
+
+/// Set one key in the host's metadata, keeping the rest of its record
+pub fn set_host_metadata(hostname: &str, key: &str, value: serde_json::Value) -> Result<()> {
+    let metadata = get_host_info(hostname)?.and_then(|info| info.4);
+    let mut metadata = parse_host_metadata(metadata.as_deref());
+    metadata[key] = value;
+    db::store_host_metadata(hostname, &metadata.to_string())
+}
+
+/// Host metadata key holding the OS, kernel, architecture and memory seen by the last provisioning run
+pub const INVENTORY_METADATA_KEY: &str = "inventory";
+
+/// What a host runs, as collected during provisioning
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HostInventory {
+    /// Display name, e.g. "Ubuntu 24.04.1 LTS" or "macOS 14.5"
+    pub os: Option<String>,
+    /// `ID` from /etc/os-release ("ubuntu", "debian", ...), or "macos"
+    pub os_id: Option<String>,
+    pub os_version: Option<String>,
+    /// Kernel release (`uname -r`)
+    pub kernel: Option<String>,
+    /// CPU architecture (`uname -m`)
+    pub arch: Option<String>,
+    pub memory_bytes: Option<u64>,
+    /// Unix timestamp of the collection
+    pub collected_at: i64,
+}
+
+impl HostInventory {
+    /// "Label: value" lines for `hal list --verbose` and `hal config -H <host>`
+    pub fn display_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(ref os) = self.os {
+            lines.push(format!("OS: {}", os));
+        }
+        if let Some(ref kernel) = self.kernel {
+            lines.push(format!("Kernel: {}", kernel));
+        }
+        if let Some(ref arch) = self.arch {
+            lines.push(format!("Architecture: {}", arch));
+        }
+        if let Some(memory) = self.memory_bytes {
+            lines.push(format!("Memory: {}", format_bytes(memory)));
+        }
+        if !lines.is_empty()
+            && let Some(when) = chrono::DateTime::from_timestamp(self.collected_at, 0)
+        {
+            lines.push(format!(
+                "Inventory Collected: {}",
+                when.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        lines
+    }
+}
+
+/// Trimmed stdout of a command, or None if it failed or printed nothing
+fn command_output<E: CommandExecutor>(exec: &E, program: &str, args: &[&str]) -> Option<String> {
+    exec.execute_simple(program, args)
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|output| !output.is_empty())
+}
+
+/// Total memory from /proc/meminfo ("MemTotal:  16318480 kB")
+fn parse_meminfo_total(meminfo: &str) -> Option<u64> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+/// Collect the OS, kernel, architecture and memory of the host behind `exec`
+/// macOS has no /etc/os-release, so `sw_vers` and `sysctl` are asked there instead. Values that
+/// can't be read are left empty rather than failing the collection.
+pub fn collect_inventory<E: CommandExecutor>(exec: &E) -> Result<HostInventory> {
+    let mut inventory = HostInventory {
+        kernel: command_output(exec, "uname", &["-r"]),
+        arch: command_output(exec, "uname", &["-m"]),
+        collected_at: chrono::Utc::now().timestamp(),
+        ..Default::default()
+    };
+
+    if exec.file_exists("/etc/os-release")? {
+        let release =
+            crate::config::env_file::parse_env_content(&exec.read_file("/etc/os-release")?);
+        let field = |key: &str| {
+            release
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+        };
+        inventory.os = field("PRETTY_NAME").or_else(|| field("NAME"));
+        inventory.os_id = field("ID");
+        inventory.os_version = field("VERSION_ID");
+    } else if let Some(version) = command_output(exec, "sw_vers", &["-productVersion"]) {
+        let name = command_output(exec, "sw_vers", &["-productName"])
+            .unwrap_or_else(|| "macOS".to_string());
+        inventory.os = Some(format!("{} {}", name, version));
+        inventory.os_id = Some("macos".to_string());
+        inventory.os_version = Some(version);
+        inventory.memory_bytes =
+            command_output(exec, "sysctl", &["-n", "hw.memsize"]).and_then(|s| s.parse().ok());
+    }
+
+    if inventory.memory_bytes.is_none()
+        && inventory.os_id.as_deref() != Some("macos")
+        && let Ok(meminfo) = exec.read_file("/proc/meminfo")
+    {
+        inventory.memory_bytes = parse_meminfo_total(&meminfo);
+    }
+    Ok(inventory)
+}
+
+/// Save a host's inventory in its metadata
+pub fn record_host_inventory(hostname: &str, inventory: &HostInventory) -> Result<()> {
+    set_host_metadata(
+        hostname,
+        INVENTORY_METADATA_KEY,
+        serde_json::to_value(inventory)?,
+    )
+}
+
+/// The inventory recorded by the host's last provisioning run, if any
+pub fn get_host_inventory(hostname: &str) -> Result<Option<HostInventory>> {
+    Ok(get_host_metadata(hostname, INVENTORY_METADATA_KEY)?
+        .and_then(|value| serde_json::from_value(value).ok()))
+}
+
+/// Get the most recent provisioning runs for a host (most recent first)
+pub fn get_provision_history(
+    hostname: &str,
+    limit: i32,
+) -> Result<Vec<db::provision_history::ProvisionHistoryRow>> {
+    db::get_provision_history(hostname, Some(limit))
+}
+
+/// One-line summary of a provisioning run for display
+fn format_provision_event(event: &db::provision_history::ProvisionHistoryRow) -> String {
+    let when = chrono::DateTime::from_timestamp(event.provisioned_at, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| event.provisioned_at.to_string());
+
+    let mut installed = Vec::new();
+    if event.docker_installed != 0 {
+        installed.push("docker");
+    }
+    if event.tailscale_installed != 0 {
+        installed.push("tailscale");
+    }
+    if event.portainer_installed != 0 {
+        installed.push("portainer");
+    }
+    let installed = if installed.is_empty() {
+        "nothing installed".to_string()
+    } else {
+        installed.join(", ")
+    };
+
+    match event.error {
+        None => format!("✓ {}  {}", when, installed),
+        Some(ref error) => format!("✗ {}  {} (failed: {})", when, installed, error),
+    }
+}
+
 /// Create an executor for a host (local or remote)
 pub fn create_executor(hostname: &str) -> Result<Executor> {
     let homelab_dir = find_homelab_dir()?;
@@ -86,21 +338,128 @@ pub fn create_executor(hostname: &str) -> Result<Executor> {
     Executor::new(hostname, &config)
 }
 
-/// List all hosts with their information
-pub fn list_hosts_display(verbose: bool) -> Result<()> {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("Available Servers");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!();
+/// Check whether a host is reachable and whether Docker is running on it
+///
+/// Hosts that don't answer a TCP/ICMP probe are reported unreachable right away. Otherwise
+/// connects via SSH (or runs locally) and executes `uname`; latency is the network round trip
+/// where it can be measured. Each probe is bounded by a 3-second timeout so unreachable hosts
+/// are reported quickly instead of hanging.
+pub fn check_host_health(hostname: &str, config: &EnvConfig) -> HostHealth {
+    let in_db = matches!(db::get_host_config(hostname), Ok(Some(_)));
+    let unreachable = HostHealth {
+        reachable: false,
+        docker_running: None,
+        latency_ms: None,
+        in_db,
+    };
 
+    let exec = match Executor::new(hostname, config) {
+        Ok(exec) => exec,
+        Err(_) => return unreachable,
+    };
+
+    match exec {
+        Executor::Local => {
+            let start = Instant::now();
+            let reachable = local::execute("uname", &[])
+                .map(|o| o.status.success())
+                .unwrap_or(false);
+            let latency_ms = start.elapsed().as_millis() as u64;
+            let docker_running =
+                local::execute("docker", &["info", "--format", "{{.ServerVersion}}"])
+                    .map(|o| o.status.success())
+                    .unwrap_or(false);
+            HostHealth {
+                reachable,
+                docker_running: Some(docker_running),
+                latency_ms: Some(latency_ms),
+                in_db,
+            }
+        }
+        Executor::Remote(ssh) => {
+            let start = Instant::now();
+            let reachable = matches!(
+                ssh.execute_simple_timeout("uname", &[], HEALTH_CHECK_TIMEOUT),
+                Ok(Some(ref output)) if output.status.success()
+            );
+            if !reachable {
+                return unreachable;
+            }
+            // Prefer the network round trip; SSH command latency includes login overhead
+            let address = ssh
+                .host
+                .rsplit_once('@')
+                .map_or(ssh.host.as_str(), |(_, address)| address);
+            let latency_ms = if ssh.jump.is_none() {
+                networking::probe_host(address, ssh.port.unwrap_or(22), HEALTH_CHECK_TIMEOUT)
+                    .latency_ms()
+            } else {
+                None
+            }
+            .unwrap_or_else(|| start.elapsed().as_millis() as u64);
+
+            let docker_check = ssh.execute_simple_timeout(
+                "docker",
+                &["info", "--format", "{{.ServerVersion}}"],
+                HEALTH_CHECK_TIMEOUT,
+            );
+            let docker_running = match docker_check {
+                Ok(Some(output)) if output.status.success() => Some(true),
+                // Fall back to passwordless sudo for users not in the docker group
+                Ok(Some(_)) => Some(matches!(
+                    ssh.execute_simple_timeout(
+                        "sudo",
+                        &["-n", "docker", "info", "--format", "{{.ServerVersion}}"],
+                        HEALTH_CHECK_TIMEOUT,
+                    ),
+                    Ok(Some(ref output)) if output.status.success()
+                )),
+                _ => None,
+            };
+
+            HostHealth {
+                reachable: true,
+                docker_running,
+                latency_ms: Some(latency_ms),
+                in_db,
+            }
+        }
+    }
+}
+
+/// Check health of several hosts concurrently
+pub fn check_hosts_health(hostnames: &[String], config: &EnvConfig) -> HashMap<String, HostHealth> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = hostnames
+            .iter()
+            .map(|hostname| {
+                scope.spawn(move || (hostname.clone(), check_host_health(hostname, config)))
+            })
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    })
+}
+
+/// Check the health of every host in the .env file and the database, keyed by name
+pub fn check_all_hosts_health() -> Result<BTreeMap<String, HostHealth>> {
+    let (all_hosts, _) = collect_all_hosts();
+    let hostnames: Vec<String> = all_hosts.into_keys().collect();
+    let config = crate::config::load_config()?;
+    Ok(check_hosts_health(&hostnames, &config)
+        .into_iter()
+        .collect())
+}
+
+/// Combine hosts from the .env file and the database
+/// Returns each host with its source ("env", "db", or "both") and the tailnet base domain
+fn collect_all_hosts() -> (HashMap<String, (&'static str, HostConfig)>, String) {
     // Try to load from env file
     let homelab_dir = crate::config::find_homelab_dir();
     let (env_hosts, tailnet_base) = if let Ok(dir) = &homelab_dir {
         match crate::config::load_env_config(dir) {
             Ok(cfg) => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "[DEBUG] Loaded {} hosts from .env file in list_hosts_display",
+                debug!(
+                    "Loaded {} hosts from .env file in list_hosts_display",
                     cfg.hosts.len()
                 );
                 (Some(cfg.hosts), cfg._tailnet_base)
@@ -111,6 +470,9 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
             }
         }
     } else {
+        if let Err(e) = &homelab_dir {
+            eprintln!("Failed to load .env config: {:#}", e);
+        }
         (None, "ts.net".to_string())
     };
 
@@ -118,7 +480,7 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
     let db_hosts = db::list_hosts().ok();
 
     // Combine hosts from both sources
-    let mut all_hosts = std::collections::HashMap::new();
+    let mut all_hosts = HashMap::new();
 
     if let Some(hosts) = env_hosts {
         for (name, config) in hosts {
@@ -143,12 +505,159 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
                     hostname: None,
                     tailscale: None,
                     backup_path: None,
+                    ssh_user: None,
+                    ssh_port: None,
+                    jump: None,
                 };
                 all_hosts.insert(name, ("db", empty_config));
             }
         }
     }
 
+    (all_hosts, tailnet_base)
+}
+
+/// Names of the hosts matching `filter` (all hosts when None), alphabetically
+fn filter_hostnames(
+    all_hosts: &HashMap<String, (&'static str, HostConfig)>,
+    filter: Option<&str>,
+) -> Vec<String> {
+    let filter = filter.map(str::to_lowercase);
+    let mut hostnames: Vec<String> = all_hosts
+        .iter()
+        .filter(|(name, (_, config))| {
+            let Some(filter) = &filter else {
+                return true;
+            };
+            [
+                Some(name.as_str()),
+                config.ip.as_deref(),
+                config.hostname.as_deref(),
+                config.tailscale.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(filter))
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    hostnames.sort();
+    hostnames
+}
+
+/// Reorder alphabetically sorted hostnames for `--sort`; ties stay alphabetical
+fn sort_hostnames(
+    hostnames: &mut [String],
+    all_hosts: &HashMap<String, (&'static str, HostConfig)>,
+    sort: HostSort,
+    health: &HashMap<String, HostHealth>,
+) {
+    match sort {
+        HostSort::Name => {}
+        HostSort::Ip => hostnames.sort_by_cached_key(|name| {
+            let ip = all_hosts[name].1.ip.clone();
+            let parsed = ip
+                .as_deref()
+                .and_then(|ip| ip.parse::<std::net::IpAddr>().ok());
+            (ip.is_none(), parsed.is_none(), parsed, ip)
+        }),
+        // Hosts that couldn't be checked at all go last
+        HostSort::Status => hostnames.sort_by_key(|name| match health.get(name) {
+            Some(status) if status.reachable => 0,
+            Some(_) => 1,
+            None => 2,
+        }),
+    }
+}
+
+/// List all hosts as JSON
+/// Each entry carries the host's connection settings and source; `verbose` adds
+/// health and provisioning details (health is also included when sorting by status)
+pub fn list_hosts_json(verbose: bool, options: &HostListOptions) -> Result<serde_json::Value> {
+    let (all_hosts, _) = collect_all_hosts();
+
+    let mut hostnames = filter_hostnames(&all_hosts, options.filter.as_deref());
+
+    let health = if options.needs_health(verbose) {
+        match crate::config::load_config() {
+            Ok(env_config) => check_hosts_health(&hostnames, &env_config),
+            Err(_) => HashMap::new(),
+        }
+    } else {
+        HashMap::new()
+    };
+    sort_hostnames(&mut hostnames, &all_hosts, options.sort, &health);
+    let devices = if verbose {
+        crate::services::tailscale::tailnet_devices()
+            .ok()
+            .filter(|d| !d.is_empty())
+    } else {
+        None
+    };
+
+    let mut hosts = Vec::new();
+    for name in &hostnames {
+        let (source, config) = &all_hosts[name];
+        let mut entry = serde_json::json!({
+            "name": name,
+            "source": source,
+            "ip": config.ip,
+            "hostname": config.hostname,
+            "tailscale": config.tailscale,
+            "backup_path": config.backup_path,
+            "ssh_user": config.ssh_user,
+            "ssh_port": config.ssh_port,
+            "jump": config.jump,
+        });
+        if options.needs_health(verbose) {
+            entry["health"] = serde_json::to_value(health.get(name))?;
+        }
+        if verbose {
+            if let Some(ref devices) = devices {
+                entry["tailnet"] =
+                    crate::services::tailscale::host_tailnet_status(name, config, devices)
+                        .to_json();
+            }
+            if let Ok(Some(info)) = get_host_info(name) {
+                entry["docker_version"] = serde_json::json!(info.1);
+                entry["tailscale_installed"] = serde_json::json!(info.2);
+                entry["portainer_installed"] = serde_json::json!(info.3);
+                entry["metadata"] = serde_json::json!(info.4);
+            }
+            if let Ok(Some(inventory)) = get_host_inventory(name) {
+                entry["inventory"] = serde_json::to_value(&inventory)?;
+            }
+            if let Ok(history) = get_provision_history(name, RECENT_PROVISION_EVENTS) {
+                entry["provision_history"] = history
+                    .iter()
+                    .map(|event| {
+                        serde_json::json!({
+                            "provisioned_at": event.provisioned_at,
+                            "success": event.success != 0,
+                            "docker_installed": event.docker_installed != 0,
+                            "tailscale_installed": event.tailscale_installed != 0,
+                            "portainer_installed": event.portainer_installed != 0,
+                            "error": event.error,
+                        })
+                    })
+                    .collect();
+            }
+        }
+        hosts.push(entry);
+    }
+
+    Ok(serde_json::Value::Array(hosts))
+}
+
+/// List all hosts with their information
+pub fn list_hosts_display(verbose: bool, options: &HostListOptions) -> Result<()> {
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Available Servers");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+
+    let (all_hosts, tailnet_base) = collect_all_hosts();
+
     if all_hosts.is_empty() {
         println!("No servers found.");
         println!();
@@ -157,14 +666,44 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
         return Ok(());
     }
 
-    // Sort hostnames for consistent output
-    let mut hostnames: Vec<_> = all_hosts.keys().collect();
-    hostnames.sort();
+    let mut hostnames = filter_hostnames(&all_hosts, options.filter.as_deref());
+    if hostnames.is_empty() {
+        println!(
+            "No servers match '{}'.",
+            options.filter.as_deref().unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    // Probe every host up front (concurrently) so slow hosts don't stall the listing
+    let health = if options.needs_health(verbose) {
+        match crate::config::load_config() {
+            Ok(env_config) => check_hosts_health(&hostnames, &env_config),
+            Err(_) => HashMap::new(),
+        }
+    } else {
+        HashMap::new()
+    };
+    sort_hostnames(&mut hostnames, &all_hosts, options.sort, &health);
 
     if verbose {
+        // None when Tailscale isn't available locally
+        let devices = crate::services::tailscale::tailnet_devices()
+            .ok()
+            .filter(|d| !d.is_empty());
+
         for hostname in &hostnames {
-            let (source, config) = all_hosts.get(*hostname).unwrap();
+            let (source, config) = &all_hosts[hostname];
             println!("Hostname: {}", hostname);
+            match health.get(hostname) {
+                Some(status) => println!("  Status: {}", status.status_line()),
+                None => println!("  Status: unknown"),
+            }
+            if let Some(ref devices) = devices {
+                let status =
+                    crate::services::tailscale::host_tailnet_status(hostname, config, devices);
+                println!("  Tailnet: {}", status.status_line());
+            }
             println!(
                 "  Source: {}",
                 match *source {
@@ -196,6 +735,15 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
             if let Some(ref backup_path) = config.backup_path {
                 println!("  Backup Path: {}", backup_path);
             }
+            if let Some(ref ssh_user) = config.ssh_user {
+                println!("  SSH User: {}", ssh_user);
+            }
+            if let Some(ssh_port) = config.ssh_port {
+                println!("  SSH Port: {}", ssh_port);
+            }
+            if let Some(ref jump) = config.jump {
+                println!("  Jump Host: {}", jump);
+            }
             // Get provisioning info from DB if available
             if let Ok(Some(info)) = get_host_info(hostname) {
                 if let Some(ref docker_version) = info.1 {
@@ -209,8 +757,26 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
                     "  Portainer Installed: {}",
                     if info.3 { "Yes" } else { "No" }
                 );
-                if let Some(ref metadata) = info.4 {
-                    println!("  Metadata: {}", metadata);
+                // The inventory gets its own lines below
+                let mut metadata = parse_host_metadata(info.4.as_deref());
+                if let Some(object) = metadata.as_object_mut() {
+                    object.remove(INVENTORY_METADATA_KEY);
+                    if !object.is_empty() {
+                        println!("  Metadata: {}", metadata);
+                    }
+                }
+            }
+            if let Ok(Some(inventory)) = get_host_inventory(hostname) {
+                for line in inventory.display_lines() {
+                    println!("  {}", line);
+                }
+            }
+            if let Ok(history) = get_provision_history(hostname, RECENT_PROVISION_EVENTS)
+                && !history.is_empty()
+            {
+                println!("  Recent Provisioning:");
+                for event in &history {
+                    println!("    {}", format_provision_event(event));
                 }
             }
             println!();
@@ -218,7 +784,7 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
     } else {
         println!("Servers:");
         for hostname in &hostnames {
-            let (source, config) = all_hosts.get(*hostname).unwrap();
+            let (source, config) = &all_hosts[hostname];
             let mut info = vec![];
             if let Some(ref ip) = config.ip {
                 info.push(format!("IP: {}", ip));
@@ -242,10 +808,21 @@ pub fn list_hosts_display(verbose: bool) -> Result<()> {
                 "both" => "[env+db]",
                 _ => "",
             };
+            // Only probed for --sort status
+            let status = health
+                .get(hostname)
+                .map(|status| format!("  {}", status.status_line()))
+                .unwrap_or_default();
             if info.is_empty() {
-                println!("  {} {}", hostname, source_marker);
+                println!("  {} {}{}", hostname, source_marker, status);
             } else {
-                println!("  {} {} ({})", hostname, source_marker, info.join(", "));
+                println!(
+                    "  {} {} ({}){}",
+                    hostname,
+                    source_marker,
+                    info.join(", "),
+                    status
+                );
             }
         }
         println!();
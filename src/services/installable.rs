@@ -0,0 +1,377 @@
+// Services `hal install` and `hal uninstall` can manage on a host
+//
+// Each service implements `InstallableService` and is listed in `SERVICES`; the install and
+// uninstall commands look names up there instead of matching on them. Services that are just a
+// compose file under `compose/` only need a `ComposeService` entry.
+use crate::config::EnvConfig;
+use crate::services::{compose, docker, portainer, smb, tailscale};
+use crate::utils::exec::{CommandExecutor, Executor};
+use anyhow::Result;
+use std::io::{self, IsTerminal, Write};
+
+/// Host a service is installed on or removed from
+pub struct ServiceTarget<'a> {
+    pub hostname: &'a str,
+    pub config: &'a EnvConfig,
+}
+
+impl ServiceTarget<'_> {
+    /// Executor for the target host (local or over SSH)
+    pub fn executor(&self) -> Result<Executor> {
+        Executor::new(self.hostname, self.config)
+    }
+}
+
+/// Flags from `hal install`; each service reads the ones that apply to it
+pub struct InstallOptions {
+    /// Portainer edition (ce or be)
+    pub edition: String,
+    /// Install the Portainer server (with UI) instead of the agent
+    pub host: bool,
+    /// Validated Tailscale ACL tags to advertise
+    pub tailscale_tags: Vec<String>,
+    /// Replace an existing Portainer install of a different edition
+    pub force: bool,
+    /// Initial Portainer admin password, for the server only
+    pub admin_password: Option<portainer::AdminPassword>,
+    /// Install even if a port the service publishes is already in use
+    pub ignore_port_conflicts: bool,
+    /// Print the commands instead of running them
+    pub dry_run: bool,
+}
+
+/// Flags from `hal uninstall`
+pub struct UninstallOptions {
+    /// Also remove the service's named Docker volumes
+    pub purge_volumes: bool,
+    /// Don't ask for confirmation
+    pub yes: bool,
+}
+
+/// A service that can be installed on (and possibly removed from) a host
+pub trait InstallableService {
+    /// Name used on the command line, e.g. `hal install watchtower`
+    fn name(&self) -> &'static str;
+
+    /// One-line description shown when listing services
+    fn description(&self) -> &'static str;
+
+    /// Whether the service is already present on the host behind `exec`
+    fn is_installed(&self, exec: &Executor) -> Result<bool>;
+
+    fn install(&self, target: &ServiceTarget, options: &InstallOptions) -> Result<()>;
+
+    fn uninstall(&self, _target: &ServiceTarget, _options: &UninstallOptions) -> Result<()> {
+        anyhow::bail!("Uninstalling {} is not supported yet", self.name())
+    }
+
+    /// Whether `install` checks its ports first (and so honours `--ignore-port-conflicts`)
+    fn checks_ports(&self) -> bool {
+        false
+    }
+}
+
+/// Every service `hal install` / `hal uninstall` knows, in the order they are listed
+static SERVICES: &[&(dyn InstallableService + Sync)] = &[
+    &Docker,
+    &Tailscale,
+    &Portainer,
+    &NPM,
+    &Smb,
+    &WATCHTOWER,
+    &UPTIME_KUMA,
+];
+
+/// Registered services
+pub fn services() -> impl Iterator<Item = &'static (dyn InstallableService + Sync)> {
+    SERVICES.iter().copied()
+}
+
+/// Look up a service by name (case-insensitive)
+pub fn find_service(name: &str) -> Option<&'static (dyn InstallableService + Sync)> {
+    services().find(|service| service.name().eq_ignore_ascii_case(name))
+}
+
+/// "  name  description" lines for every registered service
+pub fn service_list() -> String {
+    let width = services().map(|s| s.name().len()).max().unwrap_or(0);
+    services()
+        .map(|s| format!("  {:width$}  {}", s.name(), s.description(), width = width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn has_container(exec: &Executor, matches: impl Fn(&str) -> bool) -> Result<bool> {
+    if !exec.check_command_exists("docker")? {
+        return Ok(false);
+    }
+    Ok(docker::list_containers(exec)?
+        .iter()
+        .any(|name| matches(name)))
+}
+
+struct Docker;
+
+impl InstallableService for Docker {
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+
+    fn description(&self) -> &'static str {
+        "Docker Engine and the compose plugin"
+    }
+
+    fn is_installed(&self, exec: &Executor) -> Result<bool> {
+        exec.check_command_exists("docker")
+    }
+
+    fn install(&self, target: &ServiceTarget, options: &InstallOptions) -> Result<()> {
+        docker::install_docker(target.hostname, options.dry_run, target.config)
+    }
+}
+
+struct Tailscale;
+
+impl InstallableService for Tailscale {
+    fn name(&self) -> &'static str {
+        "tailscale"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tailscale client (--tailscale-tags advertises ACL tags)"
+    }
+
+    fn is_installed(&self, exec: &Executor) -> Result<bool> {
+        exec.check_command_exists("tailscale")
+    }
+
+    fn install(&self, target: &ServiceTarget, options: &InstallOptions) -> Result<()> {
+        if target.hostname != "localhost" || options.dry_run {
+            return tailscale::install_tailscale_on_host(
+                target.hostname,
+                &options.tailscale_tags,
+                options.dry_run,
+                target.config,
+            );
+        }
+        tailscale::install_tailscale()?;
+        if !options.tailscale_tags.is_empty() {
+            let exec = Executor::Local;
+            tailscale::apply_tailscale_tags(&exec, &options.tailscale_tags)?;
+            tailscale::record_tailscale_tags(target.hostname, &options.tailscale_tags)?;
+        }
+        Ok(())
+    }
+}
+
+struct Portainer;
+
+impl InstallableService for Portainer {
+    fn name(&self) -> &'static str {
+        "portainer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Portainer agent, or the server with --host (--edition ce|be)"
+    }
+
+    fn is_installed(&self, exec: &Executor) -> Result<bool> {
+        has_container(exec, |name| {
+            name == "portainer" || name == "portainer_agent"
+        })
+    }
+
+    fn install(&self, target: &ServiceTarget, options: &InstallOptions) -> Result<()> {
+        if options.host {
+            portainer::install_portainer_host(
+                target.hostname,
+                &options.edition,
+                options.force,
+                options.admin_password.as_ref(),
+                options.ignore_port_conflicts,
+                options.dry_run,
+                target.config,
+            )
+        } else {
+            portainer::install_portainer_agent(
+                target.hostname,
+                &options.edition,
+                options.force,
+                options.ignore_port_conflicts,
+                options.dry_run,
+                target.config,
+            )
+        }
+    }
+
+    fn uninstall(&self, target: &ServiceTarget, options: &UninstallOptions) -> Result<()> {
+        portainer::uninstall_portainer(
+            target.hostname,
+            options.purge_volumes,
+            options.yes,
+            target.config,
+        )
+    }
+
+    fn checks_ports(&self) -> bool {
+        true
+    }
+}
+
+struct Smb;
+
+impl InstallableService for Smb {
+    fn name(&self) -> &'static str {
+        "smb"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mounts for the SMB shares in the configuration"
+    }
+
+    fn is_installed(&self, exec: &Executor) -> Result<bool> {
+        Ok(exec
+            .execute_shell("grep -qs ' cifs ' /etc/fstab")?
+            .status
+            .success())
+    }
+
+    fn install(&self, target: &ServiceTarget, options: &InstallOptions) -> Result<()> {
+        smb::setup_smb_mounts(target.hostname, options.dry_run, target.config)
+    }
+
+    fn uninstall(&self, target: &ServiceTarget, options: &UninstallOptions) -> Result<()> {
+        if options.purge_volumes {
+            anyhow::bail!("--purge-volumes is not supported for smb (it has no Docker volumes)");
+        }
+        smb::uninstall_smb_mounts(target.hostname, target.config)
+    }
+}
+
+/// A service deployed from a compose file in the homelab's `compose/` directory
+/// Installing runs `up -d` and uninstalling runs `down`, which keeps named volumes unless
+/// `--purge-volumes` is given.
+struct ComposeService {
+    name: &'static str,
+    description: &'static str,
+    compose_file: &'static str,
+    /// Container whose presence means the service is installed
+    container: &'static str,
+    /// Host ports the compose file publishes
+    ports: &'static [u16],
+}
+
+const WATCHTOWER: ComposeService = ComposeService {
+    name: "watchtower",
+    description: "Watchtower, which keeps running containers on their latest images",
+    compose_file: "watchtower.docker-compose.yml",
+    container: "watchtower",
+    ports: &[],
+};
+
+const UPTIME_KUMA: ComposeService = ComposeService {
+    name: "uptime-kuma",
+    description: "Uptime Kuma status monitoring (web UI on port 3001)",
+    compose_file: "uptime-kuma.docker-compose.yml",
+    container: "uptime-kuma",
+    ports: &[3001],
+};
+
+const NPM: ComposeService = ComposeService {
+    name: "npm",
+    description: "Nginx Proxy Manager (configure proxy hosts with 'hal npm')",
+    compose_file: "nginx-proxy-manager.docker-compose.yml",
+    container: "nginx-proxy-manager-app-1",
+    ports: &[80, 81, 443],
+};
+
+impl ComposeService {
+    fn run(&self, target: &ServiceTarget, args: &[&str]) -> Result<()> {
+        let compose_file = compose::resolve_compose_file(self.compose_file)?;
+        compose::run_compose(
+            target.hostname,
+            target.config,
+            &compose_file,
+            None,
+            args,
+            None,
+        )
+    }
+}
+
+impl InstallableService for ComposeService {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn is_installed(&self, exec: &Executor) -> Result<bool> {
+        has_container(exec, |name| name == self.container)
+    }
+
+    fn install(&self, target: &ServiceTarget, options: &InstallOptions) -> Result<()> {
+        // Only inspects the host, so a dry run checks the ports for real too
+        docker::check_ports(
+            &target.executor()?,
+            self.ports,
+            |name| name == self.container,
+            options.ignore_port_conflicts,
+        )?;
+        if options.dry_run {
+            let compose_file = compose::resolve_compose_file(self.compose_file)?;
+            return compose::dry_run_compose(
+                target.hostname,
+                target.config,
+                &compose_file,
+                None,
+                &["up", "-d"],
+            );
+        }
+        self.run(target, &["up", "-d"])
+    }
+
+    fn uninstall(&self, target: &ServiceTarget, options: &UninstallOptions) -> Result<()> {
+        if !options.purge_volumes {
+            return self.run(target, &["down"]);
+        }
+
+        // Show what `down -v` is about to delete before anything happens
+        let compose_file = compose::resolve_compose_file(self.compose_file)?;
+        let project = compose::project_name(&compose_file);
+        let volumes = docker::list_project_volumes(&target.executor()?, &project)?;
+        if volumes.is_empty() {
+            println!("No volumes found for {}", self.name);
+        } else {
+            println!("Volumes to DELETE (data will be lost):");
+            for volume in &volumes {
+                println!("  - {}", volume);
+            }
+            println!();
+            if !options.yes {
+                if !io::stdin().is_terminal() {
+                    anyhow::bail!(
+                        "Refusing to delete {} volume(s) without confirmation; re-run with --yes",
+                        volumes.len()
+                    );
+                }
+                print!("Uninstall {} and delete these volumes? [y/N]: ", self.name);
+                io::stdout().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                let response = input.trim().to_lowercase();
+                if response != "y" && response != "yes" {
+                    println!("Cancelled - no changes were made.");
+                    return Ok(());
+                }
+            }
+        }
+        self.run(target, &["down", "-v"])
+    }
+
+    fn checks_ports(&self) -> bool {
+        !self.ports.is_empty()
+    }
+}
@@ -3,9 +3,11 @@
 
 pub mod backup;
 pub mod build;
+pub mod compose;
 pub mod dev;
 pub mod docker;
 pub mod host;
+pub mod installable;
 pub mod npm;
 pub mod pia_vpn;
 pub mod portainer;
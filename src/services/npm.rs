@@ -3,6 +3,10 @@ use crate::services::host;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long Let's Encrypt issuance may take before giving up
+const CERTIFICATE_TIMEOUT: Duration = Duration::from_secs(180);
 
 #[derive(Debug, Serialize)]
 struct LoginRequest {
@@ -45,6 +49,9 @@ struct ProxyHostRequest {
 struct ProxyHost {
     id: u32,
     domain_names: Vec<String>,
+    /// 0 when the host has no certificate
+    #[serde(default)]
+    certificate_id: u32,
     // forward_scheme: String,
     // forward_host: String,
     // forward_port: u16,
@@ -53,17 +60,12 @@ struct ProxyHost {
 pub async fn setup_proxy_hosts(
     hostname: &str,
     compose_file: &str,
+    profiles: &[String],
+    ssl: bool,
     _config: &EnvConfig,
 ) -> Result<()> {
-    let host_config = host::get_host_config_or_error(hostname)?;
-
-    let target_host = if let Some(ip) = &host_config.ip {
-        ip.clone()
-    } else if let Some(tailscale) = &host_config.tailscale {
-        tailscale.clone()
-    } else {
-        anyhow::bail!("No IP or Tailscale hostname configured for {}", hostname);
-    };
+    let target_host = forward_host_for(hostname)?;
+    let acme_email = if ssl { Some(acme_email()?) } else { None };
 
     println!(
         "Setting up Nginx Proxy Manager hosts for {} ({})...",
@@ -83,7 +85,7 @@ pub async fn setup_proxy_hosts(
         .with_context(|| format!("Failed to read compose file: {}", compose_path.display()))?;
 
     // Parse compose file to extract services with ports
-    let services = parse_compose_services(&compose_content)?;
+    let services = parse_compose_services(&compose_content, profiles)?;
 
     if services.is_empty() {
         println!("No services with exposed ports found in compose file");
@@ -91,59 +93,71 @@ pub async fn setup_proxy_hosts(
     }
 
     println!("Found {} service(s) with exposed ports:", services.len());
-    for (name, port) in &services {
-        println!("  - {}:{}", name, port);
+    for target in &services {
+        println!("  - {}:{} ({})", target.service, target.port, target.domain);
     }
     println!();
 
-    // Get NPM credentials from config
-    let npm_url =
-        crate::config::get_npm_url().unwrap_or_else(|| format!("https://{}:81", target_host));
-    let npm_username = crate::config::get_npm_username().context("NPM_USERNAME not set in .env")?;
-    let npm_password = crate::config::get_npm_password().context("NPM_PASSWORD not set in .env")?;
-
-    // Login to NPM API
-    let token = login_to_npm(&npm_url, &npm_username, &npm_password)
-        .await
-        .context("Failed to login to Nginx Proxy Manager")?;
+    let mut session = NpmSession::connect(&target_host).await?;
     println!("✓ Authenticated with Nginx Proxy Manager");
     println!();
 
     // Get existing proxy hosts
-    let existing_hosts = get_proxy_hosts(&npm_url, &token)
+    let existing_hosts = session
+        .proxy_hosts()
         .await
         .context("Failed to get existing proxy hosts")?;
     println!("Found {} existing proxy host(s)", existing_hosts.len());
     println!();
 
     // Create or update proxy hosts
-    for (service_name, port) in &services {
-        let domain = format!("{}.local", service_name); // Default domain pattern
+    for ComposeProxyTarget {
+        service: service_name,
+        domain,
+        port,
+    } in &services
+    {
         println!("Setting up proxy host for {}...", service_name);
 
         // Check if proxy host already exists
         let existing = existing_hosts
             .iter()
-            .find(|h| h.domain_names.contains(&domain));
-
-        if let Some(existing_host) = existing {
+            .find(|h| h.domain_names.contains(domain));
+
+        let host_id = if let Some(existing_host) = existing {
+            if acme_email.is_none() || existing_host.certificate_id != 0 {
+                println!(
+                    "  Proxy host already exists (ID: {}), skipping",
+                    existing_host.id
+                );
+                continue;
+            }
             println!(
-                "  Proxy host already exists (ID: {}), skipping",
+                "  Proxy host already exists (ID: {}), adding a certificate",
                 existing_host.id
             );
-            continue;
-        }
-
-        // Create new proxy host
-        match create_proxy_host(&npm_url, &token, &domain, &target_host, *port).await {
-            Ok(id) => {
-                println!("  ✓ Created proxy host (ID: {})", id);
-                println!("    Domain: {}", domain);
-                println!("    Forward: http://{}:{}", target_host, port);
-            }
-            Err(e) => {
-                println!("  ✗ Failed to create proxy host: {}", e);
+            existing_host.id
+        } else {
+            // Create new proxy host
+            match session.create_proxy_host(domain, &target_host, *port).await {
+                Ok(id) => {
+                    println!("  ✓ Created proxy host (ID: {})", id);
+                    println!("    Domain: {}", domain);
+                    println!("    Forward: http://{}:{}", target_host, port);
+                    id
+                }
+                Err(e) => {
+                    println!("  ✗ Failed to create proxy host: {}", e);
+                    println!();
+                    continue;
+                }
             }
+        };
+
+        if let Some(email) = &acme_email
+            && let Err(e) = session.secure_proxy_host(host_id, domain, email).await
+        {
+            println!("  ✗ {:#}", e);
         }
         println!();
     }
@@ -152,39 +166,135 @@ pub async fn setup_proxy_hosts(
     Ok(())
 }
 
-fn parse_compose_services(compose_content: &str) -> Result<HashMap<String, u16>> {
-    use yaml_rust::YamlLoader;
+/// A compose service that should get a proxy host
+#[derive(Debug)]
+struct ComposeProxyTarget {
+    service: String,
+    domain: String,
+    port: u16,
+}
+
+/// Label overriding the proxy host's domain (default: `{service}.local`)
+const DOMAIN_LABEL: &str = "halvor.npm.domain";
+/// Label overriding the forwarded port (default: host side of the first `ports` entry)
+const PORT_LABEL: &str = "halvor.npm.port";
+
+/// Find the services that should get proxy hosts, sorted by service name
+///
+/// Services behind a profile are skipped unless one of their profiles is in `active_profiles`
+/// or an enabled service `depends_on` them.
+fn parse_compose_services(
+    compose_content: &str,
+    active_profiles: &[String],
+) -> Result<Vec<ComposeProxyTarget>> {
+    use yaml_rust::{Yaml, YamlLoader};
 
     let docs = YamlLoader::load_from_str(compose_content).context("Failed to parse YAML")?;
 
-    if docs.is_empty() {
-        return Ok(HashMap::new());
-    }
+    let Some(services_node) = docs.first().and_then(|doc| doc["services"].as_hash()) else {
+        return Ok(Vec::new());
+    };
+    let services: HashMap<&str, &Yaml> = services_node
+        .iter()
+        .filter_map(|(name, config)| name.as_str().map(|name| (name, config)))
+        .collect();
 
-    let doc = &docs[0];
-    let mut services = HashMap::new();
-
-    if let Some(services_node) = doc["services"].as_hash() {
-        for (service_name, service_config) in services_node {
-            if let Some(name_str) = service_name.as_str() {
-                if let Some(ports_node) = service_config["ports"].as_vec() {
-                    // Get the first port mapping
-                    if let Some(first_port) = ports_node.first() {
-                        if let Some(port_str) = first_port.as_str() {
-                            // Parse "8989:8989" format
-                            if let Some(host_port) = port_str.split(':').next() {
-                                if let Ok(port) = host_port.parse::<u16>() {
-                                    services.insert(name_str.to_string(), port);
-                                }
-                            }
-                        }
-                    }
-                }
+    // Services without profiles are always enabled, the rest need an active profile
+    let mut enabled: Vec<&str> = services
+        .iter()
+        .filter(|(_, config)| match config["profiles"].as_vec() {
+            Some(profiles) => profiles
+                .iter()
+                .filter_map(|p| p.as_str())
+                .any(|p| active_profiles.iter().any(|a| a == p)),
+            None => true,
+        })
+        .map(|(name, _)| *name)
+        .collect();
+    // Compose also starts whatever an enabled service depends on
+    let mut i = 0;
+    while i < enabled.len() {
+        let config = services[enabled[i]];
+        let depends_on: Vec<&str> = match &config["depends_on"] {
+            Yaml::Array(list) => list.iter().filter_map(|d| d.as_str()).collect(),
+            Yaml::Hash(map) => map.keys().filter_map(|d| d.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        for dependency in depends_on {
+            if services.contains_key(dependency) && !enabled.contains(&dependency) {
+                enabled.push(dependency);
             }
         }
+        i += 1;
+    }
+
+    let mut skipped: Vec<&str> = services
+        .keys()
+        .filter(|name| !enabled.contains(name))
+        .copied()
+        .collect();
+    if !skipped.is_empty() {
+        skipped.sort();
+        println!(
+            "Skipping service(s) behind inactive profiles: {}",
+            skipped.join(", ")
+        );
+    }
+
+    let mut targets = Vec::new();
+    for name in enabled {
+        let config = services[name];
+        let label = |key: &str| compose_label(&config["labels"], key);
+
+        let port = match label(PORT_LABEL) {
+            Some(port) => Some(port.parse::<u16>().with_context(|| {
+                format!("Invalid {} label on service {}: {}", PORT_LABEL, name, port)
+            })?),
+            // Parse the host side of the first "8989:8989" mapping
+            None => config["ports"]
+                .as_vec()
+                .and_then(|ports| ports.first())
+                .and_then(|port| port.as_str())
+                .and_then(|port| port.split(':').next())
+                .and_then(|host_port| host_port.parse::<u16>().ok()),
+        };
+        let Some(port) = port else {
+            continue;
+        };
+
+        targets.push(ComposeProxyTarget {
+            service: name.to_string(),
+            domain: label(DOMAIN_LABEL).unwrap_or_else(|| format!("{}.local", name)),
+            port,
+        });
     }
+    targets.sort_by(|a, b| a.service.cmp(&b.service));
 
-    Ok(services)
+    Ok(targets)
+}
+
+/// Read a label from a service's `labels`, given either as a map or as a list of "key=value"
+fn compose_label(labels: &yaml_rust::Yaml, key: &str) -> Option<String> {
+    use yaml_rust::Yaml;
+
+    match labels {
+        Yaml::Hash(map) => map.iter().find_map(|(k, v)| {
+            (k.as_str() == Some(key)).then(|| match v {
+                Yaml::String(s) => s.clone(),
+                Yaml::Integer(i) => i.to_string(),
+                Yaml::Real(r) => r.clone(),
+                Yaml::Boolean(b) => b.to_string(),
+                _ => String::new(),
+            })
+        }),
+        Yaml::Array(list) => list
+            .iter()
+            .filter_map(|item| item.as_str())
+            .find_map(|item| item.split_once('=').filter(|(k, _)| *k == key))
+            .map(|(_, v)| v.to_string()),
+        _ => None,
+    }
+    .filter(|value| !value.is_empty())
 }
 
 async fn login_to_npm(url: &str, username: &str, password: &str) -> Result<String> {
@@ -218,120 +328,42 @@ async fn login_to_npm(url: &str, username: &str, password: &str) -> Result<Strin
     Ok(login_resp.token)
 }
 
-async fn get_proxy_hosts(url: &str, token: &str) -> Result<Vec<ProxyHost>> {
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .build()?;
-
-    let api_url = format!("{}/api/nginx/proxy-hosts", url);
-
-    let response = client
-        .get(&api_url)
-        .bearer_auth(token)
-        .send()
-        .await
-        .context("Failed to fetch proxy hosts")?;
-
-    if !response.status().is_success() {
-        anyhow::bail!("Failed to get proxy hosts: {}", response.status());
-    }
-
-    let hosts: Vec<ProxyHost> = response
-        .json()
-        .await
-        .context("Failed to parse proxy hosts response")?;
-
-    Ok(hosts)
-}
-
-async fn create_proxy_host(
-    url: &str,
-    token: &str,
-    domain: &str,
-    forward_host: &str,
-    forward_port: u16,
-) -> Result<u32> {
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .build()?;
-
-    let api_url = format!("{}/api/nginx/proxy-hosts", url);
-
-    let proxy_req = ProxyHostRequest {
-        domain_names: vec![domain.to_string()],
-        forward_scheme: "http".to_string(),
-        forward_host: forward_host.to_string(),
-        forward_port,
-        ssl_forced: Some(false),
-        certificate_id: None,
-        access_list_id: None,
-        advanced_config: None,
-        locations: None,
-        block_exploits: Some(false),
-        caching_enabled: Some(false),
-        allow_websocket_upgrade: Some(true),
-        http2_support: Some(false),
-    };
-
-    let response = client
-        .post(&api_url)
-        .bearer_auth(token)
-        .json(&proxy_req)
-        .send()
-        .await
-        .context("Failed to create proxy host")?;
-
-    let status = response.status();
-    let response_text = response.text().await.unwrap_or_default();
-
-    if !status.is_success() {
-        anyhow::bail!(
-            "Failed to create proxy host: {} - {}",
-            status,
-            response_text
-        );
-    }
-
-    let created: serde_json::Value =
-        serde_json::from_str(&response_text).context("Failed to parse create response")?;
-
-    let id = created["id"].as_u64().context("Response missing ID")? as u32;
-
-    Ok(id)
-}
-
-pub async fn setup_single_proxy_host(
-    hostname: &str,
-    service_spec: &str
-) -> Result<()> {
-    // Parse service spec: "servicename:port" or "servicename" (defaults to port from common services)
-    let (service_name, port) = if let Some((name, port_str)) = service_spec.split_once(':') {
+/// Parse a service spec: "servicename:port" or a known service name ("portainer", "npm")
+fn parse_service_spec(service_spec: &str) -> Result<(&str, u16)> {
+    if let Some((name, port_str)) = service_spec.split_once(':') {
         let port = port_str
             .parse::<u16>()
             .with_context(|| format!("Invalid port: {}", port_str))?;
-        (name, port)
-    } else {
-        // Check for common service names
-        let port = match service_spec.to_lowercase().as_str() {
-            "portainer" => 9000,
-            "npm" | "nginx-proxy-manager" => 81,
-            _ => anyhow::bail!(
-                "Unknown service '{}'. Use format 'servicename:port' (e.g., 'portainer:9000')",
-                service_spec
-            ),
-        };
-        (service_spec, port)
+        return Ok((name, port));
+    }
+    // Check for common service names
+    let port = match service_spec.to_lowercase().as_str() {
+        "portainer" => 9000,
+        "npm" | "nginx-proxy-manager" => 81,
+        _ => anyhow::bail!(
+            "Unknown service '{}'. Use format 'servicename:port' (e.g., 'portainer:9000')",
+            service_spec
+        ),
     };
+    Ok((service_spec, port))
+}
 
+/// Address proxy hosts should forward to for `hostname` (its IP, else its Tailscale name)
+fn forward_host_for(hostname: &str) -> Result<String> {
     let host_config = host::get_host_config_or_error(hostname)?;
-
-    let target_host = if let Some(ip) = &host_config.ip {
-        ip.clone()
+    if let Some(ip) = &host_config.ip {
+        Ok(ip.clone())
     } else if let Some(tailscale) = &host_config.tailscale {
-        tailscale.clone()
+        Ok(tailscale.clone())
     } else {
         anyhow::bail!("No IP or Tailscale hostname configured for {}", hostname);
-    };
+    }
+}
+
+pub async fn setup_single_proxy_host(hostname: &str, service_spec: &str, ssl: bool) -> Result<()> {
+    let (service_name, port) = parse_service_spec(service_spec)?;
+    let target_host = forward_host_for(hostname)?;
+    let acme_email = if ssl { Some(acme_email()?) } else { None };
 
     println!(
         "Setting up proxy host for {} on {}...",
@@ -339,21 +371,13 @@ pub async fn setup_single_proxy_host(
     );
     println!();
 
-    // Get NPM credentials from config
-    let npm_url =
-        crate::config::get_npm_url().unwrap_or_else(|| format!("https://{}:81", target_host));
-    let npm_username = crate::config::get_npm_username().context("NPM_USERNAME not set in .env")?;
-    let npm_password = crate::config::get_npm_password().context("NPM_PASSWORD not set in .env")?;
-
-    // Login to NPM API
-    let token = login_to_npm(&npm_url, &npm_username, &npm_password)
-        .await
-        .context("Failed to login to Nginx Proxy Manager")?;
+    let mut session = NpmSession::connect(&target_host).await?;
     println!("✓ Authenticated with Nginx Proxy Manager");
     println!();
 
     // Get existing proxy hosts
-    let existing_hosts = get_proxy_hosts(&npm_url, &token)
+    let existing_hosts = session
+        .proxy_hosts()
         .await
         .context("Failed to get existing proxy hosts")?;
 
@@ -364,26 +388,429 @@ pub async fn setup_single_proxy_host(
         .iter()
         .find(|h| h.domain_names.contains(&domain));
 
-    if let Some(existing_host) = existing {
+    let host_id = if let Some(existing_host) = existing {
         println!(
             "Proxy host already exists (ID: {}) for {}",
             existing_host.id, domain
         );
         println!("  Domain: {}", domain);
-        return Ok(());
+        if acme_email.is_none() || existing_host.certificate_id != 0 {
+            return Ok(());
+        }
+        existing_host.id
+    } else {
+        // Create new proxy host
+        let id = session
+            .create_proxy_host(&domain, &target_host, port)
+            .await
+            .context("Failed to create proxy host")?;
+        println!("✓ Created proxy host (ID: {})", id);
+        println!("  Domain: {}", domain);
+        println!("  Forward: http://{}:{}", target_host, port);
+        id
+    };
+
+    if let Some(email) = &acme_email {
+        println!();
+        session.secure_proxy_host(host_id, &domain, email).await?;
     }
 
-    // Create new proxy host
-    match create_proxy_host(&npm_url, &token, &domain, &target_host, port).await {
-        Ok(id) => {
-            println!("✓ Created proxy host (ID: {})", id);
-            println!("  Domain: {}", domain);
-            println!("  Forward: http://{}:{}", target_host, port);
+    Ok(())
+}
+
+/// ACME_EMAIL is required to register with Let's Encrypt
+fn acme_email() -> Result<String> {
+    crate::config::get_acme_email()
+        .context("ACME_EMAIL not set in .env (needed to request Let's Encrypt certificates)")
+}
+
+/// Authenticated NPM API session that logs in again when the token expires
+struct NpmSession {
+    url: String,
+    username: String,
+    password: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl NpmSession {
+    /// Log in with NPM_URL/NPM_USERNAME/NPM_PASSWORD (NPM_URL defaults to https://<target_host>:81)
+    async fn connect(target_host: &str) -> Result<Self> {
+        let url =
+            crate::config::get_npm_url().unwrap_or_else(|| format!("https://{}:81", target_host));
+        let username =
+            crate::config::get_npm_username().context("NPM_USERNAME not set in .env or the database")?;
+        let password =
+            crate::config::get_npm_password().context("NPM_PASSWORD not set in .env or the database")?;
+        let token = login_to_npm(&url, &username, &password)
+            .await
+            .context("Failed to login to Nginx Proxy Manager")?;
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()?;
+        Ok(Self {
+            url,
+            username,
+            password,
+            token,
+            client,
+        })
+    }
+
+    /// Send an API request, re-authenticating once if the token was rejected
+    async fn send(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<reqwest::Response> {
+        self.send_with_timeout(method, path, body, None).await
+    }
+
+    async fn send_with_timeout(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+        timeout: Option<Duration>,
+    ) -> Result<reqwest::Response> {
+        let api_url = format!("{}/api/{}", self.url, path);
+        let mut reauthenticated = false;
+        loop {
+            let mut request = self
+                .client
+                .request(method.clone(), &api_url)
+                .bearer_auth(&self.token);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            if let Some(timeout) = timeout {
+                request = request.timeout(timeout);
+            }
+            let response = request
+                .send()
+                .await
+                .with_context(|| format!("Failed to reach Nginx Proxy Manager at {}", self.url))?;
+            if response.status() != reqwest::StatusCode::UNAUTHORIZED || reauthenticated {
+                return Ok(response);
+            }
+            println!("NPM token expired, re-authenticating...");
+            self.token = login_to_npm(&self.url, &self.username, &self.password)
+                .await
+                .context("Failed to re-authenticate with Nginx Proxy Manager")?;
+            reauthenticated = true;
+        }
+    }
+
+    async fn proxy_hosts(&mut self) -> Result<Vec<ProxyHost>> {
+        let response = self
+            .send(reqwest::Method::GET, "nginx/proxy-hosts", None)
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to get proxy hosts: {}", response.status());
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse proxy hosts response")
+    }
+
+    /// Find the proxy host serving `domain`, listing the known domains if there is none
+    async fn find_proxy_host(&mut self, domain: &str) -> Result<ProxyHost> {
+        let hosts = self.proxy_hosts().await?;
+        let mut domains: Vec<String> = hosts
+            .iter()
+            .flat_map(|h| h.domain_names.clone())
+            .collect();
+        if let Some(found) = hosts
+            .into_iter()
+            .find(|h| h.domain_names.iter().any(|d| d.eq_ignore_ascii_case(domain)))
+        {
+            return Ok(found);
         }
-        Err(e) => {
-            anyhow::bail!("Failed to create proxy host: {}", e);
+        domains.sort();
+        if domains.is_empty() {
+            anyhow::bail!(
+                "No proxy host found for {} (Nginx Proxy Manager has no proxy hosts)",
+                domain
+            );
         }
+        anyhow::bail!(
+            "No proxy host found for {}. Existing domains:\n  {}",
+            domain,
+            domains.join("\n  ")
+        );
+    }
+
+    async fn create_proxy_host(
+        &mut self,
+        domain: &str,
+        forward_host: &str,
+        forward_port: u16,
+    ) -> Result<u32> {
+        let proxy_req = ProxyHostRequest {
+            domain_names: vec![domain.to_string()],
+            forward_scheme: "http".to_string(),
+            forward_host: forward_host.to_string(),
+            forward_port,
+            ssl_forced: Some(false),
+            certificate_id: None,
+            access_list_id: None,
+            advanced_config: None,
+            locations: None,
+            block_exploits: Some(false),
+            caching_enabled: Some(false),
+            allow_websocket_upgrade: Some(true),
+            http2_support: Some(false),
+        };
+        let body = serde_json::to_value(&proxy_req)?;
+
+        let response = self
+            .send(reqwest::Method::POST, "nginx/proxy-hosts", Some(&body))
+            .await?;
+        let status = response.status();
+        let response_text = response.text().await.unwrap_or_default();
+
+        if !status.is_success() {
+            anyhow::bail!(
+                "Failed to create proxy host: {} - {}",
+                status,
+                response_text
+            );
+        }
+
+        let created: serde_json::Value =
+            serde_json::from_str(&response_text).context("Failed to parse create response")?;
+
+        let id = created["id"].as_u64().context("Response missing ID")? as u32;
+
+        Ok(id)
+    }
+
+    /// Request a Let's Encrypt certificate for `domain` and bind it to proxy host `host_id`
+    async fn secure_proxy_host(&mut self, host_id: u32, domain: &str, email: &str) -> Result<()> {
+        println!(
+            "  Requesting Let's Encrypt certificate for {} (up to {}s)...",
+            domain,
+            CERTIFICATE_TIMEOUT.as_secs()
+        );
+        let certificate_id = self.request_certificate(domain, email).await?;
+        println!("  ✓ Certificate issued (ID: {})", certificate_id);
+
+        let body = serde_json::json!({
+            "certificate_id": certificate_id,
+            "ssl_forced": true,
+            "http2_support": true,
+        });
+        let response = self
+            .send(
+                reqwest::Method::PUT,
+                &format!("nginx/proxy-hosts/{}", host_id),
+                Some(&body),
+            )
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "Certificate {} was issued but binding it to proxy host {} failed: {} - {}",
+                certificate_id,
+                host_id,
+                status,
+                text
+            );
+        }
+        println!("  ✓ HTTPS enabled for {}", domain);
+        Ok(())
+    }
+
+    /// Ask NPM to issue a certificate, returning its ID once Let's Encrypt has signed it
+    async fn request_certificate(&mut self, domain: &str, email: &str) -> Result<u32> {
+        let deadline = Instant::now() + CERTIFICATE_TIMEOUT;
+        let body = serde_json::json!({
+            "provider": "letsencrypt",
+            "nice_name": domain,
+            "domain_names": [domain],
+            "meta": {
+                "letsencrypt_email": email,
+                "letsencrypt_agree": true,
+                "dns_challenge": false,
+            },
+        });
+
+        // NPM runs certbot before answering; if that outlasts the request, poll for the result
+        let response = match self
+            .send_with_timeout(
+                reqwest::Method::POST,
+                "nginx/certificates",
+                Some(&body),
+                Some(CERTIFICATE_TIMEOUT),
+            )
+            .await
+        {
+            Ok(response) => response,
+            Err(e) if e.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout()) => {
+                return self.wait_for_certificate(domain, None, deadline).await;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            let message = serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(text);
+            if is_acme_failure(&message) {
+                anyhow::bail!(
+                    "Let's Encrypt could not validate {}: {}\n\
+                     The domain must resolve publicly to Nginx Proxy Manager, with port 80 reachable.",
+                    domain,
+                    message.trim()
+                );
+            }
+            anyhow::bail!(
+                "Nginx Proxy Manager API error while requesting a certificate: {} - {}",
+                status,
+                message.trim()
+            );
+        }
+
+        let created: serde_json::Value =
+            serde_json::from_str(&text).context("Failed to parse certificate response")?;
+        let id = created["id"].as_u64().context("Certificate response missing ID")? as u32;
+        self.wait_for_certificate(domain, Some(id), deadline).await
+    }
+
+    /// Poll until the certificate (by ID, or else by domain) has an expiry date, i.e. was issued
+    async fn wait_for_certificate(
+        &mut self,
+        domain: &str,
+        id: Option<u32>,
+        deadline: Instant,
+    ) -> Result<u32> {
+        loop {
+            let response = self
+                .send(reqwest::Method::GET, "nginx/certificates", None)
+                .await?;
+            if !response.status().is_success() {
+                anyhow::bail!("Failed to get certificates: {}", response.status());
+            }
+            let certificates: Vec<serde_json::Value> = response
+                .json()
+                .await
+                .context("Failed to parse certificates response")?;
+
+            let issued = certificates.iter().find(|cert| {
+                let matches = match id {
+                    Some(id) => cert["id"].as_u64() == Some(id as u64),
+                    None => cert["domain_names"]
+                        .as_array()
+                        .is_some_and(|names| names.iter().any(|n| n.as_str() == Some(domain))),
+                };
+                matches && cert["expires_on"].as_str().is_some_and(|e| !e.is_empty())
+            });
+            if let Some(cert) = issued {
+                return cert["id"]
+                    .as_u64()
+                    .map(|id| id as u32)
+                    .context("Certificate missing ID");
+            }
+
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "Timed out after {}s waiting for the Let's Encrypt certificate for {} \
+                     (check SSL Certificates in Nginx Proxy Manager)",
+                    CERTIFICATE_TIMEOUT.as_secs(),
+                    domain
+                );
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    }
+}
+
+/// Whether an NPM error came from certbot/ACME (DNS or challenge failure) rather than the API
+fn is_acme_failure(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "challenge",
+        "dns problem",
+        "nxdomain",
+        "certbot",
+        "acme",
+        "too many certificates",
+    ]
+    .iter()
+    .any(|marker| message.contains(marker))
+}
+
+/// Delete the proxy host serving `domain`
+pub async fn delete_proxy_host(hostname: &str, domain: &str) -> Result<()> {
+    let target_host = forward_host_for(hostname).unwrap_or_else(|_| hostname.to_string());
+    let mut session = NpmSession::connect(&target_host).await?;
+    println!("✓ Authenticated with Nginx Proxy Manager");
+
+    let proxy_host = session.find_proxy_host(domain).await?;
+    let response = session
+        .send(
+            reqwest::Method::DELETE,
+            &format!("nginx/proxy-hosts/{}", proxy_host.id),
+            None,
+        )
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to delete proxy host: {} - {}", status, text);
+    }
+
+    println!("✓ Deleted proxy host (ID: {})", proxy_host.id);
+    println!("  Domain: {}", proxy_host.domain_names.join(", "));
+    Ok(())
+}
+
+/// Point the existing proxy host for a service ("name:port" or a known name) at `hostname`
+pub async fn update_proxy_host(hostname: &str, service_spec: &str) -> Result<()> {
+    let (service_name, port) = parse_service_spec(service_spec)?;
+    let target_host = forward_host_for(hostname)?;
+    let domain = format!("{}.local", service_name);
+
+    println!("Updating proxy host for {} on {}...", service_name, hostname);
+    println!();
+
+    let mut session = NpmSession::connect(&target_host).await?;
+    println!("✓ Authenticated with Nginx Proxy Manager");
+
+    let proxy_host = session.find_proxy_host(&domain).await.map_err(|e| {
+        anyhow::anyhow!(
+            "{}\n\nCreate it with: hal npm --service {} -H {}",
+            e,
+            service_spec,
+            hostname
+        )
+    })?;
+    let body = serde_json::json!({
+        "forward_scheme": "http",
+        "forward_host": target_host,
+        "forward_port": port,
+    });
+    let response = session
+        .send(
+            reqwest::Method::PUT,
+            &format!("nginx/proxy-hosts/{}", proxy_host.id),
+            Some(&body),
+        )
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to update proxy host: {} - {}", status, text);
     }
 
+    println!("✓ Updated proxy host (ID: {})", proxy_host.id);
+    println!("  Domain: {}", domain);
+    println!("  Forward: http://{}:{}", target_host, port);
     Ok(())
 }
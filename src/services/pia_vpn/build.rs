@@ -10,7 +10,7 @@ pub fn build_and_push_vpn_image(
 ) -> Result<()> {
     // Create executor - it automatically determines if execution should be local or remote
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
     if is_local {
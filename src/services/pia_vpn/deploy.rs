@@ -1,9 +1,41 @@
+use crate::services::docker;
+use crate::services::pia_vpn::verify::{DEFAULT_VERIFY_TIMEOUT, verify_vpn};
 use crate::utils::exec::{CommandExecutor, Executor};
 use anyhow::{Context, Result};
 use std::env;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-pub fn deploy_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<()> {
+const DEFAULT_VPN_IMAGE: &str = "ghcr.io/scottdkey/pia-vpn:latest";
+
+/// Region used when `--region` isn't given (openvpn/ca-montreal.ovpn)
+pub const DEFAULT_VPN_REGION: &str = "ca-montreal";
+
+/// Host metadata key holding the deployed region
+pub const VPN_REGION_METADATA_KEY: &str = "vpn_region";
+
+/// How long to wait for the container to come up after `docker compose up -d`
+const START_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Copy the VPN config for `region` to `hostname`; with `start`, also bring the container up and verify it
+/// `bwlimit_kbps` caps the upload rate of the copied files
+pub fn deploy_vpn(
+    hostname: &str,
+    config: &crate::config::EnvConfig,
+    region: Option<&str>,
+    start: bool,
+    bwlimit_kbps: Option<u32>,
+) -> Result<()> {
     let homelab_dir = crate::config::find_homelab_dir()?;
+    let openvpn_dir = homelab_dir.join("openvpn");
+    // An explicit region is checked up front; the default is only needed if files must be copied
+    let config_file = match region {
+        Some(region) => region_config_file(&openvpn_dir, region)?,
+        None => openvpn_dir.join(format!("{}.ovpn", DEFAULT_VPN_REGION)),
+    };
+    let region = region.unwrap_or(DEFAULT_VPN_REGION);
+    // The container looks for /config/<REGION>.ovpn with REGION lowercased and '-' as '_'
+    let container_region = region.to_lowercase().replace('-', "_");
 
     // Load PIA credentials from local .env
     dotenv::from_path(homelab_dir.join(".env")).context("Failed to load .env file")?;
@@ -12,8 +44,8 @@ pub fn deploy_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<(
     let pia_password = env::var("PIA_PASSWORD").context("PIA_PASSWORD not found in .env file")?;
 
     // Create executor - it automatically determines if execution should be local or remote
-    let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let exec = Executor::new(hostname, config)?.with_bwlimit(bwlimit_kbps);
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
     if is_local {
@@ -38,18 +70,17 @@ pub fn deploy_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<(
     // Don't substitute - let docker-compose read from .env file using --env-file
 
     // Determine username for VPN config path
-    let default_user = crate::config::get_default_username();
+    let default_user = exec.login_user();
     // Allow VPN_USER to override the username for config path (useful for Portainer)
-    // If not set, uses the default user
+    // If not set, uses the user halvor logs in as (the host's SSH user when remote)
     let vpn_user = env::var("VPN_USER").unwrap_or_else(|_| default_user.clone());
 
     // Check if files already exist - if so, skip deployment
-    // Check for both .ovpn and .opvn (typo) variants
     // Use /home/$USER/config/vpn (USER can be set via VPN_USER env var)
     let vpn_config_dir = format!("/home/{}/config/vpn", vpn_user);
+    let remote_config_path = format!("{}/{}.ovpn", vpn_config_dir, container_region);
     let auth_exists = exec.file_exists(&format!("{}/auth.txt", vpn_config_dir))?;
-    let config_exists = exec.file_exists(&format!("{}/ca-montreal.ovpn", vpn_config_dir))?
-        || exec.file_exists(&format!("{}/ca-montreal.opvn", vpn_config_dir))?;
+    let config_exists = exec.file_exists(&remote_config_path)?;
     let files_exist = auth_exists && config_exists;
 
     if files_exist {
@@ -63,9 +94,7 @@ pub fn deploy_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<(
         println!("VPN configuration files not found, attempting to copy...");
 
         // Copy OpenVPN config files
-        let openvpn_dir = homelab_dir.join("openvpn");
         let auth_file = openvpn_dir.join("auth.txt");
-        let config_file = openvpn_dir.join("ca-montreal.ovpn");
 
         if !auth_file.exists() {
             anyhow::bail!("OpenVPN auth file not found at {}", auth_file.display());
@@ -92,39 +121,58 @@ pub fn deploy_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<(
         let config_content = std::fs::read(&config_file)
             .with_context(|| format!("Failed to read config file: {}", config_file.display()))?;
 
-        exec.write_file(
-            &format!("{}/ca-montreal.ovpn", vpn_config_dir),
-            &config_content,
-        )?;
-        exec.execute_shell_interactive(&format!("chmod 644 {}/ca-montreal.ovpn", vpn_config_dir))?;
+        exec.write_file(&remote_config_path, &config_content)?;
+        exec.execute_shell_interactive(&format!("chmod 644 {}", remote_config_path))?;
         if is_local {
-            println!("✓ Copied ca-montreal.ovpn");
+            println!("✓ Copied {}.ovpn", container_region);
         } else {
-            println!("✓ Copied ca-montreal.ovpn to remote system");
+            println!("✓ Copied {}.ovpn to remote system", container_region);
         }
     }
 
     // Copy compose file (keep in home directory for user access)
-    exec.mkdir_p("$HOME/vpn")?;
-    exec.write_file("$HOME/vpn/docker-compose.yml", compose_content.as_bytes())?;
+    // Local paths aren't shell-expanded, so resolve $HOME here
+    let vpn_dir = if is_local {
+        crate::config::config_manager::get_home_dir()?
+            .join("vpn")
+            .display()
+            .to_string()
+    } else {
+        "$HOME/vpn".to_string()
+    };
+    exec.mkdir_p(&vpn_dir)?;
+    exec.write_file(
+        &format!("{}/docker-compose.yml", vpn_dir),
+        compose_content.as_bytes(),
+    )?;
     if is_local {
         println!("✓ Copied VPN compose file");
     } else {
         println!("✓ Copied VPN compose file to remote system");
     }
 
-    // Create .env file with PIA credentials
-    let env_content = format!(
-        "PIA_USERNAME={}\nPIA_PASSWORD={}\n",
-        pia_username, pia_password
+    // Create .env file with PIA credentials, the region (and the image override, if any)
+    let mut env_content = format!(
+        "PIA_USERNAME={}\nPIA_PASSWORD={}\nREGION={}\n",
+        pia_username, pia_password, container_region
     );
-    exec.write_file("$HOME/vpn/.env", env_content.as_bytes())?;
+    let vpn_image = env::var("VPN_IMAGE").ok().filter(|image| !image.is_empty());
+    if let Some(image) = &vpn_image {
+        env_content.push_str(&format!("VPN_IMAGE={}\n", image));
+    }
+    exec.write_file(&format!("{}/.env", vpn_dir), env_content.as_bytes())?;
     if is_local {
         println!("✓ Created .env file");
     } else {
         println!("✓ Created .env file on remote system");
     }
 
+    crate::services::host::set_host_metadata(
+        hostname,
+        VPN_REGION_METADATA_KEY,
+        serde_json::json!(region),
+    )?;
+
     println!();
     println!(
         "✓ VPN configuration files copied to {} ({})",
@@ -138,14 +186,197 @@ pub fn deploy_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<(
         vpn_user
     );
     println!(
-        "    - /home/{}/config/vpn/ca-montreal.ovpn (OpenVPN configuration)",
-        vpn_user
+        "    - /home/{}/config/vpn/{}.ovpn (OpenVPN configuration, region {})",
+        vpn_user, container_region, region
     );
+
+    if start {
+        let image = vpn_image.as_deref().unwrap_or(DEFAULT_VPN_IMAGE);
+        start_vpn(&exec, hostname, &vpn_dir, &vpn_user, image)?;
+        println!();
+        let report = verify_vpn(hostname, config, DEFAULT_VERIFY_TIMEOUT)?;
+        if !report.passed() {
+            anyhow::bail!("VPN started on {} but verification failed", hostname);
+        }
+        return Ok(());
+    }
+
     println!();
     println!("  Note: Set USER environment variable in Portainer to match the username");
     println!("        Example: USER={}", vpn_user);
     println!();
     println!("  You can now deploy the VPN manually using Portainer or docker-compose.");
+    println!(
+        "  Or rerun with --start: hal vpn deploy {} --start",
+        hostname
+    );
+
+    Ok(())
+}
+
+/// Find openvpn/<region>.ovpn, listing the available regions if it isn't there
+fn region_config_file(openvpn_dir: &Path, region: &str) -> Result<PathBuf> {
+    let valid = !region.is_empty()
+        && region
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let config_file = openvpn_dir.join(format!("{}.ovpn", region));
+    if valid && config_file.is_file() {
+        return Ok(config_file);
+    }
+
+    let regions = available_regions(openvpn_dir);
+    if regions.is_empty() {
+        anyhow::bail!(
+            "OpenVPN config for region '{}' not found: no .ovpn files in {}",
+            region,
+            openvpn_dir.display()
+        );
+    }
+    anyhow::bail!(
+        "Unknown VPN region '{}'. Available regions in {}:\n  {}",
+        region,
+        openvpn_dir.display(),
+        regions.join("\n  ")
+    );
+}
+
+/// Region names (file stems) of the .ovpn files in `openvpn_dir`, sorted
+fn available_regions(openvpn_dir: &Path) -> Vec<String> {
+    let mut regions: Vec<String> = std::fs::read_dir(openvpn_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "ovpn"))
+                .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    regions.sort();
+    regions
+}
 
+/// Pull the image and run `docker compose up -d` in `vpn_dir`, then wait for the container
+fn start_vpn(
+    exec: &Executor,
+    hostname: &str,
+    vpn_dir: &str,
+    vpn_user: &str,
+    image: &str,
+) -> Result<()> {
+    let compose_cmd = docker::get_compose_command(exec)?;
+    // The compose file mounts /home/${USER}/config/vpn, so pin USER to the config owner
+    let compose = format!(
+        "cd \"{}\" && USER={} {} --env-file .env",
+        vpn_dir, vpn_user, compose_cmd
+    );
+
+    println!();
+    println!("Pulling {}...", image);
+    let output = exec.execute_shell(&format!("{} pull", compose))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_registry_auth_error(&stderr) {
+            anyhow::bail!(
+                "Not authorized to pull {} on {}.\n\
+                 Log in on the host with `docker login ghcr.io` (a token with read:packages), \
+                 or make the package public. See docs/GHCR_SETUP.md.\n{}",
+                image,
+                hostname,
+                stderr.trim()
+            );
+        }
+        anyhow::bail!("Failed to pull {}: {}", image, stderr.trim());
+    }
+    println!("✓ Image pulled");
+
+    // --no-build: the build context only exists in the homelab checkout, not on the host
+    let output = exec.execute_shell(&format!("{} up -d --no-build", compose))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} up failed: {}",
+            compose_cmd,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    println!("✓ VPN container started");
+
+    wait_for_vpn(exec, START_TIMEOUT)
+}
+
+fn is_registry_auth_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    [
+        "unauthorized",
+        "denied",
+        "authentication required",
+        "403 forbidden",
+    ]
+    .iter()
+    .any(|needle| stderr.contains(needle))
+}
+
+/// Wait until the container is healthy (or, without a healthcheck, running with tun0 up)
+fn wait_for_vpn(exec: &Executor, timeout: Duration) -> Result<()> {
+    println!(
+        "Waiting for VPN connection (up to {}s)...",
+        timeout.as_secs()
+    );
+    let deadline = Instant::now() + timeout;
+    loop {
+        let output = exec.execute_simple(
+            "docker",
+            &[
+                "inspect",
+                "-f",
+                "{{.State.Status}} {{if .State.Health}}{{.State.Health.Status}}{{end}}",
+                "openvpn-pia",
+            ],
+        )?;
+        let state = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let mut parts = state.split_whitespace();
+        let status = parts.next().unwrap_or("");
+        let health = parts.next();
+
+        match (status, health) {
+            ("running", Some("healthy")) => break,
+            ("running", None) => {
+                let tun = exec.execute_simple(
+                    "docker",
+                    &["exec", "openvpn-pia", "ip", "addr", "show", "tun0"],
+                )?;
+                if tun.status.success() {
+                    break;
+                }
+            }
+            ("exited", _) | ("dead", _) => {
+                print_recent_logs(exec)?;
+                anyhow::bail!("VPN container stopped ({})", state);
+            }
+            _ => {}
+        }
+
+        if Instant::now() >= deadline {
+            print_recent_logs(exec)?;
+            anyhow::bail!(
+                "VPN container did not become ready within {}s (state: {})",
+                timeout.as_secs(),
+                if state.is_empty() { "unknown" } else { &state }
+            );
+        }
+        std::thread::sleep(Duration::from_secs(3));
+    }
+
+    println!("✓ VPN container is up");
+    Ok(())
+}
+
+fn print_recent_logs(exec: &Executor) -> Result<()> {
+    let output = exec.execute_shell("docker logs --tail 20 openvpn-pia 2>&1")?;
+    println!("Last container log lines:");
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        println!("  {}", line);
+    }
     Ok(())
 }
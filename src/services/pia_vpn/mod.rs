@@ -7,4 +7,4 @@ mod vpn_utils;
 // Re-export public functions
 pub use build::build_and_push_vpn_image;
 pub use deploy::deploy_vpn;
-pub use verify::verify_vpn;
+pub use verify::{DEFAULT_VERIFY_TIMEOUT, VpnCheck, VpnCheckStatus, VpnVerifyReport, verify_vpn};
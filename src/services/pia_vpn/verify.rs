@@ -1,8 +1,171 @@
+use crate::services::pia_vpn::deploy::VPN_REGION_METADATA_KEY;
 use crate::services::pia_vpn::vpn_utils;
-use crate::utils::exec::{CommandExecutor, Executor, local};
+use crate::utils::exec::Executor;
+use crate::utils::progress;
+use crate::utils::ssh::shell_escape;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::process::Output;
+use std::time::{Duration, Instant};
 
-pub fn verify_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<()> {
+/// Default budget for the whole verification
+pub const DEFAULT_VERIFY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Longest any single check may take (the curl checks use --max-time 10, plus ssh setup)
+const STEP_TIMEOUT: Duration = Duration::from_secs(20);
+
+const TOTAL_CHECKS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VpnCheckStatus {
+    Passed,
+    /// Worth a look, but doesn't fail the verification
+    Warning,
+    Failed,
+    /// The check hit its deadline and was killed
+    TimedOut,
+    /// Not run: the container is missing or the overall timeout was used up
+    Skipped,
+}
+
+impl VpnCheckStatus {
+    fn marker(self) -> &'static str {
+        match self {
+            VpnCheckStatus::Passed => "✓",
+            VpnCheckStatus::Warning | VpnCheckStatus::Skipped => "⚠",
+            VpnCheckStatus::Failed | VpnCheckStatus::TimedOut => "✗",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnCheck {
+    pub name: String,
+    pub status: VpnCheckStatus,
+    /// Human-readable result, e.g. "OpenVPN is running (PID: 42)"
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// Per-check results of `hal vpn verify`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnVerifyReport {
+    pub hostname: String,
+    pub target_host: String,
+    /// Region recorded by the last `hal vpn deploy` (None if unknown)
+    pub region: Option<String>,
+    pub checks: Vec<VpnCheck>,
+    /// Recent error lines from the OpenVPN log (None if the log check didn't run)
+    pub log_errors: Option<Vec<String>>,
+}
+
+impl VpnVerifyReport {
+    /// True when every check ran and none failed or timed out (warnings are fine)
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| {
+            !matches!(
+                check.status,
+                VpnCheckStatus::Failed | VpnCheckStatus::TimedOut | VpnCheckStatus::Skipped
+            )
+        })
+    }
+}
+
+/// Outcome of running one check command
+enum Step {
+    Done(Output),
+    TimedOut(Duration),
+    OutOfTime,
+}
+
+/// Runs checks against a shared deadline and records their results
+struct Verifier<'a> {
+    exec: &'a Executor,
+    deadline: Instant,
+    report: VpnVerifyReport,
+}
+
+impl Verifier<'_> {
+    /// Run `command`, giving it whatever is left of the overall budget (capped per step)
+    fn run(&self, exec: &Executor, command: &str) -> Result<Step> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(Step::OutOfTime);
+        }
+        let timeout = remaining.min(STEP_TIMEOUT);
+        Ok(match exec.execute_shell_timeout(command, timeout)? {
+            Some(output) => Step::Done(output),
+            None => Step::TimedOut(timeout),
+        })
+    }
+
+    /// Announce check `index`, run `command` and record what `judge` makes of its output
+    fn check(
+        &mut self,
+        index: usize,
+        name: &str,
+        command: &str,
+        judge: impl FnOnce(&Output) -> (VpnCheckStatus, String),
+    ) -> Result<VpnCheckStatus> {
+        self.check_with(self.exec, index, name, command, judge)
+    }
+
+    fn check_with(
+        &mut self,
+        exec: &Executor,
+        index: usize,
+        name: &str,
+        command: &str,
+        judge: impl FnOnce(&Output) -> (VpnCheckStatus, String),
+    ) -> Result<VpnCheckStatus> {
+        println!("[{}/{}] {}...", index, TOTAL_CHECKS, name);
+        let started = Instant::now();
+        let step = progress::with_spinner("Waiting for result", || self.run(exec, command))?;
+        let (status, detail) = match step {
+            Step::Done(output) => judge(&output),
+            Step::TimedOut(after) => (
+                VpnCheckStatus::TimedOut,
+                format!("Timed out after {}s", after.as_secs()),
+            ),
+            Step::OutOfTime => (
+                VpnCheckStatus::Skipped,
+                "Skipped: overall timeout reached".to_string(),
+            ),
+        };
+        Ok(self.record(name, status, detail, started.elapsed()))
+    }
+
+    fn record(
+        &mut self,
+        name: &str,
+        status: VpnCheckStatus,
+        detail: String,
+        elapsed: Duration,
+    ) -> VpnCheckStatus {
+        println!("   {} {}", status.marker(), detail);
+        self.report.checks.push(VpnCheck {
+            name: name.to_string(),
+            status,
+            detail,
+            duration_ms: elapsed.as_millis() as u64,
+        });
+        status
+    }
+}
+
+fn stdout_of(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Verify the VPN container on `hostname`, giving up on checks once `timeout` has elapsed
+///
+/// Returns the report even when checks fail; use `VpnVerifyReport::passed` to decide the outcome.
+pub fn verify_vpn(
+    hostname: &str,
+    config: &crate::config::EnvConfig,
+    timeout: Duration,
+) -> Result<VpnVerifyReport> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("  VPN Verification for {}", hostname);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -10,7 +173,7 @@ pub fn verify_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<(
 
     // Create executor - it automatically determines if execution should be local or remote
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
     if is_local {
@@ -18,189 +181,292 @@ pub fn verify_vpn(hostname: &str, config: &crate::config::EnvConfig) -> Result<(
     } else {
         println!("Detected remote execution - verifying via SSH");
     }
+    let region = crate::services::host::get_host_metadata(hostname, VPN_REGION_METADATA_KEY)
+        .ok()
+        .flatten()
+        .and_then(|value| value.as_str().map(str::to_string));
+    if let Some(region) = &region {
+        println!("Region: {}", region);
+    }
+    println!("Timeout: {}s", timeout.as_secs());
     println!();
 
-    verify_vpn_with_executor(hostname, &target_host, &exec)
-}
+    let mut verifier = Verifier {
+        exec: &exec,
+        deadline: Instant::now() + timeout,
+        report: VpnVerifyReport {
+            hostname: hostname.to_string(),
+            target_host: target_host.clone(),
+            region,
+            checks: Vec::new(),
+            log_errors: None,
+        },
+    };
+    run_checks(&mut verifier, hostname, &target_host)?;
 
-fn verify_vpn_with_executor(hostname: &str, target_host: &str, exec: &Executor) -> Result<()> {
-    let mut all_passed = true;
+    let report = verifier.report;
+    vpn_utils::print_summary(&report);
+    Ok(report)
+}
 
+fn run_checks(verifier: &mut Verifier, hostname: &str, target_host: &str) -> Result<()> {
     // Test 1: Check if container is running
-    println!("[1/10] Checking VPN container status...");
-    use crate::services::docker;
-    if docker::is_container_running(exec, "openvpn-pia")? {
-        println!("   ✓ VPN container is running");
-    } else {
-        println!("   ✗ VPN container is not running");
+    let container = verifier.check(
+        1,
+        "Checking VPN container status",
+        "docker ps --filter name=openvpn-pia --format '{{.Names}}'",
+        |output| {
+            if stdout_of(output).contains("openvpn-pia") {
+                (
+                    VpnCheckStatus::Passed,
+                    "VPN container is running".to_string(),
+                )
+            } else {
+                (
+                    VpnCheckStatus::Failed,
+                    format!(
+                        "VPN container is not running. Deploy VPN first with: hal vpn deploy {}",
+                        hostname
+                    ),
+                )
+            }
+        },
+    )?;
+    if container != VpnCheckStatus::Passed {
+        // Nothing else can be checked without the container
         println!();
-        anyhow::bail!(
-            "VPN container not found. Deploy VPN first with: hal vpn deploy {}",
-            hostname
-        );
+        return Ok(());
     }
 
     // Test 2: Check OpenVPN process
-    println!("[2/10] Checking OpenVPN process...");
-    let openvpn_check = exec.execute_shell("docker exec openvpn-pia pgrep -f openvpn")?;
-    if openvpn_check.status.success() {
-        let pid = String::from_utf8_lossy(&openvpn_check.stdout)
-            .trim()
-            .to_string();
-        println!("   ✓ OpenVPN is running (PID: {})", pid);
-    } else {
-        println!("   ✗ OpenVPN is not running");
-        all_passed = false;
-    }
+    verifier.check(
+        2,
+        "Checking OpenVPN process",
+        "docker exec openvpn-pia pgrep -f openvpn",
+        |output| {
+            if output.status.success() {
+                (
+                    VpnCheckStatus::Passed,
+                    format!("OpenVPN is running (PID: {})", stdout_of(output)),
+                )
+            } else {
+                (VpnCheckStatus::Failed, "OpenVPN is not running".to_string())
+            }
+        },
+    )?;
 
     // Test 3: Check TUN interface
-    println!("[3/10] Checking TUN interface...");
-    let tun_check = exec.execute_shell("docker exec openvpn-pia ip addr show tun0 2>&1")?;
-    if tun_check.status.success() {
-        let tun_output = String::from_utf8_lossy(&tun_check.stdout);
-        if let Some(ip_line) = tun_output.lines().find(|l| l.contains("inet ")) {
-            if let Some(ip_part) = ip_line.split_whitespace().nth(1) {
-                let ip = ip_part.split('/').next().unwrap_or(ip_part);
-                println!("   ✓ TUN interface is up (IP: {})", ip);
-            } else {
-                println!("   ✓ TUN interface is up");
+    verifier.check(
+        3,
+        "Checking TUN interface",
+        "docker exec openvpn-pia ip addr show tun0 2>&1",
+        |output| {
+            if !output.status.success() {
+                return (
+                    VpnCheckStatus::Failed,
+                    "TUN interface not found".to_string(),
+                );
             }
-        } else {
-            println!("⚠ TUN interface exists but no IP found");
-        }
-    } else {
-        println!("✗ TUN interface not found");
-        all_passed = false;
-    }
+            let tun_output = String::from_utf8_lossy(&output.stdout);
+            match tun_output.lines().find(|l| l.contains("inet ")) {
+                Some(ip_line) => match ip_line.split_whitespace().nth(1) {
+                    Some(ip_part) => {
+                        let ip = ip_part.split('/').next().unwrap_or(ip_part);
+                        (
+                            VpnCheckStatus::Passed,
+                            format!("TUN interface is up (IP: {})", ip),
+                        )
+                    }
+                    None => (VpnCheckStatus::Passed, "TUN interface is up".to_string()),
+                },
+                None => (
+                    VpnCheckStatus::Warning,
+                    "TUN interface exists but no IP found".to_string(),
+                ),
+            }
+        },
+    )?;
 
     // Test 4: Check routing
-    println!("[4/10] Checking routing configuration...");
-    let route_check = exec.execute_shell(
+    verifier.check(
+        4,
+        "Checking routing configuration",
         "docker exec openvpn-pia ip route | grep -E '0\\.0\\.0\\.0/1|128\\.0\\.0\\.0/1'",
+        |output| {
+            if !output.status.success() {
+                (
+                    VpnCheckStatus::Warning,
+                    "Could not verify routing".to_string(),
+                )
+            } else if stdout_of(output).contains("tun0") {
+                (
+                    VpnCheckStatus::Passed,
+                    "Traffic is routed through VPN".to_string(),
+                )
+            } else {
+                (
+                    VpnCheckStatus::Failed,
+                    "Routes may not be configured correctly".to_string(),
+                )
+            }
+        },
     )?;
-    if route_check.status.success() {
-        let route_output = String::from_utf8_lossy(&route_check.stdout);
-        if route_output.contains("tun0") {
-            println!("✓ Traffic is routed through VPN");
-        } else {
-            println!("⚠ Warning: Routes may not be configured correctly");
-            all_passed = false;
-        }
-    } else {
-        println!("⚠ Warning: Could not verify routing");
-    }
 
     // Test 5: Check Privoxy
-    println!("[5/10] Checking Privoxy process...");
-    let privoxy_check = exec.execute_shell("docker exec openvpn-pia pgrep privoxy")?;
-    if privoxy_check.status.success() {
-        let pid = String::from_utf8_lossy(&privoxy_check.stdout)
-            .trim()
-            .to_string();
-        println!("✓ Privoxy is running (PID: {})", pid);
-    } else {
-        println!("✗ Privoxy is not running");
-        all_passed = false;
-    }
+    verifier.check(
+        5,
+        "Checking Privoxy process",
+        "docker exec openvpn-pia pgrep privoxy",
+        |output| {
+            if output.status.success() {
+                (
+                    VpnCheckStatus::Passed,
+                    format!("Privoxy is running (PID: {})", stdout_of(output)),
+                )
+            } else {
+                (VpnCheckStatus::Failed, "Privoxy is not running".to_string())
+            }
+        },
+    )?;
 
     // Test 6: Check Privoxy port
-    println!("[6/10] Checking Privoxy port 8888...");
-    let port_check = exec.execute_shell("docker exec openvpn-pia ss -tlnp 2>/dev/null | grep 8888 || docker exec openvpn-pia netstat -tlnp 2>/dev/null | grep 8888")?;
-    if port_check.status.success() {
-        println!("✓ Privoxy is listening on port 8888");
-    } else {
-        println!("✗ Privoxy port 8888 not found");
-        println!("Host: {}", hostname);
-        all_passed = false;
-    }
+    verifier.check(
+        6,
+        "Checking Privoxy port 8888",
+        "docker exec openvpn-pia ss -tlnp 2>/dev/null | grep 8888 || docker exec openvpn-pia netstat -tlnp 2>/dev/null | grep 8888",
+        |output| {
+            if output.status.success() {
+                (
+                    VpnCheckStatus::Passed,
+                    "Privoxy is listening on port 8888".to_string(),
+                )
+            } else {
+                (
+                    VpnCheckStatus::Failed,
+                    format!("Privoxy port 8888 not found on {}", hostname),
+                )
+            }
+        },
+    )?;
 
     // Test 7: Test DNS resolution
-    println!("[7/10] Testing DNS resolution...");
-    let dns_check =
-        exec.execute_shell("docker exec openvpn-pia nslookup api.ipify.org 2>&1 | head -5")?;
-    if dns_check.status.success() {
-        let dns_output = String::from_utf8_lossy(&dns_check.stdout);
-        if dns_output.contains("Name:") || dns_output.contains("Address:") {
-            println!("✓ DNS resolution working");
-        } else {
-            println!("⚠ DNS resolution may have issues");
-        }
-    } else {
-        println!("⚠ DNS resolution test failed");
-    }
+    verifier.check(
+        7,
+        "Testing DNS resolution",
+        "docker exec openvpn-pia nslookup api.ipify.org 2>&1 | head -5",
+        |output| {
+            let dns_output = String::from_utf8_lossy(&output.stdout);
+            if !output.status.success() {
+                (
+                    VpnCheckStatus::Warning,
+                    "DNS resolution test failed".to_string(),
+                )
+            } else if dns_output.contains("Name:") || dns_output.contains("Address:") {
+                (VpnCheckStatus::Passed, "DNS resolution working".to_string())
+            } else {
+                (
+                    VpnCheckStatus::Warning,
+                    "DNS resolution may have issues".to_string(),
+                )
+            }
+        },
+    )?;
 
     // Test 8: Test direct connectivity (should show VPN IP)
-    println!("[8/10] Testing direct connectivity (should show VPN IP)...");
-    let direct_ip =
-        exec.execute_shell("docker exec openvpn-pia curl -s --max-time 10 https://api.ipify.org")?;
-    if direct_ip.status.success() {
-        let ip_output = String::from_utf8_lossy(&direct_ip.stdout)
-            .trim()
-            .to_string();
-        if !ip_output.is_empty() {
-            println!("✓ Direct connection working (Public IP: {})", ip_output);
-        } else {
-            println!("✗ Direct connection returned empty response");
-            all_passed = false;
-        }
-    } else {
-        println!("   ✗ Direct connection failed");
-        all_passed = false;
-    }
+    verifier.check(
+        8,
+        "Testing direct connectivity (should show VPN IP)",
+        "docker exec openvpn-pia curl -s --max-time 10 https://api.ipify.org",
+        |output| {
+            let ip = stdout_of(output);
+            if !output.status.success() {
+                (
+                    VpnCheckStatus::Failed,
+                    "Direct connection failed".to_string(),
+                )
+            } else if ip.is_empty() {
+                (
+                    VpnCheckStatus::Failed,
+                    "Direct connection returned empty response".to_string(),
+                )
+            } else {
+                (
+                    VpnCheckStatus::Passed,
+                    format!("Direct connection working (Public IP: {})", ip),
+                )
+            }
+        },
+    )?;
 
     // Test 9: Test proxy connectivity
-    println!("[9/10] Testing proxy connectivity...");
-    let proxy_ip = exec.execute_shell("docker exec openvpn-pia curl -s --proxy http://127.0.0.1:8888 --max-time 10 https://api.ipify.org")?;
-    if proxy_ip.status.success() {
-        let proxy_output = String::from_utf8_lossy(&proxy_ip.stdout).trim().to_string();
-        if !proxy_output.is_empty() {
-            println!("✓ Proxy connection working (Public IP: {})", proxy_output);
-        } else {
-            println!("✗ Proxy connection returned empty response");
-            all_passed = false;
-        }
-    } else {
-        println!("✗ Proxy connection failed");
-        all_passed = false;
-    }
+    verifier.check(
+        9,
+        "Testing proxy connectivity",
+        "docker exec openvpn-pia curl -s --proxy http://127.0.0.1:8888 --max-time 10 https://api.ipify.org",
+        |output| {
+            let ip = stdout_of(output);
+            if !output.status.success() {
+                (VpnCheckStatus::Failed, "Proxy connection failed".to_string())
+            } else if ip.is_empty() {
+                (
+                    VpnCheckStatus::Failed,
+                    "Proxy connection returned empty response".to_string(),
+                )
+            } else {
+                (
+                    VpnCheckStatus::Passed,
+                    format!("Proxy connection working (Public IP: {})", ip),
+                )
+            }
+        },
+    )?;
 
-    // Test 10: Test from host
-    println!("[10/10] Testing proxy from host...");
-    let host_proxy = local::execute(
-        "curl",
-        &[
-            "-s",
-            "--proxy",
-            &format!("http://{}:8888", target_host),
-            "--max-time",
-            "10",
-            "https://api.ipify.org",
-        ],
+    // Test 10: Test from this machine through the host's proxy port
+    let host_proxy = format!(
+        "curl -s --proxy {} --max-time 10 https://api.ipify.org",
+        shell_escape(&format!("http://{}:8888", target_host))
+    );
+    verifier.check_with(
+        &Executor::Local,
+        10,
+        "Testing proxy from host",
+        &host_proxy,
+        |output| {
+            let ip = stdout_of(output);
+            if !output.status.success() {
+                (
+                    VpnCheckStatus::Warning,
+                    "Host proxy connection failed (may be firewall/network issue)".to_string(),
+                )
+            } else if ip.is_empty() {
+                (
+                    VpnCheckStatus::Warning,
+                    "Host proxy returned empty response".to_string(),
+                )
+            } else {
+                (
+                    VpnCheckStatus::Passed,
+                    format!("Host proxy connection working (Public IP: {})", ip),
+                )
+            }
+        },
     )?;
-    if host_proxy.status.success() {
-        let host_output = String::from_utf8_lossy(&host_proxy.stdout)
-            .trim()
-            .to_string();
-        if !host_output.is_empty() {
-            println!(
-                "   ✓ Host proxy connection working (Public IP: {})",
-                host_output
-            );
-        } else {
-            println!("⚠ Host proxy returned empty response");
-        }
-    } else {
-        println!("⚠ Host proxy connection failed (may be firewall/network issue)");
-    }
 
-    // Check for errors in logs
+    // Check for errors in logs (best effort: skipped if the budget is gone)
     println!();
     println!("Checking for errors in logs...");
-    let error_check = exec.execute_shell("docker exec openvpn-pia cat /var/log/openvpn/openvpn.log 2>/dev/null | tail -50 | grep -iE 'error|failed|frag_in' | tail -5 || echo 'No errors found'")?;
-    vpn_utils::print_summary(hostname, target_host, all_passed, &error_check.stdout)?;
-
-    if !all_passed {
-        anyhow::bail!("VPN verification failed - some tests did not pass");
+    if let Step::Done(output) = verifier.run(
+        verifier.exec,
+        "docker exec openvpn-pia cat /var/log/openvpn/openvpn.log 2>/dev/null | tail -50 | grep -iE 'error|failed|frag_in' | tail -5",
+    )? {
+        verifier.report.log_errors = Some(
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+        );
     }
 
     Ok(())
@@ -1,23 +1,35 @@
-use anyhow::Result;
+use crate::services::pia_vpn::verify::{VpnCheckStatus, VpnVerifyReport};
 
-pub fn print_summary(
-    hostname: &str,
-    target_host: &str,
-    all_passed: bool,
-    error_output: &[u8],
-) -> Result<()> {
-    let error_output_str = String::from_utf8_lossy(error_output);
-    if error_output_str.contains("No errors found") || error_output_str.trim().is_empty() {
-        println!("   ✓ No recent errors in OpenVPN logs");
-    } else {
-        println!("   ⚠ Found potential issues in logs:");
-        for line in error_output_str.lines().take(5) {
-            if !line.trim().is_empty() && !line.contains("No errors found") {
-                println!("     - {}", line.trim());
+pub fn print_summary(report: &VpnVerifyReport) {
+    let hostname = &report.hostname;
+    let target_host = &report.target_host;
+    let all_passed = report.passed();
+
+    match &report.log_errors {
+        None => println!("   ⚠ Log check skipped (timeout)"),
+        Some(errors) if errors.is_empty() => {
+            println!("   ✓ No recent errors in OpenVPN logs")
+        }
+        Some(errors) => {
+            println!("   ⚠ Found potential issues in logs:");
+            for line in errors {
+                println!("     - {}", line);
             }
         }
     }
 
+    let timed_out: Vec<&str> = report
+        .checks
+        .iter()
+        .filter(|check| check.status == VpnCheckStatus::TimedOut)
+        .map(|check| check.name.as_str())
+        .collect();
+    let skipped = report
+        .checks
+        .iter()
+        .filter(|check| check.status == VpnCheckStatus::Skipped)
+        .count();
+
     println!();
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     if all_passed {
@@ -26,6 +38,12 @@ pub fn print_summary(
     } else {
         println!("  ⚠ VPN Verification Complete - Some Tests Failed");
     }
+    if !timed_out.is_empty() {
+        println!("  Timed out: {}", timed_out.join(", "));
+    }
+    if skipped > 0 {
+        println!("  Skipped: {} check(s) after the overall timeout", skipped);
+    }
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
     println!(
@@ -36,6 +54,9 @@ pub fn print_summary(
             "ISSUES DETECTED"
         }
     );
+    if let Some(region) = &report.region {
+        println!("Region: {}", region);
+    }
     println!();
     println!("Proxy Access:");
     println!("  From host: http://{}:8888", target_host);
@@ -46,6 +67,4 @@ pub fn print_summary(
         "  curl --proxy http://{}:8888 https://api.ipify.org",
         target_host
     );
-
-    Ok(())
 }
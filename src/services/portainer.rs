@@ -1,7 +1,204 @@
 use crate::config::EnvConfig;
 use crate::services::docker;
-use crate::utils::exec::{CommandExecutor, Executor};
+use crate::utils::exec::{CommandExecutor, DryRunExecutor, Executor};
 use anyhow::{Context, Result};
+use std::io::{self, Write};
+
+/// Environment variable holding the initial Portainer admin password
+pub const ADMIN_PASSWORD_VAR: &str = "PORTAINER_ADMIN_PASSWORD";
+/// Portainer rejects admin passwords shorter than this
+const ADMIN_PASSWORD_MIN_LEN: usize = 12;
+/// Compose file for the Portainer server on the host
+const HOST_COMPOSE_FILE: &str = "$HOME/portainer/docker-compose.yml";
+/// Password file on the host, mounted read-only into the container
+const ADMIN_PASSWORD_FILE: &str = "$HOME/portainer/admin_password";
+/// Compose override that passes the password file to Portainer
+const ADMIN_COMPOSE_FILE: &str = "$HOME/portainer/docker-compose.admin.yml";
+const ADMIN_COMPOSE_OVERRIDE: &str = "services:
+  portainer:
+    command: --admin-password-file /run/portainer_admin_password
+    volumes:
+      - ./admin_password:/run/portainer_admin_password:ro
+";
+/// Host metadata key noting that the admin password was pre-seeded
+const ADMIN_PASSWORD_METADATA_KEY: &str = "portainer_admin_password";
+
+/// Where the initial admin password came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminPasswordSource {
+    CommandLine,
+    File,
+    Env,
+    Prompt,
+}
+
+impl AdminPasswordSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminPasswordSource::CommandLine => "command line",
+            AdminPasswordSource::File => "file",
+            AdminPasswordSource::Env => ADMIN_PASSWORD_VAR,
+            AdminPasswordSource::Prompt => "prompt",
+        }
+    }
+}
+
+/// Initial admin password to seed into a fresh Portainer install
+pub struct AdminPassword {
+    password: String,
+    pub source: AdminPasswordSource,
+}
+
+impl AdminPassword {
+    /// Resolve the password from `--admin-password[=<value>]` or `--admin-password-file`
+    /// A bare `--admin-password` reads PORTAINER_ADMIN_PASSWORD, falling back to a prompt
+    pub fn resolve(value: Option<Option<&str>>, file: Option<&str>) -> Result<Option<Self>> {
+        let (password, source) = match (value, file) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!("Use either --admin-password or --admin-password-file, not both")
+            }
+            (None, None) => return Ok(None),
+            (None, Some(path)) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read admin password file: {}", path))?;
+                (
+                    content.trim_end_matches(['\r', '\n']).to_string(),
+                    AdminPasswordSource::File,
+                )
+            }
+            (Some(Some(value)), None) => {
+                println!(
+                    "⚠ Passing the admin password on the command line leaves it in your shell history and process list"
+                );
+                println!(
+                    "  Prefer {} or --admin-password-file instead",
+                    ADMIN_PASSWORD_VAR
+                );
+                (value.to_string(), AdminPasswordSource::CommandLine)
+            }
+            (Some(None), None) => match std::env::var(ADMIN_PASSWORD_VAR) {
+                Ok(value) if !value.is_empty() => (value, AdminPasswordSource::Env),
+                _ => (
+                    crate::utils::crypto::prompt_passphrase_confirmed("Portainer admin password")?,
+                    AdminPasswordSource::Prompt,
+                ),
+            },
+        };
+
+        validate_admin_password(&password)?;
+        Ok(Some(AdminPassword { password, source }))
+    }
+}
+
+/// Check a password against Portainer's requirements
+fn validate_admin_password(password: &str) -> Result<()> {
+    if password.chars().count() < ADMIN_PASSWORD_MIN_LEN {
+        anyhow::bail!(
+            "Portainer admin password must be at least {} characters",
+            ADMIN_PASSWORD_MIN_LEN
+        );
+    }
+    Ok(())
+}
+
+/// Write the password file and compose override used to seed the admin account
+fn write_admin_password<E: CommandExecutor>(exec: &E, admin: &AdminPassword) -> Result<()> {
+    // Create the file with owner-only permissions before the password goes in
+    exec.execute_shell(&format!(
+        "umask 077 && : > {0} && chmod 600 {0}",
+        ADMIN_PASSWORD_FILE
+    ))?;
+    exec.write_file(ADMIN_PASSWORD_FILE, admin.password.as_bytes())
+        .context("Failed to write Portainer admin password file")?;
+    exec.write_file(ADMIN_COMPOSE_FILE, ADMIN_COMPOSE_OVERRIDE.as_bytes())
+        .context("Failed to write Portainer admin compose override")?;
+    println!(
+        "✓ Wrote admin password to {} (mode 600)",
+        ADMIN_PASSWORD_FILE
+    );
+    Ok(())
+}
+
+/// Seconds to wait for a fresh Portainer to create the admin account from the password file
+const ADMIN_SEED_TIMEOUT_SECS: u32 = 60;
+
+/// Wait until Portainer reports an admin account, after which the password file isn't needed
+fn wait_for_admin_account<E: CommandExecutor>(exec: &E) -> bool {
+    let script = format!(
+        "for i in $(seq {}); do \
+         [ \"$(curl -sk -o /dev/null -w '%{{http_code}}' https://localhost:9443/api/users/admin/check)\" = 204 ] && exit 0; \
+         sleep 1; done; exit 1",
+        ADMIN_SEED_TIMEOUT_SECS
+    );
+    exec.execute_shell(&script)
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Delete the plaintext password file and the compose override that mounts it
+fn remove_admin_password<E: CommandExecutor>(exec: &E) -> Result<()> {
+    let output = exec.execute_shell(&format!(
+        "rm -f {} {}",
+        ADMIN_PASSWORD_FILE, ADMIN_COMPOSE_FILE
+    ))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to remove {}: {}",
+            ADMIN_PASSWORD_FILE,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    println!("✓ Removed admin password file from the host");
+    Ok(())
+}
+
+/// Start Portainer with the admin password file mounted, then, once the admin account exists,
+/// recreate the container without it and delete the file so the password doesn't stay on the host
+fn start_with_admin_password<E: CommandExecutor>(
+    exec: &E,
+    compose_cmd: &str,
+    admin: &AdminPassword,
+) -> Result<()> {
+    let compose_files = format!("-f {} -f {}", HOST_COMPOSE_FILE, ADMIN_COMPOSE_FILE);
+    let started = write_admin_password(exec, admin).and_then(|()| {
+        exec.execute_shell_interactive(&format!(
+            "cd $HOME/portainer && {} {} down 2>/dev/null || true && {} {} up -d",
+            compose_cmd, compose_files, compose_cmd, compose_files
+        ))
+    });
+    let seeded = started.is_ok() && wait_for_admin_account(exec);
+    if started.is_ok() {
+        exec.execute_shell_interactive(&format!(
+            "cd $HOME/portainer && {} -f {} up -d",
+            compose_cmd, HOST_COMPOSE_FILE
+        ))?;
+    }
+    remove_admin_password(exec)?;
+    started?;
+
+    if seeded {
+        println!(
+            "✓ Admin password set (Portainer ignores it if the data volume already has an admin)"
+        );
+    } else {
+        println!(
+            "⚠ Portainer didn't report an admin account within {}s; set the password in the web UI",
+            ADMIN_SEED_TIMEOUT_SECS
+        );
+    }
+    Ok(())
+}
+
+/// Note in the host's record that the admin password was pre-seeded (never the password itself)
+fn record_admin_password_note(hostname: &str, source: AdminPasswordSource) -> Result<()> {
+    crate::services::host::set_host_metadata(
+        hostname,
+        ADMIN_PASSWORD_METADATA_KEY,
+        serde_json::json!({
+            "seeded_at": chrono::Utc::now().to_rfc3339(),
+            "source": source.as_str(),
+        }),
+    )
+}
 
 /// Portainer edition type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -32,14 +229,59 @@ impl PortainerEdition {
             PortainerEdition::Be => "Business Edition",
         }
     }
+
+    /// Determine the edition from a Portainer server image name
+    /// Returns None for images that aren't a Portainer server (e.g. the agent)
+    pub fn from_image(image: &str) -> Option<Self> {
+        let repo = image.split(':').next().unwrap_or(image);
+        match repo.rsplit('/').next().unwrap_or(repo) {
+            "portainer-ee" | "portainer-be" => Some(PortainerEdition::Be),
+            "portainer-ce" | "portainer" => Some(PortainerEdition::Ce),
+            _ => None,
+        }
+    }
 }
 
-/// Install Portainer host (CE or BE)
-pub fn install_host<E: CommandExecutor>(exec: &E, edition: PortainerEdition) -> Result<()> {
-    println!();
-    println!("=== Installing Portainer {} ===", edition.display_name());
+/// Detect the edition of an existing Portainer server container, if any
+pub fn detect_installed_edition<E: CommandExecutor>(exec: &E) -> Result<Option<PortainerEdition>> {
+    let containers = docker::list_containers(exec).unwrap_or_default();
+    if !containers.iter().any(|c| c == "portainer") {
+        return Ok(None);
+    }
 
-    // Remove existing containers
+    let output = exec.execute_simple(
+        "docker",
+        &["inspect", "portainer", "--format", "{{.Config.Image}}"],
+    )?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let image = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(PortainerEdition::from_image(&image))
+}
+
+/// Host ports published by the Portainer server
+pub const HOST_PORTS: &[u16] = &[9443, 9000, 8000];
+/// Host ports published by the Portainer agent
+pub const AGENT_PORTS: &[u16] = &[9001];
+
+/// Make sure the ports Portainer needs are free, ignoring ones held by the Portainer
+/// containers that are about to be replaced
+pub fn check_ports<E: CommandExecutor>(
+    exec: &E,
+    ports: &[u16],
+    ignore_conflicts: bool,
+) -> Result<()> {
+    docker::check_ports(
+        exec,
+        ports,
+        |name| name == "portainer" || name == "portainer_agent",
+        ignore_conflicts,
+    )
+}
+
+/// Remove existing Portainer containers, leaving the data volume in place
+fn remove_existing_containers<E: CommandExecutor>(exec: &E) {
     println!("Removing any existing Portainer instances...");
 
     // Check and stop/remove portainer using docker module
@@ -52,28 +294,51 @@ pub fn install_host<E: CommandExecutor>(exec: &E, edition: PortainerEdition) ->
     }
 
     println!("✓ Removed existing Portainer containers");
+}
 
-    // Start Portainer
-    exec.mkdir_p("$HOME/portainer")?;
+/// Install Portainer host (CE or BE)
+/// When `force` is set, an existing install of the other edition is replaced
+/// When `admin` is set, it becomes the admin password of a fresh install
+/// Fails if another service holds one of HOST_PORTS, unless `ignore_port_conflicts` is set
+pub fn install_host<E: CommandExecutor>(
+    exec: &E,
+    edition: PortainerEdition,
+    force: bool,
+    admin: Option<&AdminPassword>,
+    ignore_port_conflicts: bool,
+) -> Result<()> {
+    println!();
+    println!("=== Installing Portainer {} ===", edition.display_name());
 
-    // Verify compose file exists
-    let compose_file_path = "$HOME/portainer/docker-compose.yml";
-    if !exec.file_exists(compose_file_path)? {
-        anyhow::bail!(
-            "Docker compose file not found at {}. Please ensure the compose file has been copied.",
-            compose_file_path
-        );
+    // Refuse to switch editions on top of an existing install unless forced
+    if let Some(installed) = detect_installed_edition(exec)? {
+        println!("Detected Portainer {}", installed.display_name());
+        if installed != edition && !force {
+            anyhow::bail!(
+                "Portainer {} is already installed. Re-run with --force to replace it with {} (the portainer_data volume is kept)",
+                installed.display_name(),
+                edition.display_name()
+            );
+        }
     }
+    check_ports(exec, HOST_PORTS, ignore_port_conflicts)?;
+
+    // Only touch the host once every check has passed
+    copy_compose_file(exec, edition.compose_file())?;
+
+    // Remove existing containers
+    remove_existing_containers(exec);
 
     // Get docker compose command from docker module
     let compose_cmd = docker::get_compose_command(exec)?;
 
-    // Use explicit -f flag to specify the compose file with full path
-    let compose_file_path = "$HOME/portainer/docker-compose.yml";
-    exec.execute_shell_interactive(&format!(
-        "cd $HOME/portainer && {} -f {} down 2>/dev/null || true && {} -f {} up -d",
-        compose_cmd, compose_file_path, compose_cmd, compose_file_path
-    ))?;
+    match admin {
+        Some(admin) => start_with_admin_password(exec, &compose_cmd, admin)?,
+        None => exec.execute_shell_interactive(&format!(
+            "cd $HOME/portainer && {} -f {} down 2>/dev/null || true && {} -f {} up -d",
+            compose_cmd, HOST_COMPOSE_FILE, compose_cmd, HOST_COMPOSE_FILE
+        ))?,
+    }
 
     println!(
         "✓ Portainer {} installed and running",
@@ -84,38 +349,36 @@ pub fn install_host<E: CommandExecutor>(exec: &E, edition: PortainerEdition) ->
 }
 
 /// Install Portainer Agent
-pub fn install_agent<E: CommandExecutor>(exec: &E) -> Result<()> {
+/// When `force` is set, an existing Portainer server is replaced
+/// Fails if another service holds one of AGENT_PORTS, unless `ignore_port_conflicts` is set
+pub fn install_agent<E: CommandExecutor>(
+    exec: &E,
+    force: bool,
+    ignore_port_conflicts: bool,
+) -> Result<()> {
     println!();
     println!("=== Installing Portainer Agent ===");
 
     // Ensure Docker daemon is running
     docker::ensure_docker_running(exec)?;
 
-    // Remove existing containers
-    println!("Removing any existing Portainer instances...");
-
-    // Check and stop/remove portainer containers using docker module
-    if let Ok(containers) = docker::list_containers(exec) {
-        for container in &containers {
-            if container == "portainer" || container == "portainer_agent" {
-                docker::stop_and_remove_container(exec, container).ok();
-            }
+    // Don't replace a Portainer server with an agent unless forced
+    if let Some(installed) = detect_installed_edition(exec)? {
+        println!("Detected Portainer {}", installed.display_name());
+        if !force {
+            anyhow::bail!(
+                "Portainer {} is already installed on this host. Re-run with --force to replace it with the agent (the portainer_data volume is kept)",
+                installed.display_name()
+            );
         }
     }
+    check_ports(exec, AGENT_PORTS, ignore_port_conflicts)?;
 
-    println!("✓ Removed existing Portainer containers");
-
-    // Start Portainer Agent
-    exec.mkdir_p("$HOME/portainer")?;
+    // Only touch the host once every check has passed
+    copy_compose_file(exec, AGENT_COMPOSE_FILE)?;
 
-    // Verify compose file exists
-    let compose_file_path = "$HOME/portainer/docker-compose.yml";
-    if !exec.file_exists(compose_file_path)? {
-        anyhow::bail!(
-            "Docker compose file not found at {}. Please ensure the compose file has been copied.",
-            compose_file_path
-        );
-    }
+    // Remove existing containers
+    remove_existing_containers(exec);
 
     // Get docker compose command from docker module
     let compose_cmd = docker::get_compose_command(exec)?;
@@ -132,9 +395,12 @@ pub fn install_agent<E: CommandExecutor>(exec: &E) -> Result<()> {
     Ok(())
 }
 
-/// Copy Portainer compose file to remote host
-/// This function is used by provision module and expects an Executor
-pub fn copy_compose_file<E: CommandExecutor>(exec: &E, compose_filename: &str) -> Result<()> {
+/// Compose file for the Portainer agent, in the homelab `compose/` directory
+const AGENT_COMPOSE_FILE: &str = "portainer-agent.docker-compose.yml";
+
+/// Copy Portainer compose file to the host (`install_host` and `install_agent` do this after
+/// their checks pass)
+fn copy_compose_file<E: CommandExecutor>(exec: &E, compose_filename: &str) -> Result<()> {
     // Find the homelab directory to locate the compose file
     let homelab_dir = crate::config::find_homelab_dir()?;
     let compose_file = homelab_dir.join("compose").join(compose_filename);
@@ -174,12 +440,20 @@ pub fn copy_compose_file<E: CommandExecutor>(exec: &E, compose_filename: &str) -
 }
 
 /// Install Portainer host on a host (public API for CLI)
-pub fn install_portainer_host(hostname: &str, edition: &str, config: &EnvConfig) -> Result<()> {
+pub fn install_portainer_host(
+    hostname: &str,
+    edition: &str,
+    force: bool,
+    admin: Option<&AdminPassword>,
+    ignore_port_conflicts: bool,
+    dry_run: bool,
+    config: &EnvConfig,
+) -> Result<()> {
     let edition_enum = PortainerEdition::from_str(edition)
         .with_context(|| format!("Invalid portainer edition: {}", edition))?;
 
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
     if is_local {
@@ -197,11 +471,22 @@ pub fn install_portainer_host(hostname: &str, edition: &str, config: &EnvConfig)
         );
     }
 
-    // Copy compose file (needed for both local and remote)
-    copy_compose_file(&exec, edition_enum.compose_file())?;
-    println!();
+    if dry_run {
+        let dry_exec = DryRunExecutor::new(&exec, hostname);
+        install_host(&dry_exec, edition_enum, force, admin, ignore_port_conflicts)?;
+        println!();
+        println!(
+            "✓ Portainer {} installation dry run complete for {}",
+            edition_enum.display_name(),
+            hostname
+        );
+        return Ok(());
+    }
 
-    install_host(&exec, edition_enum)?;
+    install_host(&exec, edition_enum, force, admin, ignore_port_conflicts)?;
+    if let Some(admin) = admin {
+        record_admin_password_note(hostname, admin.source)?;
+    }
 
     println!();
     println!(
@@ -214,14 +499,21 @@ pub fn install_portainer_host(hostname: &str, edition: &str, config: &EnvConfig)
 }
 
 /// Install Portainer Agent on a host (public API for CLI)
-pub fn install_portainer_agent(hostname: &str, edition: &str, config: &EnvConfig) -> Result<()> {
+pub fn install_portainer_agent(
+    hostname: &str,
+    edition: &str,
+    force: bool,
+    ignore_port_conflicts: bool,
+    dry_run: bool,
+    config: &EnvConfig,
+) -> Result<()> {
     // For agent, edition is currently not used (agent doesn't have CE/BE distinction in the same way)
     // But we accept it for consistency and future use
     let _edition_enum = PortainerEdition::from_str(edition)
         .with_context(|| format!("Invalid portainer edition: {}", edition))?;
 
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
     if is_local {
@@ -233,14 +525,123 @@ pub fn install_portainer_agent(hostname: &str, edition: &str, config: &EnvConfig
         );
     }
 
-    // Copy compose file (needed for both local and remote)
-    copy_compose_file(&exec, "portainer-agent.docker-compose.yml")?;
-    println!();
+    if dry_run {
+        install_agent(
+            &DryRunExecutor::new(&exec, hostname),
+            force,
+            ignore_port_conflicts,
+        )?;
+        println!();
+        println!(
+            "✓ Portainer Agent installation dry run complete for {}",
+            hostname
+        );
+        return Ok(());
+    }
 
-    install_agent(&exec)?;
+    install_agent(&exec, force, ignore_port_conflicts)?;
 
     println!();
     println!("✓ Portainer Agent installation complete for {}", hostname);
 
     Ok(())
 }
+
+/// Uninstall Portainer (server or agent) from a host
+/// Named volumes are removed only when `purge_volumes` is set; bind-mounted host paths are never touched
+pub fn uninstall_portainer(
+    hostname: &str,
+    purge_volumes: bool,
+    yes: bool,
+    config: &EnvConfig,
+) -> Result<()> {
+    let exec = Executor::new(hostname, config)?;
+
+    let containers: Vec<String> = docker::list_containers(&exec)?
+        .into_iter()
+        .filter(|c| c == "portainer" || c == "portainer_agent")
+        .collect();
+    if containers.is_empty() {
+        println!("No Portainer containers found on {}", hostname);
+        return Ok(());
+    }
+
+    // Work out which data lives where before removing anything
+    let existing_volumes = docker::list_volumes(&exec)?;
+    let mut volumes = Vec::new();
+    let mut bind_mounts = Vec::new();
+    for container in &containers {
+        for volume in docker::get_volume_mounts(&exec, container)? {
+            if existing_volumes.contains(&volume) && !volumes.contains(&volume) {
+                volumes.push(volume);
+            }
+        }
+        for mount in docker::get_bind_mounts(&exec, container)? {
+            if !bind_mounts.contains(&mount) {
+                bind_mounts.push(mount);
+            }
+        }
+    }
+
+    println!("Uninstalling Portainer from {}", hostname);
+    println!();
+    println!("Containers to remove:");
+    for container in &containers {
+        println!("  - {}", container);
+    }
+    if !volumes.is_empty() {
+        if purge_volumes {
+            println!("Volumes to DELETE (data will be lost):");
+        } else {
+            println!("Volumes to keep:");
+        }
+        for volume in &volumes {
+            println!("  - {}", volume);
+        }
+    }
+    if !bind_mounts.is_empty() {
+        println!("Host paths to keep (bind mounts are never deleted):");
+        for mount in &bind_mounts {
+            println!("  - {}", mount);
+        }
+    }
+    println!();
+
+    if !yes {
+        if purge_volumes && !volumes.is_empty() {
+            println!(
+                "⚠ {} volume{} will be permanently deleted",
+                volumes.len(),
+                if volumes.len() == 1 { "" } else { "s" }
+            );
+        }
+        print!("Uninstall Portainer? [y/N]: ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let response = input.trim().to_lowercase();
+        if response != "y" && response != "yes" {
+            println!("Cancelled - no changes were made.");
+            return Ok(());
+        }
+    }
+
+    for container in &containers {
+        docker::stop_and_remove_container(&exec, container)?;
+        println!("✓ Removed container {}", container);
+    }
+
+    if purge_volumes {
+        for volume in &volumes {
+            docker::remove_volume(&exec, volume)?;
+            println!("✓ Deleted volume {}", volume);
+        }
+    } else if !volumes.is_empty() {
+        println!(
+            "✓ Kept {} volume(s); remove them later with --purge-volumes",
+            volumes.len()
+        );
+    }
+
+    Ok(())
+}
@@ -1,17 +1,35 @@
 use crate::config::EnvConfig;
 use crate::services::docker;
-use crate::services::portainer::{
-    PortainerEdition, copy_compose_file, install_agent, install_host,
-};
+use crate::services::portainer::{self, PortainerEdition, install_agent, install_host};
 use crate::services::tailscale;
-use crate::utils::exec::{CommandExecutor, Executor};
+use crate::utils::exec::{CommandExecutor, DryRunExecutor, Executor};
+use crate::utils::progress::Steps;
 use anyhow::{Context, Result};
+use log::{debug, error, info, warn};
+use std::time::Duration;
+
+/// Number of times to retry the initial SSH connectivity check on flaky links
+const CONNECT_RETRIES: u32 = 4;
+/// Delay before the first connectivity retry (doubles on each attempt)
+const CONNECT_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Components installed during a provisioning run
+#[derive(Debug, Default)]
+struct ProvisionedComponents {
+    docker: bool,
+    tailscale: bool,
+    portainer: bool,
+}
 
 /// Main entry point for provisioning a host
+/// When `dry_run` is set, commands are printed instead of executed
 pub fn provision_host(
     hostname: &str,
     portainer_host: bool,
     portainer_edition: &str,
+    tailscale_tags: &[String],
+    ignore_port_conflicts: bool,
+    dry_run: bool,
     config: &EnvConfig,
 ) -> Result<()> {
     let edition = if portainer_host {
@@ -26,55 +44,148 @@ pub fn provision_host(
     let exec = Executor::new(hostname, config)?;
 
     // Get target host for display and Portainer compose file copying
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
+    if dry_run {
+        info!("Dry run: no changes will be made");
+    }
     if is_local {
-        println!("Detected local execution on {}", hostname);
+        debug!("Detected local execution on {}", hostname);
     } else {
-        println!("Detected remote execution - provisioning via SSH");
-        println!("Provisioning {} ({})...", hostname, target_host);
+        debug!("Detected remote execution - provisioning via SSH");
+        info!("Provisioning {} ({})...", hostname, target_host);
     }
-    println!();
+    info!("");
 
-    // Copy Portainer compose file (needed for both local and remote)
-    if portainer_host {
-        copy_compose_file(&exec, edition.compose_file())?;
-    } else {
-        copy_compose_file(&exec, "portainer-agent.docker-compose.yml")?;
+    // Verify the host is reachable before starting, retrying transient SSH failures
+    if let Executor::Remote(ssh) = &exec {
+        let output = ssh.execute_simple_retry("true", &[], CONNECT_RETRIES, CONNECT_BACKOFF)?;
+        if crate::utils::ssh::is_connection_failure(&output) {
+            anyhow::bail!(
+                "Could not connect to {} ({}): {}",
+                hostname,
+                target_host,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+
+    if dry_run {
+        let dry_exec = DryRunExecutor::new(&exec, hostname);
+        run_provision_steps(
+            &dry_exec,
+            portainer_host,
+            edition,
+            tailscale_tags,
+            ignore_port_conflicts,
+            is_local,
+            &mut ProvisionedComponents::default(),
+        )?;
+        info!("");
+        info!("✓ Provisioning dry run complete for {}", hostname);
+        return Ok(());
+    }
+
+    // Record what the host runs before changing it, so failed runs still leave an inventory
+    match crate::services::host::collect_inventory(&exec).and_then(|inventory| {
+        crate::services::host::record_host_inventory(hostname, &inventory)?;
+        Ok(inventory)
+    }) {
+        Ok(inventory) => debug!("Inventory: {}", inventory.display_lines().join(", ")),
+        Err(e) => warn!("⚠ Failed to record host inventory: {}", e),
+    }
+
+    let mut installed = ProvisionedComponents::default();
+    let result = run_provision_steps(
+        &exec,
+        portainer_host,
+        edition,
+        tailscale_tags,
+        ignore_port_conflicts,
+        is_local,
+        &mut installed,
+    );
+    let error = result.as_ref().err().map(|e| format!("{:#}", e));
+    if let Err(e) = crate::db::record_provision(
+        hostname,
+        installed.docker,
+        installed.tailscale,
+        installed.portainer,
+        error.as_deref(),
+    ) {
+        warn!("⚠ Failed to record provisioning history: {}", e);
+    }
+    result?;
+    if !tailscale_tags.is_empty() {
+        tailscale::record_tailscale_tags(hostname, tailscale_tags)?;
     }
 
+    info!("");
+    info!("✓ Provisioning complete for {}", hostname);
+
+    Ok(())
+}
+
+/// Run every provisioning step against an executor
+fn run_provision_steps<E: CommandExecutor>(
+    exec: &E,
+    portainer_host: bool,
+    edition: PortainerEdition,
+    tailscale_tags: &[String],
+    ignore_port_conflicts: bool,
+    is_local: bool,
+    installed: &mut ProvisionedComponents,
+) -> Result<()> {
+    let mut steps = Steps::new(5);
+
     // Execute provisioning steps using the executor
-    check_sudo_access(&exec, !is_local)?;
+    steps.next("Checking sudo access");
+    check_sudo_access(exec, !is_local)?;
+
+    // Catch port conflicts before spending time on Docker and Tailscale
+    steps.next("Checking Portainer ports");
+    let portainer_ports = if portainer_host {
+        portainer::HOST_PORTS
+    } else {
+        portainer::AGENT_PORTS
+    };
+    portainer::check_ports(exec, portainer_ports, ignore_port_conflicts)?;
 
     // Install Docker
-    docker::check_and_install(&exec)?;
-    docker::configure_permissions(&exec)?;
-    docker::configure_ipv6(&exec)?;
+    steps.next("Installing Docker");
+    docker::check_and_install(exec)?;
+    docker::configure_permissions(exec)?;
+    docker::configure_ipv6(exec)?;
+    installed.docker = true;
 
     // Install Tailscale
-    tailscale::check_and_install_remote(&exec)?;
+    steps.next("Installing Tailscale");
+    tailscale::check_and_install_remote(exec)?;
+    if !tailscale_tags.is_empty() {
+        tailscale::apply_tailscale_tags(exec, tailscale_tags)?;
+    }
+    installed.tailscale = true;
 
     // Install Portainer
+    steps.next("Installing Portainer");
     if portainer_host {
-        install_host(&exec, edition)?;
+        install_host(exec, edition, false, None, ignore_port_conflicts)?;
     } else {
         // For agent, we use CE edition (agent doesn't have separate editions currently)
-        install_agent(&exec)?;
+        install_agent(exec, false, ignore_port_conflicts)?;
     }
-
-    println!();
-    println!("✓ Provisioning complete for {}", hostname);
+    installed.portainer = true;
 
     Ok(())
 }
 
 /// Check sudo access (works for both local and remote)
 pub fn check_sudo_access<E: CommandExecutor>(exec: &E, is_remote: bool) -> Result<()> {
-    println!("=== Checking sudo access ===");
+    info!("=== Checking sudo access ===");
 
     if !exec.is_linux()? {
-        println!("✓ macOS detected (Docker Desktop handles permissions)");
+        info!("✓ macOS detected (Docker Desktop handles permissions)");
         return Ok(());
     }
 
@@ -84,29 +195,29 @@ pub fn check_sudo_access<E: CommandExecutor>(exec: &E, is_remote: bool) -> Resul
         // Remote execution: check for passwordless sudo
         let output = exec.execute_simple("sudo", &["-n", "true"])?;
         if !output.status.success() {
-            println!("Error: Passwordless sudo is required for remote provisioning.");
-            println!();
-            println!("To configure passwordless sudo, run on the target host:");
-            println!("  sudo visudo");
-            println!();
-            println!("Then add this line (replace USERNAME with your username):");
-            println!("  USERNAME ALL=(ALL) NOPASSWD: ALL");
-            println!();
-            println!("Or for more security, limit to specific commands:");
-            println!(
+            error!("Error: Passwordless sudo is required for remote provisioning.");
+            error!("");
+            error!("To configure passwordless sudo, run on the target host:");
+            error!("  sudo visudo");
+            error!("");
+            error!("Then add this line (replace USERNAME with your username):");
+            error!("  USERNAME ALL=(ALL) NOPASSWD: ALL");
+            error!("");
+            error!("Or for more security, limit to specific commands:");
+            error!(
                 "  USERNAME ALL=(ALL) NOPASSWD: /usr/bin/docker, /bin/systemctl, /usr/sbin/usermod, /bin/mkdir, /bin/tee, /bin/cp, /bin/mv, /bin/rm, /usr/bin/python3"
             );
-            println!();
+            error!("");
             anyhow::bail!("Passwordless sudo not configured");
         }
-        println!("✓ Passwordless sudo configured");
+        info!("✓ Passwordless sudo configured");
     } else {
         // Local execution: use interactive mode to prompt for password if needed
-        println!("Testing sudo access (you may be prompted for your password)...");
+        info!("Testing sudo access (you may be prompted for your password)...");
         // Use a simple command that requires sudo to test access
         // This will prompt for password if needed
         exec.execute_interactive("sudo", &["sh", "-c", "true"])?;
-        println!("✓ Sudo access verified");
+        info!("✓ Sudo access verified");
     }
 
     Ok(())
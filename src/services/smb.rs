@@ -1,11 +1,12 @@
 use crate::config::EnvConfig;
-use crate::utils::exec::{CommandExecutor, Executor};
+use crate::utils::exec::{CommandExecutor, DryRunExecutor, Executor};
 use anyhow::Result;
 
-pub fn setup_smb_mounts(hostname: &str, config: &EnvConfig) -> Result<()> {
+/// When `dry_run` is set, commands are printed instead of executed
+pub fn setup_smb_mounts(hostname: &str, dry_run: bool, config: &EnvConfig) -> Result<()> {
     // Create executor - it automatically determines if execution should be local or remote
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
     if is_local {
@@ -15,6 +16,13 @@ pub fn setup_smb_mounts(hostname: &str, config: &EnvConfig) -> Result<()> {
     }
     println!();
 
+    if dry_run {
+        setup_smb_mounts_remote(&DryRunExecutor::new(&exec, hostname), config)?;
+        println!();
+        println!("✓ SMB mount setup dry run complete for {}", hostname);
+        return Ok(());
+    }
+
     // Execute setup using Rust-native operations
     setup_smb_mounts_remote(&exec, config)?;
 
@@ -27,7 +35,7 @@ pub fn setup_smb_mounts(hostname: &str, config: &EnvConfig) -> Result<()> {
 pub fn uninstall_smb_mounts(hostname: &str, config: &EnvConfig) -> Result<()> {
     // Create executor - it automatically determines if execution should be local or remote
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
     if is_local {
@@ -49,6 +57,347 @@ pub fn uninstall_smb_mounts(hostname: &str, config: &EnvConfig) -> Result<()> {
     Ok(())
 }
 
+/// Mount state of a configured SMB share
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareState {
+    /// Mounted and a test file could be written
+    Writable,
+    /// Mounted but the write test failed
+    ReadOnly,
+    /// Mount point exists but nothing is mounted on it
+    NotMounted,
+    /// Mount point directory does not exist
+    MissingMountPoint,
+}
+
+impl ShareState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShareState::Writable => "mounted (writable)",
+            ShareState::ReadOnly => "mounted (not writable)",
+            ShareState::NotMounted => "not mounted",
+            ShareState::MissingMountPoint => "mount point missing",
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        *self == ShareState::Writable
+    }
+}
+
+/// Status of a single configured SMB share
+#[derive(Debug, Clone)]
+pub struct ShareStatus {
+    pub server: String,
+    pub share: String,
+    pub mount_point: String,
+    pub state: ShareState,
+}
+
+/// Mount point used for a share: `/mnt/smb/{server}/{share}`
+pub fn smb_mount_point(server_name: &str, share_name: &str) -> String {
+    format!("/mnt/smb/{}/{}", server_name, share_name)
+}
+
+/// Directory holding per-server SMB credentials files
+pub const SMB_CREDENTIALS_DIR: &str = "/etc/halvor/smb";
+
+/// Credentials file used for a server: `/etc/halvor/smb/{server}.credentials`
+pub fn smb_credentials_path(server_name: &str) -> String {
+    format!("{}/{}.credentials", SMB_CREDENTIALS_DIR, server_name)
+}
+
+/// Render the contents of a mount.cifs credentials file
+pub fn render_smb_credentials(username: &str, password: &str) -> String {
+    format!("username={}\npassword={}\n", username, password)
+}
+
+/// Install a root-owned (mode 600) credentials file for a server and return its path
+/// The password never appears on a command line: it is written to a private temp file and
+/// moved into place with `install`
+pub fn install_smb_credentials<E: CommandExecutor>(
+    exec: &E,
+    server_name: &str,
+    username: &str,
+    password: &str,
+) -> Result<String> {
+    let path = smb_credentials_path(server_name);
+
+    let mktemp = exec.execute_shell("umask 077 && mktemp")?;
+    if !mktemp.status.success() {
+        anyhow::bail!("Failed to create temporary credentials file");
+    }
+    let temp_path = String::from_utf8_lossy(&mktemp.stdout).trim().to_string();
+
+    let result = exec
+        .write_file(
+            &temp_path,
+            render_smb_credentials(username, password).as_bytes(),
+        )
+        .and_then(|_| {
+            exec.execute_simple("sudo", &["mkdir", "-p", SMB_CREDENTIALS_DIR])?;
+            let install = exec.execute_simple(
+                "sudo",
+                &[
+                    "install", "-m", "600", "-o", "root", "-g", "root", &temp_path, &path,
+                ],
+            )?;
+            if !install.status.success() {
+                anyhow::bail!("Failed to install SMB credentials file: {}", path);
+            }
+            Ok(())
+        });
+    exec.execute_simple("rm", &["-f", &temp_path]).ok();
+    result?;
+
+    println!("✓ Credentials for {} stored in {}", server_name, path);
+    Ok(path)
+}
+
+/// Remove a server's credentials file if present
+fn remove_smb_credentials<E: CommandExecutor>(exec: &E, server_name: &str) -> Result<()> {
+    let path = smb_credentials_path(server_name);
+    let output = exec.execute_simple("sudo", &["rm", "-f", &path])?;
+    if output.status.success() {
+        println!("✓ Removed credentials file {}", path);
+    } else {
+        println!("✗ Failed to remove credentials file {}", path);
+    }
+    Ok(())
+}
+
+/// Paths that currently have something mounted on them
+/// Reads /proc/mounts, falling back to `mount` output where /proc is unavailable
+fn mounted_paths<E: CommandExecutor>(exec: &E) -> Result<Vec<String>> {
+    if let Ok(content) = exec.read_file("/proc/mounts") {
+        // Format: <source> <mountpoint> <type> <options> 0 0 (spaces escaped as \040)
+        return Ok(content
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .map(|path| path.replace("\\040", " "))
+            .collect());
+    }
+
+    let output = exec.execute_simple("mount", &[])?;
+    if !output.status.success() {
+        anyhow::bail!("Failed to read mounted filesystems");
+    }
+    // Format: <source> on <mountpoint> type <type> (<options>)
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (path, _) = rest
+                .rsplit_once(" type ")
+                .or_else(|| rest.split_once(" ("))?;
+            Some(path.to_string())
+        })
+        .collect())
+}
+
+/// Check that a test file can be created and removed under `mount_point`
+fn is_writable<E: CommandExecutor>(exec: &E, mount_point: &str) -> Result<bool> {
+    let test_file = format!("{}/.halvor-write-test-{}", mount_point, std::process::id());
+    let output = exec.execute_shell(&format!("touch '{}' && rm -f '{}'", test_file, test_file))?;
+    Ok(output.status.success())
+}
+
+/// Determine the state of every configured SMB share on the target host
+pub fn smb_share_statuses<E: CommandExecutor>(
+    exec: &E,
+    config: &EnvConfig,
+) -> Result<Vec<ShareStatus>> {
+    let mounted = mounted_paths(exec)?;
+
+    let mut servers: Vec<_> = config.smb_servers.iter().collect();
+    servers.sort_by_key(|(name, _)| name.as_str());
+
+    let mut statuses = Vec::new();
+    for (server_name, server_config) in servers {
+        for share_name in &server_config.shares {
+            let mount_point = smb_mount_point(server_name, share_name);
+            let state = if mounted.contains(&mount_point) {
+                if is_writable(exec, &mount_point)? {
+                    ShareState::Writable
+                } else {
+                    ShareState::ReadOnly
+                }
+            } else if exec.is_directory(&mount_point)? {
+                ShareState::NotMounted
+            } else {
+                ShareState::MissingMountPoint
+            };
+
+            statuses.push(ShareStatus {
+                server: server_name.clone(),
+                share: share_name.clone(),
+                mount_point,
+                state,
+            });
+        }
+    }
+
+    Ok(statuses)
+}
+
+/// Print mount health for every configured SMB share on a host
+pub fn show_smb_status(hostname: &str, config: &EnvConfig) -> Result<()> {
+    let exec = Executor::new(hostname, config)?;
+
+    println!("=== SMB Mount Status: {} ===", hostname);
+    println!();
+
+    if config.smb_servers.is_empty() {
+        println!("No SMB servers configured in .env");
+        return Ok(());
+    }
+
+    let statuses = smb_share_statuses(&exec, config)?;
+    let server_width = statuses.iter().map(|s| s.server.len()).max().unwrap_or(0);
+    let share_width = statuses.iter().map(|s| s.share.len()).max().unwrap_or(0);
+    let mount_width = statuses
+        .iter()
+        .map(|s| s.mount_point.len())
+        .max()
+        .unwrap_or(0);
+
+    for status in &statuses {
+        let marker = match status.state {
+            ShareState::Writable => "✓",
+            ShareState::ReadOnly => "⚠",
+            ShareState::NotMounted | ShareState::MissingMountPoint => "✗",
+        };
+        println!(
+            "  {} {:<sw$}  {:<shw$}  {:<mw$}  {}",
+            marker,
+            status.server,
+            status.share,
+            status.mount_point,
+            status.state.as_str(),
+            sw = server_width,
+            shw = share_width,
+            mw = mount_width
+        );
+    }
+
+    let unhealthy = statuses.iter().filter(|s| !s.state.is_healthy()).count();
+    println!();
+    if unhealthy == 0 {
+        println!("✓ All {} share(s) mounted and writable", statuses.len());
+    } else {
+        println!(
+            "⚠ {} of {} share(s) need attention (run `hal smb` to mount them)",
+            unhealthy,
+            statuses.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Mount configured shares that are not currently mounted, reporting what was fixed
+/// With `force`, every share is unmounted and remounted (recovers from stale handles)
+pub fn remount_smb_shares(hostname: &str, force: bool, config: &EnvConfig) -> Result<()> {
+    let exec = Executor::new(hostname, config)?;
+
+    println!("=== Remounting SMB shares on {} ===", hostname);
+    println!();
+
+    if config.smb_servers.is_empty() {
+        println!("No SMB servers configured in .env");
+        return Ok(());
+    }
+
+    let statuses = smb_share_statuses(&exec, config)?;
+    let needs_mount: Vec<&ShareStatus> = statuses
+        .iter()
+        .filter(|s| {
+            force
+                || matches!(
+                    s.state,
+                    ShareState::NotMounted | ShareState::MissingMountPoint
+                )
+        })
+        .collect();
+
+    for status in &statuses {
+        if status.state == ShareState::ReadOnly && !force {
+            println!(
+                "⚠ {} - {} is mounted but not writable (use --force to remount it)",
+                status.server, status.share
+            );
+        } else if status.state.is_healthy() && !force {
+            println!(
+                "✓ {} - {} is healthy, skipping",
+                status.server, status.share
+            );
+        }
+    }
+
+    if needs_mount.is_empty() {
+        println!();
+        println!("✓ Nothing to remount on {}", hostname);
+        return Ok(());
+    }
+
+    install_smb_client(&exec)?;
+
+    let mut fixed = Vec::new();
+    let mut failed = Vec::new();
+    for status in needs_mount {
+        let server_config = &config.smb_servers[&status.server];
+
+        if matches!(status.state, ShareState::Writable | ShareState::ReadOnly) {
+            println!("Unmounting {}...", status.mount_point);
+            let umount = exec.execute_simple("sudo", &["umount", &status.mount_point])?;
+            if !umount.status.success() {
+                // Lazy unmount detaches mounts whose server has gone away
+                exec.execute_simple("sudo", &["umount", "-l", &status.mount_point])?;
+            }
+        }
+
+        let share_path = format!("//{}/{}", server_config.host, status.share);
+        match setup_smb_share(
+            &exec,
+            &status.server,
+            &status.share,
+            &share_path,
+            &status.mount_point,
+            server_config,
+        ) {
+            Ok(()) => fixed.push(status),
+            Err(e) => {
+                println!("✗ {}", e);
+                failed.push(status);
+            }
+        }
+    }
+
+    println!();
+    println!("=== Remount summary ===");
+    for status in &fixed {
+        println!(
+            "  ✓ {} - {} remounted at {} (was {})",
+            status.server,
+            status.share,
+            status.mount_point,
+            status.state.as_str()
+        );
+    }
+    for status in &failed {
+        println!(
+            "  ✗ {} - {} could not be mounted at {}",
+            status.server, status.share, status.mount_point
+        );
+    }
+
+    if !failed.is_empty() {
+        anyhow::bail!("Failed to remount {} SMB share(s)", failed.len());
+    }
+
+    Ok(())
+}
+
 fn setup_smb_mounts_remote<E: CommandExecutor>(exec: &E, config: &EnvConfig) -> Result<()> {
     println!("=== SMB Configuration ===");
     println!("Configuration loaded from .env file");
@@ -66,7 +415,11 @@ fn setup_smb_mounts_remote<E: CommandExecutor>(exec: &E, config: &EnvConfig) ->
             server_config.shares.len()
         );
         for share in &server_config.shares {
-            println!("    └─ {} -> /mnt/smb/{}/{}", share, server_name, share);
+            println!(
+                "    └─ {} -> {}",
+                share,
+                smb_mount_point(server_name, share)
+            );
         }
     }
     println!();
@@ -87,7 +440,7 @@ fn setup_smb_mounts_remote<E: CommandExecutor>(exec: &E, config: &EnvConfig) ->
     // Mount each share
     for (server_name, server_config) in &config.smb_servers {
         for share_name in &server_config.shares {
-            let mount_point = format!("/mnt/smb/{}/{}", server_name, share_name);
+            let mount_point = smb_mount_point(server_name, share_name);
             let share_path = format!("//{}/{}", server_config.host, share_name);
 
             setup_smb_share(
@@ -240,11 +593,11 @@ fn setup_smb_share<E: CommandExecutor>(
         (uid, gid)
     };
 
+    // Store credentials in a root-only file so the password stays out of process listings
+    let credentials_path = install_smb_credentials(exec, server_name, username, password)?;
+
     // Build mount options
-    let mut mount_opts = format!(
-        "username={},password={},uid={},gid={}",
-        username, password, uid, gid
-    );
+    let mut mount_opts = format!("credentials={},uid={},gid={}", credentials_path, uid, gid);
     if let Some(ref opts) = server_config.options {
         mount_opts.push_str(&format!(",{}", opts));
     }
@@ -291,7 +644,16 @@ fn setup_smb_share<E: CommandExecutor>(
 
 fn add_fstab_entry<E: CommandExecutor>(exec: &E, mount_point: &str, entry: &str) -> Result<()> {
     // Check if entry already exists
-    let fstab_content = exec.read_file("/etc/fstab")?;
+    let mut fstab_content = exec.read_file("/etc/fstab")?;
+    if fstab_content
+        .lines()
+        .any(|line| line.contains(mount_point) && line.contains("password="))
+    {
+        // Replace entries from older versions that embedded the password
+        println!("Replacing /etc/fstab entry with inline password...");
+        remove_fstab_entry(exec, mount_point)?;
+        fstab_content = exec.read_file("/etc/fstab")?;
+    }
     if fstab_content.lines().any(|line| line.contains(mount_point)) {
         println!("✓ Entry already exists in /etc/fstab");
         return Ok(());
@@ -332,7 +694,7 @@ fn uninstall_smb_mounts_remote<E: CommandExecutor>(exec: &E, config: &EnvConfig)
     // Unmount each share
     for (server_name, server_config) in &config.smb_servers {
         for share_name in &server_config.shares {
-            let mount_point = format!("/mnt/smb/{}/{}", server_name, share_name);
+            let mount_point = smb_mount_point(server_name, share_name);
 
             // Check if mounted
             let mountpoint_check = exec.execute_simple("mountpoint", &["-q", &mount_point]);
@@ -364,6 +726,8 @@ fn uninstall_smb_mounts_remote<E: CommandExecutor>(exec: &E, config: &EnvConfig)
                 }
             }
         }
+
+        remove_smb_credentials(exec, server_name)?;
     }
 
     println!();
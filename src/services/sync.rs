@@ -1,13 +1,319 @@
 use crate::config::EnvConfig;
 use crate::db;
-use crate::utils::{bytes_to_string, ssh::SshConnection};
+use crate::utils::{bytes_to_string, crypto, ssh::SshConnection};
 use anyhow::{Context, Result};
 use base64::Engine as _;
 use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 
-/// Sync data to/from a remote halvor installation
-pub fn sync_data(hostname: &str, pull: bool, config: &EnvConfig) -> Result<()> {
+/// Settings key prefix for the per-host last-synced marker
+const SYNC_MARKER_SETTING_PREFIX: &str = "sync_marker.";
+
+/// Which side wins entries changed on both sides since the last sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Keep the local values
+    Ours,
+    /// Keep the remote values
+    Theirs,
+}
+
+/// State of both sides right after the last successful sync with a host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncMarker {
+    pub synced_at: i64,
+    pub local_hash: String,
+    pub remote_hash: String,
+}
+
+/// Load the last-synced marker for a host
+pub fn load_sync_marker(hostname: &str) -> Result<Option<SyncMarker>> {
+    let key = format!("{}{}", SYNC_MARKER_SETTING_PREFIX, hostname);
+    Ok(db::get_setting(&key)?.and_then(|value| serde_json::from_str(&value).ok()))
+}
+
+fn store_sync_marker(hostname: &str, local_hash: &str, remote_hash: &str) -> Result<()> {
+    let marker = SyncMarker {
+        synced_at: chrono::Utc::now().timestamp(),
+        local_hash: local_hash.to_string(),
+        remote_hash: remote_hash.to_string(),
+    };
+    let key = format!("{}{}", SYNC_MARKER_SETTING_PREFIX, hostname);
+    db::set_setting(&key, &serde_json::to_string(&marker)?)
+}
+
+/// How local encrypted data compares to a remote installation's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    InSync,
+    /// Only local has changes - push is safe
+    LocalAhead,
+    /// Only remote has changes - pull is safe
+    RemoteAhead,
+    /// Both sides have changes the other lacks
+    Diverged,
+}
+
+impl SyncState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncState::InSync => "in sync",
+            SyncState::LocalAhead => "local ahead",
+            SyncState::RemoteAhead => "remote ahead",
+            SyncState::Diverged => "diverged",
+        }
+    }
+}
+
+/// Summary of one side's exported encrypted data
+#[derive(Debug, Clone)]
+pub struct SyncSummary {
+    pub entries: usize,
+    /// SHA-256 over every (hostname, key, encrypted value), in key order
+    pub hash: String,
+    /// Most recent `updated_at` of any entry
+    pub last_updated: Option<i64>,
+}
+
+/// Entry-by-entry comparison of local and remote encrypted data
+#[derive(Debug, Clone)]
+pub struct SyncComparison {
+    pub local: SyncSummary,
+    pub remote: SyncSummary,
+    /// Entries missing on the remote or changed more recently locally
+    pub local_newer: Vec<String>,
+    /// Entries missing locally or changed more recently on the remote
+    pub remote_newer: Vec<String>,
+    /// Entries present on both sides with different values
+    pub differing: Vec<String>,
+}
+
+impl SyncComparison {
+    pub fn state(&self) -> SyncState {
+        match (self.local_newer.is_empty(), self.remote_newer.is_empty()) {
+            (true, true) => SyncState::InSync,
+            (false, true) => SyncState::LocalAhead,
+            (true, false) => SyncState::RemoteAhead,
+            (false, false) => SyncState::Diverged,
+        }
+    }
+
+    /// Entries a push (or pull) would overwrite even though the other side changed them more recently
+    /// Empty when the marker shows the overwritten side is unchanged since the last sync
+    pub fn conflicts(&self, pull: bool, marker: Option<&SyncMarker>) -> Vec<String> {
+        let (newer, current_hash, synced_hash) = if pull {
+            (
+                &self.local_newer,
+                &self.local.hash,
+                marker.map(|m| &m.local_hash),
+            )
+        } else {
+            (
+                &self.remote_newer,
+                &self.remote.hash,
+                marker.map(|m| &m.remote_hash),
+            )
+        };
+        if synced_hash == Some(current_hash) {
+            return Vec::new();
+        }
+        newer
+            .iter()
+            .filter(|name| self.differing.contains(name))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Parse an `export_encrypted_data` blob into entries keyed by `hostname/key`
+fn parse_export(
+    data: &[u8],
+) -> Result<BTreeMap<String, db::encrypted_env_data::EncryptedEnvDataRow>> {
+    let rows: Vec<db::encrypted_env_data::EncryptedEnvDataRow> =
+        serde_json::from_slice(data).context("Failed to parse encrypted data")?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let name = format!(
+                "{}/{}",
+                row.hostname.as_deref().unwrap_or("global"),
+                row.key
+            );
+            (name, row)
+        })
+        .collect())
+}
+
+fn summarize(
+    entries: &BTreeMap<String, db::encrypted_env_data::EncryptedEnvDataRow>,
+) -> SyncSummary {
+    let mut hasher = Sha256::new();
+    for (name, row) in entries {
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update(row.encrypted_value.as_bytes());
+        hasher.update([0]);
+    }
+    SyncSummary {
+        entries: entries.len(),
+        hash: format!("{:x}", hasher.finalize()),
+        last_updated: entries.values().map(|row| row.updated_at).max(),
+    }
+}
+
+/// Compare two `export_encrypted_data` blobs
+/// Entries with identical ciphertext match; otherwise the newer `updated_at` wins
+pub fn compare_encrypted_data(local: &[u8], remote: &[u8]) -> Result<SyncComparison> {
+    let local_entries = parse_export(local)?;
+    let remote_entries = parse_export(remote)?;
+
+    let mut local_newer = Vec::new();
+    let mut remote_newer = Vec::new();
+    let mut differing = Vec::new();
+    for (name, row) in &local_entries {
+        match remote_entries.get(name) {
+            None => local_newer.push(name.clone()),
+            Some(other) if other.encrypted_value == row.encrypted_value => {}
+            Some(other) => {
+                differing.push(name.clone());
+                if row.updated_at >= other.updated_at {
+                    local_newer.push(name.clone());
+                } else {
+                    remote_newer.push(name.clone());
+                }
+            }
+        }
+    }
+    for name in remote_entries.keys() {
+        if !local_entries.contains_key(name) {
+            remote_newer.push(name.clone());
+        }
+    }
+    remote_newer.sort();
+
+    Ok(SyncComparison {
+        local: summarize(&local_entries),
+        remote: summarize(&remote_entries),
+        local_newer,
+        remote_newer,
+        differing,
+    })
+}
+
+/// Drop the named entries from an `export_encrypted_data` blob
+fn filter_export(data: &[u8], exclude: &[String]) -> Result<Vec<u8>> {
+    let rows: Vec<_> = parse_export(data)?
+        .into_iter()
+        .filter(|(name, _)| !exclude.contains(name))
+        .map(|(_, row)| row)
+        .collect();
+    serde_json::to_vec(&rows).context("Failed to serialize encrypted data")
+}
+
+/// Hash of one side's post-sync export as the marker records it
+/// Entries left out of the sync are skipped, so the marker doesn't advance for them: the side that
+/// kept its own values no longer matches the marker, and those entries are checked again next time.
+fn marker_hash(data: &[u8], excluded: &[String]) -> Result<String> {
+    let mut entries = parse_export(data)?;
+    entries.retain(|name, _| !excluded.contains(name));
+    Ok(summarize(&entries).hash)
+}
+
+/// Decide which side wins conflicting entries, prompting when no resolution was given
+/// Returns None if the user aborts
+fn resolve_conflicts(
+    conflicts: &[String],
+    pull: bool,
+    resolution: Option<ConflictResolution>,
+) -> Result<Option<ConflictResolution>> {
+    println!(
+        "⚠ {} entr{} changed on both sides since the last sync:",
+        conflicts.len(),
+        if conflicts.len() == 1 { "y" } else { "ies" }
+    );
+    for name in conflicts {
+        println!("  - {}", name);
+    }
+    if let Some(resolution) = resolution {
+        return Ok(Some(resolution));
+    }
+
+    let overwritten = if pull { "local" } else { "remote" };
+    println!(
+        "A plain {} would overwrite the {} values.",
+        if pull { "pull" } else { "push" },
+        overwritten
+    );
+    print!("Keep [o]urs (local), [t]heirs (remote), or [a]bort? [a]: ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "o" | "ours" => Some(ConflictResolution::Ours),
+        "t" | "theirs" => Some(ConflictResolution::Theirs),
+        _ => None,
+    })
+}
+
+/// Report whether local and remote encrypted data are in sync, without transferring anything
+pub fn sync_status(hostname: &str, config: &EnvConfig) -> Result<()> {
+    let (ssh, target_host) = connect(hostname, config)?;
+    println!("Comparing with {} ({})...", hostname, target_host);
+    println!();
+
+    let local = db::export_encrypted_data()?;
+    let remote = export_from_remote(&ssh)?;
+    let comparison = compare_encrypted_data(&local, &remote)?;
+
+    for (side, summary) in [("Local", &comparison.local), ("Remote", &comparison.remote)] {
+        let updated = summary
+            .last_updated
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+            .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| "never".to_string());
+        println!(
+            "  {:<7} {} entries, hash {}, last updated {}",
+            format!("{}:", side),
+            summary.entries,
+            &summary.hash[..12],
+            updated
+        );
+    }
+    match load_sync_marker(hostname)?
+        .and_then(|marker| chrono::DateTime::from_timestamp(marker.synced_at, 0))
+    {
+        Some(t) => println!("  Last synced {}", t.format("%Y-%m-%d %H:%M UTC")),
+        None => println!("  Never synced with this host"),
+    }
+    println!();
+
+    let state = comparison.state();
+    println!("Status: {}", state.as_str());
+    for name in &comparison.local_newer {
+        println!("  local newer:  {}", name);
+    }
+    for name in &comparison.remote_newer {
+        println!("  remote newer: {}", name);
+    }
+    match state {
+        SyncState::InSync => {}
+        SyncState::LocalAhead => println!("Run `hal sync -H {}` to push", hostname),
+        SyncState::RemoteAhead => println!("Run `hal sync -H {} --pull` to pull", hostname),
+        SyncState::Diverged => {
+            println!("Both sides have changes - pushing or pulling will overwrite one of them")
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve a configured host and open an SSH connection to it
+/// Returns the connection and the address it targets
+fn connect(hostname: &str, config: &EnvConfig) -> Result<(SshConnection, String)> {
     // Get target host info (try normalized hostname)
     let actual_hostname = crate::config::service::find_hostname_in_config(hostname, config)
         .ok_or_else(|| anyhow::anyhow!("Host '{}' not found in configuration", hostname))?;
@@ -27,28 +333,85 @@ pub fn sync_data(hostname: &str, pull: bool, config: &EnvConfig) -> Result<()> {
         );
     };
 
+    // Create SSH connection, as the host's SSH user when one is configured
+    let ssh_target = match &host_config.ssh_user {
+        Some(user) => format!("{}@{}", user, target_host),
+        None => target_host.clone(),
+    };
+    let jump = crate::utils::ssh::resolve_jump_host(&actual_hostname, host_config, config);
+    let ssh = SshConnection::new_with_options(&ssh_target, host_config.ssh_port, jump.as_deref())
+        .with_context(|| format!("Failed to connect to {}", target_host))?;
+    if !ssh.is_reachable() {
+        anyhow::bail!(
+            "Host {} is unreachable over SSH. Tried:\n  {}",
+            hostname,
+            target_host
+        );
+    }
+
+    Ok((ssh, target_host))
+}
+
+/// Sync data to/from a remote halvor installation
+/// Entries changed on both sides since the last sync are resolved by `resolution`, or a prompt
+pub fn sync_data(
+    hostname: &str,
+    pull: bool,
+    resolution: Option<ConflictResolution>,
+    config: &EnvConfig,
+) -> Result<()> {
+    let (ssh, target_host) = connect(hostname, config)?;
     println!("Syncing with {} ({})...", hostname, target_host);
     println!();
 
-    // Create SSH connection
-    let ssh = SshConnection::new(&target_host)
-        .with_context(|| format!("Failed to connect to {}", target_host))?;
+    // Compare both sides first so newer data on the receiving side isn't clobbered
+    let local = db::export_encrypted_data()?;
+    let remote = export_from_remote(&ssh)?;
+    let comparison = compare_encrypted_data(&local, &remote)?;
+    let marker = load_sync_marker(hostname)?;
+    let conflicts = comparison.conflicts(pull, marker.as_ref());
+
+    let mut exclude = Vec::new();
+    if !conflicts.is_empty() {
+        match resolve_conflicts(&conflicts, pull, resolution)? {
+            None => {
+                println!("Cancelled - no changes were made.");
+                return Ok(());
+            }
+            // Leave the receiving side's values in place
+            Some(ConflictResolution::Theirs) if !pull => exclude = conflicts,
+            Some(ConflictResolution::Ours) if pull => exclude = conflicts,
+            Some(_) => {}
+        }
+        println!();
+    }
 
+    // The marker records both sides as they are after the sync, read back rather than predicted
     if pull {
-        pull_from_remote(&ssh, hostname)?;
+        let incoming = filter_export(&remote, &exclude)?;
+        pull_from_remote(&ssh, &incoming)?;
+        store_sync_marker(
+            hostname,
+            &marker_hash(&db::export_encrypted_data()?, &exclude)?,
+            &marker_hash(&remote, &exclude)?,
+        )?;
     } else {
-        push_to_remote(&ssh, hostname)?;
+        let outgoing = filter_export(&local, &exclude)?;
+        push_to_remote(&ssh, &outgoing)?;
+        store_sync_marker(
+            hostname,
+            &marker_hash(&local, &exclude)?,
+            &marker_hash(&export_from_remote(&ssh)?, &exclude)?,
+        )?;
     }
 
     Ok(())
 }
 
 /// Push data to remote halvor installation
-fn push_to_remote(ssh: &SshConnection, _hostname: &str) -> Result<()> {
+fn push_to_remote(ssh: &SshConnection, encrypted_data: &[u8]) -> Result<()> {
     println!("Pushing data to remote halvor installation...");
 
-    // Export encrypted data
-    let encrypted_data = db::export_encrypted_data()?;
     println!(
         "  Exported {} bytes of encrypted data",
         encrypted_data.len()
@@ -61,7 +424,7 @@ fn push_to_remote(ssh: &SshConnection, _hostname: &str) -> Result<()> {
     // Create temp file locally with the data
     let temp_file =
         std::env::temp_dir().join(format!("hal-sync-{}.json", chrono::Utc::now().timestamp()));
-    std::fs::write(&temp_file, &encrypted_data).context("Failed to write temp sync file")?;
+    std::fs::write(&temp_file, encrypted_data).context("Failed to write temp sync file")?;
 
     // Copy encrypted data to remote
     let remote_temp = format!("/tmp/hal-sync-{}.json", chrono::Utc::now().timestamp());
@@ -70,7 +433,7 @@ fn push_to_remote(ssh: &SshConnection, _hostname: &str) -> Result<()> {
 
     // Import on remote - we'll need to add db import command or use a different approach
     // For now, let's use a base64 encoded approach via stdin
-    let data_base64 = STANDARD.encode(&encrypted_data);
+    let data_base64 = STANDARD.encode(encrypted_data);
     let import_script = format!(
         r#"
         if ! command -v hal >/dev/null 2>&1; then
@@ -102,15 +465,33 @@ fn push_to_remote(ssh: &SshConnection, _hostname: &str) -> Result<()> {
     Ok(())
 }
 
-/// Pull data from remote halvor installation
-fn pull_from_remote(ssh: &SshConnection, _hostname: &str) -> Result<()> {
+/// Pull data from remote halvor installation, importing the already-exported data
+fn pull_from_remote(ssh: &SshConnection, encrypted_data: &[u8]) -> Result<()> {
     println!("Pulling data from remote halvor installation...");
 
     // Get remote halvor database path
     let remote_db_path = get_remote_db_path(ssh)?;
     println!("  Remote database: {}", remote_db_path);
 
-    // Export from remote
+    println!(
+        "  Received {} bytes of encrypted data",
+        encrypted_data.len()
+    );
+
+    // Import locally
+    db::import_encrypted_data(encrypted_data)?;
+    println!("  Imported encrypted data");
+
+    // Note: Encryption key sync requires manual setup for security
+    println!("  Note: Encryption key sync requires manual setup");
+
+    println!("✓ Data pulled successfully");
+
+    Ok(())
+}
+
+/// Export the remote installation's encrypted data
+fn export_from_remote(ssh: &SshConnection) -> Result<Vec<u8>> {
     let export_script = r#"
         if ! command -v halvor >/dev/null 2>&1; then
             echo "Error: halvor not found on remote host"
@@ -131,22 +512,7 @@ fn pull_from_remote(ssh: &SshConnection, _hostname: &str) -> Result<()> {
         );
     }
 
-    let encrypted_data = output.stdout;
-    println!(
-        "  Received {} bytes of encrypted data",
-        encrypted_data.len()
-    );
-
-    // Import locally
-    db::import_encrypted_data(&encrypted_data)?;
-    println!("  Imported encrypted data");
-
-    // Note: Encryption key sync requires manual setup for security
-    println!("  Note: Encryption key sync requires manual setup");
-
-    println!("✓ Data pulled successfully");
-
-    Ok(())
+    Ok(output.stdout)
 }
 
 /// Get the remote halvor database path
@@ -201,3 +567,208 @@ fn copy_file_to_remote(ssh: &SshConnection, local_path: &PathBuf, remote_path: &
 
     Ok(())
 }
+
+/// Environment variable holding the current key (base64) when rotating
+pub const OLD_KEY_VAR: &str = "HALVOR_OLD_KEY";
+/// Environment variable holding the replacement key (base64) when rotating
+pub const NEW_KEY_VAR: &str = "HALVOR_NEW_KEY";
+
+/// Re-encrypt every stored value with a new key
+/// The database is updated in one transaction and the key file swapped only after it commits,
+/// so an interrupted rotation leaves everything readable with either the old or the new key
+pub fn rotate_key() -> Result<()> {
+    // Finish (or undo) a rotation that was interrupted after staging its key
+    if let Some(staged) = crypto::staged_key()? {
+        if decrypts_any(&staged)? {
+            crypto::activate_staged_key()?;
+            println!("✓ Finished an interrupted key rotation");
+            return Ok(());
+        }
+        crypto::discard_staged_key()?;
+    }
+
+    let old_key = match std::env::var(OLD_KEY_VAR) {
+        Ok(value) if !value.is_empty() => crypto::parse_key(&value)?,
+        _ => match crypto::load_key()? {
+            Some(key) => key,
+            None => crypto::parse_key(&crypto::prompt_hidden("Current key (base64): ")?)?,
+        },
+    };
+
+    // Every value must be readable before any of them is rewritten
+    let rows = db::encrypted_env_data::select_many("1=1", &[])?;
+    let unreadable = undecryptable_entries(&rows, &old_key);
+    if !unreadable.is_empty() {
+        anyhow::bail!(
+            "The current key can't decrypt {} of {} stored value(s) ({}) - nothing was changed",
+            unreadable.len(),
+            rows.len(),
+            unreadable.join(", ")
+        );
+    }
+
+    let new_key = match std::env::var(NEW_KEY_VAR) {
+        Ok(value) if !value.is_empty() => crypto::parse_key(&value)?,
+        _ => prompt_new_key()?,
+    };
+    if new_key == old_key {
+        anyhow::bail!("The new key is the same as the current key");
+    }
+
+    crypto::stage_key(&new_key)?;
+    let result = db::reencrypt_all(|encrypted| {
+        let plaintext = crypto::decrypt_with_key(encrypted, &old_key)?;
+        crypto::encrypt_with_key(&plaintext, &new_key)
+    });
+    let count = match result {
+        Ok(count) => count,
+        Err(e) => {
+            crypto::discard_staged_key().ok();
+            return Err(e.context("Key rotation failed - nothing was changed"));
+        }
+    };
+    crypto::activate_staged_key()?;
+
+    println!("✓ Re-encrypted {} value(s) with a new key", count);
+    println!("  Other machines need the new key before they can read synced data");
+    Ok(())
+}
+
+/// Ask for the replacement key twice; an empty answer generates one
+/// When stdin isn't a terminal (piped), a single line is read without confirmation
+fn prompt_new_key() -> Result<crypto::EncryptionKey> {
+    let value = crypto::prompt_hidden("New key (base64, empty to generate one): ")?;
+    if value.is_empty() {
+        return Ok(crypto::generate_key());
+    }
+    let key = crypto::parse_key(&value)?;
+    if io::stdin().is_terminal() && crypto::prompt_hidden("Confirm new key: ")? != value {
+        anyhow::bail!("Keys do not match");
+    }
+    Ok(key)
+}
+
+/// Names (`hostname/key`) of the rows a key can't decrypt
+fn undecryptable_entries(
+    rows: &[db::encrypted_env_data::EncryptedEnvDataRow],
+    key: &crypto::EncryptionKey,
+) -> Vec<String> {
+    rows.iter()
+        .filter(|row| crypto::decrypt_with_key(&row.encrypted_value, key).is_err())
+        .map(|row| {
+            format!(
+                "{}/{}",
+                row.hostname.as_deref().unwrap_or("global"),
+                row.key
+            )
+        })
+        .collect()
+}
+
+/// Whether a key decrypts at least one stored value
+fn decrypts_any(key: &crypto::EncryptionKey) -> Result<bool> {
+    let rows = db::encrypted_env_data::select_many("1=1", &[])?;
+    Ok(rows
+        .iter()
+        .any(|row| crypto::decrypt_with_key(&row.encrypted_value, key).is_ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::encrypted_env_data::EncryptedEnvDataRow;
+
+    /// An export blob of global entries: (key, ciphertext, updated_at)
+    fn export(entries: &[(&str, &str, i64)]) -> Vec<u8> {
+        let rows: Vec<EncryptedEnvDataRow> = entries
+            .iter()
+            .map(|&(key, value, updated_at)| EncryptedEnvDataRow {
+                id: key.to_string(),
+                hostname: None,
+                key: key.to_string(),
+                encrypted_value: value.to_string(),
+                created_at: 0,
+                updated_at,
+            })
+            .collect();
+        serde_json::to_vec(&rows).unwrap()
+    }
+
+    fn marker(local: &[u8], remote: &[u8], excluded: &[String]) -> SyncMarker {
+        SyncMarker {
+            synced_at: 0,
+            local_hash: marker_hash(local, excluded).unwrap(),
+            remote_hash: marker_hash(remote, excluded).unwrap(),
+        }
+    }
+
+    #[test]
+    fn rotation_lists_every_value_the_current_key_cannot_read() {
+        let key = crypto::generate_key();
+        let other = crypto::generate_key();
+        let row = |key_name: &str, hostname: Option<&str>, value: &str| EncryptedEnvDataRow {
+            id: key_name.to_string(),
+            hostname: hostname.map(str::to_string),
+            key: key_name.to_string(),
+            encrypted_value: value.to_string(),
+            created_at: 0,
+            updated_at: 0,
+        };
+        let rows = vec![
+            row("A", None, &crypto::encrypt_with_key("a", &key).unwrap()),
+            row(
+                "B",
+                Some("frigg"),
+                &crypto::encrypt_with_key("b", &other).unwrap(),
+            ),
+            row("C", None, &crypto::encrypt_with_key("c", &key).unwrap()),
+            row("D", None, "not ciphertext"),
+        ];
+
+        assert_eq!(undecryptable_entries(&rows, &key), ["frigg/B", "global/D"]);
+        assert!(undecryptable_entries(&rows[..1], &key).is_empty());
+    }
+
+    #[test]
+    fn comparison_sorts_entries_by_newer_side() {
+        let local = export(&[("A", "a1", 10), ("B", "b", 1), ("C", "c1", 5)]);
+        let remote = export(&[("A", "a2", 20), ("B", "b", 1), ("D", "d", 1)]);
+        let comparison = compare_encrypted_data(&local, &remote).unwrap();
+
+        assert_eq!(comparison.local_newer, ["global/C"]);
+        assert_eq!(comparison.remote_newer, ["global/A", "global/D"]);
+        assert_eq!(comparison.differing, ["global/A"]);
+        assert_eq!(comparison.state(), SyncState::Diverged);
+    }
+
+    #[test]
+    fn unchanged_receiving_side_has_no_conflicts() {
+        let synced = export(&[("A", "a1", 10)]);
+        let local = export(&[("A", "a2", 30)]);
+        let remote = export(&[("A", "a3", 20)]);
+        let comparison = compare_encrypted_data(&local, &remote).unwrap();
+
+        // Without a marker, the remote's newer value counts as a conflict for a pull
+        assert_eq!(comparison.conflicts(true, None), ["global/A"]);
+        // The local side still matches the last sync, so pulling over it is safe
+        let marker = marker(&local, &synced, &[]);
+        assert!(comparison.conflicts(true, Some(&marker)).is_empty());
+    }
+
+    #[test]
+    fn entries_kept_by_a_resolution_are_checked_again_next_sync() {
+        let local = export(&[("A", "local", 10), ("B", "b", 1)]);
+        let remote = export(&[("A", "remote", 20), ("B", "b", 1)]);
+        let comparison = compare_encrypted_data(&local, &remote).unwrap();
+        let conflicts = comparison.conflicts(false, None);
+        assert_eq!(conflicts, ["global/A"]);
+
+        // `--theirs` pushes everything but A, so the remote export is unchanged afterwards
+        let marker = marker(&local, &remote, &conflicts);
+        assert_ne!(marker.remote_hash, comparison.remote.hash);
+
+        // A later plain push still asks about A instead of silently overwriting the remote value
+        let comparison = compare_encrypted_data(&local, &remote).unwrap();
+        assert_eq!(comparison.conflicts(false, Some(&marker)), ["global/A"]);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::config::{self, EnvConfig, HostConfig};
 use crate::utils::exec::PackageManager;
-use crate::utils::exec::{CommandExecutor, Executor};
+use crate::utils::exec::{CommandExecutor, DryRunExecutor, Executor};
 use anyhow::{Context, Result};
 use std::process::Command;
 
@@ -144,11 +144,32 @@ pub fn check_and_install_remote<E: CommandExecutor>(exec: &E) -> Result<()> {
 
 /// Install Tailscale on a host (public API for CLI)
 /// Works for both local and remote hosts
-pub fn install_tailscale_on_host(hostname: &str, config: &EnvConfig) -> Result<()> {
+/// When `tags` is non-empty the node advertises them and they are recorded in the host DB record
+/// When `dry_run` is set, commands are printed instead of executed
+pub fn install_tailscale_on_host(
+    hostname: &str,
+    tags: &[String],
+    dry_run: bool,
+    config: &EnvConfig,
+) -> Result<()> {
     let exec = Executor::new(hostname, config)?;
-    let target_host = exec.target_host(hostname, config)?;
+    let target_host = exec.target_host(hostname)?;
     let is_local = exec.is_local();
 
+    if dry_run {
+        // The local installer runs its commands directly, so preview the executor-based one,
+        // which uses the same install script or Homebrew formula
+        println!("Dry run: no changes will be made");
+        let dry_exec = DryRunExecutor::new(&exec, hostname);
+        check_and_install_remote(&dry_exec)?;
+        if !tags.is_empty() {
+            apply_tailscale_tags(&dry_exec, tags)?;
+        }
+        println!();
+        println!("✓ Tailscale installation dry run complete for {}", hostname);
+        return Ok(());
+    }
+
     if is_local {
         // For local, use the existing install_tailscale function
         install_tailscale()?;
@@ -156,6 +177,14 @@ pub fn install_tailscale_on_host(hostname: &str, config: &EnvConfig) -> Result<(
         println!("Installing Tailscale on {} ({})...", hostname, target_host);
         println!();
         check_and_install_remote(&exec)?;
+    }
+
+    if !tags.is_empty() {
+        apply_tailscale_tags(&exec, tags)?;
+        record_tailscale_tags(hostname, tags)?;
+    }
+
+    if !is_local {
         println!();
         println!("✓ Tailscale installation complete for {}", hostname);
     }
@@ -163,6 +192,49 @@ pub fn install_tailscale_on_host(hostname: &str, config: &EnvConfig) -> Result<(
     Ok(())
 }
 
+/// Parse a comma-separated list of Tailscale ACL tags (e.g. "tag:server,tag:homelab")
+/// Every tag needs the `tag:` prefix followed by a name of letters, digits and dashes
+pub fn parse_tailscale_tags(tags: &str) -> Result<Vec<String>> {
+    let mut parsed = Vec::new();
+    for tag in tags.split(',').map(|t| t.trim()) {
+        let Some(name) = tag.strip_prefix("tag:") else {
+            anyhow::bail!(
+                "Invalid Tailscale tag '{}': tags must start with 'tag:' (e.g. tag:server)",
+                tag
+            );
+        };
+        let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            anyhow::bail!(
+                "Invalid Tailscale tag '{}': the name after 'tag:' must start with a letter and contain only letters, digits and dashes",
+                tag
+            );
+        }
+        if !parsed.iter().any(|t| t == tag) {
+            parsed.push(tag.to_string());
+        }
+    }
+    Ok(parsed)
+}
+
+/// Advertise ACL tags for the node with `tailscale up --advertise-tags`
+pub fn apply_tailscale_tags<E: CommandExecutor>(exec: &E, tags: &[String]) -> Result<()> {
+    println!();
+    println!("=== Advertising Tailscale tags ===");
+    let advertise = format!("--advertise-tags={}", tags.join(","));
+    // Interactive so the login URL is shown if the node isn't authenticated yet
+    exec.execute_interactive("sudo", &["tailscale", "up", &advertise])
+        .context("Failed to advertise Tailscale tags (is the tag owned by this user in the tailnet ACL?)")?;
+    println!("✓ Advertised tags: {}", tags.join(", "));
+    Ok(())
+}
+
+/// Record the advertised tags in the host's DB record (under `tailscale_tags` in its metadata)
+pub fn record_tailscale_tags(hostname: &str, tags: &[String]) -> Result<()> {
+    crate::services::host::set_host_metadata(hostname, "tailscale_tags", serde_json::json!(tags))
+}
+
 /// Get host configuration from config with helpful error message
 /// This is used across modules that need to access host configuration
 pub fn get_host_config<'a>(config: &'a EnvConfig, hostname: &str) -> Result<&'a HostConfig> {
@@ -183,49 +255,177 @@ pub fn get_host_config<'a>(config: &'a EnvConfig, hostname: &str) -> Result<&'a
 pub struct TailscaleDevice {
     pub name: String,
     pub ip: Option<String>,
+    pub online: bool,
+    pub last_seen: Option<String>,
 }
 
-/// List Tailscale devices on the network
-pub fn list_tailscale_devices() -> Result<Vec<TailscaleDevice>> {
+impl TailscaleDevice {
+    /// Parse a device entry from `tailscale status --json` ("Self" or a "Peer" value)
+    fn from_status_json(data: &serde_json::Value) -> Self {
+        let name = data
+            .get("DNSName")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let ip = data
+            .get("TailscaleIPs")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let online = data
+            .get("Online")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // Tailscale reports the zero time for devices that have never been seen
+        let last_seen = data
+            .get("LastSeen")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.starts_with("0001-01-01"))
+            .map(|s| s.to_string());
+
+        TailscaleDevice {
+            name,
+            ip,
+            online,
+            last_seen,
+        }
+    }
+
+    /// First label of the MagicDNS name (e.g. "bellerophon" for "bellerophon.tail1234.ts.net.")
+    pub fn short_name(&self) -> &str {
+        self.name.split('.').next().unwrap_or(&self.name)
+    }
+}
+
+/// Run `tailscale status --json` locally (None if Tailscale is not available or not connected)
+fn tailscale_status_json() -> Result<Option<serde_json::Value>> {
     let output = Command::new("tailscale")
         .args(&["status", "--json"])
         .output()
         .context("Failed to execute tailscale status")?;
 
     if !output.status.success() {
-        return Ok(Vec::new()); // Tailscale not available or not connected
+        return Ok(None);
     }
 
-    let status_json: serde_json::Value =
+    let status_json =
         serde_json::from_slice(&output.stdout).context("Failed to parse tailscale status JSON")?;
+    Ok(Some(status_json))
+}
 
+/// List Tailscale devices on the network
+pub fn list_tailscale_devices() -> Result<Vec<TailscaleDevice>> {
+    let Some(status_json) = tailscale_status_json()? else {
+        return Ok(Vec::new()); // Tailscale not available or not connected
+    };
+
+    Ok(peer_devices(&status_json))
+}
+
+/// Peers listed in `tailscale status --json` output
+fn peer_devices(status_json: &serde_json::Value) -> Vec<TailscaleDevice> {
     let mut devices = Vec::new();
 
     // Parse Tailscale status JSON format
-    if let Some(peer_map) = status_json.get("Peer") {
-        if let Some(peers) = peer_map.as_object() {
-            for (_, peer_data) in peers {
-                let name = peer_data
-                    .get("DNSName")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-
-                let ip = peer_data
-                    .get("TailscaleIPs")
-                    .and_then(|v| v.as_array())
-                    .and_then(|arr| arr.first())
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                devices.push(TailscaleDevice { name, ip });
+    if let Some(peers) = status_json.get("Peer").and_then(|p| p.as_object()) {
+        for peer_data in peers.values() {
+            devices.push(TailscaleDevice::from_status_json(peer_data));
+        }
+    }
+
+    devices
+}
+
+/// Tailnet presence of a configured host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TailnetStatus {
+    Online,
+    Offline { last_seen: Option<String> },
+    NotInTailnet,
+}
+
+impl TailnetStatus {
+    /// One-line status summary for display
+    pub fn status_line(&self) -> String {
+        match self {
+            TailnetStatus::Online => "✓ online".to_string(),
+            TailnetStatus::Offline {
+                last_seen: Some(last_seen),
+            } => format!("✗ offline (last seen {})", last_seen),
+            TailnetStatus::Offline { last_seen: None } => "✗ offline".to_string(),
+            TailnetStatus::NotInTailnet => "⚠ not in tailnet".to_string(),
+        }
+    }
+
+    /// Machine-readable form for JSON output
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            TailnetStatus::Online => serde_json::json!({ "state": "online" }),
+            TailnetStatus::Offline { last_seen } => {
+                serde_json::json!({ "state": "offline", "last_seen": last_seen })
             }
+            TailnetStatus::NotInTailnet => serde_json::json!({ "state": "not_in_tailnet" }),
         }
     }
+}
 
+/// All devices in the tailnet, including this machine
+pub fn tailnet_devices() -> Result<Vec<TailscaleDevice>> {
+    let Some(status_json) = tailscale_status_json()? else {
+        return Ok(Vec::new());
+    };
+    let mut devices = peer_devices(&status_json);
+    if let Some(self_data) = status_json.get("Self") {
+        let mut device = TailscaleDevice::from_status_json(self_data);
+        device.online = true;
+        devices.push(device);
+    }
     Ok(devices)
 }
 
+/// Find the tailnet device for a configured host
+/// Matches the tailscale name (falling back to the hostname, then the config key) against the
+/// device's MagicDNS name, or the configured IP against the device's Tailscale IP
+pub fn find_host_device<'a>(
+    hostname: &str,
+    host: &HostConfig,
+    devices: &'a [TailscaleDevice],
+) -> Option<&'a TailscaleDevice> {
+    let name = host
+        .tailscale
+        .as_deref()
+        .or(host.hostname.as_deref())
+        .unwrap_or(hostname);
+
+    devices
+        .iter()
+        .find(|d| d.short_name().eq_ignore_ascii_case(name))
+        .or_else(|| {
+            host.ip
+                .as_ref()
+                .and_then(|ip| devices.iter().find(|d| d.ip.as_ref() == Some(ip)))
+        })
+}
+
+/// Tailnet status of a configured host given the current device list
+pub fn host_tailnet_status(
+    hostname: &str,
+    host: &HostConfig,
+    devices: &[TailscaleDevice],
+) -> TailnetStatus {
+    match find_host_device(hostname, host, devices) {
+        Some(device) if device.online => TailnetStatus::Online,
+        Some(device) => TailnetStatus::Offline {
+            last_seen: device.last_seen.clone(),
+        },
+        None => TailnetStatus::NotInTailnet,
+    }
+}
+
 /// Get local Tailscale IP address
 pub fn get_tailscale_ip() -> Result<Option<String>> {
     let output = Command::new("tailscale").args(&["ip", "-4"]).output().ok();
@@ -1,18 +1,37 @@
-// Web server for serving Svelte app and exposing FFI functions via HTTP API
+// Web server for serving Svelte app and exposing FFI functions via HTTP API, plus the read-only
+// status API behind `hal web serve`
+use crate::agent::discovery::{DiscoveredHost, HostDiscovery};
 use crate::ffi::client::HalvorClient;
+use anyhow::Context;
 use axum::{
     Router,
     extract::State,
     http::StatusCode,
-    response::{IntoResponse, Json},
+    response::{
+        IntoResponse, Json,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{get, post},
 };
+use futures_util::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tower_http::{cors::CorsLayer, services::ServeDir};
 
+/// How often `/agents/stream` runs agent discovery
+const AGENT_STREAM_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Reconnect delay suggested to `/agents/stream` clients (the SSE `retry:` field)
+const AGENT_STREAM_RETRY: Duration = Duration::from_secs(5);
+
+/// Idle time after which a heartbeat comment is sent so proxies keep the stream open
+const AGENT_STREAM_HEARTBEAT: Duration = Duration::from_secs(15);
+
 #[derive(Clone)]
 pub struct AppState {
     pub client: Arc<HalvorClient>,
@@ -71,7 +90,7 @@ async fn discover_agents(State(state): State<AppState>) -> impl IntoResponse {
         Ok(hosts) => (StatusCode::OK, Json(ApiResponse::success(hosts))),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<Vec<_>>::error(e)),
+            Json(ApiResponse::<Vec<_>>::error(e.to_string())),
         ),
     }
 }
@@ -81,7 +100,7 @@ async fn discover_via_tailscale(State(state): State<AppState>) -> impl IntoRespo
         Ok(hosts) => (StatusCode::OK, Json(ApiResponse::success(hosts))),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<Vec<_>>::error(e)),
+            Json(ApiResponse::<Vec<_>>::error(e.to_string())),
         ),
     }
 }
@@ -91,7 +110,7 @@ async fn discover_via_local_network(State(state): State<AppState>) -> impl IntoR
         Ok(hosts) => (StatusCode::OK, Json(ApiResponse::success(hosts))),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<Vec<_>>::error(e)),
+            Json(ApiResponse::<Vec<_>>::error(e.to_string())),
         ),
     }
 }
@@ -104,7 +123,7 @@ async fn ping_agent(
         Ok(reachable) => (StatusCode::OK, Json(ApiResponse::success(reachable))),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<bool>::error(e)),
+            Json(ApiResponse::<bool>::error(e.to_string())),
         ),
     }
 }
@@ -117,7 +136,7 @@ async fn get_host_info(
         Ok(info) => (StatusCode::OK, Json(ApiResponse::success(info))),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<crate::agent::server::HostInfo>::error(e)),
+            Json(ApiResponse::<crate::ffi::HostInfo>::error(e.to_string())),
         ),
     }
 }
@@ -133,7 +152,7 @@ async fn execute_command(
         Ok(output) => (StatusCode::OK, Json(ApiResponse::success(output))),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<String>::error(e)),
+            Json(ApiResponse::<String>::error(e.to_string())),
         ),
     }
 }
@@ -143,11 +162,119 @@ async fn get_version(State(state): State<AppState>) -> impl IntoResponse {
         Ok(version) => (StatusCode::OK, Json(ApiResponse::success(version))),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<String>::error(e)),
+            Json(ApiResponse::<String>::error(e.to_string())),
         ),
     }
 }
 
+/// Run blocking halvor code off the async runtime and wrap its result in an ApiResponse
+async fn blocking_response<T: Serialize + Send + 'static>(
+    f: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> (StatusCode, Json<ApiResponse<T>>) {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(Ok(data)) => (StatusCode::OK, Json(ApiResponse::success(data))),
+        Ok(Err(e)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("{:#}", e))),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(e.to_string())),
+        ),
+    }
+}
+
+// Dashboard handlers (`hal web serve`)
+async fn dashboard_hosts() -> impl IntoResponse {
+    blocking_response(|| {
+        crate::services::host::list_hosts_json(
+            false,
+            &crate::services::host::HostListOptions::default(),
+        )
+    })
+    .await
+}
+
+async fn dashboard_health() -> impl IntoResponse {
+    blocking_response(crate::services::host::check_all_hosts_health).await
+}
+
+async fn dashboard_agents(State(state): State<DashboardState>) -> impl IntoResponse {
+    match state.discover_agents().await {
+        Ok(hosts) => (StatusCode::OK, Json(ApiResponse::success(hosts))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("{:#}", e))),
+        ),
+    }
+}
+
+/// Server-Sent Events feed of discovered agents
+/// Sends an `agent` event for each new or changed agent and `agent-removed` when one is no longer
+/// found, re-running discovery every 30 seconds. The stream (and its timer) is dropped as soon
+/// as the client disconnects.
+async fn dashboard_agents_stream(
+    State(state): State<DashboardState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let hello = Event::default()
+        .retry(AGENT_STREAM_RETRY)
+        .comment("halvor agent discovery");
+
+    let updates = stream::unfold(
+        (state, BTreeMap::new(), true),
+        |(state, mut known, first)| async move {
+            if !first {
+                tokio::time::sleep(AGENT_STREAM_INTERVAL).await;
+            }
+            let events = match state.discover_agents().await {
+                Ok(hosts) => agent_events(&mut known, hosts),
+                Err(e) => vec![Event::default().event("error").data(format!("{:#}", e))],
+            };
+            Some((stream::iter(events), (state, known, false)))
+        },
+    )
+    .flatten();
+
+    Sse::new(stream::once(async { hello }).chain(updates).map(Ok)).keep_alive(
+        KeepAlive::new()
+            .interval(AGENT_STREAM_HEARTBEAT)
+            .text("heartbeat"),
+    )
+}
+
+/// Events for what changed between the agents a client has seen and a new discovery result
+fn agent_events(
+    known: &mut BTreeMap<String, DiscoveredHost>,
+    hosts: Vec<DiscoveredHost>,
+) -> Vec<Event> {
+    let current: BTreeMap<String, DiscoveredHost> = hosts
+        .into_iter()
+        .map(|host| (agent_key(&host), host))
+        .collect();
+
+    let mut events = Vec::new();
+    for (key, host) in &current {
+        if known.get(key) != Some(host) {
+            events.extend(Event::default().event("agent").json_data(host).ok());
+        }
+    }
+    for (key, host) in known.iter() {
+        if !current.contains_key(key) {
+            events.extend(Event::default().event("agent-removed").json_data(host).ok());
+        }
+    }
+    *known = current;
+    events
+}
+
+/// Identity of an agent across discovery runs (matches the deduplication in `discover_all`)
+fn agent_key(host: &DiscoveredHost) -> String {
+    host.tailscale_ip
+        .clone()
+        .or_else(|| host.local_ip.clone())
+        .unwrap_or_else(|| host.hostname.clone())
+}
+
 // Health check endpoint
 async fn health() -> impl IntoResponse {
     (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
@@ -190,3 +317,59 @@ pub async fn start_server(
 
     Ok(())
 }
+
+/// When agent discovery last ran and what it found
+type LastDiscovery = Option<(Instant, Vec<DiscoveredHost>)>;
+
+/// State shared by the dashboard handlers
+#[derive(Clone, Default)]
+struct DashboardState {
+    /// Last discovery result; the lock is held while discovery runs so runs never overlap
+    discovery: Arc<tokio::sync::Mutex<LastDiscovery>>,
+}
+
+impl DashboardState {
+    /// Discover agents, reusing a result from the last half interval instead of starting
+    /// another run, so several clients share one discovery
+    async fn discover_agents(&self) -> anyhow::Result<Vec<DiscoveredHost>> {
+        let mut last = self.discovery.clone().lock_owned().await;
+        if let Some((at, hosts)) = last.as_ref()
+            && at.elapsed() < AGENT_STREAM_INTERVAL / 2
+        {
+            return Ok(hosts.clone());
+        }
+        // The guard moves into the blocking task so the lock outlives a client that
+        // disconnects mid-run
+        tokio::task::spawn_blocking(move || {
+            let hosts = HostDiscovery::default().discover_all()?;
+            *last = Some((Instant::now(), hosts.clone()));
+            Ok(hosts)
+        })
+        .await?
+    }
+}
+
+/// Start the read-only dashboard API (`hal web serve`)
+/// `/hosts` is the merged .env and database host list, `/health` probes every host and
+/// `/agents` runs agent discovery and `/agents/stream` pushes discovery changes as
+/// Server-Sent Events.
+pub async fn start_dashboard(addr: SocketAddr) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/hosts", get(dashboard_hosts))
+        .route("/health", get(dashboard_health))
+        .route("/agents", get(dashboard_agents))
+        .route("/agents/stream", get(dashboard_agents_stream))
+        .with_state(DashboardState::default());
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to listen on {}", addr))?;
+    println!("✓ Dashboard API listening on http://{}", addr);
+    println!("  GET /hosts          hosts from the .env file and database");
+    println!("  GET /health         reachability and Docker status of each host");
+    println!("  GET /agents         halvor agents discovered on the network");
+    println!("  GET /agents/stream  live agent discovery (Server-Sent Events)");
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
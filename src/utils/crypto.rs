@@ -5,9 +5,35 @@ use aes_gcm::{
 };
 use anyhow::{Context, Result};
 use base64::{Engine as _, engine::general_purpose};
+use sha2::Sha256;
 use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::sync::OnceLock;
 
 const KEY_FILE_NAME: &str = ".halvor_key";
+/// Replacement key written during rotation, renamed over the key file once the database commits
+const STAGED_KEY_FILE_NAME: &str = ".halvor_key.new";
+
+/// Environment variable holding the passphrase for the encrypted .env file
+pub const ENV_KEY_VAR: &str = "HALVOR_ENV_KEY";
+/// Environment variable holding the passphrase for encrypted backups
+pub const BACKUP_KEY_VAR: &str = "HALVOR_BACKUP_KEY";
+/// Shortest passphrase accepted when a new one is chosen
+pub const MIN_PASSPHRASE_LEN: usize = 12;
+const PBKDF2_ROUNDS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+/// Header of files written by `encrypt_stream`
+const STREAM_MAGIC: &[u8; 8] = b"HALVENC1";
+/// Random part of each chunk's nonce; the rest is the chunk counter and a last-chunk flag
+const STREAM_NONCE_PREFIX_LEN: usize = 7;
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+
+/// Key used to encrypt stored env data
+pub type EncryptionKey = Key<Aes256Gcm>;
+
+/// Passphrase for the encrypted .env file, cached so we only prompt once per run
+static ENV_PASSPHRASE: OnceLock<String> = OnceLock::new();
 
 /// Get or create the encryption key
 fn get_or_create_key() -> Result<Key<Aes256Gcm>> {
@@ -42,8 +68,17 @@ fn get_or_create_key() -> Result<Key<Aes256Gcm>> {
 
 /// Encrypt data
 pub fn encrypt(data: &str) -> Result<String> {
-    let key = get_or_create_key()?;
-    let cipher = Aes256Gcm::new(&key);
+    encrypt_with_key(data, &get_or_create_key()?)
+}
+
+/// Decrypt data
+pub fn decrypt(encrypted: &str) -> Result<String> {
+    decrypt_with_key(encrypted, &get_or_create_key()?)
+}
+
+/// Encrypt data with a specific key
+pub fn encrypt_with_key(data: &str, key: &EncryptionKey) -> Result<String> {
+    let cipher = Aes256Gcm::new(key);
     let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
 
     let ciphertext = cipher
@@ -56,10 +91,9 @@ pub fn encrypt(data: &str) -> Result<String> {
     Ok(general_purpose::STANDARD.encode(&combined))
 }
 
-/// Decrypt data
-pub fn decrypt(encrypted: &str) -> Result<String> {
-    let key = get_or_create_key()?;
-    let cipher = Aes256Gcm::new(&key);
+/// Decrypt data with a specific key
+pub fn decrypt_with_key(encrypted: &str, key: &EncryptionKey) -> Result<String> {
+    let cipher = Aes256Gcm::new(key);
 
     // Decode from base64
     let combined = general_purpose::STANDARD
@@ -135,3 +169,302 @@ pub fn key_exists() -> Result<bool> {
     let key_path = config_dir.join(KEY_FILE_NAME);
     Ok(key_path.exists())
 }
+
+/// Load the current encryption key, if one has been created
+pub fn load_key() -> Result<Option<EncryptionKey>> {
+    if !key_exists()? {
+        return Ok(None);
+    }
+    get_or_create_key().map(Some)
+}
+
+/// Generate a fresh random encryption key
+pub fn generate_key() -> EncryptionKey {
+    Aes256Gcm::generate_key(&mut OsRng)
+}
+
+/// Parse a base64 key in the format produced by `export_key`
+pub fn parse_key(key_base64: &str) -> Result<EncryptionKey> {
+    let key_bytes = general_purpose::STANDARD
+        .decode(key_base64.trim())
+        .context("Failed to decode key")?;
+    if key_bytes.len() != 32 {
+        anyhow::bail!("Invalid key: wrong length");
+    }
+    Ok(*Key::<Aes256Gcm>::from_slice(&key_bytes))
+}
+
+/// Write a replacement key next to the current one without activating it
+pub fn stage_key(key: &EncryptionKey) -> Result<()> {
+    let staged_path = config_manager::get_config_dir()?.join(STAGED_KEY_FILE_NAME);
+    fs::write(&staged_path, key.as_slice())
+        .with_context(|| format!("Failed to write key file: {}", staged_path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged_path, fs::Permissions::from_mode(0o600))
+            .context("Failed to set key file permissions")?;
+    }
+    // Make sure the key is on disk before any data is encrypted with it
+    fs::File::open(&staged_path)
+        .and_then(|file| file.sync_all())
+        .with_context(|| format!("Failed to sync key file: {}", staged_path.display()))?;
+    Ok(())
+}
+
+/// Load a key left staged by an interrupted rotation
+pub fn staged_key() -> Result<Option<EncryptionKey>> {
+    let staged_path = config_manager::get_config_dir()?.join(STAGED_KEY_FILE_NAME);
+    if !staged_path.exists() {
+        return Ok(None);
+    }
+    let key_bytes = fs::read(&staged_path)
+        .with_context(|| format!("Failed to read key file: {}", staged_path.display()))?;
+    if key_bytes.len() != 32 {
+        anyhow::bail!("Invalid staged key file: wrong length");
+    }
+    Ok(Some(*Key::<Aes256Gcm>::from_slice(&key_bytes)))
+}
+
+/// Replace the current key with the staged one (an atomic rename)
+pub fn activate_staged_key() -> Result<()> {
+    let config_dir = config_manager::get_config_dir()?;
+    fs::rename(
+        config_dir.join(STAGED_KEY_FILE_NAME),
+        config_dir.join(KEY_FILE_NAME),
+    )
+    .context("Failed to activate the new encryption key")
+}
+
+/// Throw away a staged key that was never used
+pub fn discard_staged_key() -> Result<()> {
+    let staged_path = config_manager::get_config_dir()?.join(STAGED_KEY_FILE_NAME);
+    if staged_path.exists() {
+        fs::remove_file(&staged_path)
+            .with_context(|| format!("Failed to remove {}", staged_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Derive an encryption key from a passphrase (PBKDF2-HMAC-SHA256)
+fn derive_key(passphrase: &str, salt: &[u8]) -> Key<Aes256Gcm> {
+    let mut key_bytes = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key_bytes);
+    *Key::<Aes256Gcm>::from_slice(&key_bytes)
+}
+
+/// Encrypt data with a passphrase-derived key
+/// Output is base64(salt || nonce || ciphertext) so it can be decrypted on any machine
+pub fn encrypt_with_passphrase(data: &str, passphrase: &str) -> Result<String> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, data.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Failed to encrypt data: {}", e))?;
+
+    let mut combined = salt.to_vec();
+    combined.extend_from_slice(&nonce);
+    combined.extend_from_slice(&ciphertext);
+    Ok(general_purpose::STANDARD.encode(&combined))
+}
+
+/// Decrypt data produced by `encrypt_with_passphrase`
+pub fn decrypt_with_passphrase(encrypted: &str, passphrase: &str) -> Result<String> {
+    let combined = general_purpose::STANDARD
+        .decode(encrypted)
+        .context("Failed to decode base64")?;
+
+    if combined.len() < SALT_LEN + 12 {
+        anyhow::bail!("Invalid encrypted data: too short");
+    }
+
+    let (salt, rest) = combined.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(12);
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt));
+
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt data (wrong passphrase?)"))?;
+
+    String::from_utf8(plaintext).context("Failed to convert decrypted data to string")
+}
+
+/// Nonce for chunk `counter` of a stream; flagging the last chunk makes truncation detectable
+fn stream_nonce(
+    prefix: &[u8],
+    counter: u32,
+    last: bool,
+) -> Nonce<<Aes256Gcm as AeadCore>::NonceSize> {
+    let mut nonce = [0u8; 12];
+    nonce[..STREAM_NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[STREAM_NONCE_PREFIX_LEN..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    *Nonce::from_slice(&nonce)
+}
+
+/// Read until `buf` is full or the reader is exhausted, returning the bytes read
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypt a stream of any size with a passphrase-derived key
+/// Output is magic || salt || nonce prefix, then the input in 64 KiB AES-GCM chunks, so large
+/// files (backup archives) never have to fit in memory
+pub fn encrypt_stream(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    passphrase: &str,
+) -> Result<()> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let prefix: [u8; STREAM_NONCE_PREFIX_LEN] = rand::random();
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt));
+    writer.write_all(STREAM_MAGIC)?;
+    writer.write_all(&salt)?;
+    writer.write_all(&prefix)?;
+
+    let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+    let mut counter: u32 = 0;
+    loop {
+        let len = read_full(reader, &mut buf).context("Failed to read data to encrypt")?;
+        // A short chunk (possibly empty) ends the stream
+        let last = len < STREAM_CHUNK_SIZE;
+        let ciphertext = cipher
+            .encrypt(&stream_nonce(&prefix, counter, last), &buf[..len])
+            .map_err(|e| anyhow::anyhow!("Failed to encrypt data: {}", e))?;
+        writer.write_all(&ciphertext)?;
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .context("Data too large to encrypt")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Whether data starts with the header written by `encrypt_stream`
+pub fn is_encrypted_stream(header: &[u8]) -> bool {
+    header.starts_with(STREAM_MAGIC)
+}
+
+/// Decrypt a stream produced by `encrypt_stream`
+/// Fails on a wrong passphrase and on corrupted or truncated input
+pub fn decrypt_stream(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    passphrase: &str,
+) -> Result<()> {
+    let mut header = [0u8; STREAM_MAGIC.len() + SALT_LEN + STREAM_NONCE_PREFIX_LEN];
+    let header_len = read_full(reader, &mut header).context("Failed to read encrypted data")?;
+    if header_len < header.len() || !is_encrypted_stream(&header) {
+        anyhow::bail!("Not a halvor encrypted file");
+    }
+    let (salt, prefix) = header[STREAM_MAGIC.len()..].split_at(SALT_LEN);
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt));
+
+    let mut buf = vec![0u8; STREAM_CHUNK_SIZE + TAG_LEN];
+    let mut counter: u32 = 0;
+    loop {
+        let len = read_full(reader, &mut buf).context("Failed to read encrypted data")?;
+        if len < TAG_LEN {
+            anyhow::bail!("Encrypted data is truncated");
+        }
+        let last = len < buf.len();
+        let plaintext = cipher
+            .decrypt(&stream_nonce(prefix, counter, last), &buf[..len])
+            .map_err(|_| {
+                anyhow::anyhow!("Failed to decrypt data (wrong passphrase or corrupted file?)")
+            })?;
+        writer.write_all(&plaintext)?;
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .context("Encrypted data is too large")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Get the passphrase for encrypted backups
+/// Uses HALVOR_BACKUP_KEY if set, otherwise prompts (twice when `confirm` is set)
+pub fn get_backup_passphrase(confirm: bool) -> Result<String> {
+    match std::env::var(BACKUP_KEY_VAR) {
+        Ok(value) if !value.is_empty() => Ok(value),
+        _ if confirm => prompt_passphrase_confirmed("Backup passphrase"),
+        _ => {
+            let passphrase = prompt_hidden("Backup passphrase: ")?;
+            if passphrase.is_empty() {
+                anyhow::bail!("Passphrase cannot be empty");
+            }
+            Ok(passphrase)
+        }
+    }
+}
+
+/// Get the passphrase for the encrypted .env file
+/// Uses HALVOR_ENV_KEY if set, otherwise prompts (twice when `confirm` is set)
+pub fn get_env_passphrase(confirm: bool) -> Result<String> {
+    if let Some(passphrase) = ENV_PASSPHRASE.get() {
+        return Ok(passphrase.clone());
+    }
+
+    let passphrase = match std::env::var(ENV_KEY_VAR) {
+        Ok(value) if !value.is_empty() => value,
+        _ if confirm => prompt_passphrase_confirmed("Passphrase for encrypted .env")?,
+        _ => {
+            let passphrase = prompt_hidden("Passphrase for encrypted .env: ")?;
+            if passphrase.is_empty() {
+                anyhow::bail!("Passphrase cannot be empty");
+            }
+            passphrase
+        }
+    };
+
+    Ok(ENV_PASSPHRASE.get_or_init(|| passphrase).clone())
+}
+
+/// Prompt for a new passphrase: asks twice, compares, and enforces MIN_PASSPHRASE_LEN
+/// When stdin isn't a terminal (piped), a single line is read without confirmation
+pub fn prompt_passphrase_confirmed(label: &str) -> Result<String> {
+    let passphrase = prompt_hidden(&format!("{}: ", label))?;
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        anyhow::bail!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        );
+    }
+    if io::stdin().is_terminal()
+        && prompt_hidden(&format!("Confirm {}: ", label.to_lowercase()))? != passphrase
+    {
+        anyhow::bail!("Passphrases do not match");
+    }
+    Ok(passphrase)
+}
+
+/// Read a line without echoing it to the terminal
+/// When stdin isn't a terminal (piped), the line is read from stdin as-is
+pub fn prompt_hidden(prompt: &str) -> Result<String> {
+    if io::stdin().is_terminal() {
+        return rpassword::prompt_password(prompt).context("Failed to read input");
+    }
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read input")?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
-use std::process::{Command, Output, Stdio};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
 
 // Import SshConnection from ssh module
 use crate::utils::ssh::SshConnection;
 
+/// How long the network preflight waits for each address before moving on
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Local command execution helpers
 pub mod local {
     use super::*;
@@ -140,6 +145,67 @@ pub mod local {
             .with_context(|| format!("Failed to execute shell command: {}", command))?;
         Ok(output)
     }
+
+    /// Run `sh -c <command>` with a wall-clock deadline
+    ///
+    /// Returns `Ok(None)` if the command did not finish within `timeout`; it is killed in that case.
+    pub fn execute_shell_timeout(
+        command: &str,
+        timeout: std::time::Duration,
+    ) -> Result<Option<Output>> {
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to execute shell command: {}", command))?;
+        wait_with_timeout(child, timeout)
+    }
+
+    /// Wait for a child whose stdout/stderr are piped, killing it if it runs past `timeout`
+    ///
+    /// Both pipes are read on their own threads while waiting, so a command that writes more
+    /// than the pipe buffer holds doesn't block until the deadline. Returns `Ok(None)` if the
+    /// child was killed.
+    pub fn wait_with_timeout(
+        mut child: Child,
+        timeout: std::time::Duration,
+    ) -> Result<Option<Output>> {
+        fn drain(
+            pipe: Option<impl std::io::Read + Send + 'static>,
+        ) -> std::thread::JoinHandle<Vec<u8>> {
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                if let Some(mut pipe) = pipe {
+                    pipe.read_to_end(&mut buf).ok();
+                }
+                buf
+            })
+        }
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+
+        let deadline = std::time::Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if std::time::Instant::now() >= deadline {
+                // The readers finish on their own once the killed child's pipes close
+                child.kill().ok();
+                child.wait().ok();
+                return Ok(None);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        };
+        Ok(Some(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        }))
+    }
 }
 
 /// Trait for executing commands either locally or remotely
@@ -308,78 +374,44 @@ impl Executor {
             .get(&actual_hostname)
             .with_context(|| format!("Host '{}' not found in config", hostname))?;
 
-        // Get target IP
-        let target_ip = if let Some(ip) = &host_config.ip {
-            ip.clone()
-        } else {
-            // If no IP configured, assume remote
-            return Ok(Executor::Remote({
-                let target_host = host_config.tailscale.as_ref().ok_or_else(|| {
-                    anyhow::anyhow!("No IP or Tailscale hostname configured for {}", hostname)
-                })?;
-                let default_user = crate::config::get_default_username();
-                let host_with_user = format!("{}@{}", default_user, target_host);
-                SshConnection::new(&host_with_user)?
-            }));
+        // Hosts without an IP can only be reached remotely (over Tailscale)
+        let is_local = match &host_config.ip {
+            Some(ip) => crate::utils::networking::get_local_ips()?.contains(ip),
+            None => false,
         };
-
-        // Get local IP addresses
-        let local_ips = crate::utils::networking::get_local_ips()?;
-
-        // Check if target IP matches any local IP
-        let is_local = local_ips.contains(&target_ip);
-
         if is_local {
-            Ok(Executor::Local)
-        } else {
-            // Get host configuration for remote connection (try normalized hostname)
-            let actual_hostname = crate::config::service::find_hostname_in_config(hostname, config)
-                .ok_or_else(|| anyhow::anyhow!("Host '{}' not found in config", hostname))?;
-            let host_config = config.hosts.get(&actual_hostname).with_context(|| {
-                format!(
-                    "Host '{}' not found in .env\n\nAdd configuration to .env:\n  HOST_{}_IP=\"<ip-address>\"\n  HOST_{}_TAILSCALE=\"<tailscale-hostname>\"",
-                    hostname,
-                    hostname.to_uppercase(),
-                    hostname.to_uppercase()
-                )
-            })?;
-
-            // Determine which host to connect to (prefer IP, fallback to Tailscale)
-            let target_host = if let Some(ip) = &host_config.ip {
-                ip.clone()
-            } else if let Some(tailscale) = &host_config.tailscale {
-                tailscale.clone()
-            } else {
-                anyhow::bail!("No IP or Tailscale hostname configured for {}", hostname);
-            };
-
-            // Create SSH connection
-            let default_user = crate::config::get_default_username();
-            let host_with_user = format!("{}@{}", default_user, target_host);
-            let ssh_conn = SshConnection::new(&host_with_user)?;
-
-            Ok(Executor::Remote(ssh_conn))
+            return Ok(Executor::Local);
         }
+
+        Ok(Executor::Remote(connect_reachable(
+            &actual_hostname,
+            host_config,
+            config,
+        )?))
     }
 
     /// Get the target host (for remote) or hostname (for local)
-    pub fn target_host(&self, hostname: &str, config: &crate::config::EnvConfig) -> Result<String> {
+    pub fn target_host(&self, hostname: &str) -> Result<String> {
         match self {
             Executor::Local => Ok(hostname.to_string()),
-            Executor::Remote(_) => {
-                let host_config = config
-                    .hosts
-                    .get(hostname)
-                    .with_context(|| format!("Host '{}' not found in config", hostname))?;
-                let target_host = if let Some(ip) = &host_config.ip {
-                    ip.clone()
-                } else if let Some(tailscale) = &host_config.tailscale {
-                    tailscale.clone()
-                } else {
-                    anyhow::bail!("No IP or Tailscale hostname configured for {}", hostname);
-                };
-                Ok(target_host)
-            }
+            // The address that answered, which may be a Tailscale fallback rather than the IP
+            Executor::Remote(conn) => Ok(conn
+                .host
+                .rsplit_once('@')
+                .map_or(conn.host.as_str(), |(_, address)| address)
+                .to_string()),
+        }
+    }
+
+    /// User commands run as: the current user locally, the SSH login user remotely
+    pub fn login_user(&self) -> String {
+        match self {
+            Executor::Local => crate::config::get_default_username(),
+            Executor::Remote(conn) => conn
+                .host
+                .split_once('@')
+                .map(|(user, _)| user.to_string())
+                .unwrap_or_else(crate::config::get_default_username),
         }
     }
 
@@ -387,6 +419,131 @@ impl Executor {
     pub fn is_local(&self) -> bool {
         matches!(self, Executor::Local)
     }
+
+    /// Limit file uploads to remote hosts to `kbps` kilobits per second (local writes are unaffected)
+    pub fn with_bwlimit(self, kbps: Option<u32>) -> Self {
+        match self {
+            Executor::Local => Executor::Local,
+            Executor::Remote(conn) => Executor::Remote(conn.with_bwlimit(kbps)),
+        }
+    }
+
+    /// Run a command interactively and return its exit status instead of failing on non-zero
+    pub fn execute_interactive_status(&self, program: &str, args: &[&str]) -> Result<ExitStatus> {
+        match self {
+            Executor::Local => Command::new(program)
+                .args(args)
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .with_context(|| format!("Failed to execute command: {}", program)),
+            Executor::Remote(exec) => exec.execute_interactive_status(program, args),
+        }
+    }
+
+    /// Run a shell command with a wall-clock deadline (`Ok(None)` if it timed out and was killed)
+    pub fn execute_shell_timeout(
+        &self,
+        command: &str,
+        timeout: std::time::Duration,
+    ) -> Result<Option<Output>> {
+        match self {
+            Executor::Local => local::execute_shell_timeout(command, timeout),
+            Executor::Remote(exec) => exec.execute_simple_timeout(
+                "sh",
+                &["-c", &crate::utils::ssh::shell_escape(command)],
+                timeout,
+            ),
+        }
+    }
+
+    /// Start `sh -c <command>` with the given stdin/stdout, for streaming data to or from the host
+    pub fn spawn_shell(&self, command: &str, stdin: Stdio, stdout: Stdio) -> Result<Child> {
+        match self {
+            Executor::Local => Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .stdin(stdin)
+                .stdout(stdout)
+                .stderr(Stdio::inherit())
+                .spawn()
+                .context("Failed to spawn shell command"),
+            Executor::Remote(exec) => exec.spawn_shell(command, stdin, stdout),
+        }
+    }
+
+    /// Run `sh -c <command>` interactively and return its exit status
+    pub fn execute_shell_interactive_status(&self, command: &str) -> Result<ExitStatus> {
+        match self {
+            Executor::Local => Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .context("Failed to execute shell command"),
+            Executor::Remote(exec) => exec.execute_shell_interactive_status(command),
+        }
+    }
+}
+
+/// Open an SSH connection to the first of the host's addresses that answers
+/// Fails up front, listing every address tried, instead of stalling on later commands
+fn connect_reachable(
+    hostname: &str,
+    host_config: &crate::config::HostConfig,
+    config: &crate::config::EnvConfig,
+) -> Result<SshConnection> {
+    let addresses = crate::utils::ssh::host_addresses(host_config, &config._tailnet_base);
+    if addresses.is_empty() {
+        anyhow::bail!("No IP or Tailscale hostname configured for {}", hostname);
+    }
+
+    let user = host_config.ssh_user_or_default();
+    let jump = crate::utils::ssh::resolve_jump_host(hostname, host_config, config);
+    let port = host_config.ssh_port.unwrap_or(22);
+    let mut answered = false;
+    for (address, _) in &addresses {
+        // Skip addresses that don't answer at all rather than waiting on an SSH timeout.
+        // Behind a jump host the target isn't directly reachable, and names only SSH can
+        // resolve (ssh_config aliases, ProxyCommand) can't be probed, so let SSH decide
+        if jump.is_none()
+            && crate::utils::networking::resolves(address, port)
+            && !crate::utils::networking::probe_host(address, port, PROBE_TIMEOUT).reachable
+        {
+            continue;
+        }
+        answered = true;
+        let ssh_conn = SshConnection::new_with_options(
+            &format!("{}@{}", user, address),
+            host_config.ssh_port,
+            jump.as_deref(),
+        )?;
+        if ssh_conn.is_reachable() {
+            return Ok(ssh_conn);
+        }
+    }
+
+    let tried: Vec<&str> = addresses.iter().map(|(_, label)| label.as_str()).collect();
+    if jump.is_none() && !answered {
+        anyhow::bail!(
+            "Host {} is unreachable (no answer on port {} or to ping). Tried:\n  {}",
+            hostname,
+            port,
+            tried.join("\n  ")
+        );
+    }
+    let via = jump
+        .map(|jump| format!(" via jump host {}", jump))
+        .unwrap_or_default();
+    anyhow::bail!(
+        "Host {} is unreachable over SSH{}. Tried:\n  {}",
+        hostname,
+        via,
+        tried.join("\n  ")
+    );
 }
 
 impl CommandExecutor for Executor {
@@ -529,6 +686,186 @@ impl CommandExecutor for Executor {
     }
 }
 
+/// Executor wrapper that prints commands instead of running them
+///
+/// Every command execution and write is logged to stdout prefixed with the target host
+/// and reported as successful. Read-only queries (`read_file`, `file_exists`,
+/// `check_command_exists`, etc.) and commands that only inspect the host (see
+/// [`is_read_only_probe`]) still run against the wrapped executor so detection
+/// logic behaves the same as a real run. Files "written" during the dry run are
+/// remembered so later existence checks for them succeed.
+pub struct DryRunExecutor<'a, E: CommandExecutor> {
+    inner: &'a E,
+    host: String,
+    written_files: RefCell<HashSet<String>>,
+}
+
+impl<'a, E: CommandExecutor> DryRunExecutor<'a, E> {
+    pub fn new(inner: &'a E, host: &str) -> Self {
+        Self {
+            inner,
+            host: host.to_string(),
+            written_files: RefCell::new(HashSet::new()),
+        }
+    }
+
+    fn log(&self, command: &str) {
+        println!("[dry-run] {}$ {}", self.host, command);
+    }
+
+    fn log_program(&self, program: &str, args: &[&str]) {
+        if args.is_empty() {
+            self.log(program);
+        } else {
+            self.log(&format!("{} {}", program, args.join(" ")));
+        }
+    }
+}
+
+/// Whether a command only inspects the host (versions, status, architecture, ...)
+/// A dry run still runs these so "is it installed?" checks see the real host
+pub fn is_read_only_probe(program: &str, args: &[&str]) -> bool {
+    match (program, args) {
+        // `sudo -n` fails instead of prompting, which doesn't change what the command does
+        ("sudo", ["-n", program, args @ ..]) => is_read_only_probe(program, args),
+        ("sudo", [program, args @ ..]) if !program.starts_with('-') => {
+            is_read_only_probe(program, args)
+        }
+        (_, ["--version" | "-V"]) => true,
+        (
+            "uname" | "id" | "groups" | "whoami" | "hostname" | "lsb_release" | "which" | "cat"
+            | "ls" | "test" | "stat" | "getent" | "ss" | "lsof" | "true",
+            _,
+        ) => true,
+        ("dpkg", ["--print-architecture" | "-l" | "-s", ..]) => true,
+        ("systemctl", ["is-active" | "is-enabled" | "status" | "show", ..]) => true,
+        ("docker", ["version" | "info" | "ps" | "inspect" | "images", ..]) => true,
+        ("docker", ["compose", "version" | "ls" | "ps", ..]) => true,
+        (
+            "docker",
+            [
+                "container" | "image" | "volume" | "network",
+                "ls" | "inspect",
+                ..,
+            ],
+        ) => true,
+        ("tailscale", ["version" | "status" | "ip", ..]) => true,
+        _ => false,
+    }
+}
+
+/// [`is_read_only_probe`] for a shell command, which only qualifies when it is a single
+/// plain command (no pipes, redirects, substitutions or chaining)
+fn is_read_only_shell_probe(command: &str) -> bool {
+    if command.contains([';', '&', '|', '>', '<', '`', '$', '(', '\n']) {
+        return false;
+    }
+    let words: Vec<&str> = command.split_whitespace().collect();
+    match words.split_first() {
+        Some((program, args)) => is_read_only_probe(program, args),
+        None => false,
+    }
+}
+
+/// Build a successful `Output` with no stdout/stderr
+fn successful_output() -> Output {
+    #[cfg(unix)]
+    let status = {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatus::from_raw(0)
+    };
+    #[cfg(windows)]
+    let status = {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatus::from_raw(0)
+    };
+    Output {
+        status,
+        stdout: Vec::new(),
+        stderr: Vec::new(),
+    }
+}
+
+impl<E: CommandExecutor> CommandExecutor for DryRunExecutor<'_, E> {
+    fn execute_simple(&self, program: &str, args: &[&str]) -> Result<Output> {
+        if is_read_only_probe(program, args) {
+            return self.inner.execute_simple(program, args);
+        }
+        self.log_program(program, args);
+        Ok(successful_output())
+    }
+
+    fn execute_shell(&self, command: &str) -> Result<Output> {
+        if is_read_only_shell_probe(command) {
+            return self.inner.execute_shell(command);
+        }
+        self.log(command);
+        Ok(successful_output())
+    }
+
+    fn execute_interactive(&self, program: &str, args: &[&str]) -> Result<()> {
+        self.log_program(program, args);
+        Ok(())
+    }
+
+    fn check_command_exists(&self, command: &str) -> Result<bool> {
+        self.inner.check_command_exists(command)
+    }
+
+    fn is_linux(&self) -> Result<bool> {
+        self.inner.is_linux()
+    }
+
+    fn read_file(&self, path: &str) -> Result<String> {
+        self.inner.read_file(path)
+    }
+
+    fn write_file(&self, path: &str, content: &[u8]) -> Result<()> {
+        self.log(&format!("write {} ({} bytes)", path, content.len()));
+        self.written_files.borrow_mut().insert(path.to_string());
+        Ok(())
+    }
+
+    fn mkdir_p(&self, path: &str) -> Result<()> {
+        self.log(&format!("mkdir -p {}", path));
+        Ok(())
+    }
+
+    fn file_exists(&self, path: &str) -> Result<bool> {
+        if self.written_files.borrow().contains(path) {
+            return Ok(true);
+        }
+        self.inner.file_exists(path)
+    }
+
+    fn execute_shell_interactive(&self, command: &str) -> Result<()> {
+        self.log(command);
+        Ok(())
+    }
+
+    fn get_username(&self) -> Result<String> {
+        self.inner.get_username()
+    }
+
+    fn list_directory(&self, path: &str) -> Result<Vec<String>> {
+        self.inner.list_directory(path)
+    }
+
+    fn is_directory(&self, path: &str) -> Result<bool> {
+        self.inner.is_directory(path)
+    }
+
+    #[cfg(unix)]
+    fn get_uid(&self) -> Result<u32> {
+        self.inner.get_uid()
+    }
+
+    #[cfg(unix)]
+    fn get_gid(&self) -> Result<u32> {
+        self.inner.get_gid()
+    }
+}
+
 /// Remote command executor (SSH) - SshConnection already implements CommandExecutor
 impl CommandExecutor for SshConnection {
     fn execute_simple(&self, program: &str, args: &[&str]) -> Result<Output> {
@@ -595,3 +932,63 @@ impl CommandExecutor for SshConnection {
         SshConnection::get_gid(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inspection_commands_are_read_only_probes() {
+        assert!(is_read_only_probe("docker", &["--version"]));
+        assert!(is_read_only_probe("sudo", &["docker", "info"]));
+        assert!(is_read_only_probe("sudo", &["-n", "true"]));
+        assert!(is_read_only_probe("dpkg", &["--print-architecture"]));
+        assert!(is_read_only_probe("docker", &["compose", "version"]));
+        assert!(is_read_only_probe("systemctl", &["is-active", "docker"]));
+    }
+
+    #[test]
+    fn changes_are_not_read_only_probes() {
+        assert!(!is_read_only_probe(
+            "sudo",
+            &["apt-get", "install", "-y", "docker-ce"]
+        ));
+        assert!(!is_read_only_probe("docker", &["compose", "up", "-d"]));
+        assert!(!is_read_only_probe("systemctl", &["enable", "docker"]));
+        assert!(!is_read_only_probe("sudo", &["-u", "root", "id"]));
+    }
+
+    #[test]
+    fn only_plain_shell_commands_are_probes() {
+        assert!(is_read_only_shell_probe("docker --version"));
+        assert!(is_read_only_shell_probe("sudo docker info"));
+        assert!(!is_read_only_shell_probe(
+            "docker --version && rm -rf /tmp/x"
+        ));
+        assert!(!is_read_only_shell_probe("cat /etc/os-release > /tmp/out"));
+        assert!(!is_read_only_shell_probe(
+            "curl -fsSL https://get.docker.com | sh"
+        ));
+        assert!(!is_read_only_shell_probe(""));
+    }
+
+    #[test]
+    fn timed_commands_with_large_output_finish() {
+        // Well past the 64 KiB a pipe buffers
+        let output = local::execute_shell_timeout(
+            "head -c 1000000 /dev/zero",
+            std::time::Duration::from_secs(10),
+        )
+        .unwrap()
+        .expect("command should finish before the timeout");
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), 1_000_000);
+    }
+
+    #[test]
+    fn timed_commands_are_killed_at_the_deadline() {
+        let output =
+            local::execute_shell_timeout("sleep 5", std::time::Duration::from_millis(200)).unwrap();
+        assert!(output.is_none());
+    }
+}
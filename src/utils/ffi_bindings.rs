@@ -2,12 +2,56 @@
 // This module contains functions to generate platform-specific bindings from Rust FFI code
 
 use quote::ToTokens;
+use std::collections::BTreeSet;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 
+/// Swift bindings file, relative to `root` (the crate directory)
+pub fn swift_bindings_file(root: &Path) -> PathBuf {
+    root.join("halvor-swift")
+        .join("Sources")
+        .join("HalvorSwiftFFI")
+        .join("halvor_ffi")
+        .join("generated_swift_bindings.swift")
+}
+
+/// Kotlin bindings file, relative to `root` (the crate directory)
+pub fn kotlin_bindings_file(root: &Path) -> PathBuf {
+    root.join("halvor-android")
+        .join("src")
+        .join("main")
+        .join("kotlin")
+        .join("dev")
+        .join("scottkey")
+        .join("halvor")
+        .join("GeneratedBindings.kt")
+}
+
+/// TypeScript bindings file for the web app, relative to `root` (the crate directory)
+pub fn wasm_bindings_file(root: &Path) -> PathBuf {
+    root.join("halvor-web")
+        .join("src")
+        .join("lib")
+        .join("halvor-ffi")
+        .join("generated-bindings.ts")
+}
+
+/// Write generated bindings, creating the parent directory
+fn write_bindings(path: &Path, code: String, kind: &str) -> Result<()> {
+    if let Some(output_dir) = path.parent() {
+        fs::create_dir_all(output_dir).with_context(|| {
+            format!(
+                "Failed to create {} output directory: {:?}",
+                kind, output_dir
+            )
+        })?;
+    }
+    fs::write(path, code).with_context(|| format!("Failed to write {} bindings", kind))
+}
+
 /// Generate FFI bindings (for use in build.rs or CLI)
 pub fn generate_ffi_bindings() {
     if let Err(e) = generate_ffi_bindings_cli() {
@@ -15,8 +59,68 @@ pub fn generate_ffi_bindings() {
     }
 }
 
+/// A binding file as the current FFI source would generate it
+pub struct RenderedBindings {
+    pub kind: &'static str,
+    pub path: PathBuf,
+    pub code: String,
+}
+
+/// How regenerating changed (or would change) one binding file
+pub struct BindingsChange {
+    pub path: PathBuf,
+    /// Exported names new in this file
+    pub added: Vec<String>,
+    /// Exported names no longer in this file
+    pub removed: Vec<String>,
+}
+
 /// Generate FFI bindings (CLI version with proper error handling)
 pub fn generate_ffi_bindings_cli() -> Result<()> {
+    for bindings in render_ffi_bindings()? {
+        write_bindings(&bindings.path, bindings.code, bindings.kind)?;
+    }
+    Ok(())
+}
+
+/// Regenerate the binding files, reporting each one whose contents change
+/// With `check`, nothing is written, so stale bindings can be caught in CI
+pub fn regenerate_ffi_bindings(check: bool) -> Result<Vec<BindingsChange>> {
+    let mut changes = Vec::new();
+    for bindings in render_ffi_bindings()? {
+        let current = fs::read_to_string(&bindings.path).unwrap_or_default();
+        if current == bindings.code {
+            continue;
+        }
+        let before = exported_names(&current);
+        let after = exported_names(&bindings.code);
+        changes.push(BindingsChange {
+            path: bindings.path.clone(),
+            added: after.difference(&before).cloned().collect(),
+            removed: before.difference(&after).cloned().collect(),
+        });
+        if !check {
+            write_bindings(&bindings.path, bindings.code, bindings.kind)?;
+        }
+    }
+    Ok(changes)
+}
+
+/// Function names listed in a generated file
+/// (`// - name` in Swift and Kotlin, `name(): Promise<any>;` in TypeScript)
+fn exported_names(code: &str) -> BTreeSet<String> {
+    code.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("// - ")
+                .or_else(|| line.strip_suffix("(): Promise<any>;"))
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+/// Scan src/ffi and render every binding file (none when nothing is exported)
+fn render_ffi_bindings() -> Result<Vec<RenderedBindings>> {
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| {
         env::current_dir()
             .map(|p| p.to_string_lossy().to_string())
@@ -25,7 +129,7 @@ pub fn generate_ffi_bindings_cli() -> Result<()> {
     let ffi_dir = Path::new("src/ffi");
 
     if !ffi_dir.exists() {
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     // Use println! for CLI, cargo:warning for build.rs
@@ -39,6 +143,8 @@ pub fn generate_ffi_bindings_cli() -> Result<()> {
     // Collect Rust files
     let mut rust_files = Vec::new();
     collect_rust_files(ffi_dir, &mut rust_files);
+    // Stable order so regenerating unchanged source yields identical files
+    rust_files.sort();
 
     // Parse and extract
     let mut functions = Vec::new();
@@ -68,14 +174,27 @@ pub fn generate_ffi_bindings_cli() -> Result<()> {
 
     log(&format!("Found {} FFI functions", functions.len()));
 
-    // Generate bindings
-    if !functions.is_empty() {
-        generate_swift_bindings(&manifest_dir, &functions, &structs)?;
-        generate_kotlin_bindings(&manifest_dir, &functions, &structs)?;
-        generate_wasm_bindings(&manifest_dir, &functions, &structs)?;
+    if functions.is_empty() {
+        return Ok(Vec::new());
     }
-
-    Ok(())
+    let root = Path::new(&manifest_dir);
+    Ok(vec![
+        RenderedBindings {
+            kind: "Swift",
+            path: swift_bindings_file(root),
+            code: render_swift_bindings(&functions, &structs),
+        },
+        RenderedBindings {
+            kind: "Kotlin",
+            path: kotlin_bindings_file(root),
+            code: render_kotlin_bindings(&functions, &structs),
+        },
+        RenderedBindings {
+            kind: "WASM TypeScript",
+            path: wasm_bindings_file(root),
+            code: render_wasm_bindings(&functions, &structs),
+        },
+    ])
 }
 
 fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
@@ -231,11 +350,10 @@ fn extract_ffi_items(
     }
 }
 
-fn generate_swift_bindings(
-    manifest_dir: &str,
+fn render_swift_bindings(
     functions: &[(String, Vec<String>)],
     structs: &[(String, Vec<(String, String)>)],
-) -> Result<()> {
+) -> String {
     let swift_funcs: Vec<_> = functions
         .iter()
         .filter(|(_, platforms)| platforms.contains(&"swift".to_string()))
@@ -259,24 +377,13 @@ fn generate_swift_bindings(
         code.push_str(&format!("// - {}\n", func_name));
     }
 
-    let output_dir = PathBuf::from(manifest_dir)
-        .join("halvor-swift")
-        .join("Sources")
-        .join("HalvorSwiftFFI")
-        .join("halvor_ffi");
-
-    fs::create_dir_all(&output_dir)
-        .with_context(|| format!("Failed to create Swift output directory: {:?}", output_dir))?;
-    fs::write(output_dir.join("generated_swift_bindings.swift"), code)
-        .with_context(|| "Failed to write Swift bindings")?;
-    Ok(())
+    code
 }
 
-fn generate_kotlin_bindings(
-    manifest_dir: &str,
+fn render_kotlin_bindings(
     functions: &[(String, Vec<String>)],
     structs: &[(String, Vec<(String, String)>)],
-) -> Result<()> {
+) -> String {
     let kotlin_funcs: Vec<_> = functions
         .iter()
         .filter(|(_, platforms)| platforms.contains(&"kotlin".to_string()))
@@ -300,27 +407,13 @@ fn generate_kotlin_bindings(
         code.push_str(&format!("// - {}\n", func_name));
     }
 
-    let output_dir = PathBuf::from(manifest_dir)
-        .join("halvor-android")
-        .join("src")
-        .join("main")
-        .join("kotlin")
-        .join("dev")
-        .join("scottkey")
-        .join("halvor");
-
-    fs::create_dir_all(&output_dir)
-        .with_context(|| format!("Failed to create Kotlin output directory: {:?}", output_dir))?;
-    fs::write(output_dir.join("GeneratedBindings.kt"), code)
-        .with_context(|| "Failed to write Kotlin bindings")?;
-    Ok(())
+    code
 }
 
-fn generate_wasm_bindings(
-    manifest_dir: &str,
+fn render_wasm_bindings(
     functions: &[(String, Vec<String>)],
     structs: &[(String, Vec<(String, String)>)],
-) -> Result<()> {
+) -> String {
     let wasm_funcs: Vec<_> = functions
         .iter()
         .filter(|(_, platforms)| platforms.contains(&"wasm".to_string()))
@@ -344,17 +437,7 @@ fn generate_wasm_bindings(
     }
     code.push_str("}\n");
 
-    let output_dir = PathBuf::from(manifest_dir)
-        .join("halvor-web")
-        .join("src")
-        .join("lib")
-        .join("halvor-ffi");
-
-    fs::create_dir_all(&output_dir)
-        .with_context(|| format!("Failed to create WASM output directory: {:?}", output_dir))?;
-    fs::write(output_dir.join("generated-bindings.ts"), code)
-        .with_context(|| "Failed to write WASM TypeScript bindings")?;
-    Ok(())
+    code
 }
 
 fn to_camel_case(snake: &str) -> String {
@@ -0,0 +1,51 @@
+// Leveled output for status messages
+//
+// Status and progress lines go through the `log` macros (info!, warn!, debug!) so `-q` and
+// `-v`/`-vv` can control them. Command results (tables, config dumps, prompts) stay on println!.
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+struct Logger;
+
+static LOGGER: Logger = Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Only our own debug detail; dependencies (reqwest, rustls, ...) are far too chatty
+        metadata.level() <= Level::Info || metadata.target().starts_with("halvor")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // Messages carry their own ✓/⚠/✗ markers, so info and warnings print as-is
+        match record.level() {
+            Level::Info => println!("{}", record.args()),
+            Level::Warn | Level::Error => eprintln!("{}", record.args()),
+            Level::Debug => eprintln!("[DEBUG] {}", record.args()),
+            Level::Trace => eprintln!("[TRACE] {}", record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Map `-q` / `-v` counts to a level: quiet shows warnings and errors only,
+/// the default adds progress, `-v` adds debug detail and `-vv` adds trace
+pub fn level_for(verbose: u8, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::Warn;
+    }
+    match verbose {
+        0 => LevelFilter::Info,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Install the logger; call once at startup
+pub fn init(level: LevelFilter) {
+    // Ignore a second init (e.g. from tests or embedding) and just update the level
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+}
@@ -70,9 +70,10 @@ pub fn generate_migrations_cli() -> Result<()> {
         let has_down = version != &1 || name != "initial_schema";
 
         migrations_array.push_str(&format!(
-            "    Migration {{\n        version: {},\n        name: \"{}\",\n        up: {}::up,\n        down: {},\n    }},\n",
+            "    Migration {{\n        version: {},\n        name: \"{}\",\n        source: include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/src/db/migrations/{}\")),\n        up: {}::up,\n        down: {},\n    }},\n",
             version,
             name,
+            file_name,
             mod_name,
             if has_down {
                 format!("Some({}::down)", mod_name)
@@ -4,8 +4,10 @@ pub mod env;
 pub mod exec;
 pub mod ffi_bindings;
 pub mod json_stream;
+pub mod logging;
 pub mod migrations;
 pub mod networking;
+pub mod progress;
 pub mod service;
 pub mod ssh;
 pub mod string;
@@ -14,4 +16,6 @@ pub mod update;
 // Re-export commonly used utilities
 pub use json_stream::{read_json, send_json_request, write_json};
 pub use service::{DockerOps, FileOps, HostConfigOps, ServiceContext};
-pub use string::{bytes_to_string, bytes_to_string_strict, format_address, format_bind_address};
+pub use string::{
+    bytes_to_string, bytes_to_string_strict, format_address, format_bind_address, format_bytes,
+};
@@ -1,5 +1,7 @@
-use crate::utils::exec::local;
+use crate::utils::exec::{CommandExecutor, local};
 use anyhow::Result;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
 
 /// Get all local IP addresses
 pub fn get_local_ips() -> Result<Vec<String>> {
@@ -165,3 +167,302 @@ pub fn get_tailscale_ips() -> Result<Vec<String>> {
 
     Ok(ips)
 }
+
+/// Route the system uses for outbound traffic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefaultRoute {
+    pub interface: String,
+    /// Source address the kernel picks for outbound packets
+    pub source_ip: Option<String>,
+    pub gateway: Option<String>,
+}
+
+/// Parse `ip route get <addr>` output (Linux)
+/// e.g. `1.1.1.1 via 192.168.1.1 dev eth0 src 192.168.1.50 uid 1000`
+#[cfg(target_os = "linux")]
+fn parse_ip_route_get(output: &str) -> Option<DefaultRoute> {
+    let tokens: Vec<&str> = output.split_whitespace().collect();
+    let value_after = |key: &str| {
+        tokens
+            .iter()
+            .position(|t| *t == key)
+            .and_then(|i| tokens.get(i + 1))
+            .map(|v| v.to_string())
+    };
+    Some(DefaultRoute {
+        interface: value_after("dev")?,
+        source_ip: value_after("src"),
+        gateway: value_after("via"),
+    })
+}
+
+/// Parse `route -n get default` output (macOS)
+/// Lines look like `  interface: en0` and `    gateway: 192.168.1.1`
+#[cfg(target_os = "macos")]
+fn parse_route_get_default(output: &str) -> Option<DefaultRoute> {
+    let value_of = |key: &str| {
+        output.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            (name.trim() == key).then(|| value.trim().to_string())
+        })
+    };
+    Some(DefaultRoute {
+        interface: value_of("interface")?,
+        source_ip: None,
+        gateway: value_of("gateway"),
+    })
+}
+
+/// Get the route used for outbound traffic, or None when the route command isn't available
+pub fn get_default_route() -> Result<Option<DefaultRoute>> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(output) = local::execute("ip", &["route", "get", "1.1.1.1"])
+            && output.status.success()
+        {
+            return Ok(parse_ip_route_get(&String::from_utf8_lossy(&output.stdout)));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Ok(output) = local::execute("route", &["-n", "get", "default"])
+            && output.status.success()
+        {
+            let Some(mut route) = parse_route_get_default(&String::from_utf8_lossy(&output.stdout))
+            else {
+                return Ok(None);
+            };
+            // `route get` doesn't report the source address; ask for the interface's address
+            if let Ok(output) = local::execute("ipconfig", &["getifaddr", &route.interface]) {
+                let ip = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                if output.status.success() && !ip.is_empty() {
+                    route.source_ip = Some(ip);
+                }
+            }
+            return Ok(Some(route));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get the interface used for outbound traffic (e.g. `eth0`, `en0`)
+pub fn get_default_route_interface() -> Result<Option<String>> {
+    Ok(get_default_route()?.map(|route| route.interface))
+}
+
+/// Get the local IP actually used for outbound traffic
+pub fn get_primary_ip() -> Result<Option<String>> {
+    Ok(get_default_route()?.and_then(|route| route.source_ip))
+}
+
+/// How a host answered a reachability probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeMethod {
+    Tcp,
+    Icmp,
+}
+
+/// Outcome of `probe_host`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProbeResult {
+    pub reachable: bool,
+    pub latency: Option<Duration>,
+    /// None when the host didn't answer at all
+    pub method: Option<ProbeMethod>,
+}
+
+impl ProbeResult {
+    const UNREACHABLE: ProbeResult = ProbeResult {
+        reachable: false,
+        latency: None,
+        method: None,
+    };
+
+    /// Latency in whole milliseconds, for display
+    pub fn latency_ms(&self) -> Option<u64> {
+        self.latency.map(|latency| latency.as_millis() as u64)
+    }
+}
+
+/// Whether the system resolver knows an address
+/// Names that only SSH can resolve (ssh_config `Host` aliases) don't
+pub fn resolves(addr: &str, port: u16) -> bool {
+    (addr, port)
+        .to_socket_addrs()
+        .is_ok_and(|mut addrs| addrs.next().is_some())
+}
+
+/// Check whether a host answers on the network, without logging in
+///
+/// Tries a TCP connect to `port` first (no root needed); a refused connection still
+/// proves the host is up. Falls back to a single ICMP ping, which may not be permitted
+/// everywhere. Each attempt is bounded by `timeout`.
+pub fn probe_host(addr: &str, port: u16, timeout: Duration) -> ProbeResult {
+    let Ok(socket_addrs) = (addr, port).to_socket_addrs() else {
+        // Unresolvable names won't answer a ping either
+        return ProbeResult::UNREACHABLE;
+    };
+
+    for socket_addr in socket_addrs {
+        let start = Instant::now();
+        match TcpStream::connect_timeout(&socket_addr, timeout) {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {}
+            Err(_) => continue,
+        }
+        return ProbeResult {
+            reachable: true,
+            latency: Some(start.elapsed()),
+            method: Some(ProbeMethod::Tcp),
+        };
+    }
+
+    ping_host(addr, timeout)
+}
+
+/// Send a single ICMP echo request with the system `ping`
+fn ping_host(addr: &str, timeout: Duration) -> ProbeResult {
+    let secs = timeout.as_secs().max(1).to_string();
+    let millis = timeout.as_millis().max(1).to_string();
+    let args: Vec<&str> = if cfg!(target_os = "windows") {
+        vec!["-n", "1", "-w", &millis, addr]
+    } else if cfg!(target_os = "macos") {
+        vec!["-c", "1", "-t", &secs, addr]
+    } else {
+        vec!["-c", "1", "-W", &secs, addr]
+    };
+
+    let start = Instant::now();
+    match local::execute("ping", &args) {
+        Ok(output) if output.status.success() => {
+            let elapsed = start.elapsed();
+            let latency =
+                parse_ping_time(&String::from_utf8_lossy(&output.stdout)).unwrap_or(elapsed);
+            ProbeResult {
+                reachable: true,
+                latency: Some(latency),
+                method: Some(ProbeMethod::Icmp),
+            }
+        }
+        _ => ProbeResult::UNREACHABLE,
+    }
+}
+
+/// Parse the round-trip time from a ping reply line
+/// e.g. `64 bytes from 192.168.1.5: icmp_seq=1 ttl=64 time=0.412 ms` or `time<1ms`
+fn parse_ping_time(output: &str) -> Option<Duration> {
+    let (_, rest) = output.split_once("time")?;
+    let value: String = rest
+        .trim_start_matches(['=', '<'])
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let ms: f64 = value.parse().ok()?;
+    Some(Duration::from_secs_f64(ms / 1000.0))
+}
+
+/// Find what is listening on a TCP port on the executor's host
+/// Returns None when the port is free, or the listening process when it can be determined
+/// Uses `ss`, then `netstat`, then `lsof`, whichever is installed
+pub fn port_listener<E: CommandExecutor>(exec: &E, port: u16) -> Result<Option<String>> {
+    let script = format!(
+        "if command -v ss >/dev/null 2>&1; then ss -Hltnp 'sport = :{port}'; \
+         elif command -v netstat >/dev/null 2>&1; then netstat -ltnp 2>/dev/null | awk '$4 ~ /[:.]{port}$/'; \
+         elif command -v lsof >/dev/null 2>&1; then lsof -nP -iTCP:{port} -sTCP:LISTEN | tail -n +2; fi",
+        port = port
+    );
+    let output = exec.execute_shell(&script)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(line) = stdout.lines().find(|l| !l.trim().is_empty()) else {
+        return Ok(None);
+    };
+    Ok(Some(
+        parse_listener_process(line).unwrap_or_else(|| "an unknown process".to_string()),
+    ))
+}
+
+/// Pull the process name out of an `ss -p`, `netstat -p`, or `lsof` line
+fn parse_listener_process(line: &str) -> Option<String> {
+    // ss: users:(("nginx",pid=1234,fd=6))
+    if let Some(start) = line.find("((\"") {
+        let rest = &line[start + 3..];
+        return rest.split('"').next().map(|name| name.to_string());
+    }
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    // netstat: last column is pid/program, or "-" without permission
+    if let Some(last) = fields.last()
+        && let Some((_, program)) = last.split_once('/')
+    {
+        return Some(program.to_string());
+    }
+    // lsof: first column is the command
+    if fields.len() > 8 {
+        return fields.first().map(|name| name.to_string());
+    }
+    None
+}
+
+/// Check whether a TCP port is free on the executor's host
+/// A port that can't be checked (no ss/netstat/lsof) counts as free
+pub fn is_port_free<E: CommandExecutor>(exec: &E, port: u16) -> bool {
+    matches!(port_listener(exec, port), Ok(None))
+}
+
+/// Make sure every port a service needs is free, or fail naming what holds it
+/// With `ignore_conflicts`, conflicts are only reported
+pub fn ensure_ports_free<E: CommandExecutor>(
+    exec: &E,
+    ports: &[u16],
+    ignore_conflicts: bool,
+) -> Result<()> {
+    let mut conflicts = Vec::new();
+    for &port in ports {
+        if let Ok(Some(process)) = port_listener(exec, port) {
+            conflicts.push(format!("port {} already in use by {}", port, process));
+        }
+    }
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+
+    if ignore_conflicts {
+        for conflict in &conflicts {
+            println!("⚠ {} (continuing: --ignore-port-conflicts)", conflict);
+        }
+        return Ok(());
+    }
+    anyhow::bail!(
+        "{}\nStop the conflicting service or re-run with --ignore-port-conflicts",
+        conflicts.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_resolvable_names_are_probed() {
+        assert!(resolves("127.0.0.1", 22));
+        assert!(resolves("::1", 22));
+        assert!(!resolves("no-such-host.invalid", 22));
+    }
+
+    #[test]
+    fn listener_process_is_read_from_ss_netstat_and_lsof() {
+        let ss = r#"LISTEN 0 511 0.0.0.0:443 0.0.0.0:* users:(("nginx",pid=1234,fd=6))"#;
+        let netstat = "tcp 0 0 0.0.0.0:81 0.0.0.0:* LISTEN 987/node";
+        let lsof = "docker-pr 4242 root 4u IPv4 0x1 0t0 TCP *:9443 (LISTEN)";
+
+        assert_eq!(parse_listener_process(ss).as_deref(), Some("nginx"));
+        assert_eq!(parse_listener_process(netstat).as_deref(), Some("node"));
+        assert_eq!(parse_listener_process(lsof).as_deref(), Some("docker-pr"));
+        // netstat without permission to see the owner
+        assert_eq!(
+            parse_listener_process("tcp 0 0 0.0.0.0:81 0.0.0.0:* LISTEN -"),
+            None
+        );
+    }
+}
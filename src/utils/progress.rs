@@ -0,0 +1,78 @@
+// Progress indicators for long transfers and multi-step operations
+//
+// Bars and spinners draw on stderr, and only when stderr is a terminal and output isn't
+// quieted with -q, so piped output and logs stay clean. Step counters are status lines and
+// follow the same -q rules as other progress messages.
+use indicatif::{ProgressBar, ProgressStyle};
+use log::info;
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Transfers smaller than this finish too quickly for a bar to be useful
+pub const MIN_BAR_BYTES: u64 = 1024 * 1024;
+
+/// How often spinners redraw
+const TICK: Duration = Duration::from_millis(120);
+
+/// Whether progress indicators should be drawn
+pub fn enabled() -> bool {
+    log::log_enabled!(log::Level::Info) && std::io::stderr().is_terminal()
+}
+
+/// A byte-counted bar for a transfer of `total` bytes (hidden for small transfers)
+pub fn bytes(total: u64, message: &str) -> ProgressBar {
+    if !enabled() || total < MIN_BAR_BYTES {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new(total);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "    {msg} [{bar:30}] {bytes}/{total_bytes} {binary_bytes_per_sec} eta {eta}",
+        )
+        .expect("valid progress template")
+        .progress_chars("=> "),
+    );
+    bar.set_message(message.to_string());
+    bar
+}
+
+/// A spinner with elapsed time, for work whose size isn't known up front
+pub fn spinner(message: &str) -> ProgressBar {
+    if !enabled() {
+        return ProgressBar::hidden();
+    }
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::with_template("    {spinner} {msg} ({elapsed})")
+            .expect("valid progress template"),
+    );
+    spinner.set_message(message.to_string());
+    spinner.enable_steady_tick(TICK);
+    spinner
+}
+
+/// Run `f` with a spinner showing, then clear it so the caller's result line takes its place
+pub fn with_spinner<T>(message: &str, f: impl FnOnce() -> T) -> T {
+    let spinner = spinner(message);
+    let result = f();
+    spinner.finish_and_clear();
+    result
+}
+
+/// "[X/Y] ..." counter for multi-stage flows
+pub struct Steps {
+    current: usize,
+    total: usize,
+}
+
+impl Steps {
+    pub fn new(total: usize) -> Self {
+        Self { current: 0, total }
+    }
+
+    /// Announce the next stage
+    pub fn next(&mut self, name: &str) {
+        self.current += 1;
+        info!("[{}/{}] {}", self.current, self.total, name);
+    }
+}
@@ -33,7 +33,7 @@ impl ServiceContext {
     pub fn new(hostname: &str, config: &EnvConfig) -> Result<Self> {
         let executor = Executor::new(hostname, config)?;
         let host_config = host::get_host_config_or_error(hostname)?;
-        let target_host = executor.target_host(hostname, config)?;
+        let target_host = executor.target_host(hostname)?;
         let is_local = executor.is_local();
 
         Ok(Self {
@@ -142,6 +142,16 @@ pub trait DockerOps: CommandExecutor + Sized {
         crate::services::docker::backup_volume(self, volume, backup_dir)
     }
 
+    /// Backup a volume incrementally (rsync, falling back to tar)
+    fn backup_volume_incremental(
+        &self,
+        volume: &str,
+        backup_dir: &str,
+        previous_dir: Option<&str>,
+    ) -> Result<crate::services::docker::VolumeBackupStrategy> {
+        crate::services::docker::backup_volume_incremental(self, volume, backup_dir, previous_dir)
+    }
+
     /// Restore a volume
     fn restore_volume(&self, volume: &str, backup_dir: &str) -> Result<()> {
         crate::services::docker::restore_volume(self, volume, backup_dir)
@@ -1,19 +1,196 @@
-use crate::config::{self, EnvConfig};
+use crate::config::config_manager::AddressFamily;
+use crate::config::{self, EnvConfig, HostConfig};
 use crate::utils::exec::local;
+use crate::utils::progress;
 use anyhow::{Context, Result};
+use log::debug;
 use std::io::{self, Write};
-use std::process::{Command, Output, Stdio};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Serializes interactive SSH sessions so prompts (sudo passwords, etc.) from
+/// hosts being operated on concurrently never interleave on the terminal
+static INTERACTIVE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Patterns in ssh's stderr that mean the connection itself failed,
+/// as opposed to the remote command exiting non-zero
+const CONNECTION_ERROR_PATTERNS: &[&str] = &[
+    "ssh: connect to host",
+    "Connection refused",
+    "Connection timed out",
+    "Connection reset",
+    "Connection closed by",
+    "Operation timed out",
+    "No route to host",
+    "Network is unreachable",
+    "Could not resolve hostname",
+    "kex_exchange_identification",
+    "Broken pipe",
+];
+
+/// Check whether an ssh invocation failed at the connection level
+pub fn is_connection_failure(output: &Output) -> bool {
+    if output.status.success() {
+        return false;
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    CONNECTION_ERROR_PATTERNS
+        .iter()
+        .any(|pattern| stderr.contains(pattern))
+}
+
+/// Seconds to wait for an SSH server to answer when checking whether a host is reachable
+const REACHABILITY_TIMEOUT_SECS: u32 = 5;
+
+/// Addresses configured for a host, with a label for each: IP, Tailscale hostname, then the
+/// Tailscale FQDN
+fn configured_addresses(host_config: &HostConfig, tailnet_base: &str) -> Vec<(String, String)> {
+    let mut addresses = Vec::new();
+    if let Some(ip) = &host_config.ip {
+        addresses.push((ip.clone(), format!("IP: {}", ip)));
+    }
+    if let Some(tailscale) = &host_config.tailscale {
+        addresses.push((tailscale.clone(), format!("Tailscale: {}", tailscale)));
+        let fqdn = format!("{}.{}", tailscale, tailnet_base);
+        addresses.push((fqdn.clone(), format!("Tailscale FQDN: {}", fqdn)));
+    }
+    addresses
+}
+
+/// Addresses a host can be reached at, in the order to try them, with a label for each:
+/// IP, Tailscale hostname, then the Tailscale FQDN
+/// With a preferred address family (`--prefer` / `hal config set-prefer`), the candidates are
+/// sorted by family: addresses of the preferred one (or names resolving to it) first, then names
+/// that didn't resolve, then the other family. The order within each group is kept, and names
+/// stay names so ssh_config and known_hosts entries for them still apply.
+pub fn host_addresses(host_config: &HostConfig, tailnet_base: &str) -> Vec<(String, String)> {
+    let addresses = configured_addresses(host_config, tailnet_base);
+    match config::config_manager::get_address_family() {
+        Some(family) => order_by_family(addresses, family, host_config.ssh_port.unwrap_or(22)),
+        None => addresses,
+    }
+}
+
+/// Stable-sort address candidates by `preferred`, resolving names only to classify them
+fn order_by_family(
+    mut addresses: Vec<(String, String)>,
+    preferred: AddressFamily,
+    port: u16,
+) -> Vec<(String, String)> {
+    use std::net::{IpAddr, ToSocketAddrs};
+
+    let rank = |address: &str| {
+        let families: Vec<AddressFamily> = match address.parse::<IpAddr>() {
+            Ok(ip) => vec![AddressFamily::of(&ip)],
+            Err(_) => match (address, port).to_socket_addrs() {
+                Ok(addrs) => addrs.map(|a| AddressFamily::of(&a.ip())).collect(),
+                Err(e) => {
+                    debug!("Could not resolve {}: {}", address, e);
+                    Vec::new()
+                }
+            },
+        };
+        if families.contains(&preferred) {
+            0
+        } else if families.is_empty() {
+            1
+        } else {
+            2
+        }
+    };
+    addresses.sort_by_cached_key(|(address, _)| rank(address));
+    debug!(
+        "Address order ({} first): {}",
+        preferred.name(),
+        addresses
+            .iter()
+            .map(|(a, _)| a.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    addresses
+}
+
+/// Jump host to reach `hostname` through: its `HOST_<name>_JUMP`, else `SSH_JUMP_HOST`
+/// Hops that name a configured host become that host's user, address and port. A host is never
+/// sent through itself, so the bastion stays reachable when `SSH_JUMP_HOST` names it
+pub fn resolve_jump_host(
+    hostname: &str,
+    host_config: &HostConfig,
+    config: &EnvConfig,
+) -> Option<String> {
+    let jump = host_config
+        .jump
+        .as_deref()
+        .or(config.ssh_jump_host.as_deref())?;
+    let mut hops = Vec::new();
+    for hop in jump.split(',') {
+        let (user, host_port) = match hop.split_once('@') {
+            Some((user, rest)) => (Some(user), rest),
+            None => (None, hop),
+        };
+        let (host, port) = config::split_host_port(host_port);
+        let Some(name) = config::service::find_hostname_in_config(host, config) else {
+            hops.push(hop.to_string());
+            continue;
+        };
+        if name.eq_ignore_ascii_case(hostname) {
+            return None;
+        }
+        let bastion = &config.hosts[&name];
+        let Some(address) = bastion.ip.as_ref().or(bastion.tailscale.as_ref()) else {
+            hops.push(hop.to_string());
+            continue;
+        };
+        let user = user
+            .map(str::to_string)
+            .unwrap_or_else(|| bastion.ssh_user_or_default());
+        let address = if address.contains(':') {
+            format!("[{}]", address)
+        } else {
+            address.clone()
+        };
+        hops.push(
+            match port
+                .map(str::to_string)
+                .or(bastion.ssh_port.map(|p| p.to_string()))
+            {
+                Some(port) => format!("{}@{}:{}", user, address, port),
+                None => format!("{}@{}", user, address),
+            },
+        );
+    }
+    Some(hops.join(","))
+}
 
 /// SSH connection for remote command execution
 pub struct SshConnection {
     pub(crate) host: String,
     pub(crate) use_key_auth: bool,
+    /// Upload rate limit for `write_file`, in kilobits per second
+    pub(crate) bwlimit_kbps: Option<u32>,
+    /// sshd port, when it isn't the default 22
+    pub(crate) port: Option<u16>,
+    /// Jump host(s) passed to `-J`, already resolved to addresses
+    pub(crate) jump: Option<String>,
+    /// Control socket of the shared master connection, when multiplexing is active
+    control_path: Option<PathBuf>,
 }
 
 impl SshConnection {
     pub fn new(host: &str) -> Result<Self> {
+        Self::new_with_options(host, None, None)
+    }
+
+    /// Connect to `host` on `port` (None = the default 22), going through `jump` when given
+    pub fn new_with_options(host: &str, port: Option<u16>, jump: Option<&str>) -> Result<Self> {
         // Test if key-based auth works
         let test_output = Command::new("ssh")
+            .args(port_args("-p", port))
+            .args(jump_args(jump))
             .args([
                 "-o",
                 "ConnectTimeout=1",
@@ -35,14 +212,76 @@ impl SshConnection {
 
         let use_key_auth = test_output.is_ok() && test_output.unwrap().status.success();
 
+        // The master needs non-interactive auth; password logins keep one connection per command
+        let control_path = if use_key_auth {
+            start_control_master(host, port, jump)
+        } else {
+            None
+        };
+
         Ok(Self {
             host: host.to_string(),
             use_key_auth,
+            bwlimit_kbps: None,
+            port,
+            jump: jump.map(str::to_string),
+            control_path,
         })
     }
 
+    /// Limit `write_file` uploads to `kbps` kilobits per second (None = unlimited)
+    pub fn with_bwlimit(mut self, kbps: Option<u32>) -> Self {
+        self.bwlimit_kbps = kbps.filter(|kbps| *kbps > 0);
+        self
+    }
+
+    /// Whether an SSH server answers at this address
+    /// Authentication doesn't have to succeed; only connection-level failures count
+    pub fn is_reachable(&self) -> bool {
+        // The key-auth probe in `new` already got through
+        if self.use_key_auth {
+            return true;
+        }
+        Command::new("ssh")
+            .args(port_args("-p", self.port))
+            .args(jump_args(self.jump.as_deref()))
+            .args([
+                "-o",
+                &format!("ConnectTimeout={}", REACHABILITY_TIMEOUT_SECS),
+                "-o",
+                "BatchMode=yes",
+                "-o",
+                "StrictHostKeyChecking=no",
+                &self.host,
+                "true",
+            ])
+            .stdin(Stdio::null())
+            .output()
+            .is_ok_and(|output| !is_connection_failure(&output))
+    }
+
     fn build_ssh_args(&self) -> Vec<String> {
+        let mut args = self.connection_options();
+        args.extend(port_args("-p", self.port));
+        args.push(self.host.clone());
+        args
+    }
+
+    /// Options shared by ssh and scp
+    fn connection_options(&self) -> Vec<String> {
         let mut args = vec!["-o".to_string(), "StrictHostKeyChecking=no".to_string()];
+        args.extend(jump_args(self.jump.as_deref()));
+
+        // ControlMaster=no reuses the master while it's alive and connects normally otherwise,
+        // so a stale socket only costs the handshake multiplexing would have saved
+        if let Some(control_path) = &self.control_path {
+            args.extend([
+                "-o".to_string(),
+                "ControlMaster=no".to_string(),
+                "-o".to_string(),
+                format!("ControlPath={}", control_path.display()),
+            ]);
+        }
 
         if self.use_key_auth {
             args.extend([
@@ -57,8 +296,6 @@ impl SshConnection {
                 "PreferredAuthentications=publickey,keyboard-interactive,password".to_string(),
             ]);
         }
-
-        args.push(self.host.clone());
         args
     }
 
@@ -82,11 +319,79 @@ impl SshConnection {
         Ok(output)
     }
 
+    /// Execute a command, retrying with exponential backoff when the SSH connection fails
+    ///
+    /// Only connection-level failures are retried (see `is_connection_failure`); a remote
+    /// command that runs and exits non-zero is returned immediately. `backoff` is the delay
+    /// before the first retry and doubles after each attempt.
+    pub fn execute_simple_retry(
+        &self,
+        program: &str,
+        args: &[&str],
+        retries: u32,
+        backoff: Duration,
+    ) -> Result<Output> {
+        let mut delay = backoff;
+        let mut attempt = 0;
+        loop {
+            let output = self.execute_simple(program, args)?;
+            if attempt >= retries || !is_connection_failure(&output) {
+                return Ok(output);
+            }
+            attempt += 1;
+            eprintln!(
+                "⚠ SSH connection to {} failed ({}), retrying in {}s [{}/{}]...",
+                self.host,
+                String::from_utf8_lossy(&output.stderr).trim(),
+                delay.as_secs_f32(),
+                attempt,
+                retries
+            );
+            std::thread::sleep(delay);
+            delay *= 2;
+        }
+    }
+
+    /// Execute a command with a wall-clock deadline
+    ///
+    /// Returns `Ok(None)` if the command (including connection setup) did not finish
+    /// within `timeout`; the ssh process is killed in that case.
+    pub fn execute_simple_timeout(
+        &self,
+        program: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> Result<Option<Output>> {
+        let connect_timeout = format!("ConnectTimeout={}", timeout.as_secs().max(1));
+        let mut ssh_args = vec![
+            "-o".to_string(),
+            connect_timeout,
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+        ];
+        ssh_args.extend(self.build_ssh_args());
+        ssh_args.push(program.to_string());
+        for arg in args {
+            ssh_args.push(arg.to_string());
+        }
+
+        let child = Command::new("ssh")
+            .args(&ssh_args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to execute command: {}", program))?;
+        local::wait_with_timeout(child, timeout)
+    }
+
     pub fn execute_shell(&self, command: &str) -> Result<Output> {
         let mut ssh_args = self.build_ssh_args();
         ssh_args.push("sh".to_string());
         ssh_args.push("-c".to_string());
-        ssh_args.push(command.to_string());
+        // ssh joins its arguments into one string for the remote shell, so quote the command
+        // to keep it a single argument to `sh -c`
+        ssh_args.push(shell_escape(command));
 
         let output = Command::new("ssh")
             .args(&ssh_args)
@@ -99,7 +404,40 @@ impl SshConnection {
         Ok(output)
     }
 
+    /// Start `sh -c <command>` on the host with the given stdin/stdout, for streaming data
+    pub fn spawn_shell(&self, command: &str, stdin: Stdio, stdout: Stdio) -> Result<Child> {
+        let mut ssh_args = self.build_ssh_args();
+        ssh_args.push("sh".to_string());
+        ssh_args.push("-c".to_string());
+        // ssh joins its arguments into one string for the remote shell, so quote the command
+        // to keep it a single argument to `sh -c`
+        ssh_args.push(shell_escape(command));
+
+        Command::new("ssh")
+            .args(&ssh_args)
+            .stdin(stdin)
+            .stdout(stdout)
+            .stderr(Stdio::inherit())
+            .spawn()
+            .context("Failed to spawn SSH command")
+    }
+
     pub fn execute_interactive(&self, program: &str, args: &[&str]) -> Result<()> {
+        let status = self.execute_interactive_status(program, args)?;
+        if !status.success() {
+            anyhow::bail!(
+                "Command '{}' failed with exit code: {}",
+                program,
+                status.code().unwrap_or(1)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Run a command with a TTY and return its exit status (non-zero is not an error)
+    pub fn execute_interactive_status(&self, program: &str, args: &[&str]) -> Result<ExitStatus> {
+        let _guard = INTERACTIVE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
         let mut ssh_args = self.build_ssh_args();
         ssh_args.push("-tt".to_string()); // Force TTY for interactive
 
@@ -109,18 +447,20 @@ impl SshConnection {
             ssh_args.push(arg.to_string());
         }
 
-        let status = Command::new("ssh")
+        Command::new("ssh")
             .args(&ssh_args)
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .status()
-            .with_context(|| format!("Failed to execute interactive command: {}", program))?;
+            .with_context(|| format!("Failed to execute interactive command: {}", program))
+    }
 
+    pub fn execute_shell_interactive(&self, command: &str) -> Result<()> {
+        let status = self.execute_shell_interactive_status(command)?;
         if !status.success() {
             anyhow::bail!(
-                "Command '{}' failed with exit code: {}",
-                program,
+                "Shell command failed with exit code: {}",
                 status.code().unwrap_or(1)
             );
         }
@@ -128,29 +468,24 @@ impl SshConnection {
         Ok(())
     }
 
-    pub fn execute_shell_interactive(&self, command: &str) -> Result<()> {
+    /// Run `sh -c <command>` with a TTY and return its exit status
+    pub fn execute_shell_interactive_status(&self, command: &str) -> Result<ExitStatus> {
+        let _guard = INTERACTIVE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
         let mut ssh_args = self.build_ssh_args();
         ssh_args.push("-tt".to_string()); // Force TTY for interactive
         ssh_args.push("sh".to_string());
         ssh_args.push("-c".to_string());
-        ssh_args.push(command.to_string());
+        // ssh joins its arguments into one string for the remote shell, so quote the command
+        // to keep it a single argument to `sh -c`
+        ssh_args.push(shell_escape(command));
 
-        let status = Command::new("ssh")
+        Command::new("ssh")
             .args(&ssh_args)
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .status()
-            .with_context(|| format!("Failed to execute interactive shell command"))?;
-
-        if !status.success() {
-            anyhow::bail!(
-                "Shell command failed with exit code: {}",
-                status.code().unwrap_or(1)
-            );
-        }
-
-        Ok(())
+            .with_context(|| format!("Failed to execute interactive shell command"))
     }
 
     pub fn check_command_exists(&self, command: &str) -> Result<bool> {
@@ -166,15 +501,123 @@ impl SshConnection {
     }
 
     pub fn read_file(&self, path: &str) -> Result<String> {
+        String::from_utf8(self.read_file_bytes(path)?)
+            .with_context(|| format!("Failed to decode file contents: {}", path))
+    }
+
+    /// Read a remote file byte for byte, over scp when available and `cat` otherwise
+    pub fn read_file_bytes(&self, path: &str) -> Result<Vec<u8>> {
+        if let Some(remote_path) = scp_remote_path(path).filter(|_| scp_available()) {
+            match self.scp_download(&remote_path) {
+                Ok(content) => return Ok(content),
+                Err(e) => debug!(
+                    "scp download of {} failed, falling back to cat: {}",
+                    path, e
+                ),
+            }
+        }
+
         let output = self.execute_simple("cat", &[path])?;
         if !output.status.success() {
             anyhow::bail!("Failed to read file: {}", path);
         }
-        String::from_utf8(output.stdout)
-            .with_context(|| format!("Failed to decode file contents: {}", path))
+        Ok(output.stdout)
     }
 
+    /// Write a remote file byte for byte, over scp when available and `cat` otherwise
     pub fn write_file(&self, path: &str, content: &[u8]) -> Result<()> {
+        let uploaded = match scp_remote_path(path).filter(|_| scp_available()) {
+            Some(remote_path) => match self.scp_upload(&remote_path, content) {
+                Ok(()) => true,
+                Err(e) => {
+                    debug!("scp upload to {} failed, falling back to cat: {}", path, e);
+                    false
+                }
+            },
+            None => false,
+        };
+        if !uploaded {
+            self.write_file_with_cat(path, content)?;
+        }
+
+        if self.bwlimit_kbps.is_some() {
+            self.verify_file_size(path, content.len())?;
+        }
+        Ok(())
+    }
+
+    /// `user@host` for scp paths, with IPv6 literals bracketed so their colons aren't read as
+    /// the path separator
+    fn scp_host(&self) -> String {
+        let (user, address) = match self.host.split_once('@') {
+            Some((user, address)) => (Some(user), address),
+            None => (None, self.host.as_str()),
+        };
+        let address = if address.contains(':') && !address.starts_with('[') {
+            format!("[{}]", address)
+        } else {
+            address.to_string()
+        };
+        match user {
+            Some(user) => format!("{}@{}", user, address),
+            None => address,
+        }
+    }
+
+    /// Copy `content` to `remote_path` with scp (`-l` applies the bandwidth limit)
+    fn scp_upload(&self, remote_path: &str, content: &[u8]) -> Result<()> {
+        let staged = StagedFile::new()?;
+        staged.write(content)?;
+        let target = format!("{}:{}", self.scp_host(), remote_path);
+        let source = staged.path.display().to_string();
+        // scp runs with -q, so show that a large upload is still going
+        if content.len() as u64 >= progress::MIN_BAR_BYTES {
+            let message = format!(
+                "Uploading {}",
+                crate::utils::format_bytes(content.len() as u64)
+            );
+            progress::with_spinner(&message, || self.run_scp(&source, &target))
+        } else {
+            self.run_scp(&source, &target)
+        }
+    }
+
+    /// Copy `remote_path` to a local staging file with scp and return its contents
+    fn scp_download(&self, remote_path: &str) -> Result<Vec<u8>> {
+        let staged = StagedFile::new()?;
+        let source = format!("{}:{}", self.scp_host(), remote_path);
+        self.run_scp(&source, &staged.path.display().to_string())?;
+        std::fs::read(&staged.path)
+            .with_context(|| format!("Failed to read {}", staged.path.display()))
+    }
+
+    fn run_scp(&self, source: &str, target: &str) -> Result<()> {
+        let mut args = vec!["-q".to_string()];
+        args.extend(self.connection_options());
+        // scp spells the port flag -P
+        args.extend(port_args("-P", self.port));
+        if let Some(kbps) = self.bwlimit_kbps {
+            args.extend(["-l".to_string(), kbps.to_string()]);
+        }
+        args.extend([source.to_string(), target.to_string()]);
+
+        let output = Command::new("scp")
+            .args(&args)
+            .stdin(Stdio::null())
+            .output()
+            .context("Failed to execute scp")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "scp {} {} failed: {}",
+                source,
+                target,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    fn write_file_with_cat(&self, path: &str, content: &[u8]) -> Result<()> {
         let mut ssh_args = self.build_ssh_args();
         ssh_args.push("sh".to_string());
         ssh_args.push("-c".to_string());
@@ -190,9 +633,15 @@ impl SshConnection {
             .spawn()
             .with_context(|| format!("Failed to spawn SSH command for writing file"))?;
 
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(content)?;
+        if let Some(stdin) = child.stdin.take() {
+            let bar = progress::bytes(content.len() as u64, "Uploading");
+            let mut stdin = bar.wrap_write(stdin);
+            match self.bwlimit_kbps {
+                Some(kbps) => write_throttled(&mut stdin, content, kbps)?,
+                None => stdin.write_all(content)?,
+            }
             stdin.flush()?;
+            bar.finish_and_clear();
         }
 
         let status = child
@@ -206,6 +655,25 @@ impl SshConnection {
         Ok(())
     }
 
+    /// Check that the remote file at `path` is `expected` bytes long
+    fn verify_file_size(&self, path: &str, expected: usize) -> Result<()> {
+        let output = self.execute_simple("wc", &["-c", path])?;
+        // "<bytes> <path>"
+        let actual = String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .next()
+            .and_then(|bytes| bytes.parse::<usize>().ok());
+        if actual != Some(expected) {
+            anyhow::bail!(
+                "Incomplete write to {}: expected {} bytes, found {}",
+                path,
+                expected,
+                actual.map_or("unknown".to_string(), |n| n.to_string())
+            );
+        }
+        Ok(())
+    }
+
     pub fn mkdir_p(&self, path: &str) -> Result<()> {
         let output = self.execute_simple("mkdir", &["-p", path])?;
         if !output.status.success() {
@@ -258,8 +726,202 @@ impl SshConnection {
     }
 }
 
+impl Drop for SshConnection {
+    fn drop(&mut self) {
+        let Some(control_path) = self.control_path.take() else {
+            return;
+        };
+        let _ = Command::new("ssh")
+            .args([
+                "-o",
+                &format!("ControlPath={}", control_path.display()),
+                "-O",
+                "exit",
+                &self.host,
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if let Some(dir) = control_path.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Set to `0` to open a fresh SSH connection for every command instead of sharing one
+const MULTIPLEX_ENV: &str = "HALVOR_SSH_MULTIPLEX";
+
+/// Seconds an idle master outlives halvor if it exits without closing it
+const CONTROL_PERSIST_SECS: u32 = 60;
+
+/// Start a background master connection to `host` that later commands reuse
+/// Returns its control socket, or None when multiplexing is disabled or the master didn't start
+#[cfg(unix)]
+fn start_control_master(host: &str, port: Option<u16>, jump: Option<&str>) -> Option<PathBuf> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    if std::env::var(MULTIPLEX_ENV).is_ok_and(|value| value == "0") {
+        return None;
+    }
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    // Socket paths are limited to ~100 bytes, so stay out of long per-user temp dirs
+    let dir = PathBuf::from("/tmp").join(format!(
+        "halvor-ssh-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    // A leftover directory means a crashed run with the same pid; its socket is stale
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::DirBuilder::new().mode(0o700).create(&dir).ok()?;
+    let control_path = dir.join("control");
+
+    let started = Command::new("ssh")
+        .args(port_args("-p", port))
+        .args(jump_args(jump))
+        .args([
+            "-o",
+            "ControlMaster=yes",
+            "-o",
+            &format!("ControlPath={}", control_path.display()),
+            "-o",
+            &format!("ControlPersist={}", CONTROL_PERSIST_SECS),
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ConnectTimeout=5",
+            "-o",
+            "StrictHostKeyChecking=no",
+            "-f",
+            "-N",
+            host,
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+
+    if started && control_path.exists() {
+        debug!(
+            "Sharing SSH connection to {} via {}",
+            host,
+            control_path.display()
+        );
+        Some(control_path)
+    } else {
+        let _ = std::fs::remove_dir_all(&dir);
+        None
+    }
+}
+
+/// ControlMaster needs unix domain sockets
+#[cfg(not(unix))]
+fn start_control_master(_host: &str, _port: Option<u16>, _jump: Option<&str>) -> Option<PathBuf> {
+    None
+}
+
+/// `flag <port>` for a non-default port, nothing otherwise (ssh uses `-p`, scp `-P`)
+fn port_args(flag: &str, port: Option<u16>) -> Vec<String> {
+    port.map(|port| vec![flag.to_string(), port.to_string()])
+        .unwrap_or_default()
+}
+
+/// `-J <jump>` when going through a jump host, nothing otherwise (same flag for ssh and scp)
+fn jump_args(jump: Option<&str>) -> Vec<String> {
+    jump.map(|jump| vec!["-J".to_string(), jump.to_string()])
+        .unwrap_or_default()
+}
+
+/// Whether `scp` is installed locally (checked once)
+fn scp_available() -> bool {
+    static SCP_AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *SCP_AVAILABLE.get_or_init(|| local::check_command_exists("scp"))
+}
+
+/// `path` as scp expects it, or None if it needs shell expansion scp won't do
+/// scp resolves relative paths against the remote home, so `$HOME/` and `~/` are stripped
+fn scp_remote_path(path: &str) -> Option<String> {
+    let path = path
+        .strip_prefix("$HOME/")
+        .or_else(|| path.strip_prefix("~/"))
+        .unwrap_or(path);
+    let needs_shell = path.contains('$') || path.starts_with('~');
+    (!needs_shell && !path.is_empty()).then(|| path.to_string())
+}
+
+/// Private local file that scp transfers go through, removed on drop
+///
+/// It lives in a fresh directory only the current user can enter, so another local user can't
+/// read it or plant a symlink where it will be written.
+struct StagedFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl StagedFile {
+    fn new() -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("halvor-transfer-{}", uuid::Uuid::new_v4()));
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        // Fails rather than reusing anything already at this path
+        builder
+            .create(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(Self {
+            path: dir.join("content"),
+            dir,
+        })
+    }
+
+    /// Write `content`, readable only by the current user (it may hold secrets)
+    fn write(&self, content: &[u8]) -> Result<()> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&self.path)
+            .with_context(|| format!("Failed to create {}", self.path.display()))?;
+        file.write_all(content)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Write `content` in small chunks, sleeping between them to stay under `kbps` kilobits per second
+fn write_throttled(writer: &mut impl Write, content: &[u8], kbps: u32) -> Result<()> {
+    let bytes_per_sec = (kbps as f64 * 1000.0 / 8.0).max(1.0);
+    // About ten chunks a second keeps the rate smooth without a write per byte
+    let chunk_size = ((bytes_per_sec / 10.0) as usize).max(1024);
+    let start = std::time::Instant::now();
+    let mut written = 0;
+    for chunk in content.chunks(chunk_size) {
+        writer.write_all(chunk)?;
+        written += chunk.len();
+        let due = Duration::from_secs_f64(written as f64 / bytes_per_sec);
+        if let Some(wait) = due.checked_sub(start.elapsed()) {
+            std::thread::sleep(wait);
+        }
+    }
+    Ok(())
+}
+
 /// Escape a string for safe use in shell commands
-fn shell_escape(s: &str) -> String {
+pub fn shell_escape(s: &str) -> String {
     // Simple escaping - wrap in single quotes and escape single quotes
     if s.is_empty() {
         return "''".to_string();
@@ -277,21 +939,101 @@ fn shell_escape(s: &str) -> String {
     format!("'{}'", escaped)
 }
 
-fn _remove_ssh_host_key(host: &str) -> Result<()> {
-    println!("Removing host key for {} from known_hosts...", host);
+/// Name known_hosts stores `address` under: `[address]:port` when sshd isn't on port 22
+fn known_hosts_name(address: &str, port: Option<u16>) -> String {
+    match port {
+        Some(port) if port != 22 => format!("[{}]:{}", address, port),
+        _ => address.to_string(),
+    }
+}
+
+/// Key types and fingerprints known_hosts holds for `name` (empty when there are none)
+fn known_host_keys(name: &str) -> Result<Vec<(String, String)>> {
+    let output = local::execute("ssh-keygen", &["-F", name, "-l"])?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [.., key_type, fingerprint] if fields.len() >= 3 => {
+                    Some((key_type.to_string(), fingerprint.to_string()))
+                }
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+/// Look up a configured host and the known_hosts names of each of its addresses, with labels
+fn host_key_names(hostname: &str, config: &EnvConfig) -> Result<Vec<(String, String)>> {
+    let name = config::service::find_hostname_in_config(hostname, config)
+        .with_context(|| format!("Host '{}' not found in the configuration", hostname))?;
+    let host_config = &config.hosts[&name];
+    Ok(host_addresses(host_config, &config._tailnet_base)
+        .into_iter()
+        .map(|(address, label)| (known_hosts_name(&address, host_config.ssh_port), label))
+        .collect())
+}
+
+/// Print the known_hosts keys for each address of `hostname` (every configured host when None)
+pub fn list_host_keys(hostname: Option<&str>, config: &EnvConfig) -> Result<()> {
+    let hostnames = match hostname {
+        Some(hostname) => vec![hostname.to_string()],
+        None => {
+            let mut hostnames: Vec<String> = config.hosts.keys().cloned().collect();
+            hostnames.sort();
+            hostnames
+        }
+    };
+    for hostname in &hostnames {
+        let names = host_key_names(hostname, config)?;
+        println!("{}:", hostname);
+        for (name, label) in names {
+            println!("  {}", label);
+            let keys = known_host_keys(&name)?;
+            if keys.is_empty() {
+                println!("    (no known_hosts entry)");
+            }
+            for (key_type, fingerprint) in keys {
+                println!("    {} {}", key_type, fingerprint);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove the known_hosts entries for every address of `hostname`
+pub fn remove_host_keys(hostname: &str, config: &EnvConfig) -> Result<()> {
+    let mut removed = 0;
+    for (name, _) in host_key_names(hostname, config)? {
+        if !known_host_keys(&name)?.is_empty() {
+            remove_ssh_host_key(&name)?;
+            removed += 1;
+        }
+    }
+    if removed == 0 {
+        println!("No known_hosts entries for {}'s addresses", hostname);
+    }
+    Ok(())
+}
 
+fn remove_ssh_host_key(host: &str) -> Result<()> {
     // Use exec::local for local command execution
     let output = local::execute("ssh-keygen", &["-R", host])?;
 
     if output.status.success() {
-        println!("✓ Removed host key for {}", host);
+        println!("✓ Removed host key for {} from known_hosts", host);
         Ok(())
     } else {
         anyhow::bail!("Failed to remove host key for {}", host);
     }
 }
 
-fn _prompt_remove_host_key(host: &str) -> Result<bool> {
+fn prompt_remove_host_key(host: &str) -> Result<bool> {
     print!("Remove host key for {} from known_hosts? [y/N]: ", host);
     io::stdout().flush()?;
 
@@ -302,7 +1044,79 @@ fn _prompt_remove_host_key(host: &str) -> Result<bool> {
     Ok(response == "y" || response == "yes")
 }
 
-fn _connect_ssh_key_based(host: &str, user: Option<&str>, ssh_args: &[String]) -> Result<()> {
+/// Exit status ssh itself uses when it can't connect or authenticate
+const SSH_FAILURE_EXIT_CODE: i32 = 255;
+
+/// Messages ssh prints when it gets through to the server but can't log in
+const LOGIN_ERROR_PATTERNS: &[&str] = &[
+    "Permission denied",
+    "Host key verification failed",
+    "Too many authentication failures",
+    "REMOTE HOST IDENTIFICATION HAS CHANGED",
+];
+
+/// Bytes of a session's stderr kept for classifying its exit status
+const SESSION_STDERR_TAIL: usize = 8192;
+
+/// Whether a session that exited with `code` failed in ssh itself rather than in the remote
+/// command, which can exit 255 too; ssh's own failures also leave one of its messages on stderr
+fn is_ssh_session_failure(code: Option<i32>, stderr: &str) -> bool {
+    match code {
+        None => true,
+        Some(SSH_FAILURE_EXIT_CODE) => CONNECTION_ERROR_PATTERNS
+            .iter()
+            .chain(LOGIN_ERROR_PATTERNS)
+            .any(|pattern| stderr.contains(pattern)),
+        Some(_) => false,
+    }
+}
+
+/// Run an interactive ssh, passing its stderr through while keeping the tail to classify failures
+fn run_session(cmd: &mut Command) -> Result<(ExitStatus, String)> {
+    use std::io::Read;
+
+    cmd.stderr(Stdio::piped());
+    let mut child = cmd.spawn().context("Failed to execute ssh")?;
+    let mut stderr = child.stderr.take().context("ssh stderr was not captured")?;
+    let tee = std::thread::spawn(move || {
+        let mut tail = Vec::new();
+        let mut buf = [0u8; 4096];
+        while let Ok(n) = stderr.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            let _ = io::stderr().write_all(&buf[..n]);
+            tail.extend_from_slice(&buf[..n]);
+            if tail.len() > SESSION_STDERR_TAIL {
+                tail.drain(..tail.len() - SESSION_STDERR_TAIL);
+            }
+        }
+        tail
+    });
+    let status = child.wait().context("Failed to wait for ssh")?;
+    let tail = tee.join().unwrap_or_default();
+    Ok((status, String::from_utf8_lossy(&tail).into_owned()))
+}
+
+/// Exit with the session's status once ssh got through; only ssh's own failures are returned
+fn finish_session(cmd: &mut Command) -> Result<()> {
+    let (status, stderr) = run_session(cmd)?;
+    if is_ssh_session_failure(status.code(), &stderr) {
+        anyhow::bail!(
+            "SSH connection failed with exit code: {}",
+            status.code().unwrap_or(1)
+        );
+    }
+    std::process::exit(status.code().unwrap_or(1))
+}
+
+fn connect_ssh_key_based(
+    host: &str,
+    user: Option<&str>,
+    port: Option<u16>,
+    jump: Option<&str>,
+    ssh_args: &[String],
+) -> Result<()> {
     // First, test if key-based auth works using SshConnection
     let host_str = if let Some(u) = user {
         format!("{}@{}", u, host)
@@ -311,7 +1125,7 @@ fn _connect_ssh_key_based(host: &str, user: Option<&str>, ssh_args: &[String]) -
     };
 
     // Use SshConnection to test key-based auth
-    let ssh_conn = SshConnection::new(&host_str)?;
+    let ssh_conn = SshConnection::new_with_options(&host_str, port, jump)?;
     if !ssh_conn.use_key_auth {
         anyhow::bail!("Key-based authentication not available");
     }
@@ -320,6 +1134,8 @@ fn _connect_ssh_key_based(host: &str, user: Option<&str>, ssh_args: &[String]) -
     let mut cmd = Command::new("ssh");
 
     // Use key-based authentication only (no password prompts)
+    cmd.args(port_args("-p", port));
+    cmd.args(jump_args(jump));
     cmd.args([
         "-o",
         "PreferredAuthentications=publickey",
@@ -338,25 +1154,22 @@ fn _connect_ssh_key_based(host: &str, user: Option<&str>, ssh_args: &[String]) -
     // Allow interactive output for the actual connection
     cmd.stdin(Stdio::inherit());
     cmd.stdout(Stdio::inherit());
-    cmd.stderr(Stdio::inherit());
 
     // Execute SSH - this will open the interactive session
-    let status = cmd.status()?;
-
-    // If SSH exits successfully, we're done
-    if status.success() {
-        std::process::exit(0);
-    } else {
-        // SSH connection failed, return error so we can try password-based auth
-        anyhow::bail!(
-            "SSH connection failed with exit code: {}",
-            status.code().unwrap_or(1)
-        );
-    }
+    // If SSH itself fails, return the error so we can try password-based auth
+    finish_session(&mut cmd)
 }
 
-fn _connect_ssh(host: &str, user: Option<&str>, ssh_args: &[String]) -> Result<()> {
+fn connect_ssh(
+    host: &str,
+    user: Option<&str>,
+    port: Option<u16>,
+    jump: Option<&str>,
+    ssh_args: &[String],
+) -> Result<()> {
     let mut cmd = Command::new("ssh");
+    cmd.args(port_args("-p", port));
+    cmd.args(jump_args(jump));
 
     // Add options to allow password authentication (fallback)
     cmd.args([
@@ -382,24 +1195,19 @@ fn _connect_ssh(host: &str, user: Option<&str>, ssh_args: &[String]) -> Result<(
     // Allow interactive authentication (password prompts, etc.)
     cmd.stdin(Stdio::inherit());
     cmd.stdout(Stdio::inherit());
-    cmd.stderr(Stdio::inherit());
 
     // Execute SSH - this will block and allow password prompts
-    let status = cmd.status()?;
-
-    // If SSH exits successfully, we're done
-    if status.success() {
-        std::process::exit(0);
-    } else {
-        // SSH connection failed, return error so we can try next host
-        anyhow::bail!(
-            "SSH connection failed with exit code: {}",
-            status.code().unwrap_or(1)
-        );
-    }
+    // If SSH itself fails, return the error so we can try the next host
+    finish_session(&mut cmd)
 }
 
-fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str>) -> Result<()> {
+fn copy_ssh_key(
+    host: &str,
+    port: Option<u16>,
+    jump: Option<&str>,
+    server_user: Option<&str>,
+    target_user: Option<&str>,
+) -> Result<()> {
     // Determine server username (to SSH into the server)
     let default_server_user = config::get_default_username();
     let server_username = if let Some(u) = server_user {
@@ -482,7 +1290,7 @@ fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str
             target_username
         );
         // Use SshConnection for non-interactive command execution
-        let ssh_conn = SshConnection::new(&host_str)?;
+        let ssh_conn = SshConnection::new_with_options(&host_str, port, jump)?;
         let user_exists = ssh_conn.execute_shell(&check_user_cmd)?.status.success();
 
         if !user_exists {
@@ -512,7 +1320,7 @@ fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str
             );
 
             // Use SshConnection for interactive command execution (needs TTY for sudo)
-            let ssh_conn = SshConnection::new(&host_str)?;
+            let ssh_conn = SshConnection::new_with_options(&host_str, port, jump)?;
             ssh_conn
                 .execute_shell_interactive(&create_user_cmd)
                 .with_context(|| {
@@ -532,7 +1340,7 @@ fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str
             );
 
             // Use SshConnection for non-interactive command execution
-            let ssh_conn = SshConnection::new(&host_str)?;
+            let ssh_conn = SshConnection::new_with_options(&host_str, port, jump)?;
             let password_check_output = ssh_conn.execute_shell(&check_password_cmd)?;
             let password_status_str = String::from_utf8_lossy(&password_check_output.stdout);
             let password_status = password_status_str.trim();
@@ -557,7 +1365,7 @@ fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str
                             format!(r#"echo '{}:{}' | sudo chpasswd"#, target_username, password);
 
                         // Use SshConnection for interactive command execution (needs TTY for sudo)
-                        let ssh_conn = SshConnection::new(&host_str)?;
+                        let ssh_conn = SshConnection::new_with_options(&host_str, port, jump)?;
                         if ssh_conn
                             .execute_shell_interactive(&set_password_cmd)
                             .is_ok()
@@ -598,7 +1406,7 @@ fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str
 
         println!("Installing SSH key for user '{}'...", target_username);
         // Use SshConnection for interactive command execution (needs TTY for sudo)
-        let ssh_conn = SshConnection::new(&host_str)?;
+        let ssh_conn = SshConnection::new_with_options(&host_str, port, jump)?;
         ssh_conn
             .execute_shell_interactive(&append_cmd)
             .with_context(|| {
@@ -621,18 +1429,22 @@ fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str
             );
         }
 
+        let mut args = port_args("-p", port);
+        if let Some(jump) = jump {
+            args.extend(["-o".to_string(), format!("ProxyJump={}", jump)]);
+        }
+        args.extend([
+            "-o".to_string(),
+            "StrictHostKeyChecking=no".to_string(),
+            "-o".to_string(),
+            "PreferredAuthentications=keyboard-interactive,password".to_string(),
+            "-f".to_string(), // Force mode - don't check if key is already installed
+            host_str.clone(),
+        ]);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
         // Use exec::local for local command execution
-        let output = local::execute(
-            "ssh-copy-id",
-            &[
-                "-o",
-                "StrictHostKeyChecking=no",
-                "-o",
-                "PreferredAuthentications=keyboard-interactive,password",
-                "-f", // Force mode - don't check if key is already installed
-                &host_str,
-            ],
-        )?;
+        let output = local::execute("ssh-copy-id", &args)?;
 
         if output.status.success() {
             println!("✓ SSH key copied successfully to {}", host_str);
@@ -643,7 +1455,10 @@ fn _copy_ssh_key(host: &str, server_user: Option<&str>, target_user: Option<&str
     }
 }
 
-pub fn _ssh_to_host(
+/// Open an interactive SSH session to `hostname`, trying each of its addresses in turn
+/// Key-based auth is tried first, then password auth; `ssh_args` follow the host on the ssh
+/// command line. Exits the process with the session's status once connected
+pub fn ssh_to_host(
     hostname: &str,
     user: Option<String>,
     fix_keys: bool,
@@ -663,21 +1478,18 @@ pub fn _ssh_to_host(
     let host_config = crate::services::host::get_host_config_or_error(hostname)?;
 
     // Collect all possible host addresses
-    let mut all_hosts = Vec::new();
-    if let Some(ip) = &host_config.ip {
-        all_hosts.push(ip.clone());
-    }
-    if let Some(tailscale) = &host_config.tailscale {
-        all_hosts.push(tailscale.clone());
-        all_hosts.push(format!("{}.{}", tailscale, config._tailnet_base));
-    }
+    let hosts_to_try = host_addresses(&host_config, &config._tailnet_base);
+    let port = host_config.ssh_port;
+    let jump = resolve_jump_host(hostname, &host_config, config);
+    let jump = jump.as_deref();
 
     // If fix_keys is enabled, remove host keys for all possible addresses
     if fix_keys {
         println!("Fix keys mode enabled. Removing host keys for all configured addresses...");
-        for host in &all_hosts {
-            if _prompt_remove_host_key(host)? {
-                _remove_ssh_host_key(host)?;
+        for (host, _) in &hosts_to_try {
+            let name = known_hosts_name(host, port);
+            if prompt_remove_host_key(&name)? {
+                remove_ssh_host_key(&name)?;
             }
         }
     }
@@ -699,7 +1511,7 @@ pub fn _ssh_to_host(
             Some(u.clone())
         } else {
             // Prompt for username
-            let default_user = config::get_default_username();
+            let default_user = host_config.ssh_user_or_default();
             print!("Username (press Enter for '{}'): ", default_user);
             io::stdout().flush()?;
 
@@ -717,7 +1529,7 @@ pub fn _ssh_to_host(
         // For key copying, we need server username (to SSH in) and target username (where to install key)
         // Prompt for server username, then target username
         // Server username defaults to what we'll use to connect, target username is where key goes
-        let default_target_user = config::get_default_username();
+        let default_target_user = host_config.ssh_user_or_default();
 
         print!(
             "Target username to install key for (press Enter for '{}'): ",
@@ -734,8 +1546,10 @@ pub fn _ssh_to_host(
             input.trim().to_string()
         };
 
-        _copy_ssh_key(
+        copy_ssh_key(
             target_host,
+            port,
+            jump,
             username_for_keys.as_deref(), // Server username (to SSH into)
             Some(&target_username),       // Target username (where to install key)
         )?;
@@ -752,44 +1566,26 @@ pub fn _ssh_to_host(
 
     let username_ref = username.as_deref();
 
-    // Build list of hosts to try in order
-    let mut hosts_to_try: Vec<(String, String)> = Vec::new();
-
-    if let Some(ip) = &host_config.ip {
-        hosts_to_try.push((ip.clone(), format!("IP: {}", ip)));
-    }
-
-    if let Some(tailscale) = &host_config.tailscale {
-        hosts_to_try.push((tailscale.clone(), format!("Tailscale: {}", tailscale)));
-        hosts_to_try.push((
-            format!("{}.{}", tailscale, config._tailnet_base),
-            format!("Tailscale FQDN: {}.{}", tailscale, config._tailnet_base),
-        ));
-    }
-
     // Try each host in sequence
     let total_hosts = hosts_to_try.len();
     for (idx, (host, description)) in hosts_to_try.iter().enumerate() {
         tried_hosts.push(host.clone());
 
         // Try key-based authentication first (silently, no prompts)
-        // Use default username first (SSH typically needs a username)
-        let default_username = config::get_default_username();
+        // Use the host's configured SSH user first (SSH typically needs a username)
+        let default_username = host_config.ssh_user_or_default();
 
         // Try with default username first
-        match _connect_ssh_key_based(host, Some(&default_username), ssh_args) {
-            Ok(_) => return Ok(()),
-            Err(_) => {} // Key-based auth failed, continue
+        if connect_ssh_key_based(host, Some(&default_username), port, jump, ssh_args).is_ok() {
+            return Ok(());
         }
 
         // If username was explicitly provided via flag, try that too
-        if let Some(ref u) = username {
-            if u != &default_username {
-                match _connect_ssh_key_based(host, Some(u), ssh_args) {
-                    Ok(_) => return Ok(()),
-                    Err(_) => {} // Key-based auth failed, continue
-                }
-            }
+        if let Some(ref u) = username
+            && u != &default_username
+            && connect_ssh_key_based(host, Some(u), port, jump, ssh_args).is_ok()
+        {
+            return Ok(());
         }
 
         // All key-based auth attempts failed, need password-based auth
@@ -804,7 +1600,7 @@ pub fn _ssh_to_host(
         };
         // Try to connect with password authentication as fallback
         // This will allow interactive password prompts
-        match _connect_ssh(host, final_username.as_deref(), ssh_args) {
+        match connect_ssh(host, final_username.as_deref(), port, jump, ssh_args) {
             Ok(_) => {
                 // Connection succeeded, we're done
                 return Ok(());
@@ -827,3 +1623,283 @@ pub fn _ssh_to_host(
     }
     std::process::exit(1);
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::sync::MutexGuard;
+
+    /// Serializes tests that put fake programs first on PATH
+    static PATH_LOCK: Mutex<()> = Mutex::new(());
+
+    const FAKE_HOST: &str = "fake-host";
+
+    /// Fake `ssh` that runs the command locally and logs whether it needed a handshake
+    ///
+    /// A master (`ControlMaster=yes`) creates its control path as a stand-in for the socket;
+    /// later calls whose control path exists count as multiplexed, everything else as a full
+    /// connection. `-O exit` removes the control path.
+    const FAKE_SSH: &str = r#"control_path=; master=no; op=
+while [ $# -gt 0 ]; do
+    case "$1" in
+        -o)
+            case "$2" in
+                ControlPath=*) control_path=${2#ControlPath=} ;;
+                ControlMaster=yes) master=yes ;;
+            esac
+            shift 2 ;;
+        -O) op=$2; shift 2 ;;
+        -p|-J|-l|-i) shift 2 ;;
+        -*) shift ;;
+        *) break ;;
+    esac
+done
+shift
+if [ -n "$op" ]; then
+    echo "ssh -O $op" >> '{log}'
+    [ "$op" = exit ] && rm -f "$control_path"
+    exit 0
+fi
+if [ "$master" = yes ]; then
+    echo "ssh handshake (master)" >> '{log}'
+    touch "$control_path"
+    exit 0
+fi
+if [ -n "$control_path" ] && [ -e "$control_path" ]; then
+    echo "ssh mux $*" >> '{log}'
+else
+    echo "ssh handshake $*" >> '{log}'
+fi
+exec sh -c "$*"
+"#;
+
+    /// A "remote" host that is a local directory, reached through fake `ssh` and `scp` that log
+    /// each call
+    struct FakeRemote {
+        root: PathBuf,
+        original_path: String,
+        _path_lock: MutexGuard<'static, ()>,
+    }
+
+    impl FakeRemote {
+        fn new() -> Self {
+            let path_lock = PATH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let root =
+                std::env::temp_dir().join(format!("halvor-fake-remote-{}", uuid::Uuid::new_v4()));
+            let bin = root.join("bin");
+            std::fs::create_dir_all(&bin).unwrap();
+            let log = root.join("calls.log");
+            write_script(
+                &bin.join("scp"),
+                &format!(
+                    "echo \"scp $*\" >> '{log}'\n\
+                     for arg; do src=$dst; dst=$arg; done\n\
+                     cp \"${{src#{host}:}}\" \"${{dst#{host}:}}\"\n",
+                    log = log.display(),
+                    host = FAKE_HOST
+                ),
+            );
+
+            write_script(
+                &bin.join("ssh"),
+                &FAKE_SSH.replace("{log}", &log.display().to_string()),
+            );
+
+            let original_path = std::env::var("PATH").unwrap_or_default();
+            // SAFETY: PATH_LOCK keeps the tests that read or change PATH from running concurrently
+            unsafe { std::env::set_var("PATH", format!("{}:{}", bin.display(), original_path)) };
+            Self {
+                root,
+                original_path,
+                _path_lock: path_lock,
+            }
+        }
+
+        fn connection(&self) -> SshConnection {
+            SshConnection {
+                host: FAKE_HOST.to_string(),
+                use_key_auth: true,
+                bwlimit_kbps: None,
+                port: None,
+                jump: None,
+                control_path: None,
+            }
+        }
+
+        /// Share connections unless `enabled` is false (`HALVOR_SSH_MULTIPLEX`)
+        fn set_multiplexing(&self, enabled: bool) {
+            // SAFETY: PATH_LOCK is held, and only these tests touch the variable
+            unsafe { std::env::set_var(MULTIPLEX_ENV, if enabled { "1" } else { "0" }) };
+        }
+
+        /// Recorded calls starting with `prefix`
+        fn count(&self, prefix: &str) -> usize {
+            self.calls()
+                .iter()
+                .filter(|call| call.starts_with(prefix))
+                .count()
+        }
+
+        /// Programs run so far, one line per call
+        fn calls(&self) -> Vec<String> {
+            std::fs::read_to_string(self.root.join("calls.log"))
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    impl Drop for FakeRemote {
+        fn drop(&mut self) {
+            // SAFETY: still holding PATH_LOCK
+            unsafe {
+                std::env::set_var("PATH", &self.original_path);
+                std::env::remove_var(MULTIPLEX_ENV);
+            }
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn write_script(path: &Path, body: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::write(path, format!("#!/bin/sh\n{}", body)).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn scp_round_trip_preserves_newlines_and_nul_bytes() {
+        let remote = FakeRemote::new();
+        let conn = remote.connection();
+        let blob: Vec<u8> = b"first line\nsecond\r\n\0\0nul in the middle\0\n\n"
+            .iter()
+            .copied()
+            .chain(0..=255u8)
+            .collect();
+        let remote_path = remote.root.join("blob.bin").display().to_string();
+
+        conn.scp_upload(&remote_path, &blob).unwrap();
+        assert_eq!(std::fs::read(&remote_path).unwrap(), blob);
+        assert_eq!(conn.scp_download(&remote_path).unwrap(), blob);
+
+        let calls = remote.calls();
+        assert_eq!(calls.len(), 2, "expected one scp per transfer: {:?}", calls);
+        assert!(calls[0].ends_with(&format!("{}:{}", FAKE_HOST, remote_path)));
+        assert!(calls[1].contains(&format!("{}:{} ", FAKE_HOST, remote_path)));
+    }
+
+    /// Run `commands` commands on a fresh connection and return (handshakes, multiplexed calls)
+    fn connection_round_trips(remote: &FakeRemote, commands: usize) -> (usize, usize) {
+        let conn = SshConnection::new(FAKE_HOST).unwrap();
+        for i in 0..commands {
+            let output = conn.execute_simple("echo", &[&i.to_string()]).unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout), format!("{}\n", i));
+        }
+        drop(conn);
+        (remote.count("ssh handshake"), remote.count("ssh mux"))
+    }
+
+    #[test]
+    fn multiplexing_needs_two_handshakes_for_any_number_of_commands() {
+        const COMMANDS: usize = 10;
+
+        // One handshake for the key-auth probe in `new`, then one per command
+        let remote = FakeRemote::new();
+        remote.set_multiplexing(false);
+        assert_eq!(connection_round_trips(&remote, COMMANDS), (COMMANDS + 1, 0));
+        drop(remote);
+
+        // The probe and the master are the only handshakes; every command reuses the master
+        let remote = FakeRemote::new();
+        remote.set_multiplexing(true);
+        assert_eq!(connection_round_trips(&remote, COMMANDS), (2, COMMANDS));
+        assert_eq!(remote.count("ssh -O exit"), 1);
+    }
+
+    #[test]
+    fn stale_control_socket_falls_back_to_a_fresh_connection() {
+        let remote = FakeRemote::new();
+        remote.set_multiplexing(true);
+        let conn = SshConnection::new(FAKE_HOST).unwrap();
+        let control_path = conn
+            .control_path
+            .clone()
+            .expect("master should have started");
+
+        // The master went away without halvor noticing
+        std::fs::remove_file(&control_path).unwrap();
+        let output = conn.execute_simple("echo", &["still works"]).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "still works\n");
+        assert_eq!(remote.count("ssh mux"), 0);
+
+        drop(conn);
+        assert!(!control_path.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn exit_255_counts_as_ssh_failure_only_with_an_ssh_message() {
+        let refused = "ssh: connect to host 10.0.0.1 port 22: Connection refused\n";
+        assert!(is_ssh_session_failure(Some(255), refused));
+        assert!(is_ssh_session_failure(
+            Some(255),
+            "alice@10.0.0.1: Permission denied (publickey).\n"
+        ));
+        // A remote command that exits 255 on its own is passed through
+        assert!(!is_ssh_session_failure(Some(255), ""));
+        assert!(!is_ssh_session_failure(Some(255), "script: giving up\n"));
+        assert!(!is_ssh_session_failure(Some(1), refused));
+        assert!(!is_ssh_session_failure(Some(0), ""));
+        // Killed by a signal
+        assert!(is_ssh_session_failure(None, ""));
+    }
+
+    #[test]
+    fn staged_file_is_private_and_removed_on_drop() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let staged = StagedFile::new().unwrap();
+        staged.write(b"secret").unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&staged.dir), 0o700);
+        assert_eq!(mode(&staged.path), 0o600);
+        // An existing file (or a planted symlink) is never opened for writing
+        assert!(staged.write(b"again").is_err());
+
+        let dir = staged.dir.clone();
+        drop(staged);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn preferred_family_reorders_without_replacing_names() {
+        let addresses: Vec<(String, String)> =
+            ["10.0.0.5", "localhost", "no-such-host.invalid", "fd7a::5"]
+                .iter()
+                .map(|a| (a.to_string(), format!("label {}", a)))
+                .collect();
+        let order = |family| -> Vec<String> {
+            order_by_family(addresses.clone(), family, 22)
+                .into_iter()
+                .map(|(address, _)| address)
+                .collect()
+        };
+
+        // localhost resolves to 127.0.0.1 (and usually ::1) but is still connected to by name
+        assert_eq!(
+            order(AddressFamily::Ipv4),
+            ["10.0.0.5", "localhost", "no-such-host.invalid", "fd7a::5"]
+        );
+        // Where localhost lands depends on whether it has an IPv6 entry here
+        let ipv6 = order(AddressFamily::Ipv6);
+        let position = |address: &str| ipv6.iter().position(|a| a == address).unwrap();
+        assert_eq!(position("fd7a::5"), 0);
+        assert!(position("no-such-host.invalid") < position("10.0.0.5"));
+        assert!(ipv6.iter().any(|a| a == "localhost"));
+        assert_eq!(
+            order_by_family(addresses.clone(), AddressFamily::Ipv6, 22)[0].1,
+            "label fd7a::5"
+        );
+    }
+}
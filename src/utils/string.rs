@@ -19,3 +19,19 @@ pub fn format_address(host: &str, port: u16) -> String {
 pub fn format_bind_address(port: u16) -> String {
     format!("0.0.0.0:{}", port)
 }
+
+/// Format a byte count using decimal (SI) units, matching Docker's output
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[unit])
+    } else {
+        format!("{:.2}{}", value, UNITS[unit])
+    }
+}
@@ -1,12 +1,17 @@
+use crate::config::{get_arch, get_os};
 use crate::utils::exec::local;
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::io::Write;
 
 const GITHUB_API_BASE: &str = "https://api.github.com";
 const REPO_OWNER: &str = "scottdkey"; // TODO: Make this configurable
 const REPO_NAME: &str = "homelab";
+/// Checksum manifest published with every release (`sha256sum` output format)
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+/// Optional base64 ed25519 public key; when embedded at build time, `SHA256SUMS.sig` is required
+const UPDATE_SIGNING_KEY: Option<&str> = option_env!("HALVOR_UPDATE_PUBKEY");
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReleaseChannel {
@@ -21,6 +26,11 @@ struct Release {
     prerelease: bool,
     #[serde(default)]
     published_at: Option<String>,
+    /// Release notes (markdown)
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
     #[serde(skip)]
     _assets: Vec<Asset>,
 }
@@ -33,12 +43,65 @@ struct Asset {
     _browser_download_url: String,
 }
 
+/// Startup update check results, kept in the config directory between runs
+const UPDATE_CACHE_FILE: &str = "update-check.json";
+
+/// When GitHub was last asked for the latest stable release, and what it said
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UpdateCache {
+    checked_at: i64,
+    latest: Option<String>,
+}
+
 pub fn check_for_updates(current_version: &str) -> Result<Option<String>> {
     // Skip update check in development mode
     if env::var("HAL_DEV_MODE").is_ok() || cfg!(debug_assertions) {
         return Ok(None);
     }
 
+    Ok(fetch_latest_stable_tag()?.filter(|tag| is_newer_version(tag, current_version)))
+}
+
+/// Like `check_for_updates`, but asks GitHub at most once per `interval`
+/// In between, the latest release seen last time is compared instead. Failed lookups count as a
+/// check too, so an offline machine doesn't wait on the network every run.
+pub fn check_for_updates_cached(
+    current_version: &str,
+    interval: std::time::Duration,
+) -> Result<Option<String>> {
+    // Skip update check in development mode
+    if env::var("HAL_DEV_MODE").is_ok() || cfg!(debug_assertions) {
+        return Ok(None);
+    }
+
+    let cache_path = crate::config::config_manager::get_config_dir()?.join(UPDATE_CACHE_FILE);
+    let cache: UpdateCache = std::fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let now = chrono::Utc::now().timestamp();
+    let age = now - cache.checked_at;
+    let latest = if age >= 0 && age < interval.as_secs() as i64 {
+        cache.latest
+    } else {
+        // Keep the last known release when GitHub can't be reached or is rate limiting
+        let latest = fetch_latest_stable_tag().ok().flatten().or(cache.latest);
+        let cache = UpdateCache {
+            checked_at: now,
+            latest: latest.clone(),
+        };
+        if let Err(e) = std::fs::write(&cache_path, serde_json::to_string(&cache)?) {
+            log::debug!("Failed to write {}: {}", cache_path.display(), e);
+        }
+        latest
+    };
+
+    Ok(latest.filter(|tag| is_newer_version(tag, current_version)))
+}
+
+/// Tag of the latest stable release, or None when GitHub doesn't return one
+fn fetch_latest_stable_tag() -> Result<Option<String>> {
     let client = reqwest::blocking::Client::builder()
         .user_agent("hal-cli")
         .timeout(std::time::Duration::from_secs(5))
@@ -65,13 +128,52 @@ pub fn check_for_updates(current_version: &str) -> Result<Option<String>> {
     if release.prerelease {
         return Ok(None);
     }
-    let latest_version = release.tag_name.trim_start_matches('v');
-    let current_version_normalized = current_version.trim_start_matches('v');
-    if latest_version != current_version_normalized && latest_version > current_version_normalized {
-        return Ok(Some(release.tag_name));
-    }
+    Ok(Some(release.tag_name))
+}
 
-    Ok(None)
+/// Whether release `latest` is newer than `current`, comparing the dot-separated numbers in turn
+/// (so 0.10.0 is newer than 0.9.3); a leading `v` and any pre-release suffix are ignored
+fn is_newer_version(latest: &str, current: &str) -> bool {
+    let parts = |version: &str| -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['.', '-', '+'])
+            .map_while(|part| part.parse().ok())
+            .collect()
+    };
+    parts(latest) > parts(current)
+}
+
+/// Whether an experimental release published at `published_at` is newer than the running binary
+/// Experimental builds share one version number, so the executable's modification time is
+/// compared instead. A release without a timestamp counts as newer.
+fn experimental_is_newer(published_at: Option<&str>) -> Result<bool> {
+    let Some(published_at_str) = published_at else {
+        return Ok(true);
+    };
+    // Parse the published_at timestamp (ISO 8601 format)
+    let published_at = chrono::DateTime::parse_from_rfc3339(published_at_str)
+        .context("Failed to parse release timestamp")?;
+
+    // Get the current executable's modification time
+    let current_exe = env::current_exe().context("Failed to get current executable path")?;
+    let metadata = std::fs::metadata(&current_exe).context("Failed to get executable metadata")?;
+
+    #[cfg(unix)]
+    let exe_mtime = {
+        use std::os::unix::fs::MetadataExt;
+        std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(metadata.mtime() as u64)
+    };
+
+    #[cfg(windows)]
+    let exe_mtime = metadata
+        .modified()
+        .context("Failed to get executable modification time")?;
+
+    // Convert SystemTime to DateTime<Utc>
+    let exe_datetime: chrono::DateTime<chrono::Utc> = exe_mtime.into();
+    let published_datetime: chrono::DateTime<chrono::Utc> = published_at.into();
+    Ok(published_datetime > exe_datetime)
 }
 
 pub fn check_for_experimental_updates(_current_version: &str) -> Result<Option<String>> {
@@ -104,40 +206,8 @@ pub fn check_for_experimental_updates(_current_version: &str) -> Result<Option<S
 
     let release: Release = response.json().context("Failed to parse release JSON")?;
 
-    // For experimental, check if the release is newer than the current executable
-    // by comparing timestamps
-    if let Some(published_at_str) = &release.published_at {
-        // Parse the published_at timestamp (ISO 8601 format)
-        let published_at = chrono::DateTime::parse_from_rfc3339(published_at_str)
-            .context("Failed to parse release timestamp")?;
-
-        // Get the current executable's modification time
-        let current_exe = env::current_exe().context("Failed to get current executable path")?;
-        let metadata =
-            std::fs::metadata(&current_exe).context("Failed to get executable metadata")?;
-
-        #[cfg(unix)]
-        let exe_mtime = {
-            use std::os::unix::fs::MetadataExt;
-            std::time::SystemTime::UNIX_EPOCH
-                + std::time::Duration::from_secs(metadata.mtime() as u64)
-        };
-
-        #[cfg(windows)]
-        let exe_mtime = metadata
-            .modified()
-            .context("Failed to get executable modification time")?;
-
-        // Convert SystemTime to DateTime<Utc>
-        let exe_datetime: chrono::DateTime<chrono::Utc> = exe_mtime.into();
-        let published_datetime: chrono::DateTime<chrono::Utc> = published_at.into();
-
-        // Only return experimental if the release is newer than the current executable
-        if published_datetime > exe_datetime {
-            return Ok(Some("experimental".to_string()));
-        }
-    } else {
-        // If no timestamp available, assume it's newer (fallback behavior)
+    // Only return experimental if the release is newer than the current executable
+    if experimental_is_newer(release.published_at.as_deref())? {
         return Ok(Some("experimental".to_string()));
     }
 
@@ -287,6 +357,403 @@ pub fn get_latest_experimental_version() -> Result<String> {
     Ok("experimental".to_string())
 }
 
+/// Result of `hal update --check`: the newest release on a channel and how it compares
+#[derive(Debug, Serialize)]
+pub struct UpdateCheck {
+    pub channel: &'static str,
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+    pub published_at: Option<String>,
+    /// Release notes (markdown)
+    pub notes: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Look up the newest release on the stable or experimental channel without downloading anything
+/// Unlike the startup check, a release that can't be fetched is an error rather than "no update".
+pub fn check_release(current_version: &str, experimental: bool) -> Result<UpdateCheck> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("hal-cli")
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let (url, what) = if experimental {
+        (
+            format!(
+                "{}/repos/{}/{}/releases/tags/experimental",
+                GITHUB_API_BASE, REPO_OWNER, REPO_NAME
+            ),
+            "experimental release",
+        )
+    } else {
+        (
+            format!(
+                "{}/repos/{}/{}/releases/latest",
+                GITHUB_API_BASE, REPO_OWNER, REPO_NAME
+            ),
+            "latest release",
+        )
+    };
+
+    let response = client
+        .get(&url)
+        .send()
+        .with_context(|| format!("Failed to fetch {}", what))?;
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch {}: HTTP {}", what, response.status());
+    }
+
+    let release: Release = response.json().context("Failed to parse release JSON")?;
+    let update_available = if experimental {
+        experimental_is_newer(release.published_at.as_deref())?
+    } else {
+        if release.prerelease {
+            anyhow::bail!("No stable release found (only prereleases available)");
+        }
+        is_newer_version(&release.tag_name, current_version)
+    };
+
+    Ok(UpdateCheck {
+        channel: if experimental {
+            "experimental"
+        } else {
+            "stable"
+        },
+        current: current_version.to_string(),
+        latest: release.tag_name,
+        update_available,
+        published_at: release.published_at,
+        notes: release
+            .body
+            .map(|body| body.trim().to_string())
+            .filter(|body| !body.is_empty()),
+        url: release.html_url,
+    })
+}
+
+/// Map the running OS/arch to the names used in release assets
+/// (e.g. `macos`/`x86_64` -> `darwin`/`amd64`)
+fn release_target() -> Result<(&'static str, &'static str)> {
+    let platform = match get_os() {
+        "linux" => "linux",
+        "macos" => "darwin",
+        "windows" => "windows",
+        other => anyhow::bail!("Unsupported platform for auto-update: {}", other),
+    };
+    let arch = match get_arch() {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => anyhow::bail!("Unsupported architecture for auto-update: {}", other),
+    };
+    Ok((platform, arch))
+}
+
+#[derive(Debug, Deserialize)]
+struct TaggedRelease {
+    tag_name: String,
+    assets: Vec<TaggedAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TaggedAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Look up a release by tag, trying both `0.8.3` and `v0.8.3` spellings
+fn fetch_release_by_tag(client: &reqwest::blocking::Client, tag: &str) -> Result<TaggedRelease> {
+    let bare = tag.trim_start_matches('v');
+    let candidates = [tag.to_string(), format!("v{}", bare), bare.to_string()];
+    let mut tried = Vec::new();
+
+    for candidate in candidates {
+        if tried.contains(&candidate) {
+            continue;
+        }
+        let url = format!(
+            "{}/repos/{}/{}/releases/tags/{}",
+            GITHUB_API_BASE, REPO_OWNER, REPO_NAME, candidate
+        );
+        let response = client
+            .get(&url)
+            .send()
+            .context("Failed to fetch release info")?;
+
+        if response.status().is_success() {
+            return response.json().context("Failed to parse release info");
+        }
+        if response.status() != 404 {
+            anyhow::bail!("Failed to fetch release info: HTTP {}", response.status());
+        }
+        tried.push(candidate);
+    }
+
+    anyhow::bail!("Release {} not found (tried: {})", tag, tried.join(", "))
+}
+
+/// Download and install an exact release tag, returning the resolved tag name
+pub fn install_release(tag: &str) -> Result<String> {
+    let (platform, arch) = release_target()?;
+    let extension = if cfg!(target_os = "windows") {
+        ".zip"
+    } else {
+        ".tar.gz"
+    };
+
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("hal-cli")
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .context("Failed to create HTTP client")?;
+    let release = fetch_release_by_tag(&client, tag)?;
+    println!("Found release: {}", release.tag_name);
+
+    // Prefer the canonical artifact name, then anything built for this platform/arch
+    let expected = format!(
+        "hal-{}-{}-{}{}",
+        release.tag_name.trim_start_matches('v'),
+        platform,
+        arch,
+        extension
+    );
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == expected)
+        .or_else(|| {
+            release.assets.iter().find(|asset| {
+                asset.name.contains(platform)
+                    && asset.name.contains(arch)
+                    && asset.name.ends_with(extension)
+            })
+        });
+
+    let Some(asset) = asset else {
+        eprintln!(
+            "No matching asset found for platform '{}' and arch '{}'",
+            platform, arch
+        );
+        eprintln!("Available assets:");
+        for asset in &release.assets {
+            eprintln!("  - {}", asset.name);
+        }
+        anyhow::bail!(
+            "Release {} has no build for this platform ({}) and architecture ({})",
+            release.tag_name,
+            platform,
+            arch
+        );
+    };
+
+    println!("Found matching asset: {}", asset.name);
+    download_and_install_from_url(&asset.browser_download_url, &release.tag_name)?;
+    Ok(release.tag_name)
+}
+
+/// Most commits listed before an experimental update
+const MAX_COMMITS_SHOWN: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct Comparison {
+    total_commits: usize,
+    commits: Vec<ComparedCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComparedCommit {
+    sha: String,
+    commit: CommitDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitDetail {
+    message: String,
+}
+
+/// Text of a markdown line without emphasis, code marks and link targets
+fn strip_inline_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        // [label](url) keeps the label; ![alt](url) images are dropped
+        let (before, after) = rest.split_at(start);
+        let link = after[1..].split_once("](").and_then(|(label, tail)| {
+            tail.split_once(')')
+                .filter(|_| !label.contains('['))
+                .map(|(_, tail)| (label, tail))
+        });
+        match link {
+            Some((label, tail)) => {
+                match before.strip_suffix('!') {
+                    Some(before) => out.push_str(before),
+                    None => {
+                        out.push_str(before);
+                        out.push_str(label);
+                    }
+                }
+                rest = tail;
+            }
+            None => {
+                out.push_str(before);
+                out.push('[');
+                rest = &after[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.replace("**", "").replace("__", "").replace('`', "")
+}
+
+/// Render release-note markdown as plain terminal lines
+/// Headings are underlined, list items get bullets, and link targets and emphasis are dropped.
+pub fn render_release_notes(markdown: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_comment = false;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if in_comment || line.trim_start().starts_with("<!--") {
+            in_comment = !line.contains("-->");
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if trimmed.starts_with('#') {
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+            let heading = strip_inline_markdown(trimmed[level..].trim());
+            if lines.last().is_some_and(|last| !last.is_empty()) {
+                lines.push(String::new());
+            }
+            if level <= 2 {
+                lines.push(heading.clone());
+                lines.push("─".repeat(heading.chars().count()));
+            } else {
+                lines.push(heading);
+            }
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            lines.push(format!("{}• {}", indent, strip_inline_markdown(item)));
+        } else if trimmed.is_empty() {
+            // Collapse runs of blank lines
+            if lines.last().is_some_and(|last| !last.is_empty()) {
+                lines.push(String::new());
+            }
+        } else if !trimmed.chars().all(|c| c == '-' || c == '=' || c == '*') {
+            lines.push(format!("{}{}", indent, strip_inline_markdown(trimmed)));
+        }
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// What changed between the running version and `new_version`, as terminal lines
+/// For a stable update, the notes of every release after the running one up to `new_version`
+/// (newest first); for the experimental build, the commits since the running version's tag.
+pub fn release_notes(current_version: &str, new_version: &str) -> Result<Vec<String>> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("hal-cli")
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    if new_version == "experimental" {
+        return experimental_commits(&client, current_version);
+    }
+
+    let url = format!(
+        "{}/repos/{}/{}/releases?per_page=50",
+        GITHUB_API_BASE, REPO_OWNER, REPO_NAME
+    );
+    let response = client
+        .get(&url)
+        .send()
+        .context("Failed to fetch releases")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch releases: HTTP {}", response.status());
+    }
+    let releases: Vec<Release> = response.json().context("Failed to parse release JSON")?;
+
+    let mut lines = Vec::new();
+    for release in releases.iter().filter(|release| {
+        !release.prerelease
+            && is_newer_version(&release.tag_name, current_version)
+            && !is_newer_version(&release.tag_name, new_version)
+    }) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        let published = release
+            .published_at
+            .as_deref()
+            .and_then(|published| chrono::DateTime::parse_from_rfc3339(published).ok())
+            .map(|published| format!(" ({})", published.format("%Y-%m-%d")))
+            .unwrap_or_default();
+        lines.push(format!("{}{}", release.tag_name, published));
+        let notes = render_release_notes(release.body.as_deref().unwrap_or_default());
+        if notes.is_empty() {
+            lines.push("  (no release notes)".to_string());
+        }
+        lines.extend(notes.into_iter().map(|line| format!("  {}", line)));
+    }
+    Ok(lines)
+}
+
+/// One line per commit between the running version's tag and the experimental release
+fn experimental_commits(
+    client: &reqwest::blocking::Client,
+    current_version: &str,
+) -> Result<Vec<String>> {
+    let bare = current_version.trim_start_matches('v');
+    let mut last_status = None;
+    for base in [format!("v{}", bare), bare.to_string()] {
+        let url = format!(
+            "{}/repos/{}/{}/compare/{}...experimental",
+            GITHUB_API_BASE, REPO_OWNER, REPO_NAME, base
+        );
+        let response = client.get(&url).send().context("Failed to fetch commits")?;
+        if response.status() == 404 {
+            last_status = Some(response.status());
+            continue;
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to fetch commits: HTTP {}", response.status());
+        }
+
+        let comparison: Comparison = response.json().context("Failed to parse commit list")?;
+        let mut lines: Vec<String> = comparison
+            .commits
+            .iter()
+            .rev()
+            .take(MAX_COMMITS_SHOWN)
+            .map(|commit| {
+                format!(
+                    "{} {}",
+                    &commit.sha[..commit.sha.len().min(7)],
+                    commit.commit.message.lines().next().unwrap_or_default()
+                )
+            })
+            .collect();
+        if comparison.total_commits > lines.len() {
+            lines.push(format!(
+                "... and {} more",
+                comparison.total_commits - lines.len()
+            ));
+        }
+        return Ok(lines);
+    }
+    anyhow::bail!(
+        "No release tag for {} to compare against (HTTP {})",
+        current_version,
+        last_status.map(|s| s.to_string()).unwrap_or_default()
+    )
+}
+
 pub fn prompt_for_update(new_version: &str, current_version: &str) -> Result<bool> {
     println!();
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -295,6 +762,18 @@ pub fn prompt_for_update(new_version: &str, current_version: &str) -> Result<boo
     println!("  Latest version:  {}", new_version);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
+    // Notes are a courtesy: a missing changelog or an unreachable API doesn't hold up the update
+    match release_notes(current_version, new_version) {
+        Ok(lines) if !lines.is_empty() => {
+            println!("What's new:");
+            for line in lines {
+                println!("  {}", line);
+            }
+        }
+        Ok(_) => println!("No release notes for {}.", new_version),
+        Err(e) => println!("Release notes unavailable: {}", e),
+    }
+    println!();
     print!("Would you like to download and install the update? [y/N]: ");
     std::io::stdout().flush()?;
 
@@ -308,25 +787,7 @@ pub fn prompt_for_update(new_version: &str, current_version: &str) -> Result<boo
 pub fn download_and_install_update(version: &str) -> Result<()> {
     println!("Downloading update...");
 
-    // Detect platform
-    let platform = if cfg!(target_os = "linux") {
-        "linux"
-    } else if cfg!(target_os = "macos") {
-        "darwin"
-    } else if cfg!(target_os = "windows") {
-        "windows"
-    } else {
-        anyhow::bail!("Unsupported platform for auto-update");
-    };
-
-    // Map architecture to release format (x86_64 -> amd64, aarch64 -> arm64)
-    let arch = if cfg!(target_arch = "x86_64") {
-        "amd64"
-    } else if cfg!(target_arch = "aarch64") {
-        "arm64"
-    } else {
-        anyhow::bail!("Unsupported architecture for auto-update");
-    };
+    let (platform, arch) = release_target()?;
 
     // Release artifacts are named: hal-{version}-{platform}-{arch}.tar.gz or .zip
     // For experimental releases, version is "experimental"
@@ -362,7 +823,6 @@ pub fn download_and_install_update(version: &str) -> Result<()> {
 
     // Get current executable path
     let current_exe = env::current_exe().context("Failed to get current executable path")?;
-    let backup_path = current_exe.with_extension(format!("{}.bak", extension));
 
     // Download to temp file
     let client = reqwest::blocking::Client::builder()
@@ -460,20 +920,19 @@ pub fn download_and_install_update(version: &str) -> Result<()> {
         anyhow::bail!("Failed to download update: HTTP {}", response.status());
     }
 
+    let archive = response.bytes().context("Failed to read download")?;
+    verify_release_asset(&client, version, &download_url, &archive)?;
+
     let temp_archive = std::env::temp_dir().join(format!("hal-update-{}{}", version, extension));
-    let mut file = std::fs::File::create(&temp_archive).context("Failed to create temp file")?;
-    std::io::copy(&mut response.bytes()?.as_ref(), &mut file)
-        .context("Failed to write download")?;
-    drop(file);
+    std::fs::write(&temp_archive, &archive).context("Failed to write download")?;
 
     // Continue with extraction and installation
-    extract_and_install(&temp_archive, &current_exe, &backup_path, version)
+    extract_and_install(&temp_archive, &current_exe, version)
 }
 
 fn extract_and_install(
     temp_archive: &std::path::Path,
     current_exe: &std::path::Path,
-    backup_path: &std::path::Path,
     version: &str,
 ) -> Result<()> {
     println!("Extracting archive...");
@@ -558,56 +1017,268 @@ fn extract_and_install(
 
     println!("Installing update...");
 
-    // Backup current executable
-    if local::path_exists(&current_exe) {
-        local::copy_file(&current_exe, &backup_path)?;
+    // Keep the running binary so a bad update can be undone (here or with --rollback)
+    let backup_path = backup_path_for(current_exe);
+    local::copy_file(current_exe, &backup_path).context("Failed to back up current binary")?;
+
+    replace_executable(&extracted_binary, current_exe)?;
+
+    if let Err(e) = smoke_test(current_exe, version) {
+        eprintln!("✗ New binary failed its smoke test: {:#}", e);
+        eprintln!("  Restoring the previous version...");
+        replace_executable(&backup_path, current_exe)
+            .context("Failed to restore the previous binary")?;
+        local::remove_file(temp_archive).ok();
+        local::remove_dir_all(&temp_dir).ok();
+        anyhow::bail!("Update to {} failed and was rolled back", version);
     }
+    remove_stale_update_files(current_exe);
+
+    // Clean up temp files
+    local::remove_file(&temp_archive).ok();
+    local::remove_dir_all(&temp_dir).ok();
+
+    let channel = if version == "experimental" {
+        "experimental"
+    } else {
+        "stable"
+    };
+    if let Err(e) = crate::db::record_update(version, channel, Some(env!("CARGO_PKG_VERSION"))) {
+        eprintln!("⚠ Failed to record the update in the history: {}", e);
+    }
+
+    println!("✓ Update installed successfully!");
+    println!();
+    println!("  Please restart the CLI to use the new version.");
+    println!("  If something's wrong, 'hal update --rollback' restores the previous binary.");
+
+    Ok(())
+}
+
+/// Where the previous binary is kept after an update (`hal` -> `hal.bak`, `hal.exe` -> `hal.exe.bak`)
+fn backup_path_for(current_exe: &std::path::Path) -> std::path::PathBuf {
+    let mut name = current_exe.as_os_str().to_os_string();
+    name.push(".bak");
+    std::path::PathBuf::from(name)
+}
+
+/// Swap `source` into place at `target`, which may be the running executable
+fn replace_executable(source: &std::path::Path, target: &std::path::Path) -> Result<()> {
+    // Stage next to the target so the final rename stays on one filesystem
+    let staged = target.with_extension("hal.new");
+    std::fs::copy(source, &staged).context("Failed to copy new binary to temp location")?;
 
-    // Replace current executable
-    // On Linux, we can't overwrite a file that's being executed, so we need to:
-    // 1. Copy to a temporary location next to the target
-    // 2. Remove the old file
-    // 3. Rename the new file to the target (atomic operation)
     #[cfg(unix)]
     {
-        let temp_target = current_exe.with_extension("hal.new");
-        std::fs::copy(&extracted_binary, &temp_target)
-            .context("Failed to copy new binary to temp location")?;
-        local::set_permissions(&temp_target, 0o755)
+        local::set_permissions(&staged, 0o755)
             .context("Failed to set permissions on new binary")?;
+        // Renaming over a running binary is fine on Unix: the old inode lives until it exits
+        std::fs::rename(&staged, target)
+            .context("Failed to rename new binary to target location")?;
+    }
 
-        // Remove the old file (this works even if it's being executed)
-        std::fs::remove_file(&current_exe).context("Failed to remove old binary")?;
+    #[cfg(windows)]
+    {
+        // A running exe can't be overwritten or deleted, but it can be renamed out of the way
+        let old = target.with_extension("exe.old");
+        if local::path_exists(&old) {
+            std::fs::remove_file(&old).ok();
+        }
+        if local::path_exists(target) {
+            std::fs::rename(target, &old).context("Failed to move the running binary aside")?;
+        }
+        if let Err(e) = std::fs::rename(&staged, target) {
+            std::fs::rename(&old, target).ok();
+            return Err(e).context("Failed to rename new binary to target location");
+        }
+    }
 
-        // Atomically rename the new file to the target
-        std::fs::rename(&temp_target, &current_exe)
-            .context("Failed to rename new binary to target location")?;
+    Ok(())
+}
 
-        // Remove backup after successful rename
-        if local::path_exists(&backup_path) {
-            local::remove_file(&backup_path).context("Failed to remove backup file")?;
+/// Run `<binary> --version` and check it reports the expected release
+fn smoke_test(binary: &std::path::Path, version: &str) -> Result<()> {
+    let mut child = std::process::Command::new(binary)
+        .arg("--version")
+        .env("HAL_DEV_MODE", "1") // skip release channel lookups
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .context("Failed to run new binary")?;
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if std::time::Instant::now() > deadline {
+            child.kill().ok();
+            anyhow::bail!("'--version' didn't exit within 30s");
         }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    };
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        std::io::Read::read_to_string(&mut stdout, &mut output).ok();
     }
 
-    #[cfg(windows)]
-    {
-        // On Windows, we can overwrite directly
-        local::copy_file(&extracted_binary, &current_exe)?;
+    if !status.success() {
+        anyhow::bail!("'--version' exited with {}", status);
+    }
+    // Experimental builds aren't tagged with their version, so any clean run passes
+    let expected = version.trim_start_matches('v');
+    if expected != "experimental" && !output.contains(expected) {
+        anyhow::bail!(
+            "'--version' reported '{}', expected {}",
+            output.trim(),
+            expected
+        );
+    }
+    Ok(())
+}
 
-        // Remove backup after successful copy
-        if local::path_exists(&backup_path) {
-            local::remove_file(&backup_path).context("Failed to remove backup file")?;
+/// Remove leftovers from earlier updates, keeping the latest backup for --rollback
+fn remove_stale_update_files(current_exe: &std::path::Path) {
+    let stale = [
+        current_exe.with_extension("hal.new"),
+        current_exe.with_extension("exe.old"),
+        // Backups from before --rollback existed
+        current_exe.with_extension(".tar.gz.bak"),
+        current_exe.with_extension(".zip.bak"),
+    ];
+    for path in stale {
+        if path != current_exe && local::path_exists(&path) {
+            // The Windows .old file stays locked until the previous process exits
+            local::remove_file(&path).ok();
         }
     }
+}
 
-    // Clean up temp files
-    local::remove_file(&temp_archive).ok();
-    local::remove_dir_all(&temp_dir).ok();
+/// Restore the binary saved by the last update (`hal update --rollback`)
+pub fn rollback_update() -> Result<()> {
+    let current_exe = env::current_exe().context("Failed to get current executable path")?;
+    let backup_path = backup_path_for(&current_exe);
+    if !local::path_exists(&backup_path) {
+        anyhow::bail!(
+            "No previous binary found at {}; nothing to roll back",
+            backup_path.display()
+        );
+    }
 
-    println!("✓ Update installed successfully!");
+    println!("Restoring {}...", backup_path.display());
+    replace_executable(&backup_path, &current_exe)?;
+    local::remove_file(&backup_path).context("Failed to remove backup file")?;
+    remove_stale_update_files(&current_exe);
+
+    println!("✓ Rolled back to the previous binary");
     println!();
-    println!("  Please restart the CLI to use the new version.");
+    println!("  Please restart the CLI to use it.");
+    Ok(())
+}
+
+/// Fetch a small release asset (checksums, signature), treating 404 as missing
+fn fetch_release_text(
+    client: &reqwest::blocking::Client,
+    version: &str,
+    name: &str,
+) -> Result<Option<String>> {
+    let url = format!(
+        "https://github.com/{}/{}/releases/download/{}/{}",
+        REPO_OWNER, REPO_NAME, version, name
+    );
+    let response = client
+        .get(&url)
+        .send()
+        .with_context(|| format!("Failed to download {}", name))?;
+    if response.status() == 404 {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to download {}: HTTP {}", name, response.status());
+    }
+    Ok(Some(
+        response
+            .text()
+            .with_context(|| format!("Failed to read {}", name))?,
+    ))
+}
+
+/// Check a detached ed25519 signature (base64, 64 bytes) over the checksum manifest
+fn verify_checksums_signature(public_key: &str, checksums: &str, signature: &str) -> Result<()> {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+
+    let public_key = STANDARD
+        .decode(public_key.trim())
+        .context("Embedded update signing key is not valid base64")?;
+    let signature = STANDARD
+        .decode(signature.trim())
+        .context("SHA256SUMS.sig is not valid base64")?;
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+        .verify(checksums.as_bytes(), &signature)
+        .map_err(|_| anyhow::anyhow!("SHA256SUMS signature verification failed"))
+}
 
+/// Find the expected hash for `asset_name` in `sha256sum`-style output
+fn expected_checksum<'a>(checksums: &'a str, asset_name: &str) -> Option<&'a str> {
+    checksums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        // sha256sum marks binary mode with a leading '*'
+        let name = name.trim_start().trim_start_matches('*');
+        (name == asset_name).then_some(hash)
+    })
+}
+
+/// Refuse to install a download whose SHA-256 doesn't match the release's SHA256SUMS
+/// (and, when a signing key is embedded, whose SHA256SUMS isn't signed by it)
+fn verify_release_asset(
+    client: &reqwest::blocking::Client,
+    version: &str,
+    download_url: &str,
+    archive: &[u8],
+) -> Result<()> {
+    use sha2::{Digest, Sha256};
+
+    let asset_name = download_url.rsplit('/').next().unwrap_or(download_url);
+    println!("Verifying checksum...");
+
+    let checksums = fetch_release_text(client, version, CHECKSUMS_ASSET)?.with_context(|| {
+        format!(
+            "Release {} has no {} asset; refusing to install an unverified binary",
+            version, CHECKSUMS_ASSET
+        )
+    })?;
+
+    if let Some(public_key) = UPDATE_SIGNING_KEY {
+        let signature_name = format!("{}.sig", CHECKSUMS_ASSET);
+        let signature =
+            fetch_release_text(client, version, &signature_name)?.with_context(|| {
+                format!(
+                    "Release {} has no {} asset; refusing to install an unsigned binary",
+                    version, signature_name
+                )
+            })?;
+        verify_checksums_signature(public_key, &checksums, &signature)?;
+        println!("✓ {} signature verified", CHECKSUMS_ASSET);
+    }
+
+    let expected = expected_checksum(&checksums, asset_name).with_context(|| {
+        format!(
+            "{} for release {} doesn't list {}; refusing to install",
+            CHECKSUMS_ASSET, version, asset_name
+        )
+    })?;
+    let actual = format!("{:x}", Sha256::digest(archive));
+    if !actual.eq_ignore_ascii_case(expected) {
+        anyhow::bail!(
+            "Checksum mismatch for {}: expected {}, got {}. The download may be corrupt or tampered with; not installing.",
+            asset_name,
+            expected,
+            actual
+        );
+    }
+
+    println!("✓ Checksum verified ({})", asset_name);
     Ok(())
 }
 
@@ -622,7 +1293,6 @@ fn download_and_install_from_url(download_url: &str, version: &str) -> Result<()
     } else {
         ".tar.gz"
     };
-    let backup_path = current_exe.with_extension(format!("{}.bak", extension));
 
     // Download to temp file
     let client = reqwest::blocking::Client::builder()
@@ -639,12 +1309,12 @@ fn download_and_install_from_url(download_url: &str, version: &str) -> Result<()
         anyhow::bail!("Failed to download update: HTTP {}", response.status());
     }
 
+    let archive = response.bytes().context("Failed to read download")?;
+    verify_release_asset(&client, version, download_url, &archive)?;
+
     let temp_archive = std::env::temp_dir().join(format!("hal-update-{}{}", version, extension));
-    let mut file = std::fs::File::create(&temp_archive).context("Failed to create temp file")?;
-    std::io::copy(&mut response.bytes()?.as_ref(), &mut file)
-        .context("Failed to write download")?;
-    drop(file);
+    std::fs::write(&temp_archive, &archive).context("Failed to write download")?;
 
     // Continue with extraction and installation
-    extract_and_install(&temp_archive, &current_exe, &backup_path, version)
+    extract_and_install(&temp_archive, &current_exe, version)
 }